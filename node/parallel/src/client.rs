@@ -39,6 +39,7 @@ pub trait RuntimeApiCollection:
     + cumulus_primitives_core::CollectCollationInfo<Block>
     + pallet_loans_rpc::LoansRuntimeApi<Block, AccountId, Balance>
     + pallet_router_rpc::RouterRuntimeApi<Block, Balance>
+    + pallet_streaming_rpc::StreamingRuntimeApi<Block, AccountId, Balance>
     + fp_rpc::EthereumRuntimeRPCApi<Block>
     + fp_rpc::ConvertTransactionRuntimeApi<Block>
 where
@@ -61,6 +62,7 @@ where
         + cumulus_primitives_core::CollectCollationInfo<Block>
         + pallet_loans_rpc::LoansRuntimeApi<Block, AccountId, Balance>
         + pallet_router_rpc::RouterRuntimeApi<Block, Balance>
+        + pallet_streaming_rpc::StreamingRuntimeApi<Block, AccountId, Balance>
         + fp_rpc::EthereumRuntimeRPCApi<Block>
         + fp_rpc::ConvertTransactionRuntimeApi<Block>,
     <Self as sp_api::ApiExt<Block>>::StateBackend: sp_api::StateBackend<BlakeTwo256>,
@@ -48,6 +48,7 @@ use substrate_frame_rpc_system::{System, SystemApiServer};
 use orml_oracle_rpc::{Oracle, OracleApiServer};
 use pallet_loans_rpc::{Loans, LoansApiServer};
 use pallet_router_rpc::{Router, RouterApiServer};
+use pallet_streaming_rpc::{Streaming, StreamingApiServer};
 
 pub fn frontier_database_dir(config: &Configuration, path: &str) -> std::path::PathBuf {
     let config_dir = config
@@ -169,6 +170,7 @@ where
         + orml_oracle_rpc::OracleRuntimeApi<Block, DataProviderId, CurrencyId, TimeStampedPrice>
         + pallet_loans_rpc::LoansRuntimeApi<Block, AccountId, Balance>
         + pallet_router_rpc::RouterRuntimeApi<Block, Balance>
+        + pallet_streaming_rpc::StreamingRuntimeApi<Block, AccountId, Balance>
         + fp_rpc::ConvertTransactionRuntimeApi<Block>
         + fp_rpc::EthereumRuntimeRPCApi<Block>,
     P: TransactionPool<Block = Block> + Sync + Send + 'static,
@@ -259,6 +261,7 @@ where
     io.merge(Oracle::new(client.clone()).into_rpc())?;
     io.merge(Loans::new(client.clone()).into_rpc())?;
     io.merge(Router::new(client.clone()).into_rpc())?;
+    io.merge(Streaming::new(client.clone()).into_rpc())?;
 
     Ok(io)
 }
@@ -22,6 +22,9 @@ const QUOTE_ASSET: CurrencyId = DOT;
 const INITIAL_AMOUNT: u128 = 1_000_000_000_000_000;
 const ASSET_ID: u32 = 10;
 const MINIMUM_LIQUIDITY: u128 = 1_000u128;
+const CONCENTRATED_FEE: Ratio = Ratio::from_parts(3_000);
+const TICK_SPACING: u32 = 60;
+const POSITION_AMOUNT: u128 = 10_000_000_000_000;
 
 fn assert_last_event<T: Config<I>, I: 'static>(generic_event: <T as Config<I>>::RuntimeEvent) {
     frame_system::Pallet::<T>::assert_last_event(generic_event.into());
@@ -66,6 +69,35 @@ fn initial_set_up<
     T::Assets::mint_into(QUOTE_ASSET, &caller, INITIAL_AMOUNT).ok();
 }
 
+fn concentrated_set_up<
+    T: Config<I> + pallet_assets::Config<AssetId = CurrencyId, Balance = Balance>,
+    I: 'static,
+>(
+    caller: T::AccountId,
+) {
+    initial_set_up::<T, I>(caller.clone());
+    let origin = T::CreatePoolOrigin::try_successful_origin()
+        .expect("No origin exists which can satisfy the guard");
+    assert_ok!(AMM::<T, I>::set_fee_tier(
+        origin.clone(),
+        CONCENTRATED_FEE,
+        Some(TICK_SPACING)
+    ));
+    assert_ok!(AMM::<T, I>::create_concentrated_pool(
+        origin,
+        (BASE_ASSET, QUOTE_ASSET),
+        CONCENTRATED_FEE,
+        FixedU128::one()
+    ));
+    assert_ok!(AMM::<T, I>::mint_position(
+        SystemOrigin::Signed(caller).into(),
+        (BASE_ASSET, QUOTE_ASSET),
+        (-(TICK_SPACING as i32), TICK_SPACING as i32),
+        (POSITION_AMOUNT, POSITION_AMOUNT),
+        (0, 0)
+    ));
+}
+
 benchmarks_instance_pallet! {
     where_clause {
         where T: pallet_assets::Config<AssetId = CurrencyId, Balance = Balance>
@@ -78,7 +110,7 @@ benchmarks_instance_pallet! {
         let quote_amount = 200_000u128;
         assert_ok!(AMM::<T, I>::create_pool(T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard"),
             (BASE_ASSET, QUOTE_ASSET), (base_amount, quote_amount),
-            caller.clone(), ASSET_ID));
+            caller.clone(), ASSET_ID, T::LpFee::get(), None));
     }: _(
         SystemOrigin::Signed(caller.clone()),
         (BASE_ASSET, QUOTE_ASSET),
@@ -105,7 +137,7 @@ benchmarks_instance_pallet! {
         let quote_amount = 900_000u128;
         assert_ok!(AMM::<T, I>::create_pool(T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard"),
             (BASE_ASSET, QUOTE_ASSET), (base_amount, quote_amount),
-            caller.clone(), ASSET_ID));
+            caller.clone(), ASSET_ID, T::LpFee::get(), None));
     }: _(
         SystemOrigin::Signed(caller.clone()),
         (BASE_ASSET, QUOTE_ASSET),
@@ -135,7 +167,9 @@ benchmarks_instance_pallet! {
             pair: (BASE_ASSET, QUOTE_ASSET),
             liquidity_amounts: (base_amount, quote_amount),
             lptoken_receiver: caller.clone(),
-            lp_token_id: ASSET_ID
+            lp_token_id: ASSET_ID,
+            lp_fee: T::LpFee::get(),
+            protocol_fee: None
         };
     }: {
         call.dispatch_bypass_filter(origin)?
@@ -175,6 +209,146 @@ benchmarks_instance_pallet! {
     }
     verify {
     }
+
+    update_pool_fees {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T, I>(caller.clone());
+        assert_ok!(AMM::<T, I>::create_pool(T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard"),
+            (BASE_ASSET, QUOTE_ASSET), (100_000u128, 200_000u128),
+            caller.clone(), ASSET_ID, T::LpFee::get(), None));
+        assert_ok!(AMM::<T, I>::update_protocol_fee_receiver(T::ProtocolFeeUpdateOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard"),
+            caller));
+        let origin = T::ProtocolFeeUpdateOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard");
+        let call = Call::<T, I>::update_pool_fees {
+            pair: (BASE_ASSET, QUOTE_ASSET),
+            lp_fee: Ratio::from_percent(1),
+            protocol_fee: Some(Ratio::from_percent(20))
+        };
+    }: {
+        call.dispatch_bypass_filter(origin)?
+    }
+    verify {
+        assert_last_event::<T, I>(Event::<T, I>::PoolFeesUpdated(
+            BASE_ASSET,
+            QUOTE_ASSET,
+            Ratio::from_percent(1),
+            Some(Ratio::from_percent(20))
+        ).into());
+    }
+
+    set_dynamic_fee {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T, I>(caller.clone());
+        assert_ok!(AMM::<T, I>::create_pool(T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard"),
+            (BASE_ASSET, QUOTE_ASSET), (100_000u128, 200_000u128),
+            caller, ASSET_ID, T::LpFee::get(), None));
+        let params = Some((Ratio::from_percent(5), Ratio::from_percent(20), 100u32.into()));
+        let origin = T::ProtocolFeeUpdateOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard");
+        let call = Call::<T, I>::set_dynamic_fee {
+            pair: (BASE_ASSET, QUOTE_ASSET),
+            params
+        };
+    }: {
+        call.dispatch_bypass_filter(origin)?
+    }
+    verify {
+        assert_last_event::<T, I>(Event::<T, I>::DynamicFeeUpdated(BASE_ASSET, QUOTE_ASSET, params).into());
+    }
+
+    zap_in {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T, I>(caller.clone());
+        assert_ok!(AMM::<T, I>::create_pool(T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard"),
+            (BASE_ASSET, QUOTE_ASSET), (100_000u128, 200_000u128),
+            caller.clone(), ASSET_ID, T::LpFee::get(), None));
+        let liquidity = T::Assets::balance(ASSET_ID, &caller);
+    }: _(
+        SystemOrigin::Signed(caller.clone()),
+        (BASE_ASSET, QUOTE_ASSET),
+        10_000u128,
+        1u128
+    )
+    verify {
+        assert!(T::Assets::balance(ASSET_ID, &caller) > liquidity);
+    }
+
+    zap_out {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T, I>(caller.clone());
+        assert_ok!(AMM::<T, I>::create_pool(T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard"),
+            (BASE_ASSET, QUOTE_ASSET), (100_000u128, 200_000u128),
+            caller.clone(), ASSET_ID, T::LpFee::get(), None));
+        let base_amount = T::Assets::balance(BASE_ASSET, &caller);
+    }: _(
+        SystemOrigin::Signed(caller.clone()),
+        (BASE_ASSET, QUOTE_ASSET),
+        10_000u128,
+        1u128
+    )
+    verify {
+        assert!(T::Assets::balance(BASE_ASSET, &caller) > base_amount);
+    }
+
+    set_fee_tier {
+        let origin = T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard");
+        let call = Call::<T, I>::set_fee_tier {
+            fee: CONCENTRATED_FEE,
+            tick_spacing: Some(TICK_SPACING)
+        };
+    }: {
+        call.dispatch_bypass_filter(origin)?
+    }
+    verify {
+        assert_last_event::<T, I>(Event::<T, I>::FeeTierUpdated(CONCENTRATED_FEE, Some(TICK_SPACING)).into());
+    }
+
+    create_concentrated_pool {
+        let origin = T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard");
+        assert_ok!(AMM::<T, I>::set_fee_tier(origin.clone(), CONCENTRATED_FEE, Some(TICK_SPACING)));
+        let call = Call::<T, I>::create_concentrated_pool {
+            pair: (BASE_ASSET, QUOTE_ASSET),
+            fee: CONCENTRATED_FEE,
+            initial_price: FixedU128::one()
+        };
+    }: {
+        call.dispatch_bypass_filter(origin)?
+    }
+    verify {
+        assert!(ConcentratedPools::<T, I>::contains_key(BASE_ASSET, QUOTE_ASSET));
+    }
+
+    mint_position {
+        let caller: T::AccountId = whitelisted_caller();
+        concentrated_set_up::<T, I>(caller.clone());
+    }: _(
+        SystemOrigin::Signed(caller.clone()),
+        (BASE_ASSET, QUOTE_ASSET),
+        (-2 * TICK_SPACING as i32, 2 * TICK_SPACING as i32),
+        (POSITION_AMOUNT, POSITION_AMOUNT),
+        (0, 0)
+    )
+    verify {
+        assert!(Positions::<T, I>::contains_key(caller, 1));
+    }
+
+    decrease_position_liquidity {
+        let caller: T::AccountId = whitelisted_caller();
+        concentrated_set_up::<T, I>(caller.clone());
+        let liquidity = Positions::<T, I>::get(&caller, 0).unwrap().liquidity;
+    }: _(SystemOrigin::Signed(caller.clone()), 0, liquidity, (0, 0))
+    verify {
+        assert!(!Positions::<T, I>::contains_key(caller, 0));
+    }
+
+    collect_position_fees {
+        let caller: T::AccountId = whitelisted_caller();
+        concentrated_set_up::<T, I>(caller.clone());
+        assert_ok!(AMM::<T, I>::do_concentrated_swap(&caller, (QUOTE_ASSET, BASE_ASSET), 10_000u128));
+    }: _(SystemOrigin::Signed(caller.clone()), 0)
+    verify {
+        let position = Positions::<T, I>::get(caller, 0).unwrap();
+        assert!(!position.fee_growth_inside_quote_last.is_zero());
+    }
 }
 
 impl_benchmark_test_suite!(AMM, crate::mock::new_test_ext(), crate::mock::Test,);
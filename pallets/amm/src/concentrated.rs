@@ -0,0 +1,972 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Concentrated liquidity pools
+//!
+//! Liquidity providers add liquidity to a price range between two ticks, the price
+//! at a tick is `1.0001^tick`. Within the range of the current price the pool trades
+//! along the constant product curve of the liquidity in range, crossing the initialized
+//! ticks on the way changes the liquidity in range.
+//!
+//! Prices are kept as the square root of the price of the base asset in the quote asset.
+//! The fees are paid in the asset in and accrue to the liquidity in range, positions
+//! collect them by the fee growth inside their range.
+
+use super::*;
+use num_bigint::BigUint;
+
+pub type PositionId = u64;
+
+/// The lowest tick of a concentrated liquidity pool
+pub const MIN_TICK: i32 = -500_000;
+/// The highest tick of a concentrated liquidity pool
+pub const MAX_TICK: i32 = 500_000;
+
+/// sqrt(1.0001)
+const SQRT_TICK_BASE: FixedU128 = FixedU128::from_inner(1_000_049_998_750_062_496);
+
+/// A concentrated liquidity pool of a sorted asset pair
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConcentratedPool {
+    /// The fee of the trades
+    pub fee: Ratio,
+    /// Ticks of the positions are multiples of the tick spacing
+    pub tick_spacing: u32,
+    /// The square root of the price of the base asset in the quote asset
+    pub sqrt_price: FixedU128,
+    /// The tick of the current price
+    pub tick: i32,
+    /// The liquidity in range
+    pub liquidity: Balance,
+    /// The base asset fees earned per unit of liquidity
+    pub fee_growth_global_base: FixedU128,
+    /// The quote asset fees earned per unit of liquidity
+    pub fee_growth_global_quote: FixedU128,
+}
+
+/// An initialized tick of a concentrated liquidity pool
+#[derive(
+    Encode, Decode, Eq, PartialEq, Copy, Clone, Default, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct TickInfo {
+    /// The liquidity of the positions bounded by this tick
+    pub liquidity_gross: Balance,
+    /// The liquidity added when the price crosses this tick upwards
+    pub liquidity_net: i128,
+    /// The base asset fee growth on the other side of this tick
+    pub fee_growth_outside_base: FixedU128,
+    /// The quote asset fee growth on the other side of this tick
+    pub fee_growth_outside_quote: FixedU128,
+}
+
+/// The liquidity of an owner in a price range of a concentrated liquidity pool
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position<AssetId> {
+    pub base_asset: AssetId,
+    pub quote_asset: AssetId,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: Balance,
+    /// The base asset fee growth inside the range when the fees were last collected
+    pub fee_growth_inside_base_last: FixedU128,
+    /// The quote asset fee growth inside the range when the fees were last collected
+    pub fee_growth_inside_quote_last: FixedU128,
+}
+
+/// A tick crossed by a swap and the fee growth at the time
+type CrossedTick = (i32, FixedU128, FixedU128);
+
+/// The square root of the price at a tick
+pub fn sqrt_price_at_tick(tick: i32) -> Option<FixedU128> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return None;
+    }
+    let sqrt_price = SQRT_TICK_BASE.saturating_pow(tick.unsigned_abs() as usize);
+    if tick < 0 {
+        sqrt_price.reciprocal()
+    } else {
+        Some(sqrt_price)
+    }
+}
+
+/// The largest tick whose price is not greater than the price of `sqrt_price`
+pub fn tick_at_sqrt_price(sqrt_price: FixedU128) -> Option<i32> {
+    if sqrt_price < sqrt_price_at_tick(MIN_TICK)? || sqrt_price > sqrt_price_at_tick(MAX_TICK)? {
+        return None;
+    }
+
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if sqrt_price_at_tick(mid)? <= sqrt_price {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    Some(low)
+}
+
+fn div_rounding(numerator: BigUint, denominator: &BigUint, round_up: bool) -> Option<BigUint> {
+    if denominator.is_zero() {
+        return None;
+    }
+    if round_up {
+        Some((numerator + denominator - BigUint::one()) / denominator)
+    } else {
+        Some(numerator / denominator)
+    }
+}
+
+fn to_balance(value: BigUint) -> Result<Balance, DispatchError> {
+    Ok(value.to_u128().ok_or(ArithmeticError::Overflow)?)
+}
+
+fn to_sqrt_price(value: BigUint) -> Result<FixedU128, DispatchError> {
+    Ok(FixedU128::from_inner(to_balance(value)?))
+}
+
+/// `liquidity * (1 / sqrt_price_a - 1 / sqrt_price_b)`
+fn base_amount_delta(
+    liquidity: Balance,
+    sqrt_price_a: FixedU128,
+    sqrt_price_b: FixedU128,
+    round_up: bool,
+) -> Result<Balance, DispatchError> {
+    let (sqrt_price_a, sqrt_price_b) = (
+        sqrt_price_a.min(sqrt_price_b).into_inner(),
+        sqrt_price_a.max(sqrt_price_b).into_inner(),
+    );
+    let numerator = liquidity.get_big_uint()
+        * (sqrt_price_b - sqrt_price_a).get_big_uint()
+        * FixedU128::DIV.get_big_uint();
+    let denominator = sqrt_price_a.get_big_uint() * sqrt_price_b.get_big_uint();
+    to_balance(
+        div_rounding(numerator, &denominator, round_up).ok_or(ArithmeticError::DivisionByZero)?,
+    )
+}
+
+/// `liquidity * (sqrt_price_b - sqrt_price_a)`
+fn quote_amount_delta(
+    liquidity: Balance,
+    sqrt_price_a: FixedU128,
+    sqrt_price_b: FixedU128,
+    round_up: bool,
+) -> Result<Balance, DispatchError> {
+    let (sqrt_price_a, sqrt_price_b) = (
+        sqrt_price_a.min(sqrt_price_b).into_inner(),
+        sqrt_price_a.max(sqrt_price_b).into_inner(),
+    );
+    let numerator = liquidity.get_big_uint() * (sqrt_price_b - sqrt_price_a).get_big_uint();
+    to_balance(
+        div_rounding(numerator, &FixedU128::DIV.get_big_uint(), round_up)
+            .ok_or(ArithmeticError::DivisionByZero)?,
+    )
+}
+
+/// The liquidity of `base_amount` and `quote_amount` in the range at the current price
+fn liquidity_for_amounts(
+    sqrt_price: FixedU128,
+    sqrt_price_lower: FixedU128,
+    sqrt_price_upper: FixedU128,
+    (base_amount, quote_amount): (Balance, Balance),
+) -> Result<Balance, DispatchError> {
+    // base_amount * sqrt_price_a * sqrt_price_b / (sqrt_price_b - sqrt_price_a)
+    let liquidity_for_base = |sqrt_price_a: FixedU128| -> Result<Balance, DispatchError> {
+        let numerator = base_amount.get_big_uint()
+            * sqrt_price_a.into_inner().get_big_uint()
+            * sqrt_price_upper.into_inner().get_big_uint();
+        let denominator = (sqrt_price_upper.into_inner() - sqrt_price_a.into_inner())
+            .get_big_uint()
+            * FixedU128::DIV.get_big_uint();
+        to_balance(
+            div_rounding(numerator, &denominator, false).ok_or(ArithmeticError::DivisionByZero)?,
+        )
+    };
+    // quote_amount / (sqrt_price_b - sqrt_price_a)
+    let liquidity_for_quote = |sqrt_price_b: FixedU128| -> Result<Balance, DispatchError> {
+        let numerator = quote_amount.get_big_uint() * FixedU128::DIV.get_big_uint();
+        let denominator =
+            (sqrt_price_b.into_inner() - sqrt_price_lower.into_inner()).get_big_uint();
+        to_balance(
+            div_rounding(numerator, &denominator, false).ok_or(ArithmeticError::DivisionByZero)?,
+        )
+    };
+
+    if sqrt_price <= sqrt_price_lower {
+        liquidity_for_base(sqrt_price_lower)
+    } else if sqrt_price < sqrt_price_upper {
+        Ok(min(
+            liquidity_for_base(sqrt_price)?,
+            liquidity_for_quote(sqrt_price)?,
+        ))
+    } else {
+        liquidity_for_quote(sqrt_price_upper)
+    }
+}
+
+/// The price after trading `amount` of the base asset, rounded towards the current price
+///
+/// `sqrt_price' = liquidity * sqrt_price / (liquidity +/- amount * sqrt_price)`
+fn next_sqrt_price_from_base(
+    liquidity: Balance,
+    sqrt_price: FixedU128,
+    amount: Balance,
+    add: bool,
+) -> Result<FixedU128, DispatchError> {
+    let scaled_liquidity = liquidity.get_big_uint() * FixedU128::DIV.get_big_uint();
+    let product = amount.get_big_uint() * sqrt_price.into_inner().get_big_uint();
+    let denominator = if add {
+        &scaled_liquidity + product
+    } else {
+        ensure!(scaled_liquidity > product, ArithmeticError::Underflow);
+        &scaled_liquidity - product
+    };
+    to_sqrt_price(
+        div_rounding(
+            scaled_liquidity * sqrt_price.into_inner().get_big_uint(),
+            &denominator,
+            true,
+        )
+        .ok_or(ArithmeticError::DivisionByZero)?,
+    )
+}
+
+/// The price after trading `amount` of the quote asset, rounded towards the current price
+///
+/// `sqrt_price' = sqrt_price +/- amount / liquidity`
+fn next_sqrt_price_from_quote(
+    liquidity: Balance,
+    sqrt_price: FixedU128,
+    amount: Balance,
+    add: bool,
+) -> Result<FixedU128, DispatchError> {
+    let delta = div_rounding(
+        amount.get_big_uint() * FixedU128::DIV.get_big_uint(),
+        &liquidity.get_big_uint(),
+        !add,
+    )
+    .ok_or(ArithmeticError::DivisionByZero)?;
+    let sqrt_price = sqrt_price.into_inner().get_big_uint();
+    if add {
+        to_sqrt_price(sqrt_price + delta)
+    } else {
+        ensure!(sqrt_price > delta, ArithmeticError::Underflow);
+        to_sqrt_price(sqrt_price - delta)
+    }
+}
+
+/// The result of a swap within the liquidity of a single tick range
+struct SwapStep {
+    sqrt_price: FixedU128,
+    amount_in: Balance,
+    amount_out: Balance,
+    fee_amount: Balance,
+}
+
+/// Swaps `amount_remaining` towards `sqrt_price_target` with the liquidity in range.
+/// `amount_remaining` is the amount in including the fees for exact input swaps
+/// and the amount out otherwise.
+fn compute_swap_step(
+    sqrt_price: FixedU128,
+    sqrt_price_target: FixedU128,
+    liquidity: Balance,
+    amount_remaining: Balance,
+    fee: Ratio,
+    exact_in: bool,
+) -> Result<SwapStep, DispatchError> {
+    // the price goes down while the base asset is sold
+    let base_in = sqrt_price_target <= sqrt_price;
+    let amount_in_to = |sqrt_price_next: FixedU128| {
+        if base_in {
+            base_amount_delta(liquidity, sqrt_price_next, sqrt_price, true)
+        } else {
+            quote_amount_delta(liquidity, sqrt_price, sqrt_price_next, true)
+        }
+    };
+    let amount_out_to = |sqrt_price_next: FixedU128| {
+        if base_in {
+            quote_amount_delta(liquidity, sqrt_price_next, sqrt_price, false)
+        } else {
+            base_amount_delta(liquidity, sqrt_price, sqrt_price_next, false)
+        }
+    };
+    let clamp = |sqrt_price_next: FixedU128| {
+        if base_in {
+            sqrt_price_next.max(sqrt_price_target)
+        } else {
+            sqrt_price_next.min(sqrt_price_target)
+        }
+    };
+    let fee_parts = fee.deconstruct() as u128;
+    let fee_complement = (Ratio::one().deconstruct() as u128).saturating_sub(fee_parts);
+    // amount_in * fee / (1 - fee)
+    let fee_of = |amount_in: Balance| -> Result<Balance, DispatchError> {
+        to_balance(
+            div_rounding(
+                amount_in.get_big_uint() * fee_parts.get_big_uint(),
+                &fee_complement.get_big_uint(),
+                true,
+            )
+            .ok_or(ArithmeticError::DivisionByZero)?,
+        )
+    };
+
+    if exact_in {
+        let amount_remaining_less_fee =
+            amount_remaining.saturating_sub(fee.mul_ceil(amount_remaining));
+        let max_amount_in = amount_in_to(sqrt_price_target)?;
+        let (sqrt_price_next, amount_in, fee_amount) = if amount_remaining_less_fee >= max_amount_in
+        {
+            (sqrt_price_target, max_amount_in, fee_of(max_amount_in)?)
+        } else {
+            let sqrt_price_next = if base_in {
+                next_sqrt_price_from_base(liquidity, sqrt_price, amount_remaining_less_fee, true)?
+            } else {
+                next_sqrt_price_from_quote(liquidity, sqrt_price, amount_remaining_less_fee, true)?
+            };
+            // the whole remaining amount is spent, the rounding goes to the fees
+            (
+                clamp(sqrt_price_next),
+                amount_remaining_less_fee,
+                amount_remaining.saturating_sub(amount_remaining_less_fee),
+            )
+        };
+        Ok(SwapStep {
+            sqrt_price: sqrt_price_next,
+            amount_in,
+            amount_out: amount_out_to(sqrt_price_next)?,
+            fee_amount,
+        })
+    } else {
+        let max_amount_out = amount_out_to(sqrt_price_target)?;
+        let (sqrt_price_next, amount_out) = if amount_remaining >= max_amount_out {
+            (sqrt_price_target, max_amount_out)
+        } else {
+            let sqrt_price_next = if base_in {
+                next_sqrt_price_from_quote(liquidity, sqrt_price, amount_remaining, false)?
+            } else {
+                next_sqrt_price_from_base(liquidity, sqrt_price, amount_remaining, false)?
+            };
+            (clamp(sqrt_price_next), amount_remaining)
+        };
+        let amount_in = amount_in_to(sqrt_price_next)?;
+        Ok(SwapStep {
+            sqrt_price: sqrt_price_next,
+            amount_in,
+            amount_out,
+            fee_amount: fee_of(amount_in)?,
+        })
+    }
+}
+
+fn apply_liquidity_delta(liquidity: Balance, delta: i128) -> Result<Balance, DispatchError> {
+    if delta >= 0 {
+        Ok(liquidity
+            .checked_add(delta.unsigned_abs())
+            .ok_or(ArithmeticError::Overflow)?)
+    } else {
+        Ok(liquidity
+            .checked_sub(delta.unsigned_abs())
+            .ok_or(ArithmeticError::Underflow)?)
+    }
+}
+
+// Fee growth accumulators wrap around on overflow like in Uniswap v3,
+// only the differences between them are meaningful.
+fn wrapping_add(a: FixedU128, b: FixedU128) -> FixedU128 {
+    FixedU128::from_inner(a.into_inner().wrapping_add(b.into_inner()))
+}
+
+fn wrapping_sub(a: FixedU128, b: FixedU128) -> FixedU128 {
+    FixedU128::from_inner(a.into_inner().wrapping_sub(b.into_inner()))
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+    #[require_transactional]
+    pub(crate) fn do_create_concentrated_pool(
+        (base_asset, quote_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        fee: Ratio,
+        price: FixedU128,
+    ) -> Result<ConcentratedPool, DispatchError> {
+        ensure!(
+            !ConcentratedPools::<T, I>::contains_key(base_asset, quote_asset),
+            Error::<T, I>::PoolAlreadyExists
+        );
+        let tick_spacing = Self::fee_tier(fee).ok_or(Error::<T, I>::FeeTierNotEnabled)?;
+
+        let sqrt_price = FixedU128::from_inner(
+            (price.into_inner().get_big_uint() * FixedU128::DIV.get_big_uint())
+                .sqrt()
+                .to_u128()
+                .ok_or(ArithmeticError::Overflow)?,
+        );
+        let tick = tick_at_sqrt_price(sqrt_price).ok_or(Error::<T, I>::InvalidPrice)?;
+        let pool = ConcentratedPool {
+            fee,
+            tick_spacing,
+            sqrt_price,
+            tick,
+            liquidity: Zero::zero(),
+            fee_growth_global_base: Zero::zero(),
+            fee_growth_global_quote: Zero::zero(),
+        };
+        if !Pools::<T, I>::contains_key(base_asset, quote_asset) {
+            T::PoolsHook::on_pool_created((base_asset, quote_asset));
+        }
+        ConcentratedPools::<T, I>::insert(base_asset, quote_asset, pool);
+
+        Ok(pool)
+    }
+
+    #[require_transactional]
+    pub(crate) fn do_mint_position(
+        who: &T::AccountId,
+        (base_asset, quote_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        (tick_lower, tick_upper): (i32, i32),
+        desired_amounts: (BalanceOf<T, I>, BalanceOf<T, I>),
+        (minimum_base_amount, minimum_quote_amount): (BalanceOf<T, I>, BalanceOf<T, I>),
+    ) -> Result<(PositionId, Position<AssetIdOf<T, I>>, (Balance, Balance)), DispatchError> {
+        let mut pool = Self::concentrated_pools(base_asset, quote_asset)
+            .ok_or(Error::<T, I>::PoolDoesNotExist)?;
+        let spacing = pool.tick_spacing as i32;
+        ensure!(
+            tick_lower < tick_upper
+                && tick_lower >= MIN_TICK
+                && tick_upper <= MAX_TICK
+                && tick_lower % spacing == 0
+                && tick_upper % spacing == 0,
+            Error::<T, I>::InvalidTickRange
+        );
+
+        let liquidity = liquidity_for_amounts(
+            pool.sqrt_price,
+            sqrt_price_at_tick(tick_lower).ok_or(Error::<T, I>::InvalidTickRange)?,
+            sqrt_price_at_tick(tick_upper).ok_or(Error::<T, I>::InvalidTickRange)?,
+            desired_amounts,
+        )?;
+        ensure!(!liquidity.is_zero(), Error::<T, I>::InsufficientAmountIn);
+        ensure!(
+            liquidity >= T::MinPositionLiquidity::get(),
+            Error::<T, I>::PositionLiquidityTooLow
+        );
+
+        let (base_amount, quote_amount) = Self::update_position_liquidity(
+            &mut pool,
+            (base_asset, quote_asset),
+            (tick_lower, tick_upper),
+            i128::try_from(liquidity).map_err(|_| ArithmeticError::Overflow)?,
+        )?;
+        ensure!(
+            base_amount <= desired_amounts.0 && quote_amount <= desired_amounts.1,
+            Error::<T, I>::InsufficientAmountIn
+        );
+        ensure!(
+            base_amount >= minimum_base_amount && quote_amount >= minimum_quote_amount,
+            Error::<T, I>::NotAnIdealPrice
+        );
+
+        let (fee_growth_inside_base_last, fee_growth_inside_quote_last) =
+            Self::fee_growth_inside(&pool, (base_asset, quote_asset), (tick_lower, tick_upper));
+        let position = Position {
+            base_asset,
+            quote_asset,
+            tick_lower,
+            tick_upper,
+            liquidity,
+            fee_growth_inside_base_last,
+            fee_growth_inside_quote_last,
+        };
+        let position_id = NextPositionId::<T, I>::mutate(|id| {
+            let position_id = *id;
+            *id = id.saturating_add(One::one());
+            position_id
+        });
+        Positions::<T, I>::insert(who, position_id, position);
+        ConcentratedPools::<T, I>::insert(base_asset, quote_asset, pool);
+
+        // a range out of the current price takes a single asset
+        for (asset_id, amount) in [(base_asset, base_amount), (quote_asset, quote_amount)] {
+            if !amount.is_zero() {
+                T::Assets::transfer(
+                    asset_id,
+                    who,
+                    &Self::account_id(),
+                    amount,
+                    asset_id == T::GetNativeCurrencyId::get(), // should keep alive if is native
+                )?;
+            }
+        }
+
+        Ok((position_id, position, (base_amount, quote_amount)))
+    }
+
+    /// Removes `liquidity` from a position and pays out the amounts and the fees of the position
+    #[require_transactional]
+    pub(crate) fn do_decrease_position_liquidity(
+        who: &T::AccountId,
+        position_id: PositionId,
+        liquidity: Balance,
+        (minimum_base_amount, minimum_quote_amount): (BalanceOf<T, I>, BalanceOf<T, I>),
+    ) -> Result<
+        (
+            Position<AssetIdOf<T, I>>,
+            (Balance, Balance),
+            (Balance, Balance),
+        ),
+        DispatchError,
+    > {
+        let mut position =
+            Self::positions(who, position_id).ok_or(Error::<T, I>::PositionDoesNotExist)?;
+        ensure!(
+            !liquidity.is_zero() && liquidity <= position.liquidity,
+            Error::<T, I>::InsufficientLiquidity
+        );
+        // a position is either withdrawn or keeps the min liquidity
+        let liquidity_left = position.liquidity.saturating_sub(liquidity);
+        ensure!(
+            liquidity_left.is_zero() || liquidity_left >= T::MinPositionLiquidity::get(),
+            Error::<T, I>::PositionLiquidityTooLow
+        );
+        let pair = (position.base_asset, position.quote_asset);
+        let mut pool =
+            Self::concentrated_pools(pair.0, pair.1).ok_or(Error::<T, I>::PoolDoesNotExist)?;
+
+        let fees = Self::take_position_fees(&pool, &mut position);
+        let (base_amount, quote_amount) = Self::update_position_liquidity(
+            &mut pool,
+            pair,
+            (position.tick_lower, position.tick_upper),
+            i128::try_from(liquidity)
+                .map_err(|_| ArithmeticError::Overflow)?
+                .saturating_neg(),
+        )?;
+        ensure!(
+            base_amount >= minimum_base_amount && quote_amount >= minimum_quote_amount,
+            Error::<T, I>::NotAnIdealPrice
+        );
+
+        position.liquidity = liquidity_left;
+        if position.liquidity.is_zero() {
+            Positions::<T, I>::remove(who, position_id);
+        } else {
+            Positions::<T, I>::insert(who, position_id, position);
+        }
+        ConcentratedPools::<T, I>::insert(pair.0, pair.1, pool);
+
+        Self::pay_out(
+            who,
+            pair,
+            (
+                base_amount
+                    .checked_add(fees.0)
+                    .ok_or(ArithmeticError::Overflow)?,
+                quote_amount
+                    .checked_add(fees.1)
+                    .ok_or(ArithmeticError::Overflow)?,
+            ),
+        )?;
+
+        Ok((position, (base_amount, quote_amount), fees))
+    }
+
+    #[require_transactional]
+    pub(crate) fn do_collect_position_fees(
+        who: &T::AccountId,
+        position_id: PositionId,
+    ) -> Result<(Position<AssetIdOf<T, I>>, (Balance, Balance)), DispatchError> {
+        let mut position =
+            Self::positions(who, position_id).ok_or(Error::<T, I>::PositionDoesNotExist)?;
+        let pool = Self::concentrated_pools(position.base_asset, position.quote_asset)
+            .ok_or(Error::<T, I>::PoolDoesNotExist)?;
+
+        let fees = Self::take_position_fees(&pool, &mut position);
+        Positions::<T, I>::insert(who, position_id, position);
+        Self::pay_out(who, (position.base_asset, position.quote_asset), fees)?;
+
+        Ok((position, fees))
+    }
+
+    /// Sells `amount_in` of `asset_in` to the concentrated liquidity pool
+    pub(crate) fn do_concentrated_swap(
+        who: &T::AccountId,
+        (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        amount_in: BalanceOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
+        let (_, amount_out, pool, crossed_ticks) = Self::simulate_concentrated_swap(
+            (base_asset, quote_asset),
+            !is_inverted,
+            amount_in,
+            true,
+        )?;
+        ensure!(!amount_out.is_zero(), Error::<T, I>::InsufficientAmountOut);
+
+        for (tick, fee_growth_global_base, fee_growth_global_quote) in crossed_ticks {
+            ConcentratedTicks::<T, I>::mutate((base_asset, quote_asset), tick, |info| {
+                if let Some(info) = info {
+                    info.fee_growth_outside_base =
+                        wrapping_sub(fee_growth_global_base, info.fee_growth_outside_base);
+                    info.fee_growth_outside_quote =
+                        wrapping_sub(fee_growth_global_quote, info.fee_growth_outside_quote);
+                }
+            });
+        }
+        ConcentratedPools::<T, I>::insert(base_asset, quote_asset, pool);
+
+        T::Assets::transfer(
+            asset_in,
+            who,
+            &Self::account_id(),
+            amount_in,
+            asset_in == T::GetNativeCurrencyId::get(), // should keep alive if is native
+        )?;
+        T::Assets::transfer(
+            asset_out,
+            &Self::account_id(),
+            who,
+            amount_out,
+            asset_out == T::GetNativeCurrencyId::get(), // should keep alive if is native
+        )?;
+
+        log::trace!(
+            target: "amm::do_concentrated_swap",
+            "who: {:?}, asset_in: {:?}, asset_out: {:?}, amount_in: {:?}, amount_out: {:?}",
+            &who,
+            &asset_in,
+            &asset_out,
+            &amount_in,
+            &amount_out,
+        );
+
+        Self::deposit_event(Event::<T, I>::ConcentratedTraded(
+            who.clone(),
+            asset_in,
+            asset_out,
+            amount_in,
+            amount_out,
+            pool.sqrt_price,
+            pool.liquidity,
+        ));
+
+        Ok(amount_out)
+    }
+
+    /// The amount out of selling `amount_in` to the concentrated liquidity pool
+    pub(crate) fn get_concentrated_amount_out(
+        amount_in: BalanceOf<T, I>,
+        asset_in: AssetIdOf<T, I>,
+        asset_out: AssetIdOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
+        let (_, amount_out, _, _) = Self::simulate_concentrated_swap(
+            (base_asset, quote_asset),
+            !is_inverted,
+            amount_in,
+            true,
+        )?;
+        Ok(amount_out)
+    }
+
+    /// The amount in needed to buy `amount_out` from the concentrated liquidity pool
+    pub(crate) fn get_concentrated_amount_in(
+        amount_out: BalanceOf<T, I>,
+        asset_in: AssetIdOf<T, I>,
+        asset_out: AssetIdOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
+        let (amount_in, _, _, _) = Self::simulate_concentrated_swap(
+            (base_asset, quote_asset),
+            !is_inverted,
+            amount_out,
+            false,
+        )?;
+        // To make sure the amount in sells for at least `amount_out`
+        Ok(amount_in
+            .checked_add(One::one())
+            .ok_or(ArithmeticError::Overflow)?)
+    }
+
+    /// Walks the swap through the initialized ticks without writing to storage. Returns
+    /// the amount in including the fees, the amount out, the updated pool and the ticks
+    /// crossed on the way.
+    fn simulate_concentrated_swap(
+        (base_asset, quote_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        base_in: bool,
+        amount: BalanceOf<T, I>,
+        exact_in: bool,
+    ) -> Result<(Balance, Balance, ConcentratedPool, Vec<CrossedTick>), DispatchError> {
+        let mut pool = Self::concentrated_pools(base_asset, quote_asset)
+            .ok_or(Error::<T, I>::PoolDoesNotExist)?;
+        let ticks = InitializedTicks::<T, I>::get(base_asset, quote_asset);
+        let mut crossed_ticks = Vec::new();
+        let (mut amount_remaining, mut amount_in, mut amount_out) =
+            (amount, Balance::zero(), Balance::zero());
+
+        while !amount_remaining.is_zero() {
+            let next_tick = if base_in {
+                ticks.iter().rev().find(|&&tick| tick <= pool.tick)
+            } else {
+                ticks.iter().find(|&&tick| tick > pool.tick)
+            }
+            .copied();
+            let target_tick = next_tick.unwrap_or(if base_in { MIN_TICK } else { MAX_TICK });
+            let sqrt_price_target =
+                sqrt_price_at_tick(target_tick).ok_or(Error::<T, I>::InvalidTickRange)?;
+
+            let step = compute_swap_step(
+                pool.sqrt_price,
+                sqrt_price_target,
+                pool.liquidity,
+                amount_remaining,
+                pool.fee,
+                exact_in,
+            )?;
+            let step_amount_in = step
+                .amount_in
+                .checked_add(step.fee_amount)
+                .ok_or(ArithmeticError::Overflow)?;
+            amount_remaining = amount_remaining.saturating_sub(if exact_in {
+                step_amount_in
+            } else {
+                step.amount_out
+            });
+            amount_in = amount_in
+                .checked_add(step_amount_in)
+                .ok_or(ArithmeticError::Overflow)?;
+            amount_out = amount_out
+                .checked_add(step.amount_out)
+                .ok_or(ArithmeticError::Overflow)?;
+
+            if !pool.liquidity.is_zero() {
+                let fee_growth =
+                    FixedU128::saturating_from_rational(step.fee_amount, pool.liquidity);
+                if base_in {
+                    pool.fee_growth_global_base =
+                        wrapping_add(pool.fee_growth_global_base, fee_growth);
+                } else {
+                    pool.fee_growth_global_quote =
+                        wrapping_add(pool.fee_growth_global_quote, fee_growth);
+                }
+            }
+            pool.sqrt_price = step.sqrt_price;
+
+            if pool.sqrt_price != sqrt_price_target {
+                pool.tick =
+                    tick_at_sqrt_price(pool.sqrt_price).ok_or(Error::<T, I>::InvalidPrice)?;
+                continue;
+            }
+            match next_tick {
+                Some(tick) => {
+                    let liquidity_net = Self::concentrated_ticks((base_asset, quote_asset), tick)
+                        .map(|info| info.liquidity_net)
+                        .unwrap_or_default();
+                    pool.liquidity = apply_liquidity_delta(
+                        pool.liquidity,
+                        if base_in {
+                            liquidity_net.saturating_neg()
+                        } else {
+                            liquidity_net
+                        },
+                    )?;
+                    crossed_ticks.push((
+                        tick,
+                        pool.fee_growth_global_base,
+                        pool.fee_growth_global_quote,
+                    ));
+                    pool.tick = if base_in { tick - 1 } else { tick };
+                }
+                None => {
+                    // the price range of the pool is exhausted
+                    ensure!(
+                        amount_remaining.is_zero(),
+                        Error::<T, I>::InsufficientLiquidity
+                    );
+                    pool.tick = if base_in {
+                        target_tick - 1
+                    } else {
+                        target_tick
+                    };
+                }
+            }
+        }
+
+        Ok((amount_in, amount_out, pool, crossed_ticks))
+    }
+
+    /// Adds `liquidity_delta` to the range, returns the amounts of the assets
+    /// the liquidity is worth, rounded in favor of the pool
+    fn update_position_liquidity(
+        pool: &mut ConcentratedPool,
+        pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        (tick_lower, tick_upper): (i32, i32),
+        liquidity_delta: i128,
+    ) -> Result<(Balance, Balance), DispatchError> {
+        Self::update_tick(pool, pair, tick_lower, liquidity_delta, false)?;
+        Self::update_tick(pool, pair, tick_upper, liquidity_delta, true)?;
+
+        let round_up = liquidity_delta > 0;
+        let liquidity = liquidity_delta.unsigned_abs();
+        let sqrt_price_lower =
+            sqrt_price_at_tick(tick_lower).ok_or(Error::<T, I>::InvalidTickRange)?;
+        let sqrt_price_upper =
+            sqrt_price_at_tick(tick_upper).ok_or(Error::<T, I>::InvalidTickRange)?;
+        if pool.tick < tick_lower {
+            Ok((
+                base_amount_delta(liquidity, sqrt_price_lower, sqrt_price_upper, round_up)?,
+                Zero::zero(),
+            ))
+        } else if pool.tick < tick_upper {
+            pool.liquidity = apply_liquidity_delta(pool.liquidity, liquidity_delta)?;
+            Ok((
+                base_amount_delta(liquidity, pool.sqrt_price, sqrt_price_upper, round_up)?,
+                quote_amount_delta(liquidity, sqrt_price_lower, pool.sqrt_price, round_up)?,
+            ))
+        } else {
+            Ok((
+                Zero::zero(),
+                quote_amount_delta(liquidity, sqrt_price_lower, sqrt_price_upper, round_up)?,
+            ))
+        }
+    }
+
+    fn update_tick(
+        pool: &ConcentratedPool,
+        (base_asset, quote_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        tick: i32,
+        liquidity_delta: i128,
+        upper: bool,
+    ) -> DispatchResult {
+        ConcentratedTicks::<T, I>::try_mutate((base_asset, quote_asset), tick, |info| {
+            let mut tick_info = info.unwrap_or_default();
+            if tick_info.liquidity_gross.is_zero() {
+                // by convention all the fees so far were earned below an initialized tick
+                if tick <= pool.tick {
+                    tick_info.fee_growth_outside_base = pool.fee_growth_global_base;
+                    tick_info.fee_growth_outside_quote = pool.fee_growth_global_quote;
+                }
+                InitializedTicks::<T, I>::try_mutate(base_asset, quote_asset, |ticks| {
+                    let index = ticks.binary_search(&tick).unwrap_or_else(|index| index);
+                    ticks
+                        .try_insert(index, tick)
+                        .map_err(|_| Error::<T, I>::TooManyTicks)
+                })?;
+            }
+
+            tick_info.liquidity_gross =
+                apply_liquidity_delta(tick_info.liquidity_gross, liquidity_delta)?;
+            tick_info.liquidity_net = if upper {
+                tick_info.liquidity_net.checked_sub(liquidity_delta)
+            } else {
+                tick_info.liquidity_net.checked_add(liquidity_delta)
+            }
+            .ok_or(ArithmeticError::Overflow)?;
+
+            if tick_info.liquidity_gross.is_zero() {
+                InitializedTicks::<T, I>::mutate(base_asset, quote_asset, |ticks| {
+                    ticks.retain(|&t| t != tick)
+                });
+                *info = None;
+            } else {
+                *info = Some(tick_info);
+            }
+            Ok(())
+        })
+    }
+
+    /// The fees earned per unit of liquidity inside the range
+    fn fee_growth_inside(
+        pool: &ConcentratedPool,
+        pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        (tick_lower, tick_upper): (i32, i32),
+    ) -> (FixedU128, FixedU128) {
+        let lower = Self::concentrated_ticks(pair, tick_lower).unwrap_or_default();
+        let upper = Self::concentrated_ticks(pair, tick_upper).unwrap_or_default();
+        let global = (pool.fee_growth_global_base, pool.fee_growth_global_quote);
+
+        let below = if pool.tick >= tick_lower {
+            (
+                lower.fee_growth_outside_base,
+                lower.fee_growth_outside_quote,
+            )
+        } else {
+            (
+                wrapping_sub(global.0, lower.fee_growth_outside_base),
+                wrapping_sub(global.1, lower.fee_growth_outside_quote),
+            )
+        };
+        let above = if pool.tick < tick_upper {
+            (
+                upper.fee_growth_outside_base,
+                upper.fee_growth_outside_quote,
+            )
+        } else {
+            (
+                wrapping_sub(global.0, upper.fee_growth_outside_base),
+                wrapping_sub(global.1, upper.fee_growth_outside_quote),
+            )
+        };
+
+        (
+            wrapping_sub(wrapping_sub(global.0, below.0), above.0),
+            wrapping_sub(wrapping_sub(global.1, below.1), above.1),
+        )
+    }
+
+    /// The fees earned by the position since they were last collected
+    fn take_position_fees(
+        pool: &ConcentratedPool,
+        position: &mut Position<AssetIdOf<T, I>>,
+    ) -> (Balance, Balance) {
+        let (fee_growth_inside_base, fee_growth_inside_quote) = Self::fee_growth_inside(
+            pool,
+            (position.base_asset, position.quote_asset),
+            (position.tick_lower, position.tick_upper),
+        );
+        let fees = (
+            wrapping_sub(fee_growth_inside_base, position.fee_growth_inside_base_last)
+                .saturating_mul_int(position.liquidity),
+            wrapping_sub(
+                fee_growth_inside_quote,
+                position.fee_growth_inside_quote_last,
+            )
+            .saturating_mul_int(position.liquidity),
+        );
+        position.fee_growth_inside_base_last = fee_growth_inside_base;
+        position.fee_growth_inside_quote_last = fee_growth_inside_quote;
+        fees
+    }
+
+    fn pay_out(
+        who: &T::AccountId,
+        (base_asset, quote_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        (base_amount, quote_amount): (Balance, Balance),
+    ) -> DispatchResult {
+        for (asset_id, amount) in [(base_asset, base_amount), (quote_asset, quote_amount)] {
+            if !amount.is_zero() {
+                T::Assets::transfer(
+                    asset_id,
+                    &Self::account_id(),
+                    who,
+                    amount,
+                    asset_id == T::GetNativeCurrencyId::get(), // should keep alive if is native
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -15,6 +15,17 @@
 //! # Automatic Market Maker (AMM)
 //!
 //! Given any [X, Y] asset pair, "base" is the `X` asset while "quote" is the `Y` asset.
+//!
+//! Each constant product pool carries its own LP fee and optionally its own protocol fee,
+//! both set when the pool is created. The LP fee can grow with the volatility of the pool
+//! price when the pool has a dynamic fee.
+//!
+//! Besides the constant product pools, a pair can have a concentrated liquidity pool
+//! with its own fee tier. Quotes and swaps of the `AMM` trait use the pool of the pair
+//! with the better price.
+//!
+//! Liquidity of a constant product pool can also be zapped in from or out to a single
+//! asset, the pool itself swaps the part of the other asset.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -26,6 +37,8 @@ mod mock;
 mod tests;
 
 mod benchmarking;
+mod concentrated;
+pub mod migrations;
 pub mod weights;
 
 use frame_support::{
@@ -40,14 +53,18 @@ use frame_support::{
     transactional, Blake2_128Concat, PalletId,
 };
 use frame_system::{ensure_signed, pallet_prelude::OriginFor};
-use pallet_traits::{ConvertToBigUint, Pool};
+use pallet_traits::{ConvertToBigUint, Pool, PoolsHook};
 use primitives::{Balance, CurrencyId, Ratio};
 use sp_runtime::{
     traits::{AccountIdConversion, CheckedAdd, CheckedSub, One, Saturating, Zero},
-    ArithmeticError, DispatchError, FixedPointNumber, FixedU128, SaturatedConversion,
+    ArithmeticError, DispatchError, FixedPointNumber, FixedU128, PerThing, SaturatedConversion,
 };
 use sp_std::{cmp::min, result::Result, vec::Vec};
 
+pub use concentrated::{
+    sqrt_price_at_tick, tick_at_sqrt_price, ConcentratedPool, Position, PositionId, TickInfo,
+    MAX_TICK, MIN_TICK,
+};
 pub use pallet::*;
 pub use weights::WeightInfo;
 
@@ -60,6 +77,32 @@ pub type AssetIdOf<T, I = ()> =
 pub type BalanceOf<T, I = ()> =
     <<T as Config<I>>::Assets as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 
+/// Utility type for managing upgrades/migrations.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum Versions {
+    V1,
+    V2,
+}
+
+/// Volatility-driven LP fee of a pool. The fee grows with the deviation of the spot
+/// price from the average price of the last full window, up to `max_fee`.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct DynamicFee<BlockNumber> {
+    /// Upper bound of the LP fee
+    pub max_fee: Ratio,
+    /// LP fee added per 100% deviation of the spot price from the average price
+    pub sensitivity: Ratio,
+    /// How many blocks the average price is measured over
+    pub window: BlockNumber,
+    /// Block the current window started at
+    pub window_start: BlockNumber,
+    /// `price_0_cumulative_last` of the pool when the current window started
+    pub price_0_cumulative_start: Balance,
+    /// Average price of the last full window, zero until the first window is over
+    pub average_price: FixedU128,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
@@ -92,8 +135,8 @@ pub mod pallet {
         /// Specify which origin is allowed to update fee receiver.
         type ProtocolFeeUpdateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 
-        /// Defines the fees taken out of each trade and sent back to the AMM pool,
-        /// typically 0.3%.
+        /// Defines the default fees taken out of each trade and sent back to the AMM
+        /// pool, typically 0.3%. New pools start with this fee.
         #[pallet::constant]
         type LpFee: Get<Ratio>;
 
@@ -115,6 +158,19 @@ pub mod pallet {
 
         #[pallet::constant]
         type GetNativeCurrencyId: Get<AssetIdOf<Self, I>>;
+
+        /// How many initialized ticks a concentrated liquidity pool can have
+        #[pallet::constant]
+        type MaxTicksPerPool: Get<u32>;
+
+        /// The min liquidity of a concentrated liquidity position, dust positions
+        /// would fill up the initialized ticks of a pool otherwise
+        #[pallet::constant]
+        type MinPositionLiquidity: Get<BalanceOf<Self, I>>;
+
+        /// Notified when the AMM starts trading a pair, such as the router
+        /// keeping its pool graph
+        type PoolsHook: PoolsHook<AssetIdOf<Self, I>>;
     }
 
     #[pallet::error]
@@ -141,6 +197,24 @@ pub mod pallet {
         ConversionToU128Failed,
         /// Protocol fee receiver not set
         ProtocolFeeReceiverNotSet,
+        /// The tick spacing of a fee tier cannot be zero
+        InvalidFeeTier,
+        /// Fee tier is not enabled
+        FeeTierNotEnabled,
+        /// The price is out of the tick range
+        InvalidPrice,
+        /// Pool fees must be below 100% and the dynamic fee needs a window
+        InvalidPoolFee,
+        /// Ticks must be ordered, within the tick range and multiples of the tick spacing
+        InvalidTickRange,
+        /// The pool has too many initialized ticks
+        TooManyTicks,
+        /// Position does not exist
+        PositionDoesNotExist,
+        /// The amount received is below the accepted minimum
+        SlippageExceeded,
+        /// The liquidity of the position is below the min position liquidity
+        PositionLiquidityTooLow,
     }
 
     #[pallet::event]
@@ -197,6 +271,88 @@ pub mod pallet {
 
         /// Protocol fee receiver updated
         ProtocolFeeReceiverUpdated(T::AccountId),
+
+        /// Fees of a pool updated
+        /// [base_currency_id, quote_currency_id, lp_fee, protocol_fee]
+        PoolFeesUpdated(AssetIdOf<T, I>, AssetIdOf<T, I>, Ratio, Option<Ratio>),
+
+        /// Dynamic fee of a pool updated
+        /// [base_currency_id, quote_currency_id, (max_fee, sensitivity, window)]
+        DynamicFeeUpdated(
+            AssetIdOf<T, I>,
+            AssetIdOf<T, I>,
+            Option<(Ratio, Ratio, T::BlockNumber)>,
+        ),
+
+        /// Fee tier of concentrated liquidity pools updated
+        /// [fee, tick_spacing]
+        FeeTierUpdated(Ratio, Option<u32>),
+
+        /// A concentrated liquidity pool has been created
+        /// [base_currency_id, quote_currency_id, fee, sqrt_price]
+        ConcentratedPoolCreated(AssetIdOf<T, I>, AssetIdOf<T, I>, Ratio, FixedU128),
+
+        /// Add liquidity to a price range of a concentrated liquidity pool
+        /// [owner, position_id, base_currency_id, quote_currency_id, tick_lower, tick_upper, liquidity, base_amount_added, quote_amount_added]
+        PositionMinted(
+            T::AccountId,
+            PositionId,
+            AssetIdOf<T, I>,
+            AssetIdOf<T, I>,
+            i32,
+            i32,
+            BalanceOf<T, I>,
+            BalanceOf<T, I>,
+            BalanceOf<T, I>,
+        ),
+
+        /// Remove liquidity from a position
+        /// [owner, position_id, liquidity, base_amount_removed, quote_amount_removed]
+        PositionLiquidityDecreased(
+            T::AccountId,
+            PositionId,
+            BalanceOf<T, I>,
+            BalanceOf<T, I>,
+            BalanceOf<T, I>,
+        ),
+
+        /// Fees of a position collected
+        /// [owner, position_id, base_fees, quote_fees]
+        PositionFeesCollected(T::AccountId, PositionId, BalanceOf<T, I>, BalanceOf<T, I>),
+
+        /// Trade using the liquidity of a concentrated liquidity pool
+        /// [trader, currency_id_in, currency_id_out, amount_in, amount_out, new_sqrt_price, new_liquidity]
+        ConcentratedTraded(
+            T::AccountId,
+            AssetIdOf<T, I>,
+            AssetIdOf<T, I>,
+            BalanceOf<T, I>,
+            BalanceOf<T, I>,
+            FixedU128,
+            BalanceOf<T, I>,
+        ),
+
+        /// Add liquidity to a pool from a single asset
+        /// [sender, currency_id_in, paired_currency_id, amount_in, liquidity, lp_token_id]
+        ZappedIn(
+            T::AccountId,
+            AssetIdOf<T, I>,
+            AssetIdOf<T, I>,
+            BalanceOf<T, I>,
+            BalanceOf<T, I>,
+            AssetIdOf<T, I>,
+        ),
+
+        /// Remove liquidity from a pool into a single asset
+        /// [sender, currency_id_out, paired_currency_id, liquidity, amount_out, lp_token_id]
+        ZappedOut(
+            T::AccountId,
+            AssetIdOf<T, I>,
+            AssetIdOf<T, I>,
+            BalanceOf<T, I>,
+            BalanceOf<T, I>,
+            AssetIdOf<T, I>,
+        ),
     }
 
     #[pallet::pallet]
@@ -215,7 +371,8 @@ pub mod pallet {
         OptionQuery,
     >;
 
-    /// How much the protocol is taking out of each trade.
+    /// How much the protocol is taking out of each trade of the pools without
+    /// their own protocol fee.
     #[pallet::storage]
     #[pallet::getter(fn protocol_fee)]
     pub type ProtocolFee<T: Config<I>, I: 'static = ()> = StorageValue<_, Ratio, ValueQuery>;
@@ -224,6 +381,92 @@ pub mod pallet {
     #[pallet::storage]
     pub type ProtocolFeeReceiver<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AccountId>;
 
+    /// Enabled fee tiers of concentrated liquidity pools and their tick spacing
+    #[pallet::storage]
+    #[pallet::getter(fn fee_tier)]
+    pub type FeeTiers<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Twox64Concat, Ratio, u32, OptionQuery>;
+
+    /// Concentrated liquidity pools of the sorted asset pairs
+    #[pallet::storage]
+    #[pallet::getter(fn concentrated_pools)]
+    pub type ConcentratedPools<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        ConcentratedPool,
+        OptionQuery,
+    >;
+
+    /// Initialized ticks of the concentrated liquidity pools
+    #[pallet::storage]
+    #[pallet::getter(fn concentrated_ticks)]
+    pub type ConcentratedTicks<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        Twox64Concat,
+        i32,
+        TickInfo,
+        OptionQuery,
+    >;
+
+    /// The sorted initialized ticks of each concentrated liquidity pool
+    #[pallet::storage]
+    pub type InitializedTicks<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        BoundedVec<i32, T::MaxTicksPerPool>,
+        ValueQuery,
+    >;
+
+    /// Concentrated liquidity positions of each owner
+    #[pallet::storage]
+    #[pallet::getter(fn positions)]
+    pub type Positions<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Twox64Concat,
+        PositionId,
+        Position<AssetIdOf<T, I>>,
+        OptionQuery,
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_position_id)]
+    pub type NextPositionId<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, PositionId, ValueQuery>;
+
+    /// Dynamic fees of the pools, pools without one trade at their LP fee
+    #[pallet::storage]
+    #[pallet::getter(fn dynamic_fees)]
+    pub type DynamicFees<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        DynamicFee<T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// DefaultVersion is using for initialize the StorageVersion
+    #[pallet::type_value]
+    pub(super) fn DefaultVersion<T: Config<I>, I: 'static>() -> Versions {
+        Versions::V1
+    }
+
+    /// Storage version of the pallet.
+    #[pallet::storage]
+    pub(crate) type StorageVersion<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, Versions, ValueQuery, DefaultVersion<T, I>>;
+
     #[pallet::call]
     impl<T: Config<I>, I: 'static> Pallet<T, I> {
         /// Allow users to add liquidity to a given pool
@@ -367,6 +610,9 @@ pub mod pallet {
         /// - `liquidity_amounts`: Liquidity amounts to be added in pool
         /// - `lptoken_receiver`: Allocate any liquidity tokens to lptoken_receiver
         /// - `lp_token_id`: Liquidity pool share representative token
+        /// - `lp_fee`: Fee of the trades sent back to the pool
+        /// - `protocol_fee`: Share of the LP fees minted to the protocol fee receiver,
+        ///   `None` follows the protocol fee of the AMM
         #[pallet::call_index(2)]
         #[pallet::weight(T::AMMWeightInfo::create_pool())]
         #[transactional]
@@ -376,8 +622,11 @@ pub mod pallet {
             liquidity_amounts: (BalanceOf<T, I>, BalanceOf<T, I>),
             lptoken_receiver: T::AccountId,
             lp_token_id: AssetIdOf<T, I>,
+            lp_fee: Ratio,
+            protocol_fee: Option<Ratio>,
         ) -> DispatchResultWithPostInfo {
             T::CreatePoolOrigin::ensure_origin(origin)?;
+            Self::ensure_valid_pool_fees(lp_fee, protocol_fee)?;
 
             let (is_inverted, base_asset, quote_asset) = Self::sort_assets(pair)?;
             ensure!(
@@ -398,7 +647,7 @@ pub mod pallet {
                 Error::<T, I>::LpTokenAlreadyExists
             );
 
-            let mut pool = Pool::new(lp_token_id);
+            let mut pool = Pool::new(lp_token_id, lp_fee, protocol_fee);
 
             Self::deposit_event(Event::<T, I>::PoolCreated(
                 lptoken_receiver.clone(),
@@ -414,6 +663,9 @@ pub mod pallet {
                 (base_asset, quote_asset),
             )?;
 
+            if !ConcentratedPools::<T, I>::contains_key(base_asset, quote_asset) {
+                T::PoolsHook::on_pool_created((base_asset, quote_asset));
+            }
             Pools::<T, I>::insert(base_asset, quote_asset, pool);
 
             log::trace!(
@@ -469,6 +721,361 @@ pub mod pallet {
             ));
             Ok(().into())
         }
+
+        /// Enable a fee tier of concentrated liquidity pools, governance only
+        ///
+        /// - `fee`: Fee of the trades
+        /// - `tick_spacing`: Spacing of the position ticks, `None` disables the fee tier
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::AMMWeightInfo::set_fee_tier())]
+        #[transactional]
+        pub fn set_fee_tier(
+            origin: OriginFor<T>,
+            fee: Ratio,
+            tick_spacing: Option<u32>,
+        ) -> DispatchResultWithPostInfo {
+            T::CreatePoolOrigin::ensure_origin(origin)?;
+            ensure!(
+                tick_spacing.map_or(true, |spacing| spacing > 0 && spacing <= MAX_TICK as u32),
+                Error::<T, I>::InvalidFeeTier
+            );
+            FeeTiers::<T, I>::set(fee, tick_spacing);
+            Self::deposit_event(Event::<T, I>::FeeTierUpdated(fee, tick_spacing));
+            Ok(().into())
+        }
+
+        /// Create a concentrated liquidity pool, governance only
+        ///
+        /// - `pair`: Currency pool
+        /// - `fee`: An enabled fee tier
+        /// - `initial_price`: Price of `pair.0` in `pair.1`
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::AMMWeightInfo::create_concentrated_pool())]
+        #[transactional]
+        pub fn create_concentrated_pool(
+            origin: OriginFor<T>,
+            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            fee: Ratio,
+            initial_price: FixedU128,
+        ) -> DispatchResultWithPostInfo {
+            T::CreatePoolOrigin::ensure_origin(origin)?;
+            let (is_inverted, base_asset, quote_asset) = Self::sort_assets(pair)?;
+            let price = if is_inverted {
+                initial_price.reciprocal()
+            } else {
+                Some(initial_price)
+            }
+            .filter(|price| !price.is_zero())
+            .ok_or(Error::<T, I>::InvalidPrice)?;
+
+            let pool = Self::do_create_concentrated_pool((base_asset, quote_asset), fee, price)?;
+
+            Self::deposit_event(Event::<T, I>::ConcentratedPoolCreated(
+                base_asset,
+                quote_asset,
+                fee,
+                pool.sqrt_price,
+            ));
+            Ok(().into())
+        }
+
+        /// Add liquidity to a price range of a concentrated liquidity pool
+        ///
+        /// - `pair`: Currency pool, in which liquidity will be added
+        /// - `ticks`: Lower and upper tick of the price of the base asset, the one with
+        ///   the larger id, in the quote asset
+        /// - `desired_amounts`: Liquidity amounts to be added in pool
+        /// - `minimum_amounts`: specifying its "worst case" amounts
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::AMMWeightInfo::mint_position())]
+        #[transactional]
+        pub fn mint_position(
+            origin: OriginFor<T>,
+            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            ticks: (i32, i32),
+            desired_amounts: (BalanceOf<T, I>, BalanceOf<T, I>),
+            minimum_amounts: (BalanceOf<T, I>, BalanceOf<T, I>),
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let (is_inverted, base_asset, quote_asset) = Self::sort_assets(pair)?;
+            let (desired_amounts, minimum_amounts) = if is_inverted {
+                (
+                    (desired_amounts.1, desired_amounts.0),
+                    (minimum_amounts.1, minimum_amounts.0),
+                )
+            } else {
+                (desired_amounts, minimum_amounts)
+            };
+
+            let (position_id, position, (base_amount, quote_amount)) = Self::do_mint_position(
+                &who,
+                (base_asset, quote_asset),
+                ticks,
+                desired_amounts,
+                minimum_amounts,
+            )?;
+
+            Self::deposit_event(Event::<T, I>::PositionMinted(
+                who,
+                position_id,
+                base_asset,
+                quote_asset,
+                position.tick_lower,
+                position.tick_upper,
+                position.liquidity,
+                base_amount,
+                quote_amount,
+            ));
+            Ok(().into())
+        }
+
+        /// Remove liquidity from a position, the fees of the position are paid out too
+        ///
+        /// - `position_id`: Position of the caller
+        /// - `liquidity`: Liquidity to be removed from the position
+        /// - `minimum_amounts`: specifying its "worst case" base and quote amounts
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::AMMWeightInfo::decrease_position_liquidity())]
+        #[transactional]
+        pub fn decrease_position_liquidity(
+            origin: OriginFor<T>,
+            position_id: PositionId,
+            #[pallet::compact] liquidity: BalanceOf<T, I>,
+            minimum_amounts: (BalanceOf<T, I>, BalanceOf<T, I>),
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let (_, (base_amount, quote_amount), fees) = Self::do_decrease_position_liquidity(
+                &who,
+                position_id,
+                liquidity,
+                minimum_amounts,
+            )?;
+
+            Self::deposit_event(Event::<T, I>::PositionFeesCollected(
+                who.clone(),
+                position_id,
+                fees.0,
+                fees.1,
+            ));
+            Self::deposit_event(Event::<T, I>::PositionLiquidityDecreased(
+                who,
+                position_id,
+                liquidity,
+                base_amount,
+                quote_amount,
+            ));
+            Ok(().into())
+        }
+
+        /// Collect the fees earned by a position
+        ///
+        /// - `position_id`: Position of the caller
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::AMMWeightInfo::collect_position_fees())]
+        #[transactional]
+        pub fn collect_position_fees(
+            origin: OriginFor<T>,
+            position_id: PositionId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let (_, (base_fees, quote_fees)) = Self::do_collect_position_fees(&who, position_id)?;
+
+            Self::deposit_event(Event::<T, I>::PositionFeesCollected(
+                who,
+                position_id,
+                base_fees,
+                quote_fees,
+            ));
+            Ok(().into())
+        }
+
+        /// Update the fees of a pool, governance only
+        ///
+        /// - `pair`: Currency pool
+        /// - `lp_fee`: Fee of the trades sent back to the pool
+        /// - `protocol_fee`: Share of the LP fees minted to the protocol fee receiver,
+        ///   `None` follows the protocol fee of the AMM
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::AMMWeightInfo::update_pool_fees())]
+        #[transactional]
+        pub fn update_pool_fees(
+            origin: OriginFor<T>,
+            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            lp_fee: Ratio,
+            protocol_fee: Option<Ratio>,
+        ) -> DispatchResultWithPostInfo {
+            T::ProtocolFeeUpdateOrigin::ensure_origin(origin)?;
+            Self::ensure_valid_pool_fees(lp_fee, protocol_fee)?;
+            let (_, base_asset, quote_asset) = Self::sort_assets(pair)?;
+
+            Pools::<T, I>::try_mutate(base_asset, quote_asset, |pool| -> DispatchResult {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+
+                // settle the protocol fees accrued at the previous rate
+                Self::do_mint_protocol_fee(pool)?;
+
+                pool.lp_fee = lp_fee;
+                pool.protocol_fee = protocol_fee;
+                if Self::protocol_fee_on(pool) {
+                    pool.base_amount_last = pool.base_amount;
+                    pool.quote_amount_last = pool.quote_amount;
+                }
+
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::<T, I>::PoolFeesUpdated(
+                base_asset,
+                quote_asset,
+                lp_fee,
+                protocol_fee,
+            ));
+            Ok(().into())
+        }
+
+        /// Set the dynamic fee of a pool, governance only
+        ///
+        /// - `pair`: Currency pool
+        /// - `params`: Max fee, sensitivity and window of the dynamic fee,
+        ///   `None` removes the dynamic fee
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::AMMWeightInfo::set_dynamic_fee())]
+        #[transactional]
+        pub fn set_dynamic_fee(
+            origin: OriginFor<T>,
+            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            params: Option<(Ratio, Ratio, T::BlockNumber)>,
+        ) -> DispatchResultWithPostInfo {
+            T::ProtocolFeeUpdateOrigin::ensure_origin(origin)?;
+            let (_, base_asset, quote_asset) = Self::sort_assets(pair)?;
+
+            Pools::<T, I>::try_mutate(base_asset, quote_asset, |pool| -> DispatchResult {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+
+                let dynamic_fee = match params {
+                    Some((max_fee, sensitivity, window)) => {
+                        ensure!(
+                            max_fee < Ratio::one() && !window.is_zero(),
+                            Error::<T, I>::InvalidPoolFee
+                        );
+                        // start the first window from the prices accumulated so far
+                        Self::do_update_oracle(pool)?;
+                        Some(DynamicFee {
+                            max_fee,
+                            sensitivity,
+                            window,
+                            window_start: frame_system::Pallet::<T>::block_number(),
+                            price_0_cumulative_start: pool.price_0_cumulative_last,
+                            average_price: Zero::zero(),
+                        })
+                    }
+                    None => None,
+                };
+                DynamicFees::<T, I>::set(base_asset, quote_asset, dynamic_fee);
+
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::<T, I>::DynamicFeeUpdated(
+                base_asset,
+                quote_asset,
+                params,
+            ));
+            Ok(().into())
+        }
+
+        /// Add liquidity to a pool from a single asset, the fraction of the amount
+        /// that balances the pool is swapped for the paired asset first
+        ///
+        /// - `pair`: Currency pool, the first currency is the one supplied
+        /// - `amount_in`: Amount of the supplied currency
+        /// - `min_liquidity`: Minimum liquidity to receive
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::AMMWeightInfo::zap_in())]
+        #[transactional]
+        pub fn zap_in(
+            origin: OriginFor<T>,
+            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            #[pallet::compact] amount_in: BalanceOf<T, I>,
+            #[pallet::compact] min_liquidity: BalanceOf<T, I>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::do_zap_in(&who, pair, amount_in, min_liquidity)?;
+            Ok(().into())
+        }
+
+        /// Remove liquidity from a pool into a single asset, the paired asset
+        /// removed is swapped back in the pool
+        ///
+        /// - `pair`: Currency pool, the first currency is the one received
+        /// - `liquidity`: Liquidity to be removed
+        /// - `min_amount_out`: Minimum amount of the received currency
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::AMMWeightInfo::zap_out())]
+        #[transactional]
+        pub fn zap_out(
+            origin: OriginFor<T>,
+            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            #[pallet::compact] liquidity: BalanceOf<T, I>,
+            #[pallet::compact] min_amount_out: BalanceOf<T, I>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let (asset_out, paired_asset) = pair;
+            let (is_inverted, base_asset, quote_asset) = Self::sort_assets(pair)?;
+
+            Pools::<T, I>::try_mutate(
+                base_asset,
+                quote_asset,
+                |pool| -> DispatchResultWithPostInfo {
+                    let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+
+                    Self::do_mint_protocol_fee(pool)?;
+
+                    let (base_amount_removed, quote_amount_removed) = Self::do_remove_liquidity(
+                        &who,
+                        pool,
+                        liquidity,
+                        (base_asset, quote_asset),
+                    )?;
+                    let (amount_removed, paired_amount) = if is_inverted {
+                        (quote_amount_removed, base_amount_removed)
+                    } else {
+                        (base_amount_removed, quote_amount_removed)
+                    };
+
+                    let amount_swapped =
+                        Self::do_pool_swap(&who, pool, (paired_asset, asset_out), paired_amount)?;
+                    let amount_out = amount_removed
+                        .checked_add(amount_swapped)
+                        .ok_or(ArithmeticError::Overflow)?;
+                    ensure!(
+                        amount_out >= min_amount_out,
+                        Error::<T, I>::SlippageExceeded
+                    );
+
+                    log::trace!(
+                        target: "amm::zap_out",
+                        "who: {:?}, asset_out: {:?}, paired_asset: {:?}, liquidity: {:?}, amount_out: {:?}",
+                        &who,
+                        &asset_out,
+                        &paired_asset,
+                        &liquidity,
+                        &amount_out
+                    );
+
+                    Self::deposit_event(Event::<T, I>::ZappedOut(
+                        who,
+                        asset_out,
+                        paired_asset,
+                        liquidity,
+                        amount_out,
+                        pool.lp_token_id,
+                    ));
+
+                    Ok(().into())
+                },
+            )
+        }
     }
 }
 
@@ -551,12 +1158,62 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         }
     }
 
-    fn protocol_fee_on() -> bool {
-        !Self::protocol_fee().is_zero() && Self::protolcol_fee_receiver().is_ok()
+    fn ensure_valid_pool_fees(lp_fee: Ratio, protocol_fee: Option<Ratio>) -> DispatchResult {
+        ensure!(
+            lp_fee < Ratio::one() && protocol_fee.map_or(true, |fee| fee < Ratio::one()),
+            Error::<T, I>::InvalidPoolFee
+        );
+        Ok(())
+    }
+
+    // the protocol fee of a pool, pools without their own follow the protocol fee of the AMM
+    fn pool_protocol_fee(pool: &Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>) -> Ratio {
+        pool.protocol_fee.unwrap_or_else(Self::protocol_fee)
+    }
+
+    fn protocol_fee_on(pool: &Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>) -> bool {
+        !Self::pool_protocol_fee(pool).is_zero() && Self::protolcol_fee_receiver().is_ok()
+    }
+
+    fn get_protocol_fee_reciprocal_proportion(
+        pool: &Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        Ok(Self::pool_protocol_fee(pool)
+            .saturating_reciprocal_mul_floor::<BalanceOf<T, I>>(One::one()))
     }
 
-    fn get_protocol_fee_reciprocal_proportion() -> Result<BalanceOf<T, I>, DispatchError> {
-        Ok(Self::protocol_fee().saturating_reciprocal_mul_floor::<BalanceOf<T, I>>(One::one()))
+    /// The LP fee of the next trade of a pool, the dynamic fee adds to the LP fee
+    /// in proportion to the deviation of the spot price from the average price
+    pub fn current_lp_fee(
+        (base_asset, quote_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        pool: &Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
+    ) -> Ratio {
+        let dynamic_fee = match Self::dynamic_fees(base_asset, quote_asset) {
+            Some(dynamic_fee) if !dynamic_fee.average_price.is_zero() => dynamic_fee,
+            _ => return pool.lp_fee,
+        };
+        if pool.base_amount.is_zero() {
+            return pool.lp_fee;
+        }
+
+        let spot_price = FixedU128::saturating_from_rational(pool.quote_amount, pool.base_amount);
+        let average_price = dynamic_fee.average_price;
+        let deviation = if spot_price > average_price {
+            spot_price.saturating_sub(average_price)
+        } else {
+            average_price.saturating_sub(spot_price)
+        }
+        .checked_div(&average_price)
+        .unwrap_or(FixedU128::from_inner(u128::MAX));
+        let volatility_fee = deviation.saturating_mul(dynamic_fee.sensitivity.into());
+
+        pool.lp_fee
+            .saturating_add(Ratio::from_rational(
+                volatility_fee.into_inner(),
+                FixedU128::DIV,
+            ))
+            .min(dynamic_fee.max_fee)
+            .max(pool.lp_fee)
     }
 
     // given an input amount and a vector of assets, return a vector of output
@@ -570,8 +1227,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
         amounts_out[0] = amount_in;
         for i in 0..(path.len() - 1) {
-            let (reserve_in, reserve_out) = Self::get_reserves(path[i], path[i + 1])?;
-            let amount_out = Self::get_amount_out(amounts_out[i], reserve_in, reserve_out)?;
+            let (amount_out, _) = Self::get_best_amount_out(amounts_out[i], path[i], path[i + 1])?;
             amounts_out[i + 1] = amount_out;
         }
 
@@ -590,14 +1246,79 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
         amounts_in[amount_len - 1] = amount_out;
         for i in (1..(path.len())).rev() {
-            let (reserve_in, reserve_out) = Self::get_reserves(path[i - 1], path[i])?;
-            let amount_in = Self::get_amount_in(amounts_in[i], reserve_in, reserve_out)?;
+            let amount_in = Self::get_best_amount_in(amounts_in[i], path[i - 1], path[i])?;
             amounts_in[i - 1] = amount_in;
         }
 
         Ok(amounts_in)
     }
 
+    // the largest amount out of the constant product and the concentrated liquidity
+    // pool of the pair, and whether it's the concentrated liquidity pool
+    fn get_best_amount_out(
+        amount_in: BalanceOf<T, I>,
+        asset_in: AssetIdOf<T, I>,
+        asset_out: AssetIdOf<T, I>,
+    ) -> Result<(BalanceOf<T, I>, bool), DispatchError> {
+        let amount_out = Self::get_reserves_and_fee(asset_in, asset_out).and_then(
+            |(reserve_in, reserve_out, lp_fee)| {
+                Self::get_amount_out(amount_in, reserve_in, reserve_out, lp_fee)
+            },
+        );
+        if !Self::concentrated_pool_exists(asset_in, asset_out) {
+            return amount_out.map(|amount_out| (amount_out, false));
+        }
+
+        let concentrated_amount_out =
+            Self::get_concentrated_amount_out(amount_in, asset_in, asset_out);
+        match (amount_out, concentrated_amount_out) {
+            (Ok(amount_out), Ok(concentrated_amount_out))
+                if concentrated_amount_out > amount_out =>
+            {
+                Ok((concentrated_amount_out, true))
+            }
+            (Ok(amount_out), _) => Ok((amount_out, false)),
+            (Err(err), Err(_)) if err != Error::<T, I>::PoolDoesNotExist.into() => Err(err),
+            (Err(_), concentrated_amount_out) => {
+                concentrated_amount_out.map(|amount_out| (amount_out, true))
+            }
+        }
+    }
+
+    // the smallest amount in of the constant product and the concentrated liquidity pool
+    // of the pair
+    fn get_best_amount_in(
+        amount_out: BalanceOf<T, I>,
+        asset_in: AssetIdOf<T, I>,
+        asset_out: AssetIdOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let amount_in = Self::get_reserves_and_fee(asset_in, asset_out).and_then(
+            |(reserve_in, reserve_out, lp_fee)| {
+                Self::get_amount_in(amount_out, reserve_in, reserve_out, lp_fee)
+            },
+        );
+        if !Self::concentrated_pool_exists(asset_in, asset_out) {
+            return amount_in;
+        }
+
+        let concentrated_amount_in =
+            Self::get_concentrated_amount_in(amount_out, asset_in, asset_out);
+        match (amount_in, concentrated_amount_in) {
+            (Ok(amount_in), Ok(concentrated_amount_in)) => {
+                Ok(min(amount_in, concentrated_amount_in))
+            }
+            (Ok(amount_in), Err(_)) => Ok(amount_in),
+            (Err(err), Err(_)) if err != Error::<T, I>::PoolDoesNotExist.into() => Err(err),
+            (Err(_), concentrated_amount_in) => concentrated_amount_in,
+        }
+    }
+
+    fn concentrated_pool_exists(asset_a: AssetIdOf<T, I>, asset_b: AssetIdOf<T, I>) -> bool {
+        Self::sort_assets((asset_a, asset_b)).map_or(false, |(_, base_asset, quote_asset)| {
+            ConcentratedPools::<T, I>::contains_key(base_asset, quote_asset)
+        })
+    }
+
     // extract the reserves from a pool after sorting assets
     fn get_reserves(
         asset_in: AssetIdOf<T, I>,
@@ -615,6 +1336,24 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         }
     }
 
+    // extract the reserves and the current LP fee from a pool after sorting assets
+    fn get_reserves_and_fee(
+        asset_in: AssetIdOf<T, I>,
+        asset_out: AssetIdOf<T, I>,
+    ) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>, Ratio), DispatchError> {
+        let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
+
+        let pool = Pools::<T, I>::try_get(base_asset, quote_asset)
+            .map_err(|_err| Error::<T, I>::PoolDoesNotExist)?;
+        let lp_fee = Self::current_lp_fee((base_asset, quote_asset), &pool);
+
+        if is_inverted {
+            Ok((pool.quote_amount, pool.base_amount, lp_fee))
+        } else {
+            Ok((pool.base_amount, pool.quote_amount, lp_fee))
+        }
+    }
+
     // given an input amount of an asset and pair reserves, returns the maximum output amount of the other asset
     //
     // amountIn = amountIn * (1 - fee_percent)
@@ -627,8 +1366,9 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         amount_in: BalanceOf<T, I>,
         reserve_in: BalanceOf<T, I>,
         reserve_out: BalanceOf<T, I>,
+        lp_fee: Ratio,
     ) -> Result<BalanceOf<T, I>, DispatchError> {
-        let fees = T::LpFee::get().mul_ceil(amount_in);
+        let fees = lp_fee.mul_ceil(amount_in);
 
         let amount_in = amount_in
             .checked_sub(fees)
@@ -682,6 +1422,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         amount_out: BalanceOf<T, I>,
         reserve_in: BalanceOf<T, I>,
         reserve_out: BalanceOf<T, I>,
+        lp_fee: Ratio,
     ) -> Result<BalanceOf<T, I>, DispatchError> {
         ensure!(
             amount_out < reserve_out,
@@ -709,7 +1450,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             .ok_or(ArithmeticError::Overflow)?;
 
         let fee_percent = Ratio::from_percent(100)
-            .checked_sub(&T::LpFee::get())
+            .checked_sub(&lp_fee)
             .ok_or(ArithmeticError::Underflow)?;
 
         log::trace!(
@@ -779,13 +1520,39 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         Ok(())
     }
 
+    // start a new window of the dynamic fee once the current one is over, must be
+    // called after the oracle of the pool is updated
+    fn do_update_dynamic_fee(
+        (base_asset, quote_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        pool: &Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
+    ) {
+        DynamicFees::<T, I>::mutate(base_asset, quote_asset, |dynamic_fee| {
+            if let Some(dynamic_fee) = dynamic_fee {
+                let block_number = frame_system::Pallet::<T>::block_number();
+                let elapsed = block_number.saturating_sub(dynamic_fee.window_start);
+                if elapsed.is_zero() || elapsed < dynamic_fee.window {
+                    return;
+                }
+
+                let elapsed: BalanceOf<T, I> = elapsed.saturated_into();
+                dynamic_fee.average_price = FixedU128::from_inner(
+                    pool.price_0_cumulative_last
+                        .saturating_sub(dynamic_fee.price_0_cumulative_start)
+                        / elapsed,
+                );
+                dynamic_fee.window_start = block_number;
+                dynamic_fee.price_0_cumulative_start = pool.price_0_cumulative_last;
+            }
+        })
+    }
+
     #[require_transactional]
     fn do_add_liquidity(
         who: &T::AccountId,
         pool: &mut Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
         (ideal_base_amount, ideal_quote_amount): (BalanceOf<T, I>, BalanceOf<T, I>),
         (base_asset, quote_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
-    ) -> Result<(), DispatchError> {
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
         let total_supply = T::Assets::total_issuance(pool.lp_token_id);
 
         // lock a small amount of liquidity if the pool is first initialized
@@ -860,7 +1627,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             quote_asset == T::GetNativeCurrencyId::get(), // should keep alive if is native
         )?;
 
-        if Self::protocol_fee_on() {
+        if Self::protocol_fee_on(pool) {
             // we cannot hold k_last for really large values
             // we can hold two u128s instead
             pool.base_amount_last = pool.base_amount;
@@ -880,7 +1647,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             &ideal_quote_amount
         );
 
-        Ok(())
+        Ok(liquidity)
     }
 
     fn calculate_reserves_to_remove(
@@ -941,7 +1708,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             quote_asset == T::GetNativeCurrencyId::get(), // should keep alive if is native
         )?;
 
-        if Self::protocol_fee_on() {
+        if Self::protocol_fee_on(pool) {
             // we cannot hold k_last for really large values
             // we can hold two u128s instead
             pool.base_amount_last = pool.base_amount;
@@ -972,7 +1739,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             .checked_mul(&pool.quote_amount_last.get_big_uint())
             .ok_or(ArithmeticError::Overflow)?;
 
-        if !Self::protocol_fee_on() {
+        if !Self::protocol_fee_on(pool) {
             // if fees are off and k_last is a value we need to reset it
             if !k_last.is_zero() {
                 pool.base_amount_last = Zero::zero();
@@ -1009,7 +1776,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             .and_then(|r| r.checked_mul(&total_supply))
             .ok_or(Error::<T, I>::ConversionToU128Failed)?;
 
-        let scalar = Self::get_protocol_fee_reciprocal_proportion()?
+        let scalar = Self::get_protocol_fee_reciprocal_proportion(pool)?
             .checked_sub(One::one())
             .ok_or(ArithmeticError::Underflow)?
             .get_big_uint();
@@ -1048,88 +1815,220 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         who: &T::AccountId,
         (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
         amount_in: BalanceOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        if Self::concentrated_pool_exists(asset_in, asset_out) {
+            let (_, is_concentrated) = Self::get_best_amount_out(amount_in, asset_in, asset_out)?;
+            if is_concentrated {
+                return Self::do_concentrated_swap(who, (asset_in, asset_out), amount_in);
+            }
+        }
+
+        let (_, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
+
+        Pools::<T, I>::try_mutate(
+            base_asset,
+            quote_asset,
+            |pool| -> Result<BalanceOf<T, I>, DispatchError> {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+
+                let amount_out = Self::do_pool_swap(who, pool, (asset_in, asset_out), amount_in)?;
+
+                Self::deposit_event(Event::<T, I>::Traded(
+                    who.clone(),
+                    asset_in,
+                    asset_out,
+                    amount_in,
+                    amount_out,
+                    pool.lp_token_id,
+                    pool.quote_amount,
+                    pool.base_amount,
+                ));
+
+                Ok(amount_out)
+            },
+        )
+    }
+
+    /// Trades against the reserves of a constant product pool without depositing an
+    /// event, the callers report the trade
+    fn do_pool_swap(
+        who: &T::AccountId,
+        pool: &mut Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
+        (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        amount_in: BalanceOf<T, I>,
     ) -> Result<BalanceOf<T, I>, DispatchError> {
         let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
 
+        let (supply_in, supply_out) = if is_inverted {
+            (pool.quote_amount, pool.base_amount)
+        } else {
+            (pool.base_amount, pool.quote_amount)
+        };
+
+        // the fee is taken at the price before the trade, like the quotes
+        let lp_fee = Self::current_lp_fee((base_asset, quote_asset), pool);
+        ensure!(
+            lp_fee.is_zero() || amount_in >= lp_fee.saturating_reciprocal_mul_ceil(One::one()),
+            Error::<T, I>::InsufficientAmountIn
+        );
+        ensure!(!supply_out.is_zero(), Error::<T, I>::InsufficientAmountOut);
+
+        let amount_out = Self::get_amount_out(amount_in, supply_in, supply_out, lp_fee)?;
+
+        let (new_supply_in, new_supply_out) = (
+            supply_in
+                .checked_add(amount_in)
+                .ok_or(ArithmeticError::Overflow)?,
+            supply_out
+                .checked_sub(amount_out)
+                .ok_or(ArithmeticError::Underflow)?,
+        );
+
+        // accumulate the prices of the elapsed blocks before the trade moves them
+        Self::do_update_oracle(pool)?;
+        Self::do_update_dynamic_fee((base_asset, quote_asset), pool);
+
+        if is_inverted {
+            pool.quote_amount = new_supply_in;
+            pool.base_amount = new_supply_out;
+        } else {
+            pool.base_amount = new_supply_in;
+            pool.quote_amount = new_supply_out;
+        }
+
+        T::Assets::transfer(
+            asset_in,
+            who,
+            &Self::account_id(),
+            amount_in,
+            asset_in == T::GetNativeCurrencyId::get(), // should keep alive if is native
+        )?;
+        T::Assets::transfer(
+            asset_out,
+            &Self::account_id(),
+            who,
+            amount_out,
+            asset_out == T::GetNativeCurrencyId::get(), // should keep alive if is native
+        )?;
+
+        log::trace!(
+            target: "amm::do_trade",
+            "who: {:?}, asset_in: {:?}, asset_out: {:?}, amount_in: {:?}, amount_out: {:?}",
+            &who,
+            &asset_in,
+            &asset_out,
+            &amount_in,
+            &amount_out,
+        );
+
+        Ok(amount_out)
+    }
+
+    /// Zaps `amount_in` of `pair.0` into the pool of `pair`, the part of the amount
+    /// that balances the pool is swapped for `pair.1` first. Returns the liquidity
+    /// minted to `who`
+    #[require_transactional]
+    fn do_zap_in(
+        who: &T::AccountId,
+        pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        amount_in: BalanceOf<T, I>,
+        min_liquidity: BalanceOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let (asset_in, paired_asset) = pair;
+        let (is_inverted, base_asset, quote_asset) = Self::sort_assets(pair)?;
+
         Pools::<T, I>::try_mutate(
             base_asset,
             quote_asset,
             |pool| -> Result<BalanceOf<T, I>, DispatchError> {
                 let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
 
-                let (supply_in, supply_out) = if is_inverted {
-                    (pool.quote_amount, pool.base_amount)
+                let reserve_in = if is_inverted {
+                    pool.quote_amount
                 } else {
-                    (pool.base_amount, pool.quote_amount)
+                    pool.base_amount
                 };
+                let lp_fee = Self::current_lp_fee((base_asset, quote_asset), pool);
+                let swap_amount = Self::get_zap_swap_amount(amount_in, reserve_in, lp_fee)?;
 
-                ensure!(
-                    amount_in >= T::LpFee::get().saturating_reciprocal_mul_ceil(One::one()),
-                    Error::<T, I>::InsufficientAmountIn
-                );
-                ensure!(!supply_out.is_zero(), Error::<T, I>::InsufficientAmountOut);
-
-                let amount_out = Self::get_amount_out(amount_in, supply_in, supply_out)?;
+                let paired_amount =
+                    Self::do_pool_swap(who, pool, (asset_in, paired_asset), swap_amount)?;
+                let remaining_amount = amount_in
+                    .checked_sub(swap_amount)
+                    .ok_or(ArithmeticError::Underflow)?;
 
-                let (new_supply_in, new_supply_out) = (
-                    supply_in
-                        .checked_add(amount_in)
-                        .ok_or(ArithmeticError::Overflow)?,
-                    supply_out
-                        .checked_sub(amount_out)
-                        .ok_or(ArithmeticError::Underflow)?,
-                );
-
-                if is_inverted {
-                    pool.quote_amount = new_supply_in;
-                    pool.base_amount = new_supply_out;
+                let (base_amount, quote_amount) = if is_inverted {
+                    (paired_amount, remaining_amount)
                 } else {
-                    pool.base_amount = new_supply_in;
-                    pool.quote_amount = new_supply_out;
-                }
+                    (remaining_amount, paired_amount)
+                };
+                let (ideal_base_amount, ideal_quote_amount) =
+                    Self::get_ideal_amounts(pool, (base_amount, quote_amount))?;
 
-                Self::do_update_oracle(pool)?;
+                Self::do_mint_protocol_fee(pool)?;
 
-                T::Assets::transfer(
-                    asset_in,
-                    who,
-                    &Self::account_id(),
-                    amount_in,
-                    asset_in == T::GetNativeCurrencyId::get(), // should keep alive if is native
-                )?;
-                T::Assets::transfer(
-                    asset_out,
-                    &Self::account_id(),
+                let liquidity = Self::do_add_liquidity(
                     who,
-                    amount_out,
-                    asset_out == T::GetNativeCurrencyId::get(), // should keep alive if is native
+                    pool,
+                    (ideal_base_amount, ideal_quote_amount),
+                    (base_asset, quote_asset),
                 )?;
+                ensure!(liquidity >= min_liquidity, Error::<T, I>::SlippageExceeded);
 
                 log::trace!(
-                    target: "amm::do_trade",
-                    "who: {:?}, asset_in: {:?}, asset_out: {:?}, amount_in: {:?}, amount_out: {:?}",
+                    target: "amm::zap_in",
+                    "who: {:?}, asset_in: {:?}, paired_asset: {:?}, amount_in: {:?}, swap_amount: {:?}, liquidity: {:?}",
                     &who,
                     &asset_in,
-                    &asset_out,
+                    &paired_asset,
                     &amount_in,
-                    &amount_out,
+                    &swap_amount,
+                    &liquidity
                 );
 
-                Self::deposit_event(Event::<T, I>::Traded(
+                Self::deposit_event(Event::<T, I>::ZappedIn(
                     who.clone(),
                     asset_in,
-                    asset_out,
+                    paired_asset,
                     amount_in,
-                    amount_out,
+                    liquidity,
                     pool.lp_token_id,
-                    pool.quote_amount,
-                    pool.base_amount,
                 ));
 
-                Ok(amount_out)
+                Ok(liquidity)
             },
         )
     }
+
+    /// The amount of a single asset to swap before adding the rest as liquidity,
+    /// so that both halves match the pool ratio after the swap.
+    ///
+    /// With `r` the reserve of the asset in, `a` the amount in and `g = 1 - fee`,
+    /// the swap amount is `(sqrt(r^2 (1 + g)^2 + 4 g r a) - r (1 + g)) / 2g`
+    pub fn get_zap_swap_amount(
+        amount_in: BalanceOf<T, I>,
+        reserve_in: BalanceOf<T, I>,
+        lp_fee: Ratio,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        ensure!(!reserve_in.is_zero(), Error::<T, I>::InsufficientLiquidity);
+
+        // scaled by the fee accuracy to stay in integers
+        let accuracy = (Ratio::ACCURACY as u128).get_big_uint();
+        let remaining = (lp_fee.left_from_one().deconstruct() as u128).get_big_uint();
+        let reserve_in = reserve_in.get_big_uint();
+
+        let b = &reserve_in * (&accuracy + &remaining);
+        let discriminant =
+            &b * &b + &remaining * &accuracy * &reserve_in * amount_in.get_big_uint() * 4u32;
+
+        let swap_amount = (discriminant.sqrt() - b)
+            .checked_div(&(remaining * 2u32))
+            .ok_or(ArithmeticError::DivisionByZero)?
+            .to_u128()
+            .ok_or(ArithmeticError::Overflow)?;
+
+        Ok(swap_amount)
+    }
 }
 
 impl<T: Config<I>, I: 'static>
@@ -1173,7 +2072,13 @@ impl<T: Config<I>, I: 'static>
 
     /// Returns a vector of all of the pools in storage
     fn get_pools() -> Result<Vec<(AssetIdOf<T, I>, AssetIdOf<T, I>)>, DispatchError> {
-        Ok(Pools::<T, I>::iter_keys().collect())
+        let mut pools: Vec<_> = Pools::<T, I>::iter_keys().collect();
+        for pair in ConcentratedPools::<T, I>::iter_keys() {
+            if !Pools::<T, I>::contains_key(pair.0, pair.1) {
+                pools.push(pair);
+            }
+        }
+        Ok(pools)
     }
 
     //just iterate now and require improve later when Pools increased
@@ -1202,4 +2107,15 @@ impl<T: Config<I>, I: 'static>
         }
         None
     }
+
+    /// Zaps `amount_in` of `pair.0` into the pool of `pair` for "who" and
+    /// returns the liquidity minted
+    fn zap_in(
+        who: &AccountIdOf<T>,
+        pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        amount_in: BalanceOf<T, I>,
+        min_liquidity: BalanceOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        Self::do_zap_in(who, pair, amount_in, min_liquidity)
+    }
 }
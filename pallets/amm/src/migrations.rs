@@ -0,0 +1,145 @@
+// Copyright 2021-2022 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+pub mod v2 {
+    use super::*;
+    use crate::{pallet::StorageVersion, Config};
+    use codec::DecodeAll;
+    use frame_support::{
+        log,
+        storage::unhashed,
+        traits::{Get, OnRuntimeUpgrade},
+        weights::Weight,
+    };
+
+    #[derive(Clone, Copy, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+    pub struct V1Pool<CurrencyId, Balance, BlockNumber> {
+        pub base_amount: Balance,
+        pub quote_amount: Balance,
+        pub base_amount_last: Balance,
+        pub quote_amount_last: Balance,
+        pub lp_token_id: CurrencyId,
+        pub block_timestamp_last: BlockNumber,
+        pub price_0_cumulative_last: Balance,
+        pub price_1_cumulative_last: Balance,
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T, I>::get() == crate::Versions::V1,
+            "must upgrade linearly"
+        );
+        let pools_count = Pools::<T, I>::iter_keys().count();
+        log::info!("total {:#?} pools need to migrate", pools_count);
+
+        Ok(())
+    }
+
+    /// Migration to pools carrying their own LP and protocol fee, existing pools get
+    /// the default LP fee and keep following the protocol fee of the AMM.
+    ///
+    /// Only pools whose encoding is exactly a `V1Pool` are translated, the pools of a
+    /// chain started with the V2 code already carry their fees.
+    pub fn migrate<T: Config<I>, I: 'static>() -> Weight {
+        if StorageVersion::<T, I>::get() == crate::Versions::V1 {
+            log::info!("migrating amm to Versions::V2",);
+
+            let lp_fee = T::LpFee::get();
+            let mut count = 0u64;
+            let mut migrated = 0u64;
+            for (base_asset, quote_asset) in Pools::<T, I>::iter_keys().collect::<Vec<_>>() {
+                count += 1;
+                let raw =
+                    unhashed::get_raw(&Pools::<T, I>::hashed_key_for(base_asset, quote_asset))
+                        .unwrap_or_default();
+                let pool =
+                    match V1Pool::<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>::decode_all(
+                        &mut &raw[..],
+                    ) {
+                        Ok(pool) => pool,
+                        Err(_) => continue,
+                    };
+                migrated += 1;
+                Pools::<T, I>::insert(
+                    base_asset,
+                    quote_asset,
+                    Pool {
+                        base_amount: pool.base_amount,
+                        quote_amount: pool.quote_amount,
+                        base_amount_last: pool.base_amount_last,
+                        quote_amount_last: pool.quote_amount_last,
+                        lp_token_id: pool.lp_token_id,
+                        block_timestamp_last: pool.block_timestamp_last,
+                        price_0_cumulative_last: pool.price_0_cumulative_last,
+                        price_1_cumulative_last: pool.price_1_cumulative_last,
+                        lp_fee,
+                        protocol_fee: None,
+                    },
+                );
+            }
+
+            StorageVersion::<T, I>::put(crate::Versions::V2);
+            log::info!(
+                "👜 completed amm migration to Versions::V2, {:?} of {:?} pools migrated",
+                migrated,
+                count
+            );
+
+            T::DbWeight::get().reads_writes(count + 1, migrated + 1)
+        } else {
+            T::DbWeight::get().reads(1)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn post_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T, I>::get() == crate::Versions::V2,
+            "must upgrade to V2"
+        );
+        Pools::<T, I>::iter().for_each(|(base_asset, quote_asset, pool)| {
+            log::info!(
+                "pool {:#?}, lp_fee {:?}, protocol_fee {:?}",
+                (base_asset, quote_asset),
+                pool.lp_fee,
+                pool.protocol_fee
+            );
+        });
+        log::info!("👜 amm v2 migration passes POST migrate checks ✅",);
+
+        Ok(())
+    }
+
+    /// Runs the v2 migration on runtime upgrade
+    pub struct MigrateToV2<T, I = ()>(sp_std::marker::PhantomData<(T, I)>);
+    impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV2<T, I> {
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            pre_migrate::<T, I>()?;
+            Ok(Vec::new())
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            migrate::<T, I>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+            post_migrate::<T, I>()
+        }
+    }
+}
@@ -164,6 +164,8 @@ parameter_types! {
     pub const AMMPalletId: PalletId = PalletId(*b"par/ammp");
     pub DefaultLpFee: Ratio = Ratio::from_rational(25u32, 10000u32);        // 0.25%
     pub const MinimumLiquidity: u128 = 1_000u128;
+    pub const MaxTicksPerPool: u32 = 1_000;
+    pub const MinPositionLiquidity: u128 = 1_000_000u128;
     pub const LockAccountId: AccountId = AccountId(1_u64);
     pub const MaxLengthRoute: u8 = 10;
 }
@@ -187,6 +189,9 @@ impl pallet_amm::Config for Test {
     type MinimumLiquidity = MinimumLiquidity;
     type MaxLengthRoute = MaxLengthRoute;
     type GetNativeCurrencyId = NativeCurrencyId;
+    type MaxTicksPerPool = MaxTicksPerPool;
+    type MinPositionLiquidity = MinPositionLiquidity;
+    type PoolsHook = ();
 }
 
 parameter_types! {
@@ -4,6 +4,7 @@ use frame_support::{assert_noop, assert_ok};
 use frame_system::RawOrigin;
 use pallet_traits::AMM as _;
 use primitives::tokens;
+use sp_runtime::traits::BadOrigin;
 
 const MINIMUM_LIQUIDITY: u128 = 1_000;
 
@@ -16,6 +17,8 @@ fn create_pool_should_work() {
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         assert_eq!(AMM::pools(SDOT, DOT).unwrap().base_amount, 2_000);
@@ -37,6 +40,8 @@ fn double_liquidity_correct_liq_ratio_should_work() {
             (15_000_000_000_000, 50_000_000_000_000_000), // Liquidity amounts to be added in pool
             FRANK,      // LPToken receiver
             SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+            DefaultLpFee::get(), // LP fee
+            None,       // Protocol fee, None follows the AMM protocol fee
         ));
 
         // total liquidity after pool created
@@ -69,6 +74,8 @@ fn add_liquidity_should_work() {
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
         assert_ok!(AMM::add_liquidity(
             RawOrigin::Signed(ALICE).into(), // Origin
@@ -89,7 +96,9 @@ fn add_more_liquidity_should_work() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         assert_ok!(AMM::add_liquidity(
@@ -112,7 +121,9 @@ fn add_more_liquidity_should_not_work_if_minimum_base_amount_is_higher() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         assert_noop!(
@@ -135,7 +146,9 @@ fn add_more_liquidity_with_low_balance_should_not_work() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         assert_ok!(AMM::add_liquidity(
@@ -165,7 +178,9 @@ fn add_liquidity_by_another_user_should_work() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         assert_ok!(AMM::add_liquidity(
@@ -194,7 +209,9 @@ fn cannot_create_pool_twice() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         assert_noop!(
@@ -203,7 +220,9 @@ fn cannot_create_pool_twice() {
                 (DOT, SDOT),                     // Currency pool, in which liquidity will be added
                 (1_000, 2_000),                  // Liquidity amounts to be added in pool
                 ALICE,                           // LPToken receiver
-                SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+                SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+                DefaultLpFee::get(),             // LP fee
+                None,                            // Protocol fee, None follows the AMM protocol fee
             ),
             Error::<Test>::PoolAlreadyExists, // Pool already not exist
         );
@@ -223,6 +242,8 @@ fn remove_liquidity_whole_share_should_work() {
             (1_000, 9_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         );
 
         assert_ok!(AMM::remove_liquidity(
@@ -246,6 +267,8 @@ fn remove_liquidity_only_portion_should_work() {
             (1_000, 9_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         );
 
         assert_eq!(AMM::pools(SDOT, DOT).unwrap().base_amount, 9_000);
@@ -270,7 +293,9 @@ fn remove_liquidity_user_more_liquidity_should_work() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 2_500),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
         assert_ok!(AMM::add_liquidity(
             RawOrigin::Signed(ALICE).into(), // Origin
@@ -310,6 +335,8 @@ fn remove_liquidity_with_more_liquidity_should_not_work() {
             (1_000, 9_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         );
 
         assert_noop!(
@@ -331,6 +358,8 @@ fn swap_should_work_base_to_quote() {
             (100_000_000, 100_000_000),      // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         // SDOT is base_asset 1001
@@ -376,6 +405,8 @@ fn swap_should_work_different_ratio_base_to_quote() {
             (100_000_000, 50_000_000),       // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         // SDOT is base_asset 1001
@@ -421,6 +452,8 @@ fn swap_should_work_quote_to_base() {
             (50_000_000, 100_000_000),       // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         // SDOT is base_asset 1001
@@ -466,6 +499,8 @@ fn trade_should_work_base_to_quote_flipped_currencies_on_pool_creation() {
             (100_000_000, 100_000_000),      // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         // SDOT is base_asset 1001
@@ -503,6 +538,8 @@ fn trade_should_work_quote_to_base() {
             (100_000_000, 100_000_000),      // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         // SDOT is base_asset 1001
@@ -540,6 +577,8 @@ fn trade_should_not_work_if_insufficient_amount_in() {
             (100_000, 100_000),              // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         // create pool and add liquidity
@@ -573,7 +612,9 @@ fn trade_should_work_flipped_currencies() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (100_000, 50_000),               // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         // check that pool was funded correctly
@@ -607,7 +648,9 @@ fn trade_should_not_work_if_amount_in_is_zero() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 1_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         // fail if amount_in is zero
@@ -638,7 +681,8 @@ fn amount_out_should_work() {
         let supply_in = 100_000_000;
         let supply_out = 100_000_000;
 
-        let amount_out = AMM::get_amount_out(amount_in, supply_in, supply_out).unwrap();
+        let amount_out =
+            AMM::get_amount_out(amount_in, supply_in, supply_out, DefaultLpFee::get()).unwrap();
 
         // actual value == 996.9900600091017
         // TODO: assumes we round down to int
@@ -655,6 +699,8 @@ fn amounts_out_should_work() {
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         assert_ok!(AMM::create_pool(
@@ -663,6 +709,8 @@ fn amounts_out_should_work() {
             (1_000, 1_000),                  // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN_2,               // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         let path = vec![SDOT, DOT, KSM];
@@ -684,6 +732,8 @@ fn long_route_amounts_in_should_work() {
             (10_000, 20_000),                // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         assert_ok!(AMM::create_pool(
@@ -692,6 +742,8 @@ fn long_route_amounts_in_should_work() {
             (10_000, 10_000),                // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN_2,               // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         let path = vec![SDOT, DOT, KSM];
@@ -713,6 +765,8 @@ fn short_route_amounts_in_should_work() {
             (10_000_000, 10_000_000),        // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         let path = vec![DOT, SDOT];
@@ -732,7 +786,8 @@ fn amount_in_should_work() {
         let supply_in = 100_000_000;
         let supply_out = 100_000_000;
 
-        let amount_in = AMM::get_amount_in(amount_out, supply_in, supply_out).unwrap();
+        let amount_in =
+            AMM::get_amount_in(amount_out, supply_in, supply_out, DefaultLpFee::get()).unwrap();
         // p = 1 - fee_percent
         // x * y = ( x + p * dx) ( y - dy)
         //
@@ -748,7 +803,8 @@ fn amount_in_uneven_should_work() {
         let supply_in = 100_000_000;
         let supply_out = 1_344_312_043;
 
-        let amount_in = AMM::get_amount_in(amount_out, supply_in, supply_out).unwrap();
+        let amount_in =
+            AMM::get_amount_in(amount_out, supply_in, supply_out, DefaultLpFee::get()).unwrap();
 
         assert_eq!(amount_in, 76);
     })
@@ -763,7 +819,7 @@ fn supply_out_should_larger_than_amount_out() {
         let supply_out = 100_00;
 
         assert_noop!(
-            AMM::get_amount_in(amount_out, supply_in, supply_out),
+            AMM::get_amount_in(amount_out, supply_in, supply_out, DefaultLpFee::get()),
             Error::<Test>::InsufficientSupplyOut
         );
     })
@@ -776,12 +832,14 @@ fn amount_out_and_in_should_work() {
         let supply_in = 100_000_000;
         let supply_out = 100_000_000;
 
-        let amount_in = AMM::get_amount_in(amount_out, supply_in, supply_out).unwrap();
+        let amount_in =
+            AMM::get_amount_in(amount_out, supply_in, supply_out, DefaultLpFee::get()).unwrap();
 
         // actual: 1002.5162908248136
         assert_eq!(amount_in, 1004);
 
-        let amount_out = AMM::get_amount_out(amount_in, supply_in, supply_out).unwrap();
+        let amount_out =
+            AMM::get_amount_out(amount_in, supply_in, supply_out, DefaultLpFee::get()).unwrap();
 
         // actual: 1000.0834982275963
         assert_eq!(amount_out, 1000);
@@ -799,6 +857,8 @@ fn update_oracle_should_work() {
             (100_000, 100_000),              // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         assert_eq!(AMM::pools(SDOT, DOT).unwrap().block_timestamp_last, 0);
@@ -812,11 +872,11 @@ fn update_oracle_should_work() {
         assert_eq!(AMM::pools(SDOT, DOT).unwrap().block_timestamp_last, 2);
         assert_eq!(
             AMM::pools(SDOT, DOT).unwrap().price_0_cumulative_last,
-            2_040136143738700978
+            2_000000000000000000
         );
         assert_eq!(
             AMM::pools(SDOT, DOT).unwrap().price_1_cumulative_last,
-            1_960653465346534653
+            2_000000000000000000
         );
 
         run_to_block(4);
@@ -826,11 +886,11 @@ fn update_oracle_should_work() {
         assert_eq!(AMM::pools(SDOT, DOT).unwrap().block_timestamp_last, 4);
         assert_eq!(
             AMM::pools(SDOT, DOT).unwrap().price_0_cumulative_last,
-            4_120792162342213614
+            4_040136143738700978
         );
         assert_eq!(
             AMM::pools(SDOT, DOT).unwrap().price_1_cumulative_last,
-            3_883124053581828770
+            3_960653465346534653
         );
     })
 }
@@ -845,7 +905,9 @@ fn oracle_big_block_no_overflow() {
             (DOT, KSM), // Currency pool, in which liquidity will be added
             (9_999_650_729_873_433, 30_001_051_000_000_000_000), // Liquidity amounts to be added in pool
             FRANK,                                               // LPToken receiver
-            SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,     // Liquidity pool share representative token
+            DefaultLpFee::get(), // LP fee
+            None,                // Protocol fee, None follows the AMM protocol fee
         ));
 
         assert_eq!(AMM::pools(DOT, KSM).unwrap().block_timestamp_last, 0);
@@ -868,11 +930,11 @@ fn oracle_big_block_no_overflow() {
 
         assert_eq!(
             AMM::pools(DOT, KSM).unwrap().price_0_cumulative_last,
-            105007346092879071079611683
+            105007346092900041778164824
         );
         assert_eq!(
             AMM::pools(DOT, KSM).unwrap().price_1_cumulative_last,
-            11_665850491226458031
+            11_665850491224128279
         );
 
         // increment a block
@@ -965,7 +1027,9 @@ fn create_pool_large_amount_should_work() {
             (DOT, SDOT), // Currency pool, in which liquidity will be added
             (1_000_000_000_000_000_000, 2_000_000_000_000_000_000_000), // Liquidity amounts to be added in pool
             ALICE,                                                      // LPToken receiver
-            SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,     // Liquidity pool share representative token
+            DefaultLpFee::get(), // LP fee
+            None,                // Protocol fee, None follows the AMM protocol fee
         ));
 
         assert_eq!(
@@ -1015,7 +1079,9 @@ fn create_pool_large_amount_from_an_account_without_sufficient_amount_of_tokens_
                 (DOT, SDOT), // Currency pool, in which liquidity will be added
                 (1_000_000_000_000_000_000, 2_000_000_000_000_000_000_000), // Liquidity amounts to be added in pool
                 BOB,                                                        // LPToken receiver
-                SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+                SAMPLE_LP_TOKEN,     // Liquidity pool share representative token
+                DefaultLpFee::get(), // LP fee
+                None,                // Protocol fee, None follows the AMM protocol fee
             ),
             pallet_assets::Error::<Test>::BalanceLow
         );
@@ -1055,7 +1121,9 @@ fn do_add_liquidity_exact_amounts_should_work() {
             (DOT, SDOT), // Currency pool, in which liquidity will be added
             (1_000_000_000_000_000_000, 2_000_000_000_000_000_000_000), // Liquidity amounts to be added in pool
             ALICE,                                                      // LPToken receiver
-            SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,     // Liquidity pool share representative token
+            DefaultLpFee::get(), // LP fee
+            None,                // Protocol fee, None follows the AMM protocol fee
         ));
         assert_ok!(AMM::add_liquidity(
             RawOrigin::Signed(ALICE).into(),                            // Origin
@@ -1099,6 +1167,8 @@ fn do_add_liquidity_large_amounts_should_work() {
             ), // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
     })
 }
@@ -1144,7 +1214,9 @@ fn handling_fees_should_work() {
             (DOT, SDOT),                        // Currency pool, in which liquidity will be added
             (100_000_000_000, 100_000_000_000), // Liquidity amounts to be added in pool
             BOB,                                // LPToken receiver
-            SAMPLE_LP_TOKEN                     // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                    // Liquidity pool share representative token
+            DefaultLpFee::get(),                // LP fee
+            None,                               // Protocol fee, None follows the AMM protocol fee
         ));
 
         assert_ok!(AMM::update_protocol_fee(
@@ -1226,7 +1298,9 @@ fn swap_full_balance_should_work() {
             (DOT, SDOT),                        // Currency pool, in which liquidity will be added
             (100_000_000_000, 100_000_000_000), // Liquidity amounts to be added in pool
             BOB,                                // LPToken receiver
-            SAMPLE_LP_TOKEN                     // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                    // Liquidity pool share representative token
+            DefaultLpFee::get(),                // LP fee
+            None,                               // Protocol fee, None follows the AMM protocol fee
         ));
 
         // user can swap all of their non native assets
@@ -1245,7 +1319,9 @@ fn can_only_all_if_non_native_should_work() {
                 (0, SDOT),                       // Currency pool, in which liquidity will be added
                 (100000000, 100000000),          // Liquidity amounts to be added in pool
                 BOB,                             // LPToken receiver
-                SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+                SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+                DefaultLpFee::get(),             // LP fee
+                None,                            // Protocol fee, None follows the AMM protocol fee
             ),
             pallet_balances::Error::<Test>::KeepAlive
         );
@@ -1260,7 +1336,9 @@ fn can_only_all_if_non_native_should_work() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (all_dot, all_sdot),             // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // LP fee
+            None,                            // Protocol fee, None follows the AMM protocol fee
         ));
 
         assert_eq!(Assets::balance(DOT, BOB), 0);
@@ -1300,6 +1378,8 @@ fn glmr_add_liquidity_should_work() {
             (5978650946941927074614, 100290500000000000),
             ALICE,
             SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None,
         )
         .unwrap();
 
@@ -1311,3 +1391,815 @@ fn glmr_add_liquidity_should_work() {
         ));
     })
 }
+
+const CONCENTRATED_FEE: Ratio = Ratio::from_parts(3_000);
+const TICK_SPACING: u32 = 60;
+
+fn create_concentrated_pool(pair: (CurrencyId, CurrencyId), initial_price: FixedU128) {
+    assert_ok!(AMM::set_fee_tier(
+        RawOrigin::Signed(ALICE).into(),
+        CONCENTRATED_FEE,
+        Some(TICK_SPACING)
+    ));
+    assert_ok!(AMM::create_concentrated_pool(
+        RawOrigin::Signed(ALICE).into(),
+        pair,
+        CONCENTRATED_FEE,
+        initial_price
+    ));
+}
+
+fn mint_position(
+    who: AccountId,
+    pair: (CurrencyId, CurrencyId),
+    ticks: (i32, i32),
+    desired_amounts: (Balance, Balance),
+) -> (PositionId, Balance, Balance) {
+    let position_id = AMM::next_position_id();
+    let balances = (Assets::balance(pair.0, who), Assets::balance(pair.1, who));
+    assert_ok!(AMM::mint_position(
+        RawOrigin::Signed(who).into(),
+        pair,
+        ticks,
+        desired_amounts,
+        (0, 0)
+    ));
+    (
+        position_id,
+        balances.0 - Assets::balance(pair.0, who),
+        balances.1 - Assets::balance(pair.1, who),
+    )
+}
+
+#[test]
+fn create_concentrated_pool_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            AMM::set_fee_tier(RawOrigin::Signed(BOB).into(), CONCENTRATED_FEE, Some(60)),
+            BadOrigin
+        );
+        assert_noop!(
+            AMM::set_fee_tier(RawOrigin::Signed(ALICE).into(), CONCENTRATED_FEE, Some(0)),
+            Error::<Test>::InvalidFeeTier
+        );
+        assert_noop!(
+            AMM::create_concentrated_pool(
+                RawOrigin::Signed(ALICE).into(),
+                (DOT, SDOT),
+                CONCENTRATED_FEE,
+                FixedU128::saturating_from_integer(4u128)
+            ),
+            Error::<Test>::FeeTierNotEnabled
+        );
+
+        // 4 DOT per SDOT is 0.25 SDOT per DOT
+        create_concentrated_pool((DOT, SDOT), FixedU128::saturating_from_integer(4u128));
+        let pool = AMM::concentrated_pools(SDOT, DOT).unwrap();
+        assert_eq!(pool.sqrt_price, FixedU128::from_rational(1, 2));
+        assert_eq!(pool.tick_spacing, TICK_SPACING);
+        assert_eq!(pool.liquidity, 0);
+        assert!(sqrt_price_at_tick(pool.tick).unwrap() <= pool.sqrt_price);
+        assert!(sqrt_price_at_tick(pool.tick + 1).unwrap() > pool.sqrt_price);
+
+        assert_noop!(
+            AMM::create_concentrated_pool(
+                RawOrigin::Signed(ALICE).into(),
+                (SDOT, DOT),
+                CONCENTRATED_FEE,
+                FixedU128::saturating_from_integer(4u128)
+            ),
+            Error::<Test>::PoolAlreadyExists
+        );
+        assert_eq!(AMM::get_pools().unwrap(), vec![(SDOT, DOT)]);
+    })
+}
+
+#[test]
+fn sqrt_price_at_tick_should_be_monotonic() {
+    assert_eq!(sqrt_price_at_tick(0), Some(FixedU128::one()));
+    assert_eq!(sqrt_price_at_tick(MAX_TICK + 1), None);
+    assert_eq!(sqrt_price_at_tick(MIN_TICK - 1), None);
+    for tick in [MIN_TICK, -100_000, -887, -1, 1, 60, 23_027, MAX_TICK] {
+        let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+        assert!(
+            sqrt_price < sqrt_price_at_tick(tick + 1).unwrap_or(FixedU128::from_inner(u128::MAX))
+        );
+        assert_eq!(tick_at_sqrt_price(sqrt_price), Some(tick));
+    }
+}
+
+#[test]
+fn mint_position_should_work() {
+    new_test_ext().execute_with(|| {
+        create_concentrated_pool((SDOT, DOT), FixedU128::one());
+
+        let (position_id, base_amount, quote_amount) =
+            mint_position(BOB, (SDOT, DOT), (-600, 600), (1_000_000, 1_000_000));
+        let position = AMM::positions(BOB, position_id).unwrap();
+        assert_eq!((position.tick_lower, position.tick_upper), (-600, 600));
+        assert_eq!((position.base_asset, position.quote_asset), (SDOT, DOT));
+        assert!(base_amount <= 1_000_000 && quote_amount <= 1_000_000);
+        // the range is symmetric around the current price
+        assert!(base_amount.abs_diff(quote_amount) <= 1);
+        assert_eq!(
+            AMM::concentrated_pools(SDOT, DOT).unwrap().liquidity,
+            position.liquidity
+        );
+        assert_eq!(Assets::balance(SDOT, AMM::account_id()), base_amount);
+        assert_eq!(Assets::balance(DOT, AMM::account_id()), quote_amount);
+
+        // amounts in the inverted order of the pair
+        let (_, base_amount, quote_amount) =
+            mint_position(BOB, (DOT, SDOT), (-600, 600), (2_000_000, 1_000_000));
+        assert!(base_amount <= 1_000_000 && quote_amount <= 2_000_000);
+        assert!(base_amount.abs_diff(quote_amount) <= 1);
+        assert_eq!(
+            AMM::concentrated_ticks((SDOT, DOT), -600)
+                .unwrap()
+                .liquidity_gross,
+            AMM::concentrated_pools(SDOT, DOT).unwrap().liquidity
+        );
+        assert_eq!(
+            InitializedTicks::<Test>::get(SDOT, DOT).into_inner(),
+            vec![-600, 600]
+        );
+    })
+}
+
+#[test]
+fn mint_position_out_of_range_should_take_single_asset() {
+    new_test_ext().execute_with(|| {
+        create_concentrated_pool((SDOT, DOT), FixedU128::one());
+
+        // above the current price the range is made of the base asset only
+        let (position_id, base_amount, quote_amount) =
+            mint_position(BOB, (SDOT, DOT), (60, 600), (1_000_000, 1_000_000));
+        assert!(base_amount > 0);
+        assert_eq!(quote_amount, 0);
+        assert!(AMM::positions(BOB, position_id).unwrap().liquidity > 0);
+
+        // below the current price the range is made of the quote asset only
+        let (_, base_amount, quote_amount) =
+            mint_position(BOB, (SDOT, DOT), (-600, -60), (1_000_000, 1_000_000));
+        assert_eq!(base_amount, 0);
+        assert!(quote_amount > 0);
+
+        // out of range liquidity doesn't trade
+        assert_eq!(AMM::concentrated_pools(SDOT, DOT).unwrap().liquidity, 0);
+    })
+}
+
+#[test]
+fn mint_position_with_invalid_ticks_should_not_work() {
+    new_test_ext().execute_with(|| {
+        create_concentrated_pool((SDOT, DOT), FixedU128::one());
+
+        for ticks in [(600, -600), (600, 600), (-600, 610), (MIN_TICK - 60, 600)] {
+            assert_noop!(
+                AMM::mint_position(
+                    RawOrigin::Signed(BOB).into(),
+                    (SDOT, DOT),
+                    ticks,
+                    (1_000_000, 1_000_000),
+                    (0, 0)
+                ),
+                Error::<Test>::InvalidTickRange
+            );
+        }
+        assert_noop!(
+            AMM::mint_position(
+                RawOrigin::Signed(BOB).into(),
+                (SDOT, KSM),
+                (-600, 600),
+                (1_000_000, 1_000_000),
+                (0, 0)
+            ),
+            Error::<Test>::PoolDoesNotExist
+        );
+        assert_noop!(
+            AMM::mint_position(
+                RawOrigin::Signed(BOB).into(),
+                (SDOT, DOT),
+                (-600, 600),
+                (1_000_000, 1_000_000),
+                (1_000_000, 1_000_000)
+            ),
+            Error::<Test>::NotAnIdealPrice
+        );
+    })
+}
+
+#[test]
+fn concentrated_swap_should_work() {
+    new_test_ext().execute_with(|| {
+        create_concentrated_pool((SDOT, DOT), FixedU128::one());
+        mint_position(BOB, (SDOT, DOT), (-600, 600), (1_000_000, 1_000_000));
+
+        let amounts_out = AMM::get_amounts_out(10_000, vec![DOT, SDOT]).unwrap();
+        // at most the trade minus the 0.3% fee at a price of 1
+        assert!(amounts_out[1] > 9_800 && amounts_out[1] < 9_970);
+
+        let balance = Assets::balance(SDOT, CHARLIE);
+        assert_ok!(AMM::swap(&CHARLIE, (DOT, SDOT), 10_000));
+        assert_eq!(Assets::balance(SDOT, CHARLIE) - balance, amounts_out[1]);
+        assert!(AMM::concentrated_pools(SDOT, DOT).unwrap().sqrt_price > FixedU128::one());
+
+        let amounts_in = AMM::get_amounts_in(5_000, vec![SDOT, DOT]).unwrap();
+        assert!(AMM::get_amounts_out(amounts_in[0], vec![SDOT, DOT]).unwrap()[1] >= 5_000);
+
+        assert_noop!(
+            AMM::swap(&CHARLIE, (DOT, SDOT), 10_000_000),
+            Error::<Test>::InsufficientLiquidity
+        );
+    })
+}
+
+#[test]
+fn concentrated_swap_should_cross_ticks() {
+    new_test_ext().execute_with(|| {
+        create_concentrated_pool((SDOT, DOT), FixedU128::one());
+        let (narrow, _, _) = mint_position(BOB, (SDOT, DOT), (-600, 600), (1_000_000, 1_000_000));
+        let (wide, _, _) = mint_position(BOB, (SDOT, DOT), (-1200, 1200), (1_000_000, 1_000_000));
+        let narrow_liquidity = AMM::positions(BOB, narrow).unwrap().liquidity;
+        let wide_liquidity = AMM::positions(BOB, wide).unwrap().liquidity;
+        assert_eq!(
+            AMM::concentrated_pools(SDOT, DOT).unwrap().liquidity,
+            narrow_liquidity + wide_liquidity
+        );
+
+        // push the price below the narrow range
+        assert_ok!(AMM::swap(&BOB, (SDOT, DOT), 1_800_000));
+        let pool = AMM::concentrated_pools(SDOT, DOT).unwrap();
+        assert!(pool.tick < -600 && pool.tick >= -1200);
+        assert_eq!(pool.liquidity, wide_liquidity);
+
+        // and back above it
+        assert_ok!(AMM::swap(&BOB, (DOT, SDOT), 1_800_000));
+        let pool = AMM::concentrated_pools(SDOT, DOT).unwrap();
+        assert!(pool.tick >= -600 && pool.tick < 600);
+        assert_eq!(pool.liquidity, narrow_liquidity + wide_liquidity);
+    })
+}
+
+#[test]
+fn collect_position_fees_should_work() {
+    new_test_ext().execute_with(|| {
+        create_concentrated_pool((SDOT, DOT), FixedU128::one());
+        let (position_id, _, _) =
+            mint_position(BOB, (SDOT, DOT), (-600, 600), (1_000_000, 1_000_000));
+        assert_ok!(AMM::swap(&CHARLIE, (DOT, SDOT), 10_000));
+
+        let (dot_balance, sdot_balance) = (Assets::balance(DOT, BOB), Assets::balance(SDOT, BOB));
+        assert_ok!(AMM::collect_position_fees(
+            RawOrigin::Signed(BOB).into(),
+            position_id
+        ));
+        // 0.3% of the trade, rounded down
+        let dot_fees = Assets::balance(DOT, BOB) - dot_balance;
+        assert!(dot_fees > 0 && dot_fees <= 30);
+        assert_eq!(Assets::balance(SDOT, BOB), sdot_balance);
+
+        // nothing left to collect
+        assert_ok!(AMM::collect_position_fees(
+            RawOrigin::Signed(BOB).into(),
+            position_id
+        ));
+        assert_eq!(Assets::balance(DOT, BOB) - dot_balance, dot_fees);
+
+        assert_noop!(
+            AMM::collect_position_fees(RawOrigin::Signed(CHARLIE).into(), position_id),
+            Error::<Test>::PositionDoesNotExist
+        );
+    })
+}
+
+#[test]
+fn position_fees_should_work_with_ticks_crossed_in_opposite_orders() {
+    new_test_ext().execute_with(|| {
+        create_concentrated_pool((SDOT, DOT), FixedU128::one());
+        mint_position(BOB, (SDOT, DOT), (-600, 600), (1_000_000, 1_000_000));
+        mint_position(BOB, (SDOT, DOT), (-1200, 1200), (1_000_000, 1_000_000));
+
+        // fees are earned above the tick -600, then the price crosses it downwards
+        assert_ok!(AMM::swap(&CHARLIE, (DOT, SDOT), 10_000));
+        assert_ok!(AMM::swap(&BOB, (SDOT, DOT), 1_800_000));
+        let pool = AMM::concentrated_pools(SDOT, DOT).unwrap();
+        assert!(pool.tick < -600 && pool.tick >= -1200);
+
+        // the fee growth inside the new range starts below zero
+        let (position_id, _, _) =
+            mint_position(CHARLIE, (SDOT, DOT), (-1260, -600), (1_000_000, 1_000_000));
+        let liquidity = AMM::positions(CHARLIE, position_id).unwrap().liquidity;
+
+        let fee_growth_before = pool.fee_growth_global_quote;
+        assert_ok!(AMM::swap(&EVE, (DOT, SDOT), 1_000));
+        let pool = AMM::concentrated_pools(SDOT, DOT).unwrap();
+        assert!(pool.tick < -600 && pool.tick >= -1260);
+        let fee_growth = pool.fee_growth_global_quote - fee_growth_before;
+
+        let (dot_balance, sdot_balance) = (
+            Assets::balance(DOT, CHARLIE),
+            Assets::balance(SDOT, CHARLIE),
+        );
+        assert_ok!(AMM::collect_position_fees(
+            RawOrigin::Signed(CHARLIE).into(),
+            position_id
+        ));
+        let dot_fees = Assets::balance(DOT, CHARLIE) - dot_balance;
+        assert!(dot_fees > 0);
+        assert_eq!(dot_fees, fee_growth.saturating_mul_int(liquidity));
+        assert_eq!(Assets::balance(SDOT, CHARLIE), sdot_balance);
+    })
+}
+
+#[test]
+fn decrease_position_liquidity_should_work() {
+    new_test_ext().execute_with(|| {
+        create_concentrated_pool((SDOT, DOT), FixedU128::one());
+        let (position_id, base_amount, quote_amount) =
+            mint_position(BOB, (SDOT, DOT), (-600, 600), (1_000_000, 1_000_000));
+        let liquidity = AMM::positions(BOB, position_id).unwrap().liquidity;
+
+        assert_noop!(
+            AMM::decrease_position_liquidity(
+                RawOrigin::Signed(BOB).into(),
+                position_id,
+                liquidity + 1,
+                (0, 0)
+            ),
+            Error::<Test>::InsufficientLiquidity
+        );
+
+        let (sdot_balance, dot_balance) = (Assets::balance(SDOT, BOB), Assets::balance(DOT, BOB));
+        assert_ok!(AMM::decrease_position_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            position_id,
+            liquidity / 2,
+            (0, 0)
+        ));
+        assert_eq!(
+            AMM::positions(BOB, position_id).unwrap().liquidity,
+            liquidity - liquidity / 2
+        );
+        assert_ok!(AMM::decrease_position_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            position_id,
+            liquidity - liquidity / 2,
+            (0, 0)
+        ));
+
+        // rounding is in favor of the pool
+        let base_removed = Assets::balance(SDOT, BOB) - sdot_balance;
+        let quote_removed = Assets::balance(DOT, BOB) - dot_balance;
+        assert!(base_removed <= base_amount && base_amount - base_removed <= 3);
+        assert!(quote_removed <= quote_amount && quote_amount - quote_removed <= 3);
+
+        assert!(AMM::positions(BOB, position_id).is_none());
+        assert!(AMM::concentrated_ticks((SDOT, DOT), -600).is_none());
+        assert!(InitializedTicks::<Test>::get(SDOT, DOT).is_empty());
+        assert_eq!(AMM::concentrated_pools(SDOT, DOT).unwrap().liquidity, 0);
+    })
+}
+
+#[test]
+fn dust_positions_should_not_work() {
+    new_test_ext().execute_with(|| {
+        create_concentrated_pool((SDOT, DOT), FixedU128::one());
+        assert_noop!(
+            AMM::mint_position(
+                RawOrigin::Signed(BOB).into(),
+                (SDOT, DOT),
+                (-600, 600),
+                (1_000, 1_000),
+                (0, 0)
+            ),
+            Error::<Test>::PositionLiquidityTooLow
+        );
+
+        // a position can't be decreased to dust either
+        let (position_id, _, _) =
+            mint_position(BOB, (SDOT, DOT), (-600, 600), (1_000_000, 1_000_000));
+        let liquidity = AMM::positions(BOB, position_id).unwrap().liquidity;
+        assert_noop!(
+            AMM::decrease_position_liquidity(
+                RawOrigin::Signed(BOB).into(),
+                position_id,
+                liquidity - 1,
+                (0, 0)
+            ),
+            Error::<Test>::PositionLiquidityTooLow
+        );
+        assert_ok!(AMM::decrease_position_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            position_id,
+            liquidity - MinPositionLiquidity::get(),
+            (0, 0)
+        ));
+    })
+}
+
+#[test]
+fn swap_should_use_the_best_pool() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AMM::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (SDOT, DOT),
+            (100_000, 100_000),
+            ALICE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None,
+        ));
+        create_concentrated_pool((SDOT, DOT), FixedU128::one());
+        mint_position(BOB, (SDOT, DOT), (-600, 600), (1_000_000, 1_000_000));
+
+        // the concentrated liquidity is deeper around the current price
+        let amount_out = AMM::get_amounts_out(10_000, vec![DOT, SDOT]).unwrap()[1];
+        assert_eq!(
+            amount_out,
+            AMM::get_concentrated_amount_out(10_000, DOT, SDOT).unwrap()
+        );
+        let (reserve_in, reserve_out) = AMM::get_reserves(DOT, SDOT).unwrap();
+        assert!(
+            amount_out
+                > AMM::get_amount_out(10_000, reserve_in, reserve_out, DefaultLpFee::get())
+                    .unwrap()
+        );
+
+        assert_ok!(AMM::swap(&CHARLIE, (DOT, SDOT), 10_000));
+        let pool = AMM::pools(SDOT, DOT).unwrap();
+        assert_eq!((pool.base_amount, pool.quote_amount), (100_000, 100_000));
+    })
+}
+
+#[test]
+fn pool_fees_should_be_set_at_create_pool() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            AMM::create_pool(
+                RawOrigin::Signed(ALICE).into(),
+                (DOT, SDOT),
+                (100_000, 100_000),
+                BOB,
+                SAMPLE_LP_TOKEN,
+                Ratio::one(),
+                None,
+            ),
+            Error::<Test>::InvalidPoolFee
+        );
+        assert_noop!(
+            AMM::create_pool(
+                RawOrigin::Signed(ALICE).into(),
+                (DOT, SDOT),
+                (100_000, 100_000),
+                BOB,
+                SAMPLE_LP_TOKEN,
+                DefaultLpFee::get(),
+                Some(Ratio::one()),
+            ),
+            Error::<Test>::InvalidPoolFee
+        );
+
+        assert_ok!(AMM::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            (100_000, 100_000),
+            BOB,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None,
+        ));
+        assert_ok!(AMM::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, KSM),
+            (100_000, 100_000),
+            BOB,
+            SAMPLE_LP_TOKEN_2,
+            Ratio::from_percent(1),
+            Some(Ratio::from_percent(10)),
+        ));
+
+        let pool = AMM::pools(SDOT, DOT).unwrap();
+        assert_eq!(
+            (pool.lp_fee, pool.protocol_fee),
+            (DefaultLpFee::get(), None)
+        );
+        let pool = AMM::pools(KSM, DOT).unwrap();
+        assert_eq!(
+            (pool.lp_fee, pool.protocol_fee),
+            (Ratio::from_percent(1), Some(Ratio::from_percent(10)))
+        );
+
+        // quotes use the fee of the pool
+        assert_eq!(
+            AMM::get_amounts_out(1_000, vec![DOT, KSM]).unwrap()[1],
+            AMM::get_amount_out(1_000, 100_000, 100_000, Ratio::from_percent(1)).unwrap()
+        );
+    })
+}
+
+#[test]
+fn update_pool_fees_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AMM::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            BOB,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None,
+        ));
+
+        assert_noop!(
+            AMM::update_pool_fees(
+                RuntimeOrigin::signed(BOB),
+                (DOT, SDOT),
+                Ratio::from_percent(1),
+                None
+            ),
+            BadOrigin
+        );
+        assert_noop!(
+            AMM::update_pool_fees(
+                RuntimeOrigin::signed(ALICE),
+                (DOT, SDOT),
+                Ratio::one(),
+                None
+            ),
+            Error::<Test>::InvalidPoolFee
+        );
+        assert_noop!(
+            AMM::update_pool_fees(
+                RuntimeOrigin::signed(ALICE),
+                (DOT, KSM),
+                Ratio::from_percent(1),
+                None
+            ),
+            Error::<Test>::PoolDoesNotExist
+        );
+
+        assert_ok!(AMM::update_pool_fees(
+            RuntimeOrigin::signed(ALICE),
+            (SDOT, DOT),
+            Ratio::from_percent(1),
+            Some(Ratio::from_percent(10))
+        ));
+        let pool = AMM::pools(SDOT, DOT).unwrap();
+        assert_eq!(
+            (pool.lp_fee, pool.protocol_fee),
+            (Ratio::from_percent(1), Some(Ratio::from_percent(10)))
+        );
+
+        // quotes and trades use the fee of the pool
+        let amounts_out = AMM::get_amounts_out(100_000, vec![DOT, SDOT]).unwrap();
+        assert_eq!(
+            amounts_out[1],
+            AMM::get_amount_out(100_000, 100_000_000, 100_000_000, Ratio::from_percent(1)).unwrap()
+        );
+        let amounts_in = AMM::get_amounts_in(amounts_out[1], vec![DOT, SDOT]).unwrap();
+        assert!(amounts_in[0] >= 100_000);
+
+        assert_ok!(AMM::swap(&FRANK, (DOT, SDOT), 100_000));
+        assert_eq!(Assets::balance(SDOT, FRANK), amounts_out[1]);
+    })
+}
+
+#[test]
+fn handling_fees_with_pool_protocol_fee_should_work() {
+    new_test_ext().execute_with(|| {
+        // Pool gets created and BOB should receive all of the LP tokens (minus the min amount)
+        //
+        assert_ok!(AMM::create_pool(
+            RawOrigin::Signed(ALICE).into(),    // Origin
+            (DOT, SDOT),                        // Currency pool, in which liquidity will be added
+            (100_000_000_000, 100_000_000_000), // Liquidity amounts to be added in pool
+            BOB,                                // LPToken receiver
+            SAMPLE_LP_TOKEN,                    // Liquidity pool share representative token
+            DefaultLpFee::get(),                // LP fee
+            None,                               // Protocol fee, None follows the AMM protocol fee
+        ));
+
+        assert_ok!(AMM::update_protocol_fee_receiver(
+            RuntimeOrigin::signed(ALICE),
+            PROTOCOL_FEE_RECEIVER
+        ));
+
+        // the pool starts tracking its growth when it gets its own protocol fee
+        assert_ok!(AMM::update_pool_fees(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            DefaultLpFee::get(),
+            Some(Ratio::from_percent(20))
+        ));
+
+        // Another user makes a swap that should generate fees for the LP provider and the protocol
+        assert_ok!(AMM::swap(&FRANK, (DOT, SDOT), 6_000_000));
+
+        // 1_499 lp tokens are minted for 1/5 of the fees, see handling_fees_should_work
+        assert_ok!(AMM::remove_liquidity(
+            RawOrigin::Signed(PROTOCOL_FEE_RECEIVER).into(),
+            (DOT, SDOT),
+            1_285,
+        ));
+
+        // the protocol fee receiver got the 1_499 minted lp tokens
+        assert_eq!(
+            Assets::balance(SAMPLE_LP_TOKEN, PROTOCOL_FEE_RECEIVER),
+            1_499 - 1_285
+        );
+        assert_eq!(Assets::balance(DOT, PROTOCOL_FEE_RECEIVER), 1285);
+        assert_eq!(Assets::balance(SDOT, PROTOCOL_FEE_RECEIVER), 1284);
+    })
+}
+
+#[test]
+fn dynamic_fee_should_follow_price_deviation() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AMM::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            (100_000_000_000, 100_000_000_000),
+            BOB,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None,
+        ));
+
+        assert_noop!(
+            AMM::set_dynamic_fee(
+                RuntimeOrigin::signed(ALICE),
+                (DOT, SDOT),
+                Some((Ratio::from_percent(5), Ratio::from_percent(20), 0))
+            ),
+            Error::<Test>::InvalidPoolFee
+        );
+        assert_ok!(AMM::set_dynamic_fee(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            Some((Ratio::from_percent(5), Ratio::from_percent(20), 10))
+        ));
+
+        // no average price before the first window is over
+        let pool = AMM::pools(SDOT, DOT).unwrap();
+        assert_eq!(AMM::current_lp_fee((SDOT, DOT), &pool), DefaultLpFee::get());
+
+        run_to_block(10);
+        assert_ok!(AMM::swap(&FRANK, (DOT, SDOT), 1_000));
+        assert_eq!(
+            AMM::dynamic_fees(SDOT, DOT).unwrap().average_price,
+            FixedU128::one()
+        );
+
+        // a 10% move of the price away from the average
+        assert_ok!(AMM::swap(&FRANK, (DOT, SDOT), 4_880_000_000));
+        let pool = AMM::pools(SDOT, DOT).unwrap();
+        let lp_fee = AMM::current_lp_fee((SDOT, DOT), &pool);
+        assert!(lp_fee > DefaultLpFee::get() && lp_fee < Ratio::from_percent(5));
+
+        // a large move is capped at the max fee
+        assert_ok!(AMM::swap(&FRANK, (DOT, SDOT), 10_000_000_000));
+        let pool = AMM::pools(SDOT, DOT).unwrap();
+        assert_eq!(
+            AMM::current_lp_fee((SDOT, DOT), &pool),
+            Ratio::from_percent(5)
+        );
+
+        // quotes and trades use the dynamic fee
+        let amounts_out = AMM::get_amounts_out(1_000_000, vec![DOT, SDOT]).unwrap();
+        assert_eq!(
+            amounts_out[1],
+            AMM::get_amount_out(
+                1_000_000,
+                pool.quote_amount,
+                pool.base_amount,
+                Ratio::from_percent(5)
+            )
+            .unwrap()
+        );
+        let balance = Assets::balance(SDOT, FRANK);
+        assert_ok!(AMM::swap(&FRANK, (DOT, SDOT), 1_000_000));
+        assert_eq!(Assets::balance(SDOT, FRANK) - balance, amounts_out[1]);
+
+        assert_ok!(AMM::set_dynamic_fee(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            None
+        ));
+        let pool = AMM::pools(SDOT, DOT).unwrap();
+        assert_eq!(AMM::current_lp_fee((SDOT, DOT), &pool), DefaultLpFee::get());
+    })
+}
+
+#[test]
+fn zap_in_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AMM::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            BOB,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None,
+        ));
+        let dot_balance = Assets::balance(DOT, FRANK);
+
+        assert_noop!(
+            AMM::zap_in(RawOrigin::Signed(FRANK).into(), (DOT, KSM), 1_000_000, 0),
+            Error::<Test>::PoolDoesNotExist
+        );
+        assert_noop!(
+            AMM::zap_in(
+                RawOrigin::Signed(FRANK).into(),
+                (DOT, SDOT),
+                1_000_000,
+                1_000_000
+            ),
+            Error::<Test>::SlippageExceeded
+        );
+
+        assert_ok!(AMM::zap_in(
+            RawOrigin::Signed(FRANK).into(),
+            (DOT, SDOT),
+            1_000_000,
+            490_000
+        ));
+
+        // the swapped half and the rest are added at the pool ratio, only dust is left
+        let liquidity = Assets::balance(SAMPLE_LP_TOKEN, FRANK);
+        assert!(liquidity > 490_000 && liquidity < 500_000);
+        assert!(dot_balance - Assets::balance(DOT, FRANK) >= 999_990);
+        assert!(Assets::balance(SDOT, FRANK) <= 10);
+
+        let pool = AMM::pools(SDOT, DOT).unwrap();
+        assert_eq!(
+            pool.quote_amount,
+            100_000_000 + dot_balance - Assets::balance(DOT, FRANK)
+        );
+    })
+}
+
+#[test]
+fn zap_out_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AMM::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            BOB,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None,
+        ));
+        assert_ok!(AMM::zap_in(
+            RawOrigin::Signed(FRANK).into(),
+            (DOT, SDOT),
+            1_000_000,
+            0
+        ));
+        let liquidity = Assets::balance(SAMPLE_LP_TOKEN, FRANK);
+        let dot_balance = Assets::balance(DOT, FRANK);
+        let sdot_balance = Assets::balance(SDOT, FRANK);
+
+        assert_noop!(
+            AMM::zap_out(
+                RawOrigin::Signed(FRANK).into(),
+                (DOT, SDOT),
+                liquidity,
+                1_000_000
+            ),
+            Error::<Test>::SlippageExceeded
+        );
+
+        assert_ok!(AMM::zap_out(
+            RawOrigin::Signed(FRANK).into(),
+            (DOT, SDOT),
+            liquidity,
+            990_000
+        ));
+
+        // everything comes back as DOT, minus the fees of the two swaps
+        let amount_out = Assets::balance(DOT, FRANK) - dot_balance;
+        assert!(amount_out >= 990_000 && amount_out < 1_000_000);
+        assert_eq!(Assets::balance(SDOT, FRANK), sdot_balance);
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, FRANK), 0);
+    })
+}
+
+#[test]
+fn zap_swap_amount_should_balance_the_pool_ratio() {
+    new_test_ext().execute_with(|| {
+        // without fees the swap amount is sqrt(r (r + a)) - r
+        assert_eq!(
+            AMM::get_zap_swap_amount(21_000, 100_000, Ratio::zero()),
+            Ok(10_000)
+        );
+        // fees raise the amount to swap
+        assert!(AMM::get_zap_swap_amount(21_000, 100_000, DefaultLpFee::get()).unwrap() > 10_000);
+        assert_eq!(
+            AMM::get_zap_swap_amount(21_000, 0, DefaultLpFee::get()),
+            Err(Error::<Test>::InsufficientLiquidity.into())
+        );
+    })
+}
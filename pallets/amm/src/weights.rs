@@ -50,6 +50,15 @@ pub trait WeightInfo {
   fn create_pool() -> Weight;
   fn update_protocol_fee() -> Weight;
   fn update_protocol_fee_receiver() -> Weight;
+  fn set_fee_tier() -> Weight;
+  fn create_concentrated_pool() -> Weight;
+  fn mint_position() -> Weight;
+  fn decrease_position_liquidity() -> Weight;
+  fn collect_position_fees() -> Weight;
+  fn update_pool_fees() -> Weight;
+  fn set_dynamic_fee() -> Weight;
+  fn zap_in() -> Weight;
+  fn zap_out() -> Weight;
 }
 
 /// Weights for pallet_amm using the Substrate node and recommended hardware.
@@ -91,6 +100,88 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
   fn update_protocol_fee_receiver() -> Weight {
 	Weight::from_ref_time(4_114_000 as u64).saturating_add(T::DbWeight::get().writes(1 as u64))
   }
+	// Storage: AMM FeeTiers (r:0 w:1)
+	fn set_fee_tier() -> Weight {
+		Weight::from_ref_time(14_512_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(0 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: AMM ConcentratedPools (r:1 w:1)
+	// Storage: AMM FeeTiers (r:1 w:0)
+	fn create_concentrated_pool() -> Weight {
+		Weight::from_ref_time(27_301_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: AMM ConcentratedPools (r:1 w:1)
+	// Storage: AMM ConcentratedTicks (r:2 w:2)
+	// Storage: AMM InitializedTicks (r:1 w:1)
+	// Storage: AMM NextPositionId (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	// Storage: AMM Positions (r:0 w:1)
+	fn mint_position() -> Weight {
+		Weight::from_ref_time(112_845_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(11 as u64))
+			.saturating_add(T::DbWeight::get().writes(10 as u64))
+	}
+	// Storage: AMM Positions (r:1 w:1)
+	// Storage: AMM ConcentratedPools (r:1 w:1)
+	// Storage: AMM ConcentratedTicks (r:2 w:2)
+	// Storage: AMM InitializedTicks (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	fn decrease_position_liquidity() -> Weight {
+		Weight::from_ref_time(118_920_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(11 as u64))
+			.saturating_add(T::DbWeight::get().writes(10 as u64))
+	}
+	// Storage: AMM Positions (r:1 w:1)
+	// Storage: AMM ConcentratedPools (r:1 w:0)
+	// Storage: AMM ConcentratedTicks (r:2 w:0)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	fn collect_position_fees() -> Weight {
+		Weight::from_ref_time(84_117_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(10 as u64))
+			.saturating_add(T::DbWeight::get().writes(7 as u64))
+	}
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: AMM ProtocolFeeReceiver (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets Account (r:1 w:0)
+	fn update_pool_fees() -> Weight {
+		Weight::from_ref_time(38_411_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: AMM DynamicFees (r:0 w:1)
+	fn set_dynamic_fee() -> Weight {
+		Weight::from_ref_time(24_937_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: AMM DynamicFees (r:1 w:1)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:5 w:5)
+	fn zap_in() -> Weight {
+		Weight::from_ref_time(301_264_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(11 as u64))
+			.saturating_add(T::DbWeight::get().writes(11 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: AMM DynamicFees (r:1 w:1)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:5 w:5)
+	fn zap_out() -> Weight {
+		Weight::from_ref_time(318_702_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(11 as u64))
+			.saturating_add(T::DbWeight::get().writes(11 as u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -131,4 +222,86 @@ impl WeightInfo for () {
 	fn update_protocol_fee_receiver() -> Weight {
 		Weight::from_ref_time(4_114_000 as u64).saturating_add(RocksDbWeight::get().writes(1 as u64))
 	}
+	// Storage: AMM FeeTiers (r:0 w:1)
+	fn set_fee_tier() -> Weight {
+		Weight::from_ref_time(14_512_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(0 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: AMM ConcentratedPools (r:1 w:1)
+	// Storage: AMM FeeTiers (r:1 w:0)
+	fn create_concentrated_pool() -> Weight {
+		Weight::from_ref_time(27_301_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: AMM ConcentratedPools (r:1 w:1)
+	// Storage: AMM ConcentratedTicks (r:2 w:2)
+	// Storage: AMM InitializedTicks (r:1 w:1)
+	// Storage: AMM NextPositionId (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	// Storage: AMM Positions (r:0 w:1)
+	fn mint_position() -> Weight {
+		Weight::from_ref_time(112_845_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(11 as u64))
+			.saturating_add(RocksDbWeight::get().writes(10 as u64))
+	}
+	// Storage: AMM Positions (r:1 w:1)
+	// Storage: AMM ConcentratedPools (r:1 w:1)
+	// Storage: AMM ConcentratedTicks (r:2 w:2)
+	// Storage: AMM InitializedTicks (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	fn decrease_position_liquidity() -> Weight {
+		Weight::from_ref_time(118_920_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(11 as u64))
+			.saturating_add(RocksDbWeight::get().writes(10 as u64))
+	}
+	// Storage: AMM Positions (r:1 w:1)
+	// Storage: AMM ConcentratedPools (r:1 w:0)
+	// Storage: AMM ConcentratedTicks (r:2 w:0)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	fn collect_position_fees() -> Weight {
+		Weight::from_ref_time(84_117_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(10 as u64))
+			.saturating_add(RocksDbWeight::get().writes(7 as u64))
+	}
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: AMM ProtocolFeeReceiver (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets Account (r:1 w:0)
+	fn update_pool_fees() -> Weight {
+		Weight::from_ref_time(38_411_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: AMM DynamicFees (r:0 w:1)
+	fn set_dynamic_fee() -> Weight {
+		Weight::from_ref_time(24_937_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: AMM DynamicFees (r:1 w:1)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:5 w:5)
+	fn zap_in() -> Weight {
+		Weight::from_ref_time(301_264_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(11 as u64))
+			.saturating_add(RocksDbWeight::get().writes(11 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: AMM DynamicFees (r:1 w:1)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:5 w:5)
+	fn zap_out() -> Weight {
+		Weight::from_ref_time(318_702_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(11 as u64))
+			.saturating_add(RocksDbWeight::get().writes(11 as u64))
+	}
 }
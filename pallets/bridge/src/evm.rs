@@ -0,0 +1,277 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoding of EVM headers sealed by a set of validators (clique/parlia style),
+//! and verification of receipt proofs against the receipts root of a header.
+
+use primitives::{Balance, ChainId, ChainNonce, CurrencyId};
+use rlp::{Rlp, RlpStream};
+use sp_core::{H160, H256};
+use sp_io::{crypto::secp256k1_ecdsa_recover, hashing::keccak_256};
+use sp_std::vec::Vec;
+
+/// The length of the vanity prefix in the extra data of a header
+pub const EXTRA_VANITY: usize = 32;
+/// The length of the validator seal at the end of the extra data of a header
+pub const EXTRA_SEAL: usize = 65;
+
+// The position of the fields of a RLP encoded header
+const PARENT_HASH: usize = 0;
+const RECEIPTS_ROOT: usize = 5;
+const NUMBER: usize = 8;
+const TIMESTAMP: usize = 11;
+const EXTRA_DATA: usize = 12;
+const HEADER_MIN_FIELDS: usize = 15;
+
+/// The signature of the event emitted by the bridge contract on teleport
+pub const TELEPORT_EVENT: &[u8] = b"Teleported(uint256,uint256,uint256,bytes32,uint256)";
+
+/// A header of an EVM chain
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EvmHeader {
+    pub hash: H256,
+    pub parent_hash: H256,
+    pub receipts_root: H256,
+    pub number: u64,
+    pub timestamp: u64,
+    pub extra_data: Vec<u8>,
+    /// The hash of the header without the seal, which is signed by the validator
+    pub seal_hash: H256,
+}
+
+impl EvmHeader {
+    /// Decodes a RLP encoded header
+    pub fn decode(raw: &[u8]) -> Option<Self> {
+        let rlp = Rlp::new(raw);
+        let fields = rlp.item_count().ok()?;
+        if !rlp.is_list() || fields < HEADER_MIN_FIELDS {
+            return None;
+        }
+
+        let extra_data: Vec<u8> = rlp.val_at(EXTRA_DATA).ok()?;
+        if extra_data.len() < EXTRA_VANITY + EXTRA_SEAL {
+            return None;
+        }
+
+        let mut unsealed = RlpStream::new_list(fields);
+        for index in 0..fields {
+            if index == EXTRA_DATA {
+                unsealed.append(&extra_data[..extra_data.len() - EXTRA_SEAL].to_vec());
+            } else {
+                unsealed.append_raw(rlp.at(index).ok()?.as_raw(), 1);
+            }
+        }
+
+        Some(Self {
+            hash: H256(keccak_256(raw)),
+            parent_hash: decode_hash(&rlp, PARENT_HASH)?,
+            receipts_root: decode_hash(&rlp, RECEIPTS_ROOT)?,
+            number: rlp.val_at(NUMBER).ok()?,
+            timestamp: rlp.val_at(TIMESTAMP).ok()?,
+            extra_data,
+            seal_hash: H256(keccak_256(&unsealed.out())),
+        })
+    }
+
+    /// Recovers the validator who sealed the header
+    pub fn signer(&self) -> Option<H160> {
+        let mut signature = [0u8; EXTRA_SEAL];
+        signature.copy_from_slice(&self.extra_data[self.extra_data.len() - EXTRA_SEAL..]);
+        let public = secp256k1_ecdsa_recover(&signature, self.seal_hash.as_fixed_bytes()).ok()?;
+
+        Some(H160::from_slice(&keccak_256(&public)[12..]))
+    }
+
+    /// The validators listed in the extra data of an epoch checkpoint header
+    pub fn validators(&self) -> Option<Vec<H160>> {
+        let list = &self.extra_data[EXTRA_VANITY..self.extra_data.len() - EXTRA_SEAL];
+        if list.is_empty() || list.len() % H160::len_bytes() != 0 {
+            return None;
+        }
+
+        Some(
+            list.chunks(H160::len_bytes())
+                .map(H160::from_slice)
+                .collect(),
+        )
+    }
+}
+
+/// A log emitted by a transaction
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EvmLog {
+    pub address: H160,
+    pub topics: Vec<H256>,
+    pub data: Vec<u8>,
+}
+
+/// Decodes the logs of a RLP encoded receipt, typed receipts are prefixed by their type
+pub fn decode_receipt_logs(receipt: &[u8]) -> Option<Vec<EvmLog>> {
+    let receipt = match receipt.first()? {
+        0x00..=0x7f => &receipt[1..],
+        _ => receipt,
+    };
+    let rlp = Rlp::new(receipt);
+    if !rlp.is_list() || rlp.item_count().ok()? != 4 {
+        return None;
+    }
+
+    rlp.at(3)
+        .ok()?
+        .iter()
+        .map(|log| {
+            let address: Vec<u8> = log.val_at(0).ok()?;
+            if address.len() != H160::len_bytes() {
+                return None;
+            }
+            let topics = log
+                .at(1)
+                .ok()?
+                .iter()
+                .map(|topic| {
+                    let topic: Vec<u8> = topic.as_val().ok()?;
+                    (topic.len() == H256::len_bytes()).then(|| H256::from_slice(&topic))
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            Some(EvmLog {
+                address: H160::from_slice(&address),
+                topics,
+                data: log.val_at(2).ok()?,
+            })
+        })
+        .collect()
+}
+
+/// A teleport to another chain emitted by the bridge contract
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TeleportLog {
+    pub dest_id: ChainId,
+    pub nonce: ChainNonce,
+    pub bridge_token_id: CurrencyId,
+    pub to: [u8; 32],
+    pub amount: Balance,
+}
+
+impl TeleportLog {
+    /// Decodes the ABI encoded teleport event
+    pub fn decode(log: &EvmLog) -> Option<Self> {
+        if log.topics.first() != Some(&H256(keccak_256(TELEPORT_EVENT))) || log.data.len() != 160 {
+            return None;
+        }
+
+        let words: Vec<&[u8]> = log.data.chunks(32).collect();
+        let mut to = [0u8; 32];
+        to.copy_from_slice(words[3]);
+
+        Some(Self {
+            dest_id: decode_uint(words[0])?.try_into().ok()?,
+            nonce: decode_uint(words[1])?.try_into().ok()?,
+            bridge_token_id: decode_uint(words[2])?.try_into().ok()?,
+            to,
+            amount: decode_uint(words[4])?,
+        })
+    }
+}
+
+/// Verifies a Merkle-Patricia proof of `key` in the trie of `root` and returns its value
+pub fn verify_trie_proof(root: H256, key: &[u8], proof: &[Vec<u8>]) -> Option<Vec<u8>> {
+    let nibbles = to_nibbles(key);
+    let mut key = &nibbles[..];
+    let mut nodes = proof.iter();
+    let mut node = nodes.next()?.clone();
+    if H256(keccak_256(&node)) != root {
+        return None;
+    }
+
+    loop {
+        let next = {
+            let rlp = Rlp::new(&node);
+            let child = match rlp.item_count().ok()? {
+                // Branch node
+                17 => match key.split_first() {
+                    Some((nibble, rest)) => {
+                        key = rest;
+                        rlp.at(*nibble as usize).ok()?
+                    }
+                    None => {
+                        let value: Vec<u8> = rlp.val_at(16).ok()?;
+                        return (!value.is_empty()).then_some(value);
+                    }
+                },
+                // Extension or leaf node
+                2 => {
+                    let (is_leaf, path) = decode_path(&rlp.val_at::<Vec<u8>>(0).ok()?)?;
+                    if !key.starts_with(&path) {
+                        return None;
+                    }
+                    key = &key[path.len()..];
+                    if is_leaf {
+                        return key.is_empty().then(|| rlp.val_at(1).ok()).flatten();
+                    }
+                    rlp.at(1).ok()?
+                }
+                _ => return None,
+            };
+
+            // Children shorter than a hash are embedded in their parent
+            if child.is_list() {
+                child.as_raw().to_vec()
+            } else {
+                let hash: Vec<u8> = child.as_val().ok()?;
+                let next = nodes.next()?;
+                if hash.len() != H256::len_bytes() || keccak_256(next)[..] != hash[..] {
+                    return None;
+                }
+                next.clone()
+            }
+        };
+        node = next;
+    }
+}
+
+fn decode_hash(rlp: &Rlp, index: usize) -> Option<H256> {
+    let hash: Vec<u8> = rlp.val_at(index).ok()?;
+    (hash.len() == H256::len_bytes()).then(|| H256::from_slice(&hash))
+}
+
+fn decode_uint(word: &[u8]) -> Option<u128> {
+    let (high, low) = word.split_at(16);
+    if high.iter().any(|b| *b != 0) {
+        return None;
+    }
+
+    Some(u128::from_be_bytes(low.try_into().ok()?))
+}
+
+fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// Decodes a hex-prefix encoded path, returns whether it terminates in a leaf
+fn decode_path(encoded: &[u8]) -> Option<(bool, Vec<u8>)> {
+    let (first, rest) = encoded.split_first()?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return None;
+    }
+
+    let mut path = Vec::new();
+    if flag & 1 == 1 {
+        path.push(first & 0x0f);
+    }
+    path.extend(to_nibbles(rest));
+
+    Some((flag & 2 == 2, path))
+}
@@ -19,10 +19,16 @@
 //!
 //! The bridge pallet implement the transfer of tokens between `parallel` and `eth chains`
 //! and the security of funds is secured by multiple signatures mechanism
+//!
+//! Transfers from an EVM chain can alternatively be materialized with a receipt proof
+//! checked against the headers finalized by an on-chain light client of that chain,
+//! in which case the votes of the relay members are not needed.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use crate::types::{BridgeToken, MaterializeCall, Proposal, ProposalStatus};
+use crate::types::{
+    BridgeToken, ImportedHeader, LightClient, MaterializeCall, Proposal, ProposalStatus,
+};
 use frame_support::{
     log,
     pallet_prelude::*,
@@ -38,6 +44,7 @@ use frame_support::{
 };
 use frame_system::{ensure_signed_or_root, pallet_prelude::*};
 use primitives::{Balance, BridgeInterval, ChainId, ChainNonce, CurrencyId, Ratio};
+use sp_core::{H160, H256};
 use sp_runtime::{
     traits::{AccountIdConversion, Zero},
     ArithmeticError,
@@ -45,6 +52,8 @@ use sp_runtime::{
 use sp_std::{vec, vec::Vec};
 
 mod benchmarking;
+pub mod evm;
+mod light_client;
 mod mock;
 mod tests;
 mod types;
@@ -131,6 +140,15 @@ pub mod pallet {
         #[pallet::constant]
         type ThresholdPercentage: Get<u32>;
 
+        /// The maximum number of unfinalized headers kept by a light client
+        #[pallet::constant]
+        type MaxUnfinalizedHeaders: Get<u32>;
+
+        /// The number of the latest finalized headers whose receipts roots are kept
+        /// by a light client
+        #[pallet::constant]
+        type MaxFinalizedReceiptsRoots: Get<u32>;
+
         /// Information on runtime weights.
         type WeightInfo: WeightInfo;
     }
@@ -171,6 +189,24 @@ pub mod pallet {
         ProposalAlreadyComplete,
         /// The proposal has exceeded its life time.
         ProposalExpired,
+        /// The light client of the chain_id is not initialized
+        LightClientNotInitialized,
+        /// The header cannot be decoded or doesn't follow its parent
+        InvalidHeader,
+        /// The parent of the header is neither finalized nor unfinalized
+        UnknownParentHeader,
+        /// The header is not sealed by a validator
+        UnauthorizedSigner,
+        /// The validator has sealed one of the recent headers
+        SignerRecentlySigned,
+        /// The light client has too many unfinalized headers
+        TooManyUnfinalizedHeaders,
+        /// The header is not finalized by the light client
+        HeaderNotFinalized,
+        /// The receipt proof is invalid
+        InvalidReceiptProof,
+        /// The log is not a teleport of the bridge contract to this chain
+        InvalidTeleportLog,
     }
 
     /// Event for the Bridge Pallet
@@ -279,6 +315,22 @@ pub mod pallet {
         /// Proposal was rejected
         /// [src_id, src_nonce]
         ProposalRejected(ChainId, ChainNonce),
+
+        /// The light client of a chain has been initialized
+        /// [chain_id, block_number, block_hash]
+        LightClientInitialized(ChainId, u64, H256),
+
+        /// A header has been imported by the light client
+        /// [chain_id, block_number, block_hash]
+        HeaderImported(ChainId, u64, H256),
+
+        /// The light client has finalized headers up to this one
+        /// [chain_id, block_number, block_hash]
+        HeaderFinalized(ChainId, u64, H256),
+
+        /// A teleport has been materialized with a receipt proof
+        /// [relayer, src_id, src_nonce, block_number]
+        MaterializeProved(T::AccountId, ChainId, ChainNonce, u64),
     }
 
     #[pallet::type_value]
@@ -322,6 +374,25 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Light clients of the EVM chains whose teleports can be materialized with proofs
+    #[pallet::storage]
+    #[pallet::getter(fn light_client)]
+    pub type LightClients<T: Config> =
+        StorageMap<_, Blake2_128Concat, ChainId, LightClient, OptionQuery>;
+
+    /// Headers imported after the finalized header of each light client
+    #[pallet::storage]
+    #[pallet::getter(fn unfinalized_headers)]
+    pub type UnfinalizedHeaders<T: Config> =
+        StorageMap<_, Blake2_128Concat, ChainId, Vec<ImportedHeader>, ValueQuery>;
+
+    /// Mapping of [chain_id -> block_number -> receipts_root] of the latest
+    /// [MaxFinalizedReceiptsRoots] finalized headers
+    #[pallet::storage]
+    #[pallet::getter(fn finalized_receipts_root)]
+    pub type FinalizedReceiptsRoots<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, ChainId, Twox64Concat, u64, H256, OptionQuery>;
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Register the specified chain_id
@@ -352,7 +423,9 @@ pub mod pallet {
 
         /// Unregister the specified chain_id
         #[pallet::call_index(1)]
-        #[pallet::weight(T::WeightInfo::unregister_chain())]
+        #[pallet::weight(T::WeightInfo::unregister_chain().saturating_add(
+            T::DbWeight::get().writes(T::MaxFinalizedReceiptsRoots::get().into())
+        ))]
         #[transactional]
         pub fn unregister_chain(origin: OriginFor<T>, chain_id: ChainId) -> DispatchResult {
             T::UpdateChainOrigin::ensure_origin(origin)?;
@@ -363,6 +436,13 @@ pub mod pallet {
             // Unregister the chain_id
             ChainNonces::<T>::remove(chain_id);
             BridgeRegistry::<T>::remove(chain_id);
+            LightClients::<T>::remove(chain_id);
+            UnfinalizedHeaders::<T>::remove(chain_id);
+            let _ = FinalizedReceiptsRoots::<T>::clear_prefix(
+                chain_id,
+                T::MaxFinalizedReceiptsRoots::get(),
+                None,
+            );
 
             Self::deposit_event(Event::ChainRemoved(chain_id));
 
@@ -647,6 +727,129 @@ pub mod pallet {
 
             Self::resolve_proposal(src_id, src_nonce, call)
         }
+
+        /// Initialize the light client of an EVM chain from a trusted header
+        ///
+        /// - `chain_id`: the registered chain tracked by the light client
+        /// - `header`: RLP encoded header which is considered finalized
+        /// - `validators`: the validators sealing the headers after `header`
+        /// - `bridge_contract`: the contract emitting the teleport logs
+        /// - `epoch_length`: the number of blocks between two epoch checkpoint headers
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::initialize_light_client().saturating_add(
+            T::DbWeight::get().writes(T::MaxFinalizedReceiptsRoots::get().into())
+        ))]
+        #[transactional]
+        pub fn initialize_light_client(
+            origin: OriginFor<T>,
+            chain_id: ChainId,
+            header: Vec<u8>,
+            validators: Vec<H160>,
+            bridge_contract: H160,
+            epoch_length: u64,
+        ) -> DispatchResult {
+            T::UpdateChainOrigin::ensure_origin(origin)?;
+            Self::ensure_chain_registered(chain_id)?;
+
+            let header = evm::EvmHeader::decode(&header).ok_or(Error::<T>::InvalidHeader)?;
+            ensure!(
+                !validators.is_empty() && epoch_length > 0,
+                Error::<T>::InvalidHeader
+            );
+
+            let finalized = ImportedHeader {
+                number: header.number,
+                hash: header.hash,
+                receipts_root: header.receipts_root,
+                timestamp: header.timestamp,
+                signer: header.signer().unwrap_or_default(),
+                validators: None,
+            };
+            let _ = FinalizedReceiptsRoots::<T>::clear_prefix(
+                chain_id,
+                T::MaxFinalizedReceiptsRoots::get(),
+                None,
+            );
+            FinalizedReceiptsRoots::<T>::insert(chain_id, header.number, header.receipts_root);
+            UnfinalizedHeaders::<T>::remove(chain_id);
+            LightClients::<T>::insert(
+                chain_id,
+                LightClient {
+                    bridge_contract,
+                    epoch_length,
+                    validators,
+                    finalized,
+                },
+            );
+            Self::deposit_event(Event::LightClientInitialized(
+                chain_id,
+                header.number,
+                header.hash,
+            ));
+
+            Ok(())
+        }
+
+        /// Import headers into the light client of an EVM chain
+        ///
+        /// - `chain_id`: the chain tracked by the light client
+        /// - `headers`: RLP encoded headers, each one is the child of an imported header
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::submit_headers(headers.len() as u32))]
+        #[transactional]
+        pub fn submit_headers(
+            origin: OriginFor<T>,
+            chain_id: ChainId,
+            headers: Vec<Vec<u8>>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            for header in headers {
+                Self::import_header(chain_id, &header)?;
+            }
+
+            Ok(())
+        }
+
+        /// Materialize a teleport from an EVM chain with a proof of the receipt
+        /// logging it, checked against a header finalized by the light client
+        ///
+        /// Relay members can still vote for teleports which are not proved.
+        ///
+        /// - `src_id`: chain_id of the source chain
+        /// - `block_number`: the finalized block including the teleport
+        /// - `tx_index`: index of the teleport transaction in the block
+        /// - `receipt_proof`: nodes of the receipts trie from the root to the receipt
+        /// - `log_index`: index of the teleport log in the receipt
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::WeightInfo::materialize_with_proof())]
+        #[transactional]
+        pub fn materialize_with_proof(
+            origin: OriginFor<T>,
+            src_id: ChainId,
+            block_number: u64,
+            tx_index: u64,
+            receipt_proof: Vec<Vec<u8>>,
+            log_index: u32,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_chain_registered(src_id)?;
+
+            let (src_nonce, call) =
+                Self::verify_teleport(src_id, block_number, tx_index, &receipt_proof, log_index)?;
+            Self::ensure_chain_nonce_valid(src_id, src_nonce)?;
+            Self::materialize_allowed(call.bridge_token_id, call.amount)?;
+
+            Self::execute_materialize(src_id, src_nonce, call)?;
+            Self::deposit_event(Event::MaterializeProved(
+                who,
+                src_id,
+                src_nonce,
+                block_number,
+            ));
+
+            Ok(())
+        }
     }
 
     #[pallet::hooks]
@@ -0,0 +1,172 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tracks the finalized headers of an EVM chain sealed by a set of validators, so that
+//! teleports on that chain can be materialized with a receipt proof instead of votes.
+//!
+//! A header is imported when it is sealed by a validator who didn't seal any of the
+//! last `validators / 2` headers, and it becomes final once it and its descendants
+//! are sealed by more than half of the validators. The validators listed by an epoch
+//! checkpoint header only take over once the checkpoint is finalized by the current
+//! validators.
+
+use super::*;
+use crate::evm::{self, EvmHeader, TeleportLog};
+use codec::Decode;
+
+impl<T: Config> Pallet<T> {
+    /// Imports a RLP encoded header on top of the finalized or an unfinalized header,
+    /// the unfinalized headers after its parent are dropped
+    #[require_transactional]
+    pub(crate) fn import_header(chain_id: ChainId, raw: &[u8]) -> DispatchResult {
+        let mut client =
+            Self::light_client(chain_id).ok_or(Error::<T>::LightClientNotInitialized)?;
+        let header = EvmHeader::decode(raw).ok_or(Error::<T>::InvalidHeader)?;
+        let mut headers = Self::unfinalized_headers(chain_id);
+
+        if header.parent_hash != client.finalized.hash {
+            let parent = headers
+                .iter()
+                .position(|h| h.hash == header.parent_hash)
+                .ok_or(Error::<T>::UnknownParentHeader)?;
+            headers.truncate(parent + 1);
+        } else {
+            headers.clear();
+        }
+        let parent = headers.last().unwrap_or(&client.finalized);
+        ensure!(
+            header.number == parent.number + 1 && header.timestamp > parent.timestamp,
+            Error::<T>::InvalidHeader
+        );
+
+        // unfinalized checkpoints can't change the validators
+        let signer = header.signer().ok_or(Error::<T>::InvalidHeader)?;
+        ensure!(
+            client.validators.contains(&signer),
+            Error::<T>::UnauthorizedSigner
+        );
+        ensure!(
+            !headers
+                .iter()
+                .rev()
+                .chain(sp_std::iter::once(&client.finalized))
+                .take(client.validators.len() / 2)
+                .any(|h| h.signer == signer),
+            Error::<T>::SignerRecentlySigned
+        );
+
+        let checkpoint = if header.number % client.epoch_length == 0 {
+            Some(header.validators().ok_or(Error::<T>::InvalidHeader)?)
+        } else {
+            None
+        };
+        headers.push(ImportedHeader {
+            number: header.number,
+            hash: header.hash,
+            receipts_root: header.receipts_root,
+            timestamp: header.timestamp,
+            signer,
+            validators: checkpoint,
+        });
+        Self::deposit_event(Event::<T>::HeaderImported(
+            chain_id,
+            header.number,
+            header.hash,
+        ));
+
+        let threshold = client.validators.len() / 2 + 1;
+        let mut signers = Vec::new();
+        let finalized = headers.iter().rposition(|h| {
+            // headers imported before a rotation may be sealed by removed validators
+            if client.validators.contains(&h.signer) && !signers.contains(&h.signer) {
+                signers.push(h.signer);
+            }
+            signers.len() >= threshold
+        });
+        if let Some(index) = finalized {
+            for finalized in headers.drain(..=index) {
+                FinalizedReceiptsRoots::<T>::insert(
+                    chain_id,
+                    finalized.number,
+                    finalized.receipts_root,
+                );
+                if let Some(pruned) = finalized
+                    .number
+                    .checked_sub(T::MaxFinalizedReceiptsRoots::get().into())
+                {
+                    FinalizedReceiptsRoots::<T>::remove(chain_id, pruned);
+                }
+                if let Some(validators) = finalized.validators.clone() {
+                    client.validators = validators;
+                }
+                client.finalized = finalized;
+            }
+            Self::deposit_event(Event::<T>::HeaderFinalized(
+                chain_id,
+                client.finalized.number,
+                client.finalized.hash,
+            ));
+            LightClients::<T>::insert(chain_id, client);
+        }
+
+        ensure!(
+            headers.len() as u32 <= T::MaxUnfinalizedHeaders::get(),
+            Error::<T>::TooManyUnfinalizedHeaders
+        );
+        UnfinalizedHeaders::<T>::insert(chain_id, headers);
+
+        Ok(())
+    }
+
+    /// Verifies a teleport to this chain logged by the bridge contract of `src_id`
+    /// in a receipt of a finalized header
+    pub(crate) fn verify_teleport(
+        src_id: ChainId,
+        block_number: u64,
+        tx_index: u64,
+        receipt_proof: &[Vec<u8>],
+        log_index: u32,
+    ) -> Result<(ChainNonce, MaterializeCallOf<T>), DispatchError> {
+        let client = Self::light_client(src_id).ok_or(Error::<T>::LightClientNotInitialized)?;
+        let receipts_root = Self::finalized_receipts_root(src_id, block_number)
+            .ok_or(Error::<T>::HeaderNotFinalized)?;
+
+        let receipt = evm::verify_trie_proof(receipts_root, &rlp::encode(&tx_index), receipt_proof)
+            .ok_or(Error::<T>::InvalidReceiptProof)?;
+        let log = evm::decode_receipt_logs(&receipt)
+            .and_then(|logs| logs.into_iter().nth(log_index as usize))
+            .ok_or(Error::<T>::InvalidReceiptProof)?;
+        ensure!(
+            log.address == client.bridge_contract,
+            Error::<T>::InvalidTeleportLog
+        );
+
+        let teleport = TeleportLog::decode(&log).ok_or(Error::<T>::InvalidTeleportLog)?;
+        ensure!(
+            teleport.dest_id == T::ChainId::get(),
+            Error::<T>::InvalidTeleportLog
+        );
+        let to = T::AccountId::decode(&mut &teleport.to[..])
+            .map_err(|_| Error::<T>::InvalidTeleportLog)?;
+
+        Ok((
+            teleport.nonce,
+            MaterializeCall {
+                bridge_token_id: teleport.bridge_token_id,
+                to,
+                amount: teleport.amount,
+            },
+        ))
+    }
+}
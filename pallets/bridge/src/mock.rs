@@ -12,8 +12,14 @@ use frame_system::{self as system, EnsureRoot, EnsureSigned};
 use primitives::tokens::{HKO, KSM};
 use system::EnsureSignedBy;
 
-use crate::{self as bridge, ChainId, Config};
-use sp_core::H256;
+use crate::{
+    self as bridge,
+    evm::{EXTRA_VANITY, TELEPORT_EVENT},
+    ChainId, Config,
+};
+use rlp::RlpStream;
+use sp_core::{H160, H256};
+use sp_io::hashing::keccak_256;
 use sp_runtime::{
     testing::Header,
     traits::{IdentityLookup, Zero},
@@ -204,6 +210,8 @@ parameter_types! {
     pub const ProposalLifetime: BlockNumber = 50;
     pub const RootOperatorAccountId: AccountId = 7;
     pub const ThresholdPercentage: u32 = 80;
+    pub const MaxUnfinalizedHeaders: u32 = 8;
+    pub const MaxFinalizedReceiptsRoots: u32 = 4;
     pub GiftAccount: AccountId = PalletId(*b"par/gift").into_account_truncating();
 }
 
@@ -241,6 +249,8 @@ impl Config for Test {
     type ExistentialDeposit = ExistentialDeposit;
     type ProposalLifetime = ProposalLifetime;
     type ThresholdPercentage = ThresholdPercentage;
+    type MaxUnfinalizedHeaders = MaxUnfinalizedHeaders;
+    type MaxFinalizedReceiptsRoots = MaxFinalizedReceiptsRoots;
 
     type WeightInfo = ();
 }
@@ -344,3 +354,129 @@ pub fn dollar(d: u128) -> u128 {
 pub fn usdt(d: u128) -> u128 {
     d.saturating_mul(10_u128.pow(6))
 }
+
+// The EVM chain followed by the light client
+pub const BRIDGE_CONTRACT: H160 = H160([0x42; 20]);
+pub const EPOCH_LENGTH: u64 = 100;
+
+pub fn validator_key(index: u8) -> libsecp256k1::SecretKey {
+    libsecp256k1::SecretKey::parse(&[index + 1; 32]).unwrap()
+}
+
+pub fn validator(index: u8) -> H160 {
+    let public = libsecp256k1::PublicKey::from_secret_key(&validator_key(index));
+    H160::from_slice(&keccak_256(&public.serialize()[1..])[12..])
+}
+
+/// Builds a RLP encoded header sealed by the validator `signer`,
+/// `validators` are only listed by epoch checkpoint headers
+pub fn evm_header(
+    parent_hash: H256,
+    number: u64,
+    receipts_root: H256,
+    validators: &[H160],
+    signer: u8,
+) -> Vec<u8> {
+    let encode = |extra_data: &Vec<u8>| {
+        let mut stream = RlpStream::new_list(15);
+        stream
+            .append(&parent_hash.as_bytes().to_vec())
+            .append(&vec![0u8; 32])
+            .append(&vec![0u8; 20])
+            .append(&vec![0u8; 32])
+            .append(&vec![0u8; 32])
+            .append(&receipts_root.as_bytes().to_vec())
+            .append(&vec![0u8; 256])
+            .append(&2u64)
+            .append(&number)
+            .append(&30_000_000u64)
+            .append(&0u64)
+            .append(&(1_600_000_000 + number * 3))
+            .append(extra_data)
+            .append(&vec![0u8; 32])
+            .append(&vec![0u8; 8]);
+        stream.out().to_vec()
+    };
+
+    let mut extra_data = vec![0u8; EXTRA_VANITY];
+    validators
+        .iter()
+        .for_each(|v| extra_data.extend_from_slice(v.as_bytes()));
+    let seal_hash = libsecp256k1::Message::parse(&keccak_256(&encode(&extra_data)));
+    let (signature, recovery_id) = libsecp256k1::sign(&seal_hash, &validator_key(signer));
+    extra_data.extend_from_slice(&signature.serialize());
+    extra_data.push(recovery_id.serialize());
+
+    encode(&extra_data)
+}
+
+/// Builds a typed receipt with the teleport log of `contract`
+pub fn teleport_receipt(
+    contract: H160,
+    dest_id: ChainId,
+    nonce: ChainNonce,
+    bridge_token_id: CurrencyId,
+    to: AccountId,
+    amount: Balance,
+) -> Vec<u8> {
+    let word = |value: u128| {
+        let mut word = [0u8; 32];
+        word[16..].copy_from_slice(&value.to_be_bytes());
+        word
+    };
+    let mut recipient = [0u8; 32];
+    recipient[..16].copy_from_slice(&to.encode());
+    let data = [
+        word(dest_id.into()),
+        word(nonce.into()),
+        word(bridge_token_id.into()),
+        recipient,
+        word(amount),
+    ]
+    .concat();
+
+    let mut log = RlpStream::new_list(3);
+    log.append(&contract.as_bytes().to_vec());
+    log.begin_list(1)
+        .append(&keccak_256(TELEPORT_EVENT).to_vec());
+    log.append(&data);
+
+    let mut receipt = RlpStream::new_list(4);
+    receipt
+        .append(&1u8)
+        .append(&21_000u64)
+        .append(&vec![0u8; 256]);
+    receipt.begin_list(1).append_raw(&log.out(), 1);
+
+    [vec![2u8], receipt.out().to_vec()].concat()
+}
+
+/// Builds the receipts trie of a block with two transactions,
+/// returns its root and the proofs of both receipts
+pub fn receipts_trie(first: Vec<u8>, second: Vec<u8>) -> (H256, Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    // The keys rlp(0) = 0x80 and rlp(1) = 0x01 diverge on their first nibble,
+    // so both receipts are leaves of the root branch
+    let leaf = |path: u8, receipt: Vec<u8>| {
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&vec![path]).append(&receipt);
+        stream.out().to_vec()
+    };
+    let first_leaf = leaf(0x30, first);
+    let second_leaf = leaf(0x31, second);
+
+    let mut branch = RlpStream::new_list(17);
+    for index in 0..17 {
+        match index {
+            0 => branch.append(&keccak_256(&second_leaf).to_vec()),
+            8 => branch.append(&keccak_256(&first_leaf).to_vec()),
+            _ => branch.append_empty_data(),
+        };
+    }
+    let branch = branch.out().to_vec();
+
+    (
+        H256(keccak_256(&branch)),
+        vec![branch.clone(), first_leaf],
+        vec![branch, second_leaf],
+    )
+}
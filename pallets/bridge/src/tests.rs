@@ -1039,3 +1039,405 @@ fn merge_overlapping_intervals_works() {
         vec![(1, 1), (3, 3), (5, 7)],
     );
 }
+
+fn initialize_light_client(validators: &[H160]) -> H256 {
+    let genesis = evm_header(
+        H256::zero(),
+        0,
+        H256::zero(),
+        validators,
+        validators.len() as u8 - 1,
+    );
+    assert_ok!(Bridge::initialize_light_client(
+        RuntimeOrigin::root(),
+        ETH,
+        genesis.clone(),
+        validators.to_vec(),
+        BRIDGE_CONTRACT,
+        EPOCH_LENGTH,
+    ));
+
+    H256(sp_io::hashing::keccak_256(&genesis))
+}
+
+fn header_hash(header: &[u8]) -> H256 {
+    H256(sp_io::hashing::keccak_256(header))
+}
+
+#[test]
+fn light_client_finalizes_headers_works() {
+    new_test_ext().execute_with(|| {
+        let validators = vec![validator(0), validator(1), validator(2)];
+        let genesis = evm_header(H256::zero(), 0, H256::zero(), &validators, 0);
+        assert_noop!(
+            Bridge::initialize_light_client(
+                RuntimeOrigin::root(),
+                BNB,
+                genesis,
+                validators.clone(),
+                BRIDGE_CONTRACT,
+                EPOCH_LENGTH,
+            ),
+            Error::<Test>::ChainIdNotRegistered,
+        );
+        let genesis_hash = initialize_light_client(&validators);
+
+        // Headers are sealed by validators who didn't seal the last header
+        let first = evm_header(genesis_hash, 1, H256::repeat_byte(1), &[], 0);
+        let second = evm_header(header_hash(&first), 2, H256::repeat_byte(2), &[], 1);
+        assert_noop!(
+            Bridge::submit_headers(
+                RuntimeOrigin::signed(EVE),
+                ETH,
+                vec![evm_header(genesis_hash, 1, H256::zero(), &[], 3)],
+            ),
+            Error::<Test>::UnauthorizedSigner,
+        );
+        assert_noop!(
+            Bridge::submit_headers(
+                RuntimeOrigin::signed(EVE),
+                ETH,
+                vec![evm_header(H256::zero(), 1, H256::zero(), &[], 0)],
+            ),
+            Error::<Test>::UnknownParentHeader,
+        );
+        assert_noop!(
+            Bridge::submit_headers(
+                RuntimeOrigin::signed(EVE),
+                ETH,
+                vec![evm_header(genesis_hash, 2, H256::zero(), &[], 0)],
+            ),
+            Error::<Test>::InvalidHeader,
+        );
+        assert_noop!(
+            Bridge::submit_headers(
+                RuntimeOrigin::signed(EVE),
+                ETH,
+                vec![
+                    first.clone(),
+                    evm_header(header_hash(&first), 2, H256::zero(), &[], 0)
+                ],
+            ),
+            Error::<Test>::SignerRecentlySigned,
+        );
+
+        // The first header is not final until the second validator seals the next one
+        assert_ok!(Bridge::submit_headers(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            vec![first.clone()],
+        ));
+        assert_eq!(Bridge::finalized_receipts_root(ETH, 1), None);
+        assert_eq!(Bridge::unfinalized_headers(ETH).len(), 1);
+
+        assert_ok!(Bridge::submit_headers(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            vec![second.clone()],
+        ));
+        assert_eq!(
+            Bridge::finalized_receipts_root(ETH, 1),
+            Some(H256::repeat_byte(1))
+        );
+        assert_eq!(Bridge::finalized_receipts_root(ETH, 2), None);
+        assert_eq!(
+            Bridge::light_client(ETH).unwrap().finalized.hash,
+            header_hash(&first)
+        );
+        assert_eq!(Bridge::unfinalized_headers(ETH).len(), 1);
+        assert_events(vec![
+            mock::RuntimeEvent::Bridge(Event::HeaderImported(ETH, 2, header_hash(&second))),
+            mock::RuntimeEvent::Bridge(Event::HeaderFinalized(ETH, 1, header_hash(&first))),
+        ]);
+
+        // A competing header replaces the unfinalized one
+        let fork = evm_header(header_hash(&first), 2, H256::repeat_byte(3), &[], 2);
+        assert_ok!(Bridge::submit_headers(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            vec![fork.clone()],
+        ));
+        assert_eq!(Bridge::finalized_receipts_root(ETH, 2), None);
+        assert_eq!(
+            Bridge::unfinalized_headers(ETH)
+                .iter()
+                .map(|h| h.hash)
+                .collect::<Vec<_>>(),
+            vec![header_hash(&fork)]
+        );
+    });
+}
+
+#[test]
+fn materialize_with_proof_works() {
+    new_test_ext().execute_with(|| {
+        Bridge::teleport(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            EHKO,
+            "TELE".into(),
+            dollar(50),
+        )
+        .unwrap();
+
+        let validators = vec![validator(0), validator(1), validator(2)];
+        let genesis_hash = initialize_light_client(&validators);
+        let (receipts_root, first_proof, second_proof) = receipts_trie(
+            teleport_receipt(BRIDGE_CONTRACT, 0, 0, EHKO, EVE, dollar(10)),
+            teleport_receipt(BRIDGE_CONTRACT, 0, 1, EHKO, EVE, dollar(5)),
+        );
+        let first = evm_header(genesis_hash, 1, receipts_root, &[], 0);
+        assert_ok!(Bridge::submit_headers(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            vec![first.clone()],
+        ));
+
+        // The header including the teleports must be finalized
+        assert_noop!(
+            Bridge::materialize_with_proof(
+                RuntimeOrigin::signed(DAVE),
+                ETH,
+                1,
+                0,
+                first_proof.clone(),
+                0,
+            ),
+            Error::<Test>::HeaderNotFinalized,
+        );
+        assert_ok!(Bridge::submit_headers(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            vec![evm_header(header_hash(&first), 2, H256::zero(), &[], 1)],
+        ));
+
+        assert_noop!(
+            Bridge::materialize_with_proof(
+                RuntimeOrigin::signed(DAVE),
+                ETH,
+                1,
+                1,
+                first_proof.clone(),
+                0,
+            ),
+            Error::<Test>::InvalidReceiptProof,
+        );
+        assert_noop!(
+            Bridge::materialize_with_proof(
+                RuntimeOrigin::signed(DAVE),
+                ETH,
+                1,
+                0,
+                first_proof.clone(),
+                1,
+            ),
+            Error::<Test>::InvalidReceiptProof,
+        );
+
+        // Anyone can materialize a proved teleport without the votes of the relay members
+        assert_ok!(Bridge::materialize_with_proof(
+            RuntimeOrigin::signed(DAVE),
+            ETH,
+            1,
+            0,
+            first_proof.clone(),
+            0,
+        ));
+        assert_eq!(<Test as Config>::Assets::balance(HKO, &EVE), dollar(60));
+        assert_events(vec![
+            mock::RuntimeEvent::Bridge(Event::MaterializeMinted(ETH, 0, EHKO, EVE, dollar(10))),
+            mock::RuntimeEvent::Bridge(Event::MaterializeProved(DAVE, ETH, 0, 1)),
+        ]);
+
+        // A teleport cannot be materialized twice, neither by proof nor by votes
+        assert_noop!(
+            Bridge::materialize_with_proof(RuntimeOrigin::signed(DAVE), ETH, 1, 0, first_proof, 0,),
+            Error::<Test>::ProposalAlreadyComplete,
+        );
+        assert_noop!(
+            Bridge::materialize(
+                RuntimeOrigin::signed(ALICE),
+                ETH,
+                0,
+                EHKO,
+                EVE,
+                dollar(10),
+                true
+            ),
+            Error::<Test>::ProposalAlreadyComplete,
+        );
+
+        assert_ok!(Bridge::materialize_with_proof(
+            RuntimeOrigin::signed(DAVE),
+            ETH,
+            1,
+            1,
+            second_proof,
+            0,
+        ));
+        assert_eq!(<Test as Config>::Assets::balance(HKO, &EVE), dollar(65));
+        assert_eq!(Bridge::bridge_registry(ETH), Some(vec![(0, 1)]));
+    });
+}
+
+#[test]
+fn materialize_with_proof_rejects_foreign_teleports() {
+    new_test_ext().execute_with(|| {
+        let validators = vec![validator(0)];
+        let genesis_hash = initialize_light_client(&validators);
+
+        // Teleports logged by another contract or to another chain
+        let (receipts_root, first_proof, second_proof) = receipts_trie(
+            teleport_receipt(H160::repeat_byte(1), 0, 0, EHKO, EVE, dollar(10)),
+            teleport_receipt(BRIDGE_CONTRACT, 1, 0, EHKO, EVE, dollar(10)),
+        );
+        assert_ok!(Bridge::submit_headers(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            vec![evm_header(genesis_hash, 1, receipts_root, &[], 0)],
+        ));
+        assert_eq!(Bridge::finalized_receipts_root(ETH, 1), Some(receipts_root));
+
+        assert_noop!(
+            Bridge::materialize_with_proof(RuntimeOrigin::signed(DAVE), ETH, 1, 0, first_proof, 0),
+            Error::<Test>::InvalidTeleportLog,
+        );
+        assert_noop!(
+            Bridge::materialize_with_proof(RuntimeOrigin::signed(DAVE), ETH, 1, 1, second_proof, 0),
+            Error::<Test>::InvalidTeleportLog,
+        );
+    });
+}
+
+#[test]
+fn light_client_rotates_validators_once_checkpoint_is_finalized() {
+    new_test_ext().execute_with(|| {
+        let validators = vec![validator(0), validator(1), validator(2)];
+        let genesis = evm_header(H256::zero(), 0, H256::zero(), &validators, 2);
+        assert_ok!(Bridge::initialize_light_client(
+            RuntimeOrigin::root(),
+            ETH,
+            genesis.clone(),
+            validators.clone(),
+            BRIDGE_CONTRACT,
+            2,
+        ));
+
+        // A validator seals a checkpoint listing only itself
+        let first = evm_header(header_hash(&genesis), 1, H256::zero(), &[], 0);
+        let forged = evm_header(header_hash(&first), 2, H256::zero(), &[validator(1)], 1);
+        assert_ok!(Bridge::submit_headers(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            vec![first.clone(), forged.clone()],
+        ));
+
+        // The unfinalized checkpoint doesn't change the validators, the forger can't
+        // seal the next header alone
+        assert_noop!(
+            Bridge::submit_headers(
+                RuntimeOrigin::signed(EVE),
+                ETH,
+                vec![evm_header(header_hash(&forged), 3, H256::zero(), &[], 1)],
+            ),
+            Error::<Test>::SignerRecentlySigned,
+        );
+        assert_eq!(Bridge::finalized_receipts_root(ETH, 2), None);
+        assert_eq!(Bridge::light_client(ETH).unwrap().validators, validators);
+
+        // The other validators finalize a checkpoint on another branch
+        let second = evm_header(header_hash(&first), 2, H256::zero(), &validators, 2);
+        let third = evm_header(header_hash(&second), 3, H256::zero(), &[], 0);
+        assert_ok!(Bridge::submit_headers(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            vec![second.clone(), third.clone()],
+        ));
+        assert_eq!(
+            Bridge::light_client(ETH).unwrap().finalized.hash,
+            header_hash(&second)
+        );
+
+        // A new validator can only seal headers once the rotation is finalized
+        let rotated = vec![validator(0), validator(1), validator(3)];
+        let fourth = evm_header(header_hash(&third), 4, H256::zero(), &rotated, 1);
+        assert_ok!(Bridge::submit_headers(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            vec![fourth.clone()],
+        ));
+        assert_noop!(
+            Bridge::submit_headers(
+                RuntimeOrigin::signed(EVE),
+                ETH,
+                vec![evm_header(header_hash(&fourth), 5, H256::zero(), &[], 3)],
+            ),
+            Error::<Test>::UnauthorizedSigner,
+        );
+
+        let fifth = evm_header(header_hash(&fourth), 5, H256::zero(), &[], 2);
+        assert_ok!(Bridge::submit_headers(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            vec![fifth.clone()],
+        ));
+        assert_eq!(Bridge::light_client(ETH).unwrap().finalized.number, 4);
+        assert_eq!(Bridge::light_client(ETH).unwrap().validators, rotated);
+
+        // The removed validator doesn't count towards the finality anymore
+        let sixth = evm_header(header_hash(&fifth), 6, H256::zero(), &[], 3);
+        assert_ok!(Bridge::submit_headers(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            vec![sixth.clone()],
+        ));
+        assert_eq!(Bridge::light_client(ETH).unwrap().finalized.number, 4);
+        assert_ok!(Bridge::submit_headers(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            vec![evm_header(header_hash(&sixth), 7, H256::zero(), &[], 0)],
+        ));
+        assert_eq!(Bridge::light_client(ETH).unwrap().finalized.number, 6);
+    });
+}
+
+#[test]
+fn light_client_prunes_finalized_receipts_roots() {
+    new_test_ext().execute_with(|| {
+        let validators = vec![validator(0), validator(1), validator(2)];
+        let mut parent_hash = initialize_light_client(&validators);
+        let headers = (1..=6)
+            .map(|number| {
+                let header = evm_header(
+                    parent_hash,
+                    number,
+                    H256::repeat_byte(number as u8),
+                    &[],
+                    number as u8 % 3,
+                );
+                parent_hash = header_hash(&header);
+                header
+            })
+            .collect::<Vec<_>>();
+        assert_ok!(Bridge::submit_headers(
+            RuntimeOrigin::signed(EVE),
+            ETH,
+            headers
+        ));
+
+        // Only the receipts roots of the last `MaxFinalizedReceiptsRoots` headers are kept
+        assert_eq!(Bridge::light_client(ETH).unwrap().finalized.number, 5);
+        assert_eq!(Bridge::finalized_receipts_root(ETH, 0), None);
+        assert_eq!(Bridge::finalized_receipts_root(ETH, 1), None);
+        for number in 2..=5 {
+            assert_eq!(
+                Bridge::finalized_receipts_root(ETH, number),
+                Some(H256::repeat_byte(number as u8))
+            );
+        }
+
+        assert_ok!(Bridge::unregister_chain(RuntimeOrigin::root(), ETH));
+        assert_eq!(Bridge::light_client(ETH), None);
+        assert_eq!(FinalizedReceiptsRoots::<Test>::iter_prefix(ETH).count(), 0);
+    });
+}
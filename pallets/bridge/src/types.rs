@@ -2,6 +2,7 @@ use codec::{Decode, Encode};
 use frame_support::RuntimeDebug;
 use primitives::{Balance, CurrencyId};
 use scale_info::TypeInfo;
+use sp_core::{H160, H256};
 use sp_std::{vec, vec::Vec};
 
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, Default)]
@@ -92,3 +93,28 @@ impl<T, E: Default> Default for Proposal<T, E> {
         }
     }
 }
+
+/// A header of an EVM chain imported by the light client
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct ImportedHeader {
+    pub number: u64,
+    pub hash: H256,
+    pub receipts_root: H256,
+    pub timestamp: u64,
+    pub signer: H160,
+    /// The validators listed by an epoch checkpoint header
+    pub validators: Option<Vec<H160>>,
+}
+
+/// The light client of an EVM chain whose headers are sealed by a set of validators
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct LightClient {
+    /// The bridge contract whose teleport logs can be materialized
+    pub bridge_contract: H160,
+    /// The number of blocks between two epoch checkpoint headers
+    pub epoch_length: u64,
+    /// The validators as of the finalized header
+    pub validators: Vec<H160>,
+    /// The latest finalized header
+    pub finalized: ImportedHeader,
+}
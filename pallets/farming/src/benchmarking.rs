@@ -14,6 +14,7 @@ use sp_std::prelude::*;
 
 const ASSET: CurrencyId = HKO;
 const REWARD_ASSET: CurrencyId = HKO;
+const EXTRA_REWARD_ASSET: CurrencyId = KSM;
 const ISSUE_AMOUNT: u128 = 4_000_000_000_000_000;
 const STAKING_AMOUNT: u128 = 2_000_000_000_000_000;
 const REWARD_AMOUNT: u128 = 2_000_000_000_000_000;
@@ -41,6 +42,15 @@ fn initial_set_up<T: Config + pallet_assets::Config<AssetId = CurrencyId, Balanc
     pallet_assets::Pallet::<T>::force_create(
         SystemOrigin::Root.into(),
         REWARD_ASSET.into(),
+        account_id.clone(),
+        true,
+        One::one(),
+    )
+    .ok();
+
+    pallet_assets::Pallet::<T>::force_create(
+        SystemOrigin::Root.into(),
+        EXTRA_REWARD_ASSET.into(),
         account_id,
         true,
         One::one(),
@@ -49,6 +59,7 @@ fn initial_set_up<T: Config + pallet_assets::Config<AssetId = CurrencyId, Balanc
 
     T::Assets::mint_into(ASSET, &caller, ISSUE_AMOUNT).ok();
     T::Assets::mint_into(REWARD_ASSET, &caller, ISSUE_AMOUNT).ok();
+    T::Assets::mint_into(EXTRA_REWARD_ASSET, &caller, ISSUE_AMOUNT).ok();
 
     Farming::<T>::create(
         SystemOrigin::Root.into(),
@@ -158,6 +169,40 @@ benchmarks! {
     verify {
         assert_last_event::<T>(Event::RewardAdded(ASSET, REWARD_ASSET, T::BlockNumber::from(7200u32), REWARD_AMOUNT).into());
     }
+
+    add_extra_reward_asset {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T>(caller);
+    }: _(SystemOrigin::Root, ASSET, REWARD_ASSET, T::BlockNumber::from(7200u32), EXTRA_REWARD_ASSET)
+    verify {
+        assert_last_event::<T>(Event::ExtraRewardAssetAdded(ASSET, REWARD_ASSET, T::BlockNumber::from(7200u32), EXTRA_REWARD_ASSET).into());
+    }
+
+    dispatch_extra_reward {
+        let caller: T::AccountId = whitelisted_caller();
+        let payer = T::Lookup::unlookup(caller.clone());
+        initial_set_up::<T>(caller);
+        assert_ok!(Farming::<T>::add_extra_reward_asset(
+            SystemOrigin::Root.into(),
+            ASSET,
+            REWARD_ASSET,
+            T::BlockNumber::from(7200u32),
+            EXTRA_REWARD_ASSET)
+        );
+    }: _(SystemOrigin::Root, ASSET, REWARD_ASSET, T::BlockNumber::from(7200u32), EXTRA_REWARD_ASSET, payer, REWARD_AMOUNT, T::BlockNumber::from(10u32))
+    verify {
+        assert_last_event::<T>(Event::ExtraRewardAdded(ASSET, REWARD_ASSET, T::BlockNumber::from(7200u32), EXTRA_REWARD_ASSET, REWARD_AMOUNT).into());
+    }
+
+    update_boost {
+        let caller: T::AccountId = whitelisted_caller();
+        let who = T::Lookup::unlookup(caller.clone());
+        initial_set_up::<T>(caller.clone());
+        assert_ok!(Farming::<T>::deposit(SystemOrigin::Signed(caller.clone()).into(), ASSET, REWARD_ASSET, T::BlockNumber::from(7200u32), STAKING_AMOUNT));
+    }: _(SystemOrigin::Signed(caller.clone()), ASSET, REWARD_ASSET, T::BlockNumber::from(7200u32), who)
+    verify {
+        assert_last_event::<T>(Event::BoostUpdated(caller, ASSET, REWARD_ASSET, T::BlockNumber::from(7200u32), STAKING_AMOUNT).into());
+    }
 }
 
 impl_benchmark_test_suite!(Farming, crate::mock::new_test_ext(), crate::mock::Test,);
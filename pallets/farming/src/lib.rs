@@ -15,6 +15,22 @@
 //! # Pure Farming (FAR)
 //!
 //! pallet-farming is in charge of creating a governance-controlled incentivization program for our different products.
+//!
+//! Besides its reward asset, a pool can pay extra reward assets so partner projects can
+//! co-incentivise it. Each extra reward asset runs its own reward stream and `claim` pays
+//! all of them at once.
+//!
+//! Pools staking AMM liquidity can have auto-compounding vaults. A vault claims the pool
+//! rewards on a schedule, swaps them through the router, adds them back as liquidity and
+//! deposits it in the pool again. The harvest trades must get at least their worth at the
+//! oracle prices less `MaxHarvestSlippage`. Vault shares are tracked by a vault token, whose
+//! exchange rate to the liquidity token is exposed through `LPVaultTokenExchangeRateProvider`
+//! so that prices can value vault tokens as loans collateral.
+//!
+//! Rewards are shared pro-rata on working balances rather than deposits. The working
+//! balance of a deposit is boosted by the vote escrow voting power of its owner, up to
+//! `MaxBoost` times the deposit, and `dispatch_gauge_rewards` splits rewards across pools
+//! by the votes of their gauges.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -29,7 +45,10 @@ mod types;
 pub mod weights;
 
 use frame_support::{
+    log,
     pallet_prelude::*,
+    require_transactional,
+    storage::with_transaction,
     traits::{
         fungibles::{Inspect, Mutate, Transfer},
         Get, IsType,
@@ -38,19 +57,22 @@ use frame_support::{
 };
 use frame_system::{ensure_signed, pallet_prelude::OriginFor};
 use num_traits::{cast::ToPrimitive, CheckedDiv, CheckedMul};
-use pallet_traits::{ConvertToBigUint, DecimalProvider};
-use primitives::{Balance, CurrencyId};
+use pallet_traits::{
+    ConvertToBigUint, DecimalProvider, GaugeController, LPVaultTokenCurrenciesFilter,
+    LPVaultTokenExchangeRateProvider, PriceFeeder, Router, VotingEscrow, AMM,
+};
+use primitives::{Balance, CurrencyId, Rate, Ratio};
 use sp_io::hashing::blake2_256;
 use sp_runtime::{
     traits::{
-        AccountIdConversion, CheckedAdd, CheckedSub, SaturatedConversion, Saturating, StaticLookup,
-        Zero,
+        AccountIdConversion, CheckedAdd, CheckedSub, One, SaturatedConversion, Saturating,
+        StaticLookup, Zero,
     },
-    ArithmeticError,
+    ArithmeticError, FixedPointNumber, PerThing, TransactionOutcome,
 };
-use sp_std::result::Result;
+use sp_std::{result::Result, vec, vec::Vec};
 
-use crate::types::{PoolInfo, UserPosition};
+use crate::types::{PoolInfo, RewardInfo, RewardPosition, UserPosition, VaultInfo};
 pub use pallet::*;
 pub use weights::WeightInfo;
 
@@ -98,6 +120,43 @@ pub mod pallet {
         /// Specifies upper limit of cool down duration for pool
         #[pallet::constant]
         type CoolDownMaxDuration: Get<Self::BlockNumber>;
+
+        /// AMM pools which vaults provide liquidity to
+        type AMM: AMM<Self::AccountId, CurrencyId, Balance, Self::BlockNumber>;
+
+        /// Router which vaults swap their rewards through
+        type Router: Router<Self::AccountId, CurrencyId, Balance>;
+
+        /// Specifies max amount of vaults checked in `on_idle` of each block
+        #[pallet::constant]
+        type MaxVaultHarvestsPerBlock: Get<u32>;
+
+        /// Oracle prices which the vault harvest trades are checked against
+        type PriceFeeder: PriceFeeder;
+
+        /// Specifies max slippage of the vault harvest trades from the oracle prices
+        #[pallet::constant]
+        type MaxHarvestSlippage: Get<Ratio>;
+
+        /// Vault tokens locked forever in the vault on its first deposit, so that
+        /// their price can't be inflated against later depositors
+        #[pallet::constant]
+        type MinimumVaultShares: Get<BalanceOf<Self>>;
+
+        /// Specifies max amount of extra reward assets for a pool
+        #[pallet::constant]
+        type MaxExtraRewardAssets: Get<u32>;
+
+        /// Vote escrow whose voting power boosts the working balance of deposits
+        type VotingEscrow: VotingEscrow<Self::AccountId, Balance>;
+
+        /// Gauges of pools, keyed by staking asset, reward asset and lock duration,
+        /// splitting the rewards of `dispatch_gauge_rewards`
+        type GaugeController: GaugeController<(CurrencyId, CurrencyId, Self::BlockNumber)>;
+
+        /// Specifies max working balance of a deposit as a multiple of the deposit
+        #[pallet::constant]
+        type MaxBoost: Get<Rate>;
     }
 
     #[pallet::error]
@@ -130,6 +189,26 @@ pub mod pallet {
         ExcessMaxUserLockItemsCount,
         /// Last reward is not finish
         RewardNotFinish,
+        /// Vault does not exist
+        VaultDoesNotExist,
+        /// Vault token is already a vault or has been issued
+        VaultAlreadyExists,
+        /// Vault staking asset is not an AMM liquidity token
+        NotALiquidityToken,
+        /// Vault pool must not have a cool down duration
+        PoolHasCoolDownDuration,
+        /// Vault harvest interval has not elapsed yet
+        VaultNotReadyToHarvest,
+        /// Oracle price of a vault harvest asset is not ready
+        PriceNotReady,
+        /// Extra reward asset does not exist
+        ExtraRewardAssetDoesNotExist,
+        /// Extra reward asset is already a reward asset of the pool
+        ExtraRewardAssetAlreadyExists,
+        /// Excess max extra reward assets count for pool
+        ExcessMaxExtraRewardAssetsCount,
+        /// No pool of the reward asset has gauge votes
+        NoGaugeWeights,
     }
 
     #[pallet::event]
@@ -177,6 +256,47 @@ pub mod pallet {
         ),
         /// Reward added
         RewardAdded(AssetIdOf<T>, AssetIdOf<T>, T::BlockNumber, BalanceOf<T>),
+        /// Add new vault of a pool
+        VaultAdded(
+            AssetIdOf<T>,
+            AssetIdOf<T>,
+            AssetIdOf<T>,
+            T::BlockNumber,
+            T::BlockNumber,
+        ),
+        /// Deposited Assets in vault, vault tokens were minted
+        VaultDeposited(T::AccountId, AssetIdOf<T>, BalanceOf<T>, BalanceOf<T>),
+        /// Withdrew Assets from vault, vault tokens were burned
+        VaultWithdrew(T::AccountId, AssetIdOf<T>, BalanceOf<T>, BalanceOf<T>),
+        /// Vault rewards were compounded into its pool deposit
+        VaultHarvested(AssetIdOf<T>, BalanceOf<T>),
+        /// Add new extra reward asset of a pool
+        ExtraRewardAssetAdded(AssetIdOf<T>, AssetIdOf<T>, T::BlockNumber, AssetIdOf<T>),
+        /// Extra reward added
+        ExtraRewardAdded(
+            AssetIdOf<T>,
+            AssetIdOf<T>,
+            T::BlockNumber,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+        ),
+        /// Extra reward paid for user
+        ExtraRewardPaid(
+            T::AccountId,
+            AssetIdOf<T>,
+            AssetIdOf<T>,
+            T::BlockNumber,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+        ),
+        /// Working balance of user was updated
+        BoostUpdated(
+            T::AccountId,
+            AssetIdOf<T>,
+            AssetIdOf<T>,
+            T::BlockNumber,
+            BalanceOf<T>,
+        ),
     }
 
     #[pallet::pallet]
@@ -216,6 +336,135 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Extra reward assets of a pool, they are paid alongside the pool reward asset
+    #[pallet::storage]
+    #[pallet::getter(fn extra_reward_assets)]
+    pub type ExtraRewardAssets<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, T::BlockNumber>,
+        ),
+        BoundedVec<AssetIdOf<T>, T::MaxExtraRewardAssets>,
+        ValueQuery,
+    >;
+
+    /// Reward stream of an extra reward asset of a pool
+    #[pallet::storage]
+    #[pallet::getter(fn extra_rewards)]
+    pub type ExtraRewards<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, T::BlockNumber>,
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+        ),
+        RewardInfo<T::BlockNumber, BalanceOf<T>>,
+        ValueQuery,
+    >;
+
+    /// User position in the reward stream of an extra reward asset of a pool
+    #[pallet::storage]
+    #[pallet::getter(fn extra_reward_positions)]
+    pub type ExtraRewardPositions<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, T::BlockNumber>,
+            NMapKey<Blake2_128Concat, T::AccountId>,
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+        ),
+        RewardPosition<BalanceOf<T>>,
+        ValueQuery,
+    >;
+
+    /// Total working balance of a pool, pool rewards are shared pro-rata on working
+    /// balances. Defaults to the pool total deposit until a user working balance is set
+    #[pallet::storage]
+    #[pallet::getter(fn working_supply)]
+    pub type WorkingSupply<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, T::BlockNumber>,
+        ),
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
+    /// User working balance in pool, the deposit boosted by the user voting power.
+    /// Defaults to the user deposit balance
+    #[pallet::storage]
+    #[pallet::getter(fn working_balances)]
+    pub type WorkingBalances<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, T::BlockNumber>,
+            NMapKey<Blake2_128Concat, T::AccountId>,
+        ),
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
+    /// Auto-compounding vaults, keyed by the vault token tracking the shares
+    #[pallet::storage]
+    #[pallet::getter(fn vaults)]
+    pub type Vaults<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        VaultInfo<AssetIdOf<T>, T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// Raw key of the last vault checked in `on_idle`
+    #[pallet::storage]
+    #[pallet::getter(fn harvest_cursor)]
+    pub type HarvestCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_idle(block_number: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            let mut weight = T::DbWeight::get().reads_writes(1, 1);
+            if weight.any_gt(remaining_weight) {
+                return Weight::zero();
+            }
+
+            let per_vault = Self::harvest_weight();
+            let mut vaults = match Self::harvest_cursor() {
+                Some(cursor) => Vaults::<T>::iter_from(cursor),
+                None => Vaults::<T>::iter(),
+            };
+            for _ in 0..T::MaxVaultHarvestsPerBlock::get() {
+                if weight.saturating_add(per_vault).any_gt(remaining_weight) {
+                    break;
+                }
+                weight.saturating_accrue(per_vault);
+
+                match vaults.next() {
+                    Some((vault_token, vault)) => {
+                        if block_number >= vault.next_harvest() {
+                            Self::try_harvest(vault_token, vault);
+                        }
+                    }
+                    None => {
+                        HarvestCursor::<T>::kill();
+                        return weight;
+                    }
+                }
+            }
+            HarvestCursor::<T>::put(vaults.last_raw_key().to_vec());
+
+            weight
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Create new pool from a privileged origin. Pool can be identified by a pair of asset and reward_asset.
@@ -400,7 +649,11 @@ pub mod pallet {
         /// - `lock_duration`: Lock block number after Deposit.
         /// - `amount`: the amount of staking asset want to deposit.
         #[pallet::call_index(4)]
-        #[pallet::weight(T::WeightInfo::deposit())]
+        #[pallet::weight(
+            T::WeightInfo::deposit()
+                .saturating_add(Pallet::<T>::update_extra_rewards_weight())
+                .saturating_add(Pallet::<T>::update_working_balance_weight())
+        )]
         #[transactional]
         pub fn deposit(
             origin: OriginFor<T>,
@@ -416,44 +669,7 @@ pub mod pallet {
             );
             ensure!(!amount.is_zero(), Error::<T>::NotAValidAmount);
 
-            Self::update_reward(Some(who.clone()), asset, reward_asset, lock_duration)?;
-
-            let asset_pool_account = Self::pool_account_id(asset)?;
-            Pools::<T>::mutate(
-                (asset, reward_asset, lock_duration),
-                |pool_info| -> DispatchResult {
-                    let pool_info = pool_info.as_mut().ok_or(Error::<T>::PoolDoesNotExist)?;
-
-                    ensure!(pool_info.is_active, Error::<T>::PoolIsNotActive);
-
-                    T::Assets::transfer(asset, &who, &asset_pool_account, amount, false)?;
-
-                    pool_info.total_deposited = pool_info
-                        .total_deposited
-                        .checked_add(amount)
-                        .ok_or(ArithmeticError::Overflow)?;
-
-                    Positions::<T>::mutate(
-                        (&asset, &reward_asset, &lock_duration, &who),
-                        |user_position| -> DispatchResult {
-                            user_position.deposit_balance = user_position
-                                .deposit_balance
-                                .checked_add(amount)
-                                .ok_or(ArithmeticError::Overflow)?;
-                            Ok(())
-                        },
-                    )?;
-
-                    Self::deposit_event(Event::<T>::AssetsDeposited(
-                        who,
-                        asset,
-                        reward_asset,
-                        lock_duration,
-                        amount,
-                    ));
-                    Ok(())
-                },
-            )
+            Self::do_deposit(&who, asset, reward_asset, lock_duration, amount)
         }
 
         /// Withdrawing Assets from reward Pool
@@ -465,7 +681,11 @@ pub mod pallet {
         /// - `lock_duration`: Lock block number after Deposit.
         /// - `amount`: the amount of staking asset want to withdraw.
         #[pallet::call_index(5)]
-        #[pallet::weight(T::WeightInfo::withdraw())]
+        #[pallet::weight(
+            T::WeightInfo::withdraw()
+                .saturating_add(Pallet::<T>::update_extra_rewards_weight())
+                .saturating_add(Pallet::<T>::update_working_balance_weight())
+        )]
         #[transactional]
         pub fn withdraw(
             origin: OriginFor<T>,
@@ -481,68 +701,7 @@ pub mod pallet {
             );
             ensure!(!amount.is_zero(), Error::<T>::NotAValidAmount);
 
-            let user_position = Positions::<T>::get((&asset, &reward_asset, &lock_duration, &who));
-            ensure!(
-                user_position.deposit_balance >= amount,
-                Error::<T>::DepositBalanceLow
-            );
-
-            Self::update_reward(Some(who.clone()), asset, reward_asset, lock_duration)?;
-
-            Pools::<T>::mutate(
-                (asset, reward_asset, lock_duration),
-                |pool_info| -> DispatchResult {
-                    let pool_info = pool_info.as_mut().ok_or(Error::<T>::PoolDoesNotExist)?;
-
-                    let current_block_number = <frame_system::Pallet<T>>::block_number();
-                    ensure!(
-                        current_block_number >= pool_info.unlock_height,
-                        Error::<T>::PoolUnderLock
-                    );
-
-                    pool_info.total_deposited = pool_info
-                        .total_deposited
-                        .checked_sub(amount)
-                        .ok_or(ArithmeticError::Overflow)?;
-
-                    Positions::<T>::mutate(
-                        (&asset, &reward_asset, &lock_duration, &who),
-                        |user_position| -> DispatchResult {
-                            user_position.deposit_balance = user_position
-                                .deposit_balance
-                                .checked_sub(amount)
-                                .ok_or(ArithmeticError::Overflow)?;
-
-                            if pool_info.cool_down_duration.is_zero() {
-                                let asset_pool_account = Self::pool_account_id(asset)?;
-                                T::Assets::transfer(
-                                    asset,
-                                    &asset_pool_account,
-                                    &who,
-                                    amount,
-                                    false,
-                                )?;
-                            } else {
-                                user_position
-                                    .lock_balance_items
-                                    .try_push((amount, current_block_number))
-                                    .map_err(|_| Error::<T>::ExcessMaxUserLockItemsCount)?;
-                            }
-
-                            Ok(())
-                        },
-                    )?;
-
-                    Self::deposit_event(Event::<T>::AssetsWithdrew(
-                        who,
-                        asset,
-                        reward_asset,
-                        lock_duration,
-                        amount,
-                    ));
-                    Ok(())
-                },
-            )
+            Self::do_withdraw(&who, asset, reward_asset, lock_duration, amount)
         }
 
         /// Redeem unlocked balance of staking asset from Pool
@@ -609,7 +768,11 @@ pub mod pallet {
         /// - `reward_asset`: The identifier of the reward asset.
         /// - `lock_duration`: Lock block number after Deposit.
         #[pallet::call_index(7)]
-        #[pallet::weight(T::WeightInfo::claim())]
+        #[pallet::weight(
+            T::WeightInfo::claim()
+                .saturating_mul(Pallet::<T>::reward_assets_count())
+                .saturating_add(Pallet::<T>::update_working_balance_weight())
+        )]
         #[transactional]
         pub fn claim(
             origin: OriginFor<T>,
@@ -624,34 +787,8 @@ pub mod pallet {
                 Error::<T>::PoolDoesNotExist
             );
 
-            Self::update_reward(Some(who.clone()), asset, reward_asset, lock_duration)?;
-
-            let asset_pool_account = Self::pool_account_id(reward_asset)?;
-            Positions::<T>::mutate(
-                (&asset, &reward_asset, &lock_duration, &who),
-                |user_position| -> DispatchResult {
-                    let reward_amount = user_position.reward_amount;
-                    if reward_amount > 0 {
-                        T::Assets::transfer(
-                            reward_asset,
-                            &asset_pool_account,
-                            &who,
-                            reward_amount,
-                            false,
-                        )?;
-                        user_position.reward_amount = 0;
-                    }
-
-                    Self::deposit_event(Event::<T>::RewardPaid(
-                        who.clone(),
-                        asset,
-                        reward_asset,
-                        lock_duration,
-                        reward_amount,
-                    ));
-                    Ok(())
-                },
-            )
+            Self::do_claim(&who, asset, reward_asset, lock_duration)?;
+            Ok(())
         }
 
         /// Dispatch reward asset with specified amount and duration
@@ -683,65 +820,427 @@ pub mod pallet {
             );
             ensure!(!reward_duration.is_zero(), Error::<T>::NotAValidDuration);
 
-            Self::update_reward(None, asset, reward_asset, lock_duration)?;
+            let payer = T::Lookup::lookup(payer)?;
+            Self::do_dispatch_reward(
+                asset,
+                reward_asset,
+                lock_duration,
+                &payer,
+                amount,
+                reward_duration,
+            )
+        }
 
-            let current_block_number = <frame_system::Pallet<T>>::block_number();
-            Pools::<T>::mutate(
-                (asset, reward_asset, lock_duration),
-                |pool_info| -> DispatchResult {
-                    let pool_info = pool_info.as_mut().ok_or(Error::<T>::PoolDoesNotExist)?;
-                    let duration_balance = pool_info.block_to_balance(reward_duration);
-                    let reward_rate = if current_block_number >= pool_info.period_finish {
-                        amount
-                            .checked_div(duration_balance)
-                            .ok_or(ArithmeticError::Overflow)?
-                    } else {
-                        let remaining = pool_info
-                            .period_finish
-                            .checked_sub(&current_block_number)
-                            .ok_or(ArithmeticError::Overflow)?;
-                        let left_over = pool_info
-                            .block_to_balance(remaining)
-                            .checked_mul(pool_info.reward_rate)
-                            .ok_or(ArithmeticError::Overflow)?;
-                        let total = left_over
-                            .checked_add(amount)
-                            .ok_or(ArithmeticError::Overflow)?;
-                        total
-                            .checked_div(duration_balance)
-                            .ok_or(ArithmeticError::Overflow)?
-                    };
+        /// Create an auto-compounding vault of a pool from a privileged origin. The
+        /// vault claims the pool reward every `harvest_interval` blocks, adds it back
+        /// as AMM liquidity and deposits it in the pool. Vault shares are tracked by
+        /// `vault_token`.
+        ///
+        /// The origin must conform to `UpdateOrigin`.
+        ///
+        /// - `asset`: The identifier of the staking asset, an AMM liquidity token.
+        /// - `reward_asset`: The identifier of the reward asset.
+        /// - `lock_duration`: Lock block number after Deposit.
+        /// - `vault_token`: The identifier of the vault token, must not be issued yet.
+        /// - `harvest_interval`: The number of blocks between two harvests.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::create_vault())]
+        #[transactional]
+        pub fn create_vault(
+            origin: OriginFor<T>,
+            asset: AssetIdOf<T>,
+            reward_asset: AssetIdOf<T>,
+            lock_duration: T::BlockNumber,
+            vault_token: AssetIdOf<T>,
+            harvest_interval: T::BlockNumber,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
 
-                    let new_period_finish = current_block_number
-                        .checked_add(&reward_duration)
-                        .ok_or(ArithmeticError::Overflow)?;
+            let pool_info = Pools::<T>::try_get((&asset, &reward_asset, &lock_duration))
+                .map_err(|_err| Error::<T>::PoolDoesNotExist)?;
+            ensure!(
+                pool_info.cool_down_duration.is_zero(),
+                Error::<T>::PoolHasCoolDownDuration
+            );
+            ensure!(
+                T::AMM::get_pool_by_lp_asset(asset).is_some(),
+                Error::<T>::NotALiquidityToken
+            );
+            ensure!(
+                !Vaults::<T>::contains_key(vault_token)
+                    && T::Assets::total_issuance(vault_token).is_zero(),
+                Error::<T>::VaultAlreadyExists
+            );
+            ensure!(!harvest_interval.is_zero(), Error::<T>::NotAValidDuration);
+
+            let vault = VaultInfo {
+                asset,
+                reward_asset,
+                lock_duration,
+                harvest_interval,
+                last_harvest: <frame_system::Pallet<T>>::block_number(),
+            };
 
-                    pool_info.reward_duration = reward_duration;
-                    pool_info.period_finish = new_period_finish;
-                    pool_info.reward_rate = reward_rate;
-                    pool_info.last_update_block = current_block_number;
+            Vaults::<T>::insert(vault_token, vault);
+            Self::deposit_event(Event::<T>::VaultAdded(
+                vault_token,
+                asset,
+                reward_asset,
+                lock_duration,
+                harvest_interval,
+            ));
+            Ok(())
+        }
 
-                    if amount > 0 {
-                        let asset_pool_account = Self::pool_account_id(reward_asset)?;
-                        let payer = T::Lookup::lookup(payer)?;
-                        T::Assets::transfer(
-                            reward_asset,
-                            &payer,
-                            &asset_pool_account,
-                            amount,
-                            false,
-                        )?;
-                    }
+        /// Depositing staking asset to a vault, vault tokens are minted for the share
+        /// of the vault deposit once the vault rewards are harvested
+        ///
+        /// The origin must be Signed and the sender must have sufficient balance of staking asset.
+        ///
+        /// - `vault_token`: The identifier of the vault token.
+        /// - `amount`: the amount of staking asset want to deposit.
+        #[pallet::call_index(10)]
+        #[pallet::weight(
+            T::WeightInfo::deposit_to_vault()
+                .saturating_add(Pallet::<T>::update_extra_rewards_weight())
+                .saturating_add(Pallet::<T>::update_working_balance_weight())
+                .saturating_add(Pallet::<T>::harvest_weight())
+        )]
+        #[transactional]
+        pub fn deposit_to_vault(
+            origin: OriginFor<T>,
+            vault_token: AssetIdOf<T>,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let vault = Self::vaults(vault_token).ok_or(Error::<T>::VaultDoesNotExist)?;
+            ensure!(!amount.is_zero(), Error::<T>::NotAValidAmount);
 
-                    Self::deposit_event(Event::<T>::RewardAdded(
-                        asset,
-                        reward_asset,
-                        lock_duration,
+            let vault_account = Self::vault_account_id(vault_token)?;
+            let total_issuance = T::Assets::total_issuance(vault_token);
+            // the first deposit of an empty vault is one vault token per staking asset,
+            // the minimum vault shares of it are locked in the vault
+            let (shares, locked_shares) = if total_issuance.is_zero() {
+                let locked_shares = T::MinimumVaultShares::get();
+                (
+                    amount
+                        .checked_sub(locked_shares)
+                        .ok_or(Error::<T>::NotAValidAmount)?,
+                    locked_shares,
+                )
+            } else {
+                // rewards earned so far are compounded before the new shares are minted
+                Self::do_harvest(vault_token, vault.clone())?;
+                (
+                    Self::mul_div(
                         amount,
-                    ));
-                    Ok(())
-                },
-            )
+                        total_issuance,
+                        Self::vault_deposit_balance(&vault_account, &vault),
+                    )?,
+                    Zero::zero(),
+                )
+            };
+            ensure!(!shares.is_zero(), Error::<T>::NotAValidAmount);
+
+            T::Assets::transfer(vault.asset, &who, &vault_account, amount, false)?;
+            Self::do_deposit(
+                &vault_account,
+                vault.asset,
+                vault.reward_asset,
+                vault.lock_duration,
+                amount,
+            )?;
+            if !locked_shares.is_zero() {
+                T::Assets::mint_into(vault_token, &vault_account, locked_shares)?;
+            }
+            T::Assets::mint_into(vault_token, &who, shares)?;
+
+            Self::deposit_event(Event::<T>::VaultDeposited(who, vault_token, amount, shares));
+            Ok(())
+        }
+
+        /// Withdrawing staking asset from a vault, vault tokens are burned for the
+        /// share of the vault deposit
+        ///
+        /// The origin must be Signed and the sender must have sufficient vault tokens.
+        ///
+        /// - `vault_token`: The identifier of the vault token.
+        /// - `shares`: the amount of vault tokens to burn.
+        #[pallet::call_index(11)]
+        #[pallet::weight(
+            T::WeightInfo::withdraw_from_vault()
+                .saturating_add(Pallet::<T>::update_extra_rewards_weight())
+                .saturating_add(Pallet::<T>::update_working_balance_weight())
+        )]
+        #[transactional]
+        pub fn withdraw_from_vault(
+            origin: OriginFor<T>,
+            vault_token: AssetIdOf<T>,
+            shares: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let vault = Self::vaults(vault_token).ok_or(Error::<T>::VaultDoesNotExist)?;
+            ensure!(!shares.is_zero(), Error::<T>::NotAValidAmount);
+
+            let pool_info =
+                Pools::<T>::try_get((&vault.asset, &vault.reward_asset, &vault.lock_duration))
+                    .map_err(|_err| Error::<T>::PoolDoesNotExist)?;
+            ensure!(
+                pool_info.cool_down_duration.is_zero(),
+                Error::<T>::PoolHasCoolDownDuration
+            );
+
+            let vault_account = Self::vault_account_id(vault_token)?;
+            let amount = Self::mul_div(
+                shares,
+                Self::vault_deposit_balance(&vault_account, &vault),
+                T::Assets::total_issuance(vault_token),
+            )?;
+            ensure!(!amount.is_zero(), Error::<T>::NotAValidAmount);
+
+            T::Assets::burn_from(vault_token, &who, shares)?;
+            Self::do_withdraw(
+                &vault_account,
+                vault.asset,
+                vault.reward_asset,
+                vault.lock_duration,
+                amount,
+            )?;
+            T::Assets::transfer(vault.asset, &vault_account, &who, amount, false)?;
+
+            Self::deposit_event(Event::<T>::VaultWithdrew(who, vault_token, shares, amount));
+            Ok(())
+        }
+
+        /// Harvest a vault once its harvest interval has elapsed, due vaults are
+        /// also harvested in `on_idle`
+        ///
+        /// Origin must be Signed.
+        ///
+        /// - `vault_token`: The identifier of the vault token.
+        #[pallet::call_index(12)]
+        #[pallet::weight(Pallet::<T>::harvest_weight())]
+        #[transactional]
+        pub fn harvest(origin: OriginFor<T>, vault_token: AssetIdOf<T>) -> DispatchResult {
+            ensure_signed(origin)?;
+            let vault = Self::vaults(vault_token).ok_or(Error::<T>::VaultDoesNotExist)?;
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() >= vault.next_harvest(),
+                Error::<T>::VaultNotReadyToHarvest
+            );
+
+            Self::do_harvest(vault_token, vault)
+        }
+
+        /// Add an extra reward asset to a pool from a privileged origin, depositors
+        /// of the pool earn it alongside the pool reward asset
+        ///
+        /// The origin must conform to `UpdateOrigin`.
+        ///
+        /// - `asset`: The identifier of the staking asset.
+        /// - `reward_asset`: The identifier of the reward asset.
+        /// - `lock_duration`: Lock block number after Deposit.
+        /// - `extra_reward_asset`: The identifier of the extra reward asset.
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::WeightInfo::add_extra_reward_asset())]
+        #[transactional]
+        pub fn add_extra_reward_asset(
+            origin: OriginFor<T>,
+            asset: AssetIdOf<T>,
+            reward_asset: AssetIdOf<T>,
+            lock_duration: T::BlockNumber,
+            extra_reward_asset: AssetIdOf<T>,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            ensure!(
+                Pools::<T>::contains_key((&asset, &reward_asset, &lock_duration)),
+                Error::<T>::PoolDoesNotExist
+            );
+            ensure!(
+                extra_reward_asset != reward_asset,
+                Error::<T>::ExtraRewardAssetAlreadyExists
+            );
+
+            ExtraRewardAssets::<T>::try_mutate(
+                (&asset, &reward_asset, &lock_duration),
+                |extra_reward_assets| -> DispatchResult {
+                    ensure!(
+                        !extra_reward_assets.contains(&extra_reward_asset),
+                        Error::<T>::ExtraRewardAssetAlreadyExists
+                    );
+                    extra_reward_assets
+                        .try_push(extra_reward_asset)
+                        .map_err(|_| Error::<T>::ExcessMaxExtraRewardAssetsCount)?;
+                    Ok(())
+                },
+            )?;
+
+            Self::deposit_event(Event::<T>::ExtraRewardAssetAdded(
+                asset,
+                reward_asset,
+                lock_duration,
+                extra_reward_asset,
+            ));
+            Ok(())
+        }
+
+        /// Dispatch reward of an extra reward asset of a pool from a privileged origin
+        ///
+        /// The origin must conform to `UpdateOrigin`.
+        ///
+        /// - `asset`: The identifier of the staking asset.
+        /// - `reward_asset`: The identifier of the reward asset.
+        /// - `lock_duration`: Lock block number after Deposit.
+        /// - `extra_reward_asset`: The identifier of the extra reward asset.
+        /// - `payer`: the account from which the extra reward is transferred.
+        /// - `amount`: the amount of extra reward to dispatch.
+        /// - `reward_duration`: the number of blocks the extra reward is paid over.
+        #[pallet::call_index(14)]
+        #[pallet::weight(
+            T::WeightInfo::dispatch_extra_reward()
+                .saturating_add(Pallet::<T>::update_extra_rewards_weight())
+        )]
+        #[transactional]
+        pub fn dispatch_extra_reward(
+            origin: OriginFor<T>,
+            asset: AssetIdOf<T>,
+            reward_asset: AssetIdOf<T>,
+            lock_duration: T::BlockNumber,
+            extra_reward_asset: AssetIdOf<T>,
+            payer: <T::Lookup as StaticLookup>::Source,
+            amount: BalanceOf<T>,
+            reward_duration: T::BlockNumber,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            ensure!(
+                Self::extra_reward_assets((&asset, &reward_asset, &lock_duration))
+                    .contains(&extra_reward_asset),
+                Error::<T>::ExtraRewardAssetDoesNotExist
+            );
+            ensure!(!reward_duration.is_zero(), Error::<T>::NotAValidDuration);
+
+            Self::update_reward(None, asset, reward_asset, lock_duration)?;
+
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            ExtraRewards::<T>::try_mutate(
+                (&asset, &reward_asset, &lock_duration, &extra_reward_asset),
+                |reward_info| {
+                    reward_info.notify_reward_amount(current_block_number, amount, reward_duration)
+                },
+            )?;
+
+            if amount > 0 {
+                let asset_pool_account = Self::pool_account_id(extra_reward_asset)?;
+                let payer = T::Lookup::lookup(payer)?;
+                T::Assets::transfer(
+                    extra_reward_asset,
+                    &payer,
+                    &asset_pool_account,
+                    amount,
+                    false,
+                )?;
+            }
+
+            Self::deposit_event(Event::<T>::ExtraRewardAdded(
+                asset,
+                reward_asset,
+                lock_duration,
+                extra_reward_asset,
+                amount,
+            ));
+            Ok(())
+        }
+
+        /// Update the working balance of a user in a pool with the current voting power
+        /// of the user, voting power decays so anyone can update a stale boost
+        ///
+        /// Origin must be Signed.
+        ///
+        /// - `asset`: The identifier of the staking asset.
+        /// - `reward_asset`: The identifier of the reward asset.
+        /// - `lock_duration`: Lock block number after Deposit.
+        /// - `who`: the user whose working balance is updated.
+        #[pallet::call_index(15)]
+        #[pallet::weight(
+            T::WeightInfo::update_boost().saturating_add(Pallet::<T>::update_extra_rewards_weight())
+        )]
+        #[transactional]
+        pub fn update_boost(
+            origin: OriginFor<T>,
+            asset: AssetIdOf<T>,
+            reward_asset: AssetIdOf<T>,
+            lock_duration: T::BlockNumber,
+            who: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            ensure!(
+                Pools::<T>::contains_key((&asset, &reward_asset, &lock_duration)),
+                Error::<T>::PoolDoesNotExist
+            );
+
+            Self::update_reward(Some(who.clone()), asset, reward_asset, lock_duration)?;
+            let working_balance =
+                Self::update_working_balance(&who, asset, reward_asset, lock_duration)?;
+
+            Self::deposit_event(Event::<T>::BoostUpdated(
+                who,
+                asset,
+                reward_asset,
+                lock_duration,
+                working_balance,
+            ));
+            Ok(())
+        }
+
+        /// Dispatch reward asset across the pools of the reward asset, pro-rata on the
+        /// votes of their gauges in the current epoch
+        ///
+        /// The origin must conform to `UpdateOrigin`.
+        ///
+        /// - `reward_asset`: The identifier of the reward asset.
+        /// - `payer`: the payer of reward asset.
+        /// - `amount`: the amount of reward asset to dispatch.
+        /// - `reward_duration`: the number of block this reward will last for.
+        #[pallet::call_index(16)]
+        #[pallet::weight(Pallet::<T>::dispatch_gauge_rewards_weight())]
+        #[transactional]
+        pub fn dispatch_gauge_rewards(
+            origin: OriginFor<T>,
+            reward_asset: AssetIdOf<T>,
+            payer: <T::Lookup as StaticLookup>::Source,
+            amount: BalanceOf<T>,
+            reward_duration: T::BlockNumber,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            ensure!(!reward_duration.is_zero(), Error::<T>::NotAValidDuration);
+
+            let gauges: Vec<_> = T::GaugeController::gauge_relative_weights()
+                .into_iter()
+                .filter(|((asset, gauge_reward_asset, lock_duration), weight)| {
+                    *gauge_reward_asset == reward_asset
+                        && !weight.is_zero()
+                        && Pools::<T>::contains_key((asset, gauge_reward_asset, lock_duration))
+                })
+                .collect();
+            let total_weight = gauges.iter().fold(0, |total: BalanceOf<T>, (_, weight)| {
+                total.saturating_add(weight.deconstruct().into())
+            });
+            ensure!(!total_weight.is_zero(), Error::<T>::NoGaugeWeights);
+
+            let payer = T::Lookup::lookup(payer)?;
+            for ((asset, _, lock_duration), weight) in gauges {
+                let share = Self::mul_div(amount, weight.deconstruct().into(), total_weight)?;
+                Self::do_dispatch_reward(
+                    asset,
+                    reward_asset,
+                    lock_duration,
+                    &payer,
+                    share,
+                    reward_duration,
+                )?;
+            }
+            Ok(())
         }
     }
 }
@@ -756,25 +1255,33 @@ impl<T: Config> Pallet<T> {
         let current_block_number = <frame_system::Pallet<T>>::block_number();
 
         //1, update pool reward info
-        Pools::<T>::mutate(
+        let working_supply = Pools::<T>::mutate(
             (asset, reward_asset, lock_duration),
-            |pool_info| -> DispatchResult {
+            |pool_info| -> Result<BalanceOf<T>, DispatchError> {
                 let pool_info = pool_info.as_mut().ok_or(Error::<T>::PoolDoesNotExist)?;
 
-                pool_info.update_reward_per_share(current_block_number)?;
+                let working_supply = Self::working_supply((&asset, &reward_asset, &lock_duration))
+                    .unwrap_or(pool_info.total_deposited);
+                pool_info.update_reward_per_share(current_block_number, working_supply)?;
 
                 //2, update user reward info
-                if let Some(who) = who {
+                if let Some(who) = &who {
                     Positions::<T>::mutate(
-                        (&asset, &reward_asset, &lock_duration, &who),
+                        (&asset, &reward_asset, &lock_duration, who),
                         |user_position| -> DispatchResult {
                             let diff = pool_info
-                                .reward_per_share(current_block_number)?
+                                .reward_per_share(current_block_number, working_supply)?
                                 .checked_sub(user_position.reward_per_share_paid)
                                 .ok_or(ArithmeticError::Overflow)?;
 
-                            let earned = user_position
-                                .deposit_balance
+                            let working_balance = Self::working_balances((
+                                &asset,
+                                &reward_asset,
+                                &lock_duration,
+                                who,
+                            ))
+                            .unwrap_or(user_position.deposit_balance);
+                            let earned = working_balance
                                 .get_big_uint()
                                 .checked_mul(&diff.get_big_uint())
                                 .and_then(|r| {
@@ -789,10 +1296,436 @@ impl<T: Config> Pallet<T> {
                             user_position.reward_amount = BalanceOf::<T>::saturated_from(earned);
                             user_position.reward_per_share_paid = pool_info.reward_per_share_stored;
 
+                            // from now on working balances of the pool are tracked
+                            WorkingBalances::<T>::insert(
+                                (&asset, &reward_asset, &lock_duration, who),
+                                working_balance,
+                            );
+                            WorkingSupply::<T>::insert(
+                                (&asset, &reward_asset, &lock_duration),
+                                working_supply,
+                            );
                             Ok(())
                         },
                     )?
                 }
+                Ok(working_supply)
+            },
+        )?;
+
+        //3, update extra reward info
+        Self::update_extra_rewards(
+            who.as_ref(),
+            asset,
+            reward_asset,
+            lock_duration,
+            working_supply,
+        )
+    }
+
+    fn update_extra_rewards(
+        who: Option<&T::AccountId>,
+        asset: AssetIdOf<T>,
+        reward_asset: AssetIdOf<T>,
+        lock_duration: T::BlockNumber,
+        working_supply: BalanceOf<T>,
+    ) -> DispatchResult {
+        let current_block_number = <frame_system::Pallet<T>>::block_number();
+        let working_balance = who.map(|who| {
+            Self::working_balances((&asset, &reward_asset, &lock_duration, who)).unwrap_or_default()
+        });
+
+        for extra_reward_asset in Self::extra_reward_assets((asset, reward_asset, lock_duration)) {
+            ExtraRewards::<T>::try_mutate(
+                (&asset, &reward_asset, &lock_duration, &extra_reward_asset),
+                |reward_info| -> DispatchResult {
+                    reward_info.update_reward_per_share(current_block_number, working_supply)?;
+
+                    if let (Some(who), Some(working_balance)) = (who, working_balance) {
+                        ExtraRewardPositions::<T>::mutate(
+                            (
+                                &asset,
+                                &reward_asset,
+                                &lock_duration,
+                                who,
+                                &extra_reward_asset,
+                            ),
+                            |position| -> DispatchResult {
+                                position.reward_amount =
+                                    reward_info.earned(working_balance, position)?;
+                                position.reward_per_share_paid =
+                                    reward_info.reward_per_share_stored;
+                                Ok(())
+                            },
+                        )?;
+                    }
+                    Ok(())
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Recomputes the working balance of a user in a pool after its deposit or voting
+    /// power changed. `update_reward` must be called for the user beforehand.
+    fn update_working_balance(
+        who: &T::AccountId,
+        asset: AssetIdOf<T>,
+        reward_asset: AssetIdOf<T>,
+        lock_duration: T::BlockNumber,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let pool_info = Pools::<T>::try_get((&asset, &reward_asset, &lock_duration))
+            .map_err(|_err| Error::<T>::PoolDoesNotExist)?;
+        let deposit_balance =
+            Positions::<T>::get((&asset, &reward_asset, &lock_duration, who)).deposit_balance;
+        let working_balance =
+            Self::boosted_balance(who, deposit_balance, pool_info.total_deposited)?;
+
+        let previous_working_balance =
+            WorkingBalances::<T>::get((&asset, &reward_asset, &lock_duration, who))
+                .unwrap_or_default();
+        WorkingSupply::<T>::try_mutate(
+            (&asset, &reward_asset, &lock_duration),
+            |working_supply| -> DispatchResult {
+                let total = working_supply
+                    .unwrap_or_default()
+                    .checked_sub(previous_working_balance)
+                    .and_then(|total| total.checked_add(working_balance))
+                    .ok_or(ArithmeticError::Overflow)?;
+                *working_supply = Some(total);
+                Ok(())
+            },
+        )?;
+        if deposit_balance.is_zero() {
+            WorkingBalances::<T>::remove((&asset, &reward_asset, &lock_duration, who));
+        } else {
+            WorkingBalances::<T>::insert(
+                (&asset, &reward_asset, &lock_duration, who),
+                working_balance,
+            );
+        }
+        Ok(working_balance)
+    }
+
+    /// Working balance of a deposit in the style of veCRV, the deposit plus up to
+    /// `MaxBoost - 1` times the share of the pool deposit matching the share of the
+    /// total voting power owned by `who`
+    fn boosted_balance(
+        who: &T::AccountId,
+        deposit_balance: BalanceOf<T>,
+        total_deposited: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let voting_power = T::VotingEscrow::voting_power(who);
+        let total_voting_power = T::VotingEscrow::total_voting_power();
+        if voting_power.is_zero() || total_voting_power.is_zero() {
+            return Ok(deposit_balance);
+        }
+
+        let boosted =
+            Self::mul_div(total_deposited, voting_power, total_voting_power)?.min(deposit_balance);
+        T::MaxBoost::get()
+            .saturating_sub(Rate::one())
+            .checked_mul_int(boosted)
+            .and_then(|extra| extra.checked_add(deposit_balance))
+            .ok_or_else(|| ArithmeticError::Overflow.into())
+    }
+
+    /// Weight of updating the extra reward streams of a pool for a user
+    fn update_extra_rewards_weight() -> Weight {
+        T::DbWeight::get()
+            .reads_writes(2, 2)
+            .saturating_mul(T::MaxExtraRewardAssets::get().into())
+    }
+
+    /// Weight of updating the working balance of a user, the voting power is read
+    /// from the vote escrow lock and its epoch checkpoint, walking at most one slope
+    /// change
+    fn update_working_balance_weight() -> Weight {
+        T::DbWeight::get().reads_writes(5, 2)
+    }
+
+    /// Max count of reward assets paid by a pool
+    fn reward_assets_count() -> u64 {
+        u64::from(T::MaxExtraRewardAssets::get()).saturating_add(1)
+    }
+
+    /// Weight of harvesting a vault, the trades of each reward asset also read up to
+    /// 3 oracle prices of up to 5 reads each and the AMM pool
+    fn harvest_weight() -> Weight {
+        T::WeightInfo::harvest()
+            .saturating_add(T::DbWeight::get().reads(16))
+            .saturating_add(Self::update_working_balance_weight())
+            .saturating_mul(Self::reward_assets_count())
+    }
+
+    /// Weight of dispatching reward to the pools of every gauge, the votes of a gauge
+    /// are checkpointed at every epoch start, reading them takes up to 3 reads
+    fn dispatch_gauge_rewards_weight() -> Weight {
+        T::WeightInfo::dispatch_reward()
+            .saturating_add(Self::update_extra_rewards_weight())
+            .saturating_add(T::DbWeight::get().reads(3))
+            .saturating_mul(T::GaugeController::max_gauges().max(1).into())
+    }
+
+    #[require_transactional]
+    fn do_deposit(
+        who: &T::AccountId,
+        asset: AssetIdOf<T>,
+        reward_asset: AssetIdOf<T>,
+        lock_duration: T::BlockNumber,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        Self::update_reward(Some(who.clone()), asset, reward_asset, lock_duration)?;
+
+        let asset_pool_account = Self::pool_account_id(asset)?;
+        Pools::<T>::mutate(
+            (asset, reward_asset, lock_duration),
+            |pool_info| -> DispatchResult {
+                let pool_info = pool_info.as_mut().ok_or(Error::<T>::PoolDoesNotExist)?;
+
+                ensure!(pool_info.is_active, Error::<T>::PoolIsNotActive);
+
+                T::Assets::transfer(asset, who, &asset_pool_account, amount, false)?;
+
+                pool_info.total_deposited = pool_info
+                    .total_deposited
+                    .checked_add(amount)
+                    .ok_or(ArithmeticError::Overflow)?;
+
+                Positions::<T>::mutate(
+                    (&asset, &reward_asset, &lock_duration, who),
+                    |user_position| -> DispatchResult {
+                        user_position.deposit_balance = user_position
+                            .deposit_balance
+                            .checked_add(amount)
+                            .ok_or(ArithmeticError::Overflow)?;
+                        Ok(())
+                    },
+                )?;
+
+                Self::deposit_event(Event::<T>::AssetsDeposited(
+                    who.clone(),
+                    asset,
+                    reward_asset,
+                    lock_duration,
+                    amount,
+                ));
+                Ok(())
+            },
+        )?;
+
+        Self::update_working_balance(who, asset, reward_asset, lock_duration)?;
+        Ok(())
+    }
+
+    #[require_transactional]
+    fn do_withdraw(
+        who: &T::AccountId,
+        asset: AssetIdOf<T>,
+        reward_asset: AssetIdOf<T>,
+        lock_duration: T::BlockNumber,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        let user_position = Positions::<T>::get((&asset, &reward_asset, &lock_duration, who));
+        ensure!(
+            user_position.deposit_balance >= amount,
+            Error::<T>::DepositBalanceLow
+        );
+
+        Self::update_reward(Some(who.clone()), asset, reward_asset, lock_duration)?;
+
+        Pools::<T>::mutate(
+            (asset, reward_asset, lock_duration),
+            |pool_info| -> DispatchResult {
+                let pool_info = pool_info.as_mut().ok_or(Error::<T>::PoolDoesNotExist)?;
+
+                let current_block_number = <frame_system::Pallet<T>>::block_number();
+                ensure!(
+                    current_block_number >= pool_info.unlock_height,
+                    Error::<T>::PoolUnderLock
+                );
+
+                pool_info.total_deposited = pool_info
+                    .total_deposited
+                    .checked_sub(amount)
+                    .ok_or(ArithmeticError::Overflow)?;
+
+                Positions::<T>::mutate(
+                    (&asset, &reward_asset, &lock_duration, who),
+                    |user_position| -> DispatchResult {
+                        user_position.deposit_balance = user_position
+                            .deposit_balance
+                            .checked_sub(amount)
+                            .ok_or(ArithmeticError::Overflow)?;
+
+                        if pool_info.cool_down_duration.is_zero() {
+                            let asset_pool_account = Self::pool_account_id(asset)?;
+                            T::Assets::transfer(asset, &asset_pool_account, who, amount, false)?;
+                        } else {
+                            user_position
+                                .lock_balance_items
+                                .try_push((amount, current_block_number))
+                                .map_err(|_| Error::<T>::ExcessMaxUserLockItemsCount)?;
+                        }
+
+                        Ok(())
+                    },
+                )?;
+
+                Self::deposit_event(Event::<T>::AssetsWithdrew(
+                    who.clone(),
+                    asset,
+                    reward_asset,
+                    lock_duration,
+                    amount,
+                ));
+                Ok(())
+            },
+        )?;
+
+        Self::update_working_balance(who, asset, reward_asset, lock_duration)?;
+        Ok(())
+    }
+
+    /// Pays the pending rewards of `who`, returns the amount paid of each reward asset
+    #[require_transactional]
+    fn do_claim(
+        who: &T::AccountId,
+        asset: AssetIdOf<T>,
+        reward_asset: AssetIdOf<T>,
+        lock_duration: T::BlockNumber,
+    ) -> Result<Vec<(AssetIdOf<T>, BalanceOf<T>)>, DispatchError> {
+        Self::update_reward(Some(who.clone()), asset, reward_asset, lock_duration)?;
+        Self::update_working_balance(who, asset, reward_asset, lock_duration)?;
+
+        let asset_pool_account = Self::pool_account_id(reward_asset)?;
+        let reward_amount = Positions::<T>::mutate(
+            (&asset, &reward_asset, &lock_duration, who),
+            |user_position| -> Result<BalanceOf<T>, DispatchError> {
+                let reward_amount = user_position.reward_amount;
+                if reward_amount > 0 {
+                    T::Assets::transfer(
+                        reward_asset,
+                        &asset_pool_account,
+                        who,
+                        reward_amount,
+                        false,
+                    )?;
+                    user_position.reward_amount = 0;
+                }
+
+                Self::deposit_event(Event::<T>::RewardPaid(
+                    who.clone(),
+                    asset,
+                    reward_asset,
+                    lock_duration,
+                    reward_amount,
+                ));
+                Ok(reward_amount)
+            },
+        )?;
+
+        let mut rewards = vec![(reward_asset, reward_amount)];
+        for extra_reward_asset in Self::extra_reward_assets((asset, reward_asset, lock_duration)) {
+            let extra_pool_account = Self::pool_account_id(extra_reward_asset)?;
+            let extra_reward_amount = ExtraRewardPositions::<T>::mutate(
+                (
+                    &asset,
+                    &reward_asset,
+                    &lock_duration,
+                    who,
+                    &extra_reward_asset,
+                ),
+                |position| -> Result<BalanceOf<T>, DispatchError> {
+                    let reward_amount = position.reward_amount;
+                    if reward_amount > 0 {
+                        T::Assets::transfer(
+                            extra_reward_asset,
+                            &extra_pool_account,
+                            who,
+                            reward_amount,
+                            false,
+                        )?;
+                        position.reward_amount = 0;
+                    }
+
+                    Self::deposit_event(Event::<T>::ExtraRewardPaid(
+                        who.clone(),
+                        asset,
+                        reward_asset,
+                        lock_duration,
+                        extra_reward_asset,
+                        reward_amount,
+                    ));
+                    Ok(reward_amount)
+                },
+            )?;
+            rewards.push((extra_reward_asset, extra_reward_amount));
+        }
+
+        Ok(rewards)
+    }
+
+    /// Starts a new reward period of `amount` paid by `payer` over `reward_duration`
+    /// blocks, the reward left over from the current period is carried over
+    #[require_transactional]
+    fn do_dispatch_reward(
+        asset: AssetIdOf<T>,
+        reward_asset: AssetIdOf<T>,
+        lock_duration: T::BlockNumber,
+        payer: &T::AccountId,
+        amount: BalanceOf<T>,
+        reward_duration: T::BlockNumber,
+    ) -> DispatchResult {
+        Self::update_reward(None, asset, reward_asset, lock_duration)?;
+
+        let current_block_number = <frame_system::Pallet<T>>::block_number();
+        Pools::<T>::mutate(
+            (asset, reward_asset, lock_duration),
+            |pool_info| -> DispatchResult {
+                let pool_info = pool_info.as_mut().ok_or(Error::<T>::PoolDoesNotExist)?;
+                let duration_balance = pool_info.block_to_balance(reward_duration);
+                let reward_rate = if current_block_number >= pool_info.period_finish {
+                    amount
+                        .checked_div(duration_balance)
+                        .ok_or(ArithmeticError::Overflow)?
+                } else {
+                    let remaining = pool_info
+                        .period_finish
+                        .checked_sub(&current_block_number)
+                        .ok_or(ArithmeticError::Overflow)?;
+                    let left_over = pool_info
+                        .block_to_balance(remaining)
+                        .checked_mul(pool_info.reward_rate)
+                        .ok_or(ArithmeticError::Overflow)?;
+                    let total = left_over
+                        .checked_add(amount)
+                        .ok_or(ArithmeticError::Overflow)?;
+                    total
+                        .checked_div(duration_balance)
+                        .ok_or(ArithmeticError::Overflow)?
+                };
+
+                let new_period_finish = current_block_number
+                    .checked_add(&reward_duration)
+                    .ok_or(ArithmeticError::Overflow)?;
+
+                pool_info.reward_duration = reward_duration;
+                pool_info.period_finish = new_period_finish;
+                pool_info.reward_rate = reward_rate;
+                pool_info.last_update_block = current_block_number;
+
+                if amount > 0 {
+                    let asset_pool_account = Self::pool_account_id(reward_asset)?;
+                    T::Assets::transfer(reward_asset, payer, &asset_pool_account, amount, false)?;
+                }
+
+                Self::deposit_event(Event::<T>::RewardAdded(
+                    asset,
+                    reward_asset,
+                    lock_duration,
+                    amount,
+                ));
                 Ok(())
             },
         )
@@ -803,4 +1736,214 @@ impl<T: Config> Pallet<T> {
         let entropy = (b"modlpy/liquidity", &[account_id], asset_id).using_encoded(blake2_256);
         Ok(T::AccountId::decode(&mut &entropy[..]).map_err(|_| Error::<T>::CodecError)?)
     }
+
+    fn try_harvest(vault_token: AssetIdOf<T>, vault: VaultInfo<AssetIdOf<T>, T::BlockNumber>) {
+        let _ = with_transaction(|| match Self::do_harvest(vault_token, vault) {
+            Ok(()) => TransactionOutcome::Commit(Ok(())),
+            Err(err) => {
+                log::trace!(
+                    target: "farming::try_harvest",
+                    "vault_token: {:?}, err: {:?}",
+                    vault_token,
+                    err,
+                );
+                TransactionOutcome::Rollback(Err(err))
+            }
+        });
+    }
+
+    /// Claims the vault rewards, swaps them for one of the liquidity token assets if
+    /// needed, zaps them into the AMM pool and deposits the liquidity in the vault pool
+    #[require_transactional]
+    fn do_harvest(
+        vault_token: AssetIdOf<T>,
+        mut vault: VaultInfo<AssetIdOf<T>, T::BlockNumber>,
+    ) -> DispatchResult {
+        let vault_account = Self::vault_account_id(vault_token)?;
+        let rewards = Self::do_claim(
+            &vault_account,
+            vault.asset,
+            vault.reward_asset,
+            vault.lock_duration,
+        )?;
+
+        let (base_asset, quote_asset, _) =
+            T::AMM::get_pool_by_lp_asset(vault.asset).ok_or(Error::<T>::NotALiquidityToken)?;
+        let mut liquidity: BalanceOf<T> = Zero::zero();
+        // trades are bounded by the oracle prices, so harvests can't be sandwiched
+        for (reward_asset, reward_amount) in rewards {
+            if reward_amount.is_zero() {
+                continue;
+            }
+
+            let (asset_in, amount_in) = if reward_asset == base_asset || reward_asset == quote_asset
+            {
+                (reward_asset, reward_amount)
+            } else {
+                let amount_out = T::Router::swap_exact_tokens_for_tokens(
+                    &vault_account,
+                    reward_asset,
+                    base_asset,
+                    reward_amount,
+                    Self::min_harvest_amount_out(reward_asset, base_asset, reward_amount)?,
+                )?;
+                (base_asset, amount_out)
+            };
+            let paired_asset = if asset_in == base_asset {
+                quote_asset
+            } else {
+                base_asset
+            };
+
+            let zapped = T::AMM::zap_in(
+                &vault_account,
+                (asset_in, paired_asset),
+                amount_in,
+                Self::min_harvest_liquidity(vault.asset, asset_in, amount_in)?,
+            )?;
+            liquidity = liquidity
+                .checked_add(zapped)
+                .ok_or(ArithmeticError::Overflow)?;
+        }
+
+        if !liquidity.is_zero() {
+            Self::do_deposit(
+                &vault_account,
+                vault.asset,
+                vault.reward_asset,
+                vault.lock_duration,
+                liquidity,
+            )?;
+        }
+
+        vault.last_harvest = <frame_system::Pallet<T>>::block_number();
+        Vaults::<T>::insert(vault_token, vault);
+
+        Self::deposit_event(Event::<T>::VaultHarvested(vault_token, liquidity));
+        Ok(())
+    }
+
+    /// The least amount of `asset_out` a harvest trade of `amount_in` of `asset_in`
+    /// gets, its worth at the oracle prices less the max harvest slippage
+    fn min_harvest_amount_out(
+        asset_in: AssetIdOf<T>,
+        asset_out: AssetIdOf<T>,
+        amount_in: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let price_in = Self::harvest_price(&asset_in)?;
+        let price_out = Self::harvest_price(&asset_out)?;
+        let amount_out = Self::mul_div(amount_in, price_in, price_out)?;
+
+        Ok(T::MaxHarvestSlippage::get()
+            .left_from_one()
+            .mul_floor(amount_out))
+    }
+
+    /// The least liquidity a harvest zapping `amount_in` of `asset_in` gets, its worth
+    /// at the oracle prices less the max harvest slippage.
+    ///
+    /// The liquidity is valued at the oracle prices of the pool assets from the pool
+    /// invariant, `2 * sqrt(base_amount * quote_amount * base_price * quote_price)`,
+    /// which trades skewing the pool reserves don't lower.
+    fn min_harvest_liquidity(
+        lp_asset: AssetIdOf<T>,
+        asset_in: AssetIdOf<T>,
+        amount_in: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let (base_asset, quote_asset, pool) =
+            T::AMM::get_pool_by_lp_asset(lp_asset).ok_or(Error::<T>::NotALiquidityToken)?;
+        let base_price = Self::harvest_price(&base_asset)?;
+        let quote_price = Self::harvest_price(&quote_asset)?;
+        let price_in = Self::harvest_price(&asset_in)?;
+
+        let pool_value = pool
+            .base_amount
+            .get_big_uint()
+            .checked_mul(&pool.quote_amount.get_big_uint())
+            .and_then(|r| r.checked_mul(&base_price.get_big_uint()))
+            .and_then(|r| r.checked_mul(&quote_price.get_big_uint()))
+            .map(|r| r.sqrt() * 2u32)
+            .ok_or(ArithmeticError::Overflow)?;
+        let liquidity = amount_in
+            .get_big_uint()
+            .checked_mul(&price_in.get_big_uint())
+            .and_then(|r| r.checked_mul(&T::Assets::total_issuance(lp_asset).get_big_uint()))
+            .and_then(|r| r.checked_div(&pool_value))
+            .and_then(|r| r.to_u128())
+            .ok_or(ArithmeticError::Overflow)?;
+
+        Ok(T::MaxHarvestSlippage::get()
+            .left_from_one()
+            .mul_floor(BalanceOf::<T>::saturated_from(liquidity)))
+    }
+
+    /// The oracle price of one unit of an asset traded by a harvest, as a fixed point
+    /// inner value
+    fn harvest_price(asset_id: &AssetIdOf<T>) -> Result<u128, DispatchError> {
+        T::PriceFeeder::get_price(asset_id)
+            .map(|(price, _)| price.into_inner())
+            .filter(|price| !price.is_zero())
+            .ok_or_else(|| Error::<T>::PriceNotReady.into())
+    }
+
+    fn vault_deposit_balance(
+        vault_account: &T::AccountId,
+        vault: &VaultInfo<AssetIdOf<T>, T::BlockNumber>,
+    ) -> BalanceOf<T> {
+        Positions::<T>::get((
+            &vault.asset,
+            &vault.reward_asset,
+            &vault.lock_duration,
+            vault_account,
+        ))
+        .deposit_balance
+    }
+
+    /// Converts between staking asset and vault shares, `amount * numerator / denominator`
+    fn mul_div(
+        amount: BalanceOf<T>,
+        numerator: BalanceOf<T>,
+        denominator: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let result = amount
+            .get_big_uint()
+            .checked_mul(&numerator.get_big_uint())
+            .and_then(|r| r.checked_div(&denominator.get_big_uint()))
+            .and_then(|r| r.to_u128())
+            .ok_or(ArithmeticError::Overflow)?;
+        Ok(BalanceOf::<T>::saturated_from(result))
+    }
+
+    pub fn vault_account_id(vault_token: AssetIdOf<T>) -> Result<T::AccountId, DispatchError> {
+        let account_id: T::AccountId = T::PalletId::get().into_account_truncating();
+        let entropy = (b"modlpy/lpvault", &[account_id], vault_token).using_encoded(blake2_256);
+        Ok(T::AccountId::decode(&mut &entropy[..]).map_err(|_| Error::<T>::CodecError)?)
+    }
+}
+
+impl<T: Config> LPVaultTokenExchangeRateProvider<AssetIdOf<T>> for Pallet<T> {
+    /// Staking asset amount of one vault token
+    fn get_exchange_rate(vault_token: &AssetIdOf<T>) -> Option<Rate> {
+        let vault = Self::vaults(vault_token)?;
+        let total_issuance = T::Assets::total_issuance(*vault_token);
+        if total_issuance.is_zero() {
+            return Some(Rate::one());
+        }
+
+        let vault_account = Self::vault_account_id(*vault_token).ok()?;
+        Rate::checked_from_rational(
+            Self::vault_deposit_balance(&vault_account, &vault),
+            total_issuance,
+        )
+    }
+
+    fn get_staking_asset(vault_token: &AssetIdOf<T>) -> Option<AssetIdOf<T>> {
+        Self::vaults(vault_token).map(|vault| vault.asset)
+    }
+}
+
+impl<T: Config> LPVaultTokenCurrenciesFilter<AssetIdOf<T>> for Pallet<T> {
+    fn contains(vault_token: &AssetIdOf<T>) -> bool {
+        Vaults::<T>::contains_key(vault_token)
+    }
 }
@@ -3,24 +3,32 @@ use crate as pallet_farming;
 // use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
     parameter_types,
-    traits::{AsEnsureOriginWithArg, Everything},
+    traits::{AsEnsureOriginWithArg, Everything, Hooks, SortedMembers},
+    weights::Weight,
     PalletId,
 };
-use frame_system::{self as system, EnsureRoot, EnsureSigned};
-use pallet_traits::DecimalProvider;
-use primitives::{Balance, CurrencyId};
+use frame_system::{self as system, EnsureRoot, EnsureSigned, EnsureSignedBy};
+use pallet_traits::{DecimalProvider, PriceFeeder};
+use primitives::{Balance, CurrencyId, Price, PriceDetail, Rate, Ratio};
 #[cfg(feature = "std")]
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
+    FixedPointNumber,
 };
+use std::{cell::RefCell, collections::HashMap};
 
 pub const EHKO: CurrencyId = 0;
 pub const STAKE_TOKEN: CurrencyId = 1;
 pub const REWARD_TOKEN: CurrencyId = 2;
 pub const BIG_DECIMAL_STAKE_TOKEN: CurrencyId = 3;
 pub const BIG_DECIMAL_REWARD_TOKEN: CurrencyId = 4;
+pub const QUOTE_TOKEN: CurrencyId = 5;
+pub const EXTRA_REWARD_TOKEN: CurrencyId = 6;
+pub const LP_TOKEN: CurrencyId = 42;
+pub const REWARD_LP_TOKEN: CurrencyId = 43;
+pub const VAULT_TOKEN: CurrencyId = 50;
 pub const LOCK_DURATION: u64 = 20;
 
 pub type AccountId = u128;
@@ -29,6 +37,7 @@ pub const ALICE: AccountId = 1;
 pub const BOB: AccountId = 2;
 pub const REWARD_TOKEN_PAYER: AccountId = 3;
 pub const CHARLIE: AccountId = 4;
+pub const PROTOCOL_FEE_RECEIVER: AccountId = 99;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -45,7 +54,11 @@ frame_support::construct_runtime!(
         Balances: pallet_balances::{Pallet, Call, Storage, Event<T>},
         Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
         CurrencyAdapter: pallet_currency_adapter::{Pallet, Call},
+        DefaultAMM: pallet_amm::{Pallet, Call, Storage, Event<T>},
+        AMMRoute: pallet_router::{Pallet, Call, Storage, Event<T>},
+        StableSwap: pallet_stableswap::{Pallet, Call, Storage, Event<T>},
         Farming: pallet_farming::{Pallet, Call, Storage, Event<T>},
+        VoteEscrow: pallet_vote_escrow::{Pallet, Call, Storage, Event<T>},
     }
 );
 
@@ -135,6 +148,38 @@ parameter_types! {
     pub const MaxUserLockItemsCount: u32 = 3;
     pub const LockPoolMaxDuration: u32 = 2628000;
     pub const CoolDownMaxDuration: u32 = 50400;
+    pub const MaxVaultHarvestsPerBlock: u32 = 10;
+    pub const MaxExtraRewardAssets: u32 = 3;
+    pub MaxBoost: Rate = Rate::saturating_from_rational(5, 2);
+    pub MaxHarvestSlippage: Ratio = Ratio::from_percent(1);
+    pub const MinimumVaultShares: Balance = 1_000;
+}
+
+pub struct MockPriceFeeder;
+
+impl MockPriceFeeder {
+    thread_local! {
+        pub static PRICES: RefCell<HashMap<CurrencyId, PriceDetail>> = {
+            RefCell::new(
+                vec![EHKO, STAKE_TOKEN, REWARD_TOKEN, QUOTE_TOKEN, EXTRA_REWARD_TOKEN]
+                    .iter()
+                    .map(|&x| (x, (Price::saturating_from_integer(1), 1)))
+                    .collect()
+            )
+        };
+    }
+
+    pub fn set_price(asset_id: CurrencyId, price: Price) {
+        Self::PRICES.with(|prices| {
+            prices.borrow_mut().insert(asset_id, (price, 1u64));
+        });
+    }
+}
+
+impl PriceFeeder for MockPriceFeeder {
+    fn get_price(asset_id: &CurrencyId) -> Option<PriceDetail> {
+        Self::PRICES.with(|prices| prices.borrow().get(asset_id).copied())
+    }
 }
 
 pub struct Decimal;
@@ -159,6 +204,127 @@ impl pallet_farming::Config for Test {
     type LockPoolMaxDuration = LockPoolMaxDuration;
     type CoolDownMaxDuration = CoolDownMaxDuration;
     type Decimal = Decimal;
+    type AMM = DefaultAMM;
+    type Router = AMMRoute;
+    type MaxVaultHarvestsPerBlock = MaxVaultHarvestsPerBlock;
+    type PriceFeeder = MockPriceFeeder;
+    type MaxHarvestSlippage = MaxHarvestSlippage;
+    type MinimumVaultShares = MinimumVaultShares;
+    type MaxExtraRewardAssets = MaxExtraRewardAssets;
+    type VotingEscrow = VoteEscrow;
+    type GaugeController = VoteEscrow;
+    type MaxBoost = MaxBoost;
+}
+
+parameter_types! {
+    pub const VoteEscrowPalletId: PalletId = PalletId(*b"par/vote");
+    pub const EpochDuration: BlockNumber = 10;
+    pub const MaxLockDuration: BlockNumber = 100;
+    pub const MaxGauges: u32 = 3;
+}
+
+impl pallet_vote_escrow::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Assets = CurrencyAdapter;
+    type PalletId = VoteEscrowPalletId;
+    type LockCurrencyId = NativeCurrencyId;
+    type EpochDuration = EpochDuration;
+    type MaxLockDuration = MaxLockDuration;
+    type GaugeId = (CurrencyId, CurrencyId, BlockNumber);
+    type MaxGauges = MaxGauges;
+    type UpdateOrigin = EnsureRoot<AccountId>;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const AMMPalletId: PalletId = PalletId(*b"par/ammp");
+    pub DefaultLpFee: Ratio = Ratio::from_rational(25u32, 10000u32);
+    pub const MinimumLiquidity: u128 = 1_000u128;
+    pub const MaxTicksPerPool: u32 = 1_000;
+    pub const MinPositionLiquidity: u128 = 1_000_000u128;
+    pub const LockAccountId: AccountId = ALICE;
+}
+
+pub struct AliceCreatePoolOrigin;
+impl SortedMembers<AccountId> for AliceCreatePoolOrigin {
+    fn sorted_members() -> Vec<AccountId> {
+        vec![ALICE]
+    }
+}
+
+impl pallet_amm::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Assets = CurrencyAdapter;
+    type PalletId = AMMPalletId;
+    type LockAccountId = LockAccountId;
+    type AMMWeightInfo = ();
+    type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type ProtocolFeeUpdateOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type LpFee = DefaultLpFee;
+    type MinimumLiquidity = MinimumLiquidity;
+    type MaxLengthRoute = MaxLengthRoute;
+    type GetNativeCurrencyId = NativeCurrencyId;
+    type MaxTicksPerPool = MaxTicksPerPool;
+    type MinPositionLiquidity = MinPositionLiquidity;
+    type PoolsHook = AMMRoute;
+}
+
+parameter_types! {
+    pub const MaxLengthRoute: u8 = 10;
+    pub const MaxRouteSplits: u32 = 4;
+    pub const MaxRoutePools: u32 = 100;
+    pub const RouterPalletId: PalletId = PalletId(*b"ammroute");
+}
+
+impl pallet_router::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type PalletId = RouterPalletId;
+    type AMM = DefaultAMM;
+    type StableSwap = StableSwap;
+    type AMMRouterWeightInfo = ();
+    type MaxLengthRoute = MaxLengthRoute;
+    type MaxRouteSplits = MaxRouteSplits;
+    type MaxRoutePools = MaxRoutePools;
+    type Assets = CurrencyAdapter;
+    type GetNativeCurrencyId = NativeCurrencyId;
+}
+
+parameter_types! {
+    pub const StableSwapPalletId: PalletId = PalletId(*b"par/sswp");
+    pub const NumTokens: u8 = 2;
+    pub const Precision: u128 = 100;
+    pub const AmplificationCoefficient: u8 = 85;
+    pub const MaxPoolAssets: u32 = 4;
+    pub const MinRampBlocks: u64 = 10;
+    pub DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);
+    pub const DefaultProtocolFeeReceiver: AccountId = PROTOCOL_FEE_RECEIVER;
+}
+
+pub struct LiquidStakingExchangeRateProvider;
+impl pallet_traits::ExchangeRateProvider<CurrencyId> for LiquidStakingExchangeRateProvider {
+    fn get_exchange_rate(_: &CurrencyId) -> Option<Rate> {
+        Some(Rate::one())
+    }
+}
+
+impl pallet_stableswap::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Assets = CurrencyAdapter;
+    type WeightInfo = ();
+    type PalletId = StableSwapPalletId;
+    type NumTokens = NumTokens;
+    type Precision = Precision;
+    type AmplificationCoefficient = AmplificationCoefficient;
+    type MaxPoolAssets = MaxPoolAssets;
+    type MinRampBlocks = MinRampBlocks;
+    type ProtocolFeeReceiver = DefaultProtocolFeeReceiver;
+    type LpFee = DefaultLpFee;
+    type LockAccountId = LockAccountId;
+    type ProtocolFee = DefaultProtocolFee;
+    type MinimumLiquidity = MinimumLiquidity;
+    type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type LiquidStakingExchangeRateProvider = LiquidStakingExchangeRateProvider;
+    type PoolsHook = AMMRoute;
 }
 
 parameter_types! {
@@ -211,6 +377,26 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
         )
         .unwrap();
 
+        Assets::force_create(
+            RuntimeOrigin::root(),
+            EXTRA_REWARD_TOKEN.into(),
+            REWARD_TOKEN_PAYER,
+            true,
+            1,
+        )
+        .unwrap();
+        Assets::force_create(RuntimeOrigin::root(), QUOTE_TOKEN.into(), ALICE, true, 1).unwrap();
+        Assets::force_create(RuntimeOrigin::root(), LP_TOKEN.into(), ALICE, true, 1).unwrap();
+        Assets::force_create(
+            RuntimeOrigin::root(),
+            REWARD_LP_TOKEN.into(),
+            ALICE,
+            true,
+            1,
+        )
+        .unwrap();
+        Assets::force_create(RuntimeOrigin::root(), VAULT_TOKEN.into(), ALICE, true, 1).unwrap();
+
         Assets::mint(
             RuntimeOrigin::signed(ALICE),
             STAKE_TOKEN.into(),
@@ -218,6 +404,13 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
             500_000_000,
         )
         .unwrap();
+        Assets::mint(
+            RuntimeOrigin::signed(ALICE),
+            QUOTE_TOKEN.into(),
+            ALICE,
+            500_000_000,
+        )
+        .unwrap();
         Assets::mint(
             RuntimeOrigin::signed(ALICE),
             STAKE_TOKEN.into(),
@@ -239,6 +432,13 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
             3_000_000_000_000_000,
         )
         .unwrap();
+        Assets::mint(
+            RuntimeOrigin::signed(REWARD_TOKEN_PAYER),
+            EXTRA_REWARD_TOKEN.into(),
+            REWARD_TOKEN_PAYER,
+            3_000_000_000_000_000,
+        )
+        .unwrap();
         Assets::mint(
             RuntimeOrigin::signed(ALICE),
             BIG_DECIMAL_STAKE_TOKEN.into(),
@@ -284,5 +484,6 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 pub(crate) fn run_to_block(n: BlockNumber) {
     for b in (System::block_number() + 1)..=n {
         System::set_block_number(b);
+        Farming::on_idle(b, Weight::MAX);
     }
 }
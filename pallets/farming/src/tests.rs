@@ -2,6 +2,7 @@ use super::*;
 use crate::mock::*;
 use frame_support::{assert_noop, assert_ok};
 use frame_system::RawOrigin;
+use primitives::{Price, Ratio};
 
 #[test]
 fn pool_create_work() {
@@ -711,6 +712,210 @@ fn pool_claim_work() {
     })
 }
 
+#[test]
+fn pool_add_extra_reward_asset_work() {
+    new_test_ext().execute_with(|| {
+        // 1, can not add extra reward asset to a pool which is not exists
+        assert_noop!(
+            Farming::add_extra_reward_asset(
+                RuntimeOrigin::root(),
+                EHKO,
+                REWARD_TOKEN,
+                LOCK_DURATION,
+                EXTRA_REWARD_TOKEN,
+            ),
+            Error::<Test>::PoolDoesNotExist,
+        );
+
+        // 2, can not add the pool reward asset
+        assert_noop!(
+            Farming::add_extra_reward_asset(
+                RuntimeOrigin::root(),
+                STAKE_TOKEN,
+                REWARD_TOKEN,
+                LOCK_DURATION,
+                REWARD_TOKEN,
+            ),
+            Error::<Test>::ExtraRewardAssetAlreadyExists,
+        );
+
+        // 3, can not dispatch reward of an extra reward asset which is not added
+        assert_noop!(
+            Farming::dispatch_extra_reward(
+                RuntimeOrigin::root(),
+                STAKE_TOKEN,
+                REWARD_TOKEN,
+                LOCK_DURATION,
+                EXTRA_REWARD_TOKEN,
+                REWARD_TOKEN_PAYER,
+                1_000,
+                100,
+            ),
+            Error::<Test>::ExtraRewardAssetDoesNotExist,
+        );
+
+        assert_ok!(Farming::add_extra_reward_asset(
+            RuntimeOrigin::root(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            EXTRA_REWARD_TOKEN,
+        ));
+        System::assert_last_event(RuntimeEvent::Farming(crate::Event::ExtraRewardAssetAdded(
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            EXTRA_REWARD_TOKEN,
+        )));
+
+        // 4, can not add the same extra reward asset twice
+        assert_noop!(
+            Farming::add_extra_reward_asset(
+                RuntimeOrigin::root(),
+                STAKE_TOKEN,
+                REWARD_TOKEN,
+                LOCK_DURATION,
+                EXTRA_REWARD_TOKEN,
+            ),
+            Error::<Test>::ExtraRewardAssetAlreadyExists,
+        );
+
+        // 5, can not add more than MaxExtraRewardAssets
+        assert_ok!(Farming::add_extra_reward_asset(
+            RuntimeOrigin::root(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            BIG_DECIMAL_REWARD_TOKEN,
+        ));
+        assert_ok!(Farming::add_extra_reward_asset(
+            RuntimeOrigin::root(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            EHKO,
+        ));
+        assert_noop!(
+            Farming::add_extra_reward_asset(
+                RuntimeOrigin::root(),
+                STAKE_TOKEN,
+                REWARD_TOKEN,
+                LOCK_DURATION,
+                QUOTE_TOKEN,
+            ),
+            Error::<Test>::ExcessMaxExtraRewardAssetsCount,
+        );
+    })
+}
+
+#[test]
+fn pool_claim_extra_reward_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Farming::add_extra_reward_asset(
+            RuntimeOrigin::root(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            EXTRA_REWARD_TOKEN,
+        ));
+        assert_ok!(Farming::deposit(
+            RawOrigin::Signed(ALICE).into(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            100_000_000,
+        ));
+        assert_ok!(Farming::deposit(
+            RawOrigin::Signed(BOB).into(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            100_000_000,
+        ));
+
+        run_to_block(10);
+        assert_ok!(Farming::dispatch_reward(
+            RuntimeOrigin::root(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            REWARD_TOKEN_PAYER,
+            1_000_000_000_000_000,
+            100,
+        ));
+        assert_ok!(Farming::dispatch_extra_reward(
+            RuntimeOrigin::root(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            EXTRA_REWARD_TOKEN,
+            REWARD_TOKEN_PAYER,
+            1_000_000_000_000_000,
+            100,
+        ));
+        System::assert_last_event(RuntimeEvent::Farming(crate::Event::ExtraRewardAdded(
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            EXTRA_REWARD_TOKEN,
+            1_000_000_000_000_000,
+        )));
+        let reward_info =
+            Farming::extra_rewards((STAKE_TOKEN, REWARD_TOKEN, LOCK_DURATION, EXTRA_REWARD_TOKEN));
+        assert_eq!(reward_info.reward_rate, 10_000_000_000_000);
+        assert_eq!(reward_info.period_finish, 110);
+
+        // one claim pays every reward stream
+        run_to_block(60);
+        assert_ok!(Farming::claim(
+            RawOrigin::Signed(ALICE).into(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+        ));
+        assert_eq!(
+            <Test as Config>::Assets::balance(REWARD_TOKEN, &ALICE),
+            250_000_000_000_000
+        );
+        assert_eq!(
+            <Test as Config>::Assets::balance(EXTRA_REWARD_TOKEN, &ALICE),
+            250_000_000_000_000
+        );
+        System::assert_last_event(RuntimeEvent::Farming(crate::Event::ExtraRewardPaid(
+            ALICE,
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            EXTRA_REWARD_TOKEN,
+            250_000_000_000_000,
+        )));
+
+        // streams keep running after a withdraw changes the pool deposit
+        assert_ok!(Farming::withdraw(
+            RawOrigin::Signed(ALICE).into(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            100_000_000,
+        ));
+        run_to_block(110);
+        assert_ok!(Farming::claim(
+            RawOrigin::Signed(BOB).into(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+        ));
+        assert_eq!(
+            <Test as Config>::Assets::balance(REWARD_TOKEN, &BOB),
+            750_000_000_000_000
+        );
+        assert_eq!(
+            <Test as Config>::Assets::balance(EXTRA_REWARD_TOKEN, &BOB),
+            750_000_000_000_000
+        );
+    })
+}
+
 #[test]
 fn pool_claim_precision_work() {
     new_test_ext().execute_with(|| {
@@ -1095,3 +1300,547 @@ fn pool_withdraw_without_keep_alive_work() {
         ));
     })
 }
+
+fn create_vault_pools() {
+    // the vault pool is staking the STAKE_TOKEN / QUOTE_TOKEN liquidity
+    assert_ok!(DefaultAMM::create_pool(
+        RawOrigin::Signed(ALICE).into(),
+        (STAKE_TOKEN, QUOTE_TOKEN),
+        (100_000_000, 100_000_000),
+        ALICE,
+        LP_TOKEN,
+        DefaultLpFee::get(),
+        None,
+    ));
+    assert_ok!(Farming::create(
+        RuntimeOrigin::root(),
+        LP_TOKEN,
+        REWARD_TOKEN,
+        0,
+        0,
+    ));
+    assert_ok!(Farming::set_pool_status(
+        RuntimeOrigin::root(),
+        LP_TOKEN,
+        REWARD_TOKEN,
+        0,
+        true,
+    ));
+}
+
+fn vault_deposit_balance() -> Balance {
+    let vault_account = Farming::vault_account_id(VAULT_TOKEN).unwrap();
+    Farming::positions((LP_TOKEN, REWARD_TOKEN, 0, vault_account)).deposit_balance
+}
+
+fn create_vault_with_rewards() {
+    create_vault_pools();
+    // REWARD_TOKEN is routed to STAKE_TOKEN before being zapped in
+    assert_ok!(Assets::mint(
+        RuntimeOrigin::signed(REWARD_TOKEN_PAYER),
+        REWARD_TOKEN.into(),
+        ALICE,
+        100_000_000,
+    ));
+    assert_ok!(DefaultAMM::create_pool(
+        RawOrigin::Signed(ALICE).into(),
+        (REWARD_TOKEN, STAKE_TOKEN),
+        (100_000_000, 100_000_000),
+        ALICE,
+        REWARD_LP_TOKEN,
+        DefaultLpFee::get(),
+        None,
+    ));
+    assert_ok!(Farming::create_vault(
+        RuntimeOrigin::root(),
+        LP_TOKEN,
+        REWARD_TOKEN,
+        0,
+        VAULT_TOKEN,
+        5,
+    ));
+    assert_ok!(Farming::deposit_to_vault(
+        RawOrigin::Signed(ALICE).into(),
+        VAULT_TOKEN,
+        1_000_000,
+    ));
+    assert_ok!(Farming::dispatch_reward(
+        RuntimeOrigin::root(),
+        LP_TOKEN,
+        REWARD_TOKEN,
+        0,
+        REWARD_TOKEN_PAYER,
+        100_000,
+        10,
+    ));
+}
+
+#[test]
+fn vault_create_work() {
+    new_test_ext().execute_with(|| {
+        create_vault_pools();
+
+        // 1, create vault of a pool does not exist
+        assert_noop!(
+            Farming::create_vault(RuntimeOrigin::root(), LP_TOKEN, EHKO, 0, VAULT_TOKEN, 5),
+            Error::<Test>::PoolDoesNotExist,
+        );
+
+        // 2, create vault of a pool with cool down duration
+        assert_noop!(
+            Farming::create_vault(
+                RuntimeOrigin::root(),
+                STAKE_TOKEN,
+                REWARD_TOKEN,
+                LOCK_DURATION,
+                VAULT_TOKEN,
+                5,
+            ),
+            Error::<Test>::PoolHasCoolDownDuration,
+        );
+
+        // 3, create vault of a pool not staking liquidity
+        assert_ok!(Farming::create(
+            RuntimeOrigin::root(),
+            QUOTE_TOKEN,
+            REWARD_TOKEN,
+            0,
+            0,
+        ));
+        assert_noop!(
+            Farming::create_vault(
+                RuntimeOrigin::root(),
+                QUOTE_TOKEN,
+                REWARD_TOKEN,
+                0,
+                VAULT_TOKEN,
+                5,
+            ),
+            Error::<Test>::NotALiquidityToken,
+        );
+
+        // 4, create vault with an issued vault token
+        assert_noop!(
+            Farming::create_vault(
+                RuntimeOrigin::root(),
+                LP_TOKEN,
+                REWARD_TOKEN,
+                0,
+                STAKE_TOKEN,
+                5
+            ),
+            Error::<Test>::VaultAlreadyExists,
+        );
+
+        // 5, create vault with an invalid harvest interval
+        assert_noop!(
+            Farming::create_vault(
+                RuntimeOrigin::root(),
+                LP_TOKEN,
+                REWARD_TOKEN,
+                0,
+                VAULT_TOKEN,
+                0
+            ),
+            Error::<Test>::NotAValidDuration,
+        );
+
+        assert_ok!(Farming::create_vault(
+            RuntimeOrigin::root(),
+            LP_TOKEN,
+            REWARD_TOKEN,
+            0,
+            VAULT_TOKEN,
+            5,
+        ));
+        let vault = Farming::vaults(VAULT_TOKEN).unwrap();
+        assert_eq!(vault.asset, LP_TOKEN);
+        assert_eq!(vault.next_harvest(), 6);
+        assert!(<Farming as LPVaultTokenCurrenciesFilter<CurrencyId>>::contains(&VAULT_TOKEN));
+        assert!(!<Farming as LPVaultTokenCurrenciesFilter<CurrencyId>>::contains(&LP_TOKEN));
+
+        // 6, create vault already exists
+        assert_noop!(
+            Farming::create_vault(
+                RuntimeOrigin::root(),
+                LP_TOKEN,
+                REWARD_TOKEN,
+                0,
+                VAULT_TOKEN,
+                5
+            ),
+            Error::<Test>::VaultAlreadyExists,
+        );
+    })
+}
+
+#[test]
+fn vault_deposit_and_withdraw_work() {
+    new_test_ext().execute_with(|| {
+        create_vault_pools();
+        assert_ok!(Farming::create_vault(
+            RuntimeOrigin::root(),
+            LP_TOKEN,
+            REWARD_TOKEN,
+            0,
+            VAULT_TOKEN,
+            5,
+        ));
+        let lp_balance = <Test as Config>::Assets::balance(LP_TOKEN, &ALICE);
+
+        assert_noop!(
+            Farming::deposit_to_vault(RawOrigin::Signed(ALICE).into(), VAULT_TOKEN, 0),
+            Error::<Test>::NotAValidAmount,
+        );
+        // the first deposit has to cover the minimum vault shares
+        assert_noop!(
+            Farming::deposit_to_vault(RawOrigin::Signed(ALICE).into(), VAULT_TOKEN, 1_000),
+            Error::<Test>::NotAValidAmount,
+        );
+        assert_ok!(Farming::deposit_to_vault(
+            RawOrigin::Signed(ALICE).into(),
+            VAULT_TOKEN,
+            1_000_000,
+        ));
+
+        // the first deposit is one vault token per staking asset,
+        // the minimum vault shares of it are locked in the vault
+        let vault_account = Farming::vault_account_id(VAULT_TOKEN).unwrap();
+        assert_eq!(
+            <Test as Config>::Assets::balance(VAULT_TOKEN, &ALICE),
+            999_000
+        );
+        assert_eq!(
+            <Test as Config>::Assets::balance(VAULT_TOKEN, &vault_account),
+            1_000
+        );
+        assert_eq!(
+            <Test as Config>::Assets::balance(LP_TOKEN, &ALICE),
+            lp_balance - 1_000_000
+        );
+        assert_eq!(vault_deposit_balance(), 1_000_000);
+        assert_eq!(
+            <Farming as LPVaultTokenExchangeRateProvider<CurrencyId>>::get_exchange_rate(
+                &VAULT_TOKEN
+            ),
+            Some(Rate::one())
+        );
+
+        assert_ok!(Farming::withdraw_from_vault(
+            RawOrigin::Signed(ALICE).into(),
+            VAULT_TOKEN,
+            400_000,
+        ));
+        assert_eq!(
+            <Test as Config>::Assets::balance(VAULT_TOKEN, &ALICE),
+            599_000
+        );
+        assert_eq!(
+            <Test as Config>::Assets::balance(LP_TOKEN, &ALICE),
+            lp_balance - 600_000
+        );
+        assert_eq!(vault_deposit_balance(), 600_000);
+
+        assert!(Farming::withdraw_from_vault(
+            RawOrigin::Signed(ALICE).into(),
+            VAULT_TOKEN,
+            599_001,
+        )
+        .is_err());
+    })
+}
+
+#[test]
+fn vault_harvest_work() {
+    new_test_ext().execute_with(|| {
+        create_vault_with_rewards();
+
+        run_to_block(5);
+        assert_noop!(
+            Farming::harvest(RawOrigin::Signed(BOB).into(), VAULT_TOKEN),
+            Error::<Test>::VaultNotReadyToHarvest,
+        );
+
+        // the due vault is harvested in on_idle
+        run_to_block(6);
+        let vault = Farming::vaults(VAULT_TOKEN).unwrap();
+        assert_eq!(vault.last_harvest, 6);
+        let vault_account = Farming::vault_account_id(VAULT_TOKEN).unwrap();
+        let position = Farming::positions((LP_TOKEN, REWARD_TOKEN, 0, vault_account));
+        assert_eq!(position.reward_amount, 0);
+        let compounded = vault_deposit_balance() - 1_000_000;
+        assert!(compounded > 24_000);
+        System::assert_last_event(RuntimeEvent::Farming(crate::Event::VaultHarvested(
+            VAULT_TOKEN,
+            compounded,
+        )));
+
+        // vault tokens are now worth more staking asset
+        let rate = <Farming as LPVaultTokenExchangeRateProvider<CurrencyId>>::get_exchange_rate(
+            &VAULT_TOKEN,
+        )
+        .unwrap();
+        assert!(rate > Rate::one());
+
+        let lp_balance = <Test as Config>::Assets::balance(LP_TOKEN, &ALICE);
+        let withdrawn = 999_000 * (1_000_000 + compounded) / 1_000_000;
+        assert_ok!(Farming::withdraw_from_vault(
+            RawOrigin::Signed(ALICE).into(),
+            VAULT_TOKEN,
+            999_000,
+        ));
+        assert_eq!(
+            <Test as Config>::Assets::balance(LP_TOKEN, &ALICE),
+            lp_balance + withdrawn
+        );
+        assert_eq!(vault_deposit_balance(), 1_000_000 + compounded - withdrawn);
+    })
+}
+
+#[test]
+fn vault_deposit_harvest_first_work() {
+    new_test_ext().execute_with(|| {
+        create_vault_with_rewards();
+        assert_ok!(Assets::transfer(
+            RuntimeOrigin::signed(ALICE),
+            LP_TOKEN.into(),
+            BOB,
+            1_000_000,
+        ));
+
+        // the rewards earned so far are compounded before BOB's shares are minted
+        run_to_block(3);
+        assert_ok!(Farming::deposit_to_vault(
+            RawOrigin::Signed(BOB).into(),
+            VAULT_TOKEN,
+            1_000_000,
+        ));
+        let vault_account = Farming::vault_account_id(VAULT_TOKEN).unwrap();
+        let position = Farming::positions((LP_TOKEN, REWARD_TOKEN, 0, vault_account));
+        assert_eq!(position.reward_amount, 0);
+        assert_eq!(Farming::vaults(VAULT_TOKEN).unwrap().last_harvest, 3);
+
+        let shares = <Test as Config>::Assets::balance(VAULT_TOKEN, &BOB);
+        assert!(shares < 1_000_000);
+        assert_eq!(
+            shares,
+            1_000_000 * 1_000_000 / (vault_deposit_balance() - 1_000_000)
+        );
+    })
+}
+
+#[test]
+fn vault_harvest_bounded_by_oracle_price_work() {
+    new_test_ext().execute_with(|| {
+        create_vault_with_rewards();
+
+        // the router pays one STAKE_TOKEN per REWARD_TOKEN, half the oracle price
+        MockPriceFeeder::set_price(REWARD_TOKEN, Price::saturating_from_integer(2));
+        run_to_block(6);
+        assert!(Farming::harvest(RawOrigin::Signed(BOB).into(), VAULT_TOKEN).is_err());
+
+        // the vault is harvested once the trade is within the max harvest slippage
+        MockPriceFeeder::set_price(REWARD_TOKEN, Price::saturating_from_integer(1));
+        assert_ok!(Farming::harvest(RawOrigin::Signed(BOB).into(), VAULT_TOKEN));
+        let vault_account = Farming::vault_account_id(VAULT_TOKEN).unwrap();
+        let position = Farming::positions((LP_TOKEN, REWARD_TOKEN, 0, vault_account));
+        assert_eq!(position.reward_amount, 0);
+
+        // no price, no harvest
+        MockPriceFeeder::PRICES.with(|prices| prices.borrow_mut().remove(&REWARD_TOKEN));
+        run_to_block(11);
+        assert_noop!(
+            Farming::harvest(RawOrigin::Signed(BOB).into(), VAULT_TOKEN),
+            Error::<Test>::PriceNotReady,
+        );
+    })
+}
+
+#[test]
+fn pool_boost_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Farming::deposit(
+            RawOrigin::Signed(ALICE).into(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            100_000_000,
+        ));
+        assert_ok!(Farming::deposit(
+            RawOrigin::Signed(BOB).into(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            100_000_000,
+        ));
+        // without voting power working balances are deposits
+        assert_eq!(
+            Farming::working_supply((STAKE_TOKEN, REWARD_TOKEN, LOCK_DURATION)),
+            Some(200_000_000)
+        );
+
+        // ALICE owns all the voting power, her deposit gets the max boost
+        run_to_block(10);
+        assert_ok!(VoteEscrow::create_lock(
+            RawOrigin::Signed(ALICE).into(),
+            1_000_000,
+            110,
+        ));
+        assert_ok!(Farming::update_boost(
+            RawOrigin::Signed(BOB).into(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            ALICE,
+        ));
+        System::assert_last_event(RuntimeEvent::Farming(crate::Event::BoostUpdated(
+            ALICE,
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            250_000_000,
+        )));
+        assert_eq!(
+            Farming::working_supply((STAKE_TOKEN, REWARD_TOKEN, LOCK_DURATION)),
+            Some(350_000_000)
+        );
+
+        assert_ok!(Farming::dispatch_reward(
+            RuntimeOrigin::root(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            REWARD_TOKEN_PAYER,
+            1_000_000_000_000_000,
+            100,
+        ));
+
+        // rewards are shared 250 to 100
+        run_to_block(80);
+        assert_ok!(Farming::claim(
+            RawOrigin::Signed(ALICE).into(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+        ));
+        assert_ok!(Farming::claim(
+            RawOrigin::Signed(BOB).into(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+        ));
+        assert_eq!(
+            <Test as Config>::Assets::balance(REWARD_TOKEN, &ALICE),
+            500_000_000_000_000
+        );
+        assert_eq!(
+            <Test as Config>::Assets::balance(REWARD_TOKEN, &BOB),
+            200_000_000_000_000
+        );
+
+        // withdrawing the whole deposit removes the working balance
+        assert_ok!(Farming::withdraw(
+            RawOrigin::Signed(ALICE).into(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            LOCK_DURATION,
+            100_000_000,
+        ));
+        assert_eq!(
+            Farming::working_balances((STAKE_TOKEN, REWARD_TOKEN, LOCK_DURATION, ALICE)),
+            None
+        );
+        assert_eq!(
+            Farming::working_supply((STAKE_TOKEN, REWARD_TOKEN, LOCK_DURATION)),
+            Some(100_000_000)
+        );
+    })
+}
+
+#[test]
+fn dispatch_gauge_rewards_work() {
+    new_test_ext().execute_with(|| {
+        let pool_1 = (STAKE_TOKEN, REWARD_TOKEN, LOCK_DURATION);
+        let pool_2 = (STAKE_TOKEN, REWARD_TOKEN, 0);
+        let other_reward_pool = (STAKE_TOKEN, EXTRA_REWARD_TOKEN, 0);
+        assert_ok!(Farming::create(
+            RuntimeOrigin::root(),
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            0,
+            0,
+        ));
+        for gauge in [pool_1, pool_2, other_reward_pool] {
+            assert_ok!(VoteEscrow::add_gauge(RuntimeOrigin::root(), gauge));
+        }
+
+        assert_ok!(VoteEscrow::create_lock(
+            RawOrigin::Signed(ALICE).into(),
+            1_000_000,
+            100,
+        ));
+        assert_ok!(VoteEscrow::create_lock(
+            RawOrigin::Signed(BOB).into(),
+            1_000_000,
+            100,
+        ));
+        assert_ok!(VoteEscrow::vote_for_gauge(
+            RawOrigin::Signed(ALICE).into(),
+            pool_1,
+            Ratio::one(),
+        ));
+        assert_ok!(VoteEscrow::vote_for_gauge(
+            RawOrigin::Signed(BOB).into(),
+            pool_2,
+            Ratio::from_percent(50),
+        ));
+        assert_ok!(VoteEscrow::vote_for_gauge(
+            RawOrigin::Signed(BOB).into(),
+            other_reward_pool,
+            Ratio::from_percent(50),
+        ));
+
+        // votes apply from the next epoch
+        assert_noop!(
+            Farming::dispatch_gauge_rewards(
+                RuntimeOrigin::root(),
+                REWARD_TOKEN,
+                REWARD_TOKEN_PAYER,
+                900_000_000_000_000,
+                100,
+            ),
+            Error::<Test>::NoGaugeWeights,
+        );
+
+        // gauges of other reward assets are left out
+        run_to_block(10);
+        assert_ok!(Farming::dispatch_gauge_rewards(
+            RuntimeOrigin::root(),
+            REWARD_TOKEN,
+            REWARD_TOKEN_PAYER,
+            900_000_000_000_000,
+            100,
+        ));
+        System::assert_last_event(RuntimeEvent::Farming(crate::Event::RewardAdded(
+            STAKE_TOKEN,
+            REWARD_TOKEN,
+            0,
+            300_000_000_000_000,
+        )));
+        assert_eq!(
+            Farming::pools(pool_1).unwrap().reward_rate,
+            6_000_000_000_000
+        );
+        assert_eq!(
+            Farming::pools(pool_2).unwrap().reward_rate,
+            3_000_000_000_000
+        );
+        assert_eq!(
+            <Test as Config>::Assets::balance(
+                REWARD_TOKEN,
+                &Farming::pool_account_id(REWARD_TOKEN).unwrap()
+            ),
+            900_000_000_000_000
+        );
+    })
+}
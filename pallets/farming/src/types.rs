@@ -61,13 +61,14 @@ impl<
         }
     }
 
-    /// Calculate reward amount for one share of staking asset.
+    /// Calculate reward amount for one share out of the pool working supply.
     /// Return ArithmeticError if it encounter an arithmetic error.
     pub fn reward_per_share(
         &self,
         current_block_number: BlockNumber,
+        working_supply: BalanceOf,
     ) -> Result<BalanceOf, ArithmeticError> {
-        if self.total_deposited.is_zero() {
+        if working_supply.is_zero() {
             Ok(self.reward_per_share_stored)
         } else {
             let last_reward_block = self.last_reward_block_applicable(current_block_number);
@@ -77,7 +78,7 @@ impl<
                 .get_big_uint()
                 .checked_mul(&self.reward_rate.get_big_uint())
                 .and_then(|r| r.checked_mul(&self.amount_per_share().get_big_uint()))
-                .and_then(|r| r.checked_div(&self.total_deposited.get_big_uint()))
+                .and_then(|r| r.checked_div(&working_supply.get_big_uint()))
                 .and_then(|r| r.to_u128())
                 .ok_or(ArithmeticError::Overflow)?;
 
@@ -94,8 +95,10 @@ impl<
     pub fn update_reward_per_share(
         &mut self,
         current_block_number: BlockNumber,
+        working_supply: BalanceOf,
     ) -> Result<(), ArithmeticError> {
-        self.reward_per_share_stored = self.reward_per_share(current_block_number)?;
+        self.reward_per_share_stored =
+            self.reward_per_share(current_block_number, working_supply)?;
         self.last_update_block = self.last_reward_block_applicable(current_block_number);
 
         Ok(())
@@ -110,6 +113,149 @@ impl<
     }
 }
 
+/// Reward stream of an extra reward asset of a pool, it is accounted like the pool
+/// reward asset against the pool working balances
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct RewardInfo<BlockNumber, BalanceOf> {
+    /// current reward duration
+    pub reward_duration: BlockNumber,
+    /// block number of reward ends
+    pub period_finish: BlockNumber,
+    /// block number of last reward update
+    pub last_update_block: BlockNumber,
+    /// reward number for one block.
+    pub reward_rate: BalanceOf,
+    /// reward index for one share staking asset.
+    pub reward_per_share_stored: BalanceOf,
+}
+
+impl<
+        BlockNumber: Copy + PartialOrd + Saturating + UniqueSaturatedInto<u128>,
+        BalanceOf: ConvertToBigUint + TokenBalance,
+    > RewardInfo<BlockNumber, BalanceOf>
+{
+    /// Return valid reward block for current block number.
+    pub fn last_reward_block_applicable(&self, current_block_number: BlockNumber) -> BlockNumber {
+        if current_block_number > self.period_finish {
+            self.period_finish
+        } else {
+            current_block_number
+        }
+    }
+
+    /// Calculate reward amount for one share out of the pool working supply.
+    /// Return ArithmeticError if it encounter an arithmetic error.
+    pub fn reward_per_share(
+        &self,
+        current_block_number: BlockNumber,
+        working_supply: BalanceOf,
+    ) -> Result<BalanceOf, ArithmeticError> {
+        if working_supply.is_zero() {
+            Ok(self.reward_per_share_stored)
+        } else {
+            let last_reward_block = self.last_reward_block_applicable(current_block_number);
+            let block_diff =
+                self.block_to_balance(last_reward_block.saturating_sub(self.last_update_block));
+            let reward_per_share_add = block_diff
+                .get_big_uint()
+                .checked_mul(&self.reward_rate.get_big_uint())
+                .and_then(|r| r.checked_mul(&self.amount_per_share().get_big_uint()))
+                .and_then(|r| r.checked_div(&working_supply.get_big_uint()))
+                .and_then(|r| r.to_u128())
+                .ok_or(ArithmeticError::Overflow)?;
+
+            let ret = self
+                .reward_per_share_stored
+                .checked_add(&BalanceOf::saturated_from(reward_per_share_add))
+                .ok_or(ArithmeticError::Overflow)?;
+            Ok(ret)
+        }
+    }
+
+    /// Update reward amount for one share of staking asset and updating block.
+    /// Return ArithmeticError if it encounter an arithmetic error.
+    pub fn update_reward_per_share(
+        &mut self,
+        current_block_number: BlockNumber,
+        working_supply: BalanceOf,
+    ) -> Result<(), ArithmeticError> {
+        self.reward_per_share_stored =
+            self.reward_per_share(current_block_number, working_supply)?;
+        self.last_update_block = self.last_reward_block_applicable(current_block_number);
+
+        Ok(())
+    }
+
+    /// Calculate the reward earned by a working balance since the reward index it was paid.
+    /// Return ArithmeticError if it encounter an arithmetic error.
+    pub fn earned(
+        &self,
+        working_balance: BalanceOf,
+        position: &RewardPosition<BalanceOf>,
+    ) -> Result<BalanceOf, ArithmeticError> {
+        let diff = self
+            .reward_per_share_stored
+            .checked_sub(&position.reward_per_share_paid)
+            .ok_or(ArithmeticError::Overflow)?;
+
+        let earned = working_balance
+            .get_big_uint()
+            .checked_mul(&diff.get_big_uint())
+            .and_then(|r| r.checked_div(&self.amount_per_share().get_big_uint()))
+            .and_then(|r| r.checked_add(&position.reward_amount.get_big_uint()))
+            .and_then(|r| r.to_u128())
+            .ok_or(ArithmeticError::Overflow)?;
+        Ok(BalanceOf::saturated_from(earned))
+    }
+
+    /// Start a new reward period of `amount` lasting `reward_duration` blocks, the
+    /// reward left over from the current period is carried over.
+    /// Return ArithmeticError if it encounter an arithmetic error.
+    pub fn notify_reward_amount(
+        &mut self,
+        current_block_number: BlockNumber,
+        amount: BalanceOf,
+        reward_duration: BlockNumber,
+    ) -> Result<(), ArithmeticError> {
+        let duration_balance = self.block_to_balance(reward_duration);
+        let total = if current_block_number >= self.period_finish {
+            amount
+        } else {
+            let remaining = self.period_finish.saturating_sub(current_block_number);
+            self.block_to_balance(remaining)
+                .checked_mul(&self.reward_rate)
+                .and_then(|left_over| left_over.checked_add(&amount))
+                .ok_or(ArithmeticError::Overflow)?
+        };
+
+        self.reward_rate = total
+            .checked_div(&duration_balance)
+            .ok_or(ArithmeticError::DivisionByZero)?;
+        self.reward_duration = reward_duration;
+        self.period_finish = current_block_number.saturating_add(reward_duration);
+        self.last_update_block = current_block_number;
+
+        Ok(())
+    }
+
+    pub fn block_to_balance(&self, duration: BlockNumber) -> BalanceOf {
+        BalanceOf::saturated_from(duration.saturated_into())
+    }
+
+    pub fn amount_per_share(&self) -> BalanceOf {
+        BalanceOf::saturated_from(10_u64.pow(12))
+    }
+}
+
+/// User position in a reward stream of an extra reward asset
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct RewardPosition<BalanceOf> {
+    /// User pending reward amount
+    pub reward_amount: BalanceOf,
+    /// User reward index
+    pub reward_per_share_paid: BalanceOf,
+}
+
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct UserPosition<BalanceOf, BoundedBalance> {
     /// User balance in reward pool
@@ -134,3 +280,24 @@ impl<BalanceOf: Default, BoundedBalance: Default> Default
         }
     }
 }
+
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct VaultInfo<AssetId, BlockNumber> {
+    /// staking asset of the vault pool, an AMM liquidity token
+    pub asset: AssetId,
+    /// reward asset of the vault pool
+    pub reward_asset: AssetId,
+    /// lock duration of the vault pool
+    pub lock_duration: BlockNumber,
+    /// number of blocks between two harvests
+    pub harvest_interval: BlockNumber,
+    /// block number of last harvest
+    pub last_harvest: BlockNumber,
+}
+
+impl<AssetId, BlockNumber: Copy + Saturating> VaultInfo<AssetId, BlockNumber> {
+    /// Return the block number from which the vault can be harvested.
+    pub fn next_harvest(&self) -> BlockNumber {
+        self.last_harvest.saturating_add(self.harvest_interval)
+    }
+}
@@ -54,6 +54,13 @@ pub trait WeightInfo {
 	fn redeem() -> Weight;
 	fn claim() -> Weight;
 	fn dispatch_reward() -> Weight;
+	fn create_vault() -> Weight;
+	fn deposit_to_vault() -> Weight;
+	fn withdraw_from_vault() -> Weight;
+	fn harvest() -> Weight;
+	fn add_extra_reward_asset() -> Weight;
+	fn dispatch_extra_reward() -> Weight;
+	fn update_boost() -> Weight;
 }
 
 /// Weights for pallet_farming using the Substrate node and recommended hardware.
@@ -136,6 +143,81 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(6 as u64))
 			.saturating_add(T::DbWeight::get().writes(6 as u64))
 	}
+	// Storage: Farming Pools (r:1 w:0)
+	// Storage: AMM Pools (r:1 w:0)
+	// Storage: Farming Vaults (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:0)
+	fn create_vault() -> Weight {
+		Weight::from_ref_time(55_128_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Farming Vaults (r:1 w:0)
+	// Storage: Farming Pools (r:1 w:1)
+	// Storage: Farming Positions (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	// Storage: System Account (r:1 w:1)
+	fn deposit_to_vault() -> Weight {
+		Weight::from_ref_time(182_431_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(10 as u64))
+			.saturating_add(T::DbWeight::get().writes(9 as u64))
+	}
+	// Storage: Farming Vaults (r:1 w:0)
+	// Storage: Farming Pools (r:1 w:1)
+	// Storage: Farming Positions (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	// Storage: System Account (r:1 w:1)
+	fn withdraw_from_vault() -> Weight {
+		Weight::from_ref_time(191_806_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(10 as u64))
+			.saturating_add(T::DbWeight::get().writes(9 as u64))
+	}
+	// Storage: Farming Vaults (r:1 w:1)
+	// Storage: Farming Pools (r:1 w:1)
+	// Storage: Farming Positions (r:1 w:1)
+	// Storage: AMM Pools (r:2 w:2)
+	// Storage: AMM ProtocolFee (r:1 w:0)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:10 w:10)
+	// Storage: System Account (r:2 w:0)
+	fn harvest() -> Weight {
+		Weight::from_ref_time(523_659_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(21 as u64))
+			.saturating_add(T::DbWeight::get().writes(18 as u64))
+	}
+	// Storage: Farming Pools (r:1 w:0)
+	// Storage: Farming ExtraRewardAssets (r:1 w:1)
+	fn add_extra_reward_asset() -> Weight {
+		Weight::from_ref_time(38_214_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Farming ExtraRewardAssets (r:1 w:0)
+	// Storage: Farming Pools (r:1 w:1)
+	// Storage: Farming ExtraRewards (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn dispatch_extra_reward() -> Weight {
+		Weight::from_ref_time(152_940_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(8 as u64))
+			.saturating_add(T::DbWeight::get().writes(7 as u64))
+	}
+	// Storage: Farming Pools (r:2 w:1)
+	// Storage: Farming WorkingSupply (r:2 w:1)
+	// Storage: Farming Positions (r:2 w:1)
+	// Storage: Farming WorkingBalances (r:2 w:1)
+	// Storage: Farming ExtraRewardAssets (r:1 w:0)
+	// Storage: VoteEscrow Locked (r:1 w:0)
+	// Storage: VoteEscrow Supply (r:1 w:0)
+	fn update_boost() -> Weight {
+		Weight::from_ref_time(61_837_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(11 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -217,4 +299,79 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(6 as u64))
 			.saturating_add(RocksDbWeight::get().writes(6 as u64))
 	}
+	// Storage: Farming Pools (r:1 w:0)
+	// Storage: AMM Pools (r:1 w:0)
+	// Storage: Farming Vaults (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:0)
+	fn create_vault() -> Weight {
+		Weight::from_ref_time(55_128_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: Farming Vaults (r:1 w:0)
+	// Storage: Farming Pools (r:1 w:1)
+	// Storage: Farming Positions (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	// Storage: System Account (r:1 w:1)
+	fn deposit_to_vault() -> Weight {
+		Weight::from_ref_time(182_431_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(10 as u64))
+			.saturating_add(RocksDbWeight::get().writes(9 as u64))
+	}
+	// Storage: Farming Vaults (r:1 w:0)
+	// Storage: Farming Pools (r:1 w:1)
+	// Storage: Farming Positions (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	// Storage: System Account (r:1 w:1)
+	fn withdraw_from_vault() -> Weight {
+		Weight::from_ref_time(191_806_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(10 as u64))
+			.saturating_add(RocksDbWeight::get().writes(9 as u64))
+	}
+	// Storage: Farming Vaults (r:1 w:1)
+	// Storage: Farming Pools (r:1 w:1)
+	// Storage: Farming Positions (r:1 w:1)
+	// Storage: AMM Pools (r:2 w:2)
+	// Storage: AMM ProtocolFee (r:1 w:0)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:10 w:10)
+	// Storage: System Account (r:2 w:0)
+	fn harvest() -> Weight {
+		Weight::from_ref_time(523_659_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(21 as u64))
+			.saturating_add(RocksDbWeight::get().writes(18 as u64))
+	}
+	// Storage: Farming Pools (r:1 w:0)
+	// Storage: Farming ExtraRewardAssets (r:1 w:1)
+	fn add_extra_reward_asset() -> Weight {
+		Weight::from_ref_time(38_214_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Farming ExtraRewardAssets (r:1 w:0)
+	// Storage: Farming Pools (r:1 w:1)
+	// Storage: Farming ExtraRewards (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn dispatch_extra_reward() -> Weight {
+		Weight::from_ref_time(152_940_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(8 as u64))
+			.saturating_add(RocksDbWeight::get().writes(7 as u64))
+	}
+	// Storage: Farming Pools (r:2 w:1)
+	// Storage: Farming WorkingSupply (r:2 w:1)
+	// Storage: Farming Positions (r:2 w:1)
+	// Storage: Farming WorkingBalances (r:2 w:1)
+	// Storage: Farming ExtraRewardAssets (r:1 w:0)
+	// Storage: VoteEscrow Locked (r:1 w:0)
+	// Storage: VoteEscrow Supply (r:1 w:0)
+	fn update_boost() -> Weight {
+		Weight::from_ref_time(61_837_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(11 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
 }
@@ -333,6 +333,9 @@ parameter_types! {
     pub const LoansPalletId: PalletId = PalletId(*b"par/loan");
     pub const RewardAssetId: CurrencyId = HKO;
     pub const LiquidationFreeAssetId: CurrencyId = KSM;
+    pub StreamCollateralDiscount: Ratio = Ratio::from_percent(30);
+    pub const MaxStreamCollaterals: u32 = 10;
+    pub MaxRewardBoost: Rate = Rate::saturating_from_rational(5, 2);
 }
 
 impl pallet_loans::Config for Test {
@@ -346,6 +349,13 @@ impl pallet_loans::Config for Test {
     type Assets = Assets;
     type RewardAssetId = RewardAssetId;
     type LiquidationFreeAssetId = LiquidationFreeAssetId;
+    type RuntimeCall = RuntimeCall;
+    type FlashLoanReceiver = ();
+    type StreamCollateral = ();
+    type StreamCollateralDiscount = StreamCollateralDiscount;
+    type MaxStreamCollaterals = MaxStreamCollaterals;
+    type VotingEscrow = ();
+    type MaxRewardBoost = MaxRewardBoost;
 }
 
 parameter_types! {
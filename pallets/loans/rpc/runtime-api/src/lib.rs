@@ -15,15 +15,29 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::Codec;
+use pallet_traits::{
+    AccountMode, AccountPosition, AuctionId, LiquidationAuction, LiquidationSimulation,
+    MarketStatus,
+};
 use primitives::{CurrencyId, Liquidity, Rate, Ratio, Shortfall};
 use sp_runtime::{DispatchError, FixedU128};
+use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
+    #[api_version(2)]
     pub trait LoansApi<AccountId, Balance> where
         AccountId: Codec,
         Balance: Codec {
         fn get_account_liquidity(account: AccountId) -> Result<(Liquidity, Shortfall, Liquidity, Shortfall), DispatchError>;
-        fn get_market_status(asset_id: CurrencyId) -> Result<(Rate, Rate, Rate, Ratio, Balance, Balance, FixedU128), DispatchError>;
+        fn get_market_status(asset_id: CurrencyId) -> Result<MarketStatus<Balance>, DispatchError>;
         fn get_liquidation_threshold_liquidity(account: AccountId) -> Result<(Liquidity, Shortfall, Liquidity, Shortfall), DispatchError>;
+        fn get_liquidation_auctions() -> Vec<(AuctionId, LiquidationAuction<AccountId, CurrencyId, Balance>, Rate)>;
+        fn get_account_mode(account: AccountId) -> AccountMode<CurrencyId>;
+        fn get_liquidation_candidates(min_shortfall: Shortfall, start_key: Option<Vec<u8>>, limit: u32) -> Result<(Vec<(AccountId, Shortfall)>, Option<Vec<u8>>), DispatchError>;
+        fn get_account_position(account: AccountId) -> Result<AccountPosition<CurrencyId, Balance>, DispatchError>;
+        fn simulate_liquidation(borrower: AccountId, liquidation_asset_id: CurrencyId, repay_amount: Balance, collateral_asset_id: CurrencyId) -> Result<LiquidationSimulation<Balance>, DispatchError>;
+
+        #[changed_in(2)]
+        fn get_market_status(asset_id: CurrencyId) -> Result<(Rate, Rate, Rate, Ratio, Balance, Balance, FixedU128), DispatchError>;
     }
 }
@@ -22,11 +22,26 @@ use jsonrpsee::{
     proc_macros::rpc,
     types::error::{CallError, ErrorCode, ErrorObject},
 };
-use primitives::{CurrencyId, Liquidity, Rate, Ratio, Shortfall};
-use sp_api::ProvideRuntimeApi;
+use pallet_traits::{
+    AccountMode, AccountPosition, AssetPosition, AuctionId, LiquidationAuction,
+    LiquidationSimulation, MarketStatus,
+};
+use primitives::{CurrencyId, Liquidity, Rate, Shortfall};
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
 use sp_rpc::number::NumberOrHex;
-use sp_runtime::{generic::BlockId, traits::Block as BlockT, FixedU128};
+use sp_runtime::{
+    generic::BlockId,
+    traits::{Block as BlockT, Zero},
+};
+
+/// An open liquidation auction with balances in RPC representation and its current price.
+pub type RpcLiquidationAuction<AccountId> = (
+    AuctionId,
+    LiquidationAuction<AccountId, CurrencyId, NumberOrHex>,
+    Rate,
+);
 
 #[rpc(client, server)]
 pub trait LoansApi<BlockHash, AccountId, Balance>
@@ -44,13 +59,47 @@ where
         &self,
         asset_id: CurrencyId,
         at: Option<BlockHash>,
-    ) -> RpcResult<(Rate, Rate, Rate, Ratio, NumberOrHex, NumberOrHex, FixedU128)>;
+    ) -> RpcResult<MarketStatus<NumberOrHex>>;
     #[method(name = "loans_getLiquidationThresholdLiquidity")]
     fn get_liquidation_threshold_liquidity(
         &self,
         account: AccountId,
         at: Option<BlockHash>,
     ) -> RpcResult<(Liquidity, Shortfall, Liquidity, Shortfall)>;
+    #[method(name = "loans_getLiquidationAuctions")]
+    fn get_liquidation_auctions(
+        &self,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<RpcLiquidationAuction<AccountId>>>;
+    #[method(name = "loans_getAccountMode")]
+    fn get_account_mode(
+        &self,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<AccountMode<CurrencyId>>;
+    #[method(name = "loans_getLiquidationCandidates")]
+    fn get_liquidation_candidates(
+        &self,
+        min_shortfall: Shortfall,
+        start_key: Option<Bytes>,
+        limit: u32,
+        at: Option<BlockHash>,
+    ) -> RpcResult<(Vec<(AccountId, Shortfall)>, Option<Bytes>)>;
+    #[method(name = "loans_getAccountPosition")]
+    fn get_account_position(
+        &self,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<AccountPosition<CurrencyId, NumberOrHex>>;
+    #[method(name = "loans_simulateLiquidation")]
+    fn simulate_liquidation(
+        &self,
+        borrower: AccountId,
+        liquidation_asset_id: CurrencyId,
+        repay_amount: NumberOrHex,
+        collateral_asset_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<LiquidationSimulation<NumberOrHex>>;
 }
 
 /// A struct that implements the [`LoansApi`].
@@ -73,6 +122,7 @@ pub enum Error {
     RuntimeError,
     AccountLiquidityError,
     MarketStatusError,
+    LiquidationError,
 }
 
 impl From<Error> for i32 {
@@ -81,6 +131,7 @@ impl From<Error> for i32 {
             Error::RuntimeError => 1,
             Error::AccountLiquidityError => 2,
             Error::MarketStatusError => 3,
+            Error::LiquidationError => 4,
         }
     }
 }
@@ -95,7 +146,7 @@ where
     C: HeaderBackend<Block>,
     C::Api: LoansRuntimeApi<Block, AccountId, Balance>,
     AccountId: Codec,
-    Balance: Codec + Copy + TryFrom<NumberOrHex> + Into<NumberOrHex> + std::fmt::Display,
+    Balance: Codec + Copy + Zero + TryFrom<NumberOrHex> + Into<NumberOrHex> + std::fmt::Display,
 {
     fn get_account_liquidity(
         &self,
@@ -116,33 +167,61 @@ where
         &self,
         asset_id: CurrencyId,
         at: Option<<Block as BlockT>::Hash>,
-    ) -> RpcResult<(Rate, Rate, Rate, Ratio, NumberOrHex, NumberOrHex, FixedU128)> {
+    ) -> RpcResult<MarketStatus<NumberOrHex>> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or(
             // If the block hash is not supplied assume the best block.
             self.client.info().best_hash,
         ));
-        let (
-            borrow_rate,
-            supply_rate,
-            exchange_rate,
-            util,
-            total_borrows,
-            total_reserves,
-            borrow_index,
-        ) = api
-            .get_market_status(&at, asset_id)
+        let api_version = api
+            .api_version::<dyn LoansRuntimeApi<Block, AccountId, Balance>>(&at)
             .map_err(runtime_error_into_rpc_error)?
-            .map_err(market_status_error_into_rpc_error)?;
-        Ok((
-            borrow_rate,
-            supply_rate,
-            exchange_rate,
-            util,
-            try_into_rpc_balance(total_borrows)?,
-            try_into_rpc_balance(total_reserves)?,
-            borrow_index,
-        ))
+            .unwrap_or_default();
+        let status = if api_version < 2 {
+            // runtimes before stable rate borrows return the variable rate fields only
+            #[allow(deprecated)]
+            let status = api
+                .get_market_status_before_version_2(&at, asset_id)
+                .map_err(runtime_error_into_rpc_error)?;
+            status.map(
+                |(
+                    borrow_rate,
+                    supply_rate,
+                    exchange_rate,
+                    utilization,
+                    total_borrows,
+                    total_reserves,
+                    borrow_index,
+                )| MarketStatus {
+                    borrow_rate,
+                    supply_rate,
+                    exchange_rate,
+                    utilization,
+                    total_borrows,
+                    total_reserves,
+                    borrow_index,
+                    total_stable_borrows: Balance::zero(),
+                    stable_borrow_rate: None,
+                    average_stable_borrow_rate: Rate::zero(),
+                },
+            )
+        } else {
+            api.get_market_status(&at, asset_id)
+                .map_err(runtime_error_into_rpc_error)?
+        }
+        .map_err(market_status_error_into_rpc_error)?;
+        Ok(MarketStatus {
+            borrow_rate: status.borrow_rate,
+            supply_rate: status.supply_rate,
+            exchange_rate: status.exchange_rate,
+            utilization: status.utilization,
+            total_borrows: try_into_rpc_balance(status.total_borrows)?,
+            total_reserves: try_into_rpc_balance(status.total_reserves)?,
+            borrow_index: status.borrow_index,
+            total_stable_borrows: try_into_rpc_balance(status.total_stable_borrows)?,
+            stable_borrow_rate: status.stable_borrow_rate,
+            average_stable_borrow_rate: status.average_stable_borrow_rate,
+        })
     }
 
     fn get_liquidation_threshold_liquidity(
@@ -159,6 +238,135 @@ where
             .map_err(runtime_error_into_rpc_error)?
             .map_err(account_liquidity_error_into_rpc_error)
     }
+
+    fn get_liquidation_auctions(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<RpcLiquidationAuction<AccountId>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        api.get_liquidation_auctions(&at)
+            .map_err(runtime_error_into_rpc_error)?
+            .into_iter()
+            .map(|(auction_id, auction, price)| {
+                Ok((
+                    auction_id,
+                    LiquidationAuction {
+                        borrower: auction.borrower,
+                        liquidation_asset_id: auction.liquidation_asset_id,
+                        collateral_asset_id: auction.collateral_asset_id,
+                        debt: try_into_rpc_balance(auction.debt)?,
+                        collateral: try_into_rpc_balance(auction.collateral)?,
+                        start_time: auction.start_time,
+                        config: auction.config,
+                    },
+                    price,
+                ))
+            })
+            .collect()
+    }
+
+    fn get_account_mode(
+        &self,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<AccountMode<CurrencyId>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        api.get_account_mode(&at, account)
+            .map_err(runtime_error_into_rpc_error)
+    }
+
+    fn get_liquidation_candidates(
+        &self,
+        min_shortfall: Shortfall,
+        start_key: Option<Bytes>,
+        limit: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<(Vec<(AccountId, Shortfall)>, Option<Bytes>)> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        let (candidates, next_key) = api
+            .get_liquidation_candidates(&at, min_shortfall, start_key.map(|key| key.0), limit)
+            .map_err(runtime_error_into_rpc_error)?
+            .map_err(account_liquidity_error_into_rpc_error)?;
+        Ok((candidates, next_key.map(Bytes)))
+    }
+
+    fn get_account_position(
+        &self,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<AccountPosition<CurrencyId, NumberOrHex>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        let position = api
+            .get_account_position(&at, account)
+            .map_err(runtime_error_into_rpc_error)?
+            .map_err(account_liquidity_error_into_rpc_error)?;
+        Ok(AccountPosition {
+            assets: position
+                .assets
+                .into_iter()
+                .map(|asset| {
+                    Ok(AssetPosition {
+                        asset_id: asset.asset_id,
+                        supplied: try_into_rpc_balance(asset.supplied)?,
+                        supply_interest: try_into_rpc_balance(asset.supply_interest)?,
+                        is_collateral: asset.is_collateral,
+                        borrowed: try_into_rpc_balance(asset.borrowed)?,
+                        borrow_interest: try_into_rpc_balance(asset.borrow_interest)?,
+                    })
+                })
+                .collect::<RpcResult<_>>()?,
+            unclaimed_reward: try_into_rpc_balance(position.unclaimed_reward)?,
+            health_factor: position.health_factor,
+        })
+    }
+
+    fn simulate_liquidation(
+        &self,
+        borrower: AccountId,
+        liquidation_asset_id: CurrencyId,
+        repay_amount: NumberOrHex,
+        collateral_asset_id: CurrencyId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<LiquidationSimulation<NumberOrHex>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        let repay_amount = try_from_rpc_balance(repay_amount)?;
+        let simulation = api
+            .simulate_liquidation(
+                &at,
+                borrower,
+                liquidation_asset_id,
+                repay_amount,
+                collateral_asset_id,
+            )
+            .map_err(runtime_error_into_rpc_error)?
+            .map_err(liquidation_error_into_rpc_error)?;
+        Ok(LiquidationSimulation {
+            seized_collateral: try_into_rpc_balance(simulation.seized_collateral)?,
+            liquidator_collateral: try_into_rpc_balance(simulation.liquidator_collateral)?,
+            reserved_collateral: try_into_rpc_balance(simulation.reserved_collateral)?,
+            auction: simulation.auction,
+        })
+    }
 }
 
 /// Converts a runtime trap into an RPC error.
@@ -188,6 +396,15 @@ fn market_status_error_into_rpc_error(err: impl std::fmt::Debug) -> JsonRpseeErr
     )))
 }
 
+/// Converts a liquidation error into an RPC error.
+fn liquidation_error_into_rpc_error(err: impl std::fmt::Debug) -> JsonRpseeError {
+    JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        Error::LiquidationError.into(),
+        "Not able to simulate liquidation",
+        Some(format!("{:?}", err)),
+    )))
+}
+
 fn try_into_rpc_balance<T: std::fmt::Display + Copy + TryInto<NumberOrHex>>(
     value: T,
 ) -> RpcResult<NumberOrHex> {
@@ -199,3 +416,13 @@ fn try_into_rpc_balance<T: std::fmt::Display + Copy + TryInto<NumberOrHex>>(
         )))
     })
 }
+
+fn try_from_rpc_balance<T: TryFrom<NumberOrHex>>(value: NumberOrHex) -> RpcResult<T> {
+    value.try_into().map_err(|_| {
+        JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+            ErrorCode::InvalidParams.code(),
+            format!("{:?} doesn't fit in the balance type", value),
+            None::<()>,
+        )))
+    })
+}
@@ -0,0 +1,361 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pallet_traits::{AuctionDecay, AuctionId, LiquidationAuction, LiquidationAuctionConfig};
+use sp_runtime::{traits::Zero, DispatchResult};
+
+use crate::*;
+
+impl<T: Config> Pallet<T> {
+    /// Checks the auction parameters for sanity
+    pub(crate) fn ensure_valid_auction_config(config: &LiquidationAuctionConfig) -> DispatchResult {
+        let valid_decay = match config.decay {
+            AuctionDecay::Linear { duration } => !duration.is_zero(),
+            AuctionDecay::Stepwise { step, cut } => {
+                !step.is_zero() && !cut.is_zero() && cut < Ratio::one()
+            }
+        };
+        ensure!(
+            valid_decay
+                && !config.min_price.is_zero()
+                && config.start_premium >= config.min_price
+                && !config.duration.is_zero(),
+            Error::<T>::InvalidAuctionConfig
+        );
+
+        Ok(())
+    }
+
+    /// The collateral price relative to the oracle price, `elapsed` seconds after
+    /// the auction started.
+    pub fn auction_price(config: &LiquidationAuctionConfig, elapsed: Timestamp) -> Rate {
+        let price = match config.decay {
+            AuctionDecay::Linear { duration } => {
+                if elapsed >= duration {
+                    config.min_price
+                } else {
+                    // start_premium - (start_premium - min_price) * elapsed / duration
+                    let decayed = config
+                        .start_premium
+                        .saturating_sub(config.min_price)
+                        .saturating_mul(Rate::saturating_from_rational(elapsed, duration));
+                    config.start_premium.saturating_sub(decayed)
+                }
+            }
+            AuctionDecay::Stepwise { step, cut } => {
+                // start_premium * (1 - cut) ^ (elapsed / step)
+                let steps: usize = elapsed
+                    .checked_div(step)
+                    .unwrap_or_default()
+                    .saturated_into();
+                let factor: Rate = Ratio::one().saturating_sub(cut).into();
+                config
+                    .start_premium
+                    .saturating_mul(factor.saturating_pow(steps))
+            }
+        };
+
+        price.max(config.min_price)
+    }
+
+    // Returns a stored liquidation auction.
+    //
+    // Returns `Err` if the auction does not exist.
+    pub fn liquidation_auction(
+        auction_id: AuctionId,
+    ) -> Result<LiquidationAuctionOf<T>, DispatchError> {
+        LiquidationAuctions::<T>::try_get(auction_id)
+            .map_err(|_err| Error::<T>::AuctionDoesNotExist.into())
+    }
+
+    /// All open liquidation auctions along with their current collateral price
+    pub fn get_liquidation_auctions() -> Vec<(AuctionId, LiquidationAuctionOf<T>, Rate)> {
+        let now = T::UnixTime::now().as_secs();
+        LiquidationAuctions::<T>::iter()
+            .map(|(auction_id, auction)| {
+                let price =
+                    Self::auction_price(&auction.config, now.saturating_sub(auction.start_time));
+                (auction_id, auction, price)
+            })
+            .collect()
+    }
+
+    /// Moves the collateral seized from `borrower` into a new descending price auction.
+    ///
+    /// The debt stays with the borrower until bidders repay it.
+    #[require_transactional]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn start_liquidation_auction(
+        liquidator: &T::AccountId,
+        borrower: &T::AccountId,
+        liquidation_asset_id: AssetIdOf<T>,
+        collateral_asset_id: AssetIdOf<T>,
+        repay_amount: BalanceOf<T>,
+        collateral_underlying_amount: BalanceOf<T>,
+        config: LiquidationAuctionConfig,
+    ) -> DispatchResult {
+        ensure!(
+            !BorrowerAuction::<T>::contains_key(borrower),
+            Error::<T>::AuctionAlreadyStarted
+        );
+
+        // update supply index before modify supply balance.
+        Self::update_reward_supply_index(collateral_asset_id)?;
+        Self::distribute_supplier_reward(collateral_asset_id, borrower)?;
+
+        let exchange_rate = Self::exchange_rate_stored(collateral_asset_id)?;
+        let collateral_amount =
+            Self::calc_collateral_amount(collateral_underlying_amount, exchange_rate)?;
+        AccountDeposits::<T>::try_mutate(
+            collateral_asset_id,
+            borrower,
+            |deposits| -> DispatchResult {
+                deposits.voucher_balance = deposits
+                    .voucher_balance
+                    .checked_sub(collateral_amount)
+                    .ok_or(ArithmeticError::Underflow)?;
+                Ok(())
+            },
+        )?;
+
+        let auction_id = NextAuctionId::<T>::get();
+        NextAuctionId::<T>::put(auction_id.checked_add(1).ok_or(ArithmeticError::Overflow)?);
+        LiquidationAuctions::<T>::insert(
+            auction_id,
+            LiquidationAuction {
+                borrower: borrower.clone(),
+                liquidation_asset_id,
+                collateral_asset_id,
+                debt: repay_amount,
+                collateral: collateral_amount,
+                start_time: T::UnixTime::now().as_secs(),
+                config,
+            },
+        );
+        BorrowerAuction::<T>::insert(borrower, auction_id);
+
+        Self::deposit_event(Event::<T>::LiquidationAuctionStarted(
+            auction_id,
+            liquidator.clone(),
+            borrower.clone(),
+            liquidation_asset_id,
+            collateral_asset_id,
+            repay_amount,
+            collateral_amount,
+        ));
+
+        Ok(())
+    }
+
+    /// The bidder repays up to `repay_amount` of the auctioned debt and receives
+    /// collateral (as voucher amount) at the current auction price.
+    #[require_transactional]
+    pub(crate) fn do_bid_liquidation_auction(
+        bidder: &T::AccountId,
+        auction_id: AuctionId,
+        repay_amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        ensure!(!repay_amount.is_zero(), Error::<T>::InvalidAmount);
+        let mut auction = Self::liquidation_auction(auction_id)?;
+        ensure!(
+            bidder != &auction.borrower,
+            Error::<T>::LiquidatorIsBorrower
+        );
+        let liquidation_asset_id = auction.liquidation_asset_id;
+        let collateral_asset_id = auction.collateral_asset_id;
+        Self::ensure_active_market(liquidation_asset_id)?;
+        Self::ensure_active_market(collateral_asset_id)?;
+        Self::accrue_interest(liquidation_asset_id)?;
+        Self::accrue_interest(collateral_asset_id)?;
+
+        let now = T::UnixTime::now().as_secs();
+        let auction_price =
+            Self::auction_price(&auction.config, now.saturating_sub(auction.start_time));
+        let collateral_price = Self::get_price(collateral_asset_id)?
+            .checked_mul(&auction_price)
+            .ok_or(ArithmeticError::Overflow)?;
+        let debt_price = Self::get_price(liquidation_asset_id)?;
+        let exchange_rate = Self::exchange_rate_stored(collateral_asset_id)?;
+
+        let account_borrows =
+            Self::current_borrow_balance(&auction.borrower, liquidation_asset_id)?;
+        let mut repay_amount = repay_amount.min(auction.debt).min(account_borrows);
+        // The decaying auction price already discounts the collateral
+        let repay_value = Self::get_asset_value(liquidation_asset_id, repay_amount)?;
+        let mut collateral_amount = Self::calc_collateral_amount(
+            repay_value
+                .checked_div(&collateral_price)
+                .ok_or(ArithmeticError::Underflow)?
+                .into_inner(),
+            exchange_rate,
+        )?;
+        if collateral_amount > auction.collateral {
+            // The bid clears the auction, only charge for the collateral left
+            collateral_amount = auction.collateral;
+            let collateral_underlying_amount =
+                Self::calc_underlying_amount(collateral_amount, exchange_rate)?;
+            repay_amount = collateral_price
+                .checked_mul(&FixedU128::from_inner(collateral_underlying_amount))
+                .and_then(|r| r.checked_div(&debt_price))
+                .map(|r| r.into_inner())
+                .ok_or(ArithmeticError::Overflow)?;
+        }
+        ensure!(
+            !repay_amount.is_zero() && !collateral_amount.is_zero(),
+            Error::<T>::InvalidAmount
+        );
+
+        // 1.bidder repays borrower's debt
+        Self::update_reward_borrow_index(liquidation_asset_id)?;
+        Self::distribute_borrower_reward(liquidation_asset_id, &auction.borrower)?;
+        T::Assets::transfer(
+            liquidation_asset_id,
+            bidder,
+            &Self::account_id(),
+            repay_amount,
+            false,
+        )?;
+        Self::reduce_borrow(
+            &auction.borrower,
+            liquidation_asset_id,
+            account_borrows,
+            repay_amount,
+        )?;
+
+        // 2.bidder receives the collateral voucher
+        Self::update_reward_supply_index(collateral_asset_id)?;
+        Self::distribute_supplier_reward(collateral_asset_id, bidder)?;
+        Self::increase_voucher_balance(bidder, collateral_asset_id, collateral_amount)?;
+
+        auction.debt = auction.debt.saturating_sub(repay_amount);
+        auction.collateral = auction
+            .collateral
+            .checked_sub(collateral_amount)
+            .ok_or(ArithmeticError::Underflow)?;
+        Self::deposit_event(Event::<T>::LiquidationAuctionBid(
+            auction_id,
+            bidder.clone(),
+            repay_amount,
+            collateral_amount,
+        ));
+
+        if auction.debt.is_zero() || auction.collateral.is_zero() || repay_amount == account_borrows
+        {
+            // The auctioned debt has been covered, the collateral left goes back to the borrower
+            Self::distribute_supplier_reward(collateral_asset_id, &auction.borrower)?;
+            Self::increase_voucher_balance(
+                &auction.borrower,
+                collateral_asset_id,
+                auction.collateral,
+            )?;
+            Self::remove_liquidation_auction(auction_id, &auction.borrower);
+            Self::deposit_event(Event::<T>::LiquidationAuctionClosed(
+                auction_id,
+                auction.collateral,
+            ));
+        } else {
+            LiquidationAuctions::<T>::insert(auction_id, auction);
+        }
+
+        Ok(())
+    }
+
+    /// Settles an expired auction. The reserves of the liquidation market cover
+    /// the debt left, and the collateral left is added to the reserves of its market.
+    #[require_transactional]
+    pub(crate) fn do_settle_liquidation_auction(auction_id: AuctionId) -> DispatchResult {
+        let auction = Self::liquidation_auction(auction_id)?;
+        let liquidation_asset_id = auction.liquidation_asset_id;
+        let collateral_asset_id = auction.collateral_asset_id;
+        let now = T::UnixTime::now().as_secs();
+        ensure!(
+            now >= auction.start_time.saturating_add(auction.config.duration),
+            Error::<T>::AuctionNotExpired
+        );
+        Self::accrue_interest(liquidation_asset_id)?;
+        Self::accrue_interest(collateral_asset_id)?;
+
+        let account_borrows =
+            Self::current_borrow_balance(&auction.borrower, liquidation_asset_id)?;
+        let total_reserves = Self::total_reserves(liquidation_asset_id);
+        let covered_amount = auction.debt.min(account_borrows).min(total_reserves);
+        if !covered_amount.is_zero() {
+            Self::update_reward_borrow_index(liquidation_asset_id)?;
+            Self::distribute_borrower_reward(liquidation_asset_id, &auction.borrower)?;
+            TotalReserves::<T>::insert(
+                liquidation_asset_id,
+                total_reserves
+                    .checked_sub(covered_amount)
+                    .ok_or(ArithmeticError::Underflow)?,
+            );
+            Self::reduce_borrow(
+                &auction.borrower,
+                liquidation_asset_id,
+                account_borrows,
+                covered_amount,
+            )?;
+        }
+
+        // The auctioned vouchers are burnt and their underlying goes to the reserves,
+        // which leaves the exchange rate unchanged
+        let collateral_underlying_amount = Self::calc_underlying_amount(
+            auction.collateral,
+            Self::exchange_rate_stored(collateral_asset_id)?,
+        )?;
+        Self::update_reward_supply_index(collateral_asset_id)?;
+        TotalSupply::<T>::try_mutate(collateral_asset_id, |total_supply| -> DispatchResult {
+            *total_supply = total_supply
+                .checked_sub(auction.collateral)
+                .ok_or(ArithmeticError::Underflow)?;
+            Ok(())
+        })?;
+        TotalReserves::<T>::try_mutate(collateral_asset_id, |total_reserves| -> DispatchResult {
+            *total_reserves = total_reserves
+                .checked_add(collateral_underlying_amount)
+                .ok_or(ArithmeticError::Overflow)?;
+            Ok(())
+        })?;
+
+        Self::remove_liquidation_auction(auction_id, &auction.borrower);
+        Self::deposit_event(Event::<T>::LiquidationAuctionSettled(
+            auction_id,
+            covered_amount,
+            collateral_underlying_amount,
+        ));
+
+        Ok(())
+    }
+
+    fn remove_liquidation_auction(auction_id: AuctionId, borrower: &T::AccountId) {
+        LiquidationAuctions::<T>::remove(auction_id);
+        BorrowerAuction::<T>::remove(borrower);
+    }
+
+    fn increase_voucher_balance(
+        who: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+        voucher_amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        if voucher_amount.is_zero() {
+            return Ok(());
+        }
+        AccountDeposits::<T>::try_mutate(asset_id, who, |deposits| -> DispatchResult {
+            deposits.voucher_balance = deposits
+                .voucher_balance
+                .checked_add(voucher_amount)
+                .ok_or(ArithmeticError::Overflow)?;
+            Ok(())
+        })
+    }
+}
@@ -7,6 +7,7 @@ use crate::{AccountBorrows, Pallet as Loans};
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
 use frame_support::assert_ok;
 use frame_system::{self, RawOrigin as SystemOrigin};
+use pallet_traits::AuctionDecay;
 use primitives::{
     tokens::{CDOT_6_13, DOT, KSM, PKSM, PSKSM, PUSDT, SKSM, USDT},
     Balance, CurrencyId,
@@ -23,6 +24,11 @@ const RATE_MODEL_MOCK: InterestRateModel = InterestRateModel::Jump(JumpModel {
     jump_utilization: Ratio::from_percent(80),
 });
 
+const STABLE_BORROW_CONFIG_MOCK: StableBorrowConfig = StableBorrowConfig {
+    rate_premium: Rate::from_inner(Rate::DIV / 100 * 2),
+    rebalance_threshold: Rate::from_inner(Rate::DIV),
+};
+
 fn market_mock<T: Config>() -> Market<BalanceOf<T>> {
     Market {
         close_factor: Ratio::from_percent(50),
@@ -44,6 +50,30 @@ fn market_mock<T: Config>() -> Market<BalanceOf<T>> {
     }
 }
 
+fn liquidation_auction_config_mock() -> LiquidationAuctionConfig {
+    LiquidationAuctionConfig {
+        start_premium: Rate::one(),
+        min_price: Rate::from_inner(Rate::DIV / 100 * 80),
+        decay: AuctionDecay::Linear { duration: 600 },
+        duration: 1200,
+    }
+}
+
+fn isolation_config_mock() -> IsolationConfig<CurrencyId> {
+    IsolationConfig {
+        debt_ceiling: Liquidity::saturating_from_integer(1_000_000),
+        borrowable_assets: vec![KSM].try_into().unwrap(),
+    }
+}
+
+fn emode_category_mock() -> EModeCategory<CurrencyId> {
+    EModeCategory {
+        collateral_factor: Ratio::from_percent(90),
+        liquidation_threshold: Ratio::from_percent(93),
+        assets: vec![KSM, SKSM].try_into().unwrap(),
+    }
+}
+
 fn pending_market_mock<T: Config>(ptoken_id: CurrencyId) -> Market<BalanceOf<T>> {
     let mut market = market_mock::<T>();
     market.state = MarketState::Pending;
@@ -177,6 +207,8 @@ fn set_account_borrows<T: Config>(
         BorrowSnapshot {
             principal: borrow_balance,
             borrow_index: Rate::one(),
+            stable_rate: None,
+            last_update: 0,
         },
     );
     TotalBorrows::<T>::insert(asset_id, borrow_balance);
@@ -447,6 +479,203 @@ benchmarks! {
     verify {
         assert_last_event::<T>(Event::<T>::LiquidationFreeCollateralsUpdated(vec![CDOT_6_13]).into());
     }
+
+    update_liquidation_auction_config {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), SKSM, pending_market_mock::<T>(PSKSM)));
+    }: _(SystemOrigin::Root, SKSM, Some(liquidation_auction_config_mock()))
+    verify {
+        assert_last_event::<T>(Event::<T>::LiquidationAuctionConfigUpdated(SKSM, Some(liquidation_auction_config_mock())).into());
+    }
+
+    bid_liquidation_auction {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+        let bob: T::AccountId = account("Sample", 101, SEED);
+        transfer_initial_balance::<T>(alice.clone());
+        transfer_initial_balance::<T>(bob.clone());
+        let deposit_amount: u32 = 200_000_000;
+        let borrowed_amount: u32 = 200_000_000;
+        let liquidate_amount: u32 = 100_000_000;
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), SKSM, pending_market_mock::<T>(PSKSM)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), SKSM));
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), KSM, pending_market_mock::<T>(PKSM)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), KSM));
+        assert_ok!(Loans::<T>::update_liquidation_auction_config(SystemOrigin::Root.into(), SKSM, Some(liquidation_auction_config_mock())));
+        assert_ok!(Loans::<T>::mint(SystemOrigin::Signed(bob.clone()).into(), KSM, deposit_amount.into()));
+        assert_ok!(Loans::<T>::mint(SystemOrigin::Signed(alice.clone()).into(), SKSM, deposit_amount.into()));
+        assert_ok!(Loans::<T>::collateral_asset(SystemOrigin::Signed(alice.clone()).into(), SKSM, true));
+        set_account_borrows::<T>(alice.clone(), KSM, borrowed_amount.into());
+        assert_ok!(Loans::<T>::liquidate_borrow(SystemOrigin::Signed(bob.clone()).into(), alice.clone(), KSM, liquidate_amount.into(), SKSM));
+        let auction_id = Loans::<T>::borrower_auction(&alice).unwrap();
+    }: _(SystemOrigin::Signed(bob.clone()), auction_id, liquidate_amount.into())
+    verify {
+        assert!(Loans::<T>::borrower_auction(&alice).is_none());
+    }
+
+    settle_liquidation_auction {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+        let bob: T::AccountId = account("Sample", 101, SEED);
+        transfer_initial_balance::<T>(alice.clone());
+        transfer_initial_balance::<T>(bob.clone());
+        let payer = T::Lookup::unlookup(bob.clone());
+        let deposit_amount: u32 = 200_000_000;
+        let borrowed_amount: u32 = 200_000_000;
+        let liquidate_amount: u32 = 100_000_000;
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), SKSM, pending_market_mock::<T>(PSKSM)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), SKSM));
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), KSM, pending_market_mock::<T>(PKSM)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), KSM));
+        assert_ok!(Loans::<T>::update_liquidation_auction_config(SystemOrigin::Root.into(), SKSM, Some(liquidation_auction_config_mock())));
+        assert_ok!(Loans::<T>::add_reserves(SystemOrigin::Root.into(), payer, KSM, liquidate_amount.into()));
+        assert_ok!(Loans::<T>::mint(SystemOrigin::Signed(bob.clone()).into(), KSM, deposit_amount.into()));
+        assert_ok!(Loans::<T>::mint(SystemOrigin::Signed(alice.clone()).into(), SKSM, deposit_amount.into()));
+        assert_ok!(Loans::<T>::collateral_asset(SystemOrigin::Signed(alice.clone()).into(), SKSM, true));
+        set_account_borrows::<T>(alice.clone(), KSM, borrowed_amount.into());
+        assert_ok!(Loans::<T>::liquidate_borrow(SystemOrigin::Signed(bob.clone()).into(), alice.clone(), KSM, liquidate_amount.into(), SKSM));
+        let auction_id = Loans::<T>::borrower_auction(&alice).unwrap();
+        // expire the auction
+        LiquidationAuctions::<T>::mutate(auction_id, |auction| {
+            if let Some(auction) = auction {
+                auction.config.duration = 0;
+            }
+        });
+    }: _(SystemOrigin::Signed(bob.clone()), auction_id)
+    verify {
+        assert!(Loans::<T>::borrower_auction(&alice).is_none());
+    }
+
+    update_isolation_config {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), SKSM, pending_market_mock::<T>(PSKSM)));
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), KSM, pending_market_mock::<T>(PKSM)));
+    }: _(SystemOrigin::Root, SKSM, Some(isolation_config_mock()))
+    verify {
+        assert_last_event::<T>(Event::<T>::IsolationConfigUpdated(SKSM, Some(isolation_config_mock())).into());
+    }
+
+    update_emode_category {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), SKSM, pending_market_mock::<T>(PSKSM)));
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), KSM, pending_market_mock::<T>(PKSM)));
+    }: _(SystemOrigin::Root, 1, Some(emode_category_mock()))
+    verify {
+        assert_last_event::<T>(Event::<T>::EModeCategoryUpdated(1, Some(emode_category_mock())).into());
+    }
+
+    set_emode {
+        let caller: T::AccountId = whitelisted_caller();
+        transfer_initial_balance::<T>(caller.clone());
+        let deposit_amount: u32 = 200_000_000;
+        let borrowed_amount: u32 = 100_000_000;
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), SKSM, pending_market_mock::<T>(PSKSM)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), SKSM));
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), KSM, pending_market_mock::<T>(PKSM)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), KSM));
+        assert_ok!(Loans::<T>::update_emode_category(SystemOrigin::Root.into(), 1, Some(emode_category_mock())));
+        assert_ok!(Loans::<T>::mint(SystemOrigin::Signed(caller.clone()).into(), SKSM, deposit_amount.into()));
+        assert_ok!(Loans::<T>::collateral_asset(SystemOrigin::Signed(caller.clone()).into(), SKSM, true));
+        assert_ok!(Loans::<T>::mint(SystemOrigin::Signed(caller.clone()).into(), KSM, deposit_amount.into()));
+        assert_ok!(Loans::<T>::borrow(SystemOrigin::Signed(caller.clone()).into(), KSM, borrowed_amount.into()));
+    }: _(SystemOrigin::Signed(caller.clone()), Some(1))
+    verify {
+        assert_last_event::<T>(Event::<T>::AccountEModeSet(caller, Some(1)).into());
+    }
+
+    update_flash_loan_fee {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+    }: _(SystemOrigin::Root, USDT, Some(Ratio::from_perthousand(1)))
+    verify {
+        assert_last_event::<T>(Event::<T>::FlashLoanFeeUpdated(USDT, Some(Ratio::from_perthousand(1))).into());
+    }
+
+    flash_loan {
+        let caller: T::AccountId = whitelisted_caller();
+        transfer_initial_balance::<T>(caller.clone());
+        let deposit_amount: u32 = 200_000_000;
+        let flash_loan_amount: u32 = 100_000_000;
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), USDT));
+        assert_ok!(Loans::<T>::update_flash_loan_fee(SystemOrigin::Root.into(), USDT, Some(Ratio::from_perthousand(1))));
+        assert_ok!(Loans::<T>::mint(SystemOrigin::Signed(caller.clone()).into(), USDT, deposit_amount.into()));
+    }: _(SystemOrigin::Signed(caller.clone()), USDT, flash_loan_amount.into(), vec![])
+    verify {
+        assert_last_event::<T>(Event::<T>::FlashLoaned(caller, USDT, flash_loan_amount.into(), 100_000_u128).into());
+    }
+
+    approve_borrow {
+        let caller: T::AccountId = whitelisted_caller();
+        let delegatee: T::AccountId = account("Sample", 100, SEED);
+        let allowance: u32 = 100_000_000;
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), USDT));
+    }: _(SystemOrigin::Signed(caller.clone()), USDT, T::Lookup::unlookup(delegatee.clone()), allowance.into())
+    verify {
+        assert_last_event::<T>(Event::<T>::BorrowAllowanceApproved(caller, delegatee, USDT, allowance.into()).into());
+    }
+
+    revoke_borrow_allowance {
+        let caller: T::AccountId = whitelisted_caller();
+        let delegatee: T::AccountId = account("Sample", 100, SEED);
+        let allowance: u32 = 100_000_000;
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), USDT));
+        assert_ok!(Loans::<T>::approve_borrow(SystemOrigin::Signed(caller.clone()).into(), USDT, T::Lookup::unlookup(delegatee.clone()), allowance.into()));
+    }: _(SystemOrigin::Signed(caller.clone()), USDT, T::Lookup::unlookup(delegatee.clone()))
+    verify {
+        assert_last_event::<T>(Event::<T>::BorrowAllowanceRevoked(caller, delegatee, USDT).into());
+    }
+
+    borrow_on_behalf {
+        let caller: T::AccountId = whitelisted_caller();
+        let delegator: T::AccountId = account("Sample", 100, SEED);
+        transfer_initial_balance::<T>(delegator.clone());
+        let deposit_amount: u32 = 200_000_000;
+        let borrowed_amount: u32 = 100_000_000;
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), USDT));
+        assert_ok!(Loans::<T>::mint(SystemOrigin::Signed(delegator.clone()).into(), USDT, deposit_amount.into()));
+        assert_ok!(Loans::<T>::collateral_asset(SystemOrigin::Signed(delegator.clone()).into(), USDT, true));
+        assert_ok!(Loans::<T>::approve_borrow(SystemOrigin::Signed(delegator.clone()).into(), USDT, T::Lookup::unlookup(caller.clone()), borrowed_amount.into()));
+    }: _(SystemOrigin::Signed(caller.clone()), USDT, T::Lookup::unlookup(delegator.clone()), borrowed_amount.into())
+    verify {
+        assert_last_event::<T>(Event::<T>::BorrowedOnBehalf(caller, delegator, USDT, borrowed_amount.into()).into());
+    }
+
+    update_stable_borrow_config {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+    }: _(SystemOrigin::Root, USDT, Some(STABLE_BORROW_CONFIG_MOCK))
+    verify {
+        assert_last_event::<T>(Event::<T>::StableBorrowConfigUpdated(USDT, Some(STABLE_BORROW_CONFIG_MOCK)).into());
+    }
+
+    borrow_stable {
+        let caller: T::AccountId = whitelisted_caller();
+        transfer_initial_balance::<T>(caller.clone());
+        let deposit_amount: u32 = 200_000_000;
+        let borrowed_amount: u32 = 100_000_000;
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), USDT));
+        assert_ok!(Loans::<T>::update_stable_borrow_config(SystemOrigin::Root.into(), USDT, Some(STABLE_BORROW_CONFIG_MOCK)));
+        assert_ok!(Loans::<T>::mint(SystemOrigin::Signed(caller.clone()).into(), USDT, deposit_amount.into()));
+        assert_ok!(Loans::<T>::collateral_asset(SystemOrigin::Signed(caller.clone()).into(), USDT, true));
+    }: _(SystemOrigin::Signed(caller.clone()), USDT, borrowed_amount.into())
+    verify {
+        assert!(Loans::<T>::account_borrows(USDT, &caller).stable_rate.is_some());
+    }
+
+    rebalance_stable_borrow_rate {
+        let caller: T::AccountId = whitelisted_caller();
+        let borrower: T::AccountId = account("Sample", 100, SEED);
+        transfer_initial_balance::<T>(borrower.clone());
+        let deposit_amount: u32 = 200_000_000;
+        let borrowed_amount: u32 = 100_000_000;
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), USDT));
+        assert_ok!(Loans::<T>::update_stable_borrow_config(SystemOrigin::Root.into(), USDT, Some(STABLE_BORROW_CONFIG_MOCK)));
+        assert_ok!(Loans::<T>::mint(SystemOrigin::Signed(borrower.clone()).into(), USDT, deposit_amount.into()));
+        assert_ok!(Loans::<T>::collateral_asset(SystemOrigin::Signed(borrower.clone()).into(), USDT, true));
+        assert_ok!(Loans::<T>::borrow_stable(SystemOrigin::Signed(borrower.clone()).into(), USDT, borrowed_amount.into()));
+    }: _(SystemOrigin::Signed(caller), T::Lookup::unlookup(borrower.clone()), USDT)
+    verify {
+        assert!(Loans::<T>::account_borrows(USDT, &borrower).stable_rate.is_some());
+    }
 }
 
 impl_benchmark_test_suite!(Loans, crate::mock::new_test_ext(), crate::mock::Test);
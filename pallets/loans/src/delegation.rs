@@ -0,0 +1,82 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sp_runtime::DispatchResult;
+
+use crate::*;
+
+impl<T: Config> Pallet<T> {
+    pub(crate) fn do_approve_borrow(
+        delegator: &T::AccountId,
+        delegatee: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        ensure!(delegator != delegatee, Error::<T>::SelfDelegation);
+        Self::ensure_active_market(asset_id)?;
+
+        BorrowAllowances::<T>::insert((asset_id, delegator, delegatee), amount);
+        Self::deposit_event(Event::<T>::BorrowAllowanceApproved(
+            delegator.clone(),
+            delegatee.clone(),
+            asset_id,
+            amount,
+        ));
+        Ok(())
+    }
+
+    pub(crate) fn do_revoke_borrow_allowance(
+        delegator: &T::AccountId,
+        delegatee: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+    ) -> DispatchResult {
+        BorrowAllowances::<T>::remove((asset_id, delegator, delegatee));
+        Self::deposit_event(Event::<T>::BorrowAllowanceRevoked(
+            delegator.clone(),
+            delegatee.clone(),
+            asset_id,
+        ));
+        Ok(())
+    }
+
+    /// `delegatee` borrows `amount` out of its allowance, the debt is recorded
+    /// for `delegator` and has to pass the liquidity checks of the delegator.
+    #[require_transactional]
+    pub(crate) fn do_borrow_on_behalf(
+        delegatee: &T::AccountId,
+        delegator: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+        BorrowAllowances::<T>::try_mutate(
+            (asset_id, delegator, delegatee),
+            |allowance| -> DispatchResult {
+                *allowance = allowance
+                    .checked_sub(amount)
+                    .ok_or(Error::<T>::InsufficientBorrowAllowance)?;
+                Ok(())
+            },
+        )?;
+
+        Self::borrow_to(delegator, delegatee, asset_id, amount, false)?;
+        Self::deposit_event(Event::<T>::BorrowedOnBehalf(
+            delegatee.clone(),
+            delegator.clone(),
+            asset_id,
+            amount,
+        ));
+        Ok(())
+    }
+}
@@ -60,6 +60,47 @@ impl<T: Config> Pallet<T> {
         Ok(reward_delta)
     }
 
+    /// Total borrows of a market at the initial borrow index
+    fn total_base_borrows(asset_id: AssetIdOf<T>) -> Result<BalanceOf<T>, DispatchError> {
+        let current_borrow_amount =
+            TotalBorrows::<T>::get(asset_id).saturating_add(TotalStableBorrows::<T>::get(asset_id));
+        BorrowIndex::<T>::get(asset_id)
+            .reciprocal()
+            .and_then(|r| r.checked_mul_int(current_borrow_amount))
+            .ok_or_else(|| ArithmeticError::Overflow.into())
+    }
+
+    /// Reward share of a balance in the style of veCRV, `1 / MaxRewardBoost` of the
+    /// balance plus the share of the market total matching the share of the total
+    /// voting power owned by `who`, up to the balance. Rewards of the unboosted
+    /// shares are left in the reward account.
+    fn boosted_reward_share(
+        who: &T::AccountId,
+        share: BalanceOf<T>,
+        total_share: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let total_voting_power = T::VotingEscrow::total_voting_power();
+        if share.is_zero() || total_voting_power.is_zero() {
+            return Ok(share);
+        }
+
+        let unboosted = T::MaxRewardBoost::get()
+            .reciprocal()
+            .ok_or(ArithmeticError::DivisionByZero)?;
+        let voting_power_share = total_share
+            .get_big_uint()
+            .checked_mul(&T::VotingEscrow::voting_power(who).get_big_uint())
+            .and_then(|r| r.checked_div(&total_voting_power.get_big_uint()))
+            .and_then(|r| r.to_u128())
+            .ok_or(ArithmeticError::Overflow)?;
+        let boosted = unboosted.saturating_mul_int(share).saturating_add(
+            Rate::one()
+                .saturating_sub(unboosted)
+                .saturating_mul_int(voting_power_share),
+        );
+        Ok(boosted.min(share))
+    }
+
     pub(crate) fn update_reward_supply_index(asset_id: AssetIdOf<T>) -> DispatchResult {
         let current_block_number = <frame_system::Pallet<T>>::block_number();
         RewardSupplyState::<T>::try_mutate(asset_id, |supply_state| -> DispatchResult {
@@ -92,16 +133,10 @@ impl<T: Config> Pallet<T> {
             }
             let borrow_speed = RewardBorrowSpeed::<T>::get(asset_id);
             if !borrow_speed.is_zero() {
-                let current_borrow_amount = TotalBorrows::<T>::get(asset_id);
-                let current_borrow_index = BorrowIndex::<T>::get(asset_id);
-                let base_borrow_amount = current_borrow_index
-                    .reciprocal()
-                    .and_then(|r| r.checked_mul_int(current_borrow_amount))
-                    .ok_or(ArithmeticError::Overflow)?;
                 let delta_index = Self::calculate_reward_delta_index(
                     delta_block,
                     borrow_speed,
-                    base_borrow_amount,
+                    Self::total_base_borrows(asset_id)?,
                 )?;
                 borrow_state.index = borrow_state
                     .index
@@ -131,7 +166,11 @@ impl<T: Config> Pallet<T> {
 
                 RewardAccrued::<T>::try_mutate(supplier, |total_reward| -> DispatchResult {
                     let supplier_account = AccountDeposits::<T>::get(asset_id, supplier);
-                    let supplier_amount = supplier_account.voucher_balance;
+                    let supplier_amount = Self::boosted_reward_share(
+                        supplier,
+                        supplier_account.voucher_balance,
+                        TotalSupply::<T>::get(asset_id),
+                    )?;
                     let reward_delta = Self::calculate_reward_delta(supplier_amount, delta_index)?;
                     *total_reward = total_reward
                         .checked_add(reward_delta)
@@ -171,6 +210,11 @@ impl<T: Config> Pallet<T> {
                         .reciprocal()
                         .and_then(|r| r.checked_mul_int(current_borrow_amount))
                         .ok_or(ArithmeticError::Overflow)?;
+                    let base_borrow_amount = Self::boosted_reward_share(
+                        borrower,
+                        base_borrow_amount,
+                        Self::total_base_borrows(asset_id)?,
+                    )?;
                     let reward_delta =
                         Self::calculate_reward_delta(base_borrow_amount, delta_index)?;
                     *total_reward = total_reward
@@ -0,0 +1,86 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sp_runtime::DispatchResult;
+
+use crate::*;
+
+impl<T: Config> Pallet<T> {
+    /// Lends `amount` of the market cash to `initiator`, dispatches `calls` and takes
+    /// back `amount` plus the flash loan fee, which goes to the reserves.
+    ///
+    /// The caller must make the whole call transactional so that the loan is
+    /// reverted when any step fails.
+    #[require_transactional]
+    pub(crate) fn do_flash_loan(
+        origin: OriginFor<T>,
+        initiator: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+        calls: Vec<<T as Config>::RuntimeCall>,
+    ) -> DispatchResult {
+        ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+        Self::ensure_active_market(asset_id)?;
+        let fee_ratio = Self::flash_loan_fee(asset_id).ok_or(Error::<T>::FlashLoanDisabled)?;
+        Self::accrue_interest(asset_id)?;
+        Self::ensure_enough_cash(asset_id, amount)?;
+        let fee = fee_ratio.mul_ceil(amount);
+
+        // 1.lend the cash, the exchange rate keeps counting it as cash
+        FlashLoanOutstanding::<T>::try_mutate(asset_id, |outstanding| -> DispatchResult {
+            *outstanding = outstanding
+                .checked_add(amount)
+                .ok_or(ArithmeticError::Overflow)?;
+            Ok(())
+        })?;
+        T::Assets::transfer(asset_id, &Self::account_id(), initiator, amount, false)?;
+
+        // 2.let the initiator use it
+        T::FlashLoanReceiver::on_flash_loan(initiator, asset_id, amount, fee)?;
+        for call in calls {
+            call.dispatch(origin.clone()).map_err(|err| err.error)?;
+        }
+
+        // 3.take back the loan plus the fee
+        let repay_amount = amount.checked_add(fee).ok_or(ArithmeticError::Overflow)?;
+        T::Assets::transfer(
+            asset_id,
+            initiator,
+            &Self::account_id(),
+            repay_amount,
+            false,
+        )?;
+        FlashLoanOutstanding::<T>::try_mutate(asset_id, |outstanding| -> DispatchResult {
+            *outstanding = outstanding
+                .checked_sub(amount)
+                .ok_or(ArithmeticError::Underflow)?;
+            Ok(())
+        })?;
+        TotalReserves::<T>::try_mutate(asset_id, |total_reserves| -> DispatchResult {
+            *total_reserves = total_reserves
+                .checked_add(fee)
+                .ok_or(ArithmeticError::Overflow)?;
+            Ok(())
+        })?;
+
+        Self::deposit_event(Event::<T>::FlashLoaned(
+            initiator.clone(),
+            asset_id,
+            amount,
+            fee,
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Read only views of the account positions for the runtime api.
+//!
+//! Interest and rewards are brought up to date before reading the positions,
+//! those state changes are always rolled back.
+
+use frame_support::storage::{with_transaction, TransactionOutcome};
+use pallet_traits::{AccountPosition, AssetPosition, LiquidationSimulation};
+use sp_runtime::DispatchResult;
+
+use crate::*;
+
+/// The max count of borrows scanned for one page of liquidation candidates
+pub const MAX_LIQUIDATION_CANDIDATES_SCAN: u32 = 1_000;
+
+impl<T: Config> Pallet<T> {
+    /// Borrowers whose liquidation shortfall is above `min_shortfall`, in storage order.
+    ///
+    /// Scans at most `limit` borrows after the raw `start_key`, capped by
+    /// `MAX_LIQUIDATION_CANDIDATES_SCAN`. Returns the candidates found along with the
+    /// `start_key` of the next page, `None` once all the borrows are scanned.
+    pub fn get_liquidation_candidates(
+        min_shortfall: Shortfall,
+        start_key: Option<Vec<u8>>,
+        limit: u32,
+    ) -> Result<(Vec<(T::AccountId, Shortfall)>, Option<Vec<u8>>), DispatchError> {
+        with_transaction(|| {
+            TransactionOutcome::Rollback(Self::liquidation_candidates(
+                min_shortfall,
+                start_key,
+                limit,
+            ))
+        })
+    }
+
+    /// The deposits, borrows, unclaimed reward and health factor of `account`
+    pub fn get_account_position(
+        account: &T::AccountId,
+    ) -> Result<AccountPosition<AssetIdOf<T>, BalanceOf<T>>, DispatchError> {
+        with_transaction(|| TransactionOutcome::Rollback(Self::account_position(account)))
+    }
+
+    /// The collateral `liquidate_borrow` would seize from `borrower` for `repay_amount`
+    pub fn simulate_liquidation(
+        borrower: &T::AccountId,
+        liquidation_asset_id: AssetIdOf<T>,
+        repay_amount: BalanceOf<T>,
+        collateral_asset_id: AssetIdOf<T>,
+    ) -> Result<LiquidationSimulation<BalanceOf<T>>, DispatchError> {
+        with_transaction(|| {
+            TransactionOutcome::Rollback(Self::liquidation_simulation(
+                borrower,
+                liquidation_asset_id,
+                repay_amount,
+                collateral_asset_id,
+            ))
+        })
+    }
+
+    fn liquidation_candidates(
+        min_shortfall: Shortfall,
+        start_key: Option<Vec<u8>>,
+        limit: u32,
+    ) -> Result<(Vec<(T::AccountId, Shortfall)>, Option<Vec<u8>>), DispatchError> {
+        Self::accrue_active_markets()?;
+
+        // A borrower is visited at the first of its borrows in storage order
+        let markets: Vec<AssetIdOf<T>> = Markets::<T>::iter_keys().collect();
+        let first_borrow_key = |borrower: &T::AccountId| {
+            markets
+                .iter()
+                .filter(|asset_id| {
+                    !Self::account_borrows(**asset_id, borrower)
+                        .principal
+                        .is_zero()
+                })
+                .map(|asset_id| AccountBorrows::<T>::hashed_key_for(asset_id, borrower))
+                .min()
+        };
+        let mut borrows = match start_key {
+            Some(start_key) => AccountBorrows::<T>::iter_from(start_key),
+            None => AccountBorrows::<T>::iter(),
+        };
+
+        let mut candidates = Vec::new();
+        for _ in 0..limit.min(MAX_LIQUIDATION_CANDIDATES_SCAN) {
+            let (asset_id, borrower, snapshot) = match borrows.next() {
+                Some(borrow) => borrow,
+                None => return Ok((candidates, None)),
+            };
+            if snapshot.principal.is_zero()
+                || first_borrow_key(&borrower)
+                    != Some(AccountBorrows::<T>::hashed_key_for(asset_id, &borrower))
+            {
+                continue;
+            }
+            let shortfall = Self::liquidation_shortfall(&borrower)?;
+            if shortfall > min_shortfall {
+                candidates.push((borrower, shortfall));
+            }
+        }
+
+        Ok((candidates, Some(borrows.last_raw_key().to_vec())))
+    }
+
+    fn account_position(
+        account: &T::AccountId,
+    ) -> Result<AccountPosition<AssetIdOf<T>, BalanceOf<T>>, DispatchError> {
+        Self::accrue_active_markets()?;
+
+        let mut assets = Vec::new();
+        for (asset_id, _) in Self::active_markets().collect::<Vec<_>>() {
+            Self::collect_market_reward(asset_id, account)?;
+            let deposits = Self::account_deposits(asset_id, account);
+            let snapshot = Self::account_borrows(asset_id, account);
+            if deposits.voucher_balance.is_zero() && snapshot.principal.is_zero() {
+                continue;
+            }
+
+            let exchange_rate = Self::exchange_rate_stored(asset_id)?;
+            Self::update_earned_stored(account, asset_id, exchange_rate)?;
+            let borrowed = Self::current_borrow_balance(account, asset_id)?;
+            assets.push(AssetPosition {
+                asset_id,
+                supplied: Self::calc_underlying_amount(deposits.voucher_balance, exchange_rate)?,
+                supply_interest: Self::account_earned(asset_id, account).total_earned_prior,
+                is_collateral: deposits.is_collateral,
+                borrowed,
+                borrow_interest: borrowed.saturating_sub(snapshot.principal),
+            });
+        }
+
+        let total_borrow_value = Self::total_borrowed_value(account)?;
+        let health_factor = if total_borrow_value.is_zero() {
+            None
+        } else {
+            Some(
+                Self::total_liquidation_threshold_value(account)?
+                    .checked_div(&total_borrow_value)
+                    .ok_or(ArithmeticError::Overflow)?,
+            )
+        };
+
+        Ok(AccountPosition {
+            assets,
+            unclaimed_reward: Self::reward_accrued(account),
+            health_factor,
+        })
+    }
+
+    fn liquidation_simulation(
+        borrower: &T::AccountId,
+        liquidation_asset_id: AssetIdOf<T>,
+        repay_amount: BalanceOf<T>,
+        collateral_asset_id: AssetIdOf<T>,
+    ) -> Result<LiquidationSimulation<BalanceOf<T>>, DispatchError> {
+        Self::ensure_collateral_not_reserved(collateral_asset_id)?;
+        Self::accrue_interest(liquidation_asset_id)?;
+        Self::accrue_interest(collateral_asset_id)?;
+
+        let market = Self::market(liquidation_asset_id)?;
+        let seized_collateral = Self::calc_seized_collateral(
+            borrower,
+            liquidation_asset_id,
+            repay_amount,
+            collateral_asset_id,
+            &market,
+        )?;
+        if Self::liquidation_auction_config(collateral_asset_id).is_some() {
+            return Ok(LiquidationSimulation {
+                seized_collateral,
+                liquidator_collateral: Zero::zero(),
+                reserved_collateral: Zero::zero(),
+                auction: true,
+            });
+        }
+
+        let exchange_rate = Self::exchange_rate_stored(collateral_asset_id)?;
+        let collateral_amount = Self::calc_collateral_amount(seized_collateral, exchange_rate)?;
+        let reserved_collateral = Self::calc_underlying_amount(
+            Self::calc_incentive_reserved_amount(collateral_amount, &market)?,
+            exchange_rate,
+        )?;
+
+        Ok(LiquidationSimulation {
+            seized_collateral,
+            liquidator_collateral: seized_collateral.saturating_sub(reserved_collateral),
+            reserved_collateral,
+            auction: false,
+        })
+    }
+
+    fn accrue_active_markets() -> DispatchResult {
+        for (asset_id, _) in Self::active_markets().collect::<Vec<_>>() {
+            Self::accrue_interest(asset_id)?;
+        }
+
+        Ok(())
+    }
+}
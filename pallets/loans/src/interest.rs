@@ -31,58 +31,71 @@ impl<T: Config> Pallet<T> {
             return Ok(());
         }
 
-        let (
-            borrow_rate,
-            supply_rate,
-            exchange_rate,
-            util,
-            total_borrows_new,
-            total_reserves_new,
-            borrow_index_new,
-        ) = Self::get_market_status(asset_id)?;
+        let status = Self::get_market_status(asset_id)?;
 
         Self::update_last_accrued_interest_time(asset_id, now)?;
-        TotalBorrows::<T>::insert(asset_id, total_borrows_new);
-        TotalReserves::<T>::insert(asset_id, total_reserves_new);
-        BorrowIndex::<T>::insert(asset_id, borrow_index_new);
+        Self::update_rate_model_state(
+            asset_id,
+            status.utilization,
+            now - last_accrued_interest_time,
+        )?;
+        TotalBorrows::<T>::insert(asset_id, status.total_borrows);
+        TotalStableBorrows::<T>::insert(asset_id, status.total_stable_borrows);
+        TotalReserves::<T>::insert(asset_id, status.total_reserves);
+        BorrowIndex::<T>::insert(asset_id, status.borrow_index);
 
         //save redundant storage right now.
-        UtilizationRatio::<T>::insert(asset_id, util);
-        BorrowRate::<T>::insert(asset_id, borrow_rate);
-        SupplyRate::<T>::insert(asset_id, supply_rate);
-        ExchangeRate::<T>::insert(asset_id, exchange_rate);
+        UtilizationRatio::<T>::insert(asset_id, status.utilization);
+        BorrowRate::<T>::insert(asset_id, status.borrow_rate);
+        SupplyRate::<T>::insert(asset_id, status.supply_rate);
+        ExchangeRate::<T>::insert(asset_id, status.exchange_rate);
 
         Ok(())
     }
 
+    /// The market status with the interest accrued up to now.
+    ///
+    /// `borrow_rate` and `total_borrows` are those of the variable rate borrows,
+    /// the supply rate is earned on the interest of both variable and stable rate borrows.
     pub fn get_market_status(
         asset_id: AssetIdOf<T>,
-    ) -> Result<
-        (
-            Rate,
-            Rate,
-            Rate,
-            Ratio,
-            BalanceOf<T>,
-            BalanceOf<T>,
-            FixedU128,
-        ),
-        DispatchError,
-    > {
+    ) -> Result<MarketStatus<BalanceOf<T>>, DispatchError> {
         let market = Self::market(asset_id)?;
         let total_supply = Self::total_supply(asset_id);
         let total_cash = Self::get_total_cash(asset_id);
         let mut total_borrows = Self::total_borrows(asset_id);
+        let mut total_stable_borrows = Self::total_stable_borrows(asset_id);
         let mut total_reserves = Self::total_reserves(asset_id);
         let mut borrow_index = Self::borrow_index(asset_id);
+        let average_stable_borrow_rate = Self::average_stable_borrow_rate(asset_id);
 
-        let util = Self::calc_utilization_ratio(total_cash, total_borrows, total_reserves)?;
+        let util = Self::calc_utilization_ratio(
+            total_cash,
+            total_borrows
+                .checked_add(total_stable_borrows)
+                .ok_or(ArithmeticError::Overflow)?,
+            total_reserves,
+        )?;
         let borrow_rate = market
             .rate_model
             .get_borrow_rate(util)
             .ok_or(ArithmeticError::Overflow)?;
+        let overall_borrow_rate = Self::weighted_rate(
+            borrow_rate,
+            total_borrows,
+            average_stable_borrow_rate,
+            total_stable_borrows,
+        )?;
         let supply_rate =
-            InterestRateModel::get_supply_rate(borrow_rate, util, market.reserve_factor);
+            InterestRateModel::get_supply_rate(overall_borrow_rate, util, market.reserve_factor);
+        let stable_borrow_rate = match Self::stable_borrow_config(asset_id) {
+            Some(config) => Some(
+                borrow_rate
+                    .checked_add(&config.rate_premium)
+                    .ok_or(ArithmeticError::Overflow)?,
+            ),
+            None => None,
+        };
 
         let now = T::UnixTime::now().as_secs();
         let last_accrued_interest_time = Self::last_accrued_interest_time(asset_id);
@@ -94,10 +107,19 @@ impl<T: Config> Pallet<T> {
             total_borrows = interest_accumulated
                 .checked_add(total_borrows)
                 .ok_or(ArithmeticError::Overflow)?;
-            total_reserves = market
-                .reserve_factor
-                .mul_floor(interest_accumulated)
-                .checked_add(total_reserves)
+            let stable_interest_accumulated = Self::accrued_interest(
+                average_stable_borrow_rate,
+                total_stable_borrows,
+                delta_time,
+            )
+            .ok_or(ArithmeticError::Overflow)?;
+            total_stable_borrows = stable_interest_accumulated
+                .checked_add(total_stable_borrows)
+                .ok_or(ArithmeticError::Overflow)?;
+            total_reserves = interest_accumulated
+                .checked_add(stable_interest_accumulated)
+                .map(|interest| market.reserve_factor.mul_floor(interest))
+                .and_then(|r| r.checked_add(total_reserves))
                 .ok_or(ArithmeticError::Overflow)?;
 
             borrow_index = Self::increment_index(borrow_rate, borrow_index, delta_time)
@@ -105,18 +127,49 @@ impl<T: Config> Pallet<T> {
                 .ok_or(ArithmeticError::Overflow)?;
         }
 
-        let exchange_rate =
-            Self::calculate_exchange_rate(total_supply, total_cash, total_borrows, total_reserves)?;
+        let exchange_rate = Self::calculate_exchange_rate(
+            total_supply,
+            total_cash,
+            total_borrows
+                .checked_add(total_stable_borrows)
+                .ok_or(ArithmeticError::Overflow)?,
+            total_reserves,
+        )?;
 
-        Ok((
+        Ok(MarketStatus {
             borrow_rate,
             supply_rate,
             exchange_rate,
-            util,
+            utilization: util,
             total_borrows,
             total_reserves,
             borrow_index,
-        ))
+            total_stable_borrows,
+            stable_borrow_rate,
+            average_stable_borrow_rate,
+        })
+    }
+
+    /// Moves the time dependent state of the market's rate model forward,
+    /// only the adaptive model is affected.
+    fn update_rate_model_state(
+        asset_id: AssetIdOf<T>,
+        util: Ratio,
+        delta_time: Timestamp,
+    ) -> DispatchResult {
+        let market = Self::market(asset_id)?;
+        let rate_model = market
+            .rate_model
+            .evolve(util, delta_time)
+            .ok_or(ArithmeticError::Overflow)?;
+        if rate_model != market.rate_model {
+            Self::mutate_market(asset_id, |stored_market| {
+                stored_market.rate_model = rate_model;
+                stored_market.clone()
+            })?;
+        }
+
+        Ok(())
     }
 
     /// Update the exchange rate according to the totalCash, totalBorrows and totalSupply.
@@ -125,7 +178,9 @@ impl<T: Config> Pallet<T> {
     pub fn exchange_rate_stored(asset_id: AssetIdOf<T>) -> Result<Rate, DispatchError> {
         let total_supply = Self::total_supply(asset_id);
         let total_cash = Self::get_total_cash(asset_id);
-        let total_borrows = Self::total_borrows(asset_id);
+        let total_borrows = Self::total_borrows(asset_id)
+            .checked_add(Self::total_stable_borrows(asset_id))
+            .ok_or(ArithmeticError::Overflow)?;
         let total_reserves = Self::total_reserves(asset_id);
 
         Self::calculate_exchange_rate(total_supply, total_cash, total_borrows, total_reserves)
@@ -172,7 +227,7 @@ impl<T: Config> Pallet<T> {
         })
     }
 
-    fn accrued_interest(
+    pub(crate) fn accrued_interest(
         borrow_rate: Rate,
         amount: BalanceOf<T>,
         delta_time: Timestamp,
@@ -183,6 +238,29 @@ impl<T: Config> Pallet<T> {
             .checked_div(SECONDS_PER_YEAR.into())
     }
 
+    /// The average of `rate` and `other_rate` weighted by their amounts
+    pub(crate) fn weighted_rate(
+        rate: Rate,
+        amount: BalanceOf<T>,
+        other_rate: Rate,
+        other_amount: BalanceOf<T>,
+    ) -> Result<Rate, DispatchError> {
+        let total = amount
+            .checked_add(other_amount)
+            .ok_or(ArithmeticError::Overflow)?;
+        if total.is_zero() {
+            return Ok(rate);
+        }
+
+        let weighted = rate.checked_mul(&Rate::saturating_from_rational(amount, total));
+        let other_weighted =
+            other_rate.checked_mul(&Rate::saturating_from_rational(other_amount, total));
+        weighted
+            .zip(other_weighted)
+            .and_then(|(a, b)| a.checked_add(&b))
+            .ok_or_else(|| ArithmeticError::Overflow.into())
+    }
+
     fn increment_index(borrow_rate: Rate, index: Rate, delta_time: Timestamp) -> Option<Rate> {
         borrow_rate
             .checked_mul(&index)?
@@ -27,6 +27,7 @@ use core::cmp::max;
 pub use crate::rate_model::*;
 
 use frame_support::{
+    dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
     log,
     pallet_prelude::*,
     require_transactional,
@@ -40,11 +41,14 @@ use frame_system::pallet_prelude::*;
 use num_traits::cast::ToPrimitive;
 pub use pallet::*;
 use pallet_traits::{
-    ConvertToBigUint, Loans as LoansTrait, LoansMarketDataProvider, LoansPositionDataProvider,
-    MarketInfo, MarketStatus, PriceFeeder,
+    AuctionId, ConvertToBigUint, EModeCategoryId, FlashLoanReceiver, LiquidationAuction,
+    LiquidationAuctionConfig, Loans as LoansTrait, LoansMarketDataProvider,
+    LoansPositionDataProvider, MarketInfo, MarketStatus, PriceFeeder, StreamCollateral,
+    VotingEscrow,
 };
 use primitives::{
-    is_auxiliary_token, Balance, CurrencyId, Liquidity, Price, Rate, Ratio, Shortfall, Timestamp,
+    is_auxiliary_token, Balance, CurrencyId, Liquidity, Price, Rate, Ratio, Shortfall, StreamId,
+    Timestamp,
 };
 use sp_runtime::{
     traits::{
@@ -56,7 +60,10 @@ use sp_runtime::{
 use sp_std::{result::Result, vec::Vec};
 
 use sp_io::hashing::blake2_256;
-pub use types::{BorrowSnapshot, Deposits, EarnedSnapshot, Market, MarketState, RewardMarketState};
+pub use types::{
+    BorrowSnapshot, Deposits, EModeCategory, EarnedSnapshot, IsolationConfig, Market, MarketState,
+    RewardMarketState, SeizedStream, StableBorrowConfig,
+};
 pub use weights::WeightInfo;
 
 mod benchmarking;
@@ -66,10 +73,17 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+mod auction;
+mod delegation;
 mod farming;
+mod flash_loan;
+mod health;
 mod interest;
+mod modes;
 mod ptoken;
 mod rate_model;
+mod stable;
+mod streams;
 mod types;
 
 pub mod migrations;
@@ -86,6 +100,7 @@ type AssetIdOf<T> =
     <<T as Config>::Assets as Inspect<<T as frame_system::Config>::AccountId>>::AssetId;
 type BalanceOf<T> =
     <<T as Config>::Assets as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+type LiquidationAuctionOf<T> = LiquidationAuction<AccountIdOf<T>, AssetIdOf<T>, BalanceOf<T>>;
 
 /// Utility type for managing upgrades/migrations.
 #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
@@ -96,6 +111,7 @@ pub enum Versions {
     V4,
     V5,
     V6,
+    V7,
 }
 
 #[frame_support::pallet]
@@ -138,6 +154,35 @@ pub mod pallet {
 
         #[pallet::constant]
         type LiquidationFreeAssetId: Get<AssetIdOf<Self>>;
+
+        /// The overarching call type, flash loans dispatch a batch of it.
+        type RuntimeCall: Parameter
+            + Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
+            + GetDispatchInfo;
+
+        /// Notified before the calls of a flash loan are dispatched
+        type FlashLoanReceiver: FlashLoanReceiver<Self::AccountId, AssetIdOf<Self>, BalanceOf<Self>>;
+
+        /// Streams which can be pledged as collateral
+        type StreamCollateral: StreamCollateral<Self::AccountId, AssetIdOf<Self>, BalanceOf<Self>>;
+
+        /// Discount applied to the unvested balance of a pledged stream before the
+        /// risk params of its market
+        #[pallet::constant]
+        type StreamCollateralDiscount: Get<Ratio>;
+
+        /// The max count of streams an account can pledge
+        #[pallet::constant]
+        type MaxStreamCollaterals: Get<u32>;
+
+        /// Vote escrow whose voting power boosts the reward share of suppliers and
+        /// borrowers
+        type VotingEscrow: VotingEscrow<Self::AccountId, BalanceOf<Self>>;
+
+        /// Specifies max reward share of a balance as a multiple of the share of a
+        /// balance without voting power, must not be less than one
+        #[pallet::constant]
+        type MaxRewardBoost: Get<Rate>;
     }
 
     #[pallet::error]
@@ -200,6 +245,48 @@ pub mod pallet {
         CodecError,
         /// Collateral is reserved and cannot be liquidated
         CollateralReserved,
+        /// Invalid liquidation auction params
+        InvalidAuctionConfig,
+        /// Liquidation auction does not exist
+        AuctionDoesNotExist,
+        /// The borrower already has an open liquidation auction
+        AuctionAlreadyStarted,
+        /// Liquidation auction cannot be settled before it expires
+        AuctionNotExpired,
+        /// Invalid isolation params
+        InvalidIsolationConfig,
+        /// Isolated collateral cannot be combined with other collateral or efficiency mode
+        IsolationModeConflict,
+        /// The asset cannot be borrowed against the isolated collateral
+        NotBorrowableInIsolation,
+        /// Upper bound of borrowing against the isolated collateral is exceeded
+        DebtCeilingExceeded,
+        /// Invalid efficiency mode category
+        InvalidEModeCategory,
+        /// Efficiency mode category does not exist
+        EModeCategoryDoesNotExist,
+        /// The asset is not in the efficiency mode category of the account
+        NotInEModeCategory,
+        /// The market doesn't offer flash loans
+        FlashLoanDisabled,
+        /// An account can't delegate borrowing power to itself
+        SelfDelegation,
+        /// The borrow exceeds the allowance approved by the delegator
+        InsufficientBorrowAllowance,
+        /// The market doesn't offer stable rate borrows
+        StableBorrowDisabled,
+        /// Variable and stable rate borrows of the same asset can't be combined
+        BorrowRateModeMismatch,
+        /// The supply rate is above the rebalance threshold or the borrow is not a stable one
+        RebalanceNotAllowed,
+        /// The stream is not pledged by the account
+        StreamNotPledged,
+        /// Upper bound of pledged streams is exceeded
+        TooManyStreamCollaterals,
+        /// The stream was not seized by a liquidation
+        StreamNotSeized,
+        /// The stream is seized by a liquidation
+        StreamIsSeized,
     }
 
     #[pallet::event]
@@ -265,6 +352,89 @@ pub mod pallet {
         IncentiveReservesReduced(T::AccountId, AssetIdOf<T>, BalanceOf<T>),
         /// Liquidation free collaterals has been updated
         LiquidationFreeCollateralsUpdated(Vec<AssetIdOf<T>>),
+        /// Liquidation auction params of a collateral market has been updated
+        /// [asset_id, config]
+        LiquidationAuctionConfigUpdated(AssetIdOf<T>, Option<LiquidationAuctionConfig>),
+        /// Event emitted when the seized collateral of a borrower is put into an auction
+        /// [auction_id, liquidator, borrower, liquidation_asset_id, collateral_asset_id, debt, collateral_amount]
+        LiquidationAuctionStarted(
+            AuctionId,
+            T::AccountId,
+            T::AccountId,
+            AssetIdOf<T>,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+        ),
+        /// Event emitted when a bidder repays auctioned debt
+        /// [auction_id, bidder, repay_amount, collateral_amount]
+        LiquidationAuctionBid(AuctionId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+        /// Event emitted when the auctioned debt is fully repaid
+        /// [auction_id, collateral_amount_returned]
+        LiquidationAuctionClosed(AuctionId, BalanceOf<T>),
+        /// Event emitted when an expired auction falls back to the reserves
+        /// [auction_id, debt_covered_by_reserves, collateral_underlying_amount_to_reserves]
+        LiquidationAuctionSettled(AuctionId, BalanceOf<T>, BalanceOf<T>),
+        /// Isolation params of a collateral market has been updated
+        /// [asset_id, config]
+        IsolationConfigUpdated(AssetIdOf<T>, Option<IsolationConfig<AssetIdOf<T>>>),
+        /// Efficiency mode category has been updated
+        /// [category_id, category]
+        EModeCategoryUpdated(EModeCategoryId, Option<EModeCategory<AssetIdOf<T>>>),
+        /// Event emitted when an account enters or leaves efficiency mode
+        /// [account, category_id]
+        AccountEModeSet(T::AccountId, Option<EModeCategoryId>),
+        /// Flash loan fee of a market has been updated
+        /// [asset_id, fee]
+        FlashLoanFeeUpdated(AssetIdOf<T>, Option<Ratio>),
+        /// Event emitted when a flash loan is repaid
+        /// [initiator, asset_id, amount, fee]
+        FlashLoaned(T::AccountId, AssetIdOf<T>, BalanceOf<T>, BalanceOf<T>),
+        /// Event emitted when a delegator approves a borrow allowance
+        /// [delegator, delegatee, asset_id, amount]
+        BorrowAllowanceApproved(T::AccountId, T::AccountId, AssetIdOf<T>, BalanceOf<T>),
+        /// Event emitted when a delegator revokes a borrow allowance
+        /// [delegator, delegatee, asset_id]
+        BorrowAllowanceRevoked(T::AccountId, T::AccountId, AssetIdOf<T>),
+        /// Event emitted when a delegatee borrows against the delegator's collateral
+        /// [delegatee, delegator, asset_id, amount]
+        BorrowedOnBehalf(T::AccountId, T::AccountId, AssetIdOf<T>, BalanceOf<T>),
+        /// Stable rate borrowing params of a market has been updated
+        /// [asset_id, config]
+        StableBorrowConfigUpdated(AssetIdOf<T>, Option<StableBorrowConfig>),
+        /// Event emitted when cash is borrowed at a stable rate
+        /// [sender, asset_id, amount, stable_rate]
+        StableBorrowed(T::AccountId, AssetIdOf<T>, BalanceOf<T>, Rate),
+        /// Event emitted when the rate of a stable rate borrow is re-priced
+        /// [borrower, asset_id, old_rate, new_rate]
+        StableBorrowRateRebalanced(T::AccountId, AssetIdOf<T>, Rate, Rate),
+        /// Event emitted when a stream is pledged as collateral
+        /// [recipient, stream_id]
+        StreamPledged(T::AccountId, StreamId),
+        /// Event emitted when a pledged stream is released
+        /// [recipient, stream_id]
+        StreamReleased(T::AccountId, StreamId),
+        /// Event emitted when a pledged stream is seized, the liquidator repaid the debt
+        /// of the borrower and is paid by the withdrawals of the stream
+        /// [liquidator, borrower, stream_id, liquidation_asset_id, repay_amount, payout]
+        StreamSeized(
+            T::AccountId,
+            T::AccountId,
+            StreamId,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+        ),
+        /// Event emitted when the withdrawals of a seized stream pay the liquidator and
+        /// repay a debt
+        /// [borrower, stream_id, asset_id, paid_amount, repay_amount]
+        RepaidBorrowWithStream(
+            T::AccountId,
+            StreamId,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+        ),
     }
 
     /// The timestamp of the last calculation of accrued interest
@@ -292,6 +462,20 @@ pub mod pallet {
     pub type TotalBorrows<T: Config> =
         StorageMap<_, Blake2_128Concat, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
 
+    /// Total amount of outstanding stable rate borrows of the underlying in this market
+    /// CurrencyId -> Balance
+    #[pallet::storage]
+    #[pallet::getter(fn total_stable_borrows)]
+    pub type TotalStableBorrows<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+    /// The stable rate of the outstanding stable rate borrows weighted by amount
+    /// CurrencyId -> Rate
+    #[pallet::storage]
+    #[pallet::getter(fn average_stable_borrow_rate)]
+    pub type AverageStableBorrowRate<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, Rate, ValueQuery>;
+
     /// Total amount of reserves of the underlying held in this market
     /// CurrencyId -> Balance
     #[pallet::storage]
@@ -313,6 +497,21 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// The amount a delegatee may still borrow against the collateral of a delegator
+    /// CurrencyId -> Delegator -> Delegatee -> Balance
+    #[pallet::storage]
+    #[pallet::getter(fn borrow_allowance)]
+    pub type BorrowAllowances<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, T::AccountId>,
+            NMapKey<Blake2_128Concat, T::AccountId>,
+        ),
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
     /// Mapping of account addresses to deposit details
     /// CollateralType -> Owner -> Deposits
     #[pallet::storage]
@@ -451,6 +650,103 @@ pub mod pallet {
     pub type RewardAccrued<T: Config> =
         StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
 
+    /// Liquidation auction params of collateral markets, markets without params
+    /// are liquidated instantly
+    #[pallet::storage]
+    #[pallet::getter(fn liquidation_auction_config)]
+    pub type LiquidationAuctionConfigs<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, LiquidationAuctionConfig>;
+
+    /// Open liquidation auctions
+    #[pallet::storage]
+    pub type LiquidationAuctions<T: Config> =
+        StorageMap<_, Blake2_128Concat, AuctionId, LiquidationAuctionOf<T>>;
+
+    /// The open liquidation auction of each borrower
+    #[pallet::storage]
+    #[pallet::getter(fn borrower_auction)]
+    pub type BorrowerAuction<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, AuctionId>;
+
+    /// The id of the next liquidation auction
+    #[pallet::storage]
+    #[pallet::getter(fn next_auction_id)]
+    pub type NextAuctionId<T: Config> = StorageValue<_, AuctionId, ValueQuery>;
+
+    /// Isolation params of collateral markets
+    #[pallet::storage]
+    #[pallet::getter(fn isolation_config)]
+    pub type IsolationConfigs<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, IsolationConfig<AssetIdOf<T>>>;
+
+    /// Borrowed principal backed by each isolated collateral
+    /// IsolatedCollateralType -> BorrowedCurrencyType -> Balance
+    #[pallet::storage]
+    #[pallet::getter(fn isolated_borrows)]
+    pub type IsolatedBorrows<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    /// Efficiency mode categories
+    #[pallet::storage]
+    #[pallet::getter(fn emode_category)]
+    pub type EModeCategories<T: Config> =
+        StorageMap<_, Blake2_128Concat, EModeCategoryId, EModeCategory<AssetIdOf<T>>>;
+
+    /// The efficiency mode category of each account
+    #[pallet::storage]
+    #[pallet::getter(fn account_emode)]
+    pub type AccountEMode<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, EModeCategoryId>;
+
+    /// Flash loan fee of each market, markets without a fee don't offer flash loans
+    #[pallet::storage]
+    #[pallet::getter(fn flash_loan_fee)]
+    pub type FlashLoanFees<T: Config> = StorageMap<_, Blake2_128Concat, AssetIdOf<T>, Ratio>;
+
+    /// The amount lent by ongoing flash loans of each market
+    /// CurrencyType -> Balance
+    #[pallet::storage]
+    #[pallet::getter(fn flash_loan_outstanding)]
+    pub type FlashLoanOutstanding<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+    /// Stable rate borrowing params of each market, markets without params
+    /// only offer variable rate borrows
+    #[pallet::storage]
+    #[pallet::getter(fn stable_borrow_config)]
+    pub type StableBorrowConfigs<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, StableBorrowConfig>;
+
+    /// Streams pledged as collateral by each account
+    /// AccountId -> StreamIds
+    #[pallet::storage]
+    #[pallet::getter(fn stream_collaterals)]
+    pub type StreamCollaterals<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<StreamId, T::MaxStreamCollaterals>,
+        ValueQuery,
+    >;
+
+    /// Streams seized by liquidations, their withdrawals pay the liquidators
+    /// StreamId -> SeizedStream
+    #[pallet::storage]
+    #[pallet::getter(fn seized_streams)]
+    pub type SeizedStreams<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        StreamId,
+        SeizedStream<T::AccountId, BalanceOf<T>>,
+        OptionQuery,
+    >;
+
     /// DefaultVersion is using for initialize the StorageVersion
     #[pallet::type_value]
     pub(super) fn DefaultVersion<T: Config>() -> Versions {
@@ -644,7 +940,7 @@ pub mod pallet {
                 *stored_market = Market {
                     state: stored_market.state,
                     ptoken_id: stored_market.ptoken_id,
-                    rate_model: stored_market.rate_model,
+                    rate_model: stored_market.rate_model.clone(),
                     collateral_factor,
                     liquidation_threshold,
                     reserve_factor,
@@ -989,11 +1285,7 @@ pub mod pallet {
             collateral_asset_id: AssetIdOf<T>,
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
-            ensure!(
-                !Self::liquidation_free_collaterals().contains(&collateral_asset_id)
-                    && !is_auxiliary_token(collateral_asset_id),
-                Error::<T>::CollateralReserved
-            );
+            Self::ensure_collateral_not_reserved(collateral_asset_id)?;
             Self::accrue_interest(liquidation_asset_id)?;
             Self::accrue_interest(collateral_asset_id)?;
             Self::do_liquidate_borrow(
@@ -1136,6 +1428,375 @@ pub mod pallet {
             Self::deposit_event(Event::<T>::LiquidationFreeCollateralsUpdated(collaterals));
             Ok(().into())
         }
+
+        /// Updates the liquidation auction params of a collateral market.
+        ///
+        /// Liquidations seizing this collateral put it into a descending price auction
+        /// instead of transferring it to the liquidator. `None` restores instant liquidation.
+        ///
+        /// - `asset_id`: market related currency
+        /// - `config`: the new auction params
+        #[pallet::call_index(22)]
+        #[pallet::weight(T::WeightInfo::update_liquidation_auction_config())]
+        #[transactional]
+        pub fn update_liquidation_auction_config(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            config: Option<LiquidationAuctionConfig>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            Self::market(asset_id)?;
+            if let Some(config) = &config {
+                Self::ensure_valid_auction_config(config)?;
+            }
+            LiquidationAuctionConfigs::<T>::set(asset_id, config);
+            Self::deposit_event(Event::<T>::LiquidationAuctionConfigUpdated(
+                asset_id, config,
+            ));
+            Ok(().into())
+        }
+
+        /// The sender repays part of the debt of a liquidation auction and receives
+        /// collateral at the current auction price.
+        ///
+        /// - `auction_id`: the auction to bid on.
+        /// - `repay_amount`: the max amount of debt to be repaid.
+        #[pallet::call_index(23)]
+        #[pallet::weight(T::WeightInfo::bid_liquidation_auction())]
+        #[transactional]
+        pub fn bid_liquidation_auction(
+            origin: OriginFor<T>,
+            auction_id: AuctionId,
+            #[pallet::compact] repay_amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::do_bid_liquidation_auction(&who, auction_id, repay_amount)?;
+            Ok(().into())
+        }
+
+        /// Settles an expired liquidation auction, the reserves cover the debt left
+        /// and receive the collateral left.
+        ///
+        /// - `auction_id`: the auction to be settled.
+        #[pallet::call_index(24)]
+        #[pallet::weight(T::WeightInfo::settle_liquidation_auction())]
+        #[transactional]
+        pub fn settle_liquidation_auction(
+            origin: OriginFor<T>,
+            auction_id: AuctionId,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            Self::do_settle_liquidation_auction(auction_id)?;
+            Ok(().into())
+        }
+
+        /// Updates the isolation params of a collateral market.
+        ///
+        /// An isolated collateral must be the only collateral of an account and only
+        /// backs borrows of the whitelisted assets up to the debt ceiling. `None`
+        /// turns the market back into a regular market.
+        ///
+        /// - `asset_id`: market related currency
+        /// - `config`: the new isolation params
+        #[pallet::call_index(25)]
+        #[pallet::weight(T::WeightInfo::update_isolation_config())]
+        #[transactional]
+        pub fn update_isolation_config(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            config: Option<IsolationConfig<AssetIdOf<T>>>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            Self::market(asset_id)?;
+            if let Some(config) = &config {
+                Self::ensure_valid_isolation_config(config)?;
+            }
+            IsolationConfigs::<T>::set(asset_id, config.clone());
+            Self::deposit_event(Event::<T>::IsolationConfigUpdated(asset_id, config));
+            Ok(().into())
+        }
+
+        /// Creates, updates or removes an efficiency mode category.
+        ///
+        /// - `category_id`: the category to be updated
+        /// - `category`: the new category params, `None` removes the category
+        #[pallet::call_index(26)]
+        #[pallet::weight(T::WeightInfo::update_emode_category())]
+        #[transactional]
+        pub fn update_emode_category(
+            origin: OriginFor<T>,
+            category_id: EModeCategoryId,
+            category: Option<EModeCategory<AssetIdOf<T>>>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            if let Some(category) = &category {
+                Self::ensure_valid_emode_category(category)?;
+            }
+            EModeCategories::<T>::set(category_id, category.clone());
+            Self::deposit_event(Event::<T>::EModeCategoryUpdated(category_id, category));
+            Ok(().into())
+        }
+
+        /// The sender enters an efficiency mode category, or leaves it with `None`.
+        ///
+        /// All the borrows of the sender must be assets of the category.
+        ///
+        /// - `category_id`: the category to enter
+        #[pallet::call_index(27)]
+        #[pallet::weight(T::WeightInfo::set_emode())]
+        #[transactional]
+        pub fn set_emode(
+            origin: OriginFor<T>,
+            category_id: Option<EModeCategoryId>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::do_set_emode(&who, category_id)?;
+            Ok(().into())
+        }
+
+        /// Updates the flash loan fee of a market.
+        ///
+        /// - `asset_id`: market related currency
+        /// - `fee`: the fee ratio of the flash loaned amount, `None` disables flash loans
+        #[pallet::call_index(28)]
+        #[pallet::weight(T::WeightInfo::update_flash_loan_fee())]
+        #[transactional]
+        pub fn update_flash_loan_fee(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            fee: Option<Ratio>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            Self::market(asset_id)?;
+            if let Some(fee) = fee {
+                ensure!(fee < Ratio::one(), Error::<T>::InvalidFactor);
+            }
+            FlashLoanFees::<T>::set(asset_id, fee);
+            Self::deposit_event(Event::<T>::FlashLoanFeeUpdated(asset_id, fee));
+            Ok(().into())
+        }
+
+        /// Lends the cash of a market to the sender and dispatches `calls`, the loan
+        /// plus the market's fee must be repaid by the end of the call.
+        ///
+        /// - `asset_id`: the market to borrow from.
+        /// - `amount`: the amount to be borrowed.
+        /// - `calls`: the calls dispatched with the origin of the sender.
+        #[pallet::call_index(29)]
+        #[pallet::weight({
+            let dispatch_weight = calls
+                .iter()
+                .map(|call| call.get_dispatch_info().weight)
+                .fold(Weight::zero(), |total, weight| total.saturating_add(weight));
+            T::WeightInfo::flash_loan().saturating_add(dispatch_weight)
+        })]
+        #[transactional]
+        pub fn flash_loan(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            #[pallet::compact] amount: BalanceOf<T>,
+            calls: Vec<<T as Config>::RuntimeCall>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin.clone())?;
+            Self::do_flash_loan(origin, &who, asset_id, amount, calls)?;
+            Ok(().into())
+        }
+
+        /// Allows `delegatee` to borrow up to `amount` of an asset against the
+        /// collateral of the sender, replaces any previous allowance.
+        ///
+        /// - `asset_id`: the asset that may be borrowed.
+        /// - `delegatee`: the account allowed to borrow.
+        /// - `amount`: the allowance.
+        #[pallet::call_index(30)]
+        #[pallet::weight(T::WeightInfo::approve_borrow())]
+        #[transactional]
+        pub fn approve_borrow(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            delegatee: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let delegatee = T::Lookup::lookup(delegatee)?;
+            Self::do_approve_borrow(&who, &delegatee, asset_id, amount)?;
+            Ok(().into())
+        }
+
+        /// Removes the borrow allowance of `delegatee`, the debt already borrowed
+        /// stays with the sender.
+        ///
+        /// - `asset_id`: the asset of the allowance.
+        /// - `delegatee`: the account allowed to borrow.
+        #[pallet::call_index(31)]
+        #[pallet::weight(T::WeightInfo::revoke_borrow_allowance())]
+        #[transactional]
+        pub fn revoke_borrow_allowance(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            delegatee: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let delegatee = T::Lookup::lookup(delegatee)?;
+            Self::do_revoke_borrow_allowance(&who, &delegatee, asset_id)?;
+            Ok(().into())
+        }
+
+        /// Sender borrows against the collateral of `delegator`, the debt accrues
+        /// to `delegator` and the borrowed assets go to the sender.
+        ///
+        /// - `asset_id`: the asset to be borrowed.
+        /// - `delegator`: the account that approved the allowance.
+        /// - `borrow_amount`: the amount to be borrowed.
+        #[pallet::call_index(32)]
+        #[pallet::weight(T::WeightInfo::borrow_on_behalf())]
+        #[transactional]
+        pub fn borrow_on_behalf(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            delegator: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] borrow_amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let delegator = T::Lookup::lookup(delegator)?;
+            Self::do_borrow_on_behalf(&who, &delegator, asset_id, borrow_amount)?;
+            Ok(().into())
+        }
+
+        /// Updates the stable rate borrowing params of a market.
+        ///
+        /// - `asset_id`: market related currency
+        /// - `config`: the stable rate params, `None` disables new stable rate borrows
+        #[pallet::call_index(33)]
+        #[pallet::weight(T::WeightInfo::update_stable_borrow_config())]
+        #[transactional]
+        pub fn update_stable_borrow_config(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            config: Option<StableBorrowConfig>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            Self::market(asset_id)?;
+            StableBorrowConfigs::<T>::set(asset_id, config);
+            Self::deposit_event(Event::<T>::StableBorrowConfigUpdated(asset_id, config));
+            Ok(().into())
+        }
+
+        /// Sender borrows assets at a rate locked at borrow time.
+        ///
+        /// - `asset_id`: the asset to be borrowed.
+        /// - `borrow_amount`: the amount to be borrowed.
+        #[pallet::call_index(34)]
+        #[pallet::weight(T::WeightInfo::borrow_stable())]
+        #[transactional]
+        pub fn borrow_stable(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            #[pallet::compact] borrow_amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::do_borrow_stable(&who, asset_id, borrow_amount)?;
+            Ok(().into())
+        }
+
+        /// Re-prices the stable rate borrow of `borrower` at the current stable rate,
+        /// allowed once the supply rate of the market falls below its rebalance threshold.
+        ///
+        /// - `borrower`: the account of the stable rate borrow.
+        /// - `asset_id`: the borrowed asset.
+        #[pallet::call_index(35)]
+        #[pallet::weight(T::WeightInfo::rebalance_stable_borrow_rate())]
+        #[transactional]
+        pub fn rebalance_stable_borrow_rate(
+            origin: OriginFor<T>,
+            borrower: <T::Lookup as StaticLookup>::Source,
+            asset_id: AssetIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            let borrower = T::Lookup::lookup(borrower)?;
+            Self::do_rebalance_stable_borrow_rate(&borrower, asset_id)?;
+            Ok(().into())
+        }
+
+        /// The recipient pledges the unvested balance of a non-cancellable stream as
+        /// collateral, the stream can't be cancelled or transferred while pledged.
+        ///
+        /// - `stream_id`: the stream to be pledged.
+        #[pallet::call_index(36)]
+        #[pallet::weight(T::WeightInfo::pledge_stream())]
+        #[transactional]
+        pub fn pledge_stream(
+            origin: OriginFor<T>,
+            stream_id: StreamId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::do_pledge_stream(&who, stream_id)?;
+            Ok(().into())
+        }
+
+        /// The recipient releases a pledged stream after checking the liquidity.
+        ///
+        /// - `stream_id`: the stream to be released.
+        #[pallet::call_index(37)]
+        #[pallet::weight(T::WeightInfo::release_stream())]
+        #[transactional]
+        pub fn release_stream(
+            origin: OriginFor<T>,
+            stream_id: StreamId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::do_release_stream(&who, stream_id)?;
+            Ok(().into())
+        }
+
+        /// The liquidator repays the debt of a borrower under its liquidation threshold
+        /// and seizes a stream pledged by the borrower, the withdrawals of the stream pay
+        /// the repaid value plus the liquidation incentive to the liquidator.
+        ///
+        /// - `borrower`: the borrower to be liquidated.
+        /// - `stream_id`: the stream to seize from the borrower.
+        /// - `liquidation_asset_id`: the asset of the debt to be repaid.
+        /// - `repay_amount`: the amount to be repaid borrow.
+        #[pallet::call_index(38)]
+        #[pallet::weight(T::WeightInfo::liquidate_stream())]
+        #[transactional]
+        pub fn liquidate_stream(
+            origin: OriginFor<T>,
+            borrower: <T::Lookup as StaticLookup>::Source,
+            stream_id: StreamId,
+            liquidation_asset_id: AssetIdOf<T>,
+            #[pallet::compact] repay_amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let borrower = T::Lookup::lookup(borrower)?;
+            Self::accrue_interest(liquidation_asset_id)?;
+            Self::do_liquidate_stream(
+                &who,
+                &borrower,
+                stream_id,
+                liquidation_asset_id,
+                repay_amount,
+            )?;
+            Ok(().into())
+        }
+
+        /// Withdraws the vested balance of a seized stream to pay the liquidator, then
+        /// to repay the debt of the borrower in the asset of the stream, the rest goes to
+        /// the borrower. The stream goes back to the borrower, still pledged, once the
+        /// liquidator is paid.
+        ///
+        /// - `stream_id`: the seized stream.
+        #[pallet::call_index(39)]
+        #[pallet::weight(T::WeightInfo::repay_borrow_with_stream())]
+        #[transactional]
+        pub fn repay_borrow_with_stream(
+            origin: OriginFor<T>,
+            stream_id: StreamId,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            Self::do_repay_borrow_with_stream(stream_id)?;
+            Ok(().into())
+        }
     }
 }
 
@@ -1307,7 +1968,8 @@ impl<T: Config> Pallet<T> {
         let underlying_amount =
             Self::calc_underlying_amount(deposits.voucher_balance, exchange_rate)?;
         let market = Self::market(asset_id)?;
-        let effects_amount = market.collateral_factor.mul_ceil(underlying_amount);
+        let (collateral_factor, _) = Self::account_risk_params(supplier, asset_id, &market);
+        let effects_amount = collateral_factor.mul_ceil(underlying_amount);
 
         Ok(BalanceOf::<T>::saturated_from(effects_amount))
     }
@@ -1339,7 +2001,8 @@ impl<T: Config> Pallet<T> {
         let underlying_amount =
             Self::calc_underlying_amount(deposits.voucher_balance, exchange_rate)?;
         let market = Self::market(asset_id)?;
-        let effects_amount = market.liquidation_threshold.mul_ceil(underlying_amount);
+        let (_, liquidation_threshold) = Self::account_risk_params(borrower, asset_id, &market);
+        let effects_amount = liquidation_threshold.mul_ceil(underlying_amount);
 
         Self::get_asset_value(asset_id, effects_amount)
     }
@@ -1351,6 +2014,9 @@ impl<T: Config> Pallet<T> {
                 .checked_add(&Self::collateral_asset_value(supplier, asset_id)?)
                 .ok_or(ArithmeticError::Overflow)?;
         }
+        total_asset_value = total_asset_value
+            .checked_add(&Self::stream_collateral_value(supplier, false)?)
+            .ok_or(ArithmeticError::Overflow)?;
 
         Ok(total_asset_value)
     }
@@ -1366,6 +2032,9 @@ impl<T: Config> Pallet<T> {
                 )?)
                 .ok_or(ArithmeticError::Overflow)?;
         }
+        total_asset_value = total_asset_value
+            .checked_add(&Self::stream_collateral_value(borrower, true)?)
+            .ok_or(ArithmeticError::Overflow)?;
 
         Ok(total_asset_value)
     }
@@ -1464,6 +2133,7 @@ impl<T: Config> Pallet<T> {
         Self::ensure_under_borrow_cap(asset_id, borrow_amount)?;
         Self::ensure_enough_cash(asset_id, borrow_amount)?;
         let borrow_value = Self::get_asset_value(asset_id, borrow_amount)?;
+        Self::ensure_borrow_mode_allowed(borrower, asset_id, borrow_value)?;
         Self::ensure_liquidity(
             borrower,
             borrow_value,
@@ -1473,6 +2143,68 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Records `amount` of new debt for `borrower` and sends the borrowed assets
+    /// to `receiver`.
+    ///
+    /// A `stable` borrow locks the current stable rate of the market, the rate of
+    /// the existing stable debt is blended with it.
+    fn borrow_to(
+        borrower: &T::AccountId,
+        receiver: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+        stable: bool,
+    ) -> DispatchResult {
+        Self::ensure_active_market(asset_id)?;
+        let snapshot = Self::account_borrows(asset_id, borrower);
+        ensure!(
+            snapshot.principal.is_zero() || snapshot.stable_rate.is_some() == stable,
+            Error::<T>::BorrowRateModeMismatch
+        );
+
+        Self::accrue_interest(asset_id)?;
+        Self::borrow_allowed(asset_id, borrower, amount)?;
+
+        // update borrow index after accrue interest.
+        Self::update_reward_borrow_index(asset_id)?;
+        Self::distribute_borrower_reward(asset_id, borrower)?;
+
+        let account_borrows = Self::current_borrow_balance(borrower, asset_id)?;
+        let account_borrows_new = account_borrows
+            .checked_add(amount)
+            .ok_or(ArithmeticError::Overflow)?;
+        let stable_rate = if stable {
+            let rate = Self::stable_borrow_rate(asset_id, amount)?;
+            Self::increase_stable_borrows(asset_id, amount, rate)?;
+            Some(Self::weighted_rate(
+                snapshot.stable_rate.unwrap_or_default(),
+                account_borrows,
+                rate,
+                amount,
+            )?)
+        } else {
+            let total_borrows = Self::total_borrows(asset_id);
+            let total_borrows_new = total_borrows
+                .checked_add(amount)
+                .ok_or(ArithmeticError::Overflow)?;
+            TotalBorrows::<T>::insert(asset_id, total_borrows_new);
+            None
+        };
+        AccountBorrows::<T>::insert(
+            asset_id,
+            borrower,
+            BorrowSnapshot {
+                principal: account_borrows_new,
+                borrow_index: Self::borrow_index(asset_id),
+                stable_rate,
+                last_update: Self::last_accrued_interest_time(asset_id),
+            },
+        );
+        Self::increase_isolated_borrows(borrower, asset_id, amount)?;
+        T::Assets::transfer(asset_id, &Self::account_id(), receiver, amount, false)?;
+        Ok(())
+    }
+
     #[require_transactional]
     fn do_repay_borrow_with_amount(
         borrower: &T::AccountId,
@@ -1487,25 +2219,46 @@ impl<T: Config> Pallet<T> {
         Self::distribute_borrower_reward(asset_id, borrower)?;
 
         T::Assets::transfer(asset_id, borrower, &Self::account_id(), repay_amount, false)?;
+        Self::reduce_borrow(borrower, asset_id, account_borrows, repay_amount)?;
+
+        Ok(())
+    }
+
+    /// Reduces the debt of `borrower`, whose current borrow balance is `account_borrows`,
+    /// by `amount`.
+    pub(crate) fn reduce_borrow(
+        borrower: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+        account_borrows: BalanceOf<T>,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
         let account_borrows_new = account_borrows
-            .checked_sub(repay_amount)
+            .checked_sub(amount)
             .ok_or(ArithmeticError::Underflow)?;
-        let total_borrows = Self::total_borrows(asset_id);
+        let stable_rate = Self::account_borrows(asset_id, borrower).stable_rate;
         // NOTE : total_borrows use a different way to calculate interest
         // so when user repays all borrows, total_borrows can be less than account_borrows
         // which will cause it to fail with `ArithmeticError::Underflow`
         //
         // Change it back to checked_sub will cause Underflow
-        let total_borrows_new = total_borrows.saturating_sub(repay_amount);
+        match stable_rate {
+            Some(rate) => Self::decrease_stable_borrows(asset_id, amount, rate),
+            None => TotalBorrows::<T>::mutate(asset_id, |total_borrows| {
+                *total_borrows = total_borrows.saturating_sub(amount)
+            }),
+        }
         AccountBorrows::<T>::insert(
             asset_id,
             borrower,
             BorrowSnapshot {
                 principal: account_borrows_new,
                 borrow_index: Self::borrow_index(asset_id),
+                // the rate mode can change once the debt is fully repaid
+                stable_rate: stable_rate.filter(|_| !account_borrows_new.is_zero()),
+                last_update: Self::last_accrued_interest_time(asset_id),
             },
         );
-        TotalBorrows::<T>::insert(asset_id, total_borrows_new);
+        Self::reduce_isolated_borrows(borrower, asset_id, amount);
 
         Ok(())
     }
@@ -1520,6 +2273,15 @@ impl<T: Config> Pallet<T> {
         if snapshot.principal.is_zero() || snapshot.borrow_index.is_zero() {
             return Ok(Zero::zero());
         }
+        // Stable rate borrows accrue simple interest at the locked rate
+        // up to the last accrual of the market
+        if let Some(stable_rate) = snapshot.stable_rate {
+            let delta_time =
+                Self::last_accrued_interest_time(asset_id).saturating_sub(snapshot.last_update);
+            return Self::accrued_interest(stable_rate, snapshot.principal, delta_time)
+                .and_then(|interest| interest.checked_add(snapshot.principal))
+                .ok_or_else(|| ArithmeticError::Overflow.into());
+        }
         // Calculate new borrow balance using the interest index:
         // recent_borrow_balance = snapshot.principal * borrow_index / snapshot.borrow_index
         let recent_borrow_balance = Self::borrow_index(asset_id)
@@ -1556,6 +2318,26 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Returns how far `borrower` is below its liquidation threshold, the account
+    /// can be liquidated if it isn't zero.
+    pub(crate) fn liquidation_shortfall(
+        borrower: &T::AccountId,
+    ) -> Result<Shortfall, DispatchError> {
+        let (liquidity, shortfall, lf_liquidity, _) =
+            Self::get_account_liquidation_threshold_liquidity(borrower)?;
+
+        // C_other >= B_other + B_dot_over
+        // C_other >= B_other + max(B_dot - C_lf, 0)
+        // C_other + C_lf >= B_other + B_dot - B_dot + C_lf + max(B_dot - C_lf, 0)
+        // C_all - B_all >= max(0, C_lf - B_dot)
+        // C_all - B_all >= 0 && C_all - B_all >= max(0, C_lf - B_dot)
+        // shortfall == 0 && liquidity > lf_liquidity
+        if !shortfall.is_zero() {
+            return Ok(shortfall);
+        }
+        Ok(lf_liquidity.saturating_sub(liquidity))
+    }
+
     /// Checks if the liquidation should be allowed to occur
     fn liquidate_borrow_allowed(
         borrower: &T::AccountId,
@@ -1571,16 +2353,7 @@ impl<T: Config> Pallet<T> {
             repay_amount,
             market
         );
-        let (liquidity, shortfall, lf_liquidity, _) =
-            Self::get_account_liquidation_threshold_liquidity(borrower)?;
-
-        // C_other >= B_other + B_dot_over
-        // C_other >= B_other + max(B_dot - C_lf, 0)
-        // C_other + C_lf >= B_other + B_dot - B_dot + C_lf + max(B_dot - C_lf, 0)
-        // C_all - B_all >= max(0, C_lf - B_dot)
-        // C_all - B_all >= 0 && C_all - B_all >= max(0, C_lf - B_dot)
-        // shortfall == 0 && liquidity > lf_liquidity
-        if shortfall.is_zero() && liquidity >= lf_liquidity {
+        if Self::liquidation_shortfall(borrower)?.is_zero() {
             return Err(Error::<T>::InsufficientShortfall.into());
         }
 
@@ -1619,6 +2392,9 @@ impl<T: Config> Pallet<T> {
     /// account for borrower. Then the protocol will reduce borrower's debt
     /// and liquidator will receive collateral_asset_id(as voucher amount) from
     /// borrower.
+    ///
+    /// If collateral_asset_id has liquidation auction params, the liquidator repays
+    /// nothing and the seized collateral is put into an auction instead.
     #[require_transactional]
     pub fn do_liquidate_borrow(
         liquidator: T::AccountId,
@@ -1627,17 +2403,58 @@ impl<T: Config> Pallet<T> {
         repay_amount: BalanceOf<T>,
         collateral_asset_id: AssetIdOf<T>,
     ) -> DispatchResult {
-        Self::ensure_active_market(liquidation_asset_id)?;
-        Self::ensure_active_market(collateral_asset_id)?;
-
-        let market = Self::market(liquidation_asset_id)?;
-
         if borrower == liquidator {
             return Err(Error::<T>::LiquidatorIsBorrower.into());
         }
-        Self::liquidate_borrow_allowed(&borrower, liquidation_asset_id, repay_amount, &market)?;
+        let market = Self::market(liquidation_asset_id)?;
+        let real_collateral_underlying_amount = Self::calc_seized_collateral(
+            &borrower,
+            liquidation_asset_id,
+            repay_amount,
+            collateral_asset_id,
+            &market,
+        )?;
+
+        if let Some(config) = Self::liquidation_auction_config(collateral_asset_id) {
+            return Self::start_liquidation_auction(
+                &liquidator,
+                &borrower,
+                liquidation_asset_id,
+                collateral_asset_id,
+                repay_amount,
+                real_collateral_underlying_amount,
+                config,
+            );
+        }
+
+        //inside transfer token
+        Self::liquidated_transfer(
+            &liquidator,
+            &borrower,
+            liquidation_asset_id,
+            collateral_asset_id,
+            repay_amount,
+            real_collateral_underlying_amount,
+            &market,
+        )?;
+
+        Ok(())
+    }
+
+    /// Checks the liquidation and returns the underlying amount of `collateral_asset_id`
+    /// seized from `borrower` for `repay_amount`.
+    pub(crate) fn calc_seized_collateral(
+        borrower: &T::AccountId,
+        liquidation_asset_id: AssetIdOf<T>,
+        repay_amount: BalanceOf<T>,
+        collateral_asset_id: AssetIdOf<T>,
+        market: &Market<BalanceOf<T>>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        Self::ensure_active_market(liquidation_asset_id)?;
+        Self::ensure_active_market(collateral_asset_id)?;
+        Self::liquidate_borrow_allowed(borrower, liquidation_asset_id, repay_amount, market)?;
 
-        let deposits = AccountDeposits::<T>::get(collateral_asset_id, &borrower);
+        let deposits = AccountDeposits::<T>::get(collateral_asset_id, borrower);
         if !deposits.is_collateral {
             return Err(Error::<T>::DepositsAreNotCollateral.into());
         }
@@ -1671,18 +2488,22 @@ impl<T: Config> Pallet<T> {
             .ok_or(ArithmeticError::Underflow)?
             .into_inner();
 
-        //inside transfer token
-        Self::liquidated_transfer(
-            &liquidator,
-            &borrower,
-            liquidation_asset_id,
-            collateral_asset_id,
-            repay_amount,
-            real_collateral_underlying_amount,
-            &market,
-        )?;
+        Ok(real_collateral_underlying_amount)
+    }
 
-        Ok(())
+    /// The part of the seized `collateral_amount` vouchers kept as incentive reserves
+    pub(crate) fn calc_incentive_reserved_amount(
+        collateral_amount: BalanceOf<T>,
+        market: &Market<BalanceOf<T>>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let incentive_reserved_amount = market.liquidate_incentive_reserved_factor.mul_floor(
+            FixedU128::from_inner(collateral_amount)
+                .checked_div(&market.liquidate_incentive)
+                .map(|r| r.into_inner())
+                .ok_or(ArithmeticError::Underflow)?,
+        );
+
+        Ok(incentive_reserved_amount)
     }
 
     #[require_transactional]
@@ -1723,22 +2544,12 @@ impl<T: Config> Pallet<T> {
 
         // 2.the system reduce borrower's debt
         let account_borrows = Self::current_borrow_balance(borrower, liquidation_asset_id)?;
-        let account_borrows_new = account_borrows
-            .checked_sub(repay_amount)
-            .ok_or(ArithmeticError::Underflow)?;
-        let total_borrows = Self::total_borrows(liquidation_asset_id);
-        let total_borrows_new = total_borrows
-            .checked_sub(repay_amount)
-            .ok_or(ArithmeticError::Underflow)?;
-        AccountBorrows::<T>::insert(
-            liquidation_asset_id,
+        Self::reduce_borrow(
             borrower,
-            BorrowSnapshot {
-                principal: account_borrows_new,
-                borrow_index: Self::borrow_index(liquidation_asset_id),
-            },
-        );
-        TotalBorrows::<T>::insert(liquidation_asset_id, total_borrows_new);
+            liquidation_asset_id,
+            account_borrows,
+            repay_amount,
+        )?;
 
         // update supply index before modify supply balance.
         Self::update_reward_supply_index(collateral_asset_id)?;
@@ -1764,12 +2575,8 @@ impl<T: Config> Pallet<T> {
                 Ok(())
             },
         )?;
-        let incentive_reserved_amount = market.liquidate_incentive_reserved_factor.mul_floor(
-            FixedU128::from_inner(collateral_amount)
-                .checked_div(&market.liquidate_incentive)
-                .map(|r| r.into_inner())
-                .ok_or(ArithmeticError::Underflow)?,
-        );
+        let incentive_reserved_amount =
+            Self::calc_incentive_reserved_amount(collateral_amount, market)?;
         // increase liquidator's voucher_balance
         AccountDeposits::<T>::try_mutate(
             collateral_asset_id,
@@ -1815,6 +2622,17 @@ impl<T: Config> Pallet<T> {
             .ok_or_else(|| Error::<T>::MarketNotActivated.into())
     }
 
+    // Ensures `collateral_asset_id` can be seized by liquidations.
+    fn ensure_collateral_not_reserved(collateral_asset_id: AssetIdOf<T>) -> DispatchResult {
+        ensure!(
+            !Self::liquidation_free_collaterals().contains(&collateral_asset_id)
+                && !is_auxiliary_token(collateral_asset_id),
+            Error::<T>::CollateralReserved
+        );
+
+        Ok(())
+    }
+
     /// Ensure market is enough to supply `amount` asset.
     fn ensure_under_supply_cap(asset_id: AssetIdOf<T>, amount: BalanceOf<T>) -> DispatchResult {
         let market = Self::market(asset_id)?;
@@ -1836,7 +2654,8 @@ impl<T: Config> Pallet<T> {
         let market = Self::market(asset_id)?;
         let total_borrows = Self::total_borrows(asset_id);
         let new_total_borrows = total_borrows
-            .checked_add(amount)
+            .checked_add(Self::total_stable_borrows(asset_id))
+            .and_then(|r| r.checked_add(amount))
             .ok_or(ArithmeticError::Overflow)?;
         ensure!(
             new_total_borrows <= market.borrow_cap,
@@ -1848,7 +2667,7 @@ impl<T: Config> Pallet<T> {
 
     /// Make sure there is enough cash available in the pool
     fn ensure_enough_cash(asset_id: AssetIdOf<T>, amount: BalanceOf<T>) -> DispatchResult {
-        let reducible_cash = Self::get_total_cash(asset_id)
+        let reducible_cash = T::Assets::reducible_balance(asset_id, &Self::account_id(), false)
             .checked_sub(Self::total_reserves(asset_id))
             .ok_or(ArithmeticError::Underflow)?;
         if reducible_cash < amount {
@@ -1917,8 +2736,10 @@ impl<T: Config> Pallet<T> {
             .ok_or(ArithmeticError::Underflow)?)
     }
 
+    // Returns the cash of the market, including the amount lent by ongoing flash loans.
     fn get_total_cash(asset_id: AssetIdOf<T>) -> BalanceOf<T> {
         T::Assets::reducible_balance(asset_id, &Self::account_id(), false)
+            .saturating_add(Self::flash_loan_outstanding(asset_id))
     }
 
     // Returns the uniform format price.
@@ -2063,33 +2884,7 @@ impl<T: Config> LoansTrait<AssetIdOf<T>, AccountIdOf<T>, BalanceOf<T>> for Palle
         asset_id: AssetIdOf<T>,
         amount: BalanceOf<T>,
     ) -> Result<(), DispatchError> {
-        Self::ensure_active_market(asset_id)?;
-
-        Self::accrue_interest(asset_id)?;
-        Self::borrow_allowed(asset_id, borrower, amount)?;
-
-        // update borrow index after accrue interest.
-        Self::update_reward_borrow_index(asset_id)?;
-        Self::distribute_borrower_reward(asset_id, borrower)?;
-
-        let account_borrows = Self::current_borrow_balance(borrower, asset_id)?;
-        let account_borrows_new = account_borrows
-            .checked_add(amount)
-            .ok_or(ArithmeticError::Overflow)?;
-        let total_borrows = Self::total_borrows(asset_id);
-        let total_borrows_new = total_borrows
-            .checked_add(amount)
-            .ok_or(ArithmeticError::Overflow)?;
-        AccountBorrows::<T>::insert(
-            asset_id,
-            borrower,
-            BorrowSnapshot {
-                principal: account_borrows_new,
-                borrow_index: Self::borrow_index(asset_id),
-            },
-        );
-        TotalBorrows::<T>::insert(asset_id, total_borrows_new);
-        T::Assets::transfer(asset_id, &Self::account_id(), borrower, amount, false)?;
+        Self::borrow_to(borrower, borrower, asset_id, amount, false)?;
         Self::deposit_event(Event::<T>::Borrowed(borrower.clone(), asset_id, amount));
         Ok(())
     }
@@ -2107,6 +2902,7 @@ impl<T: Config> LoansTrait<AssetIdOf<T>, AccountIdOf<T>, BalanceOf<T>> for Palle
         let mut deposits = Self::account_deposits(asset_id, supplier);
         // turn on the collateral button
         if enable {
+            Self::ensure_collateral_mode_allowed(supplier, asset_id)?;
             deposits.is_collateral = true;
             AccountDeposits::<T>::insert(asset_id, supplier, deposits);
             Self::deposit_event(Event::<T>::CollateralAssetAdded(supplier.clone(), asset_id));
@@ -2189,35 +2985,13 @@ impl<T: Config> LoansMarketDataProvider<AssetIdOf<T>, BalanceOf<T>> for Pallet<T
     }
 
     fn get_market_status(asset_id: AssetIdOf<T>) -> Result<MarketStatus<Balance>, DispatchError> {
-        let (
-            borrow_rate,
-            supply_rate,
-            exchange_rate,
-            utilization,
-            total_borrows,
-            total_reserves,
-            borrow_index,
-        ) = Self::get_market_status(asset_id)?;
-        Ok(MarketStatus {
-            borrow_rate,
-            supply_rate,
-            exchange_rate,
-            utilization,
-            total_borrows,
-            total_reserves,
-            borrow_index,
-        })
+        Self::get_market_status(asset_id)
     }
 
     fn get_full_interest_rate(asset_id: AssetIdOf<T>) -> Option<Rate> {
-        if let Ok(market) = Self::market(asset_id) {
-            let rate = match market.rate_model {
-                InterestRateModel::Jump(jump) => Some(jump.full_rate),
-                _ => None,
-            };
-            return rate;
-        }
-        None
+        Self::market(asset_id)
+            .ok()
+            .and_then(|market| market.rate_model.get_full_rate())
     }
 }
 
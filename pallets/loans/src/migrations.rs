@@ -188,3 +188,96 @@ pub mod v3 {
         Ok(())
     }
 }
+
+pub mod v7 {
+    use super::*;
+    use crate::{pallet::StorageVersion, Config, Weight};
+    use frame_support::{
+        log,
+        traits::{Get, OnRuntimeUpgrade},
+    };
+
+    #[derive(Clone, Copy, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+    pub struct V6BorrowSnapshot<Balance> {
+        /// Principal Total balance (with accrued interest), after applying the most recent balance-changing action
+        pub principal: Balance,
+        /// InterestIndex Global borrowIndex as of the most recent balance-changing action
+        pub borrow_index: Rate,
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T>::get() == crate::Versions::V6,
+            "must upgrade linearly"
+        );
+        let borrows_count = AccountBorrows::<T>::iter_keys().count();
+        log::info!("total {:#?} account borrows need to migrate", borrows_count);
+
+        log::info!("👜 loans v7 migration passes PRE migrate checks ✅",);
+
+        Ok(())
+    }
+
+    /// Migration to borrow snapshots with a rate mode, all existing borrows are variable.
+    pub fn migrate<T: Config>() -> Weight {
+        if StorageVersion::<T>::get() == crate::Versions::V6 {
+            log::info!("migrating loans to Versions::V7",);
+
+            let mut count = 0u64;
+            AccountBorrows::<T>::translate::<V6BorrowSnapshot<BalanceOf<T>>, _>(
+                |asset_id, _borrower, snapshot| {
+                    count += 1;
+                    Some(BorrowSnapshot {
+                        principal: snapshot.principal,
+                        borrow_index: snapshot.borrow_index,
+                        stable_rate: None,
+                        last_update: LastAccruedInterestTime::<T>::get(asset_id),
+                    })
+                },
+            );
+
+            StorageVersion::<T>::put(crate::Versions::V7);
+            log::info!("👜 completed loans migration to Versions::V7",);
+
+            T::DbWeight::get().reads_writes(count.saturating_mul(2) + 1, count + 1)
+        } else {
+            T::DbWeight::get().reads(1)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T>::get() == crate::Versions::V7,
+            "must upgrade to V7"
+        );
+        frame_support::ensure!(
+            AccountBorrows::<T>::iter().all(|(_, _, snapshot)| snapshot.stable_rate.is_none()),
+            "all migrated borrows must be variable"
+        );
+
+        log::info!("👜 loans v7 migration passes POST migrate checks ✅",);
+
+        Ok(())
+    }
+
+    /// Runs the v7 migration on runtime upgrade
+    pub struct MigrateToV7<T>(sp_std::marker::PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV7<T> {
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            pre_migrate::<T>()?;
+            Ok(Vec::new())
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+            post_migrate::<T>()
+        }
+    }
+}
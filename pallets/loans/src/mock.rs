@@ -23,8 +23,9 @@ use frame_support::{
 use frame_system::{EnsureRoot, EnsureSigned, EnsureSignedBy};
 use orml_traits::{DataFeeder, DataProvider, DataProviderExtended};
 use pallet_traits::{
-    DecimalProvider, ExchangeRateProvider, LiquidStakingCurrenciesProvider,
-    VaultTokenCurrenciesFilter, VaultTokenExchangeRateProvider,
+    DecimalProvider, ExchangeRateProvider, LPVaultTokenCurrenciesFilter,
+    LPVaultTokenExchangeRateProvider, LiquidStakingCurrenciesProvider, VaultTokenCurrenciesFilter,
+    VaultTokenExchangeRateProvider,
 };
 use primitives::{
     tokens::{CDOT_6_13, PCDOT_6_13},
@@ -54,6 +55,7 @@ construct_runtime!(
         Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
         DefaultAMM: pallet_amm::{Pallet, Call, Storage, Event<T>},
         CurrencyAdapter: pallet_currency_adapter::{Pallet, Call},
+        Streaming: pallet_streaming::{Pallet, Call, Storage, Event<T>},
     }
 );
 
@@ -203,6 +205,24 @@ impl VaultTokenCurrenciesFilter<CurrencyId> for TokenCurrenciesFilter {
     }
 }
 
+pub struct LPVaultTokenFilter;
+impl LPVaultTokenCurrenciesFilter<CurrencyId> for LPVaultTokenFilter {
+    fn contains(_asset_id: &CurrencyId) -> bool {
+        false
+    }
+}
+
+pub struct LPVaultTokenRateProvider;
+impl LPVaultTokenExchangeRateProvider<CurrencyId> for LPVaultTokenRateProvider {
+    fn get_exchange_rate(_: &CurrencyId) -> Option<Rate> {
+        None
+    }
+
+    fn get_staking_asset(_: &CurrencyId) -> Option<CurrencyId> {
+        None
+    }
+}
+
 pub struct VaultLoansRateProvider;
 impl LoansMarketDataProvider<CurrencyId, Balance> for VaultLoansRateProvider {
     fn get_full_interest_rate(_asset_id: CurrencyId) -> Option<Rate> {
@@ -231,6 +251,8 @@ parameter_types! {
     // pub const DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);
     pub  DefaultLpFee: Ratio = Ratio::from_rational(25u32, 10000u32);         // 0.3%
     pub const MinimumLiquidity: u128 = 1_000u128;
+    pub const MaxTicksPerPool: u32 = 1_000;
+    pub const MinPositionLiquidity: u128 = 1_000_000u128;
     pub const LockAccountId: AccountId = ALICE;
     pub const MaxLengthRoute: u8 = 10;
 }
@@ -254,6 +276,14 @@ impl pallet_amm::Config for Test {
     type MinimumLiquidity = MinimumLiquidity;
     type MaxLengthRoute = MaxLengthRoute;
     type GetNativeCurrencyId = NativeCurrencyId;
+    type MaxTicksPerPool = MaxTicksPerPool;
+    type MinPositionLiquidity = MinPositionLiquidity;
+    type PoolsHook = ();
+}
+
+parameter_types! {
+    pub const MaxPriceGuards: u32 = 10;
+    pub const MaxTwapSources: u32 = 10;
 }
 
 impl pallet_prices::Config for Test {
@@ -265,9 +295,14 @@ impl pallet_prices::Config for Test {
     type LiquidStakingCurrenciesProvider = LiquidStaking;
     type VaultTokenCurrenciesFilter = TokenCurrenciesFilter;
     type VaultTokenExchangeRateProvider = TokenExchangeRateProvider;
+    type LPVaultTokenCurrenciesFilter = LPVaultTokenFilter;
+    type LPVaultTokenExchangeRateProvider = LPVaultTokenRateProvider;
     type VaultLoansRateProvider = VaultLoansRateProvider;
     type RelayCurrency = RelayCurrency;
     type Decimal = Decimal;
+    type UnixTime = TimestampPallet;
+    type MaxPriceGuards = MaxPriceGuards;
+    type MaxTwapSources = MaxTwapSources;
     type AMM = DefaultAMM;
     type Assets = CurrencyAdapter;
     type WeightInfo = ();
@@ -308,6 +343,31 @@ impl PriceFeeder for MockPriceFeeder {
     }
 }
 
+pub struct MockVotingEscrow;
+
+impl MockVotingEscrow {
+    thread_local! {
+        pub static VOTING_POWERS: RefCell<HashMap<AccountId, Balance>> = RefCell::new(HashMap::new());
+    }
+
+    pub fn set_voting_power(who: AccountId, voting_power: Balance) {
+        Self::VOTING_POWERS.with(|voting_powers| {
+            voting_powers.borrow_mut().insert(who, voting_power);
+        });
+    }
+}
+
+impl VotingEscrow<AccountId, Balance> for MockVotingEscrow {
+    fn voting_power(who: &AccountId) -> Balance {
+        Self::VOTING_POWERS
+            .with(|voting_powers| voting_powers.borrow().get(who).copied().unwrap_or_default())
+    }
+
+    fn total_voting_power() -> Balance {
+        Self::VOTING_POWERS.with(|voting_powers| voting_powers.borrow().values().sum())
+    }
+}
+
 parameter_types! {
     pub const AssetDeposit: u64 = 1;
     pub const ApprovalDeposit: u64 = 1;
@@ -344,6 +404,9 @@ parameter_types! {
     pub const LoansPalletId: PalletId = PalletId(*b"par/loan");
     pub const RewardAssetId: CurrencyId = HKO;
     pub const LiquidationFreeAssetId: CurrencyId = DOT;
+    pub StreamCollateralDiscount: Ratio = Ratio::from_percent(30);
+    pub const MaxStreamCollaterals: u32 = 2;
+    pub MaxRewardBoost: Rate = Rate::saturating_from_rational(5, 2);
 }
 
 impl Config for Test {
@@ -357,6 +420,13 @@ impl Config for Test {
     type Assets = CurrencyAdapter;
     type RewardAssetId = RewardAssetId;
     type LiquidationFreeAssetId = LiquidationFreeAssetId;
+    type RuntimeCall = RuntimeCall;
+    type FlashLoanReceiver = ();
+    type StreamCollateral = Streaming;
+    type StreamCollateralDiscount = StreamCollateralDiscount;
+    type MaxStreamCollaterals = MaxStreamCollaterals;
+    type VotingEscrow = MockVotingEscrow;
+    type MaxRewardBoost = MaxRewardBoost;
 }
 
 parameter_types! {
@@ -370,6 +440,27 @@ impl pallet_currency_adapter::Config for Test {
     type LockOrigin = EnsureRoot<AccountId>;
 }
 
+parameter_types! {
+    pub const StreamPalletId: PalletId = PalletId(*b"par/strm");
+    pub const MaxStreamsCount: u32 = 128;
+    pub const MaxFinishedStreamsCount: u32 = 2;
+    pub const MaxSchedulePoints: u32 = 4;
+}
+
+impl pallet_streaming::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type PalletId = StreamPalletId;
+    type MaxStreamsCount = MaxStreamsCount;
+    type MaxFinishedStreamsCount = MaxFinishedStreamsCount;
+    type UnixTime = TimestampPallet;
+    type Assets = CurrencyAdapter;
+    type UpdateOrigin = EnsureRoot<AccountId>;
+    type WeightInfo = ();
+    type NativeCurrencyId = NativeCurrencyId;
+    type NativeExistentialDeposit = ExistentialDeposit;
+    type MaxSchedulePoints = MaxSchedulePoints;
+}
+
 pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
     let t = frame_system::GenesisConfig::default()
         .build_storage::<Test>()
@@ -414,6 +505,8 @@ pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
 
         Loans::update_liquidation_free_collateral(RuntimeOrigin::root(), vec![CDOT_6_13]).unwrap();
 
+        Streaming::set_minimum_deposit(RuntimeOrigin::root(), DOT, 0).unwrap();
+
         System::set_block_number(0);
         TimestampPallet::set_timestamp(6000);
     });
@@ -0,0 +1,244 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pallet_traits::{AccountMode, EModeCategoryId};
+use sp_runtime::DispatchResult;
+
+use crate::*;
+
+impl<T: Config> Pallet<T> {
+    /// Checks the isolation params for sanity
+    pub(crate) fn ensure_valid_isolation_config(
+        config: &IsolationConfig<AssetIdOf<T>>,
+    ) -> DispatchResult {
+        ensure!(
+            !config.debt_ceiling.is_zero() && !config.borrowable_assets.is_empty(),
+            Error::<T>::InvalidIsolationConfig
+        );
+        for asset_id in config.borrowable_assets.iter() {
+            Self::market(*asset_id)?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks the efficiency mode category for sanity
+    pub(crate) fn ensure_valid_emode_category(
+        category: &EModeCategory<AssetIdOf<T>>,
+    ) -> DispatchResult {
+        ensure!(
+            !category.collateral_factor.is_zero()
+                && category.collateral_factor <= category.liquidation_threshold
+                && category.liquidation_threshold < Ratio::one()
+                && !category.assets.is_empty(),
+            Error::<T>::InvalidEModeCategory
+        );
+        for asset_id in category.assets.iter() {
+            Self::market(*asset_id)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns which collateral can back the borrows of `account`
+    pub fn get_account_mode(account: &T::AccountId) -> AccountMode<AssetIdOf<T>> {
+        if let Some(asset_id) = Self::isolated_collateral(account) {
+            return AccountMode::Isolated(asset_id);
+        }
+        match AccountEMode::<T>::get(account) {
+            Some(category_id) if EModeCategories::<T>::contains_key(category_id) => {
+                AccountMode::EMode(category_id)
+            }
+            _ => AccountMode::Cross,
+        }
+    }
+
+    /// The isolated market `account` uses as collateral, if any
+    pub(crate) fn isolated_collateral(account: &T::AccountId) -> Option<AssetIdOf<T>> {
+        IsolationConfigs::<T>::iter_keys()
+            .find(|asset_id| Self::account_deposits(asset_id, account).is_collateral)
+    }
+
+    /// The efficiency mode category `account` opted into, if any
+    pub(crate) fn account_emode_category(
+        account: &T::AccountId,
+    ) -> Option<EModeCategory<AssetIdOf<T>>> {
+        AccountEMode::<T>::get(account).and_then(EModeCategories::<T>::get)
+    }
+
+    /// Returns the `(collateral_factor, liquidation_threshold)` of `asset_id` for
+    /// `account`, the params of its efficiency mode category apply to the assets
+    /// of the category.
+    pub(crate) fn account_risk_params(
+        account: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+        market: &Market<BalanceOf<T>>,
+    ) -> (Ratio, Ratio) {
+        match Self::account_emode_category(account) {
+            Some(category) if category.assets.contains(&asset_id) => (
+                max(market.collateral_factor, category.collateral_factor),
+                max(market.liquidation_threshold, category.liquidation_threshold),
+            ),
+            _ => (market.collateral_factor, market.liquidation_threshold),
+        }
+    }
+
+    /// An isolated collateral can't be combined with any other collateral
+    pub(crate) fn ensure_collateral_mode_allowed(
+        supplier: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+    ) -> DispatchResult {
+        if !IsolationConfigs::<T>::contains_key(asset_id) {
+            ensure!(
+                Self::isolated_collateral(supplier).is_none(),
+                Error::<T>::IsolationModeConflict
+            );
+            return Ok(());
+        }
+
+        ensure!(
+            AccountEMode::<T>::get(supplier).is_none(),
+            Error::<T>::IsolationModeConflict
+        );
+        // The flag is checked regardless of the balance, since vouchers can be
+        // deposited again into a collateral market with an empty balance
+        let has_other_collateral = Markets::<T>::iter_keys()
+            .filter(|id| id != &asset_id)
+            .any(|id| Self::account_deposits(id, supplier).is_collateral);
+        ensure!(!has_other_collateral, Error::<T>::IsolationModeConflict);
+
+        Ok(())
+    }
+
+    /// Checks the borrow against the whitelist and debt ceiling of the isolated
+    /// collateral, or the assets of the efficiency mode category.
+    pub(crate) fn ensure_borrow_mode_allowed(
+        borrower: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+        borrow_value: FixedU128,
+    ) -> DispatchResult {
+        match Self::get_account_mode(borrower) {
+            AccountMode::Cross => Ok(()),
+            AccountMode::Isolated(collateral_asset_id) => {
+                let config = IsolationConfigs::<T>::get(collateral_asset_id)
+                    .ok_or(Error::<T>::InvalidIsolationConfig)?;
+                ensure!(
+                    config.borrowable_assets.contains(&asset_id),
+                    Error::<T>::NotBorrowableInIsolation
+                );
+                let isolated_debt = Self::isolated_debt_value(collateral_asset_id, &config)?
+                    .checked_add(&borrow_value)
+                    .ok_or(ArithmeticError::Overflow)?;
+                ensure!(
+                    isolated_debt <= config.debt_ceiling,
+                    Error::<T>::DebtCeilingExceeded
+                );
+                Ok(())
+            }
+            AccountMode::EMode(category_id) => {
+                let category = EModeCategories::<T>::get(category_id)
+                    .ok_or(Error::<T>::EModeCategoryDoesNotExist)?;
+                ensure!(
+                    category.assets.contains(&asset_id),
+                    Error::<T>::NotInEModeCategory
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// The current value of all borrows backed by an isolated collateral
+    fn isolated_debt_value(
+        collateral_asset_id: AssetIdOf<T>,
+        config: &IsolationConfig<AssetIdOf<T>>,
+    ) -> Result<FixedU128, DispatchError> {
+        let mut total_debt_value = FixedU128::zero();
+        for asset_id in config.borrowable_assets.iter() {
+            let amount = Self::isolated_borrows(collateral_asset_id, asset_id);
+            if amount.is_zero() {
+                continue;
+            }
+            total_debt_value = total_debt_value
+                .checked_add(&Self::get_asset_value(*asset_id, amount)?)
+                .ok_or(ArithmeticError::Overflow)?;
+        }
+
+        Ok(total_debt_value)
+    }
+
+    pub(crate) fn increase_isolated_borrows(
+        borrower: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        if let Some(collateral_asset_id) = Self::isolated_collateral(borrower) {
+            IsolatedBorrows::<T>::try_mutate(
+                collateral_asset_id,
+                asset_id,
+                |borrows| -> DispatchResult {
+                    *borrows = borrows
+                        .checked_add(amount)
+                        .ok_or(ArithmeticError::Overflow)?;
+                    Ok(())
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn reduce_isolated_borrows(
+        borrower: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+    ) {
+        if let Some(collateral_asset_id) = Self::isolated_collateral(borrower) {
+            // Interest accrued on the debt isn't tracked, hence saturating
+            IsolatedBorrows::<T>::mutate(collateral_asset_id, asset_id, |borrows| {
+                *borrows = borrows.saturating_sub(amount)
+            });
+        }
+    }
+
+    /// Moves `who` into an efficiency mode category, or back to the market params.
+    #[require_transactional]
+    pub(crate) fn do_set_emode(
+        who: &T::AccountId,
+        category_id: Option<EModeCategoryId>,
+    ) -> DispatchResult {
+        if let Some(category_id) = category_id {
+            let category = EModeCategories::<T>::get(category_id)
+                .ok_or(Error::<T>::EModeCategoryDoesNotExist)?;
+            ensure!(
+                Self::isolated_collateral(who).is_none(),
+                Error::<T>::IsolationModeConflict
+            );
+            for (asset_id, _) in Self::active_markets() {
+                if !category.assets.contains(&asset_id)
+                    && !Self::current_borrow_balance(who, asset_id)?.is_zero()
+                {
+                    return Err(Error::<T>::NotInEModeCategory.into());
+                }
+            }
+        }
+
+        AccountEMode::<T>::set(who, category_id);
+        // Leaving a category lowers the collateral factors
+        let (_, shortfall, _, _) = Self::get_account_liquidity(who)?;
+        ensure!(shortfall.is_zero(), Error::<T>::InsufficientLiquidity);
+
+        Self::deposit_event(Event::<T>::AccountEModeSet(who.clone(), category_id));
+        Ok(())
+    }
+}
@@ -12,18 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use primitives::{Rate, Ratio};
+use primitives::{Rate, Ratio, Timestamp, SECONDS_PER_YEAR};
 use scale_info::TypeInfo;
-use sp_runtime::traits::{CheckedAdd, CheckedDiv, CheckedSub, Saturating};
+use sp_runtime::traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Saturating};
 
 use crate::*;
 
 /// Parallel interest rate model
 #[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
-#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo)]
+#[derive(Encode, Decode, Eq, PartialEq, Clone, RuntimeDebug, TypeInfo)]
 pub enum InterestRateModel {
     Jump(JumpModel),
     Curve(CurveModel),
+    Kinked(KinkedModel),
+    Adaptive(AdaptiveModel),
 }
 
 impl Default for InterestRateModel {
@@ -56,10 +58,34 @@ impl InterestRateModel {
         Self::Curve(CurveModel::new_model(base_rate))
     }
 
+    pub fn new_kinked_model(base_rate: Rate, kinks: Vec<RateKink>) -> Option<Self> {
+        KinkedModel::new_model(base_rate, kinks).map(Self::Kinked)
+    }
+
+    pub fn new_adaptive_model(
+        target_utilization: Ratio,
+        initial_rate_at_target: Rate,
+        min_rate_at_target: Rate,
+        max_rate_at_target: Rate,
+        adjustment_speed: Rate,
+        curve_steepness: Rate,
+    ) -> Self {
+        Self::Adaptive(AdaptiveModel::new_model(
+            target_utilization,
+            initial_rate_at_target,
+            min_rate_at_target,
+            max_rate_at_target,
+            adjustment_speed,
+            curve_steepness,
+        ))
+    }
+
     pub fn check_model(&self) -> bool {
         match self {
             Self::Jump(jump) => jump.check_model(),
             Self::Curve(curve) => curve.check_model(),
+            Self::Kinked(kinked) => kinked.check_model(),
+            Self::Adaptive(adaptive) => adaptive.check_model(),
         }
     }
 
@@ -68,6 +94,31 @@ impl InterestRateModel {
         match self {
             Self::Jump(jump) => jump.get_borrow_rate(utilization),
             Self::Curve(curve) => curve.get_borrow_rate(utilization),
+            Self::Kinked(kinked) => kinked.get_borrow_rate(utilization),
+            Self::Adaptive(adaptive) => adaptive.get_borrow_rate(utilization),
+        }
+    }
+
+    /// The borrow interest rate when utilization rate is 100%
+    pub fn get_full_rate(&self) -> Option<Rate> {
+        match self {
+            Self::Jump(jump) => Some(jump.full_rate),
+            Self::Curve(_) => None,
+            Self::Kinked(kinked) => kinked.get_borrow_rate(Ratio::one()),
+            Self::Adaptive(adaptive) => adaptive.get_borrow_rate(Ratio::one()),
+        }
+    }
+
+    /// Returns the model after `delta_time` seconds spent at `utilization`.
+    ///
+    /// Only the adaptive model carries time dependent state, other models
+    /// are returned unchanged.
+    pub fn evolve(&self, utilization: Ratio, delta_time: Timestamp) -> Option<Self> {
+        match self {
+            Self::Adaptive(adaptive) => {
+                adaptive.evolve(utilization, delta_time).map(Self::Adaptive)
+            }
+            _ => Some(self.clone()),
         }
     }
 
@@ -187,6 +238,227 @@ impl CurveModel {
     }
 }
 
+/// A point of the kinked interest rate model
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, Default, TypeInfo)]
+pub struct RateKink {
+    /// The utilization point of the kink
+    pub utilization: Ratio,
+    /// The borrow interest rate at the kink
+    pub rate: Rate,
+}
+
+impl RateKink {
+    pub fn new(utilization: Ratio, rate: Rate) -> RateKink {
+        Self { utilization, rate }
+    }
+}
+
+/// The max number of kinks of the kinked interest rate model
+pub type MaxRateKinks = ConstU32<8>;
+
+/// The kinked interest rate model
+///
+/// A piecewise-linear model going from `base_rate` at 0% utilization through
+/// every kink, the last kink has to sit at 100% utilization.
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Encode, Decode, Eq, PartialEq, Clone, RuntimeDebug, Default, TypeInfo)]
+pub struct KinkedModel {
+    /// The base interest rate when utilization rate is 0
+    pub base_rate: Rate,
+    /// The kinks sorted by utilization
+    pub kinks: BoundedVec<RateKink, MaxRateKinks>,
+}
+
+impl KinkedModel {
+    pub const MAX_BASE_RATE: Rate = Rate::from_inner(100_000_000_000_000_000); // 10%
+    pub const MAX_RATE: Rate = Rate::from_inner(2_000_000_000_000_000_000); // 200%
+
+    /// Create a new kinked model, returns `None` if there are too many kinks
+    pub fn new_model(base_rate: Rate, kinks: Vec<RateKink>) -> Option<KinkedModel> {
+        Some(Self {
+            base_rate,
+            kinks: kinks.try_into().ok()?,
+        })
+    }
+
+    /// Check the kinked model for sanity
+    pub fn check_model(&self) -> bool {
+        if self.base_rate > Self::MAX_BASE_RATE {
+            return false;
+        }
+        match self.kinks.last() {
+            Some(kink) if kink.utilization == Ratio::one() => {}
+            _ => return false,
+        }
+
+        let mut prev = RateKink::new(Ratio::zero(), self.base_rate);
+        for kink in self.kinks.iter() {
+            if kink.utilization <= prev.utilization
+                || kink.rate < prev.rate
+                || kink.rate > Self::MAX_RATE
+            {
+                return false;
+            }
+            prev = *kink;
+        }
+
+        true
+    }
+
+    /// Calculates the borrow interest rate of kinked model
+    pub fn get_borrow_rate(&self, utilization: Ratio) -> Option<Rate> {
+        let mut prev = RateKink::new(Ratio::zero(), self.base_rate);
+        for kink in self.kinks.iter() {
+            if utilization <= kink.utilization {
+                // (utilization - prev_utilization) * (rate - prev_rate) / (utilization - prev_utilization) + prev_rate
+                let excess_util = utilization.saturating_sub(prev.utilization);
+                let result = kink
+                    .rate
+                    .checked_sub(&prev.rate)?
+                    .saturating_mul(excess_util.into())
+                    .checked_div(&kink.utilization.saturating_sub(prev.utilization).into())?
+                    .checked_add(&prev.rate)?;
+
+                return Some(result);
+            }
+            prev = *kink;
+        }
+
+        Some(prev.rate)
+    }
+}
+
+/// The adaptive interest rate model
+///
+/// The borrow interest rate follows a curve around `rate_at_target`, and
+/// `rate_at_target` itself drifts up while utilization stays above
+/// `target_utilization` and down while it stays below.
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, Default, TypeInfo)]
+pub struct AdaptiveModel {
+    /// The utilization the model steers the market towards
+    pub target_utilization: Ratio,
+    /// The current interest rate on target utilization point
+    pub rate_at_target: Rate,
+    /// The lower bound of rate_at_target
+    pub min_rate_at_target: Rate,
+    /// The upper bound of rate_at_target
+    pub max_rate_at_target: Rate,
+    /// The yearly relative change of rate_at_target when utilization is 0% or 100%
+    pub adjustment_speed: Rate,
+    /// The ratio between the rate at 100% utilization and rate_at_target
+    pub curve_steepness: Rate,
+}
+
+impl AdaptiveModel {
+    pub const MAX_RATE_AT_TARGET: Rate = Rate::from_inner(1_000_000_000_000_000_000); // 100%
+    pub const MAX_ADJUSTMENT_SPEED: Rate = Rate::from_inner(100_000_000_000_000_000_000); // 100 per year
+    pub const MAX_CURVE_STEEPNESS: Rate = Rate::from_inner(10_000_000_000_000_000_000); // 10
+
+    /// Create a new adaptive model
+    pub fn new_model(
+        target_utilization: Ratio,
+        initial_rate_at_target: Rate,
+        min_rate_at_target: Rate,
+        max_rate_at_target: Rate,
+        adjustment_speed: Rate,
+        curve_steepness: Rate,
+    ) -> AdaptiveModel {
+        Self {
+            target_utilization,
+            rate_at_target: initial_rate_at_target,
+            min_rate_at_target,
+            max_rate_at_target,
+            adjustment_speed,
+            curve_steepness,
+        }
+    }
+
+    /// Check the adaptive model for sanity
+    pub fn check_model(&self) -> bool {
+        if self.target_utilization.is_zero() || self.target_utilization == Ratio::one() {
+            return false;
+        }
+        if self.max_rate_at_target > Self::MAX_RATE_AT_TARGET
+            || self.adjustment_speed > Self::MAX_ADJUSTMENT_SPEED
+            || self.curve_steepness > Self::MAX_CURVE_STEEPNESS
+            || self.curve_steepness < Rate::one()
+        {
+            return false;
+        }
+        // the rate at target evolves multiplicatively, it would never move from zero
+        if self.min_rate_at_target.is_zero()
+            || self.min_rate_at_target > self.rate_at_target
+            || self.rate_at_target > self.max_rate_at_target
+        {
+            return false;
+        }
+
+        true
+    }
+
+    /// Returns the distance between `utilization` and the target, normalized to
+    /// [0, 1], and whether `utilization` is above the target.
+    fn normalized_error(&self, utilization: Ratio) -> Option<(Rate, bool)> {
+        if utilization > self.target_utilization {
+            // (utilization - target_utilization) / (1 - target_utilization)
+            let error = Rate::from(utilization.saturating_sub(self.target_utilization))
+                .checked_div(&Ratio::one().saturating_sub(self.target_utilization).into())?;
+            Some((error, true))
+        } else {
+            // (target_utilization - utilization) / target_utilization
+            let error = Rate::from(self.target_utilization.saturating_sub(utilization))
+                .checked_div(&self.target_utilization.into())?;
+            Some((error, false))
+        }
+    }
+
+    /// Calculates the borrow interest rate of adaptive model
+    pub fn get_borrow_rate(&self, utilization: Ratio) -> Option<Rate> {
+        let (error, above_target) = self.normalized_error(utilization)?;
+        let factor = if above_target {
+            // 1 + (curve_steepness - 1) * error
+            self.curve_steepness
+                .checked_sub(&Rate::one())?
+                .checked_mul(&error)?
+                .checked_add(&Rate::one())?
+        } else {
+            // 1 - (1 - 1 / curve_steepness) * error
+            let one_minus_inverse =
+                Rate::one().checked_sub(&Rate::one().checked_div(&self.curve_steepness)?)?;
+            Rate::one().checked_sub(&one_minus_inverse.checked_mul(&error)?)?
+        };
+
+        self.rate_at_target.checked_mul(&factor)
+    }
+
+    /// Moves rate_at_target after `delta_time` seconds spent at `utilization`
+    pub fn evolve(&self, utilization: Ratio, delta_time: Timestamp) -> Option<AdaptiveModel> {
+        let (error, above_target) = self.normalized_error(utilization)?;
+        // adjustment_speed * error * delta_time / SECONDS_PER_YEAR
+        let adjustment = self
+            .adjustment_speed
+            .checked_mul(&error)?
+            .checked_mul(&Rate::saturating_from_integer(delta_time))?
+            .checked_div(&Rate::saturating_from_integer(SECONDS_PER_YEAR))?;
+        let rate_at_target = if above_target {
+            self.rate_at_target
+                .checked_mul(&Rate::one().checked_add(&adjustment)?)?
+        } else {
+            self.rate_at_target
+                .saturating_mul(Rate::one().saturating_sub(adjustment))
+        };
+
+        Some(Self {
+            rate_at_target: rate_at_target
+                .max(self.min_rate_at_target)
+                .min(self.max_rate_at_target),
+            ..*self
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,4 +542,171 @@ mod tests {
             Rate::from_inner(154217728000000000)
         );
     }
+
+    // Test kinked model
+    fn kinks_mock() -> Vec<RateKink> {
+        vec![
+            RateKink::new(
+                Ratio::from_percent(50),
+                Rate::saturating_from_rational(6, 100),
+            ),
+            RateKink::new(
+                Ratio::from_percent(80),
+                Rate::saturating_from_rational(12, 100),
+            ),
+            RateKink::new(
+                Ratio::from_percent(100),
+                Rate::saturating_from_rational(92, 100),
+            ),
+        ]
+    }
+
+    fn kinked_model_mock() -> KinkedModel {
+        KinkedModel::new_model(Rate::saturating_from_rational(2, 100), kinks_mock()).unwrap()
+    }
+
+    #[test]
+    fn kinked_model_check_works() {
+        let base_rate = Rate::saturating_from_rational(2, 100);
+        assert!(kinked_model_mock().check_model());
+
+        // no kinks
+        assert!(!KinkedModel::new_model(base_rate, vec![])
+            .unwrap()
+            .check_model());
+        // too many kinks
+        assert!(KinkedModel::new_model(base_rate, vec![Default::default(); 9]).is_none());
+        // last kink is not at 100% utilization
+        let mut kinks = kinks_mock();
+        kinks.pop();
+        assert!(!KinkedModel::new_model(base_rate, kinks)
+            .unwrap()
+            .check_model());
+        // unsorted kinks
+        let mut kinks = kinks_mock();
+        kinks[1].utilization = Ratio::from_percent(40);
+        assert!(!KinkedModel::new_model(base_rate, kinks)
+            .unwrap()
+            .check_model());
+        // decreasing rates
+        let mut kinks = kinks_mock();
+        kinks[1].rate = Rate::saturating_from_rational(5, 100);
+        assert!(!KinkedModel::new_model(base_rate, kinks)
+            .unwrap()
+            .check_model());
+        // rate above ceiling
+        let mut kinks = kinks_mock();
+        kinks[2].rate = KinkedModel::MAX_RATE + Rate::from_inner(1);
+        assert!(!KinkedModel::new_model(base_rate, kinks)
+            .unwrap()
+            .check_model());
+    }
+
+    #[test]
+    fn kinked_model_correctly_calculates_borrow_rate() {
+        let model = kinked_model_mock();
+        assert_eq!(
+            model.get_borrow_rate(Ratio::zero()).unwrap(),
+            Rate::saturating_from_rational(2, 100)
+        );
+        assert_eq!(
+            model.get_borrow_rate(Ratio::from_percent(25)).unwrap(),
+            Rate::saturating_from_rational(4, 100)
+        );
+        assert_eq!(
+            model.get_borrow_rate(Ratio::from_percent(50)).unwrap(),
+            Rate::saturating_from_rational(6, 100)
+        );
+        assert_eq!(
+            model.get_borrow_rate(Ratio::from_percent(65)).unwrap(),
+            Rate::saturating_from_rational(9, 100)
+        );
+        assert_eq!(
+            model.get_borrow_rate(Ratio::from_percent(90)).unwrap(),
+            Rate::saturating_from_rational(52, 100)
+        );
+        assert_eq!(
+            model.get_borrow_rate(Ratio::one()).unwrap(),
+            Rate::saturating_from_rational(92, 100)
+        );
+    }
+
+    // Test adaptive model
+    fn adaptive_model_mock() -> AdaptiveModel {
+        AdaptiveModel::new_model(
+            Ratio::from_percent(90),
+            Rate::saturating_from_rational(4, 100),
+            Rate::saturating_from_rational(1, 100),
+            Rate::saturating_from_rational(20, 100),
+            Rate::saturating_from_integer(50),
+            Rate::saturating_from_integer(4),
+        )
+    }
+
+    #[test]
+    fn adaptive_model_check_works() {
+        assert!(adaptive_model_mock().check_model());
+
+        let mut model = adaptive_model_mock();
+        model.target_utilization = Ratio::one();
+        assert!(!model.check_model());
+        let mut model = adaptive_model_mock();
+        model.curve_steepness = Rate::saturating_from_rational(1, 2);
+        assert!(!model.check_model());
+        let mut model = adaptive_model_mock();
+        model.rate_at_target = Rate::saturating_from_rational(21, 100);
+        assert!(!model.check_model());
+        let mut model = adaptive_model_mock();
+        model.max_rate_at_target = Rate::saturating_from_integer(2);
+        assert!(!model.check_model());
+        let mut model = adaptive_model_mock();
+        model.min_rate_at_target = Rate::zero();
+        assert!(!model.check_model());
+        let mut model = adaptive_model_mock();
+        model.min_rate_at_target = Rate::zero();
+        model.rate_at_target = Rate::zero();
+        assert!(!model.check_model());
+    }
+
+    #[test]
+    fn adaptive_model_correctly_calculates_borrow_rate() {
+        let model = adaptive_model_mock();
+        // rate_at_target / curve_steepness
+        assert_eq!(
+            model.get_borrow_rate(Ratio::zero()).unwrap(),
+            Rate::saturating_from_rational(1, 100)
+        );
+        assert_eq!(
+            model.get_borrow_rate(Ratio::from_percent(90)).unwrap(),
+            Rate::saturating_from_rational(4, 100)
+        );
+        // rate_at_target * curve_steepness
+        assert_eq!(
+            model.get_borrow_rate(Ratio::one()).unwrap(),
+            Rate::saturating_from_rational(16, 100)
+        );
+    }
+
+    #[test]
+    fn adaptive_model_evolves_towards_target() {
+        let model = adaptive_model_mock();
+        let one_day = 24 * 3600;
+
+        // Nothing moves at target utilization
+        assert_eq!(
+            model.evolve(Ratio::from_percent(90), one_day).unwrap(),
+            model
+        );
+        // Rate at target goes up above target utilization
+        let evolved = model.evolve(Ratio::one(), one_day).unwrap();
+        assert!(evolved.rate_at_target > model.rate_at_target);
+        // Rate at target goes down below target utilization
+        let evolved = model.evolve(Ratio::zero(), one_day).unwrap();
+        assert!(evolved.rate_at_target < model.rate_at_target);
+        // Rate at target is bounded
+        let evolved = model.evolve(Ratio::one(), SECONDS_PER_YEAR).unwrap();
+        assert_eq!(evolved.rate_at_target, model.max_rate_at_target);
+        let evolved = model.evolve(Ratio::zero(), SECONDS_PER_YEAR).unwrap();
+        assert_eq!(evolved.rate_at_target, model.min_rate_at_target);
+    }
 }
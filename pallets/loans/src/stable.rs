@@ -0,0 +1,171 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Stable rate borrows.
+//!
+//! A stable rate borrow accrues simple interest at the rate locked at borrow time
+//! instead of following the borrow index of the market. The stable rate is the
+//! variable borrow rate after the borrow plus the premium of the market.
+
+use sp_runtime::DispatchResult;
+
+use crate::*;
+
+impl<T: Config> Pallet<T> {
+    pub(crate) fn do_borrow_stable(
+        borrower: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        ensure!(
+            Self::stable_borrow_config(asset_id).is_some(),
+            Error::<T>::StableBorrowDisabled
+        );
+
+        Self::borrow_to(borrower, borrower, asset_id, amount, true)?;
+        let stable_rate = Self::account_borrows(asset_id, borrower)
+            .stable_rate
+            .unwrap_or_default();
+        Self::deposit_event(Event::<T>::StableBorrowed(
+            borrower.clone(),
+            asset_id,
+            amount,
+            stable_rate,
+        ));
+        Ok(())
+    }
+
+    #[require_transactional]
+    pub(crate) fn do_rebalance_stable_borrow_rate(
+        borrower: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+    ) -> DispatchResult {
+        Self::ensure_active_market(asset_id)?;
+        let config =
+            Self::stable_borrow_config(asset_id).ok_or(Error::<T>::StableBorrowDisabled)?;
+        Self::accrue_interest(asset_id)?;
+
+        let snapshot = Self::account_borrows(asset_id, borrower);
+        let old_rate = snapshot
+            .stable_rate
+            .filter(|_| !snapshot.principal.is_zero())
+            .ok_or(Error::<T>::RebalanceNotAllowed)?;
+        ensure!(
+            Self::get_market_status(asset_id)?.supply_rate < config.rebalance_threshold,
+            Error::<T>::RebalanceNotAllowed
+        );
+
+        Self::update_reward_borrow_index(asset_id)?;
+        Self::distribute_borrower_reward(asset_id, borrower)?;
+
+        let account_borrows = Self::current_borrow_balance(borrower, asset_id)?;
+        let new_rate = Self::stable_borrow_rate(asset_id, Zero::zero())?;
+        Self::decrease_stable_borrows(asset_id, account_borrows, old_rate);
+        Self::increase_stable_borrows(asset_id, account_borrows, new_rate)?;
+        AccountBorrows::<T>::insert(
+            asset_id,
+            borrower,
+            BorrowSnapshot {
+                principal: account_borrows,
+                borrow_index: Self::borrow_index(asset_id),
+                stable_rate: Some(new_rate),
+                last_update: Self::last_accrued_interest_time(asset_id),
+            },
+        );
+
+        Self::deposit_event(Event::<T>::StableBorrowRateRebalanced(
+            borrower.clone(),
+            asset_id,
+            old_rate,
+            new_rate,
+        ));
+        Ok(())
+    }
+
+    /// The stable rate locked by a new borrow of `borrow_amount`
+    pub(crate) fn stable_borrow_rate(
+        asset_id: AssetIdOf<T>,
+        borrow_amount: BalanceOf<T>,
+    ) -> Result<Rate, DispatchError> {
+        let config =
+            Self::stable_borrow_config(asset_id).ok_or(Error::<T>::StableBorrowDisabled)?;
+        let market = Self::market(asset_id)?;
+        let total_cash = Self::get_total_cash(asset_id).saturating_sub(borrow_amount);
+        let total_borrows = Self::total_borrows(asset_id)
+            .checked_add(Self::total_stable_borrows(asset_id))
+            .and_then(|r| r.checked_add(borrow_amount))
+            .ok_or(ArithmeticError::Overflow)?;
+        let util = Self::calc_utilization_ratio(
+            total_cash,
+            total_borrows,
+            Self::total_reserves(asset_id),
+        )?;
+
+        market
+            .rate_model
+            .get_borrow_rate(util)
+            .and_then(|r| r.checked_add(&config.rate_premium))
+            .ok_or_else(|| ArithmeticError::Overflow.into())
+    }
+
+    pub(crate) fn increase_stable_borrows(
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+        rate: Rate,
+    ) -> DispatchResult {
+        let total_stable_borrows = Self::total_stable_borrows(asset_id);
+        let average_rate = Self::weighted_rate(
+            Self::average_stable_borrow_rate(asset_id),
+            total_stable_borrows,
+            rate,
+            amount,
+        )?;
+        TotalStableBorrows::<T>::insert(
+            asset_id,
+            total_stable_borrows
+                .checked_add(amount)
+                .ok_or(ArithmeticError::Overflow)?,
+        );
+        AverageStableBorrowRate::<T>::insert(asset_id, average_rate);
+
+        Ok(())
+    }
+
+    /// The market total accrues at the average rate, it can drift apart from the sum
+    /// of the positions and is only reduced as far as it goes.
+    pub(crate) fn decrease_stable_borrows(
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+        rate: Rate,
+    ) {
+        let total_stable_borrows = Self::total_stable_borrows(asset_id);
+        let total_stable_borrows_new = total_stable_borrows.saturating_sub(amount);
+        // average_new = (average * total - rate * amount) / total_new
+        let average_rate = if total_stable_borrows_new.is_zero() {
+            Rate::zero()
+        } else {
+            Self::average_stable_borrow_rate(asset_id)
+                .saturating_mul(Rate::saturating_from_rational(
+                    total_stable_borrows,
+                    total_stable_borrows_new,
+                ))
+                .saturating_sub(rate.saturating_mul(Rate::saturating_from_rational(
+                    amount,
+                    total_stable_borrows_new,
+                )))
+        };
+        TotalStableBorrows::<T>::insert(asset_id, total_stable_borrows_new);
+        AverageStableBorrowRate::<T>::insert(asset_id, average_rate);
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sp_runtime::DispatchResult;
+
+use crate::*;
+
+impl<T: Config> Pallet<T> {
+    /// The value of the streams pledged by `account`, the unvested balance of each
+    /// stream, less the payout owed to a liquidator, is discounted then weighted by the
+    /// collateral factor, or the liquidation threshold if `liquidation` is set, of its
+    /// market.
+    pub(crate) fn stream_collateral_value(
+        account: &T::AccountId,
+        liquidation: bool,
+    ) -> Result<FixedU128, DispatchError> {
+        let mut total_stream_value = FixedU128::zero();
+        for stream_id in Self::stream_collaterals(account) {
+            let value = Self::pledged_stream_value(account, stream_id, liquidation)?;
+            total_stream_value = total_stream_value
+                .checked_add(&value)
+                .ok_or(ArithmeticError::Overflow)?;
+        }
+
+        Ok(total_stream_value)
+    }
+
+    fn pledged_stream_value(
+        account: &T::AccountId,
+        stream_id: StreamId,
+        liquidation: bool,
+    ) -> Result<FixedU128, DispatchError> {
+        let (asset_id, unvested_balance) = T::StreamCollateral::unvested_balance(stream_id)?;
+        let market = match Self::ensure_active_market(asset_id) {
+            Ok(market) => market,
+            Err(_) => return Ok(FixedU128::zero()),
+        };
+        let (collateral_factor, liquidation_threshold) =
+            Self::account_risk_params(account, asset_id, &market);
+        let factor = if liquidation {
+            liquidation_threshold
+        } else {
+            collateral_factor
+        };
+        let payout = Self::seized_streams(stream_id).map_or(Zero::zero(), |seized| seized.payout);
+        let discounted_balance = (Ratio::one() - T::StreamCollateralDiscount::get())
+            .mul_floor(unvested_balance.saturating_sub(payout));
+
+        Self::get_asset_value(asset_id, factor.mul_floor(discounted_balance))
+    }
+
+    #[require_transactional]
+    pub(crate) fn do_pledge_stream(who: &T::AccountId, stream_id: StreamId) -> DispatchResult {
+        let asset_id = T::StreamCollateral::pledge(who, stream_id)?;
+        Self::ensure_active_market(asset_id)?;
+        ensure!(
+            !IsolationConfigs::<T>::contains_key(asset_id)
+                && Self::isolated_collateral(who).is_none(),
+            Error::<T>::IsolationModeConflict
+        );
+
+        StreamCollaterals::<T>::try_mutate(who, |stream_ids| {
+            stream_ids
+                .try_push(stream_id)
+                .map_err(|_| Error::<T>::TooManyStreamCollaterals)
+        })?;
+
+        Self::deposit_event(Event::<T>::StreamPledged(who.clone(), stream_id));
+        Ok(())
+    }
+
+    #[require_transactional]
+    pub(crate) fn do_release_stream(who: &T::AccountId, stream_id: StreamId) -> DispatchResult {
+        ensure!(
+            !SeizedStreams::<T>::contains_key(stream_id),
+            Error::<T>::StreamIsSeized
+        );
+        // check the liquidity once the stream is removed from the collateral
+        Self::remove_stream_collateral(who, stream_id)?;
+        let total_collateral_value = Self::total_collateral_value(who)?;
+        let total_borrowed_value = Self::total_borrowed_value(who)?;
+        log::trace!(
+            target: "loans::release_stream",
+            "total_collateral_value: {:?}, total_borrowed_value: {:?}",
+            total_collateral_value.into_inner(),
+            total_borrowed_value.into_inner(),
+        );
+        ensure!(
+            total_collateral_value >= total_borrowed_value,
+            Error::<T>::InsufficientLiquidity
+        );
+
+        T::StreamCollateral::release(who, stream_id)?;
+        Self::deposit_event(Event::<T>::StreamReleased(who.clone(), stream_id));
+        Ok(())
+    }
+
+    /// The liquidator repays `repay_amount` of the debt of `borrower` and seizes a stream
+    /// pledged by `borrower`, like `do_liquidate_borrow` the liquidator is owed the repaid
+    /// value plus the liquidation incentive, paid by the withdrawals of the stream.
+    #[require_transactional]
+    pub(crate) fn do_liquidate_stream(
+        liquidator: &T::AccountId,
+        borrower: &T::AccountId,
+        stream_id: StreamId,
+        liquidation_asset_id: AssetIdOf<T>,
+        repay_amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        ensure!(liquidator != borrower, Error::<T>::LiquidatorIsBorrower);
+        ensure!(
+            Self::stream_collaterals(borrower).contains(&stream_id),
+            Error::<T>::StreamNotPledged
+        );
+        ensure!(
+            !SeizedStreams::<T>::contains_key(stream_id),
+            Error::<T>::StreamIsSeized
+        );
+        let market = Self::ensure_active_market(liquidation_asset_id)?;
+        Self::liquidate_borrow_allowed(borrower, liquidation_asset_id, repay_amount, &market)?;
+
+        let (asset_id, unvested_balance) = T::StreamCollateral::unvested_balance(stream_id)?;
+        Self::ensure_active_market(asset_id)?;
+        // payout contains the incentive of liquidator and the punishment of the borrower
+        let payout_value = Self::get_asset_value(liquidation_asset_id, repay_amount)?
+            .checked_mul(&market.liquidate_incentive)
+            .ok_or(ArithmeticError::Overflow)?;
+        let payout = payout_value
+            .checked_div(&Self::get_price(asset_id)?)
+            .ok_or(ArithmeticError::Underflow)?
+            .into_inner();
+        ensure!(
+            payout <= unvested_balance,
+            Error::<T>::InsufficientCollateral
+        );
+
+        Self::update_reward_borrow_index(liquidation_asset_id)?;
+        Self::distribute_borrower_reward(liquidation_asset_id, borrower)?;
+        T::Assets::transfer(
+            liquidation_asset_id,
+            liquidator,
+            &Self::account_id(),
+            repay_amount,
+            false,
+        )?;
+        let account_borrows = Self::current_borrow_balance(borrower, liquidation_asset_id)?;
+        Self::reduce_borrow(
+            borrower,
+            liquidation_asset_id,
+            account_borrows,
+            repay_amount,
+        )?;
+
+        T::StreamCollateral::redirect(stream_id, &Self::account_id())?;
+        SeizedStreams::<T>::insert(
+            stream_id,
+            SeizedStream {
+                borrower: borrower.clone(),
+                liquidator: liquidator.clone(),
+                payout,
+            },
+        );
+
+        Self::deposit_event(Event::<T>::StreamSeized(
+            liquidator.clone(),
+            borrower.clone(),
+            stream_id,
+            liquidation_asset_id,
+            repay_amount,
+            payout,
+        ));
+        Ok(())
+    }
+
+    /// Withdraws the vested balance of a seized stream, it pays the liquidator first, then
+    /// repays the debt of the borrower in the asset of the stream and the rest goes to
+    /// the borrower. The stream stays pledged and goes back to the borrower once the
+    /// liquidator is paid.
+    #[require_transactional]
+    pub(crate) fn do_repay_borrow_with_stream(stream_id: StreamId) -> DispatchResult {
+        let mut seized = Self::seized_streams(stream_id).ok_or(Error::<T>::StreamNotSeized)?;
+        let borrower = seized.borrower.clone();
+        let (asset_id, amount) = T::StreamCollateral::withdraw_vested(stream_id)?;
+
+        let paid_amount = amount.min(seized.payout);
+        if !paid_amount.is_zero() {
+            T::Assets::transfer(
+                asset_id,
+                &Self::account_id(),
+                &seized.liquidator,
+                paid_amount,
+                false,
+            )?;
+            seized.payout -= paid_amount;
+        }
+
+        let mut rest = amount - paid_amount;
+        let mut repay_amount = Zero::zero();
+        if !rest.is_zero() && Self::ensure_active_market(asset_id).is_ok() {
+            Self::accrue_interest(asset_id)?;
+            let account_borrows = Self::current_borrow_balance(&borrower, asset_id)?;
+            repay_amount = rest.min(account_borrows);
+            if !repay_amount.is_zero() {
+                Self::update_reward_borrow_index(asset_id)?;
+                Self::distribute_borrower_reward(asset_id, &borrower)?;
+                // the withdrawn balance is already in the pallet account
+                Self::reduce_borrow(&borrower, asset_id, account_borrows, repay_amount)?;
+                rest -= repay_amount;
+            }
+        }
+
+        // the rest goes back to the borrower
+        if !rest.is_zero() {
+            T::Assets::transfer(asset_id, &Self::account_id(), &borrower, rest, false)?;
+        }
+
+        if T::StreamCollateral::has_finished(stream_id) {
+            Self::remove_stream_collateral(&borrower, stream_id)?;
+            SeizedStreams::<T>::remove(stream_id);
+        } else if seized.payout.is_zero() {
+            T::StreamCollateral::redirect(stream_id, &borrower)?;
+            SeizedStreams::<T>::remove(stream_id);
+        } else {
+            SeizedStreams::<T>::insert(stream_id, seized);
+        }
+
+        Self::deposit_event(Event::<T>::RepaidBorrowWithStream(
+            borrower,
+            stream_id,
+            asset_id,
+            paid_amount,
+            repay_amount,
+        ));
+        Ok(())
+    }
+
+    fn remove_stream_collateral(who: &T::AccountId, stream_id: StreamId) -> DispatchResult {
+        StreamCollaterals::<T>::try_mutate(who, |stream_ids| -> DispatchResult {
+            let index = stream_ids
+                .iter()
+                .position(|id| *id == stream_id)
+                .ok_or(Error::<T>::StreamNotPledged)?;
+            stream_ids.remove(index);
+            Ok(())
+        })
+    }
+}
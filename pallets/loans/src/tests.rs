@@ -12,11 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod account_health;
+mod credit_delegation;
 mod edge_cases;
+mod flash_loan;
 mod interest_rate;
 mod liquidate_borrow;
+mod liquidation_auction;
 mod market;
+mod market_modes;
 mod ptokens;
+mod stable_borrow;
+mod stream_collateral;
 
 use frame_support::{assert_err, assert_noop, assert_ok};
 
@@ -883,6 +890,8 @@ fn current_borrow_balance_works() {
             BorrowSnapshot {
                 principal: 0,
                 borrow_index: Rate::one(),
+                stable_rate: None,
+                last_update: 0,
             },
         );
         assert_eq!(Loans::current_borrow_balance(&ALICE, DOT).unwrap(), 0);
@@ -894,6 +903,8 @@ fn current_borrow_balance_works() {
             BorrowSnapshot {
                 principal: 100,
                 borrow_index: Rate::zero(),
+                stable_rate: None,
+                last_update: 0,
             },
         );
         assert_eq!(Loans::current_borrow_balance(&ALICE, DOT).unwrap(), 0);
@@ -906,6 +917,8 @@ fn current_borrow_balance_works() {
             BorrowSnapshot {
                 principal: 100,
                 borrow_index: Rate::one(),
+                stable_rate: None,
+                last_update: 0,
             },
         );
         assert_eq!(Loans::current_borrow_balance(&ALICE, DOT).unwrap(), 120);
@@ -1376,6 +1389,49 @@ fn reward_calculation_multi_player_in_one_market_works() {
     })
 }
 
+#[test]
+fn reward_calculation_with_voting_power_boost_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(10)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), DOT, unit(10)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(BOB),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(BOB), DOT, unit(1)));
+
+        _run_to_block(10);
+        assert_ok!(Loans::update_market_reward_speed(
+            RuntimeOrigin::root(),
+            DOT,
+            Some(unit(1)),
+            Some(unit(1)),
+        ));
+        // BOB owns all the voting power
+        MockVotingEscrow::set_voting_power(BOB, 1_000);
+
+        // Alice supply:10     supply reward: 10 * 1/2 * 2/5
+        // BOB supply:10       supply reward: 10 * 1/2, max boost
+        // BOB borrow:1        borrow reward: 10, the whole market
+        _run_to_block(20);
+        assert_ok!(Loans::update_reward_supply_index(DOT));
+        assert_ok!(Loans::distribute_supplier_reward(DOT, &ALICE));
+        assert_ok!(Loans::distribute_supplier_reward(DOT, &BOB));
+        assert_ok!(Loans::update_reward_borrow_index(DOT));
+        assert_ok!(Loans::distribute_borrower_reward(DOT, &BOB));
+        assert!(almost_equal(Loans::reward_accrued(ALICE), unit(2)));
+        assert!(almost_equal(Loans::reward_accrued(BOB), unit(15)));
+
+        // without voting power ALICE earns half of the supply rewards
+        MockVotingEscrow::set_voting_power(BOB, 0);
+        _run_to_block(30);
+        assert_ok!(Loans::update_reward_supply_index(DOT));
+        assert_ok!(Loans::distribute_supplier_reward(DOT, &ALICE));
+        assert!(almost_equal(Loans::reward_accrued(ALICE), unit(7)));
+    })
+}
+
 #[test]
 fn reward_calculation_after_liquidate_borrow_works() {
     new_test_ext().execute_with(|| {
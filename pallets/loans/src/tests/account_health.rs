@@ -0,0 +1,172 @@
+use crate::{
+    mock::{
+        _run_to_block, almost_equal, new_test_ext, Loans, MockPriceFeeder, RuntimeOrigin, Test,
+        ALICE, BOB, DAVE, DOT, KSM, USDT,
+    },
+    tests::unit,
+    Error,
+};
+use frame_support::{assert_noop, assert_ok};
+use pallet_traits::{AuctionDecay, LiquidationAuctionConfig, LiquidationSimulation};
+use primitives::Rate;
+use sp_runtime::{traits::Zero, FixedPointNumber, FixedU128};
+
+#[test]
+fn get_liquidation_candidates_works() {
+    new_test_ext().execute_with(|| {
+        initial_setup();
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(DAVE), USDT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(DAVE),
+            USDT,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(DAVE), KSM, unit(80)));
+        assert_eq!(
+            Loans::get_liquidation_candidates(Zero::zero(), None, 10).unwrap(),
+            (vec![], None)
+        );
+
+        // ALICE: USDT collateral 200 * 55% = 110 < KSM borrow 100 * 2 = 200
+        // DAVE: USDT collateral 200 * 55% = 110 < KSM borrow 80 * 2 = 160
+        MockPriceFeeder::set_price(KSM, 2.into());
+        let alice_shortfall = FixedU128::from_inner(unit(90));
+        let dave_shortfall = FixedU128::from_inner(unit(50));
+        assert_eq!(
+            Loans::get_liquidation_candidates(Zero::zero(), None, 10).unwrap(),
+            (vec![(ALICE, alice_shortfall), (DAVE, dave_shortfall)], None)
+        );
+        assert_eq!(
+            Loans::get_liquidation_candidates(FixedU128::from_inner(unit(60)), None, 10).unwrap(),
+            (vec![(ALICE, alice_shortfall)], None)
+        );
+
+        // Each page scans at most `limit` borrows and resumes after the last one
+        let (first_page, next_key) =
+            Loans::get_liquidation_candidates(Zero::zero(), None, 1).unwrap();
+        assert_eq!(first_page.len(), 1);
+        let (second_page, next_key) =
+            Loans::get_liquidation_candidates(Zero::zero(), next_key, 1).unwrap();
+        assert_eq!(second_page.len(), 1);
+        assert!(next_key.is_some());
+        assert_eq!(
+            Loans::get_liquidation_candidates(Zero::zero(), next_key, 1).unwrap(),
+            (vec![], None)
+        );
+        assert_eq!(
+            [first_page, second_page].concat(),
+            vec![(ALICE, alice_shortfall), (DAVE, dave_shortfall)]
+        );
+    })
+}
+
+#[test]
+fn get_account_position_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::update_market_reward_speed(
+            RuntimeOrigin::root(),
+            DOT,
+            Some(unit(1)),
+            Some(0),
+        ));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(50)));
+
+        let position = Loans::get_account_position(&DAVE).unwrap();
+        assert!(position.assets.is_empty());
+        assert_eq!(position.health_factor, None);
+
+        // DOT collateral: 200 * 55% = 110, KSM borrow: 50
+        let position = Loans::get_account_position(&ALICE).unwrap();
+        assert_eq!(
+            position.health_factor,
+            Some(FixedU128::saturating_from_rational(22, 10))
+        );
+
+        _run_to_block(10);
+        let position = Loans::get_account_position(&ALICE).unwrap();
+        assert_eq!(position.assets.len(), 2);
+        let dot = position.assets.iter().find(|p| p.asset_id == DOT).unwrap();
+        assert_eq!(dot.supplied, unit(200));
+        assert_eq!(dot.supply_interest, 0);
+        assert!(dot.is_collateral);
+        assert_eq!(dot.borrowed, 0);
+        let ksm = position.assets.iter().find(|p| p.asset_id == KSM).unwrap();
+        assert_eq!(ksm.supplied, 0);
+        assert!(!ksm.borrow_interest.is_zero());
+        assert_eq!(ksm.borrowed, unit(50) + ksm.borrow_interest);
+        assert!(position.health_factor.unwrap() < FixedU128::saturating_from_rational(22, 10));
+        // 10 blocks of DOT supply reward
+        assert!(almost_equal(position.unclaimed_reward, unit(10)));
+
+        // Nothing is written to the storage
+        assert_eq!(Loans::reward_accrued(ALICE), 0);
+        assert_eq!(Loans::account_borrows(KSM, ALICE).principal, unit(50));
+    })
+}
+
+#[test]
+fn simulate_liquidation_works() {
+    new_test_ext().execute_with(|| {
+        initial_setup();
+        assert_noop!(
+            Loans::simulate_liquidation(&ALICE, KSM, unit(50), USDT),
+            Error::<Test>::InsufficientShortfall
+        );
+
+        MockPriceFeeder::set_price(KSM, 2.into());
+        assert_noop!(
+            Loans::simulate_liquidation(&ALICE, KSM, unit(51), USDT),
+            Error::<Test>::TooMuchRepay
+        );
+        // seized = (50 * 2) * 1.1 = 110, reserved = 110 / 1.1 * 3% = 3
+        assert_eq!(
+            Loans::simulate_liquidation(&ALICE, KSM, unit(50), USDT).unwrap(),
+            LiquidationSimulation {
+                seized_collateral: unit(110),
+                liquidator_collateral: unit(107),
+                reserved_collateral: unit(3),
+                auction: false,
+            }
+        );
+
+        assert_ok!(Loans::update_liquidation_auction_config(
+            RuntimeOrigin::root(),
+            USDT,
+            Some(LiquidationAuctionConfig {
+                start_premium: Rate::saturating_from_rational(125, 100),
+                min_price: Rate::saturating_from_rational(75, 100),
+                decay: AuctionDecay::Linear { duration: 100 },
+                duration: 200,
+            })
+        ));
+        assert_eq!(
+            Loans::simulate_liquidation(&ALICE, KSM, unit(50), USDT).unwrap(),
+            LiquidationSimulation {
+                seized_collateral: unit(110),
+                liquidator_collateral: 0,
+                reserved_collateral: 0,
+                auction: true,
+            }
+        );
+    })
+}
+
+fn initial_setup() {
+    // Bob deposits 200 KSM
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+    // Alice deposits 200 USDT as collateral and borrows 100 KSM
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(200)));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(ALICE),
+        USDT,
+        true
+    ));
+    assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(100)));
+}
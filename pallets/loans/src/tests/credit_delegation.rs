@@ -0,0 +1,143 @@
+use crate::{
+    mock::{new_test_ext, Assets, Loans, RuntimeOrigin, Test, ALICE, BOB, DAVE, KSM, SDOT, USDT},
+    tests::unit,
+    Error,
+};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::traits::Zero;
+
+#[test]
+fn approve_and_revoke_borrow_allowance_works() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Loans::approve_borrow(RuntimeOrigin::signed(ALICE), KSM, ALICE, unit(50)),
+            Error::<Test>::SelfDelegation
+        );
+        assert_noop!(
+            Loans::approve_borrow(RuntimeOrigin::signed(ALICE), SDOT, BOB, unit(50)),
+            Error::<Test>::MarketNotActivated
+        );
+
+        assert_ok!(Loans::approve_borrow(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            BOB,
+            unit(50)
+        ));
+        assert_eq!(Loans::borrow_allowance((KSM, ALICE, BOB)), unit(50));
+        assert_eq!(Loans::borrow_allowance((KSM, BOB, ALICE)), 0);
+        // A new approval replaces the allowance
+        assert_ok!(Loans::approve_borrow(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            BOB,
+            unit(30)
+        ));
+        assert_eq!(Loans::borrow_allowance((KSM, ALICE, BOB)), unit(30));
+
+        assert_ok!(Loans::revoke_borrow_allowance(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            BOB
+        ));
+        assert_eq!(Loans::borrow_allowance((KSM, ALICE, BOB)), 0);
+    })
+}
+
+#[test]
+fn borrow_on_behalf_accrues_debt_to_delegator() {
+    new_test_ext().execute_with(|| {
+        initial_setup();
+        assert_ok!(Loans::approve_borrow(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            DAVE,
+            unit(60)
+        ));
+
+        assert_noop!(
+            Loans::borrow_on_behalf(RuntimeOrigin::signed(DAVE), KSM, ALICE, 0),
+            Error::<Test>::InvalidAmount
+        );
+        assert_noop!(
+            Loans::borrow_on_behalf(RuntimeOrigin::signed(DAVE), KSM, ALICE, unit(70)),
+            Error::<Test>::InsufficientBorrowAllowance
+        );
+        assert_noop!(
+            Loans::borrow_on_behalf(RuntimeOrigin::signed(BOB), KSM, ALICE, unit(10)),
+            Error::<Test>::InsufficientBorrowAllowance
+        );
+
+        assert_ok!(Loans::borrow_on_behalf(
+            RuntimeOrigin::signed(DAVE),
+            KSM,
+            ALICE,
+            unit(40)
+        ));
+        assert_eq!(Assets::balance(KSM, &DAVE), unit(40));
+        assert_eq!(Assets::balance(KSM, &ALICE), unit(1000));
+        assert_eq!(Loans::account_borrows(KSM, ALICE).principal, unit(40));
+        assert_eq!(Loans::account_borrows(KSM, DAVE).principal, 0);
+        assert_eq!(Loans::borrow_allowance((KSM, ALICE, DAVE)), unit(20));
+
+        // The delegator repays the debt
+        assert_ok!(Loans::repay_borrow(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            unit(40)
+        ));
+        assert_eq!(Loans::account_borrows(KSM, ALICE).principal, 0);
+    })
+}
+
+#[test]
+fn borrow_on_behalf_checks_delegator_liquidity() {
+    new_test_ext().execute_with(|| {
+        initial_setup();
+        assert_ok!(Loans::approve_borrow(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            DAVE,
+            unit(200)
+        ));
+
+        // ALICE borrowing power: USDT 200 * 50% = 100
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(40)));
+        assert_noop!(
+            Loans::borrow_on_behalf(RuntimeOrigin::signed(DAVE), KSM, ALICE, unit(70)),
+            Error::<Test>::InsufficientLiquidity
+        );
+        assert_ok!(Loans::borrow_on_behalf(
+            RuntimeOrigin::signed(DAVE),
+            KSM,
+            ALICE,
+            unit(60)
+        ));
+        assert_eq!(Loans::account_borrows(KSM, ALICE).principal, unit(100));
+        assert!(Loans::get_account_liquidity(&ALICE).unwrap().0.is_zero());
+
+        // Revoking the allowance leaves the debt with ALICE
+        assert_ok!(Loans::revoke_borrow_allowance(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            DAVE
+        ));
+        assert_noop!(
+            Loans::borrow_on_behalf(RuntimeOrigin::signed(DAVE), KSM, ALICE, 1),
+            Error::<Test>::InsufficientBorrowAllowance
+        );
+        assert_eq!(Loans::account_borrows(KSM, ALICE).principal, unit(100));
+    })
+}
+
+fn initial_setup() {
+    // Bob deposits 200 KSM
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+    // Alice deposits 200 USDT as collateral
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(200)));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(ALICE),
+        USDT,
+        true
+    ));
+}
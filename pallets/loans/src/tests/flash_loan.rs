@@ -0,0 +1,154 @@
+use crate::{
+    mock::{new_test_ext, Assets, Loans, RuntimeCall, RuntimeOrigin, Test, ALICE, BOB, KSM},
+    tests::unit,
+    Call, Error,
+};
+use frame_support::{assert_noop, assert_ok};
+use primitives::Ratio;
+use sp_runtime::{traits::BadOrigin, FixedPointNumber};
+
+#[test]
+fn update_flash_loan_fee_works() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Loans::update_flash_loan_fee(
+                RuntimeOrigin::signed(ALICE),
+                KSM,
+                Some(Ratio::from_percent(1))
+            ),
+            BadOrigin
+        );
+        assert_noop!(
+            Loans::update_flash_loan_fee(
+                RuntimeOrigin::root(),
+                KSM,
+                Some(Ratio::from_percent(100))
+            ),
+            Error::<Test>::InvalidFactor
+        );
+        assert_ok!(Loans::update_flash_loan_fee(
+            RuntimeOrigin::root(),
+            KSM,
+            Some(Ratio::from_percent(1))
+        ));
+        assert_eq!(Loans::flash_loan_fee(KSM), Some(Ratio::from_percent(1)));
+    })
+}
+
+#[test]
+fn flash_loan_requires_enabled_market_and_cash() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+        assert_noop!(
+            Loans::flash_loan(RuntimeOrigin::signed(ALICE), KSM, unit(100), vec![]),
+            Error::<Test>::FlashLoanDisabled
+        );
+
+        initial_setup();
+        assert_noop!(
+            Loans::flash_loan(RuntimeOrigin::signed(ALICE), KSM, 0, vec![]),
+            Error::<Test>::InvalidAmount
+        );
+        assert_noop!(
+            Loans::flash_loan(RuntimeOrigin::signed(ALICE), KSM, unit(201), vec![]),
+            Error::<Test>::InsufficientCash
+        );
+    })
+}
+
+#[test]
+fn flash_loan_fee_goes_to_reserves() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+        initial_setup();
+        let exchange_rate = Loans::exchange_rate_stored(KSM).unwrap();
+
+        assert_ok!(Loans::flash_loan(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            unit(100),
+            vec![RuntimeCall::System(frame_system::Call::remark {
+                remark: vec![1, 2, 3]
+            })]
+        ));
+
+        // fee = 100 * 1% = 1
+        assert_eq!(Assets::balance(KSM, &ALICE), unit(999));
+        assert_eq!(Loans::total_reserves(KSM), unit(1));
+        assert_eq!(Loans::flash_loan_outstanding(KSM), 0);
+        assert_eq!(Loans::exchange_rate_stored(KSM).unwrap(), exchange_rate);
+    })
+}
+
+#[test]
+fn flash_loaned_cash_can_be_used_by_calls() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+        initial_setup();
+        let exchange_rate = Loans::exchange_rate_stored(KSM).unwrap();
+
+        // The flash loaned cash is still counted by the exchange rate
+        assert_ok!(Loans::flash_loan(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            unit(100),
+            vec![RuntimeCall::Loans(Call::mint {
+                asset_id: KSM,
+                mint_amount: unit(100)
+            })]
+        ));
+
+        assert_eq!(Assets::balance(KSM, &ALICE), unit(899));
+        assert_eq!(
+            exchange_rate.saturating_mul_int(Loans::account_deposits(KSM, ALICE).voucher_balance),
+            unit(100)
+        );
+        assert_eq!(Loans::exchange_rate_stored(KSM).unwrap(), exchange_rate);
+    })
+}
+
+#[test]
+fn flash_loan_is_atomic() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+        initial_setup();
+
+        // A failed call reverts the loan
+        assert_noop!(
+            Loans::flash_loan(
+                RuntimeOrigin::signed(ALICE),
+                KSM,
+                unit(100),
+                vec![RuntimeCall::Loans(Call::borrow {
+                    asset_id: KSM,
+                    borrow_amount: unit(1)
+                })]
+            ),
+            Error::<Test>::InsufficientLiquidity
+        );
+
+        // Not enough left to repay the loan plus fee
+        assert!(Loans::flash_loan(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            unit(100),
+            vec![RuntimeCall::Loans(Call::mint {
+                asset_id: KSM,
+                mint_amount: unit(1000)
+            })]
+        )
+        .is_err());
+        assert_eq!(Assets::balance(KSM, &ALICE), unit(1000));
+        assert_eq!(Loans::account_deposits(KSM, ALICE).voucher_balance, 0);
+        assert_eq!(Loans::flash_loan_outstanding(KSM), 0);
+        assert_eq!(Loans::total_reserves(KSM), 0);
+    })
+}
+
+fn initial_setup() {
+    assert_ok!(Loans::update_flash_loan_fee(
+        RuntimeOrigin::root(),
+        KSM,
+        Some(Ratio::from_percent(1))
+    ));
+}
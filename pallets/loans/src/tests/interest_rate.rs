@@ -1,9 +1,9 @@
 use crate::tests::Loans;
-use crate::{mock::*, Markets};
+use crate::{mock::*, InterestRateModel, Markets, RateKink};
 use frame_support::assert_ok;
-use primitives::{Rate, Ratio, SECONDS_PER_YEAR};
+use primitives::{CurrencyId, Rate, Ratio, SECONDS_PER_YEAR};
 use sp_runtime::{
-    traits::{CheckedDiv, One, Saturating},
+    traits::{CheckedDiv, One, Saturating, Zero},
     FixedPointNumber,
 };
 
@@ -406,3 +406,151 @@ fn a_market_can_only_accrue_interest_once_in_a_block() {
         );
     })
 }
+
+fn borrow_ksm_at_75_percent_utilization() {
+    // Bob deposits 200 KSM
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+    // Alice deposits 300 DOT as collateral and borrows 150 KSM
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(300)));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(ALICE),
+        DOT,
+        true
+    ));
+    assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(150)));
+}
+
+fn rate_at_target(asset_id: CurrencyId) -> Rate {
+    match Loans::market(asset_id).unwrap().rate_model {
+        InterestRateModel::Adaptive(model) => model.rate_at_target,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn kinked_rate_model_accrues_interest() {
+    new_test_ext().execute_with(|| {
+        let rate_model = InterestRateModel::new_kinked_model(
+            Rate::saturating_from_rational(2, 100),
+            vec![
+                RateKink::new(
+                    Ratio::from_percent(50),
+                    Rate::saturating_from_rational(6, 100),
+                ),
+                RateKink::new(
+                    Ratio::from_percent(80),
+                    Rate::saturating_from_rational(12, 100),
+                ),
+                RateKink::new(Ratio::one(), Rate::saturating_from_rational(92, 100)),
+            ],
+        )
+        .unwrap();
+        assert_ok!(Loans::update_rate_model(
+            RuntimeOrigin::root(),
+            KSM,
+            rate_model
+        ));
+        borrow_ksm_at_75_percent_utilization();
+
+        let mut borrow_index = Rate::one();
+        let mut total_borrows = unit(150);
+        for i in 1..10 {
+            let delta_time = 6u128;
+            TimestampPallet::set_timestamp(6000 * (i + 1));
+            assert_ok!(Loans::accrue_interest(KSM));
+            let util_ratio = Ratio::from_rational(total_borrows, unit(50) + total_borrows);
+            assert_eq!(Loans::utilization_ratio(KSM), util_ratio);
+
+            // Second segment: (util - 50%) * (12% - 6%) / (80% - 50%) + 6%
+            let excess_util = util_ratio.saturating_sub(Ratio::from_percent(50));
+            let borrow_rate = Rate::saturating_from_rational(6, 100)
+                .saturating_mul(excess_util.into())
+                .checked_div(&Ratio::from_percent(30).into())
+                .unwrap()
+                + Rate::saturating_from_rational(6, 100);
+            assert_eq!(Loans::borrow_rate(KSM), borrow_rate);
+
+            let interest_accumulated: u128 = borrow_rate
+                .saturating_mul_int(total_borrows)
+                .saturating_mul(delta_time)
+                .checked_div(SECONDS_PER_YEAR.into())
+                .unwrap();
+            total_borrows += interest_accumulated;
+            assert_eq!(Loans::total_borrows(KSM), total_borrows);
+
+            borrow_index = borrow_index
+                .saturating_mul(borrow_rate)
+                .saturating_mul(Rate::saturating_from_integer(delta_time))
+                .checked_div(&Rate::saturating_from_integer(SECONDS_PER_YEAR))
+                .unwrap()
+                + borrow_index;
+            assert_eq!(Loans::borrow_index(KSM), borrow_index);
+        }
+        // 75% utilization plus accrued interest sits just above 11%
+        assert!(Loans::borrow_rate(KSM) > Rate::saturating_from_rational(11, 100));
+        assert!(Loans::borrow_rate(KSM) < Rate::saturating_from_rational(12, 100));
+
+        // Repaying moves the market back to the first segment
+        assert_ok!(Loans::repay_borrow(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            unit(100)
+        ));
+        TimestampPallet::set_timestamp(6000 * 11);
+        assert_ok!(Loans::accrue_interest(KSM));
+        assert!(Loans::utilization_ratio(KSM) < Ratio::from_percent(50));
+        assert!(Loans::borrow_rate(KSM) < Rate::saturating_from_rational(6, 100));
+    })
+}
+
+#[test]
+fn adaptive_rate_model_drifts_with_utilization() {
+    new_test_ext().execute_with(|| {
+        let rate_model = InterestRateModel::new_adaptive_model(
+            Ratio::from_percent(50),
+            Rate::saturating_from_rational(4, 100),
+            Rate::saturating_from_rational(1, 100),
+            Rate::saturating_from_rational(20, 100),
+            Rate::saturating_from_integer(50),
+            Rate::saturating_from_integer(4),
+        );
+        assert_ok!(Loans::update_rate_model(
+            RuntimeOrigin::root(),
+            KSM,
+            rate_model.clone()
+        ));
+        borrow_ksm_at_75_percent_utilization();
+
+        let one_day = 24 * 3600;
+        let mut expected_model = rate_model;
+        let mut last_borrow_rate = Rate::zero();
+        for i in 1..5 {
+            TimestampPallet::set_timestamp(6000 + one_day * 1000 * i);
+            assert_ok!(Loans::accrue_interest(KSM));
+            let util_ratio = Loans::utilization_ratio(KSM);
+            assert!(util_ratio > Ratio::from_percent(50));
+
+            // The rate of the elapsed period is priced with the previous state
+            assert_eq!(
+                Loans::borrow_rate(KSM),
+                expected_model.get_borrow_rate(util_ratio).unwrap()
+            );
+            assert!(Loans::borrow_rate(KSM) > last_borrow_rate);
+            last_borrow_rate = Loans::borrow_rate(KSM);
+
+            expected_model = expected_model.evolve(util_ratio, one_day).unwrap();
+            assert_eq!(Loans::market(KSM).unwrap().rate_model, expected_model);
+        }
+
+        // Rate at target moves back down once utilization drops below target
+        let before = rate_at_target(KSM);
+        assert_ok!(Loans::repay_borrow(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            unit(140)
+        ));
+        TimestampPallet::set_timestamp(6000 + one_day * 1000 * 6);
+        assert_ok!(Loans::accrue_interest(KSM));
+        assert!(rate_at_target(KSM) < before);
+    })
+}
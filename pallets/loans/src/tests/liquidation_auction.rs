@@ -0,0 +1,339 @@
+use crate::{
+    mock::{
+        new_test_ext, AccountId, Assets, Loans, MockPriceFeeder, RuntimeOrigin, Test,
+        TimestampPallet, ALICE, BOB, KSM, USDT,
+    },
+    tests::unit,
+    Error,
+};
+use frame_support::{assert_noop, assert_ok};
+use pallet_traits::{AuctionDecay, LiquidationAuctionConfig};
+use primitives::{tokens::CDOT_6_13, CurrencyId, Rate, Ratio};
+use sp_runtime::{
+    traits::{BadOrigin, One, Zero},
+    FixedPointNumber,
+};
+
+#[test]
+fn update_liquidation_auction_config_works() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Loans::update_liquidation_auction_config(
+                RuntimeOrigin::signed(ALICE),
+                USDT,
+                Some(linear_config())
+            ),
+            BadOrigin
+        );
+
+        let mut config = linear_config();
+        config.min_price = Rate::zero();
+        assert_noop!(
+            Loans::update_liquidation_auction_config(RuntimeOrigin::root(), USDT, Some(config)),
+            Error::<Test>::InvalidAuctionConfig
+        );
+        let mut config = linear_config();
+        config.start_premium = Rate::from_inner(Rate::DIV / 2);
+        assert_noop!(
+            Loans::update_liquidation_auction_config(RuntimeOrigin::root(), USDT, Some(config)),
+            Error::<Test>::InvalidAuctionConfig
+        );
+        let mut config = linear_config();
+        config.decay = AuctionDecay::Stepwise {
+            step: 10,
+            cut: Ratio::one(),
+        };
+        assert_noop!(
+            Loans::update_liquidation_auction_config(RuntimeOrigin::root(), USDT, Some(config)),
+            Error::<Test>::InvalidAuctionConfig
+        );
+
+        assert_ok!(Loans::update_liquidation_auction_config(
+            RuntimeOrigin::root(),
+            USDT,
+            Some(linear_config())
+        ));
+        assert_eq!(
+            Loans::liquidation_auction_config(USDT),
+            Some(linear_config())
+        );
+        assert_ok!(Loans::update_liquidation_auction_config(
+            RuntimeOrigin::root(),
+            USDT,
+            None
+        ));
+        assert_eq!(Loans::liquidation_auction_config(USDT), None);
+    })
+}
+
+#[test]
+fn auction_price_decays_linearly() {
+    let config = linear_config();
+    assert_eq!(Loans::auction_price(&config, 0), Rate::from_float(1.25));
+    assert_eq!(Loans::auction_price(&config, 50), Rate::one());
+    assert_eq!(Loans::auction_price(&config, 100), Rate::from_float(0.75));
+    assert_eq!(Loans::auction_price(&config, 150), Rate::from_float(0.75));
+}
+
+#[test]
+fn auction_price_decays_stepwise() {
+    let config = LiquidationAuctionConfig {
+        start_premium: Rate::saturating_from_rational(12, 10),
+        min_price: Rate::saturating_from_rational(9, 10),
+        decay: AuctionDecay::Stepwise {
+            step: 10,
+            cut: Ratio::from_percent(10),
+        },
+        duration: 200,
+    };
+    assert_eq!(
+        Loans::auction_price(&config, 0),
+        Rate::saturating_from_rational(12, 10)
+    );
+    assert_eq!(
+        Loans::auction_price(&config, 9),
+        Rate::saturating_from_rational(12, 10)
+    );
+    assert_eq!(
+        Loans::auction_price(&config, 10),
+        Rate::saturating_from_rational(108, 100)
+    );
+    assert_eq!(
+        Loans::auction_price(&config, 25),
+        Rate::saturating_from_rational(972, 1000)
+    );
+    // floored at min_price
+    assert_eq!(
+        Loans::auction_price(&config, 30),
+        Rate::saturating_from_rational(9, 10)
+    );
+}
+
+#[test]
+fn liquidate_borrow_starts_auction() {
+    new_test_ext().execute_with(|| {
+        initial_setup();
+        assert_ok!(Loans::liquidate_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM,
+            unit(50),
+            USDT
+        ));
+
+        // The liquidator doesn't repay anything, the debt stays with the borrower
+        assert_eq!(Assets::balance(KSM, &BOB), unit(800));
+        assert_eq!(Loans::account_borrows(KSM, ALICE).principal, unit(100));
+        // Seized collateral = (50 * 2) * 1.1 = 110
+        assert_eq!(deposit_value(USDT, &ALICE), unit(90));
+        assert_eq!(deposit_value(USDT, &BOB), 0);
+
+        let auction_id = Loans::borrower_auction(ALICE).unwrap();
+        let auction = Loans::liquidation_auction(auction_id).unwrap();
+        assert_eq!(auction.borrower, ALICE);
+        assert_eq!(auction.liquidation_asset_id, KSM);
+        assert_eq!(auction.collateral_asset_id, USDT);
+        assert_eq!(auction.debt, unit(50));
+        assert_eq!(
+            Loans::exchange_rate(USDT).saturating_mul_int(auction.collateral),
+            unit(110)
+        );
+        assert_eq!(Loans::next_auction_id(), auction_id + 1);
+        assert_eq!(
+            Loans::get_liquidation_auctions(),
+            vec![(auction_id, auction, Rate::from_float(1.25))]
+        );
+
+        // Only one open auction per borrower
+        assert_noop!(
+            Loans::liquidate_borrow(RuntimeOrigin::signed(BOB), ALICE, KSM, unit(10), USDT),
+            Error::<Test>::AuctionAlreadyStarted
+        );
+    })
+}
+
+#[test]
+fn bids_repay_debt_at_decaying_price() {
+    new_test_ext().execute_with(|| {
+        initial_setup();
+        assert_ok!(Loans::liquidate_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM,
+            unit(50),
+            USDT
+        ));
+        let auction_id = Loans::borrower_auction(ALICE).unwrap();
+
+        assert_noop!(
+            Loans::bid_liquidation_auction(RuntimeOrigin::signed(ALICE), auction_id, unit(25)),
+            Error::<Test>::LiquidatorIsBorrower
+        );
+        assert_noop!(
+            Loans::bid_liquidation_auction(RuntimeOrigin::signed(BOB), auction_id + 1, unit(25)),
+            Error::<Test>::AuctionDoesNotExist
+        );
+
+        // repay value = 25 * 2 = $50, collateral price = 1 * 1.25
+        // collateral = 50 / 1.25 = 40
+        assert_ok!(Loans::bid_liquidation_auction(
+            RuntimeOrigin::signed(BOB),
+            auction_id,
+            unit(25)
+        ));
+        assert_eq!(Assets::balance(KSM, &BOB), unit(775));
+        assert_eq!(Loans::account_borrows(KSM, ALICE).principal, unit(75));
+        assert_eq!(deposit_value(USDT, &BOB), unit(40));
+        let auction = Loans::liquidation_auction(auction_id).unwrap();
+        assert_eq!(auction.debt, unit(25));
+        assert_eq!(
+            Loans::exchange_rate(USDT).saturating_mul_int(auction.collateral),
+            unit(70)
+        );
+
+        // Half way through the decay the collateral is sold at the oracle price
+        // collateral = 50 / 1 = 50
+        TimestampPallet::set_timestamp(6000 + 50 * 1000);
+        assert_ok!(Loans::bid_liquidation_auction(
+            RuntimeOrigin::signed(BOB),
+            auction_id,
+            unit(100)
+        ));
+        assert_eq!(Assets::balance(KSM, &BOB), unit(750));
+        assert_eq!(deposit_value(USDT, &BOB), unit(90));
+
+        // The debt is covered, the 20 collateral left goes back to ALICE
+        assert!(Loans::borrower_auction(ALICE).is_none());
+        assert!(Loans::liquidation_auction(auction_id).is_err());
+        assert_eq!(deposit_value(USDT, &ALICE), unit(110));
+    })
+}
+
+#[test]
+fn bid_clearing_the_auction_only_repays_collateral_left() {
+    new_test_ext().execute_with(|| {
+        initial_setup();
+        assert_ok!(Loans::liquidate_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM,
+            unit(50),
+            USDT
+        ));
+        let auction_id = Loans::borrower_auction(ALICE).unwrap();
+
+        // The collateral left is worth less than the debt once its price halves
+        MockPriceFeeder::set_price(USDT, Rate::saturating_from_rational(1, 2));
+        assert_ok!(Loans::bid_liquidation_auction(
+            RuntimeOrigin::signed(BOB),
+            auction_id,
+            unit(50)
+        ));
+
+        // repay = 110 * 0.5 * 1.25 / 2 = 34.375
+        assert_eq!(Assets::balance(KSM, &BOB), 765_625_000_000_000);
+        assert_eq!(
+            Loans::account_borrows(KSM, ALICE).principal,
+            65_625_000_000_000
+        );
+        assert_eq!(deposit_value(USDT, &BOB), unit(110));
+        assert!(Loans::borrower_auction(ALICE).is_none());
+        assert_eq!(deposit_value(USDT, &ALICE), unit(90));
+    })
+}
+
+#[test]
+fn expired_auction_is_settled_against_reserves() {
+    new_test_ext().execute_with(|| {
+        initial_setup();
+        assert_ok!(Loans::add_reserves(
+            RuntimeOrigin::root(),
+            BOB,
+            KSM,
+            unit(100)
+        ));
+        assert_ok!(Loans::liquidate_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM,
+            unit(50),
+            USDT
+        ));
+        let auction_id = Loans::borrower_auction(ALICE).unwrap();
+
+        TimestampPallet::set_timestamp(6000 + 199 * 1000);
+        assert_noop!(
+            Loans::settle_liquidation_auction(RuntimeOrigin::signed(BOB), auction_id),
+            Error::<Test>::AuctionNotExpired
+        );
+
+        TimestampPallet::set_timestamp(6000 + 200 * 1000);
+        assert_ok!(Loans::accrue_interest(KSM));
+        assert_ok!(Loans::accrue_interest(USDT));
+        let total_reserves = Loans::total_reserves(KSM);
+        let account_borrows = Loans::current_borrow_balance(&ALICE, KSM).unwrap();
+        let collateral_reserves = Loans::total_reserves(USDT);
+        let exchange_rate = Loans::exchange_rate_stored(USDT).unwrap();
+        assert_ok!(Loans::settle_liquidation_auction(
+            RuntimeOrigin::signed(BOB),
+            auction_id
+        ));
+
+        // The reserves cover the debt left and receive the 110 collateral left
+        assert_eq!(Loans::total_reserves(KSM), total_reserves - unit(50));
+        assert_eq!(
+            Loans::account_borrows(KSM, ALICE).principal,
+            account_borrows - unit(50)
+        );
+        assert_eq!(Loans::total_reserves(USDT), collateral_reserves + unit(110));
+        assert_eq!(Loans::exchange_rate_stored(USDT).unwrap(), exchange_rate);
+        assert_eq!(deposit_value(USDT, &ALICE), unit(90));
+        assert!(Loans::borrower_auction(ALICE).is_none());
+        assert!(Loans::get_liquidation_auctions().is_empty());
+    })
+}
+
+fn linear_config() -> LiquidationAuctionConfig {
+    LiquidationAuctionConfig {
+        start_premium: Rate::from_float(1.25),
+        min_price: Rate::from_float(0.75),
+        decay: AuctionDecay::Linear { duration: 100 },
+        duration: 200,
+    }
+}
+
+fn deposit_value(asset_id: CurrencyId, who: &AccountId) -> u128 {
+    Loans::exchange_rate(asset_id)
+        .saturating_mul_int(Loans::account_deposits(asset_id, who).voucher_balance)
+}
+
+fn initial_setup() {
+    // Bob deposits 200 KSM
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+    // Alice deposits 200 USDT and 200 CDOT as collateral
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(200)));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(ALICE),
+        USDT,
+        true
+    ));
+    assert_ok!(Loans::mint(
+        RuntimeOrigin::signed(ALICE),
+        CDOT_6_13,
+        unit(200)
+    ));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(ALICE),
+        CDOT_6_13,
+        true
+    ));
+    // Alice borrows 100 KSM
+    assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(100)));
+    // Adjust KSM price to make shortfall
+    MockPriceFeeder::set_price(KSM, 2.into());
+    assert_ok!(Loans::update_liquidation_auction_config(
+        RuntimeOrigin::root(),
+        USDT,
+        Some(linear_config())
+    ));
+}
@@ -344,7 +344,7 @@ fn update_rate_model_works() {
         assert_ok!(Loans::update_rate_model(
             RuntimeOrigin::root(),
             DOT,
-            new_rate_model,
+            new_rate_model.clone(),
         ));
         assert_eq!(Loans::market(DOT).unwrap().rate_model, new_rate_model);
 
@@ -0,0 +1,330 @@
+use crate::{
+    mock::{
+        new_test_ext, Loans, MockPriceFeeder, RuntimeOrigin, Test, ALICE, BOB, DAVE, DOT, KSM,
+        SDOT, USDT,
+    },
+    tests::unit,
+    AccountDeposits, EModeCategory, Error, IsolationConfig,
+};
+use frame_support::{assert_noop, assert_ok};
+use pallet_traits::AccountMode;
+use primitives::{Rate, Ratio};
+use sp_runtime::{traits::Zero, FixedPointNumber, FixedU128};
+
+#[test]
+fn update_isolation_config_works() {
+    new_test_ext().execute_with(|| {
+        let mut config = isolation_config();
+        config.debt_ceiling = FixedU128::zero();
+        assert_noop!(
+            Loans::update_isolation_config(RuntimeOrigin::root(), USDT, Some(config)),
+            Error::<Test>::InvalidIsolationConfig
+        );
+        let mut config = isolation_config();
+        config.borrowable_assets = Default::default();
+        assert_noop!(
+            Loans::update_isolation_config(RuntimeOrigin::root(), USDT, Some(config)),
+            Error::<Test>::InvalidIsolationConfig
+        );
+        let mut config = isolation_config();
+        config.borrowable_assets = vec![SDOT].try_into().unwrap();
+        assert_noop!(
+            Loans::update_isolation_config(RuntimeOrigin::root(), USDT, Some(config)),
+            Error::<Test>::MarketDoesNotExist
+        );
+
+        assert_ok!(Loans::update_isolation_config(
+            RuntimeOrigin::root(),
+            USDT,
+            Some(isolation_config())
+        ));
+        assert_eq!(Loans::isolation_config(USDT), Some(isolation_config()));
+        assert_ok!(Loans::update_isolation_config(
+            RuntimeOrigin::root(),
+            USDT,
+            None
+        ));
+        assert_eq!(Loans::isolation_config(USDT), None);
+    })
+}
+
+#[test]
+fn isolated_collateral_cannot_be_combined() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::update_isolation_config(
+            RuntimeOrigin::root(),
+            USDT,
+            Some(isolation_config())
+        ));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(200)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_noop!(
+            Loans::collateral_asset(RuntimeOrigin::signed(ALICE), USDT, true),
+            Error::<Test>::IsolationModeConflict
+        );
+
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            false
+        ));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            USDT,
+            true
+        ));
+        assert_eq!(Loans::get_account_mode(&ALICE), AccountMode::Isolated(USDT));
+        assert_noop!(
+            Loans::collateral_asset(RuntimeOrigin::signed(ALICE), DOT, true),
+            Error::<Test>::IsolationModeConflict
+        );
+        assert_ok!(Loans::update_emode_category(
+            RuntimeOrigin::root(),
+            1,
+            Some(emode_category())
+        ));
+        assert_noop!(
+            Loans::set_emode(RuntimeOrigin::signed(ALICE), Some(1)),
+            Error::<Test>::IsolationModeConflict
+        );
+    })
+}
+
+#[test]
+fn isolation_checks_collateral_flags_of_empty_deposits() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::update_isolation_config(
+            RuntimeOrigin::root(),
+            USDT,
+            Some(isolation_config())
+        ));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(200)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+
+        // All DOT vouchers are seized, the market stays a collateral of ALICE and
+        // new deposits would back her borrows along with the isolated collateral
+        AccountDeposits::<Test>::mutate(DOT, ALICE, |deposits| {
+            deposits.voucher_balance = Zero::zero()
+        });
+        assert_noop!(
+            Loans::collateral_asset(RuntimeOrigin::signed(ALICE), USDT, true),
+            Error::<Test>::IsolationModeConflict
+        );
+    })
+}
+
+#[test]
+fn isolated_borrows_are_bounded() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), DOT, unit(200)));
+        assert_ok!(Loans::update_isolation_config(
+            RuntimeOrigin::root(),
+            USDT,
+            Some(isolation_config())
+        ));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            USDT,
+            true
+        ));
+
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(10)),
+            Error::<Test>::NotBorrowableInIsolation
+        );
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(40)));
+        assert_eq!(Loans::isolated_borrows(USDT, KSM), unit(40));
+        // The debt ceiling is $50
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(20)),
+            Error::<Test>::DebtCeilingExceeded
+        );
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(10)));
+        assert_eq!(Loans::isolated_borrows(USDT, KSM), unit(50));
+
+        // Repaid debt frees up the debt ceiling
+        assert_ok!(Loans::repay_borrow(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            unit(30)
+        ));
+        assert_eq!(Loans::isolated_borrows(USDT, KSM), unit(20));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(30)));
+
+        // The debt ceiling is valued with the current price
+        MockPriceFeeder::set_price(KSM, 2.into());
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(1)),
+            Error::<Test>::DebtCeilingExceeded
+        );
+    })
+}
+
+#[test]
+fn update_emode_category_works() {
+    new_test_ext().execute_with(|| {
+        let mut category = emode_category();
+        category.collateral_factor = Ratio::from_percent(95);
+        assert_noop!(
+            Loans::update_emode_category(RuntimeOrigin::root(), 1, Some(category)),
+            Error::<Test>::InvalidEModeCategory
+        );
+        let mut category = emode_category();
+        category.liquidation_threshold = Ratio::from_percent(100);
+        assert_noop!(
+            Loans::update_emode_category(RuntimeOrigin::root(), 1, Some(category)),
+            Error::<Test>::InvalidEModeCategory
+        );
+        let mut category = emode_category();
+        category.assets = vec![DOT, SDOT].try_into().unwrap();
+        assert_noop!(
+            Loans::update_emode_category(RuntimeOrigin::root(), 1, Some(category)),
+            Error::<Test>::MarketDoesNotExist
+        );
+
+        assert_ok!(Loans::update_emode_category(
+            RuntimeOrigin::root(),
+            1,
+            Some(emode_category())
+        ));
+        assert_eq!(Loans::emode_category(1), Some(emode_category()));
+        assert_noop!(
+            Loans::set_emode(RuntimeOrigin::signed(ALICE), Some(2)),
+            Error::<Test>::EModeCategoryDoesNotExist
+        );
+    })
+}
+
+#[test]
+fn emode_raises_borrowing_power_inside_category() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::update_emode_category(
+            RuntimeOrigin::root(),
+            1,
+            Some(emode_category())
+        ));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(DAVE), USDT, unit(200)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_eq!(Loans::get_account_mode(&ALICE), AccountMode::Cross);
+        assert_eq!(
+            Loans::get_account_liquidity(&ALICE).unwrap().0,
+            FixedU128::from_inner(unit(50))
+        );
+
+        assert_ok!(Loans::set_emode(RuntimeOrigin::signed(ALICE), Some(1)));
+        assert_eq!(Loans::account_emode(ALICE), Some(1));
+        assert_eq!(Loans::get_account_mode(&ALICE), AccountMode::EMode(1));
+        // DOT collateral: 100 * 80% = 80
+        assert_eq!(
+            Loans::get_account_liquidity(&ALICE).unwrap().0,
+            FixedU128::from_inner(unit(80))
+        );
+
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(70)));
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), USDT, unit(1)),
+            Error::<Test>::NotInEModeCategory
+        );
+        // Leaving the category would leave ALICE with a shortfall
+        assert_noop!(
+            Loans::set_emode(RuntimeOrigin::signed(ALICE), None),
+            Error::<Test>::InsufficientLiquidity
+        );
+
+        // DOT collateral: 100 * 90% = 90 > KSM borrow: 70 * 1.2 = 84
+        MockPriceFeeder::set_price(KSM, Rate::saturating_from_rational(12, 10));
+        let ksm_market = Loans::market(KSM).unwrap();
+        assert_noop!(
+            Loans::liquidate_borrow_allowed(&ALICE, KSM, unit(10), &ksm_market),
+            Error::<Test>::InsufficientShortfall
+        );
+        // DOT collateral: 100 * 90% = 90 < KSM borrow: 70 * 1.5 = 105
+        MockPriceFeeder::set_price(KSM, Rate::saturating_from_rational(15, 10));
+        assert_eq!(
+            Loans::get_account_liquidation_threshold_liquidity(&ALICE)
+                .unwrap()
+                .1,
+            FixedU128::from_inner(unit(15))
+        );
+        assert_ok!(Loans::liquidate_borrow_allowed(
+            &ALICE,
+            KSM,
+            unit(10),
+            &ksm_market
+        ));
+    })
+}
+
+#[test]
+fn borrows_must_be_in_category_to_enter_emode() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::update_emode_category(
+            RuntimeOrigin::root(),
+            1,
+            Some(emode_category())
+        ));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(DAVE), USDT, unit(200)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), USDT, unit(10)));
+        assert_noop!(
+            Loans::set_emode(RuntimeOrigin::signed(ALICE), Some(1)),
+            Error::<Test>::NotInEModeCategory
+        );
+
+        assert_ok!(Loans::repay_borrow_all(RuntimeOrigin::signed(ALICE), USDT));
+        assert_ok!(Loans::set_emode(RuntimeOrigin::signed(ALICE), Some(1)));
+        // An account in efficiency mode can't use isolated collateral
+        assert_ok!(Loans::update_isolation_config(
+            RuntimeOrigin::root(),
+            USDT,
+            Some(isolation_config())
+        ));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(100)));
+        assert_noop!(
+            Loans::collateral_asset(RuntimeOrigin::signed(ALICE), USDT, true),
+            Error::<Test>::IsolationModeConflict
+        );
+
+        assert_ok!(Loans::set_emode(RuntimeOrigin::signed(ALICE), None));
+        assert_eq!(Loans::get_account_mode(&ALICE), AccountMode::Cross);
+    })
+}
+
+fn isolation_config() -> IsolationConfig<primitives::CurrencyId> {
+    IsolationConfig {
+        debt_ceiling: FixedU128::from_inner(unit(50)),
+        borrowable_assets: vec![KSM].try_into().unwrap(),
+    }
+}
+
+fn emode_category() -> EModeCategory<primitives::CurrencyId> {
+    EModeCategory {
+        collateral_factor: Ratio::from_percent(80),
+        liquidation_threshold: Ratio::from_percent(90),
+        assets: vec![DOT, KSM].try_into().unwrap(),
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{
+    mock::{
+        new_test_ext, Loans, RuntimeOrigin, Test, TimestampPallet, ALICE, BOB, DAVE, KSM, SDOT,
+        USDT,
+    },
+    tests::unit,
+    Error, StableBorrowConfig,
+};
+use frame_support::{assert_noop, assert_ok};
+use primitives::{Rate, Ratio, SECONDS_PER_YEAR};
+use sp_runtime::{traits::Zero, DispatchError, FixedPointNumber};
+
+const STABLE_BORROW_CONFIG: StableBorrowConfig = StableBorrowConfig {
+    rate_premium: Rate::from_inner(Rate::DIV / 100 * 2),
+    rebalance_threshold: Rate::from_inner(Rate::DIV / 100 * 3),
+};
+
+#[test]
+fn update_stable_borrow_config_works() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Loans::update_stable_borrow_config(
+                RuntimeOrigin::signed(ALICE),
+                KSM,
+                Some(STABLE_BORROW_CONFIG)
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Loans::update_stable_borrow_config(
+                RuntimeOrigin::root(),
+                SDOT,
+                Some(STABLE_BORROW_CONFIG)
+            ),
+            Error::<Test>::MarketDoesNotExist
+        );
+
+        assert_ok!(Loans::update_stable_borrow_config(
+            RuntimeOrigin::root(),
+            KSM,
+            Some(STABLE_BORROW_CONFIG)
+        ));
+        assert_eq!(Loans::stable_borrow_config(KSM), Some(STABLE_BORROW_CONFIG));
+        assert_eq!(
+            Loans::get_market_status(KSM).unwrap().stable_borrow_rate,
+            Some(Rate::saturating_from_rational(4, 100))
+        );
+
+        assert_ok!(Loans::update_stable_borrow_config(
+            RuntimeOrigin::root(),
+            KSM,
+            None
+        ));
+        assert_eq!(Loans::stable_borrow_config(KSM), None);
+        assert_eq!(
+            Loans::get_market_status(KSM).unwrap().stable_borrow_rate,
+            None
+        );
+    })
+}
+
+#[test]
+fn borrow_stable_locks_rate() {
+    new_test_ext().execute_with(|| {
+        initial_setup();
+        assert_noop!(
+            Loans::borrow_stable(RuntimeOrigin::signed(ALICE), KSM, unit(100)),
+            Error::<Test>::StableBorrowDisabled
+        );
+        assert_ok!(Loans::update_stable_borrow_config(
+            RuntimeOrigin::root(),
+            KSM,
+            Some(STABLE_BORROW_CONFIG)
+        ));
+
+        // utilization after the borrow: 100 / 200 = 50%
+        // stable rate: 2% + 8% * 50% / 80% + 2% premium = 9%
+        assert_ok!(Loans::borrow_stable(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            unit(100)
+        ));
+        let stable_rate = Rate::saturating_from_rational(9, 100);
+        let snapshot = Loans::account_borrows(KSM, ALICE);
+        assert_eq!(snapshot.principal, unit(100));
+        assert_eq!(snapshot.stable_rate, Some(stable_rate));
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(1)),
+            Error::<Test>::BorrowRateModeMismatch
+        );
+
+        // A variable rate borrow raises the utilization to 75%
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(DAVE), USDT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(DAVE),
+            USDT,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(DAVE), KSM, unit(50)));
+        let status = Loans::get_market_status(KSM).unwrap();
+        assert_eq!(status.total_borrows, unit(50));
+        assert_eq!(status.total_stable_borrows, unit(100));
+        assert_eq!(status.average_stable_borrow_rate, stable_rate);
+        assert_eq!(status.utilization, Ratio::from_percent(75));
+        assert_eq!(status.borrow_rate, Rate::saturating_from_rational(95, 1000));
+        assert_eq!(
+            status.stable_borrow_rate,
+            Some(Rate::saturating_from_rational(115, 1000))
+        );
+
+        // The stable rate borrow accrues at the locked rate
+        TimestampPallet::set_timestamp(6000 + SECONDS_PER_YEAR * 1000);
+        assert_ok!(Loans::accrue_interest(KSM));
+        assert_eq!(
+            Loans::current_borrow_balance(&ALICE, KSM).unwrap(),
+            unit(109)
+        );
+        assert_eq!(Loans::total_stable_borrows(KSM), unit(109));
+
+        assert_ok!(Loans::repay_borrow_all(RuntimeOrigin::signed(ALICE), KSM));
+        let snapshot = Loans::account_borrows(KSM, ALICE);
+        assert!(snapshot.principal.is_zero());
+        assert_eq!(snapshot.stable_rate, None);
+        assert!(Loans::total_stable_borrows(KSM).is_zero());
+        assert!(Loans::average_stable_borrow_rate(KSM).is_zero());
+        // The rate mode can change once the debt is repaid
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(10)));
+    })
+}
+
+#[test]
+fn rebalance_stable_borrow_rate_works() {
+    new_test_ext().execute_with(|| {
+        initial_setup();
+        assert_ok!(Loans::update_stable_borrow_config(
+            RuntimeOrigin::root(),
+            KSM,
+            Some(STABLE_BORROW_CONFIG)
+        ));
+        assert_ok!(Loans::borrow_stable(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            unit(100)
+        ));
+        assert_noop!(
+            Loans::rebalance_stable_borrow_rate(RuntimeOrigin::signed(DAVE), BOB, KSM),
+            Error::<Test>::RebalanceNotAllowed
+        );
+        // supply rate: 9% * 85% * 50% = 3.825% >= 3%
+        assert_noop!(
+            Loans::rebalance_stable_borrow_rate(RuntimeOrigin::signed(DAVE), ALICE, KSM),
+            Error::<Test>::RebalanceNotAllowed
+        );
+
+        // utilization: 100 / 400 = 25%, supply rate: 9% * 85% * 25% = 1.9125% < 3%
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+        assert_ok!(Loans::rebalance_stable_borrow_rate(
+            RuntimeOrigin::signed(DAVE),
+            ALICE,
+            KSM
+        ));
+        // stable rate: 2% + 8% * 25% / 80% + 2% premium = 6.5%
+        let stable_rate = Rate::saturating_from_rational(65, 1000);
+        let snapshot = Loans::account_borrows(KSM, ALICE);
+        assert_eq!(snapshot.principal, unit(100));
+        assert_eq!(snapshot.stable_rate, Some(stable_rate));
+        assert_eq!(Loans::total_stable_borrows(KSM), unit(100));
+        assert_eq!(Loans::average_stable_borrow_rate(KSM), stable_rate);
+    })
+}
+
+fn initial_setup() {
+    // Bob deposits 200 KSM
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+    // Alice deposits 200 USDT as collateral
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(200)));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(ALICE),
+        USDT,
+        true
+    ));
+}
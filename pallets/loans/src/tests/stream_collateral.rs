@@ -0,0 +1,215 @@
+use crate::{
+    mock::{
+        new_test_ext, Assets, Loans, RuntimeOrigin, Streaming, Test, TimestampPallet, ALICE, BOB,
+        CHARLIE, DAVE, DOT, USDT,
+    },
+    tests::unit,
+    Error, SeizedStream,
+};
+use frame_support::{assert_noop, assert_ok};
+use pallet_streaming::Streams;
+
+fn create_stream(cancellable: bool) {
+    // BOB streams 100 DOT to ALICE over 100 seconds
+    assert_ok!(Streaming::create(
+        RuntimeOrigin::signed(BOB),
+        ALICE,
+        unit(100),
+        DOT,
+        6,
+        106,
+        cancellable
+    ));
+}
+
+#[test]
+fn pledge_stream_works() {
+    new_test_ext().execute_with(|| {
+        create_stream(true);
+        assert_noop!(
+            Loans::pledge_stream(RuntimeOrigin::signed(ALICE), 0),
+            pallet_streaming::Error::<Test>::StreamIsCancellable
+        );
+
+        create_stream(false);
+        assert_noop!(
+            Loans::pledge_stream(RuntimeOrigin::signed(BOB), 1),
+            pallet_streaming::Error::<Test>::NotTheRecipient
+        );
+        assert_ok!(Loans::pledge_stream(RuntimeOrigin::signed(ALICE), 1));
+        assert_eq!(Loans::stream_collaterals(ALICE).into_inner(), vec![1]);
+        assert!(Streams::<Test>::get(1).unwrap().is_collateral());
+        assert_noop!(
+            Loans::pledge_stream(RuntimeOrigin::signed(ALICE), 1),
+            pallet_streaming::Error::<Test>::StreamIsCollateral
+        );
+
+        // A pledged stream can't be transferred
+        assert_noop!(
+            Streaming::transfer_recipient(RuntimeOrigin::signed(ALICE), 1, CHARLIE),
+            pallet_streaming::Error::<Test>::StreamIsCollateral
+        );
+        assert_noop!(
+            Streaming::transfer_sender(RuntimeOrigin::signed(BOB), 1, CHARLIE),
+            pallet_streaming::Error::<Test>::StreamIsCollateral
+        );
+    })
+}
+
+#[test]
+fn borrow_against_stream_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(DAVE), DOT, unit(200)));
+        create_stream(false);
+        assert_ok!(Loans::pledge_stream(RuntimeOrigin::signed(ALICE), 0));
+
+        // 100 * (1 - 30%) * 50% = 35
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(36)),
+            Error::<Test>::InsufficientLiquidity
+        );
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(30)));
+
+        assert_noop!(
+            Loans::release_stream(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::InsufficientLiquidity
+        );
+        assert_noop!(
+            Loans::release_stream(RuntimeOrigin::signed(ALICE), 1),
+            Error::<Test>::StreamNotPledged
+        );
+
+        assert_ok!(Loans::repay_borrow_all(RuntimeOrigin::signed(ALICE), DOT));
+        assert_ok!(Loans::release_stream(RuntimeOrigin::signed(ALICE), 0));
+        assert!(Loans::stream_collaterals(ALICE).is_empty());
+        assert!(!Streams::<Test>::get(0).unwrap().is_collateral());
+    })
+}
+
+#[test]
+fn liquidate_stream_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(DAVE), DOT, unit(200)));
+        create_stream(false);
+        assert_ok!(Loans::pledge_stream(RuntimeOrigin::signed(ALICE), 0));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(30)));
+
+        assert_noop!(
+            Loans::liquidate_stream(RuntimeOrigin::signed(DAVE), ALICE, 0, DOT, unit(15)),
+            Error::<Test>::InsufficientShortfall
+        );
+
+        // Half of the stream is vested, 50 * (1 - 30%) * 55% < 30
+        TimestampPallet::set_timestamp(56000);
+        assert_noop!(
+            Loans::liquidate_stream(RuntimeOrigin::signed(ALICE), ALICE, 0, DOT, unit(15)),
+            Error::<Test>::LiquidatorIsBorrower
+        );
+        assert_noop!(
+            Loans::liquidate_stream(RuntimeOrigin::signed(DAVE), ALICE, 0, DOT, unit(16)),
+            Error::<Test>::TooMuchRepay
+        );
+        assert_noop!(
+            Loans::repay_borrow_with_stream(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Test>::StreamNotSeized
+        );
+
+        // DAVE repays 15 DOT and is owed 15 * 110% = 16.5 DOT by the stream
+        assert_ok!(Loans::liquidate_stream(
+            RuntimeOrigin::signed(DAVE),
+            ALICE,
+            0,
+            DOT,
+            unit(15)
+        ));
+        assert_eq!(Assets::balance(DOT, &DAVE), unit(785));
+        assert_eq!(Loans::stream_collaterals(ALICE).into_inner(), vec![0]);
+        assert_eq!(
+            Loans::seized_streams(0),
+            Some(SeizedStream {
+                borrower: ALICE,
+                liquidator: DAVE,
+                payout: unit(165) / 10,
+            })
+        );
+        assert_eq!(
+            Streams::<Test>::get(0).unwrap().recipient,
+            Loans::account_id()
+        );
+        assert_noop!(
+            Loans::liquidate_stream(RuntimeOrigin::signed(DAVE), ALICE, 0, DOT, unit(1)),
+            Error::<Test>::StreamIsSeized
+        );
+        assert_noop!(
+            Loans::release_stream(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::StreamIsSeized
+        );
+
+        // The vested balance pays DAVE then repays the rest of the debt, the stream
+        // goes back to ALICE and stays pledged
+        assert_ok!(Loans::repay_borrow_with_stream(
+            RuntimeOrigin::signed(CHARLIE),
+            0
+        ));
+        assert_eq!(Assets::balance(DOT, &DAVE), unit(785) + unit(165) / 10);
+        assert_eq!(Loans::current_borrow_balance(&ALICE, DOT), Ok(0));
+        assert_eq!(Loans::seized_streams(0), None);
+        assert_eq!(Loans::stream_collaterals(ALICE).into_inner(), vec![0]);
+        let stream = Streams::<Test>::get(0).unwrap();
+        assert_eq!(stream.recipient, ALICE);
+        assert!(stream.is_collateral());
+    })
+}
+
+#[test]
+fn liquidate_stream_with_debt_in_another_asset_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(DAVE), USDT, unit(200)));
+        create_stream(false);
+        assert_ok!(Loans::pledge_stream(RuntimeOrigin::signed(ALICE), 0));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), USDT, unit(30)));
+
+        // ALICE withdraws the vested 30 DOT, 70 * (1 - 30%) * 55% < 30
+        TimestampPallet::set_timestamp(36000);
+        assert_ok!(Streaming::withdraw(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            unit(30)
+        ));
+        assert_ok!(Loans::liquidate_stream(
+            RuntimeOrigin::signed(DAVE),
+            ALICE,
+            0,
+            USDT,
+            unit(15)
+        ));
+        assert_eq!(Assets::balance(USDT, &DAVE), unit(785));
+
+        // 10 DOT are vested, all of them go to DAVE and the stream stays seized
+        TimestampPallet::set_timestamp(46000);
+        assert_ok!(Loans::repay_borrow_with_stream(
+            RuntimeOrigin::signed(CHARLIE),
+            0
+        ));
+        assert_eq!(Assets::balance(DOT, &DAVE), unit(1010));
+        assert_eq!(Assets::balance(DOT, &ALICE), unit(1030));
+        assert_eq!(
+            Loans::seized_streams(0).map(|seized| seized.payout),
+            Some(unit(65) / 10)
+        );
+
+        // DAVE gets the last 6.5 DOT of the payout, the rest goes to ALICE
+        TimestampPallet::set_timestamp(56000);
+        assert_ok!(Loans::repay_borrow_with_stream(
+            RuntimeOrigin::signed(CHARLIE),
+            0
+        ));
+        assert_eq!(Assets::balance(DOT, &DAVE), unit(1010) + unit(65) / 10);
+        assert_eq!(Assets::balance(DOT, &ALICE), unit(1030) + unit(35) / 10);
+        assert_eq!(Loans::seized_streams(0), None);
+        assert_eq!(Loans::stream_collaterals(ALICE).into_inner(), vec![0]);
+        let stream = Streams::<Test>::get(0).unwrap();
+        assert_eq!(stream.recipient, ALICE);
+        assert!(stream.is_collateral());
+    })
+}
@@ -1,6 +1,6 @@
 use crate::InterestRateModel;
 use frame_support::pallet_prelude::*;
-use primitives::{CurrencyId, Rate, Ratio};
+use primitives::{CurrencyId, Liquidity, Rate, Ratio, Timestamp};
 use scale_info::TypeInfo;
 
 /// Container for borrow balance information
@@ -10,6 +10,10 @@ pub struct BorrowSnapshot<Balance> {
     pub principal: Balance,
     /// InterestIndex Global borrowIndex as of the most recent balance-changing action
     pub borrow_index: Rate,
+    /// The rate locked by a stable rate borrow, `None` for variable rate borrows
+    pub stable_rate: Option<Rate>,
+    /// Unix time of the most recent balance-changing action
+    pub last_update: Timestamp,
 }
 
 /// Container for earned amount information
@@ -70,6 +74,65 @@ pub struct Market<Balance> {
     pub ptoken_id: CurrencyId,
 }
 
+/// The max number of assets of an isolation config or an efficiency mode category
+pub type MaxModeAssets = ConstU32<16>;
+
+/// Isolation params of a collateral market.
+///
+/// An isolated collateral must be the only collateral of an account and can only
+/// back borrows of the whitelisted assets.
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+pub struct IsolationConfig<AssetId> {
+    /// Upper bound of the total borrowed value backed by this collateral
+    pub debt_ceiling: Liquidity,
+    /// Assets which can be borrowed against this collateral
+    pub borrowable_assets: BoundedVec<AssetId, MaxModeAssets>,
+}
+
+/// Efficiency mode category.
+///
+/// A group of correlated assets. Accounts which opted into the category can only
+/// borrow assets of the category, and their collateral in the category use the
+/// category's risk params.
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+pub struct EModeCategory<AssetId> {
+    /// The collateral utilization ratio inside the category
+    pub collateral_factor: Ratio,
+    /// The liquidation threshold inside the category
+    pub liquidation_threshold: Ratio,
+    /// Assets of the category
+    pub assets: BoundedVec<AssetId, MaxModeAssets>,
+}
+
+/// Stable rate borrowing params of a market.
+///
+/// A stable rate borrow locks the borrow rate at borrow time instead of following
+/// the market's borrow index.
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+pub struct StableBorrowConfig {
+    /// Added to the variable borrow rate to get the stable borrow rate
+    pub rate_premium: Rate,
+    /// Stable rate borrows can be re-priced once the supply rate falls below this rate
+    pub rebalance_threshold: Rate,
+}
+
+/// A pledged stream seized by a liquidation.
+///
+/// The withdrawals of the stream pay the liquidator, who repaid the debt of the
+/// borrower, until `payout` is reached.
+#[derive(Clone, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+pub struct SeizedStream<AccountId, Balance> {
+    /// The borrower who pledged the stream
+    pub borrower: AccountId,
+    /// The liquidator who repaid the debt
+    pub liquidator: AccountId,
+    /// The balance of the stream still owed to the liquidator
+    pub payout: Balance,
+}
+
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, Default)]
 pub struct RewardMarketState<BlockNumber, Balance> {
     pub index: Balance,
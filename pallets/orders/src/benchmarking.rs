@@ -0,0 +1,139 @@
+//! Orders pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+#[allow(unused_imports)]
+use crate::Pallet as Orders;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::{
+    assert_ok,
+    traits::{fungibles::Mutate, EnsureOrigin},
+};
+use frame_system::{self, RawOrigin as SystemOrigin};
+use primitives::{tokens, Balance, CurrencyId};
+use sp_runtime::traits::StaticLookup;
+
+const DOT: CurrencyId = tokens::DOT;
+const SDOT: CurrencyId = tokens::SDOT;
+const INITIAL_AMOUNT: u128 = 1_000_000_000_000_000;
+const ASSET_ID: u32 = 11;
+const AMOUNT_IN: u128 = 1_000;
+const MIN_AMOUNT_OUT: u128 = 900;
+const TIP: u128 = 10;
+const EXPIRY: u32 = 1_000;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+    frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+fn initial_set_up<
+    T: Config + pallet_assets::Config<AssetId = CurrencyId, Balance = Balance> + pallet_amm::Config,
+>(
+    caller: T::AccountId,
+) {
+    let account_id = T::Lookup::unlookup(caller.clone());
+
+    pallet_assets::Pallet::<T>::force_create(
+        SystemOrigin::Root.into(),
+        tokens::SDOT.into(),
+        account_id.clone(),
+        true,
+        One::one(),
+    )
+    .ok();
+
+    pallet_assets::Pallet::<T>::force_create(
+        SystemOrigin::Root.into(),
+        tokens::DOT.into(),
+        account_id,
+        true,
+        One::one(),
+    )
+    .ok();
+
+    <T as pallet_router::Config>::Assets::mint_into(DOT, &caller, INITIAL_AMOUNT).ok();
+
+    let pool_creator = account("pool_creator", 1, 0);
+    <T as pallet_router::Config>::Assets::mint_into(DOT, &pool_creator, INITIAL_AMOUNT).ok();
+    <T as pallet_router::Config>::Assets::mint_into(SDOT, &pool_creator, INITIAL_AMOUNT).ok();
+
+    pallet_assets::Pallet::<T>::force_create(
+        SystemOrigin::Root.into(),
+        ASSET_ID.into(),
+        T::Lookup::unlookup(pool_creator.clone()),
+        true,
+        One::one(),
+    )
+    .ok();
+
+    if pallet_amm::Pallet::<T>::pools(SDOT, DOT) == None {
+        assert_ok!(pallet_amm::Pallet::<T>::create_pool(
+            T::CreatePoolOrigin::try_successful_origin()
+                .expect("No origin exists which can satisfy the guard"),
+            (DOT, SDOT),
+            (100_000_000u128, 100_000_000u128),
+            pool_creator,
+            ASSET_ID,
+            <T as pallet_amm::Config>::LpFee::get(),
+            None
+        ));
+    }
+}
+
+benchmarks! {
+    where_clause {
+        where
+            T: pallet_assets::Config<AssetId = CurrencyId, Balance = Balance> + pallet_amm::Config
+    }
+
+    place_limit_order {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T>(caller.clone());
+    }: _(SystemOrigin::Signed(caller.clone()), DOT, SDOT, AMOUNT_IN, MIN_AMOUNT_OUT, TIP, EXPIRY.into())
+    verify {
+        assert_last_event::<T>(Event::LimitOrderPlaced(
+            0, caller, DOT, SDOT, AMOUNT_IN, MIN_AMOUNT_OUT, TIP, EXPIRY.into()
+        ).into());
+    }
+
+    place_twap_order {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T>(caller.clone());
+        let slices = T::MaxTwapSlices::get();
+    }: _(SystemOrigin::Signed(caller.clone()), DOT, SDOT, AMOUNT_IN, MIN_AMOUNT_OUT, TIP, 1u32.into(), slices, EXPIRY.into())
+    verify {
+        assert_last_event::<T>(Event::TwapOrderPlaced(
+            0, caller, DOT, SDOT, AMOUNT_IN, MIN_AMOUNT_OUT, TIP, 1u32.into(), slices, EXPIRY.into()
+        ).into());
+    }
+
+    cancel_order {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T>(caller.clone());
+        assert_ok!(Orders::<T>::place_limit_order(
+            SystemOrigin::Signed(caller.clone()).into(),
+            DOT, SDOT, AMOUNT_IN, MIN_AMOUNT_OUT, TIP, EXPIRY.into()
+        ));
+    }: _(SystemOrigin::Signed(caller.clone()), 0)
+    verify {
+        assert_last_event::<T>(Event::OrderCancelled(0, caller, AMOUNT_IN + TIP).into());
+    }
+
+    execute_order {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T>(caller.clone());
+        assert_ok!(Orders::<T>::place_limit_order(
+            SystemOrigin::Signed(caller.clone()).into(),
+            DOT, SDOT, AMOUNT_IN, MIN_AMOUNT_OUT, TIP, EXPIRY.into()
+        ));
+        let keeper: T::AccountId = account("keeper", 0, 0);
+    }: _(SystemOrigin::Signed(keeper.clone()), 0)
+    verify {
+        assert!(Orders::<T>::orders(0).is_none());
+        assert_eq!(<T as pallet_router::Config>::Assets::balance(DOT, &keeper), TIP);
+    }
+}
+
+impl_benchmark_test_suite!(Orders, crate::mock::new_test_ext(), crate::mock::Runtime,);
@@ -0,0 +1,570 @@
+// Copyright 2021-2022 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Orders pallet
+//!
+//! ## Overview
+//!
+//! Limit and TWAP orders executed through the router, against the AMM and the stable
+//! swap pools.
+//! The sold asset is escrowed in the pallet account when the order is placed. An
+//! order is executed once the best quote meets its price, either in `on_idle` or by
+//! any keeper, who is paid the tip of the execution. TWAP orders sell their amount
+//! in equal slices, one slice every `interval` blocks. Orders left open after
+//! their expiry are refunded to their owner.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use crate::types::{Order, OrderId, OrderKind};
+use frame_support::{
+    log,
+    pallet_prelude::*,
+    require_transactional,
+    storage::with_transaction,
+    traits::tokens::fungibles::{Inspect, Transfer},
+    transactional, PalletId,
+};
+use frame_system::pallet_prelude::*;
+use pallet_router::WeightInfo as RouterWeightInfo;
+use sp_runtime::{
+    traits::{AccountIdConversion, One, Saturating, Zero},
+    ArithmeticError, TransactionOutcome,
+};
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+mod benchmarking;
+
+pub mod types;
+
+pub use pallet::*;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+type AssetIdOf<T> = <<T as pallet_router::Config>::Assets as Inspect<
+    <T as frame_system::Config>::AccountId,
+>>::AssetId;
+type BalanceOf<T> = <<T as pallet_router::Config>::Assets as Inspect<
+    <T as frame_system::Config>::AccountId,
+>>::Balance;
+type AccountOf<T> = <T as frame_system::Config>::AccountId;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config + pallet_router::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// The orders module id, keeps all the escrowed assets of orders.
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
+
+        /// The max count of orders checked in `on_idle` of each block
+        #[pallet::constant]
+        type MaxOrdersPerBlock: Get<u32>;
+
+        /// The max count of slices of a TWAP order
+        #[pallet::constant]
+        type MaxTwapSlices: Get<u32>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Amount in must not be zero
+        ZeroAmount,
+        /// Asset in and asset out must be different
+        IdenticalAssets,
+        /// Expiry must be after the current block
+        InvalidExpiry,
+        /// Interval and slices must not be zero, slices must not exceed MaxTwapSlices
+        /// and each slice must not be empty
+        InvalidTwapParameters,
+        /// The order id is not found
+        OrderDoesNotExist,
+        /// Caller is not the order owner
+        NotTheOwner,
+        /// The order has expired
+        OrderExpired,
+        /// The next execution of the order has not been reached
+        OrderNotReady,
+        /// The best quote doesn't meet the order price
+        PriceNotMet,
+        /// The router can't quote the pair
+        NoAvailableQuote,
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(crate) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Places a limit order
+        /// \[order_id, owner, asset_in, asset_out, amount_in, min_amount_out, tip, expiry\]
+        LimitOrderPlaced(
+            OrderId,
+            AccountOf<T>,
+            AssetIdOf<T>,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            T::BlockNumber,
+        ),
+        /// Places a TWAP order
+        /// \[order_id, owner, asset_in, asset_out, amount_in, min_amount_out, tip, interval,
+        /// slices, expiry\]
+        TwapOrderPlaced(
+            OrderId,
+            AccountOf<T>,
+            AssetIdOf<T>,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            T::BlockNumber,
+            u32,
+            T::BlockNumber,
+        ),
+        /// An order or a slice of it was executed, keeper is none when executed in `on_idle`
+        /// \[order_id, keeper, amount_in, amount_out\]
+        OrderExecuted(OrderId, Option<AccountOf<T>>, BalanceOf<T>, BalanceOf<T>),
+        /// An order was completely filled
+        /// \[order_id, owner, amount_in, amount_out\]
+        OrderFilled(OrderId, AccountOf<T>, BalanceOf<T>, BalanceOf<T>),
+        /// An order was cancelled by its owner
+        /// \[order_id, owner, refunded_amount\]
+        OrderCancelled(OrderId, AccountOf<T>, BalanceOf<T>),
+        /// An order expired before being filled
+        /// \[order_id, owner, refunded_amount\]
+        OrderExpired(OrderId, AccountOf<T>, BalanceOf<T>),
+    }
+
+    /// Next Order Id
+    #[pallet::storage]
+    #[pallet::getter(fn next_order)]
+    pub type NextOrderId<T: Config> = StorageValue<_, OrderId, ValueQuery>;
+
+    /// Open orders
+    #[pallet::storage]
+    #[pallet::getter(fn orders)]
+    pub type Orders<T: Config> = StorageMap<_, Twox64Concat, OrderId, Order<T>, OptionQuery>;
+
+    /// Raw key of the last order checked in `on_idle`
+    #[pallet::storage]
+    #[pallet::getter(fn matching_cursor)]
+    pub type MatchingCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(PhantomData<T>);
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_idle(block_number: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            let mut weight = T::DbWeight::get().reads_writes(1, 1);
+            if weight.any_gt(remaining_weight) {
+                return Weight::zero();
+            }
+
+            let per_order = Self::execute_order_weight();
+            let mut orders = match Self::matching_cursor() {
+                Some(cursor) => Orders::<T>::iter_from(cursor),
+                None => Orders::<T>::iter(),
+            };
+            for _ in 0..T::MaxOrdersPerBlock::get() {
+                if weight.saturating_add(per_order).any_gt(remaining_weight) {
+                    break;
+                }
+                weight.saturating_accrue(per_order);
+
+                match orders.next() {
+                    Some((order_id, order)) => Self::try_match_order(order_id, order, block_number),
+                    None => {
+                        MatchingCursor::<T>::kill();
+                        return weight;
+                    }
+                }
+            }
+            MatchingCursor::<T>::put(orders.last_raw_key().to_vec());
+
+            weight
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Place a limit order
+        ///
+        /// The whole `amount_in` and the `tip` are escrowed in the pallet account.
+        /// The order is executed once the best quote is at least `min_amount_out`.
+        ///
+        /// - `asset_in`: the asset to sell
+        /// - `asset_out`: the asset to buy
+        /// - `amount_in`: the amount of `asset_in` to sell
+        /// - `min_amount_out`: the minimum amount of `asset_out` to receive
+        /// - `tip`: the amount of `asset_in` paid to the keeper executing the order
+        /// - `expiry`: the last block the order can be executed at
+        #[pallet::call_index(0)]
+        #[pallet::weight(<T as Config>::WeightInfo::place_limit_order())]
+        #[transactional]
+        pub fn place_limit_order(
+            origin: OriginFor<T>,
+            asset_in: AssetIdOf<T>,
+            asset_out: AssetIdOf<T>,
+            #[pallet::compact] amount_in: BalanceOf<T>,
+            #[pallet::compact] min_amount_out: BalanceOf<T>,
+            #[pallet::compact] tip: BalanceOf<T>,
+            expiry: T::BlockNumber,
+        ) -> DispatchResultWithPostInfo {
+            let owner = ensure_signed(origin)?;
+
+            let order_id = Self::do_place_order(
+                owner.clone(),
+                asset_in,
+                asset_out,
+                amount_in,
+                min_amount_out,
+                tip,
+                OrderKind::Limit,
+                expiry,
+            )?;
+
+            Self::deposit_event(Event::<T>::LimitOrderPlaced(
+                order_id,
+                owner,
+                asset_in,
+                asset_out,
+                amount_in,
+                min_amount_out,
+                tip,
+                expiry,
+            ));
+
+            Ok(().into())
+        }
+
+        /// Place a TWAP order
+        ///
+        /// `amount_in` is sold in `slices` equal slices, one slice every `interval`
+        /// blocks starting from the current block. Each slice is held to the price of
+        /// `min_amount_out` for `amount_in` and pays `tip` to its keeper.
+        ///
+        /// - `asset_in`: the asset to sell
+        /// - `asset_out`: the asset to buy
+        /// - `amount_in`: the amount of `asset_in` to sell
+        /// - `min_amount_out`: the minimum amount of `asset_out` to receive
+        /// - `tip`: the amount of `asset_in` paid to the keeper executing each slice
+        /// - `interval`: the blocks between two slices
+        /// - `slices`: the count of slices
+        /// - `expiry`: the last block the order can be executed at
+        #[pallet::call_index(1)]
+        #[pallet::weight(<T as Config>::WeightInfo::place_twap_order())]
+        #[transactional]
+        pub fn place_twap_order(
+            origin: OriginFor<T>,
+            asset_in: AssetIdOf<T>,
+            asset_out: AssetIdOf<T>,
+            #[pallet::compact] amount_in: BalanceOf<T>,
+            #[pallet::compact] min_amount_out: BalanceOf<T>,
+            #[pallet::compact] tip: BalanceOf<T>,
+            interval: T::BlockNumber,
+            slices: u32,
+            expiry: T::BlockNumber,
+        ) -> DispatchResultWithPostInfo {
+            let owner = ensure_signed(origin)?;
+            ensure!(
+                !interval.is_zero()
+                    && !slices.is_zero()
+                    && slices <= T::MaxTwapSlices::get()
+                    && amount_in >= BalanceOf::<T>::from(slices),
+                Error::<T>::InvalidTwapParameters
+            );
+
+            let order_id = Self::do_place_order(
+                owner.clone(),
+                asset_in,
+                asset_out,
+                amount_in,
+                min_amount_out,
+                tip,
+                OrderKind::Twap { interval, slices },
+                expiry,
+            )?;
+
+            Self::deposit_event(Event::<T>::TwapOrderPlaced(
+                order_id,
+                owner,
+                asset_in,
+                asset_out,
+                amount_in,
+                min_amount_out,
+                tip,
+                interval,
+                slices,
+                expiry,
+            ));
+
+            Ok(().into())
+        }
+
+        /// Cancel an open order and refund the escrowed assets to its owner
+        ///
+        /// Can only be called by the owner
+        ///
+        /// - `order_id`: the order id which will be cancelled
+        #[pallet::call_index(2)]
+        #[pallet::weight(<T as Config>::WeightInfo::cancel_order())]
+        #[transactional]
+        pub fn cancel_order(origin: OriginFor<T>, order_id: OrderId) -> DispatchResultWithPostInfo {
+            let owner = ensure_signed(origin)?;
+
+            let order = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderDoesNotExist)?;
+            ensure!(order.owner == owner, Error::<T>::NotTheOwner);
+
+            let refunded_amount = Self::do_refund_order(order_id, &order)?;
+            Self::deposit_event(Event::<T>::OrderCancelled(order_id, owner, refunded_amount));
+
+            Ok(().into())
+        }
+
+        /// Execute an order, or its next slice, whose price is met
+        ///
+        /// Can be called by anyone, the caller receives the tip of the execution
+        ///
+        /// - `order_id`: the order id which will be executed
+        #[pallet::call_index(3)]
+        #[pallet::weight(Pallet::<T>::execute_order_weight())]
+        #[transactional]
+        pub fn execute_order(
+            origin: OriginFor<T>,
+            order_id: OrderId,
+        ) -> DispatchResultWithPostInfo {
+            let keeper = ensure_signed(origin)?;
+            Self::do_execute_order(order_id, Some(keeper))?;
+            Ok(().into())
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    pub fn account_id() -> AccountOf<T> {
+        <T as Config>::PalletId::get().into_account_truncating()
+    }
+
+    /// The weight of executing an order, the route discovery is weighed for the max
+    /// count of pools the router searches
+    pub fn execute_order_weight() -> Weight {
+        <T as Config>::WeightInfo::execute_order().saturating_add(
+            <T as pallet_router::Config>::AMMRouterWeightInfo::get_best_route(
+                <T as pallet_router::Config>::MaxRoutePools::get(),
+            ),
+        )
+    }
+
+    /// Returns the routes splitting the sale of `amount_in` of `asset_in` for the best
+    /// output through the router, with the total amount out.
+    #[allow(clippy::type_complexity)]
+    pub fn get_best_quote(
+        amount_in: BalanceOf<T>,
+        asset_in: AssetIdOf<T>,
+        asset_out: AssetIdOf<T>,
+    ) -> Result<(Vec<(Vec<AssetIdOf<T>>, BalanceOf<T>)>, BalanceOf<T>), DispatchError> {
+        pallet_router::Pallet::<T>::get_best_split_route(amount_in, asset_in, asset_out, false)
+            .map_err(|_| Error::<T>::NoAvailableQuote.into())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[require_transactional]
+    fn do_place_order(
+        owner: AccountOf<T>,
+        asset_in: AssetIdOf<T>,
+        asset_out: AssetIdOf<T>,
+        amount_in: BalanceOf<T>,
+        min_amount_out: BalanceOf<T>,
+        tip: BalanceOf<T>,
+        kind: OrderKind<T::BlockNumber>,
+        expiry: T::BlockNumber,
+    ) -> Result<OrderId, DispatchError> {
+        ensure!(!amount_in.is_zero(), Error::<T>::ZeroAmount);
+        ensure!(asset_in != asset_out, Error::<T>::IdenticalAssets);
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(expiry > now, Error::<T>::InvalidExpiry);
+
+        let order = Order::<T>::new(
+            owner.clone(),
+            asset_in,
+            asset_out,
+            amount_in,
+            min_amount_out,
+            tip,
+            kind,
+            now,
+            expiry,
+        );
+        let escrow_amount = amount_in
+            .checked_add(order.tips_left()?)
+            .ok_or(ArithmeticError::Overflow)?;
+        T::Assets::transfer(
+            asset_in,
+            &owner,
+            &Self::account_id(),
+            escrow_amount,
+            asset_in == T::GetNativeCurrencyId::get(),
+        )?;
+
+        let order_id = NextOrderId::<T>::try_mutate(|id| -> Result<OrderId, DispatchError> {
+            let order_id = *id;
+            *id = id
+                .checked_add(One::one())
+                .ok_or(ArithmeticError::Overflow)?;
+            Ok(order_id)
+        })?;
+        Orders::<T>::insert(order_id, order);
+
+        Ok(order_id)
+    }
+
+    /// Sells the next slice of an order, the tip of the slice goes to the keeper or
+    /// back to the owner when executed in `on_idle`
+    #[transactional]
+    pub fn do_execute_order(order_id: OrderId, keeper: Option<AccountOf<T>>) -> DispatchResult {
+        let mut order = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderDoesNotExist)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(now <= order.expiry, Error::<T>::OrderExpired);
+        ensure!(now >= order.next_execution, Error::<T>::OrderNotReady);
+
+        let amount_in = order.next_slice_amount();
+        let min_amount_out = order.min_amount_out_for(amount_in)?;
+        let (routes, quoted_amount_out) =
+            Self::get_best_quote(amount_in, order.asset_in, order.asset_out)?;
+        ensure!(quoted_amount_out >= min_amount_out, Error::<T>::PriceNotMet);
+
+        let account_id = Self::account_id();
+        let amount_out = pallet_router::Pallet::<T>::do_swap_exact_tokens_for_tokens_split(
+            &account_id,
+            &routes,
+        )?;
+        ensure!(amount_out >= min_amount_out, Error::<T>::PriceNotMet);
+
+        T::Assets::transfer(
+            order.asset_out,
+            &account_id,
+            &order.owner,
+            amount_out,
+            false,
+        )?;
+        if !order.tip.is_zero() {
+            T::Assets::transfer(
+                order.asset_in,
+                &account_id,
+                keeper.as_ref().unwrap_or(&order.owner),
+                order.tip,
+                false,
+            )?;
+        }
+
+        order.remaining_amount_in = order
+            .remaining_amount_in
+            .checked_sub(amount_in)
+            .ok_or(ArithmeticError::Underflow)?;
+        order.filled_amount_out = order
+            .filled_amount_out
+            .checked_add(amount_out)
+            .ok_or(ArithmeticError::Overflow)?;
+        order.slices_left = order.slices_left.saturating_sub(1);
+        if let OrderKind::Twap { interval, .. } = order.kind {
+            order.next_execution = now.saturating_add(interval);
+        }
+
+        log::trace!(
+            target: "orders::do_execute_order",
+            "order_id: {:?}, keeper: {:?}, routes: {:?}, amount_in: {:?}, amount_out: {:?}",
+            order_id,
+            keeper,
+            routes,
+            amount_in,
+            amount_out,
+        );
+        Self::deposit_event(Event::<T>::OrderExecuted(
+            order_id, keeper, amount_in, amount_out,
+        ));
+
+        if order.is_filled() {
+            Orders::<T>::remove(order_id);
+            Self::deposit_event(Event::<T>::OrderFilled(
+                order_id,
+                order.owner,
+                order.amount_in,
+                order.filled_amount_out,
+            ));
+        } else {
+            Orders::<T>::insert(order_id, order);
+        }
+
+        Ok(())
+    }
+
+    /// Refunds what's left of an order to its owner and removes it
+    #[require_transactional]
+    fn do_refund_order(order_id: OrderId, order: &Order<T>) -> Result<BalanceOf<T>, DispatchError> {
+        let refunded_amount = order
+            .remaining_amount_in
+            .checked_add(order.tips_left()?)
+            .ok_or(ArithmeticError::Overflow)?;
+        T::Assets::transfer(
+            order.asset_in,
+            &Self::account_id(),
+            &order.owner,
+            refunded_amount,
+            false,
+        )?;
+        Orders::<T>::remove(order_id);
+
+        Ok(refunded_amount)
+    }
+
+    /// Expires or executes an order from `on_idle`, orders whose price is not met
+    /// are left for the next blocks
+    fn try_match_order(order_id: OrderId, order: Order<T>, now: T::BlockNumber) {
+        if now > order.expiry {
+            let _ = with_transaction(|| match Self::do_refund_order(order_id, &order) {
+                Ok(refunded_amount) => {
+                    Self::deposit_event(Event::<T>::OrderExpired(
+                        order_id,
+                        order.owner.clone(),
+                        refunded_amount,
+                    ));
+                    TransactionOutcome::Commit(Ok(()))
+                }
+                Err(err) => TransactionOutcome::Rollback(Err(err)),
+            });
+        } else if now >= order.next_execution {
+            if let Err(err) = Self::do_execute_order(order_id, None) {
+                log::trace!(
+                    target: "orders::try_match_order",
+                    "order_id: {:?}, err: {:?}",
+                    order_id,
+                    err,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,403 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mocks for the orders module.
+
+use super::*;
+use crate as pallet_orders;
+
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{AsEnsureOriginWithArg, Everything, Hooks, SortedMembers},
+    weights::Weight,
+    PalletId,
+};
+use frame_system::{EnsureRoot, EnsureSigned, EnsureSignedBy};
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup};
+
+pub use primitives::{tokens, Amount, Balance, CurrencyId, Rate, Ratio};
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CHARLIE: AccountId = 3;
+pub const DAVE: AccountId = 4;
+pub const PROTOCOL_FEE_RECEIVER: AccountId = 99;
+
+pub const DOT: CurrencyId = tokens::DOT;
+pub const SDOT: CurrencyId = tokens::SDOT;
+pub const USDT: CurrencyId = tokens::USDT;
+pub const KSM: CurrencyId = tokens::KSM;
+pub const SAMPLE_LP_TOKEN: CurrencyId = 42;
+pub const SAMPLE_LP_TOKEN_2: CurrencyId = 43;
+pub const SAMPLE_LP_TOKEN_3: CurrencyId = 44;
+pub const SAMPLE_STABLE_LP_TOKEN: CurrencyId = 45;
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+    type RuntimeOrigin = RuntimeOrigin;
+    type Index = u64;
+    type BlockNumber = BlockNumber;
+    type RuntimeCall = RuntimeCall;
+    type Hash = H256;
+    type Hashing = ::sp_runtime::traits::BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = BlockHashCount;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type DbWeight = ();
+    type BaseCallFilter = Everything;
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+// pallet-balances configuration
+parameter_types! {
+    pub const ExistentialDeposit: Balance = 1;
+    pub const MaxLocks: u32 = 50;
+}
+
+impl pallet_balances::Config for Runtime {
+    type MaxLocks = MaxLocks;
+    type Balance = Balance;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+}
+
+// pallet-assets configuration
+parameter_types! {
+    pub const AssetDeposit: u64 = 1;
+    pub const ApprovalDeposit: u64 = 1;
+    pub const AssetAccountDeposit: u64 = 1;
+    pub const StringLimit: u32 = 50;
+    pub const MetadataDepositBase: u64 = 1;
+    pub const MetadataDepositPerByte: u64 = 1;
+}
+
+impl pallet_assets::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Balance = Balance;
+    type AssetId = CurrencyId;
+    type AssetIdParameter = codec::Compact<CurrencyId>;
+    type Currency = Balances;
+    type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+    type ForceOrigin = EnsureRoot<AccountId>;
+    type AssetDeposit = AssetDeposit;
+    type MetadataDepositBase = MetadataDepositBase;
+    type MetadataDepositPerByte = MetadataDepositPerByte;
+    type AssetAccountDeposit = AssetAccountDeposit;
+    type ApprovalDeposit = ApprovalDeposit;
+    type StringLimit = StringLimit;
+    type Freezer = ();
+    type Extra = ();
+    type WeightInfo = ();
+    type RemoveItemsLimit = frame_support::traits::ConstU32<1000>;
+    type CallbackHandle = ();
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = ();
+}
+
+// AMM instance initialization
+parameter_types! {
+    pub const AMMPalletId: PalletId = PalletId(*b"par/ammp");
+    // pub const DefaultLpFee: Ratio = Ratio::from_rational(25u32, 10000u32);        // 0.25%
+    // pub const DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);
+    pub  DefaultLpFee: Ratio = Ratio::from_rational(25u32, 10000u32);         // 0.3%
+    pub const MinimumLiquidity: u128 = 1_000u128;
+    pub const MaxTicksPerPool: u32 = 1_000;
+    pub const MinPositionLiquidity: u128 = 1_000_000u128;
+    pub const LockAccountId: AccountId = ALICE;
+
+}
+
+pub struct AliceCreatePoolOrigin;
+impl SortedMembers<AccountId> for AliceCreatePoolOrigin {
+    fn sorted_members() -> Vec<AccountId> {
+        vec![ALICE]
+    }
+}
+
+impl pallet_amm::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Assets = CurrencyAdapter;
+    type PalletId = AMMPalletId;
+    type LockAccountId = LockAccountId;
+    type AMMWeightInfo = ();
+    type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type ProtocolFeeUpdateOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type LpFee = DefaultLpFee;
+    type MinimumLiquidity = MinimumLiquidity;
+    type MaxLengthRoute = MaxLengthRoute;
+    type GetNativeCurrencyId = NativeCurrencyId;
+    type MaxTicksPerPool = MaxTicksPerPool;
+    type MinPositionLiquidity = MinPositionLiquidity;
+    type PoolsHook = AMMRoute;
+}
+
+parameter_types! {
+    pub const NativeCurrencyId: CurrencyId = 0;
+}
+
+impl pallet_currency_adapter::Config for Runtime {
+    type Assets = Assets;
+    type Balances = Balances;
+    type GetNativeCurrencyId = NativeCurrencyId;
+    type LockOrigin = EnsureRoot<AccountId>;
+}
+
+parameter_types! {
+    pub const MaxLengthRoute: u8 = 10;
+    pub const MaxRouteSplits: u32 = 4;
+    pub const MaxRoutePools: u32 = 100;
+    pub const RouterPalletId: PalletId = PalletId(*b"ammroute");
+}
+
+impl pallet_router::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type PalletId = RouterPalletId;
+    type AMM = DefaultAMM;
+    type StableSwap = StableSwap;
+    type AMMRouterWeightInfo = ();
+    type MaxLengthRoute = MaxLengthRoute;
+    type MaxRouteSplits = MaxRouteSplits;
+    type MaxRoutePools = MaxRoutePools;
+    type Assets = CurrencyAdapter;
+    type GetNativeCurrencyId = NativeCurrencyId;
+}
+
+parameter_types! {
+    pub const StableSwapPalletId: PalletId = PalletId(*b"par/sswp");
+    pub const NumTokens: u8 = 2;
+    pub const Precision: u128 = 100;
+    pub const AmplificationCoefficient: u8 = 85;
+    pub const MaxPoolAssets: u32 = 4;
+    pub const MinRampBlocks: u64 = 10;
+    pub DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);
+    pub const DefaultProtocolFeeReceiver: AccountId = PROTOCOL_FEE_RECEIVER;
+}
+
+pub struct LiquidStakingExchangeRateProvider;
+impl pallet_traits::ExchangeRateProvider<CurrencyId> for LiquidStakingExchangeRateProvider {
+    fn get_exchange_rate(_: &CurrencyId) -> Option<Rate> {
+        Some(Rate::one())
+    }
+}
+
+impl pallet_stableswap::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Assets = CurrencyAdapter;
+    type WeightInfo = ();
+    type PalletId = StableSwapPalletId;
+    type NumTokens = NumTokens;
+    type Precision = Precision;
+    type AmplificationCoefficient = AmplificationCoefficient;
+    type MaxPoolAssets = MaxPoolAssets;
+    type MinRampBlocks = MinRampBlocks;
+    type ProtocolFeeReceiver = DefaultProtocolFeeReceiver;
+    type LpFee = DefaultLpFee;
+    type LockAccountId = LockAccountId;
+    type ProtocolFee = DefaultProtocolFee;
+    type MinimumLiquidity = MinimumLiquidity;
+    type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type LiquidStakingExchangeRateProvider = LiquidStakingExchangeRateProvider;
+    type PoolsHook = AMMRoute;
+}
+
+parameter_types! {
+    pub const OrdersPalletId: PalletId = PalletId(*b"par/ordr");
+    pub const MaxOrdersPerBlock: u32 = 10;
+    pub const MaxTwapSlices: u32 = 10;
+}
+
+impl Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type PalletId = OrdersPalletId;
+    type MaxOrdersPerBlock = MaxOrdersPerBlock;
+    type MaxTwapSlices = MaxTwapSlices;
+    type WeightInfo = ();
+}
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+    pub enum Runtime where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Event<T>},
+        // AMM instances
+        DefaultAMM: pallet_amm::{Pallet, Call, Storage, Event<T>},
+        // AMM Route
+        AMMRoute: pallet_router::{Pallet, Call, Storage, Event<T>},
+        StableSwap: pallet_stableswap::{Pallet, Call, Storage, Event<T>},
+        Orders: pallet_orders::{Pallet, Call, Storage, Event<T>},
+        CurrencyAdapter: pallet_currency_adapter::{Pallet, Call},
+    }
+);
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Runtime>()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Runtime> {
+        balances: vec![(ALICE, 100_000_000), (BOB, 100_000_000)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| {
+        Assets::force_create(RuntimeOrigin::root(), tokens::DOT.into(), ALICE, true, 1).unwrap();
+        Assets::force_create(RuntimeOrigin::root(), tokens::SDOT.into(), ALICE, true, 1).unwrap();
+        Assets::force_create(RuntimeOrigin::root(), tokens::KSM.into(), ALICE, true, 1).unwrap();
+        Assets::force_create(RuntimeOrigin::root(), tokens::USDT.into(), ALICE, true, 1).unwrap();
+
+        // lp tokens
+        Assets::force_create(
+            RuntimeOrigin::root(),
+            SAMPLE_LP_TOKEN.into(),
+            ALICE,
+            true,
+            1,
+        )
+        .unwrap();
+        Assets::force_create(
+            RuntimeOrigin::root(),
+            SAMPLE_LP_TOKEN_2.into(),
+            ALICE,
+            true,
+            1,
+        )
+        .unwrap();
+        Assets::force_create(
+            RuntimeOrigin::root(),
+            SAMPLE_LP_TOKEN_3.into(),
+            ALICE,
+            true,
+            1,
+        )
+        .unwrap();
+        Assets::force_create(
+            RuntimeOrigin::root(),
+            SAMPLE_STABLE_LP_TOKEN.into(),
+            ALICE,
+            true,
+            1,
+        )
+        .unwrap();
+
+        Assets::mint(
+            RuntimeOrigin::signed(ALICE),
+            tokens::DOT.into(),
+            ALICE,
+            10_000,
+        )
+        .unwrap();
+        Assets::mint(
+            RuntimeOrigin::signed(ALICE),
+            tokens::SDOT.into(),
+            ALICE,
+            10_000,
+        )
+        .unwrap();
+        Assets::mint(
+            RuntimeOrigin::signed(ALICE),
+            tokens::KSM.into(),
+            ALICE,
+            10_000,
+        )
+        .unwrap();
+
+        Assets::mint(
+            RuntimeOrigin::signed(ALICE),
+            tokens::USDT.into(),
+            ALICE,
+            10_000,
+        )
+        .unwrap();
+
+        Assets::mint(
+            RuntimeOrigin::signed(ALICE),
+            tokens::DOT.into(),
+            BOB,
+            1_000_000,
+        )
+        .unwrap();
+
+        Assets::mint(
+            RuntimeOrigin::signed(ALICE),
+            tokens::DOT.into(),
+            DAVE,
+            1000_000_000,
+        )
+        .unwrap();
+        Assets::mint(
+            RuntimeOrigin::signed(ALICE),
+            tokens::KSM.into(),
+            DAVE,
+            1000_000_000,
+        )
+        .unwrap();
+        Assets::mint(
+            RuntimeOrigin::signed(ALICE),
+            tokens::SDOT.into(),
+            DAVE,
+            1000_000_000,
+        )
+        .unwrap();
+        Assets::mint(
+            RuntimeOrigin::signed(ALICE),
+            tokens::USDT.into(),
+            DAVE,
+            1000_000_000,
+        )
+        .unwrap();
+
+        System::set_block_number(1);
+    });
+
+    ext
+}
+
+pub fn run_to_block(n: BlockNumber) {
+    while System::block_number() < n {
+        System::set_block_number(System::block_number() + 1);
+        Orders::on_idle(System::block_number(), Weight::MAX);
+    }
+}
@@ -0,0 +1,380 @@
+// Copyright 2021-2022 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Unit tests for the orders pallet.
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Orders, *};
+
+fn create_pools() {
+    assert_ok!(DefaultAMM::create_pool(
+        RuntimeOrigin::signed(ALICE),
+        (DOT, SDOT),
+        (100_000_000, 100_000_000),
+        DAVE,
+        SAMPLE_LP_TOKEN,
+        DefaultLpFee::get(),
+        None
+    ));
+}
+
+fn balance(asset_id: CurrencyId, who: &AccountId) -> Balance {
+    <Runtime as pallet_router::Config>::Assets::balance(asset_id, who)
+}
+
+#[test]
+fn place_limit_order_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Orders::place_limit_order(
+            RuntimeOrigin::signed(BOB),
+            DOT,
+            SDOT,
+            1_000,
+            990,
+            10,
+            100
+        ));
+
+        // amount in and tip are escrowed
+        assert_eq!(balance(DOT, &BOB), 1_000_000 - 1_010);
+        assert_eq!(balance(DOT, &Orders::account_id()), 1_010);
+
+        let order = Orders::orders(0).unwrap();
+        assert_eq!(order.owner, BOB);
+        assert_eq!(order.remaining_amount_in, 1_000);
+        assert_eq!(order.slices_left, 1);
+        assert_eq!(order.next_execution, 1);
+        assert_eq!(Orders::next_order(), 1);
+
+        System::assert_last_event(RuntimeEvent::Orders(crate::Event::LimitOrderPlaced(
+            0, BOB, DOT, SDOT, 1_000, 990, 10, 100,
+        )));
+    });
+}
+
+#[test]
+fn place_order_with_invalid_parameters_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Orders::place_limit_order(RuntimeOrigin::signed(BOB), DOT, SDOT, 0, 0, 0, 100),
+            Error::<Runtime>::ZeroAmount
+        );
+        assert_noop!(
+            Orders::place_limit_order(RuntimeOrigin::signed(BOB), DOT, DOT, 1_000, 0, 0, 100),
+            Error::<Runtime>::IdenticalAssets
+        );
+        assert_noop!(
+            Orders::place_limit_order(RuntimeOrigin::signed(BOB), DOT, SDOT, 1_000, 0, 0, 1),
+            Error::<Runtime>::InvalidExpiry
+        );
+        assert_noop!(
+            Orders::place_twap_order(
+                RuntimeOrigin::signed(BOB),
+                DOT,
+                SDOT,
+                1_000,
+                0,
+                0,
+                0,
+                2,
+                100
+            ),
+            Error::<Runtime>::InvalidTwapParameters
+        );
+        assert_noop!(
+            Orders::place_twap_order(
+                RuntimeOrigin::signed(BOB),
+                DOT,
+                SDOT,
+                1_000,
+                0,
+                0,
+                1,
+                MaxTwapSlices::get() + 1,
+                100
+            ),
+            Error::<Runtime>::InvalidTwapParameters
+        );
+    });
+}
+
+#[test]
+fn execute_limit_order_should_work() {
+    new_test_ext().execute_with(|| {
+        create_pools();
+        assert_ok!(Orders::place_limit_order(
+            RuntimeOrigin::signed(BOB),
+            DOT,
+            SDOT,
+            1_000,
+            990,
+            10,
+            100
+        ));
+
+        let (routes, amount_out) = Orders::get_best_quote(1_000, DOT, SDOT).unwrap();
+        assert_eq!(routes, vec![(vec![DOT, SDOT], 1_000)]);
+
+        // anyone can execute the order and receives the tip
+        assert_ok!(Orders::execute_order(RuntimeOrigin::signed(CHARLIE), 0));
+
+        assert_eq!(balance(SDOT, &BOB), amount_out);
+        assert_eq!(balance(DOT, &CHARLIE), 10);
+        assert_eq!(balance(DOT, &Orders::account_id()), 0);
+        assert!(Orders::orders(0).is_none());
+
+        System::assert_has_event(RuntimeEvent::Orders(crate::Event::OrderExecuted(
+            0,
+            Some(CHARLIE),
+            1_000,
+            amount_out,
+        )));
+        System::assert_last_event(RuntimeEvent::Orders(crate::Event::OrderFilled(
+            0, BOB, 1_000, amount_out,
+        )));
+    });
+}
+
+#[test]
+fn execute_order_should_not_work_if_price_not_met() {
+    new_test_ext().execute_with(|| {
+        create_pools();
+        assert_ok!(Orders::place_limit_order(
+            RuntimeOrigin::signed(BOB),
+            DOT,
+            SDOT,
+            1_000,
+            1_000,
+            10,
+            100
+        ));
+
+        assert_noop!(
+            Orders::execute_order(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Runtime>::PriceNotMet
+        );
+
+        // on_idle leaves the order open
+        run_to_block(3);
+        assert_eq!(Orders::orders(0).unwrap().remaining_amount_in, 1_000);
+    });
+}
+
+#[test]
+fn on_idle_should_only_check_open_orders() {
+    new_test_ext().execute_with(|| {
+        create_pools();
+        for order_id in 0..12 {
+            assert_ok!(Orders::place_limit_order(
+                RuntimeOrigin::signed(BOB),
+                DOT,
+                SDOT,
+                1_000,
+                1_000,
+                10,
+                100
+            ));
+            if order_id != 5 {
+                assert_ok!(Orders::cancel_order(RuntimeOrigin::signed(BOB), order_id));
+            }
+        }
+
+        // the cancelled orders are skipped and the cursor is reset after the last order
+        assert_eq!(
+            Orders::on_idle(2, Weight::MAX),
+            <Runtime as frame_system::Config>::DbWeight::get()
+                .reads_writes(1, 1)
+                .saturating_add(Orders::execute_order_weight())
+        );
+        assert_eq!(Orders::matching_cursor(), None);
+        assert_eq!(Orders::orders(5).unwrap().remaining_amount_in, 1_000);
+    });
+}
+
+#[test]
+fn execute_order_without_quote_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Orders::place_limit_order(
+            RuntimeOrigin::signed(BOB),
+            DOT,
+            SDOT,
+            1_000,
+            990,
+            10,
+            100
+        ));
+
+        assert_noop!(
+            Orders::execute_order(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Runtime>::NoAvailableQuote
+        );
+    });
+}
+
+#[test]
+fn twap_order_should_execute_in_slices() {
+    new_test_ext().execute_with(|| {
+        create_pools();
+        assert_ok!(Orders::place_twap_order(
+            RuntimeOrigin::signed(BOB),
+            DOT,
+            SDOT,
+            3_000,
+            2_900,
+            10,
+            5,
+            3,
+            100
+        ));
+        assert_eq!(balance(DOT, &Orders::account_id()), 3_030);
+
+        // the first slice is executed by a keeper
+        assert_ok!(Orders::execute_order(RuntimeOrigin::signed(CHARLIE), 0));
+        let order = Orders::orders(0).unwrap();
+        assert_eq!(order.remaining_amount_in, 2_000);
+        assert_eq!(order.slices_left, 2);
+        assert_eq!(order.next_execution, 6);
+        assert_noop!(
+            Orders::execute_order(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Runtime>::OrderNotReady
+        );
+
+        // the next slices are executed in on_idle, their tips go back to the owner
+        run_to_block(6);
+        assert_eq!(Orders::orders(0).unwrap().slices_left, 1);
+        run_to_block(10);
+        assert_eq!(Orders::orders(0).unwrap().slices_left, 1);
+        run_to_block(11);
+        assert!(Orders::orders(0).is_none());
+
+        assert_eq!(balance(DOT, &CHARLIE), 10);
+        assert_eq!(balance(DOT, &BOB), 1_000_000 - 3_010);
+        assert_eq!(balance(DOT, &Orders::account_id()), 0);
+        assert!(balance(SDOT, &BOB) >= 2_900);
+    });
+}
+
+#[test]
+fn cancel_order_should_work() {
+    new_test_ext().execute_with(|| {
+        create_pools();
+        assert_ok!(Orders::place_twap_order(
+            RuntimeOrigin::signed(BOB),
+            DOT,
+            SDOT,
+            3_000,
+            2_900,
+            10,
+            5,
+            3,
+            100
+        ));
+        assert_ok!(Orders::execute_order(RuntimeOrigin::signed(CHARLIE), 0));
+
+        assert_noop!(
+            Orders::cancel_order(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Runtime>::NotTheOwner
+        );
+        assert_ok!(Orders::cancel_order(RuntimeOrigin::signed(BOB), 0));
+
+        // remaining amount and tips of the slices left are refunded
+        assert!(Orders::orders(0).is_none());
+        assert_eq!(balance(DOT, &BOB), 1_000_000 - 1_010);
+        assert_eq!(balance(DOT, &Orders::account_id()), 0);
+        System::assert_last_event(RuntimeEvent::Orders(crate::Event::OrderCancelled(
+            0, BOB, 2_020,
+        )));
+
+        assert_noop!(
+            Orders::cancel_order(RuntimeOrigin::signed(BOB), 0),
+            Error::<Runtime>::OrderDoesNotExist
+        );
+    });
+}
+
+#[test]
+fn expired_order_should_be_refunded() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Orders::place_limit_order(
+            RuntimeOrigin::signed(BOB),
+            DOT,
+            SDOT,
+            1_000,
+            990,
+            10,
+            5
+        ));
+
+        run_to_block(5);
+        assert!(Orders::orders(0).is_some());
+
+        run_to_block(6);
+        assert_noop!(
+            Orders::execute_order(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Runtime>::OrderDoesNotExist
+        );
+        assert_eq!(balance(DOT, &BOB), 1_000_000);
+        System::assert_last_event(RuntimeEvent::Orders(crate::Event::OrderExpired(
+            0, BOB, 1_010,
+        )));
+    });
+}
+
+#[test]
+fn order_should_use_the_best_quote() {
+    new_test_ext().execute_with(|| {
+        // the AMM pool is priced far below the stable swap pool
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, USDT),
+            (100_000_000, 50_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
+        ));
+        assert_ok!(StableSwap::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, USDT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_STABLE_LP_TOKEN
+        ));
+
+        // the stable swap pool is used by the router
+        let (_, amount_out) = Orders::get_best_quote(1_000, DOT, USDT).unwrap();
+        assert_eq!(
+            amount_out,
+            AMMRoute::get_amounts_out(1_000, &[DOT, USDT]).unwrap().0[1]
+        );
+        assert_eq!(
+            AMMRoute::get_amounts_out(1_000, &[DOT, USDT]).unwrap().1,
+            vec![true]
+        );
+        assert!(amount_out > 900);
+
+        assert_ok!(Orders::place_limit_order(
+            RuntimeOrigin::signed(BOB),
+            DOT,
+            USDT,
+            1_000,
+            900,
+            10,
+            100
+        ));
+        assert_ok!(Orders::execute_order(RuntimeOrigin::signed(CHARLIE), 0));
+        assert_eq!(balance(USDT, &BOB), amount_out);
+    });
+}
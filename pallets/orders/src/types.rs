@@ -0,0 +1,115 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::RuntimeDebug;
+use scale_info::TypeInfo;
+use sp_runtime::{
+    helpers_128bit::multiply_by_rational_with_rounding, traits::Zero, ArithmeticError,
+    DispatchError, Rounding,
+};
+
+use crate::{AccountOf, AssetIdOf, BalanceOf, Config};
+
+pub type OrderId = u64;
+
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum OrderKind<BlockNumber> {
+    // Swap the whole amount once the quoted output meets the limit price
+    Limit,
+    // Swap the amount in equal slices, one slice every `interval` blocks
+    Twap { interval: BlockNumber, slices: u32 },
+}
+
+#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(T))]
+#[codec(mel_bound())]
+pub struct Order<T: Config> {
+    // The owner of the order
+    pub owner: AccountOf<T>,
+    // The asset sold by the order
+    pub asset_in: AssetIdOf<T>,
+    // The asset bought by the order
+    pub asset_out: AssetIdOf<T>,
+    // The total amount of `asset_in` to sell
+    pub amount_in: BalanceOf<T>,
+    // The amount of `asset_in` still escrowed for trading
+    pub remaining_amount_in: BalanceOf<T>,
+    // The amount of `asset_out` received so far
+    pub filled_amount_out: BalanceOf<T>,
+    // The minimum amount of `asset_out` for the whole `amount_in`, slices are
+    // held to the same price
+    pub min_amount_out: BalanceOf<T>,
+    // The tip in `asset_in` paid to the keeper of each execution
+    pub tip: BalanceOf<T>,
+    // Limit or TWAP
+    pub kind: OrderKind<T::BlockNumber>,
+    // The executions left before the order is filled
+    pub slices_left: u32,
+    // The first block the next execution can happen at
+    pub next_execution: T::BlockNumber,
+    // The last block the order can be executed at
+    pub expiry: T::BlockNumber,
+}
+
+impl<T: Config> Order<T> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        owner: AccountOf<T>,
+        asset_in: AssetIdOf<T>,
+        asset_out: AssetIdOf<T>,
+        amount_in: BalanceOf<T>,
+        min_amount_out: BalanceOf<T>,
+        tip: BalanceOf<T>,
+        kind: OrderKind<T::BlockNumber>,
+        next_execution: T::BlockNumber,
+        expiry: T::BlockNumber,
+    ) -> Self {
+        let slices_left = match kind {
+            OrderKind::Limit => 1,
+            OrderKind::Twap { slices, .. } => slices,
+        };
+        Self {
+            owner,
+            asset_in,
+            asset_out,
+            amount_in,
+            remaining_amount_in: amount_in,
+            filled_amount_out: Zero::zero(),
+            min_amount_out,
+            tip,
+            kind,
+            slices_left,
+            next_execution,
+            expiry,
+        }
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.slices_left.is_zero()
+    }
+
+    // The amount of `asset_in` sold by the next execution, the last slice takes
+    // whatever rounding left behind
+    pub fn next_slice_amount(&self) -> BalanceOf<T> {
+        if self.slices_left <= 1 {
+            return self.remaining_amount_in;
+        }
+        self.remaining_amount_in / BalanceOf::<T>::from(self.slices_left)
+    }
+
+    // The minimum amount of `asset_out` for selling `amount` at the order price
+    pub fn min_amount_out_for(&self, amount: BalanceOf<T>) -> Result<BalanceOf<T>, DispatchError> {
+        multiply_by_rational_with_rounding(
+            self.min_amount_out,
+            amount,
+            self.amount_in,
+            Rounding::Up,
+        )
+        .ok_or_else(|| ArithmeticError::Overflow.into())
+    }
+
+    // The tips still escrowed for the executions left
+    pub fn tips_left(&self) -> Result<BalanceOf<T>, DispatchError> {
+        self.tip
+            .checked_mul(BalanceOf::<T>::from(self.slices_left))
+            .ok_or_else(|| ArithmeticError::Overflow.into())
+    }
+}
@@ -19,17 +19,32 @@
 //! This pallet provides the price from Oracle Module by implementing the
 //! `PriceFeeder` trait. In case of emergency, the price can be set directly
 //! by Oracle Collective.
+//!
+//! Oracle prices of an asset can be guarded by a max age and a max deviation
+//! between consecutive rounds. Stale prices are rejected, a deviating round trips
+//! the circuit breaker of the asset which rejects its prices until it is reset.
+//!
+//! Assets with on-chain liquidity only can be priced by the time weighted average
+//! price of their AMM pool against an asset with an oracle price, on its own or
+//! combined with the oracle feed of the asset.
+//!
+//! Farming vault tokens are priced by the liquidity token their vault deposits,
+//! scaled by the exchange rate of the vault token.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use frame_support::{
-    dispatch::DispatchClass, log, pallet_prelude::*, traits::fungibles::Inspect, transactional,
+    dispatch::DispatchClass,
+    log,
+    pallet_prelude::*,
+    traits::{fungibles::Inspect, UnixTime},
+    transactional,
 };
 use frame_system::pallet_prelude::*;
 use orml_traits::{DataFeeder, DataProvider, DataProviderExtended};
 use primitives::*;
 use sp_runtime::{
-    traits::{CheckedDiv, CheckedMul},
+    traits::{CheckedAdd, CheckedDiv, CheckedMul, SaturatedConversion, Saturating, Zero},
     FixedPointNumber, FixedU128,
 };
 use sp_std::vec::Vec;
@@ -45,6 +60,46 @@ mod tests;
 
 pub mod weights;
 
+/// Safety params of the oracle price of an asset
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct PriceGuard {
+    /// Oracle prices older than this many milliseconds are rejected
+    pub max_age: Moment,
+    /// The largest change between two oracle rounds before the circuit breaker trips
+    pub max_deviation: Ratio,
+}
+
+/// How the AMM TWAP of an asset is combined with its oracle price
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum TwapPriceMode {
+    /// The TWAP only
+    Twap,
+    /// The oracle price, the TWAP if the oracle price is missing or rejected
+    OracleOrTwap,
+    /// The mean of the oracle price and the TWAP, both are required
+    Mean,
+}
+
+/// AMM TWAP price source of an asset
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct TwapSource<BlockNumber> {
+    /// The other asset of the pool, it must have an oracle or emergency price
+    pub quote_asset: CurrencyId,
+    /// The minimum number of blocks the price is averaged over
+    pub window: BlockNumber,
+    pub mode: TwapPriceMode,
+}
+
+/// Cumulative pool price of an asset at a block
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct TwapObservation<BlockNumber> {
+    pub block_number: BlockNumber,
+    pub price_cumulative: u128,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
@@ -87,6 +142,13 @@ pub mod pallet {
         /// relay currency.
         type VaultTokenExchangeRateProvider: VaultTokenExchangeRateProvider<CurrencyId>;
 
+        /// Farming vault tokens, which are priced by the liquidity they deposit
+        type LPVaultTokenCurrenciesFilter: LPVaultTokenCurrenciesFilter<CurrencyId>;
+
+        /// The provider of the exchange rate between farming vault tokens and the
+        /// liquidity token they deposit
+        type LPVaultTokenExchangeRateProvider: LPVaultTokenExchangeRateProvider<CurrencyId>;
+
         /// The provider of Loans rate for vault_token
         type VaultLoansRateProvider: LoansMarketDataProvider<CurrencyId, BalanceOf<Self>>;
 
@@ -106,6 +168,17 @@ pub mod pallet {
         /// Decimal provider.
         type Decimal: DecimalProvider<CurrencyId>;
 
+        /// Unix time, used to reject stale oracle prices
+        type UnixTime: UnixTime;
+
+        /// The max number of assets with a price guard, all of them are checked every block
+        #[pallet::constant]
+        type MaxPriceGuards: Get<u32>;
+
+        /// The max number of assets with a TWAP source, all of them are observed every block
+        #[pallet::constant]
+        type MaxTwapSources: Get<u32>;
+
         /// Weight information
         type WeightInfo: WeightInfo;
     }
@@ -117,6 +190,32 @@ pub mod pallet {
         SetPrice(CurrencyId, Price),
         /// Reset emergency price. \[asset_id\]
         ResetPrice(CurrencyId),
+        /// Price guard of an asset has been updated. \[asset_id, guard\]
+        PriceGuardUpdated(CurrencyId, Option<PriceGuard>),
+        /// An oracle round deviated too much from the last accepted price.
+        /// \[asset_id, last_accepted_price, rejected_price\]
+        CircuitBreakerTripped(CurrencyId, Price, Price),
+        /// The circuit breaker of an asset has been reset. \[asset_id\]
+        CircuitBreakerReset(CurrencyId),
+        /// TWAP price source of an asset has been updated. \[asset_id, source\]
+        TwapSourceUpdated(CurrencyId, Option<TwapSource<T::BlockNumber>>),
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The max age and the max deviation of a price guard cannot be zero,
+        /// the max deviation cannot be over 100%
+        InvalidPriceGuard,
+        /// The circuit breaker of the asset is not tripped
+        CircuitBreakerNotTripped,
+        /// The window of a TWAP source cannot be zero and the quote asset must differ
+        InvalidTwapSource,
+        /// There is no AMM pool for the asset and the quote asset
+        TwapPoolNotFound,
+        /// The max number of price guards has been reached
+        TooManyPriceGuards,
+        /// The max number of TWAP sources has been reached
+        TooManyTwapSources,
     }
 
     /// Mapping from currency id to it's emergency price
@@ -125,6 +224,51 @@ pub mod pallet {
     pub type EmergencyPrice<T: Config> =
         StorageMap<_, Twox64Concat, CurrencyId, Price, OptionQuery>;
 
+    /// Mapping from currency id to the time it's emergency price was set
+    #[pallet::storage]
+    #[pallet::getter(fn emergency_price_timestamp)]
+    pub type EmergencyPriceTimestamp<T: Config> =
+        StorageMap<_, Twox64Concat, CurrencyId, Moment, ValueQuery>;
+
+    /// Safety params of the oracle prices, prices of assets without params are not checked
+    #[pallet::storage]
+    #[pallet::getter(fn price_guard)]
+    pub type PriceGuards<T: Config> =
+        CountedStorageMap<_, Twox64Concat, CurrencyId, PriceGuard, OptionQuery>;
+
+    /// The last oracle price accepted by the circuit breaker of each guarded asset
+    #[pallet::storage]
+    #[pallet::getter(fn last_accepted_price)]
+    pub type LastAcceptedPrice<T: Config> =
+        StorageMap<_, Twox64Concat, CurrencyId, PriceDetail, OptionQuery>;
+
+    /// The oracle price which tripped the circuit breaker of an asset
+    #[pallet::storage]
+    #[pallet::getter(fn tripped_price)]
+    pub type TrippedPrices<T: Config> =
+        StorageMap<_, Twox64Concat, CurrencyId, PriceDetail, OptionQuery>;
+
+    /// AMM TWAP price sources of the assets
+    #[pallet::storage]
+    #[pallet::getter(fn twap_source)]
+    pub type TwapSources<T: Config> =
+        CountedStorageMap<_, Twox64Concat, CurrencyId, TwapSource<T::BlockNumber>, OptionQuery>;
+
+    /// The previous and the latest cumulative price observations of each TWAP source,
+    /// the TWAP is taken since the previous one
+    #[pallet::storage]
+    #[pallet::getter(fn twap_observations)]
+    pub type TwapObservations<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        CurrencyId,
+        (
+            TwapObservation<T::BlockNumber>,
+            TwapObservation<T::BlockNumber>,
+        ),
+        OptionQuery,
+    >;
+
     /// Mapping from foreign vault token to our's vault token
     #[pallet::storage]
     #[pallet::getter(fn foreign_to_native_asset)]
@@ -135,6 +279,30 @@ pub mod pallet {
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_initialize(block_number: T::BlockNumber) -> Weight {
+            let mut guarded_count = 0u64;
+            for (asset_id, guard) in PriceGuards::<T>::iter() {
+                guarded_count += 1;
+                Self::check_circuit_breaker(asset_id, guard);
+            }
+
+            let mut twap_count = 0u64;
+            for (asset_id, source) in TwapSources::<T>::iter() {
+                twap_count += 1;
+                Self::update_twap_observations(asset_id, source, block_number);
+            }
+
+            // PriceGuards, TrippedPrices, LastAcceptedPrice and the oracle of each guarded asset
+            // TwapSources, TwapObservations and the pool of each TWAP source
+            T::DbWeight::get().reads_writes(
+                guarded_count * 4 + twap_count * 3,
+                guarded_count + twap_count,
+            )
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Set emergency price
@@ -179,11 +347,92 @@ pub mod pallet {
             ForeignToNativeAsset::<T>::insert(foreign_asset_id, asset_id);
             Ok(().into())
         }
+
+        /// Set the price guard of an asset, `None` stops checking its oracle prices
+        #[pallet::call_index(3)]
+        #[pallet::weight((<T as Config>::WeightInfo::set_price_guard(), DispatchClass::Operational))]
+        #[transactional]
+        pub fn set_price_guard(
+            origin: OriginFor<T>,
+            asset_id: CurrencyId,
+            guard: Option<PriceGuard>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            if let Some(guard) = guard {
+                ensure!(
+                    !guard.max_age.is_zero()
+                        && !guard.max_deviation.is_zero()
+                        && guard.max_deviation <= Ratio::from_percent(100),
+                    Error::<T>::InvalidPriceGuard
+                );
+                ensure!(
+                    PriceGuards::<T>::contains_key(asset_id)
+                        || PriceGuards::<T>::count() < T::MaxPriceGuards::get(),
+                    Error::<T>::TooManyPriceGuards
+                );
+                PriceGuards::<T>::insert(asset_id, guard);
+            } else {
+                PriceGuards::<T>::remove(asset_id);
+                LastAcceptedPrice::<T>::remove(asset_id);
+                TrippedPrices::<T>::remove(asset_id);
+            }
+            Self::deposit_event(Event::<T>::PriceGuardUpdated(asset_id, guard));
+            Ok(().into())
+        }
+
+        /// Reset the tripped circuit breaker of an asset, the current oracle price
+        /// becomes the last accepted price
+        #[pallet::call_index(4)]
+        #[pallet::weight((<T as Config>::WeightInfo::reset_circuit_breaker(), DispatchClass::Operational))]
+        #[transactional]
+        pub fn reset_circuit_breaker(
+            origin: OriginFor<T>,
+            asset_id: CurrencyId,
+        ) -> DispatchResultWithPostInfo {
+            T::FeederOrigin::ensure_origin(origin)?;
+            TrippedPrices::<T>::take(asset_id).ok_or(Error::<T>::CircuitBreakerNotTripped)?;
+            LastAcceptedPrice::<T>::set(asset_id, Self::get_oracle_price(&asset_id));
+            Self::deposit_event(Event::<T>::CircuitBreakerReset(asset_id));
+            Ok(().into())
+        }
+
+        /// Set the AMM TWAP price source of an asset, `None` prices it by the oracle only.
+        /// The TWAP is available once a full window has been observed.
+        #[pallet::call_index(5)]
+        #[pallet::weight((<T as Config>::WeightInfo::set_twap_source(), DispatchClass::Operational))]
+        #[transactional]
+        pub fn set_twap_source(
+            origin: OriginFor<T>,
+            asset_id: CurrencyId,
+            source: Option<TwapSource<T::BlockNumber>>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            TwapObservations::<T>::remove(asset_id);
+            if let Some(source) = source {
+                ensure!(
+                    !source.window.is_zero() && source.quote_asset != asset_id,
+                    Error::<T>::InvalidTwapSource
+                );
+                ensure!(
+                    TwapSources::<T>::contains_key(asset_id)
+                        || TwapSources::<T>::count() < T::MaxTwapSources::get(),
+                    Error::<T>::TooManyTwapSources
+                );
+                let observation = Self::twap_observation(asset_id, source.quote_asset)
+                    .ok_or(Error::<T>::TwapPoolNotFound)?;
+                TwapObservations::<T>::insert(asset_id, (observation, observation));
+                TwapSources::<T>::insert(asset_id, source);
+            } else {
+                TwapSources::<T>::remove(asset_id);
+            }
+            Self::deposit_event(Event::<T>::TwapSourceUpdated(asset_id, source));
+            Ok(().into())
+        }
     }
 }
 
 impl<T: Config> Pallet<T> {
-    // get emergency price, the timestamp is zero
+    // get emergency price, the timestamp is the time it was set
     fn get_emergency_price(asset_id: &CurrencyId) -> Option<PriceDetail> {
         Self::emergency_price(asset_id).and_then(|p| {
             let mantissa = Self::get_asset_mantissa(asset_id)?;
@@ -194,10 +443,160 @@ impl<T: Config> Pallet<T> {
                 mantissa
             );
             p.checked_div(&FixedU128::from_inner(mantissa))
-                .map(|price| (price, 0))
+                .map(|price| (price, Self::emergency_price_timestamp(asset_id)))
         })
     }
 
+    fn get_oracle_price(asset_id: &CurrencyId) -> Option<PriceDetail> {
+        let mantissa = Self::get_asset_mantissa(asset_id)?;
+        T::Source::get(&T::RelayCurrency::get())
+            .and_then(|base_price| Self::get_special_asset_price(*asset_id, base_price))
+            .or_else(|| T::Source::get(asset_id))
+            .and_then(|price| Self::normalize_detail_price(price, mantissa))
+    }
+
+    /// The oracle price is fresh, doesn't deviate from the last accepted price
+    /// and the circuit breaker of the asset is not tripped
+    fn is_price_valid(asset_id: &CurrencyId, (price, timestamp): PriceDetail) -> bool {
+        let guard = match Self::price_guard(asset_id) {
+            Some(guard) => guard,
+            None => return true,
+        };
+        let now = T::UnixTime::now().as_millis().saturated_into::<Moment>();
+        if TrippedPrices::<T>::contains_key(asset_id)
+            || now.saturating_sub(timestamp) > guard.max_age
+        {
+            return false;
+        }
+
+        Self::last_accepted_price(asset_id).map_or(true, |(last_price, _)| {
+            !Self::exceeds_deviation(price, last_price, guard.max_deviation)
+        })
+    }
+
+    /// Moves the last accepted price to a new oracle round or trips the
+    /// circuit breaker if the round deviates too much
+    fn check_circuit_breaker(asset_id: CurrencyId, guard: PriceGuard) {
+        if TrippedPrices::<T>::contains_key(asset_id) {
+            return;
+        }
+        let price = match Self::get_oracle_price(&asset_id) {
+            Some(price) => price,
+            None => return,
+        };
+        match Self::last_accepted_price(asset_id) {
+            Some(last) if last.1 == price.1 => {}
+            Some((last_price, _))
+                if Self::exceeds_deviation(price.0, last_price, guard.max_deviation) =>
+            {
+                TrippedPrices::<T>::insert(asset_id, price);
+                Self::deposit_event(Event::<T>::CircuitBreakerTripped(
+                    asset_id, last_price, price.0,
+                ));
+            }
+            _ => LastAcceptedPrice::<T>::insert(asset_id, price),
+        }
+    }
+
+    /// The cumulative pool price of `asset_id` in `quote_asset` at the current block,
+    /// extrapolated from the last update of the pool with its current reserves
+    fn twap_observation(
+        asset_id: CurrencyId,
+        quote_asset: CurrencyId,
+    ) -> Option<TwapObservation<T::BlockNumber>> {
+        let pool = T::AMM::get_pool_by_asset_pair((asset_id, quote_asset))?;
+        // pools are keyed by the larger asset id, `price_0` is the price of the base asset
+        let (price_cumulative_last, reserve_in, reserve_out) = if asset_id > quote_asset {
+            (
+                pool.price_0_cumulative_last,
+                pool.base_amount,
+                pool.quote_amount,
+            )
+        } else {
+            (
+                pool.price_1_cumulative_last,
+                pool.quote_amount,
+                pool.base_amount,
+            )
+        };
+        if reserve_in.is_zero() {
+            return None;
+        }
+
+        let block_number = frame_system::Pallet::<T>::block_number();
+        let time_elapsed: u128 = block_number
+            .saturating_sub(pool.block_timestamp_last)
+            .saturated_into();
+        let price_cumulative = Price::saturating_from_rational(reserve_out, reserve_in)
+            .checked_mul(&Price::saturating_from_integer(time_elapsed))
+            .and_then(|r| r.checked_add(&Price::from_inner(price_cumulative_last)))?
+            .into_inner();
+        Some(TwapObservation {
+            block_number,
+            price_cumulative,
+        })
+    }
+
+    /// Takes a new observation once the latest one is a window old
+    fn update_twap_observations(
+        asset_id: CurrencyId,
+        source: TwapSource<T::BlockNumber>,
+        block_number: T::BlockNumber,
+    ) {
+        let observation = match Self::twap_observation(asset_id, source.quote_asset) {
+            Some(observation) => observation,
+            None => return,
+        };
+        let observations = match Self::twap_observations(asset_id) {
+            Some((_, latest))
+                if block_number.saturating_sub(latest.block_number) >= source.window =>
+            {
+                (latest, observation)
+            }
+            Some(observations) => observations,
+            None => (observation, observation),
+        };
+        TwapObservations::<T>::insert(asset_id, observations);
+    }
+
+    /// The TWAP of an asset since the previous observation, in the price of the quote asset
+    fn get_twap_price(
+        asset_id: &CurrencyId,
+        source: TwapSource<T::BlockNumber>,
+    ) -> Option<PriceDetail> {
+        let (previous, _) = Self::twap_observations(asset_id)?;
+        let current = Self::twap_observation(*asset_id, source.quote_asset)?;
+        let time_elapsed = current.block_number.saturating_sub(previous.block_number);
+        if time_elapsed < source.window {
+            return None;
+        }
+
+        let time_elapsed: u128 = time_elapsed.saturated_into();
+        let twap = Price::from_inner(
+            current
+                .price_cumulative
+                .checked_sub(previous.price_cumulative)?,
+        )
+        .checked_div(&Price::saturating_from_integer(time_elapsed))?;
+        let (quote_price, timestamp) = Self::get_emergency_price(&source.quote_asset)
+            .or_else(|| Self::get_guarded_oracle_price(&source.quote_asset))?;
+        twap.checked_mul(&quote_price)
+            .map(|price| (price, timestamp))
+    }
+
+    fn get_guarded_oracle_price(asset_id: &CurrencyId) -> Option<PriceDetail> {
+        Self::get_oracle_price(asset_id).filter(|&price| Self::is_price_valid(asset_id, price))
+    }
+
+    fn exceeds_deviation(price: Price, last_price: Price, max_deviation: Ratio) -> bool {
+        let deviation = if price > last_price {
+            price.saturating_sub(last_price)
+        } else {
+            last_price.saturating_sub(price)
+        };
+        deviation > last_price.saturating_mul(max_deviation.into())
+    }
+
     fn get_asset_mantissa(asset_id: &CurrencyId) -> Option<u128> {
         10u128.checked_pow(T::Decimal::get_decimal(asset_id)?.into())
     }
@@ -263,6 +662,15 @@ impl<T: Config> Pallet<T> {
             .and_then(|rate| Self::scale_timestamped_price(base_price, rate))
     }
 
+    fn get_farming_vault_token_price(vault_token: &CurrencyId) -> Option<PriceDetail> {
+        let lp_asset = T::LPVaultTokenExchangeRateProvider::get_staking_asset(vault_token)?;
+        let rate = T::LPVaultTokenExchangeRateProvider::get_exchange_rate(vault_token)?;
+        let (lp_asset_price, timestamp) = <Self as PriceFeeder>::get_price(&lp_asset)?;
+        lp_asset_price
+            .checked_mul(&rate)
+            .map(|price| (price, timestamp))
+    }
+
     fn get_lp_vault_asset_price(
         asset_id: CurrencyId,
         base_price: TimeStampedPrice,
@@ -302,15 +710,34 @@ impl<T: Config> PriceFeeder for Pallet<T> {
     /// This particular price makes it easy to calculate the asset value in other pallets,
     /// because we don't have to consider decimal for each asset.
     ///
-    /// Timestamp is zero means the price is emergency price
+    /// Emergency prices carry the time they were set and bypass the price guard,
+    /// oracle prices rejected by the price guard of the asset are `None`.
+    /// Assets with a TWAP source are priced according to its mode, the TWAP carries
+    /// the timestamp of the quote asset price.
     fn get_price(asset_id: &CurrencyId) -> Option<PriceDetail> {
         // if emergency price exists, return it
         Self::get_emergency_price(asset_id).or_else(|| {
-            let mantissa = Self::get_asset_mantissa(asset_id)?;
-            T::Source::get(&T::RelayCurrency::get())
-                .and_then(|base_price| Self::get_special_asset_price(*asset_id, base_price))
-                .or_else(|| T::Source::get(asset_id))
-                .and_then(|price| Self::normalize_detail_price(price, mantissa))
+            if T::LPVaultTokenCurrenciesFilter::contains(asset_id) {
+                return Self::get_farming_vault_token_price(asset_id);
+            }
+            let source = match Self::twap_source(asset_id) {
+                Some(source) => source,
+                None => return Self::get_guarded_oracle_price(asset_id),
+            };
+            match source.mode {
+                TwapPriceMode::Twap => Self::get_twap_price(asset_id, source),
+                TwapPriceMode::OracleOrTwap => Self::get_guarded_oracle_price(asset_id)
+                    .or_else(|| Self::get_twap_price(asset_id, source)),
+                TwapPriceMode::Mean => {
+                    let (oracle_price, oracle_timestamp) =
+                        Self::get_guarded_oracle_price(asset_id)?;
+                    let (twap_price, twap_timestamp) = Self::get_twap_price(asset_id, source)?;
+                    oracle_price
+                        .checked_add(&twap_price)
+                        .and_then(|r| r.checked_div(&Price::saturating_from_integer(2u128)))
+                        .map(|price| (price, oracle_timestamp.min(twap_timestamp)))
+                }
+            }
         })
     }
 }
@@ -320,12 +747,17 @@ impl<T: Config> EmergencyPriceFeeder<CurrencyId, Price> for Pallet<T> {
     fn set_emergency_price(asset_id: CurrencyId, price: Price) {
         // set price direct
         EmergencyPrice::<T>::insert(asset_id, price);
+        EmergencyPriceTimestamp::<T>::insert(
+            asset_id,
+            T::UnixTime::now().as_millis().saturated_into::<Moment>(),
+        );
         <Pallet<T>>::deposit_event(Event::SetPrice(asset_id, price));
     }
 
     /// Reset emergency price
     fn reset_emergency_price(asset_id: CurrencyId) {
         EmergencyPrice::<T>::remove(asset_id);
+        EmergencyPriceTimestamp::<T>::remove(asset_id);
         <Pallet<T>>::deposit_event(Event::ResetPrice(asset_id));
     }
 }
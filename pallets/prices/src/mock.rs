@@ -24,8 +24,9 @@ use frame_support::{
 use frame_system::{EnsureRoot, EnsureSigned, EnsureSignedBy};
 use sp_core::H256;
 use sp_runtime::{testing::Header, traits::IdentityLookup, FixedPointNumber};
+use std::{cell::RefCell, collections::HashMap};
 
-pub use primitives::tokens::{CDOT_7_14, CKSM_20_27, DOT, KSM, LP_DOT_CDOT_7_14, SDOT, SKSM};
+pub use primitives::tokens::{CDOT_7_14, CKSM_20_27, DOT, KSM, LP_DOT_CDOT_7_14, PHA, SDOT, SKSM};
 
 pub type AccountId = u128;
 pub type BlockNumber = u64;
@@ -34,6 +35,8 @@ pub const CHARLIE: AccountId = 2;
 
 pub const PRICE_ONE: u128 = 1_000_000_000_000_000_000;
 
+pub const LP_VAULT_TOKEN: CurrencyId = 7_000;
+
 parameter_types! {
     pub const BlockHashCount: u64 = 250;
 }
@@ -65,37 +68,58 @@ impl frame_system::Config for Test {
     type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+parameter_types! {
+    pub const MinimumPeriod: u64 = 5;
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
+}
+
 pub type TimeStampedPrice = orml_oracle::TimestampedValue<Price, Moment>;
 pub struct MockDataProvider;
+
+impl MockDataProvider {
+    thread_local! {
+        pub static PRICES: RefCell<HashMap<CurrencyId, TimeStampedPrice>> = {
+            RefCell::new(
+                vec![(DOT, 100), (KSM, 500)]
+                    .into_iter()
+                    .map(|(asset_id, price)| {
+                        (
+                            asset_id,
+                            TimeStampedPrice {
+                                value: Price::saturating_from_integer(price),
+                                timestamp: 0,
+                            },
+                        )
+                    })
+                    .collect()
+            )
+        };
+    }
+
+    pub fn set_price(asset_id: CurrencyId, value: Price, timestamp: Moment) {
+        Self::PRICES.with(|prices| {
+            prices
+                .borrow_mut()
+                .insert(asset_id, TimeStampedPrice { value, timestamp });
+        });
+    }
+}
+
 impl DataProvider<CurrencyId, TimeStampedPrice> for MockDataProvider {
     fn get(asset_id: &CurrencyId) -> Option<TimeStampedPrice> {
-        match *asset_id {
-            DOT => Some(TimeStampedPrice {
-                value: Price::saturating_from_integer(100),
-                timestamp: 0,
-            }),
-            KSM => Some(TimeStampedPrice {
-                value: Price::saturating_from_integer(500),
-                timestamp: 0,
-            }),
-            _ => None,
-        }
+        Self::PRICES.with(|prices| prices.borrow().get(asset_id).cloned())
     }
 }
 
 impl DataProviderExtended<CurrencyId, TimeStampedPrice> for MockDataProvider {
     fn get_no_op(asset_id: &CurrencyId) -> Option<TimeStampedPrice> {
-        match *asset_id {
-            DOT => Some(TimeStampedPrice {
-                value: Price::saturating_from_integer(100),
-                timestamp: 0,
-            }),
-            KSM => Some(TimeStampedPrice {
-                value: Price::saturating_from_integer(500),
-                timestamp: 0,
-            }),
-            _ => None,
-        }
+        Self::PRICES.with(|prices| prices.borrow().get(asset_id).cloned())
     }
 
     fn get_all_values() -> Vec<(CurrencyId, Option<TimeStampedPrice>)> {
@@ -132,7 +156,7 @@ pub struct Decimal;
 impl DecimalProvider<CurrencyId> for Decimal {
     fn get_decimal(asset_id: &CurrencyId) -> Option<u8> {
         match *asset_id {
-            DOT | SDOT => Some(10),
+            DOT | SDOT | PHA => Some(10),
             KSM | SKSM => Some(12),
             CKSM_20_27 => Some(12),
             CDOT_7_14 => Some(10),
@@ -160,6 +184,24 @@ impl VaultTokenCurrenciesFilter<CurrencyId> for TokenCurrenciesFilter {
     }
 }
 
+pub struct LPVaultTokenFilter;
+impl LPVaultTokenCurrenciesFilter<CurrencyId> for LPVaultTokenFilter {
+    fn contains(asset_id: &CurrencyId) -> bool {
+        asset_id == &LP_VAULT_TOKEN
+    }
+}
+
+pub struct LPVaultTokenRateProvider;
+impl LPVaultTokenExchangeRateProvider<CurrencyId> for LPVaultTokenRateProvider {
+    fn get_exchange_rate(_: &CurrencyId) -> Option<Rate> {
+        Some(Rate::saturating_from_rational(150, 100))
+    }
+
+    fn get_staking_asset(_: &CurrencyId) -> Option<CurrencyId> {
+        Some(DOT)
+    }
+}
+
 pub struct VaultLoansRateProvider;
 impl LoansMarketDataProvider<CurrencyId, Balance> for VaultLoansRateProvider {
     fn get_full_interest_rate(_asset_id: CurrencyId) -> Option<Rate> {
@@ -179,6 +221,8 @@ impl LoansMarketDataProvider<CurrencyId, Balance> for VaultLoansRateProvider {
 parameter_types! {
     pub const RelayCurrency: CurrencyId = DOT;
     pub const NativeCurrencyId: CurrencyId = 1;
+    pub const MaxPriceGuards: u32 = 1;
+    pub const MaxTwapSources: u32 = 1;
 }
 
 impl pallet_currency_adapter::Config for Test {
@@ -246,6 +290,8 @@ parameter_types! {
     // pub const DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);
     pub  DefaultLpFee: Ratio = Ratio::from_rational(25u32, 10000u32);         // 0.3%
     pub const MinimumLiquidity: u128 = 1_000u128;
+    pub const MaxTicksPerPool: u32 = 1_000;
+    pub const MinPositionLiquidity: u128 = 1_000_000u128;
     pub const LockAccountId: AccountId = ALICE;
     pub const MaxLengthRoute: u8 = 10;
 }
@@ -269,6 +315,9 @@ impl pallet_amm::Config for Test {
     type MinimumLiquidity = MinimumLiquidity;
     type MaxLengthRoute = MaxLengthRoute;
     type GetNativeCurrencyId = NativeCurrencyId;
+    type MaxTicksPerPool = MaxTicksPerPool;
+    type MinPositionLiquidity = MinPositionLiquidity;
+    type PoolsHook = ();
 }
 
 impl crate::Config for Test {
@@ -280,9 +329,14 @@ impl crate::Config for Test {
     type LiquidStakingExchangeRateProvider = LiquidStakingExchangeRateProvider;
     type VaultTokenCurrenciesFilter = TokenCurrenciesFilter;
     type VaultTokenExchangeRateProvider = TokenExchangeRateProvider;
+    type LPVaultTokenCurrenciesFilter = LPVaultTokenFilter;
+    type LPVaultTokenExchangeRateProvider = LPVaultTokenRateProvider;
     type VaultLoansRateProvider = VaultLoansRateProvider;
     type RelayCurrency = RelayCurrency;
     type Decimal = Decimal;
+    type UnixTime = TimestampPallet;
+    type MaxPriceGuards = MaxPriceGuards;
+    type MaxTwapSources = MaxTwapSources;
     type AMM = DefaultAMM;
     type Assets = CurrencyAdapter;
     type WeightInfo = ();
@@ -298,6 +352,7 @@ construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        TimestampPallet: pallet_timestamp::{Pallet, Call, Storage, Inherent},
         Balances: pallet_balances::{Pallet, Call, Storage, Event<T>},
         Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
         DefaultAMM: pallet_amm::{Pallet, Call, Storage, Event<T>},
@@ -214,6 +214,22 @@ fn get_ctoken_price_work() {
     });
 }
 
+#[test]
+fn get_farming_vault_token_price_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            Prices::get_price(&LP_VAULT_TOKEN),
+            Some((Price::from_inner(15_000_000_000 * PRICE_ONE), 0))
+        );
+
+        MockDataProvider::set_price(DOT, Price::saturating_from_integer(200), 0);
+        assert_eq!(
+            Prices::get_price(&LP_VAULT_TOKEN),
+            Some((Price::from_inner(30_000_000_000 * PRICE_ONE), 0))
+        );
+    });
+}
+
 #[test]
 fn get_lp_ctoken_price_work() {
     new_test_ext().execute_with(|| {
@@ -223,6 +239,8 @@ fn get_lp_ctoken_price_work() {
             (300 * PRICE_ONE, 100 * PRICE_ONE), //3:1
             ALICE,
             LP_DOT_CDOT_7_14,
+            DefaultLpFee::get(),
+            None,
         )
         .unwrap();
 
@@ -244,6 +262,8 @@ fn get_lp_ctoken_price_with_different_exchange_rate_will_not_change() {
             (200 * PRICE_ONE, 100 * PRICE_ONE), //2:1
             ALICE,
             LP_DOT_CDOT_7_14,
+            DefaultLpFee::get(),
+            None,
         )
         .unwrap();
 
@@ -273,6 +293,8 @@ fn get_lp_ctoken_no_op_price_work() {
             (300 * PRICE_ONE, 100 * PRICE_ONE),
             ALICE,
             LP_DOT_CDOT_7_14,
+            DefaultLpFee::get(),
+            None,
         )
         .unwrap();
 
@@ -303,3 +325,345 @@ fn get_foreign_ctoken_price_work() {
         assert_eq!(Prices::get_price(&CDOT_7_14), Prices::get_price(&LC_DOT));
     });
 }
+
+const PRICE_GUARD: PriceGuard = PriceGuard {
+    max_age: 60_000,
+    max_deviation: Ratio::from_percent(10),
+};
+
+#[test]
+fn set_price_guard_call_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Prices::set_price_guard(RuntimeOrigin::signed(CHARLIE), DOT, Some(PRICE_GUARD)),
+            BadOrigin
+        );
+        assert_noop!(
+            Prices::set_price_guard(
+                RuntimeOrigin::signed(ALICE),
+                DOT,
+                Some(PriceGuard {
+                    max_age: 0,
+                    ..PRICE_GUARD
+                })
+            ),
+            Error::<Test>::InvalidPriceGuard
+        );
+        assert_noop!(
+            Prices::set_price_guard(
+                RuntimeOrigin::signed(ALICE),
+                DOT,
+                Some(PriceGuard {
+                    max_deviation: Ratio::zero(),
+                    ..PRICE_GUARD
+                })
+            ),
+            Error::<Test>::InvalidPriceGuard
+        );
+
+        assert_ok!(Prices::set_price_guard(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            Some(PRICE_GUARD)
+        ));
+        assert_eq!(Prices::price_guard(DOT), Some(PRICE_GUARD));
+        // Every guarded asset is checked on initialize
+        assert_noop!(
+            Prices::set_price_guard(RuntimeOrigin::signed(ALICE), KSM, Some(PRICE_GUARD)),
+            Error::<Test>::TooManyPriceGuards
+        );
+        assert_ok!(Prices::set_price_guard(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            Some(PriceGuard {
+                max_deviation: Ratio::from_percent(100),
+                ..PRICE_GUARD
+            })
+        ));
+
+        assert_ok!(Prices::set_price_guard(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            None
+        ));
+        assert_eq!(Prices::price_guard(DOT), None);
+    });
+}
+
+#[test]
+fn stale_price_is_rejected() {
+    new_test_ext().execute_with(|| {
+        TimestampPallet::set_timestamp(100_000);
+        assert!(Prices::get_price(&DOT).is_some());
+
+        assert_ok!(Prices::set_price_guard(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            Some(PRICE_GUARD)
+        ));
+        assert_eq!(Prices::get_price(&DOT), None);
+
+        MockDataProvider::set_price(DOT, Price::saturating_from_integer(100), 40_000);
+        assert_eq!(
+            Prices::get_price(&DOT),
+            Some((Price::from_inner(10_000_000_000 * PRICE_ONE), 40_000))
+        );
+    });
+}
+
+#[test]
+fn deviating_price_trips_circuit_breaker() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Prices::set_price_guard(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            Some(PRICE_GUARD)
+        ));
+        Prices::on_initialize(1);
+        let last_price = Price::from_inner(10_000_000_000 * PRICE_ONE);
+        assert_eq!(Prices::last_accepted_price(DOT), Some((last_price, 0)));
+
+        // 5% is within the max deviation
+        MockDataProvider::set_price(DOT, Price::saturating_from_integer(105), 6_000);
+        Prices::on_initialize(2);
+        let last_price = Price::from_inner(10_500_000_000 * PRICE_ONE);
+        assert_eq!(Prices::last_accepted_price(DOT), Some((last_price, 6_000)));
+
+        // 20% deviation is rejected before the breaker trips
+        MockDataProvider::set_price(DOT, Price::saturating_from_integer(84), 12_000);
+        assert_eq!(Prices::get_price(&DOT), None);
+        Prices::on_initialize(3);
+        let rejected_price = Price::from_inner(8_400_000_000 * PRICE_ONE);
+        assert_eq!(Prices::tripped_price(DOT), Some((rejected_price, 12_000)));
+        System::assert_last_event(RuntimeEvent::Prices(crate::Event::CircuitBreakerTripped(
+            DOT,
+            last_price,
+            rejected_price,
+        )));
+
+        // the breaker stays tripped even if the oracle recovers
+        MockDataProvider::set_price(DOT, Price::saturating_from_integer(105), 18_000);
+        Prices::on_initialize(4);
+        assert_eq!(Prices::get_price(&DOT), None);
+        assert_eq!(Prices::last_accepted_price(DOT), Some((last_price, 6_000)));
+    });
+}
+
+#[test]
+fn reset_circuit_breaker_call_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_noop!(
+            Prices::reset_circuit_breaker(RuntimeOrigin::signed(ALICE), DOT),
+            Error::<Test>::CircuitBreakerNotTripped
+        );
+        assert_ok!(Prices::set_price_guard(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            Some(PRICE_GUARD)
+        ));
+        Prices::on_initialize(1);
+        MockDataProvider::set_price(DOT, Price::saturating_from_integer(50), 6_000);
+        Prices::on_initialize(2);
+        assert!(Prices::tripped_price(DOT).is_some());
+
+        assert_noop!(
+            Prices::reset_circuit_breaker(RuntimeOrigin::signed(CHARLIE), DOT),
+            BadOrigin
+        );
+        assert_ok!(Prices::reset_circuit_breaker(
+            RuntimeOrigin::signed(ALICE),
+            DOT
+        ));
+        System::assert_last_event(RuntimeEvent::Prices(crate::Event::CircuitBreakerReset(DOT)));
+
+        let price = (Price::from_inner(5_000_000_000 * PRICE_ONE), 6_000);
+        assert_eq!(Prices::tripped_price(DOT), None);
+        assert_eq!(Prices::last_accepted_price(DOT), Some(price));
+        assert_eq!(Prices::get_price(&DOT), Some(price));
+    });
+}
+
+#[test]
+fn emergency_price_bypasses_price_guard() {
+    new_test_ext().execute_with(|| {
+        TimestampPallet::set_timestamp(100_000);
+        assert_ok!(Prices::set_price_guard(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            Some(PRICE_GUARD)
+        ));
+        assert_eq!(Prices::get_price(&DOT), None);
+
+        assert_ok!(Prices::set_price(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            Price::saturating_from_integer(90),
+        ));
+        assert_eq!(
+            Prices::get_price(&DOT),
+            Some((Price::from_inner(9_000_000_000 * PRICE_ONE), 100_000))
+        );
+
+        assert_ok!(Prices::reset_price(RuntimeOrigin::signed(ALICE), DOT));
+        assert_eq!(Prices::emergency_price_timestamp(DOT), 0);
+        assert_eq!(Prices::get_price(&DOT), None);
+    });
+}
+
+const LP_PHA_DOT: CurrencyId = 5000;
+const TWAP_SOURCE: TwapSource<u64> = TwapSource {
+    quote_asset: DOT,
+    window: 10,
+    mode: TwapPriceMode::Twap,
+};
+
+fn create_pha_dot_pool() {
+    Assets::force_create(RuntimeOrigin::root(), PHA.into(), ALICE, true, 1).unwrap();
+    Assets::force_create(RuntimeOrigin::root(), LP_PHA_DOT.into(), ALICE, true, 1).unwrap();
+    Assets::mint(
+        RuntimeOrigin::signed(ALICE),
+        PHA.into(),
+        ALICE,
+        1000 * PRICE_ONE,
+    )
+    .unwrap();
+    DefaultAMM::create_pool(
+        RuntimeOrigin::signed(ALICE),
+        (PHA, DOT),
+        (400 * PRICE_ONE, 100 * PRICE_ONE), //4:1
+        ALICE,
+        LP_PHA_DOT,
+        DefaultLpFee::get(),
+        None,
+    )
+    .unwrap();
+}
+
+#[test]
+fn set_twap_source_call_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pha_dot_pool();
+
+        assert_noop!(
+            Prices::set_twap_source(RuntimeOrigin::signed(CHARLIE), PHA, Some(TWAP_SOURCE)),
+            BadOrigin
+        );
+        assert_noop!(
+            Prices::set_twap_source(
+                RuntimeOrigin::signed(ALICE),
+                PHA,
+                Some(TwapSource {
+                    window: 0,
+                    ..TWAP_SOURCE
+                })
+            ),
+            Error::<Test>::InvalidTwapSource
+        );
+        assert_noop!(
+            Prices::set_twap_source(RuntimeOrigin::signed(ALICE), DOT, Some(TWAP_SOURCE)),
+            Error::<Test>::InvalidTwapSource
+        );
+        assert_noop!(
+            Prices::set_twap_source(
+                RuntimeOrigin::signed(ALICE),
+                PHA,
+                Some(TwapSource {
+                    quote_asset: KSM,
+                    ..TWAP_SOURCE
+                })
+            ),
+            Error::<Test>::TwapPoolNotFound
+        );
+
+        assert_ok!(Prices::set_twap_source(
+            RuntimeOrigin::signed(ALICE),
+            PHA,
+            Some(TWAP_SOURCE)
+        ));
+        assert_eq!(Prices::twap_source(PHA), Some(TWAP_SOURCE));
+        assert!(Prices::twap_observations(PHA).is_some());
+
+        assert_ok!(Prices::set_twap_source(
+            RuntimeOrigin::signed(ALICE),
+            PHA,
+            None
+        ));
+        assert_eq!(Prices::twap_source(PHA), None);
+        assert_eq!(Prices::twap_observations(PHA), None);
+    });
+}
+
+#[test]
+fn get_twap_price_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pha_dot_pool();
+        assert_eq!(Prices::get_price(&PHA), None);
+        assert_ok!(Prices::set_twap_source(
+            RuntimeOrigin::signed(ALICE),
+            PHA,
+            Some(TWAP_SOURCE)
+        ));
+
+        // a full window has not been observed yet
+        System::set_block_number(5);
+        Prices::on_initialize(5);
+        assert_eq!(Prices::get_price(&PHA), None);
+
+        // dot_price / 4
+        System::set_block_number(11);
+        Prices::on_initialize(11);
+        let twap_price = Price::from_inner(2_500_000_000 * PRICE_ONE);
+        assert_eq!(Prices::get_price(&PHA), Some((twap_price, 0)));
+
+        // a trade moving the spot price barely moves the TWAP
+        assert_ok!(DefaultAMM::swap(&ALICE, (DOT, PHA), 100 * PRICE_ONE));
+        System::set_block_number(12);
+        Prices::on_initialize(12);
+        let (price, _) = Prices::get_price(&PHA).unwrap();
+        assert!(price > twap_price);
+        assert!(price < twap_price.saturating_mul(Price::saturating_from_rational(13, 10)));
+    });
+}
+
+#[test]
+fn twap_price_modes_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pha_dot_pool();
+        assert_ok!(Prices::set_twap_source(
+            RuntimeOrigin::signed(ALICE),
+            PHA,
+            Some(TwapSource {
+                mode: TwapPriceMode::OracleOrTwap,
+                ..TWAP_SOURCE
+            })
+        ));
+        System::set_block_number(11);
+        Prices::on_initialize(11);
+        let twap_price = Price::from_inner(2_500_000_000 * PRICE_ONE);
+        assert_eq!(Prices::get_price(&PHA), Some((twap_price, 0)));
+
+        MockDataProvider::set_price(PHA, Price::saturating_from_integer(30), 0);
+        let oracle_price = Price::from_inner(3_000_000_000 * PRICE_ONE);
+        assert_eq!(Prices::get_price(&PHA), Some((oracle_price, 0)));
+
+        assert_ok!(Prices::set_twap_source(
+            RuntimeOrigin::signed(ALICE),
+            PHA,
+            Some(TwapSource {
+                mode: TwapPriceMode::Mean,
+                ..TWAP_SOURCE
+            })
+        ));
+        System::set_block_number(21);
+        Prices::on_initialize(21);
+        assert_eq!(
+            Prices::get_price(&PHA),
+            Some((Price::from_inner(2_750_000_000 * PRICE_ONE), 0))
+        );
+    });
+}
@@ -26,6 +26,9 @@ pub trait WeightInfo {
     fn set_price() -> Weight;
     fn reset_price() -> Weight;
     fn set_foreign_asset() -> Weight;
+    fn set_price_guard() -> Weight;
+    fn reset_circuit_breaker() -> Weight;
+    fn set_twap_source() -> Weight;
 }
 
 /// Weights for pallet_prices using the Substrate node and recommended hardware.
@@ -46,6 +49,21 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().reads(1 as u64))
             .saturating_add(T::DbWeight::get().writes(1 as u64))
     }
+    fn set_price_guard() -> Weight {
+        Weight::from_ref_time(24_120_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(0 as u64))
+            .saturating_add(T::DbWeight::get().writes(3 as u64))
+    }
+    fn reset_circuit_breaker() -> Weight {
+        Weight::from_ref_time(31_540_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(4 as u64))
+            .saturating_add(T::DbWeight::get().writes(2 as u64))
+    }
+    fn set_twap_source() -> Weight {
+        Weight::from_ref_time(33_870_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(2 as u64))
+            .saturating_add(T::DbWeight::get().writes(2 as u64))
+    }
 }
 
 // For backwards compatibility and tests
@@ -65,4 +83,19 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().reads(1 as u64))
             .saturating_add(RocksDbWeight::get().writes(1 as u64))
     }
+    fn set_price_guard() -> Weight {
+        Weight::from_ref_time(24_120_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(0 as u64))
+            .saturating_add(RocksDbWeight::get().writes(3 as u64))
+    }
+    fn reset_circuit_breaker() -> Weight {
+        Weight::from_ref_time(31_540_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(4 as u64))
+            .saturating_add(RocksDbWeight::get().writes(2 as u64))
+    }
+    fn set_twap_source() -> Weight {
+        Weight::from_ref_time(33_870_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(2 as u64))
+            .saturating_add(RocksDbWeight::get().writes(2 as u64))
+    }
 }
@@ -20,8 +20,21 @@ use sp_runtime::DispatchError;
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
+    #[api_version(2)]
     pub trait RouterApi<Balance> where
         Balance: Codec, {
+        /// Returns the best way to trade amount, split across routes. Each route comes
+        /// with the part of the amount traded along it, the total amount out (or in if
+        /// reversed) comes last.
+        fn get_best_route(
+            amount: Balance,
+            token_in: CurrencyId,
+            token_out: CurrencyId,
+            reversed: bool,
+        ) -> Result<(Vec<(Vec<CurrencyId>, Balance)>, Balance), DispatchError>;
+
+        /// Returns the best route to trade amount with the amount out (or in if reversed).
+        #[changed_in(2)]
         fn get_best_route(
             amount: Balance,
             token_in: CurrencyId,
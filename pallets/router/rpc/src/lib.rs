@@ -23,7 +23,7 @@ use jsonrpsee::{
     types::error::{CallError, ErrorCode, ErrorObject},
 };
 use primitives::CurrencyId;
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_rpc::number::NumberOrHex;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
@@ -42,7 +42,7 @@ where
         token_out: CurrencyId,
         reversed: bool,
         at: Option<BlockHash>,
-    ) -> RpcResult<(Vec<CurrencyId>, NumberOrHex)>;
+    ) -> RpcResult<(Vec<(Vec<CurrencyId>, NumberOrHex)>, NumberOrHex)>;
 }
 
 /// A struct that implements the [`RouteApi`].
@@ -92,20 +92,31 @@ where
         token_out: CurrencyId,
         reversed: bool,
         at: Option<<Block as BlockT>::Hash>,
-    ) -> RpcResult<(Vec<CurrencyId>, NumberOrHex)> {
+    ) -> RpcResult<(Vec<(Vec<CurrencyId>, NumberOrHex)>, NumberOrHex)> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or(self.client.info().best_hash));
-        let (route, amt) = api
-            .get_best_route(
-                &at,
-                decode_hex(amount, "balance")?,
-                token_in,
-                token_out,
-                reversed,
-            )
+        let amount: Balance = decode_hex(amount, "balance")?;
+        let api_version = api
+            .api_version::<dyn RouterRuntimeApi<Block, Balance>>(&at)
             .map_err(runtime_error_into_rpc_error)?
-            .map_err(smart_route_rpc_error)?;
-        Ok((route, try_into_rpc_balance(amt)?))
+            .unwrap_or_default();
+        let (split_route, amt) = if api_version < 2 {
+            // runtimes before splitting trades return a single route for the whole amount
+            #[allow(deprecated)]
+            let best_route = api
+                .get_best_route_before_version_2(&at, amount, token_in, token_out, reversed)
+                .map_err(runtime_error_into_rpc_error)?;
+            best_route.map(|(route, amt)| (vec![(route, amount)], amt))
+        } else {
+            api.get_best_route(&at, amount, token_in, token_out, reversed)
+                .map_err(runtime_error_into_rpc_error)?
+        }
+        .map_err(smart_route_rpc_error)?;
+        let split_route = split_route
+            .into_iter()
+            .map(|(route, amount)| Ok((route, try_into_rpc_balance(amount)?)))
+            .collect::<Result<Vec<_>, JsonRpseeError>>()?;
+        Ok((split_route, try_into_rpc_balance(amt)?))
     }
 }
 
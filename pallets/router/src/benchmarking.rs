@@ -28,6 +28,8 @@ const DOT: CurrencyId = tokens::DOT;
 const SDOT: CurrencyId = tokens::SDOT;
 const INITIAL_AMOUNT: u128 = 1_000_000_000_000_000;
 const ASSET_ID: u32 = 11;
+const ROUTE_ASSET_ID: u32 = 100_000;
+const ROUTE_LP_ASSET_ID: u32 = 200_000;
 
 fn assert_last_event<T: Config<I>, I: 'static>(generic_event: <T as Config<I>>::RuntimeEvent) {
     frame_system::Pallet::<T>::assert_last_event(generic_event.into());
@@ -81,7 +83,9 @@ fn initial_set_up<
             (DOT, SDOT),
             (100_000_000u128, 100_000_000u128),
             pool_creator.clone(),
-            ASSET_ID
+            ASSET_ID,
+            <T as pallet_amm::Config>::LpFee::get(),
+            None
         ));
     }
     assert_ok!(pallet_amm::Pallet::<T>::add_liquidity(
@@ -92,6 +96,70 @@ fn initial_set_up<
     ));
 }
 
+// Creates `count` pools, every two of them make a route from DOT to SDOT through a new asset
+fn create_route_pools<
+    T: Config<I> + pallet_assets::Config<AssetId = CurrencyId, Balance = Balance> + pallet_amm::Config,
+    I: 'static,
+>(
+    count: u32,
+) {
+    let pool_creator: T::AccountId = account("pool_creator", 1, 0);
+    let pool_creator_lookup = T::Lookup::unlookup(pool_creator.clone());
+
+    for asset_id in [DOT, SDOT] {
+        pallet_assets::Pallet::<T>::force_create(
+            SystemOrigin::Root.into(),
+            asset_id.into(),
+            pool_creator_lookup.clone(),
+            true,
+            One::one(),
+        )
+        .ok();
+        <T as crate::Config<I>>::Assets::mint_into(asset_id, &pool_creator, INITIAL_AMOUNT).ok();
+    }
+
+    for i in 0..count {
+        let route_asset = ROUTE_ASSET_ID + i / 2;
+        if i % 2 == 0 {
+            assert_ok!(pallet_assets::Pallet::<T>::force_create(
+                SystemOrigin::Root.into(),
+                route_asset.into(),
+                pool_creator_lookup.clone(),
+                true,
+                One::one(),
+            ));
+            assert_ok!(<T as crate::Config<I>>::Assets::mint_into(
+                route_asset,
+                &pool_creator,
+                INITIAL_AMOUNT
+            ));
+        }
+        assert_ok!(pallet_assets::Pallet::<T>::force_create(
+            SystemOrigin::Root.into(),
+            (ROUTE_LP_ASSET_ID + i).into(),
+            pool_creator_lookup.clone(),
+            true,
+            One::one(),
+        ));
+
+        let pair = if i % 2 == 0 {
+            (DOT, route_asset)
+        } else {
+            (route_asset, SDOT)
+        };
+        assert_ok!(pallet_amm::Pallet::<T>::create_pool(
+            T::CreatePoolOrigin::try_successful_origin()
+                .expect("No origin exists which can satisfy the guard"),
+            pair,
+            (100_000_000u128, 100_000_000u128),
+            pool_creator.clone(),
+            ROUTE_LP_ASSET_ID + i,
+            <T as pallet_amm::Config>::LpFee::get(),
+            None
+        ));
+    }
+}
+
 benchmarks_instance_pallet! {
     where_clause {
         where
@@ -134,6 +202,18 @@ benchmarks_instance_pallet! {
         assert_eq!(amount_in, expected);
         assert_last_event::<T, I>(Event::Traded(caller, expected, routes, amount_out).into());
     }
+
+    get_best_route {
+        let p in 2 .. <T as Config<I>>::MaxRoutePools::get();
+        create_route_pools::<T, I>(p);
+        let amount_in = 1_000_000u128;
+    }: {
+        assert_ok!(AMMRoute::<T, I>::get_best_split_route(amount_in, DOT, SDOT, false));
+    }
+    verify {
+        let (routes, _) = AMMRoute::<T, I>::get_best_split_route(amount_in, DOT, SDOT, false).unwrap();
+        assert!(!routes.is_empty());
+    }
 }
 
 impl_benchmark_test_suite!(AMMRoute, crate::mock::new_test_ext(), crate::mock::Runtime,);
@@ -0,0 +1,57 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+/// A non directed graph of the assets we are routing between, two assets are
+/// adjacent when a pool trades them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolGraph<AssetId: Ord> {
+    adjacents: BTreeMap<AssetId, Vec<AssetId>>,
+}
+
+impl<AssetId: Ord + Copy> PoolGraph<AssetId> {
+    /// Builds the graph from pool asset pairs, a pair listed by several pools is a
+    /// single edge
+    pub fn new(pools: impl IntoIterator<Item = (AssetId, AssetId)>) -> Self {
+        let mut graph = Self {
+            adjacents: BTreeMap::new(),
+        };
+        pools.into_iter().for_each(|(a, b)| {
+            graph.add_edge(a, b);
+            graph.add_edge(b, a);
+        });
+        graph
+    }
+
+    fn add_edge(&mut self, from: AssetId, to: AssetId) {
+        let adjacents = self.adjacents.entry(from).or_insert_with(Vec::new);
+        if !adjacents.contains(&to) {
+            adjacents.push(to);
+        }
+    }
+
+    /// Whether a pool trades the asset
+    pub fn contains(&self, asset: &AssetId) -> bool {
+        self.adjacents.contains_key(asset)
+    }
+
+    /// The assets a pool trades the asset against
+    pub fn adjacents(&self, asset: &AssetId) -> &[AssetId] {
+        self.adjacents
+            .get(asset)
+            .map(|adjacents| adjacents.as_slice())
+            .unwrap_or_default()
+    }
+}
@@ -21,6 +21,8 @@
 pub use pallet::*;
 
 mod benchmarking;
+mod graph;
+pub mod migrations;
 
 #[cfg(test)]
 mod mock;
@@ -28,26 +30,46 @@ mod mock;
 mod tests;
 
 pub mod weights;
+pub use graph::PoolGraph;
 pub use weights::WeightInfo;
 
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// Utility type for managing upgrades/migrations.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum Versions {
+    V1,
+    V2,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
     use frame_support::{
         ensure, log,
-        pallet_prelude::{DispatchResult, DispatchResultWithPostInfo},
+        pallet_prelude::{DispatchResult, DispatchResultWithPostInfo, StorageValue, ValueQuery},
         require_transactional,
         traits::{
             fungibles::{Inspect, Mutate, Transfer},
             Get, IsType,
         },
-        transactional, BoundedVec, PalletId,
+        transactional, BoundedBTreeMap, BoundedVec, PalletId,
     };
     use frame_system::{ensure_signed, pallet_prelude::OriginFor};
-    use pallet_traits::AMM;
+    use pallet_traits::{PoolsHook, StableSwap, AMM};
     use primitives::{Balance, CurrencyId};
-    use sp_runtime::{traits::Zero, DispatchError};
-    use sp_std::{cmp::Reverse, collections::btree_map::BTreeMap, vec::Vec};
+    use sp_runtime::{traits::Zero, ArithmeticError, DispatchError};
+    use sp_std::{
+        cmp::Reverse,
+        collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+        vec,
+        vec::Vec,
+    };
+
+    /// The count of equal parts a trade is cut into when splitting it across routes
+    const SPLIT_STEPS: u128 = 20;
 
     pub type Route<T, I> = BoundedVec<
         (
@@ -77,6 +99,9 @@ pub mod pallet {
         /// Specify all the AMMs we are routing between
         type AMM: AMM<AccountIdOf<Self>, AssetIdOf<Self, I>, BalanceOf<Self, I>, Self::BlockNumber>;
 
+        /// Specify the stable swap pools we are routing between
+        type StableSwap: StableSwap<AccountIdOf<Self>, AssetIdOf<Self, I>, BalanceOf<Self, I>>;
+
         /// Weight information for extrinsics in this pallet.
         type AMMRouterWeightInfo: WeightInfo;
 
@@ -84,6 +109,16 @@ pub mod pallet {
         #[pallet::constant]
         type MaxLengthRoute: Get<u32>;
 
+        /// How many routes a trade can be split across at most, route discovery
+        /// also keeps this many paths to each asset at most
+        #[pallet::constant]
+        type MaxRouteSplits: Get<u32>;
+
+        /// How many asset pairs the AMM and the stable swap can trade at most, pool
+        /// creation fails beyond it
+        #[pallet::constant]
+        type MaxRoutePools: Get<u32>;
+
         /// The asset id for native currency.
         #[pallet::constant]
         type GetNativeCurrencyId: Get<AssetIdOf<Self, I>>;
@@ -118,6 +153,10 @@ pub mod pallet {
         TokenDoesNotExists,
         /// Route between tokens is not possible
         NoPossibleRoute,
+        /// Exceed the max count of routes a trade can be split across
+        ExceedMaxRouteSplits,
+        /// Split routes don't share the same input and output tokens
+        MismatchedSplitRoutes,
     }
 
     #[pallet::event]
@@ -133,6 +172,27 @@ pub mod pallet {
         ),
     }
 
+    /// The asset pairs of the pools we are routing between, with how many of the AMM
+    /// and the stable swap trade each pair. Kept by their pool hooks.
+    #[pallet::storage]
+    #[pallet::getter(fn route_pools)]
+    pub type RoutePools<T: Config<I>, I: 'static = ()> = StorageValue<
+        _,
+        BoundedBTreeMap<(AssetIdOf<T, I>, AssetIdOf<T, I>), u32, T::MaxRoutePools>,
+        ValueQuery,
+    >;
+
+    /// DefaultVersion is using for initialize the StorageVersion
+    #[pallet::type_value]
+    pub(super) fn DefaultVersion<T: Config<I>, I: 'static>() -> Versions {
+        Versions::V1
+    }
+
+    /// Storage version of the pallet.
+    #[pallet::storage]
+    pub(crate) type StorageVersion<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, Versions, ValueQuery, DefaultVersion<T, I>>;
+
     impl<T: Config<I>, I: 'static> Pallet<T, I> {
         /// Check that routes are unique and that the length > 0 and < MaxLengthRoute
         #[require_transactional]
@@ -156,77 +216,139 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Returns a sorted list of all routes and their output amounts from a
-        /// start token to end token by traversing a graph.
+        /// Returns the graph of the pools we are routing between
+        pub fn pool_graph() -> PoolGraph<AssetIdOf<T, I>> {
+            PoolGraph::new(Self::route_pools().into_inner().into_keys())
+        }
+
+        /// Sorts the assets of a pair the way route pools are keyed
+        pub(crate) fn sort_pair(
+            (asset_a, asset_b): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        ) -> (AssetIdOf<T, I>, AssetIdOf<T, I>) {
+            if asset_a < asset_b {
+                (asset_a, asset_b)
+            } else {
+                (asset_b, asset_a)
+            }
+        }
+
+        /// Returns a sorted list of the routes and their output amounts from a
+        /// start token to end token by traversing the pool graph.
         pub fn get_all_routes(
             amount: BalanceOf<T, I>,
             token_in: AssetIdOf<T, I>,
             token_out: AssetIdOf<T, I>,
             reversed: bool,
         ) -> Result<Vec<(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>)>, DispatchError> {
-            // get all the pool asset pairs from the AMM
-            let pools = T::AMM::get_pools()?;
-
-            let mut graph: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
-
-            // build a non directed graph from pool asset pairs
-            pools.into_iter().for_each(|(a, b)| {
-                graph.entry(a).or_insert_with(Vec::new).push(b);
-                graph.entry(b).or_insert_with(Vec::new).push(a);
-            });
-
-            // init mutable variables
-            let mut path = Vec::new();
-            let mut paths = Vec::new();
-
-            let mut start = token_in;
-            let mut end = token_out;
-
-            let mut queue: Vec<(u32, u32, Vec<u32>)> = Vec::from([(start, end, path)]);
+            let graph = Self::pool_graph();
+            Self::find_routes(&graph, amount, token_in, token_out, reversed)
+        }
 
+        /// Returns the routes from start token to end token and their output amounts
+        /// (or input amounts if reversed), sorted greatest to least.
+        ///
+        /// Paths are extended one hop at a time up to `MaxLengthRoute` assets, quoting
+        /// each hop once. A path is dropped as soon as `MaxRouteSplits` paths with no
+        /// more hops reached its last asset with a better amount, so the search does
+        /// `O(MaxLengthRoute * MaxRouteSplits * pools)` quotes at most.
+        pub fn find_routes(
+            graph: &PoolGraph<AssetIdOf<T, I>>,
+            amount: BalanceOf<T, I>,
+            token_in: AssetIdOf<T, I>,
+            token_out: AssetIdOf<T, I>,
+            reversed: bool,
+        ) -> Result<Vec<(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>)>, DispatchError> {
             // check that both tokens exist in graph
+            ensure!(graph.contains(&token_in), Error::<T, I>::TokenDoesNotExists);
             ensure!(
-                graph.contains_key(&start),
+                graph.contains(&token_out),
                 Error::<T, I>::TokenDoesNotExists
             );
-            ensure!(graph.contains_key(&end), Error::<T, I>::TokenDoesNotExists);
-
-            // iterate until we build all routes
-            while !queue.is_empty() {
-                // desugared RFC 2909-destructuring-assignment
-                let (_start, _end, _path) = queue.swap_remove(0);
-                start = _start;
-                end = _end;
-                path = _path;
 
-                path.push(start);
+            if token_in == token_out {
+                return Ok(vec![(vec![token_in], amount)]);
+            }
 
-                // exit if we reached our target
-                if start == end {
-                    paths.push(path.clone());
+            // amounts in are quoted backwards, from the end token
+            let (start, end) = if reversed {
+                (token_out, token_in)
+            } else {
+                (token_in, token_out)
+            };
+            let is_better = |a: BalanceOf<T, I>, b: BalanceOf<T, I>| {
+                if reversed {
+                    a < b
+                } else {
+                    a > b
+                }
+            };
+            let max_hops = (T::MaxLengthRoute::get() as usize).saturating_sub(1);
+            let max_paths = (T::MaxRouteSplits::get() as usize).max(1);
+
+            // the best amounts any path reached each asset with, best first
+            let mut best_amounts: BTreeMap<AssetIdOf<T, I>, Vec<BalanceOf<T, I>>> = BTreeMap::new();
+            let mut paths = vec![(vec![start], amount)];
+            let mut routes = Vec::new();
+
+            for _ in 0..max_hops {
+                let mut next_paths = Vec::new();
+                for (path, path_amount) in paths.iter() {
+                    let last = path[path.len() - 1];
+                    for &node in graph.adjacents(&last) {
+                        if path.contains(&node) {
+                            continue;
+                        }
+
+                        let quote = if reversed {
+                            Self::get_amounts_in(*path_amount, &[node, last])
+                                .map(|(amounts, _)| amounts[0])
+                        } else {
+                            Self::get_amounts_out(*path_amount, &[last, node])
+                                .map(|(amounts, _)| amounts[1])
+                        };
+                        let node_amount = match quote {
+                            Ok(node_amount) => node_amount,
+                            Err(_) => continue,
+                        };
+
+                        // skip dominated paths
+                        let amounts = best_amounts.entry(node).or_insert_with(Vec::new);
+                        if amounts.len() >= max_paths
+                            && !is_better(node_amount, amounts[amounts.len() - 1])
+                        {
+                            continue;
+                        }
+                        let index = amounts
+                            .iter()
+                            .position(|a| is_better(node_amount, *a))
+                            .unwrap_or(amounts.len());
+                        amounts.insert(index, node_amount);
+                        amounts.truncate(max_paths);
+
+                        let mut node_path = path.clone();
+                        node_path.push(node);
+                        if node == end {
+                            routes.push((node_path, node_amount));
+                        } else {
+                            next_paths.push((node_path, node_amount));
+                        }
+                    }
                 }
 
-                // cant error because we fetch pools above
-                let adjacents = graph.get(&start).unwrap();
-
-                // items that are adjacent but not already in path
-                let difference: Vec<_> = adjacents
-                    .iter()
-                    .filter(|item| !path.contains(item))
-                    .collect();
-
-                for node in difference {
-                    queue.push((*node, end, path.clone()));
+                if next_paths.is_empty() {
+                    break;
                 }
+                paths = next_paths;
             }
 
-            // get output amounts for all routes
-            let mut output_routes = Self::get_output_routes(amount, paths, reversed);
+            if reversed {
+                routes.iter_mut().for_each(|(route, _)| route.reverse());
+            }
 
             // sort values greatest to least
-            output_routes.sort_by_key(|k| Reverse(k.1));
+            routes.sort_by_key(|k| Reverse(k.1));
 
-            Ok(output_routes)
+            Ok(routes)
         }
 
         /// Returns the route that results in the largest amount out for amount in
@@ -265,23 +387,262 @@ pub mod pallet {
         ) -> Vec<(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>)> {
             let mut output_routes = Vec::new();
 
-            if reversed {
-                for route in routes {
-                    let amounts = T::AMM::get_amounts_in(amount, route.clone());
-                    if let Ok(amounts) = amounts {
-                        output_routes.push((route, amounts[0]));
-                    }
+            for route in routes {
+                if let Ok(amount) = Self::get_route_amount(amount, &route, reversed) {
+                    output_routes.push((route, amount));
                 }
+            }
+
+            output_routes
+        }
+
+        /// Returns the amount out of a route for amount in, or the amount in of a route
+        /// for amount out if reversed
+        pub fn get_route_amount(
+            amount: BalanceOf<T, I>,
+            route: &[AssetIdOf<T, I>],
+            reversed: bool,
+        ) -> Result<BalanceOf<T, I>, DispatchError> {
+            if reversed {
+                let (amounts, _) = Self::get_amounts_in(amount, route)?;
+                Ok(amounts[0])
             } else {
-                for route in routes {
-                    let amounts = T::AMM::get_amounts_out(amount, route.clone());
-                    if let Ok(amounts) = amounts {
-                        output_routes.push((route, amounts[amounts.len() - 1]));
+                let (amounts, _) = Self::get_amounts_out(amount, route)?;
+                Ok(amounts[amounts.len() - 1])
+            }
+        }
+
+        /// Returns the amounts along the route for amount in, each hop trades on the
+        /// AMM or the stable swap pool giving the most. The booleans are true for the
+        /// hops trading on the stable swap.
+        pub fn get_amounts_out(
+            amount_in: BalanceOf<T, I>,
+            route: &[AssetIdOf<T, I>],
+        ) -> Result<(Vec<BalanceOf<T, I>>, Vec<bool>), DispatchError> {
+            ensure!(!route.is_empty(), Error::<T, I>::EmptyRoute);
+            let mut amounts = vec![amount_in];
+            let mut is_stable = Vec::with_capacity(route.len() - 1);
+            for hop in route.windows(2) {
+                let amm_amount_out =
+                    T::AMM::get_amounts_out(amounts[amounts.len() - 1], hop.to_vec())
+                        .map(|amounts| amounts[1]);
+                let stable_amount_out =
+                    T::StableSwap::get_amounts_out(amounts[amounts.len() - 1], hop.to_vec())
+                        .map(|amounts| amounts[1]);
+                let (amount_out, stable) = match (amm_amount_out, stable_amount_out) {
+                    (Ok(amm), Ok(stable)) if amm >= stable => (amm, false),
+                    (_, Ok(stable)) => (stable, true),
+                    (Ok(amm), Err(_)) => (amm, false),
+                    (Err(err), Err(_)) => return Err(err),
+                };
+                amounts.push(amount_out);
+                is_stable.push(stable);
+            }
+
+            Ok((amounts, is_stable))
+        }
+
+        /// Returns the amounts along the route for amount out, each hop trades on the
+        /// AMM or the stable swap pool costing the least. The booleans are true for the
+        /// hops trading on the stable swap.
+        pub fn get_amounts_in(
+            amount_out: BalanceOf<T, I>,
+            route: &[AssetIdOf<T, I>],
+        ) -> Result<(Vec<BalanceOf<T, I>>, Vec<bool>), DispatchError> {
+            ensure!(!route.is_empty(), Error::<T, I>::EmptyRoute);
+            let mut amounts = vec![amount_out];
+            let mut is_stable = Vec::with_capacity(route.len() - 1);
+            for hop in route.windows(2).rev() {
+                let amm_amount_in =
+                    T::AMM::get_amounts_in(amounts[0], hop.to_vec()).map(|amounts| amounts[0]);
+                let stable_amount_in = T::StableSwap::get_amounts_in(amounts[0], hop.to_vec())
+                    .map(|amounts| amounts[0]);
+                let (amount_in, stable) = match (amm_amount_in, stable_amount_in) {
+                    (Ok(amm), Ok(stable)) if amm <= stable => (amm, false),
+                    (_, Ok(stable)) => (stable, true),
+                    (Ok(amm), Err(_)) => (amm, false),
+                    (Err(err), Err(_)) => return Err(err),
+                };
+                amounts.insert(0, amount_in);
+                is_stable.insert(0, stable);
+            }
+
+            Ok((amounts, is_stable))
+        }
+
+        /// Returns the best way to trade amount, split across at most `MaxRouteSplits`
+        /// routes which share no pool. Each route comes with the part of the amount
+        /// traded along it, the total amount out (or in if reversed) comes last.
+        #[allow(clippy::type_complexity)]
+        pub fn get_best_split_route(
+            amount: BalanceOf<T, I>,
+            token_in: AssetIdOf<T, I>,
+            token_out: AssetIdOf<T, I>,
+            reversed: bool,
+        ) -> Result<
+            (
+                Vec<(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>)>,
+                BalanceOf<T, I>,
+            ),
+            DispatchError,
+        > {
+            let mut all_routes = Self::get_all_routes(amount, token_in, token_out, reversed)?;
+            ensure!(!all_routes.is_empty(), Error::<T, I>::NoPossibleRoute);
+            if reversed {
+                all_routes.reverse();
+            }
+
+            // pick the best routes which don't trade on the same pairs
+            let mut used_pairs = BTreeSet::new();
+            let mut candidates: Vec<Vec<AssetIdOf<T, I>>> = Vec::new();
+            for (route, _) in all_routes.iter() {
+                if candidates.len() >= T::MaxRouteSplits::get() as usize {
+                    break;
+                }
+                let pairs: Vec<_> = route
+                    .windows(2)
+                    .map(|hop| (hop[0].min(hop[1]), hop[0].max(hop[1])))
+                    .collect();
+                if pairs.iter().any(|pair| used_pairs.contains(pair)) {
+                    continue;
+                }
+                used_pairs.extend(pairs);
+                candidates.push(route.clone());
+            }
+
+            let (best_route, best_amount) = all_routes.swap_remove(0);
+            if candidates.len() <= 1 || amount < SPLIT_STEPS {
+                return Ok((vec![(best_route, amount)], best_amount));
+            }
+
+            // give each part of the amount to the route with the best marginal price
+            let step = amount / SPLIT_STEPS;
+            let mut allocations: Vec<BalanceOf<T, I>> = vec![Zero::zero(); candidates.len()];
+            let mut quotes: Vec<BalanceOf<T, I>> = vec![Zero::zero(); candidates.len()];
+            for i in 0..SPLIT_STEPS {
+                let step_amount = if i == SPLIT_STEPS - 1 {
+                    amount - step * (SPLIT_STEPS - 1)
+                } else {
+                    step
+                };
+                let mut best: Option<(usize, BalanceOf<T, I>, BalanceOf<T, I>)> = None;
+                for (j, route) in candidates.iter().enumerate() {
+                    let quote =
+                        match Self::get_route_amount(allocations[j] + step_amount, route, reversed)
+                        {
+                            Ok(quote) => quote,
+                            Err(_) => continue,
+                        };
+                    let marginal = quote.saturating_sub(quotes[j]);
+                    let is_better = match best {
+                        None => true,
+                        Some((_, _, best_marginal)) if reversed => marginal < best_marginal,
+                        Some((_, _, best_marginal)) => marginal > best_marginal,
+                    };
+                    if is_better {
+                        best = Some((j, quote, marginal));
                     }
                 }
+                let (j, quote, _) = best.ok_or(Error::<T, I>::NoPossibleRoute)?;
+                allocations[j] += step_amount;
+                quotes[j] = quote;
             }
 
-            output_routes
+            let total: BalanceOf<T, I> = quotes.iter().sum();
+            // fall back to the best single route if splitting doesn't do better
+            if (reversed && total >= best_amount) || (!reversed && total <= best_amount) {
+                return Ok((vec![(best_route, amount)], best_amount));
+            }
+
+            let split_route = candidates
+                .into_iter()
+                .zip(allocations)
+                .filter(|(_, allocation)| !allocation.is_zero())
+                .collect();
+
+            log::trace!(
+                target: "router::get_best_split_route",
+                "amount: {:?}, token_in: {:?}, token_out: {:?}, reversed: {:?}, split_route: {:?}, total: {:?}",
+                amount,
+                token_in,
+                token_out,
+                reversed,
+                split_route,
+                total
+            );
+
+            Ok((split_route, total))
+        }
+
+        /// Check that split routes are valid routes sharing the same input and output
+        /// tokens, and that there are at most `MaxRouteSplits` of them
+        pub fn split_route_checks(
+            routes: &[(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>)],
+        ) -> DispatchResult {
+            ensure!(!routes.is_empty(), Error::<T, I>::EmptyRoute);
+            ensure!(
+                routes.len() <= T::MaxRouteSplits::get() as usize,
+                Error::<T, I>::ExceedMaxRouteSplits
+            );
+
+            let (first_route, _) = &routes[0];
+            for (route, amount) in routes {
+                Self::route_checks(route)?;
+                ensure!(!amount.is_zero(), Error::<T, I>::ZeroBalance);
+                ensure!(
+                    route[0] == first_route[0]
+                        && route[route.len() - 1] == first_route[first_route.len() - 1],
+                    Error::<T, I>::MismatchedSplitRoutes
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Trades the amounts along the route, each hop on the AMM or the stable swap
+        #[require_transactional]
+        pub fn do_swap_route(
+            trader: &AccountIdOf<T>,
+            route: &[AssetIdOf<T, I>],
+            amounts: &[BalanceOf<T, I>],
+            is_stable: &[bool],
+        ) -> DispatchResult {
+            for i in 0..(route.len() - 1) {
+                let next_index = i + 1;
+                if is_stable[i] {
+                    T::StableSwap::swap(trader, (route[i], route[next_index]), amounts[i])?;
+                } else {
+                    T::AMM::swap(trader, (route[i], route[next_index]), amounts[i])?;
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Trades the amounts in along the split routes, returns the total amount out
+        #[require_transactional]
+        pub fn do_swap_exact_tokens_for_tokens_split(
+            trader: &AccountIdOf<T>,
+            routes: &[(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>)],
+        ) -> Result<BalanceOf<T, I>, DispatchError> {
+            let mut total_amount_out: BalanceOf<T, I> = Zero::zero();
+            for (route, amount_in) in routes {
+                let (amounts, is_stable) = Self::get_amounts_out(*amount_in, route)?;
+                Self::do_swap_route(trader, route, &amounts, &is_stable)?;
+
+                let amount_out = amounts[amounts.len() - 1];
+                total_amount_out = total_amount_out
+                    .checked_add(amount_out)
+                    .ok_or(ArithmeticError::Overflow)?;
+                Self::deposit_event(Event::Traded(
+                    trader.clone(),
+                    *amount_in,
+                    route.clone(),
+                    amount_out,
+                ));
+            }
+
+            Ok(total_amount_out)
         }
     }
 
@@ -324,7 +685,7 @@ pub mod pallet {
                 Error::<T, I>::InsufficientBalance
             );
 
-            let amounts = T::AMM::get_amounts_out(amount_in, route.clone())?;
+            let (amounts, is_stable) = Self::get_amounts_out(amount_in, &route)?;
 
             // make sure the required amount in does not violate our input
             ensure!(
@@ -332,10 +693,7 @@ pub mod pallet {
                 Error::<T, I>::MinimumAmountOutViolated
             );
 
-            for i in 0..(route.len() - 1) {
-                let next_index = i + 1;
-                T::AMM::swap(&trader, (route[i], route[next_index]), amounts[i])?;
-            }
+            Self::do_swap_route(&trader, &route, &amounts, &is_stable)?;
 
             Self::deposit_event(Event::Traded(
                 trader,
@@ -374,7 +732,7 @@ pub mod pallet {
             );
 
             // calculate trading amounts
-            let amounts = T::AMM::get_amounts_in(amount_out, route.clone())?;
+            let (amounts, is_stable) = Self::get_amounts_in(amount_out, &route)?;
 
             // we need to check after calc so we know how much is expected to be input
             // Ensure the trader has enough tokens for transaction.
@@ -394,10 +752,7 @@ pub mod pallet {
                 Error::<T, I>::MaximumAmountInViolated
             );
 
-            for i in 0..(route.len() - 1) {
-                let next_index = i + 1;
-                T::AMM::swap(&trader, (route[i], route[next_index]), amounts[i])?;
-            }
+            Self::do_swap_route(&trader, &route, &amounts, &is_stable)?;
 
             Self::deposit_event(Event::Traded(
                 trader,
@@ -408,5 +763,158 @@ pub mod pallet {
 
             Ok(().into())
         }
+
+        /// Given input amount is fixed, trades it split across several routes.
+        ///
+        /// - `origin`: the trader.
+        /// - `routes`: the routes and the amount in traded along each of them, all the
+        ///   routes must share the same input and output tokens
+        /// - `min_amount_out`: the minimum a trader is willing to receive in total
+        #[pallet::call_index(2)]
+        #[pallet::weight(
+            T::AMMRouterWeightInfo::swap_exact_tokens_for_tokens()
+                .saturating_mul(routes.len() as u64)
+        )]
+        #[transactional]
+        pub fn swap_exact_tokens_for_tokens_split(
+            origin: OriginFor<T>,
+            routes: Vec<(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>)>,
+            #[pallet::compact] min_amount_out: BalanceOf<T, I>,
+        ) -> DispatchResultWithPostInfo {
+            let trader = ensure_signed(origin)?;
+
+            // do all checks on routes
+            Self::split_route_checks(&routes)?;
+
+            // Ensure the trader has enough tokens for transaction.
+            let from_currency_id = routes[0].0[0];
+            let amount_in = routes
+                .iter()
+                .try_fold(BalanceOf::<T, I>::zero(), |total, (_, amount)| {
+                    total.checked_add(*amount)
+                })
+                .ok_or(ArithmeticError::Overflow)?;
+            ensure!(
+                T::Assets::reducible_balance(
+                    from_currency_id,
+                    &trader,
+                    from_currency_id == T::GetNativeCurrencyId::get()
+                ) >= amount_in,
+                Error::<T, I>::InsufficientBalance
+            );
+
+            let amount_out = Self::do_swap_exact_tokens_for_tokens_split(&trader, &routes)?;
+
+            // make sure the total amount out does not violate our input
+            ensure!(
+                amount_out >= min_amount_out,
+                Error::<T, I>::MinimumAmountOutViolated
+            );
+
+            Ok(().into())
+        }
+
+        /// Given output amount is fixed, trades for it split across several routes.
+        ///
+        /// - `origin`: the trader.
+        /// - `routes`: the routes and the amount out traded along each of them, all the
+        ///   routes must share the same input and output tokens
+        /// - `max_amount_in`: the maximum a trader is willing to input in total
+        #[pallet::call_index(3)]
+        #[pallet::weight(
+            T::AMMRouterWeightInfo::swap_tokens_for_exact_tokens()
+                .saturating_mul(routes.len() as u64)
+        )]
+        #[transactional]
+        pub fn swap_tokens_for_exact_tokens_split(
+            origin: OriginFor<T>,
+            routes: Vec<(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>)>,
+            #[pallet::compact] max_amount_in: BalanceOf<T, I>,
+        ) -> DispatchResultWithPostInfo {
+            let trader = ensure_signed(origin)?;
+
+            // do all checks on routes
+            Self::split_route_checks(&routes)?;
+
+            let mut total_amount_in: BalanceOf<T, I> = Zero::zero();
+            for (route, amount_out) in routes {
+                let (amounts, is_stable) = Self::get_amounts_in(amount_out, &route)?;
+                total_amount_in = total_amount_in
+                    .checked_add(amounts[0])
+                    .ok_or(ArithmeticError::Overflow)?;
+
+                // make sure the required amount in does not violate our input
+                ensure!(
+                    max_amount_in >= total_amount_in,
+                    Error::<T, I>::MaximumAmountInViolated
+                );
+
+                Self::do_swap_route(&trader, &route, &amounts, &is_stable)?;
+
+                Self::deposit_event(Event::Traded(
+                    trader.clone(),
+                    amounts[0],
+                    route,
+                    amounts[amounts.len() - 1],
+                ));
+            }
+
+            Ok(().into())
+        }
+    }
+
+    impl<T: Config<I>, I: 'static>
+        pallet_traits::Router<AccountIdOf<T>, AssetIdOf<T, I>, BalanceOf<T, I>> for Pallet<T, I>
+    {
+        /// Trades `amount_in` along the best split routes, used by pallets
+        /// swapping on behalf of their accounts
+        fn swap_exact_tokens_for_tokens(
+            who: &AccountIdOf<T>,
+            asset_in: AssetIdOf<T, I>,
+            asset_out: AssetIdOf<T, I>,
+            amount_in: BalanceOf<T, I>,
+            min_amount_out: BalanceOf<T, I>,
+        ) -> Result<BalanceOf<T, I>, DispatchError> {
+            let (routes, _) = Self::get_best_split_route(amount_in, asset_in, asset_out, false)?;
+            let amount_out = Self::do_swap_exact_tokens_for_tokens_split(who, &routes)?;
+            ensure!(
+                amount_out >= min_amount_out,
+                Error::<T, I>::MinimumAmountOutViolated
+            );
+
+            Ok(amount_out)
+        }
+    }
+
+    impl<T: Config<I>, I: 'static> PoolsHook<AssetIdOf<T, I>> for Pallet<T, I> {
+        /// Adds the pair to the pool graph, or counts one more pallet trading it.
+        /// Pairs beyond the max route pools are tradable but not routed.
+        fn on_pool_created(pair: (AssetIdOf<T, I>, AssetIdOf<T, I>)) {
+            let pair = Self::sort_pair(pair);
+            RoutePools::<T, I>::mutate(|pools| match pools.get_mut(&pair) {
+                Some(count) => *count = count.saturating_add(1),
+                None => {
+                    if pools.try_insert(pair, 1).is_err() {
+                        log::warn!(
+                            target: "router::on_pool_created",
+                            "pair: {:?} exceeds the max route pools, it is not routed",
+                            pair,
+                        );
+                    }
+                }
+            });
+        }
+
+        /// Drops the pair from the pool graph once no pallet trades it
+        fn on_pool_removed(pair: (AssetIdOf<T, I>, AssetIdOf<T, I>)) {
+            let pair = Self::sort_pair(pair);
+            RoutePools::<T, I>::mutate(|pools| match pools.get_mut(&pair) {
+                Some(count) if *count > 1 => *count -= 1,
+                Some(_) => {
+                    pools.remove(&pair);
+                }
+                None => {}
+            });
+        }
     }
 }
@@ -0,0 +1,121 @@
+// Copyright 2021-2022 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+pub mod v2 {
+    use super::*;
+    use crate::{pallet::StorageVersion, Config, RoutePools};
+    use frame_support::{
+        log,
+        traits::{Get, OnRuntimeUpgrade},
+        weights::Weight,
+        BoundedBTreeMap,
+    };
+    use pallet_traits::{StableSwap, AMM};
+    use sp_std::collections::btree_map::BTreeMap;
+    #[cfg(feature = "try-runtime")]
+    use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
+
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T, I>::get() == crate::Versions::V1,
+            "must upgrade linearly"
+        );
+        let mut pairs = T::AMM::get_pools().unwrap_or_default();
+        pairs.extend(T::StableSwap::get_pools().unwrap_or_default());
+        let pairs = pairs
+            .into_iter()
+            .map(Pallet::<T, I>::sort_pair)
+            .collect::<BTreeSet<_>>();
+        frame_support::ensure!(
+            pairs.len() <= T::MaxRoutePools::get() as usize,
+            "pairs exceed the max route pools"
+        );
+
+        Ok(())
+    }
+
+    /// Migration to the stored pool graph, the asset pairs the AMM and the stable
+    /// swap already trade are recounted from their pools. Nothing is migrated if
+    /// they exceed the max route pools, it must be raised first.
+    pub fn migrate<T: Config<I>, I: 'static>() -> Weight {
+        if StorageVersion::<T, I>::get() == crate::Versions::V1 {
+            log::info!("migrating router to Versions::V2",);
+
+            let mut pairs = T::AMM::get_pools().unwrap_or_default();
+            pairs.extend(T::StableSwap::get_pools().unwrap_or_default());
+            let count = pairs.len() as u64;
+
+            let mut pools = BTreeMap::new();
+            for pair in pairs {
+                let pool_count: &mut u32 =
+                    pools.entry(Pallet::<T, I>::sort_pair(pair)).or_default();
+                *pool_count = pool_count.saturating_add(1);
+            }
+            let pairs_count = pools.len();
+            let pools: BoundedBTreeMap<_, _, T::MaxRoutePools> = match pools.try_into() {
+                Ok(pools) => pools,
+                Err(_) => {
+                    log::error!(
+                        "pairs: {:?} exceed the max route pools: {:?}, router migration aborted",
+                        pairs_count,
+                        T::MaxRoutePools::get()
+                    );
+                    return T::DbWeight::get().reads(count + 1);
+                }
+            };
+            RoutePools::<T, I>::put(pools);
+
+            StorageVersion::<T, I>::put(crate::Versions::V2);
+            log::info!("👜 completed router migration to Versions::V2",);
+
+            T::DbWeight::get().reads_writes(count + 1, 2)
+        } else {
+            T::DbWeight::get().reads(1)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn post_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T, I>::get() == crate::Versions::V2,
+            "must upgrade to V2"
+        );
+        log::info!("route pools {:#?}", RoutePools::<T, I>::get().into_inner());
+        log::info!("👜 router v2 migration passes POST migrate checks ✅",);
+
+        Ok(())
+    }
+
+    /// Runs the v2 migration on runtime upgrade
+    pub struct MigrateToV2<T, I = ()>(sp_std::marker::PhantomData<(T, I)>);
+    impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV2<T, I> {
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            pre_migrate::<T, I>()?;
+            Ok(Vec::new())
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            migrate::<T, I>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+            post_migrate::<T, I>()
+        }
+    }
+}
@@ -24,9 +24,12 @@ use frame_support::{
 };
 use frame_system::{EnsureRoot, EnsureSigned, EnsureSignedBy};
 use sp_core::H256;
-use sp_runtime::{testing::Header, traits::IdentityLookup};
+use sp_runtime::{
+    testing::Header,
+    traits::{IdentityLookup, One},
+};
 
-pub use primitives::{tokens, Amount, Balance, CurrencyId, Ratio};
+pub use primitives::{tokens, Amount, Balance, CurrencyId, Rate, Ratio};
 
 pub type AccountId = u128;
 pub type BlockNumber = u64;
@@ -34,6 +37,7 @@ pub type BlockNumber = u64;
 pub const ALICE: AccountId = 1;
 pub const BOB: AccountId = 2;
 pub const DAVE: AccountId = 4;
+pub const PROTOCOL_FEE_RECEIVER: AccountId = 99;
 
 pub const DOT: CurrencyId = tokens::DOT;
 pub const SDOT: CurrencyId = tokens::SDOT;
@@ -42,6 +46,7 @@ pub const KSM: CurrencyId = tokens::KSM;
 pub const SAMPLE_LP_TOKEN: CurrencyId = 42;
 pub const SAMPLE_LP_TOKEN_2: CurrencyId = 43;
 pub const SAMPLE_LP_TOKEN_3: CurrencyId = 44;
+pub const SAMPLE_STABLE_LP_TOKEN: CurrencyId = 45;
 parameter_types! {
     pub const BlockHashCount: u64 = 250;
 }
@@ -131,6 +136,8 @@ parameter_types! {
     // pub const DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);
     pub  DefaultLpFee: Ratio = Ratio::from_rational(25u32, 10000u32);         // 0.3%
     pub const MinimumLiquidity: u128 = 1_000u128;
+    pub const MaxTicksPerPool: u32 = 1_000;
+    pub const MinPositionLiquidity: u128 = 1_000_000u128;
     pub const LockAccountId: AccountId = ALICE;
 
 }
@@ -154,6 +161,9 @@ impl pallet_amm::Config for Runtime {
     type MinimumLiquidity = MinimumLiquidity;
     type MaxLengthRoute = MaxLengthRoute;
     type GetNativeCurrencyId = NativeCurrencyId;
+    type MaxTicksPerPool = MaxTicksPerPool;
+    type MinPositionLiquidity = MinPositionLiquidity;
+    type PoolsHook = AMMRoute;
 }
 
 parameter_types! {
@@ -167,8 +177,48 @@ impl pallet_currency_adapter::Config for Runtime {
     type LockOrigin = EnsureRoot<AccountId>;
 }
 
+parameter_types! {
+    pub const StableSwapPalletId: PalletId = PalletId(*b"par/sswp");
+    pub const NumTokens: u8 = 2;
+    pub const Precision: u128 = 100;
+    pub const AmplificationCoefficient: u8 = 85;
+    pub const MaxPoolAssets: u32 = 4;
+    pub const MinRampBlocks: u64 = 10;
+    pub DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);
+    pub const DefaultProtocolFeeReceiver: AccountId = PROTOCOL_FEE_RECEIVER;
+}
+
+pub struct LiquidStakingExchangeRateProvider;
+impl pallet_traits::ExchangeRateProvider<CurrencyId> for LiquidStakingExchangeRateProvider {
+    fn get_exchange_rate(_: &CurrencyId) -> Option<Rate> {
+        Some(Rate::one())
+    }
+}
+
+impl pallet_stableswap::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Assets = CurrencyAdapter;
+    type WeightInfo = ();
+    type PalletId = StableSwapPalletId;
+    type NumTokens = NumTokens;
+    type Precision = Precision;
+    type AmplificationCoefficient = AmplificationCoefficient;
+    type MaxPoolAssets = MaxPoolAssets;
+    type MinRampBlocks = MinRampBlocks;
+    type ProtocolFeeReceiver = DefaultProtocolFeeReceiver;
+    type LpFee = DefaultLpFee;
+    type LockAccountId = LockAccountId;
+    type ProtocolFee = DefaultProtocolFee;
+    type MinimumLiquidity = MinimumLiquidity;
+    type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type LiquidStakingExchangeRateProvider = LiquidStakingExchangeRateProvider;
+    type PoolsHook = AMMRoute;
+}
+
 parameter_types! {
     pub const MaxLengthRoute: u8 = 10;
+    pub const MaxRouteSplits: u32 = 4;
+    pub const MaxRoutePools: u32 = 100;
     pub const RouterPalletId: PalletId = PalletId(*b"ammroute");
 }
 
@@ -176,8 +226,11 @@ impl Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type PalletId = RouterPalletId;
     type AMM = DefaultAMM;
+    type StableSwap = StableSwap;
     type AMMRouterWeightInfo = ();
     type MaxLengthRoute = MaxLengthRoute;
+    type MaxRouteSplits = MaxRouteSplits;
+    type MaxRoutePools = MaxRoutePools;
     type Assets = CurrencyAdapter;
     type GetNativeCurrencyId = NativeCurrencyId;
 }
@@ -197,7 +250,8 @@ construct_runtime!(
         // AMM instances
         DefaultAMM: pallet_amm::{Pallet, Call, Storage, Event<T>},
         // AMM Route
-        AMMRoute: pallet_route::{Pallet, Call, Event<T>},
+        AMMRoute: pallet_route::{Pallet, Call, Storage, Event<T>},
+        StableSwap: pallet_stableswap::{Pallet, Call, Storage, Event<T>},
         CurrencyAdapter: pallet_currency_adapter::{Pallet, Call},
     }
 );
@@ -245,6 +299,14 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
             1,
         )
         .unwrap();
+        Assets::force_create(
+            RuntimeOrigin::root(),
+            SAMPLE_STABLE_LP_TOKEN.into(),
+            ALICE,
+            true,
+            1,
+        )
+        .unwrap();
 
         Assets::mint(
             RuntimeOrigin::signed(ALICE),
@@ -17,6 +17,7 @@
 use super::*;
 use frame_support::{assert_noop, assert_ok};
 use mock::*;
+use sp_std::collections::btree_map::BTreeMap;
 
 #[test]
 fn too_many_routes_should_not_work() {
@@ -77,7 +78,9 @@ fn swap_exact_tokens_for_tokens_should_work() {
             (DOT, SDOT),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
 
         let route = vec![DOT, SDOT];
@@ -111,7 +114,9 @@ fn swap_tokens_for_exact_tokens_should_work() {
             (DOT, SDOT),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
 
         let route = vec![DOT, SDOT];
@@ -145,7 +150,9 @@ fn pool_as_bridge_swap_tokens_for_exact_tokens_should_work() {
             (USDT, SDOT),
             (40_000_000, 1_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
         // 1 SDOT ~= 40 USDT
 
@@ -155,7 +162,9 @@ fn pool_as_bridge_swap_tokens_for_exact_tokens_should_work() {
             (DOT, SDOT),
             (50_000_000, 50_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+            None
         ));
         // 1 DOT == 1 SDOT
 
@@ -195,7 +204,9 @@ fn swap_exact_tokens_for_tokens_should_not_work_if_amount_less_than_min_amount_o
             (DOT, SDOT),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
 
         // check that pool was funded correctly
@@ -233,7 +244,9 @@ fn swap_tokens_for_exact_tokens_should_not_work_if_amount_more_than_max_amount_i
             (DOT, SDOT),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
 
         // check that pool was funded correctly
@@ -271,7 +284,9 @@ fn trade_should_work_more_than_one_route() {
             (DOT, SDOT),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -280,7 +295,9 @@ fn trade_should_work_more_than_one_route() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -289,7 +306,9 @@ fn trade_should_work_more_than_one_route() {
             (USDT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
+            None
         ));
 
         // CHECK POOLS
@@ -416,7 +435,9 @@ fn get_all_routes_should_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -425,7 +446,9 @@ fn get_all_routes_should_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -434,7 +457,9 @@ fn get_all_routes_should_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
+            None
         ));
 
         let routes = AMMRoute::get_all_routes(
@@ -453,6 +478,77 @@ fn get_all_routes_should_work() {
     })
 }
 
+#[test]
+fn get_all_routes_should_keep_the_best_routes() {
+    new_test_ext().execute_with(|| {
+        for lp_token in [46, 47, 48] {
+            assert_ok!(Assets::force_create(
+                RuntimeOrigin::root(),
+                lp_token.into(),
+                ALICE,
+                true,
+                1
+            ));
+        }
+
+        // every pair of DOT, SDOT, KSM and USDT has a pool
+        for (pair, liquidity, lp_token) in [
+            ((DOT, SDOT), (100_000_000, 90_000_000), SAMPLE_LP_TOKEN),
+            ((SDOT, KSM), (100_000_000, 100_000_000), SAMPLE_LP_TOKEN_2),
+            ((DOT, KSM), (100_000_000, 70_000_000), SAMPLE_LP_TOKEN_3),
+            ((DOT, USDT), (100_000_000, 95_000_000), 46),
+            ((USDT, KSM), (100_000_000, 80_000_000), 47),
+            ((SDOT, USDT), (100_000_000, 110_000_000), 48),
+        ] {
+            assert_ok!(DefaultAMM::create_pool(
+                RuntimeOrigin::signed(ALICE),
+                pair,
+                liquidity,
+                DAVE,
+                lp_token,
+                DefaultLpFee::get(),
+                None
+            ));
+        }
+
+        let all_paths = vec![
+            vec![DOT, KSM],
+            vec![DOT, SDOT, KSM],
+            vec![DOT, USDT, KSM],
+            vec![DOT, SDOT, USDT, KSM],
+            vec![DOT, USDT, SDOT, KSM],
+        ];
+
+        // only the best `MaxRouteSplits` paths to each asset are extended
+        let routes = AMMRoute::get_all_routes(1_000, DOT, KSM, false).unwrap();
+        assert!(routes.len() <= MaxRouteSplits::get() as usize);
+        let best_amount_out = all_paths
+            .iter()
+            .map(|path| AMMRoute::get_route_amount(1_000, path, false).unwrap())
+            .max()
+            .unwrap();
+        assert_eq!(routes[0].1, best_amount_out);
+        assert!(routes.windows(2).all(|w| w[0].1 >= w[1].1));
+
+        let routes = AMMRoute::get_all_routes(1_000, DOT, KSM, true).unwrap();
+        assert!(routes.len() <= MaxRouteSplits::get() as usize);
+        let best_amount_in = all_paths
+            .iter()
+            .map(|path| AMMRoute::get_route_amount(1_000, path, true).unwrap())
+            .min()
+            .unwrap();
+        assert_eq!(routes[routes.len() - 1].1, best_amount_in);
+        for (route, amount_in) in routes {
+            assert_eq!(route[0], DOT);
+            assert_eq!(route[route.len() - 1], KSM);
+            assert_eq!(
+                AMMRoute::get_route_amount(1_000, &route, true).unwrap(),
+                amount_in
+            );
+        }
+    })
+}
+
 #[test]
 fn get_best_route_should_work() {
     new_test_ext().execute_with(|| {
@@ -463,7 +559,9 @@ fn get_best_route_should_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -472,7 +570,9 @@ fn get_best_route_should_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -481,7 +581,9 @@ fn get_best_route_should_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
+            None
         ));
 
         let best_route = AMMRoute::get_best_route(
@@ -507,7 +609,9 @@ fn get_route_for_tokens_not_in_graph_should_not_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -516,7 +620,9 @@ fn get_route_for_tokens_not_in_graph_should_not_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -525,7 +631,9 @@ fn get_route_for_tokens_not_in_graph_should_not_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
+            None
         ));
 
         assert_noop!(
@@ -550,7 +658,9 @@ fn get_route_for_tokens_not_possible_should_not_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -559,7 +669,9 @@ fn get_route_for_tokens_not_possible_should_not_work() {
             (KSM, USDT),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
+            None
         ));
 
         assert_noop!(
@@ -582,7 +694,9 @@ fn get_routes_for_non_existing_pair_should_not_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
+            None
         ));
         assert_noop!(
             AMMRoute::get_best_route(
@@ -606,7 +720,9 @@ fn get_best_route_same_tokens_should_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -615,7 +731,9 @@ fn get_best_route_same_tokens_should_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -624,7 +742,9 @@ fn get_best_route_same_tokens_should_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
+            None
         ));
 
         let best_route = AMMRoute::get_best_route(
@@ -649,7 +769,9 @@ fn get_best_route_same_tokens_reversed_should_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -658,7 +780,9 @@ fn get_best_route_same_tokens_reversed_should_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -667,7 +791,9 @@ fn get_best_route_same_tokens_reversed_should_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
+            None
         ));
 
         let best_route = AMMRoute::get_best_route(
@@ -692,7 +818,9 @@ fn get_all_routes_reversed_should_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -701,7 +829,9 @@ fn get_all_routes_reversed_should_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -710,7 +840,9 @@ fn get_all_routes_reversed_should_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
+            None
         ));
 
         let routes = AMMRoute::get_all_routes(
@@ -739,7 +871,9 @@ fn get_best_route_reversed_should_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -748,7 +882,9 @@ fn get_best_route_reversed_should_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+            None
         ));
 
         // create pool and add liquidity
@@ -757,7 +893,9 @@ fn get_best_route_reversed_should_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
+            None
         ));
 
         let best_route = AMMRoute::get_best_route(
@@ -772,3 +910,280 @@ fn get_best_route_reversed_should_work() {
         assert_eq!(best_route, (vec![101, 1001, 100], 1119));
     })
 }
+
+fn create_split_pools() {
+    assert_ok!(DefaultAMM::create_pool(
+        RuntimeOrigin::signed(ALICE),
+        (DOT, SDOT),
+        (1_000_000, 1_000_000),
+        DAVE,
+        SAMPLE_LP_TOKEN,
+        DefaultLpFee::get(),
+        None
+    ));
+    assert_ok!(DefaultAMM::create_pool(
+        RuntimeOrigin::signed(ALICE),
+        (SDOT, KSM),
+        (1_000_000, 1_000_000),
+        DAVE,
+        SAMPLE_LP_TOKEN_2,
+        DefaultLpFee::get(),
+        None
+    ));
+    assert_ok!(DefaultAMM::create_pool(
+        RuntimeOrigin::signed(ALICE),
+        (DOT, KSM),
+        (1_000_000, 1_000_000),
+        DAVE,
+        SAMPLE_LP_TOKEN_3,
+        DefaultLpFee::get(),
+        None
+    ));
+}
+
+#[test]
+fn get_amounts_out_should_use_the_best_pool_of_each_hop() {
+    new_test_ext().execute_with(|| {
+        // the AMM pool is priced far below the stable swap pool
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, USDT),
+            (100_000_000, 50_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
+        ));
+        assert_ok!(StableSwap::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, USDT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_STABLE_LP_TOKEN
+        ));
+
+        let (amounts, is_stable) = AMMRoute::get_amounts_out(1_000, &[DOT, USDT]).unwrap();
+        assert_eq!(is_stable, vec![true]);
+        assert_eq!(
+            amounts,
+            <StableSwap as pallet_traits::StableSwap<_, _, _>>::get_amounts_out(
+                1_000,
+                vec![DOT, USDT]
+            )
+            .unwrap()
+        );
+
+        // buying from the pair also goes through the stable swap pool
+        let (_, is_stable) = AMMRoute::get_amounts_in(1_000, &[DOT, USDT]).unwrap();
+        assert_eq!(is_stable, vec![true]);
+
+        // the pair is a single edge of the graph
+        let routes = AMMRoute::get_all_routes(1_000, DOT, USDT, false).unwrap();
+        assert_eq!(routes, vec![(vec![DOT, USDT], amounts[1])]);
+    })
+}
+
+#[test]
+fn swap_exact_tokens_for_tokens_across_amm_and_stable_swap_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
+        ));
+        assert_ok!(StableSwap::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (SDOT, USDT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_STABLE_LP_TOKEN
+        ));
+
+        let (route, amount_out) = AMMRoute::get_best_route(10_000, DOT, USDT, false).unwrap();
+        assert_eq!(route, vec![DOT, SDOT, USDT]);
+
+        assert_ok!(AMMRoute::swap_exact_tokens_for_tokens(
+            RuntimeOrigin::signed(DAVE),
+            route,
+            10_000,
+            amount_out
+        ));
+        assert_eq!(
+            Assets::balance(USDT, DAVE),
+            1_000_000_000 - 100_000_000 + amount_out
+        );
+    })
+}
+
+#[test]
+fn get_best_split_route_should_split_large_trades() {
+    new_test_ext().execute_with(|| {
+        create_split_pools();
+
+        // trades too small to be cut take the best route only
+        let (split_route, amount_out) =
+            AMMRoute::get_best_split_route(10, DOT, KSM, false).unwrap();
+        assert_eq!(split_route, vec![(vec![DOT, KSM], 10)]);
+        assert_eq!(
+            amount_out,
+            AMMRoute::get_best_route(10, DOT, KSM, false).unwrap().1
+        );
+
+        // large trades are split across routes which share no pool
+        let amount_in = 200_000;
+        let (split_route, amount_out) =
+            AMMRoute::get_best_split_route(amount_in, DOT, KSM, false).unwrap();
+        assert_eq!(split_route.len(), 2);
+        assert_eq!(
+            split_route
+                .iter()
+                .map(|(_, amount)| amount)
+                .sum::<Balance>(),
+            amount_in
+        );
+        assert!(
+            amount_out
+                > AMMRoute::get_best_route(amount_in, DOT, KSM, false)
+                    .unwrap()
+                    .1
+        );
+
+        let ksm_before = Assets::balance(KSM, DAVE);
+        assert_ok!(AMMRoute::swap_exact_tokens_for_tokens_split(
+            RuntimeOrigin::signed(DAVE),
+            split_route,
+            amount_out
+        ));
+        assert_eq!(Assets::balance(KSM, DAVE), ksm_before + amount_out);
+    })
+}
+
+#[test]
+fn get_best_split_route_reversed_should_work() {
+    new_test_ext().execute_with(|| {
+        create_split_pools();
+
+        let amount_out = 200_000;
+        let (split_route, amount_in) =
+            AMMRoute::get_best_split_route(amount_out, DOT, KSM, true).unwrap();
+        assert_eq!(split_route.len(), 2);
+        assert!(
+            amount_in
+                < AMMRoute::get_best_route(amount_out, DOT, KSM, true)
+                    .unwrap()
+                    .1
+        );
+
+        let dot_before = Assets::balance(DOT, DAVE);
+        let ksm_before = Assets::balance(KSM, DAVE);
+        assert_ok!(AMMRoute::swap_tokens_for_exact_tokens_split(
+            RuntimeOrigin::signed(DAVE),
+            split_route,
+            amount_in
+        ));
+        assert_eq!(Assets::balance(DOT, DAVE), dot_before - amount_in);
+        assert!(Assets::balance(KSM, DAVE) >= ksm_before + amount_out);
+    })
+}
+
+#[test]
+fn swap_split_with_invalid_routes_should_not_work() {
+    new_test_ext().execute_with(|| {
+        create_split_pools();
+
+        assert_noop!(
+            AMMRoute::swap_exact_tokens_for_tokens_split(
+                RuntimeOrigin::signed(DAVE),
+                vec![(vec![DOT, KSM], 1_000), (vec![DOT, SDOT], 1_000)],
+                0
+            ),
+            Error::<Runtime>::MismatchedSplitRoutes
+        );
+        assert_noop!(
+            AMMRoute::swap_exact_tokens_for_tokens_split(
+                RuntimeOrigin::signed(DAVE),
+                vec![(vec![DOT, KSM], 1_000); MaxRouteSplits::get() as usize + 1],
+                0
+            ),
+            Error::<Runtime>::ExceedMaxRouteSplits
+        );
+        assert_noop!(
+            AMMRoute::swap_exact_tokens_for_tokens_split(
+                RuntimeOrigin::signed(DAVE),
+                vec![(vec![DOT, KSM], 1_000), (vec![DOT, SDOT, KSM], 1_000)],
+                100_000
+            ),
+            Error::<Runtime>::MinimumAmountOutViolated
+        );
+    })
+}
+
+#[test]
+fn route_pools_should_follow_pool_creation() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, USDT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
+        ));
+        assert_ok!(StableSwap::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (USDT, DOT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_STABLE_LP_TOKEN
+        ));
+
+        // the pair is stored once and counted for both pallets
+        let pair = AMMRoute::sort_pair((DOT, USDT));
+        assert_eq!(
+            AMMRoute::route_pools().into_inner(),
+            BTreeMap::from([(pair, 2)])
+        );
+        assert!(AMMRoute::pool_graph().contains(&DOT));
+        assert_eq!(AMMRoute::pool_graph().adjacents(&DOT), &[USDT]);
+
+        // the pair stays in the graph until no pallet trades it
+        <AMMRoute as pallet_traits::PoolsHook<_>>::on_pool_removed((USDT, DOT));
+        assert_eq!(
+            AMMRoute::route_pools().into_inner(),
+            BTreeMap::from([(pair, 1)])
+        );
+        <AMMRoute as pallet_traits::PoolsHook<_>>::on_pool_removed((DOT, USDT));
+        assert!(AMMRoute::route_pools().is_empty());
+        assert!(!AMMRoute::pool_graph().contains(&DOT));
+    })
+}
+
+#[test]
+fn pairs_beyond_max_route_pools_are_not_routed() {
+    new_test_ext().execute_with(|| {
+        for asset in 0..MaxRoutePools::get() {
+            <AMMRoute as pallet_traits::PoolsHook<_>>::on_pool_created((
+                10_000 + asset,
+                20_000 + asset,
+            ));
+        }
+
+        // pools are still created for a new pair beyond the max route pools
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+            None
+        ));
+        assert_eq!(AMMRoute::route_pools().len(), MaxRoutePools::get() as usize);
+        assert!(!AMMRoute::pool_graph().contains(&SDOT));
+    })
+}
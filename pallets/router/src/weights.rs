@@ -47,6 +47,7 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
 	fn swap_exact_tokens_for_tokens() -> Weight;
 	fn swap_tokens_for_exact_tokens() -> Weight;
+	fn get_best_route(p: u32, ) -> Weight;
 }
 
 /// Weights for pallet_router using the Substrate node and recommended hardware.
@@ -70,6 +71,16 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(8 as u64))
 			.saturating_add(T::DbWeight::get().writes(8 as u64))
 	}
+	// Storage: AMMRoute RoutePools (r:1 w:0)
+	// Storage: AMM Pools (r:100 w:0)
+	// Storage: AMM DynamicFees (r:100 w:0)
+	fn get_best_route(p: u32, ) -> Weight {
+		Weight::from_ref_time(18_342_000 as u64)
+			// Standard Error: 21_000
+			.saturating_add(Weight::from_ref_time(41_607_000 as u64).saturating_mul(p as u64))
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().reads((5 as u64).saturating_mul(p as u64)))
+	}
 }
 
 // For backwards compatibility and tests
@@ -92,4 +103,14 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(8 as u64))
 			.saturating_add(RocksDbWeight::get().writes(8 as u64))
 	}
+	// Storage: AMMRoute RoutePools (r:1 w:0)
+	// Storage: AMM Pools (r:100 w:0)
+	// Storage: AMM DynamicFees (r:100 w:0)
+	fn get_best_route(p: u32, ) -> Weight {
+		Weight::from_ref_time(18_342_000 as u64)
+			// Standard Error: 21_000
+			.saturating_add(Weight::from_ref_time(41_607_000 as u64).saturating_mul(p as u64))
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().reads((5 as u64).saturating_mul(p as u64)))
+	}
 }
@@ -1,4 +1,5 @@
-use num_traits::{CheckedDiv, ToPrimitive};
+use num_bigint::BigUint as NumBigUint;
+use num_traits::{CheckedDiv, CheckedSub, One, ToPrimitive, Zero};
 use pallet_traits::ConvertToBigUint;
 use sp_runtime::{biguint::BigUint, helpers_128bit::to_big_uint, ArithmeticError, DispatchError};
 
@@ -141,3 +142,151 @@ pub fn compute_base(new_quote: u128, amp_coeff: u128, d: u128) -> Result<u128, D
     }
     Err(DispatchError::Other("Error computing d"))
 }
+
+fn big_uint_to_u128(value: NumBigUint) -> Result<u128, DispatchError> {
+    value
+        .to_u128()
+        .ok_or_else(|| ArithmeticError::Overflow.into())
+}
+
+fn big_uint_div(a: &NumBigUint, b: &NumBigUint) -> Result<NumBigUint, DispatchError> {
+    a.checked_div(b)
+        .ok_or_else(|| ArithmeticError::DivisionByZero.into())
+}
+
+fn converged(a: &NumBigUint, b: &NumBigUint) -> bool {
+    if a > b {
+        a - b <= NumBigUint::one()
+    } else {
+        b - a <= NumBigUint::one()
+    }
+}
+
+/// `A * n^n` for a pool of `n` assets
+fn compute_ann(amp_coeff: u128, n: usize) -> Result<NumBigUint, DispatchError> {
+    let ann = amp_coeff.get_big_uint() * NumBigUint::from(n).pow(n as u32);
+    if ann.is_zero() {
+        return Err(ArithmeticError::DivisionByZero.into());
+    }
+    Ok(ann)
+}
+
+/// Same as `compute_d` for a pool of any count of assets
+///
+/// ```pseudocode
+/// D[j + 1] = (A * n^n * sum(x_i) + n * D_p) * D[j] / ((A * n^n - 1) * D[j] + (n + 1) * D_p)
+/// D_p = D[j]^(n+1) / (n^n * prod(x_i))
+/// ```
+pub fn compute_d_n(balances: &[u128], amp_coeff: u128) -> Result<u128, DispatchError> {
+    let n = balances.len();
+    let n_big = NumBigUint::from(n);
+    let sum = balances.iter().fold(NumBigUint::zero(), |sum, balance| {
+        sum + balance.get_big_uint()
+    });
+    if sum.is_zero() {
+        return Ok(0_u128);
+    }
+    let ann = compute_ann(amp_coeff, n)?;
+
+    let mut d = sum.clone();
+    for _ in 0..255 {
+        let mut d_p = d.clone();
+        for balance in balances {
+            d_p = big_uint_div(&(d_p * &d), &(balance.get_big_uint() * &n_big))?;
+        }
+
+        let d_prev = d.clone();
+        let numerator = (&ann * &sum + &d_p * &n_big) * &d;
+        let denominator = (&ann - NumBigUint::one()) * &d + (&n_big + NumBigUint::one()) * &d_p;
+        d = big_uint_div(&numerator, &denominator)?;
+
+        if converged(&d, &d_prev) {
+            return big_uint_to_u128(d);
+        }
+    }
+    Err(DispatchError::Other("could not compute d"))
+}
+
+/// Solves the invariant for the balance of one asset, given the balances of all the
+/// others in a pool of `n` assets
+///
+/// ```pseudocode
+/// y = (y^2 + c) / (2 * y + b - D)
+/// c = D^(n+1) / (n^n * prod' * A * n^n)
+/// b = sum' + D / (A * n^n)
+/// ```
+fn solve_y(
+    n: usize,
+    other_balances: impl Iterator<Item = u128>,
+    amp_coeff: u128,
+    d: u128,
+) -> Result<u128, DispatchError> {
+    let n_big = NumBigUint::from(n);
+    let d = d.get_big_uint();
+    let ann = compute_ann(amp_coeff, n)?;
+
+    let mut c = d.clone();
+    let mut s = NumBigUint::zero();
+    for balance in other_balances {
+        s += balance.get_big_uint();
+        c = big_uint_div(&(c * &d), &(balance.get_big_uint() * &n_big))?;
+    }
+    c = big_uint_div(&(c * &d), &(&ann * &n_big))?;
+    let b = s + big_uint_div(&d, &ann)?;
+
+    let mut y = d.clone();
+    for _ in 0..255 {
+        let y_prev = y.clone();
+        let denominator = (NumBigUint::from(2_u8) * &y + &b)
+            .checked_sub(&d)
+            .ok_or(ArithmeticError::Underflow)?;
+        y = big_uint_div(&(&y * &y + &c), &denominator)?;
+
+        if converged(&y, &y_prev) {
+            return big_uint_to_u128(y);
+        }
+    }
+    Err(DispatchError::Other("could not compute y"))
+}
+
+/// Computes the balance of asset `j` keeping the invariant `d` once the balance of
+/// asset `i` is `x`
+pub fn compute_y(
+    i: usize,
+    j: usize,
+    x: u128,
+    balances: &[u128],
+    amp_coeff: u128,
+    d: u128,
+) -> Result<u128, DispatchError> {
+    let n = balances.len();
+    if i == j || i >= n || j >= n {
+        return Err(DispatchError::Other("invalid asset indexes"));
+    }
+    let other_balances = balances
+        .iter()
+        .enumerate()
+        .filter(|(k, _)| *k != j)
+        .map(|(k, balance)| if k == i { x } else { *balance });
+    solve_y(n, other_balances, amp_coeff, d)
+}
+
+/// Computes the balance of asset `i` keeping the invariant `d`, the balances of the
+/// other assets don't change
+pub fn compute_y_d(
+    i: usize,
+    balances: &[u128],
+    amp_coeff: u128,
+    d: u128,
+) -> Result<u128, DispatchError> {
+    let n = balances.len();
+    if i >= n {
+        return Err(DispatchError::Other("invalid asset index"));
+    }
+    let other_balances = balances
+        .iter()
+        .enumerate()
+        .filter(|(k, _)| *k != i)
+        .map(|(_, balance)| *balance);
+    solve_y(n, other_balances, amp_coeff, d)
+}
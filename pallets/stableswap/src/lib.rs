@@ -15,13 +15,27 @@
 //! # Stable Swap
 //!
 //! Provide low slippage and low fees when trading stablecoins
+//!
+//! Pools trade two assets, or up to `MaxPoolAssets` assets for pools of several
+//! assets. The amplification coefficient of each pool can be ramped linearly over
+//! a window of blocks by governance.
+//!
+//! Assets of a pool can be rate scaled, their balances are then normalised by the
+//! exchange rate of the asset before applying the invariant. This keeps pairs of a
+//! liquid staking derivative and its staking asset tight although the derivative
+//! accrues value.
+//!
+//! Liquidity can be zapped in from or out to a single asset of a pool. Pools of two
+//! assets swap the part of the other asset, pools of several assets take the single
+//! asset as an imbalanced deposit or withdrawal.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 pub use pallet::*;
-use types::Pool;
+use types::{AmplificationRamp, MultiAssetPool, Pool};
 extern crate alloc;
 
 mod helpers;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -40,15 +54,16 @@ use frame_support::{
     transactional, Blake2_128Concat, PalletId,
 };
 
-use pallet_traits::ConvertToBigUint;
-use primitives::{Balance, CurrencyId, Ratio};
+use pallet_traits::{ConvertToBigUint, ExchangeRateProvider, PoolsHook};
+use primitives::{Balance, CurrencyId, Rate, Ratio};
 use sp_runtime::{
+    helpers_128bit::multiply_by_rational_with_rounding,
     traits::{AccountIdConversion, CheckedAdd, CheckedSub, One, Saturating, Zero},
-    ArithmeticError, DispatchError, FixedPointNumber, FixedU128, SaturatedConversion,
+    ArithmeticError, DispatchError, FixedPointNumber, FixedU128, Rounding, SaturatedConversion,
 };
-use sp_std::{cmp::min, ops::Div, result::Result, vec::Vec};
+use sp_std::{cmp::min, ops::Div, result::Result, vec, vec::Vec};
 
-use crate::helpers::{compute_base, compute_d};
+use crate::helpers::{compute_base, compute_d, compute_d_n, compute_y, compute_y_d};
 use num_traits::{CheckedDiv, CheckedMul, ToPrimitive};
 
 pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
@@ -56,6 +71,20 @@ pub type AssetIdOf<T, I = ()> =
     <<T as Config<I>>::Assets as Inspect<<T as frame_system::Config>::AccountId>>::AssetId;
 pub type BalanceOf<T, I = ()> =
     <<T as Config<I>>::Assets as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+pub type MultiAssetPoolOf<T, I = ()> =
+    MultiAssetPool<AssetIdOf<T, I>, BalanceOf<T, I>, <T as Config<I>>::MaxPoolAssets>;
+
+/// Utility type for managing upgrades/migrations.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum Versions {
+    V1,
+    V2,
+}
+
+/// The max amplification coefficient of a pool
+pub const MAX_AMPLIFICATION: u128 = 1_000_000;
+/// The max factor an amplification coefficient can change by in one ramp
+pub const MAX_AMPLIFICATION_CHANGE: u128 = 10;
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -104,12 +133,29 @@ pub mod pallet {
         #[pallet::constant]
         type Precision: Get<u128>;
 
-        /// Optimal Amplification Coefficient
+        /// Optimal Amplification Coefficient, pools use it until theirs is ramped
         #[pallet::constant]
         type AmplificationCoefficient: Get<u8>;
 
-        /// Specify which origin is allowed to create new pools.
+        /// The max count of assets in a pool of several assets
+        #[pallet::constant]
+        type MaxPoolAssets: Get<u32>;
+
+        /// The min count of blocks an amplification coefficient ramp lasts
+        #[pallet::constant]
+        type MinRampBlocks: Get<Self::BlockNumber>;
+
+        /// Specify which origin is allowed to create new pools, ramp their
+        /// amplification coefficient and scale their assets by exchange rates.
         type CreatePoolOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// The exchange rates of liquid staking assets, used to scale the balances
+        /// of rate scaled assets
+        type LiquidStakingExchangeRateProvider: ExchangeRateProvider<AssetIdOf<Self, I>>;
+
+        /// Notified when the stable swap starts trading a pair, such as the router
+        /// keeping its pool graph
+        type PoolsHook: PoolsHook<AssetIdOf<Self, I>>;
     }
 
     #[pallet::error]
@@ -136,6 +182,20 @@ pub mod pallet {
         IdenticalAssets,
         /// Not an ideal price ratio
         NotAnIdealPrice,
+        /// Pool assets are duplicated or their count is out of bounds
+        InvalidPoolAssets,
+        /// Amounts don't match the assets of the pool
+        MismatchedAmounts,
+        /// Asset is not traded by the pool
+        AssetNotInPool,
+        /// Amounts are worse than the accepted limit
+        SlippageExceeded,
+        /// Amplification coefficient is zero, too large or changes too much
+        InvalidAmplification,
+        /// Amplification coefficient ramp ends too early
+        InvalidRampBlock,
+        /// Exchange rate of a rate scaled asset is not available
+        MissingExchangeRate,
     }
 
     #[pallet::event]
@@ -185,6 +245,52 @@ pub mod pallet {
             BalanceOf<T, I>,
             BalanceOf<T, I>,
         ),
+        /// Create a pool of several assets
+        /// [lptoken_receiver, assets, lp_token_id, amplification]
+        MultiAssetPoolCreated(T::AccountId, Vec<AssetIdOf<T, I>>, AssetIdOf<T, I>, u128),
+        /// Add liquidity into a pool of several assets
+        /// [sender, lp_token_id, amounts_added, liquidity]
+        MultiAssetLiquidityAdded(
+            T::AccountId,
+            AssetIdOf<T, I>,
+            Vec<BalanceOf<T, I>>,
+            BalanceOf<T, I>,
+        ),
+        /// Remove liquidity from a pool of several assets
+        /// [sender, lp_token_id, amounts_removed, liquidity]
+        MultiAssetLiquidityRemoved(
+            T::AccountId,
+            AssetIdOf<T, I>,
+            Vec<BalanceOf<T, I>>,
+            BalanceOf<T, I>,
+        ),
+        /// Start ramping the amplification coefficient of a pool
+        /// [lp_token_id, initial_amplification, future_amplification, initial_block, future_block]
+        AmplificationRampStarted(AssetIdOf<T, I>, u128, u128, T::BlockNumber, T::BlockNumber),
+        /// Stop ramping the amplification coefficient of a pool
+        /// [lp_token_id, amplification]
+        AmplificationRampStopped(AssetIdOf<T, I>, u128),
+        /// Update the assets of a pool scaled by their exchange rate
+        /// [lp_token_id, assets]
+        RateScaledAssetsUpdated(AssetIdOf<T, I>, Vec<AssetIdOf<T, I>>),
+        /// Add liquidity to a pool from a single asset
+        /// [sender, lp_token_id, currency_id_in, amount_in, liquidity]
+        ZappedIn(
+            T::AccountId,
+            AssetIdOf<T, I>,
+            AssetIdOf<T, I>,
+            BalanceOf<T, I>,
+            BalanceOf<T, I>,
+        ),
+        /// Remove liquidity from a pool into a single asset
+        /// [sender, lp_token_id, currency_id_out, liquidity, amount_out]
+        ZappedOut(
+            T::AccountId,
+            AssetIdOf<T, I>,
+            AssetIdOf<T, I>,
+            BalanceOf<T, I>,
+            BalanceOf<T, I>,
+        ),
     }
 
     #[pallet::pallet]
@@ -203,6 +309,72 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// The pair of the pool of two assets, keyed by its lp token
+    #[pallet::storage]
+    #[pallet::getter(fn lp_token_pairs)]
+    pub type LpTokenPairs<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        OptionQuery,
+    >;
+
+    /// A bag of liquidity composed by several different assets, keyed by its lp token
+    #[pallet::storage]
+    #[pallet::getter(fn multi_asset_pools)]
+    pub type MultiAssetPools<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T, I>, MultiAssetPoolOf<T, I>, OptionQuery>;
+
+    /// The lp token of the pool of several assets trading a pair, the first pool
+    /// created with the pair trades it
+    #[pallet::storage]
+    #[pallet::getter(fn multi_asset_pool_pairs)]
+    pub type MultiAssetPoolPairs<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        AssetIdOf<T, I>,
+        OptionQuery,
+    >;
+
+    /// The amplification coefficient of pools keyed by their lp token, pools
+    /// without one use `AmplificationCoefficient`
+    #[pallet::storage]
+    #[pallet::getter(fn amplifications)]
+    pub type Amplifications<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        AmplificationRamp<T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// The assets of pools scaled by their exchange rate, keyed by the lp token of
+    /// the pool
+    #[pallet::storage]
+    #[pallet::getter(fn rate_scaled_assets)]
+    pub type RateScaledAssets<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        BoundedVec<AssetIdOf<T, I>, T::MaxPoolAssets>,
+        ValueQuery,
+    >;
+
+    /// DefaultVersion is using for initialize the StorageVersion
+    #[pallet::type_value]
+    pub(super) fn DefaultVersion<T: Config<I>, I: 'static>() -> Versions {
+        Versions::V1
+    }
+
+    /// Storage version of the pallet.
+    #[pallet::storage]
+    pub(crate) type StorageVersion<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, Versions, ValueQuery, DefaultVersion<T, I>>;
+
     // No Extrinsic Calls
     #[pallet::call]
     impl<T: Config<I>, I: 'static> Pallet<T, I> {
@@ -255,7 +427,7 @@ pub mod pallet {
                         Error::<T, I>::NotAnIdealPrice
                     );
 
-                    Self::do_mint_protocol_fee(pool)?;
+                    Self::do_mint_protocol_fee(pool, (base_asset, quote_asset))?;
 
                     // Adds liquidity
                     Self::do_add_liquidity(
@@ -311,7 +483,7 @@ pub mod pallet {
             Pools::<T, I>::try_mutate(base_asset, quote_asset, |pool| -> DispatchResult {
                 let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
 
-                Self::do_mint_protocol_fee(pool)?;
+                Self::do_mint_protocol_fee(pool, (base_asset, quote_asset))?;
 
                 let (base_amount_removed, quote_amount_removed) =
                     Self::do_remove_liquidity(&who, pool, liquidity, (base_asset, quote_asset))?;
@@ -372,6 +544,8 @@ pub mod pallet {
                 Error::<T, I>::LpTokenAlreadyExists
             );
 
+            Self::ensure_rate_scaled_assets_in_pool(lp_token_id, &[base_asset, quote_asset])?;
+
             let mut pool = Pool::new(lp_token_id);
 
             Self::deposit_event(Event::<T, I>::PoolCreated(
@@ -388,7 +562,11 @@ pub mod pallet {
                 (base_asset, quote_asset),
             )?;
 
+            if !MultiAssetPoolPairs::<T, I>::contains_key(base_asset, quote_asset) {
+                T::PoolsHook::on_pool_created((base_asset, quote_asset));
+            }
             Pools::<T, I>::insert(base_asset, quote_asset, pool);
+            LpTokenPairs::<T, I>::insert(lp_token_id, (base_asset, quote_asset));
 
             log::trace!(
                 target: "stableswap::create_pool",
@@ -415,6 +593,607 @@ pub mod pallet {
 
             Ok(().into())
         }
+
+        /// Create a pool of several assets
+        ///
+        /// - `assets`: the assets traded by the pool
+        /// - `liquidity_amounts`: the initial liquidity of each asset
+        /// - `amplification`: the amplification coefficient of the pool
+        /// - `lptoken_receiver`: the account receiving the lp tokens
+        /// - `lp_token_id`: the lp token of the pool, it identifies the pool
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::create_multi_asset_pool())]
+        #[transactional]
+        pub fn create_multi_asset_pool(
+            origin: OriginFor<T>,
+            assets: Vec<AssetIdOf<T, I>>,
+            liquidity_amounts: Vec<BalanceOf<T, I>>,
+            amplification: u128,
+            lptoken_receiver: T::AccountId,
+            lp_token_id: AssetIdOf<T, I>,
+        ) -> DispatchResultWithPostInfo {
+            T::CreatePoolOrigin::ensure_origin(origin)?;
+
+            // check for duplicates with O(n^2) complexity, the count of assets is capped
+            ensure!(
+                assets.len() >= 2
+                    && (1..assets.len()).all(|i| !assets[i..].contains(&assets[i - 1])),
+                Error::<T, I>::InvalidPoolAssets
+            );
+            let assets: BoundedVec<AssetIdOf<T, I>, T::MaxPoolAssets> = assets
+                .try_into()
+                .map_err(|_| Error::<T, I>::InvalidPoolAssets)?;
+            ensure!(
+                liquidity_amounts.len() == assets.len(),
+                Error::<T, I>::MismatchedAmounts
+            );
+            ensure!(
+                liquidity_amounts.iter().all(|amount| !amount.is_zero()),
+                Error::<T, I>::InsufficientAmountIn
+            );
+            ensure!(
+                !amplification.is_zero() && amplification <= MAX_AMPLIFICATION,
+                Error::<T, I>::InvalidAmplification
+            );
+            ensure!(
+                !MultiAssetPools::<T, I>::contains_key(lp_token_id),
+                Error::<T, I>::PoolAlreadyExists
+            );
+
+            // check that this is a new asset to avoid using an asset that
+            // already has tokens minted
+            ensure!(
+                T::Assets::total_issuance(lp_token_id).is_zero(),
+                Error::<T, I>::LpTokenAlreadyExists
+            );
+
+            Self::ensure_rate_scaled_assets_in_pool(lp_token_id, &assets)?;
+
+            let balances: BoundedVec<BalanceOf<T, I>, T::MaxPoolAssets> =
+                vec![Zero::zero(); assets.len()]
+                    .try_into()
+                    .map_err(|_| Error::<T, I>::InvalidPoolAssets)?;
+            let mut pool = MultiAssetPool {
+                assets,
+                balances,
+                lp_token_id,
+            };
+            Amplifications::<T, I>::insert(
+                lp_token_id,
+                AmplificationRamp::new(amplification, frame_system::Pallet::<T>::block_number()),
+            );
+
+            let liquidity = Self::do_add_multi_asset_liquidity(
+                &lptoken_receiver,
+                &mut pool,
+                &liquidity_amounts,
+            )?;
+
+            for (i, asset_a) in pool.assets.iter().enumerate() {
+                for asset_b in pool.assets.iter().skip(i + 1) {
+                    let (_, base_asset, quote_asset) = Self::sort_assets((*asset_a, *asset_b))?;
+                    if !MultiAssetPoolPairs::<T, I>::contains_key(base_asset, quote_asset) {
+                        if !Pools::<T, I>::contains_key(base_asset, quote_asset) {
+                            T::PoolsHook::on_pool_created((base_asset, quote_asset));
+                        }
+                        MultiAssetPoolPairs::<T, I>::insert(base_asset, quote_asset, lp_token_id);
+                    }
+                }
+            }
+            let assets = pool.assets.to_vec();
+            MultiAssetPools::<T, I>::insert(lp_token_id, pool);
+
+            log::trace!(
+                target: "stableswap::create_multi_asset_pool",
+                "lptoken_receiver: {:?}, assets: {:?}, liquidity_amounts: {:?}, amplification: {:?}, \
+                 liquidity: {:?}",
+                &lptoken_receiver,
+                &assets,
+                &liquidity_amounts,
+                &amplification,
+                &liquidity
+            );
+
+            Self::deposit_event(Event::<T, I>::MultiAssetPoolCreated(
+                lptoken_receiver.clone(),
+                assets,
+                lp_token_id,
+                amplification,
+            ));
+            Self::deposit_event(Event::<T, I>::MultiAssetLiquidityAdded(
+                lptoken_receiver,
+                lp_token_id,
+                liquidity_amounts,
+                liquidity,
+            ));
+
+            Ok(().into())
+        }
+
+        /// Add liquidity to a pool of several assets, in any proportion
+        ///
+        /// Amounts off the ratio of the pool pay a fee on their imbalance.
+        ///
+        /// - `lp_token_id`: the lp token of the pool
+        /// - `amounts`: the amount of each asset added, can be zero
+        /// - `min_liquidity`: the minimum amount of lp tokens minted
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::add_multi_asset_liquidity())]
+        #[transactional]
+        pub fn add_multi_asset_liquidity(
+            origin: OriginFor<T>,
+            lp_token_id: AssetIdOf<T, I>,
+            amounts: Vec<BalanceOf<T, I>>,
+            #[pallet::compact] min_liquidity: BalanceOf<T, I>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            MultiAssetPools::<T, I>::try_mutate(lp_token_id, |pool| -> DispatchResult {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+
+                let liquidity = Self::do_add_multi_asset_liquidity(&who, pool, &amounts)?;
+                ensure!(liquidity >= min_liquidity, Error::<T, I>::SlippageExceeded);
+
+                log::trace!(
+                    target: "stableswap::add_multi_asset_liquidity",
+                    "who: {:?}, lp_token_id: {:?}, amounts: {:?}, liquidity: {:?}",
+                    &who,
+                    &lp_token_id,
+                    &amounts,
+                    &liquidity
+                );
+
+                Self::deposit_event(Event::<T, I>::MultiAssetLiquidityAdded(
+                    who,
+                    lp_token_id,
+                    amounts,
+                    liquidity,
+                ));
+
+                Ok(())
+            })
+        }
+
+        /// Remove liquidity from a pool of several assets, in the ratio of the pool
+        ///
+        /// - `lp_token_id`: the lp token of the pool
+        /// - `liquidity`: the amount of lp tokens burnt
+        /// - `min_amounts`: the minimum amount of each asset removed
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::remove_multi_asset_liquidity())]
+        #[transactional]
+        pub fn remove_multi_asset_liquidity(
+            origin: OriginFor<T>,
+            lp_token_id: AssetIdOf<T, I>,
+            #[pallet::compact] liquidity: BalanceOf<T, I>,
+            min_amounts: Vec<BalanceOf<T, I>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            MultiAssetPools::<T, I>::try_mutate(lp_token_id, |pool| -> DispatchResult {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+                ensure!(
+                    min_amounts.len() == pool.assets.len(),
+                    Error::<T, I>::MismatchedAmounts
+                );
+                ensure!(!liquidity.is_zero(), Error::<T, I>::InsufficientLiquidity);
+
+                let total_supply = T::Assets::total_issuance(lp_token_id);
+                let amounts = pool
+                    .balances
+                    .iter()
+                    .map(|balance| {
+                        multiply_by_rational_with_rounding(
+                            *balance,
+                            liquidity,
+                            total_supply,
+                            Rounding::Down,
+                        )
+                        .ok_or(ArithmeticError::Overflow)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                ensure!(
+                    amounts
+                        .iter()
+                        .zip(min_amounts.iter())
+                        .all(|(amount, min_amount)| amount >= min_amount),
+                    Error::<T, I>::SlippageExceeded
+                );
+
+                Self::do_remove_multi_asset_liquidity(&who, pool, &amounts, liquidity)?;
+
+                Self::deposit_event(Event::<T, I>::MultiAssetLiquidityRemoved(
+                    who,
+                    lp_token_id,
+                    amounts,
+                    liquidity,
+                ));
+
+                Ok(())
+            })
+        }
+
+        /// Remove exact amounts of liquidity from a pool of several assets, in any
+        /// proportion
+        ///
+        /// Amounts off the ratio of the pool pay a fee on their imbalance.
+        ///
+        /// - `lp_token_id`: the lp token of the pool
+        /// - `amounts`: the amount of each asset removed, can be zero
+        /// - `max_liquidity`: the maximum amount of lp tokens burnt
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::remove_multi_asset_liquidity_imbalance())]
+        #[transactional]
+        pub fn remove_multi_asset_liquidity_imbalance(
+            origin: OriginFor<T>,
+            lp_token_id: AssetIdOf<T, I>,
+            amounts: Vec<BalanceOf<T, I>>,
+            #[pallet::compact] max_liquidity: BalanceOf<T, I>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            MultiAssetPools::<T, I>::try_mutate(lp_token_id, |pool| -> DispatchResult {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+                ensure!(
+                    amounts.len() == pool.assets.len(),
+                    Error::<T, I>::MismatchedAmounts
+                );
+                ensure!(
+                    amounts.iter().any(|amount| !amount.is_zero()),
+                    Error::<T, I>::InsufficientAmountOut
+                );
+
+                let amp = Self::amplification(lp_token_id);
+                let rates = Self::multi_asset_exchange_rates(pool)?;
+                let total_supply = T::Assets::total_issuance(lp_token_id);
+                let new_balances = pool
+                    .balances
+                    .iter()
+                    .zip(amounts.iter())
+                    .map(|(balance, amount)| {
+                        balance
+                            .checked_sub(*amount)
+                            .ok_or(Error::<T, I>::InsufficientLiquidity)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let old_balances = Self::scaled_balances(&pool.balances, &rates)?;
+                let new_balances = Self::scaled_balances(&new_balances, &rates)?;
+
+                let d0 = compute_d_n(&old_balances, amp)?;
+                let d1 = compute_d_n(&new_balances, amp)?;
+                let balances_after_fees =
+                    Self::balances_after_imbalance_fees(&old_balances, &new_balances, d0, d1)?;
+                let d2 = compute_d_n(&balances_after_fees, amp)?;
+
+                let liquidity = multiply_by_rational_with_rounding(
+                    total_supply,
+                    d0.saturating_sub(d2),
+                    d0,
+                    Rounding::Up,
+                )
+                .ok_or(ArithmeticError::Overflow)?;
+                ensure!(liquidity <= max_liquidity, Error::<T, I>::SlippageExceeded);
+
+                Self::do_remove_multi_asset_liquidity(&who, pool, &amounts, liquidity)?;
+
+                Self::deposit_event(Event::<T, I>::MultiAssetLiquidityRemoved(
+                    who,
+                    lp_token_id,
+                    amounts,
+                    liquidity,
+                ));
+
+                Ok(())
+            })
+        }
+
+        /// Remove liquidity from a pool of several assets as a single asset
+        ///
+        /// - `lp_token_id`: the lp token of the pool
+        /// - `liquidity`: the amount of lp tokens burnt
+        /// - `asset_id`: the asset removed
+        /// - `min_amount`: the minimum amount of the asset removed
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::remove_multi_asset_liquidity_one_asset())]
+        #[transactional]
+        pub fn remove_multi_asset_liquidity_one_asset(
+            origin: OriginFor<T>,
+            lp_token_id: AssetIdOf<T, I>,
+            #[pallet::compact] liquidity: BalanceOf<T, I>,
+            asset_id: AssetIdOf<T, I>,
+            #[pallet::compact] min_amount: BalanceOf<T, I>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            MultiAssetPools::<T, I>::try_mutate(lp_token_id, |pool| -> DispatchResult {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+                let index = pool
+                    .index_of(&asset_id)
+                    .ok_or(Error::<T, I>::AssetNotInPool)?;
+
+                let amount = Self::get_withdraw_one_asset_amount(pool, liquidity, index)?;
+                ensure!(amount >= min_amount, Error::<T, I>::SlippageExceeded);
+
+                let mut amounts = vec![Zero::zero(); pool.assets.len()];
+                amounts[index] = amount;
+                Self::do_remove_multi_asset_liquidity(&who, pool, &amounts, liquidity)?;
+
+                Self::deposit_event(Event::<T, I>::MultiAssetLiquidityRemoved(
+                    who,
+                    lp_token_id,
+                    amounts,
+                    liquidity,
+                ));
+
+                Ok(())
+            })
+        }
+
+        /// Ramp the amplification coefficient of a pool linearly from its current
+        /// value, until `future_block`
+        ///
+        /// - `lp_token_id`: the lp token of the pool
+        /// - `future_amplification`: the amplification coefficient once the ramp ends
+        /// - `future_block`: the block the ramp ends at
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::ramp_amplification())]
+        #[transactional]
+        pub fn ramp_amplification(
+            origin: OriginFor<T>,
+            lp_token_id: AssetIdOf<T, I>,
+            future_amplification: u128,
+            future_block: T::BlockNumber,
+        ) -> DispatchResult {
+            T::CreatePoolOrigin::ensure_origin(origin)?;
+            ensure!(
+                Self::pool_exists(lp_token_id),
+                Error::<T, I>::PoolDoesNotExist
+            );
+
+            let block_number = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                future_block >= block_number.saturating_add(T::MinRampBlocks::get()),
+                Error::<T, I>::InvalidRampBlock
+            );
+
+            let initial_amplification = Self::amplification(lp_token_id);
+            ensure!(
+                !future_amplification.is_zero()
+                    && future_amplification <= MAX_AMPLIFICATION
+                    && future_amplification
+                        <= initial_amplification.saturating_mul(MAX_AMPLIFICATION_CHANGE)
+                    && initial_amplification
+                        <= future_amplification.saturating_mul(MAX_AMPLIFICATION_CHANGE),
+                Error::<T, I>::InvalidAmplification
+            );
+
+            Amplifications::<T, I>::insert(
+                lp_token_id,
+                AmplificationRamp {
+                    initial_amplification,
+                    future_amplification,
+                    initial_block: block_number,
+                    future_block,
+                },
+            );
+
+            Self::deposit_event(Event::<T, I>::AmplificationRampStarted(
+                lp_token_id,
+                initial_amplification,
+                future_amplification,
+                block_number,
+                future_block,
+            ));
+
+            Ok(())
+        }
+
+        /// Stop ramping the amplification coefficient of a pool, it keeps its
+        /// current value
+        ///
+        /// - `lp_token_id`: the lp token of the pool
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::stop_ramp_amplification())]
+        #[transactional]
+        pub fn stop_ramp_amplification(
+            origin: OriginFor<T>,
+            lp_token_id: AssetIdOf<T, I>,
+        ) -> DispatchResult {
+            T::CreatePoolOrigin::ensure_origin(origin)?;
+            ensure!(
+                Self::pool_exists(lp_token_id),
+                Error::<T, I>::PoolDoesNotExist
+            );
+
+            let amplification = Self::amplification(lp_token_id);
+            Amplifications::<T, I>::insert(
+                lp_token_id,
+                AmplificationRamp::new(amplification, frame_system::Pallet::<T>::block_number()),
+            );
+
+            Self::deposit_event(Event::<T, I>::AmplificationRampStopped(
+                lp_token_id,
+                amplification,
+            ));
+
+            Ok(())
+        }
+
+        /// Set the assets of a pool scaled by their exchange rate, the balances of
+        /// these assets are multiplied by their rate before applying the invariant.
+        ///
+        /// Scaling changes the invariant of a pool, so it's set before the pool is
+        /// created and the pool must trade these assets.
+        ///
+        /// - `lp_token_id`: the lp token of the pool to create
+        /// - `assets`: the rate scaled assets, none if empty
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::set_rate_scaled_assets())]
+        #[transactional]
+        pub fn set_rate_scaled_assets(
+            origin: OriginFor<T>,
+            lp_token_id: AssetIdOf<T, I>,
+            assets: Vec<AssetIdOf<T, I>>,
+        ) -> DispatchResult {
+            T::CreatePoolOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                Self::pool_assets(lp_token_id).is_none(),
+                Error::<T, I>::PoolAlreadyExists
+            );
+            for asset_id in assets.iter() {
+                ensure!(
+                    T::LiquidStakingExchangeRateProvider::get_exchange_rate(asset_id).is_some(),
+                    Error::<T, I>::MissingExchangeRate
+                );
+            }
+
+            let rate_scaled_assets: BoundedVec<AssetIdOf<T, I>, T::MaxPoolAssets> = assets
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T, I>::InvalidPoolAssets)?;
+            if rate_scaled_assets.is_empty() {
+                RateScaledAssets::<T, I>::remove(lp_token_id);
+            } else {
+                RateScaledAssets::<T, I>::insert(lp_token_id, rate_scaled_assets);
+            }
+
+            Self::deposit_event(Event::<T, I>::RateScaledAssetsUpdated(lp_token_id, assets));
+
+            Ok(())
+        }
+
+        /// Add liquidity to a pool from a single asset
+        ///
+        /// Pools of two assets swap the fraction of the amount that balances the pool
+        /// first, pools of several assets take it as an imbalanced deposit.
+        ///
+        /// - `lp_token_id`: the lp token of the pool
+        /// - `asset_id`: the asset added
+        /// - `amount_in`: the amount of the asset added
+        /// - `min_liquidity`: the minimum amount of lp tokens minted
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::zap_in())]
+        #[transactional]
+        pub fn zap_in(
+            origin: OriginFor<T>,
+            lp_token_id: AssetIdOf<T, I>,
+            asset_id: AssetIdOf<T, I>,
+            #[pallet::compact] amount_in: BalanceOf<T, I>,
+            #[pallet::compact] min_liquidity: BalanceOf<T, I>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!amount_in.is_zero(), Error::<T, I>::InsufficientAmountIn);
+
+            let liquidity = if MultiAssetPools::<T, I>::contains_key(lp_token_id) {
+                MultiAssetPools::<T, I>::try_mutate(
+                    lp_token_id,
+                    |pool| -> Result<BalanceOf<T, I>, DispatchError> {
+                        let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+                        let index = pool
+                            .index_of(&asset_id)
+                            .ok_or(Error::<T, I>::AssetNotInPool)?;
+
+                        let mut amounts = vec![Zero::zero(); pool.assets.len()];
+                        amounts[index] = amount_in;
+                        Self::do_add_multi_asset_liquidity(&who, pool, &amounts)
+                    },
+                )?
+            } else {
+                let paired_asset = Self::paired_asset(lp_token_id, asset_id)?;
+                Self::do_zap_in_pair(&who, (asset_id, paired_asset), amount_in)?
+            };
+            ensure!(liquidity >= min_liquidity, Error::<T, I>::SlippageExceeded);
+
+            log::trace!(
+                target: "stableswap::zap_in",
+                "who: {:?}, lp_token_id: {:?}, asset_id: {:?}, amount_in: {:?}, liquidity: {:?}",
+                &who,
+                &lp_token_id,
+                &asset_id,
+                &amount_in,
+                &liquidity
+            );
+
+            Self::deposit_event(Event::<T, I>::ZappedIn(
+                who,
+                lp_token_id,
+                asset_id,
+                amount_in,
+                liquidity,
+            ));
+
+            Ok(())
+        }
+
+        /// Remove liquidity from a pool into a single asset
+        ///
+        /// Pools of two assets swap the other asset removed back, pools of several
+        /// assets withdraw the single asset.
+        ///
+        /// - `lp_token_id`: the lp token of the pool
+        /// - `liquidity`: the amount of lp tokens burnt
+        /// - `asset_id`: the asset received
+        /// - `min_amount_out`: the minimum amount of the asset received
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::WeightInfo::zap_out())]
+        #[transactional]
+        pub fn zap_out(
+            origin: OriginFor<T>,
+            lp_token_id: AssetIdOf<T, I>,
+            #[pallet::compact] liquidity: BalanceOf<T, I>,
+            asset_id: AssetIdOf<T, I>,
+            #[pallet::compact] min_amount_out: BalanceOf<T, I>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!liquidity.is_zero(), Error::<T, I>::InsufficientLiquidity);
+
+            let amount_out = if MultiAssetPools::<T, I>::contains_key(lp_token_id) {
+                MultiAssetPools::<T, I>::try_mutate(
+                    lp_token_id,
+                    |pool| -> Result<BalanceOf<T, I>, DispatchError> {
+                        let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+                        let index = pool
+                            .index_of(&asset_id)
+                            .ok_or(Error::<T, I>::AssetNotInPool)?;
+                        let amount_out =
+                            Self::get_withdraw_one_asset_amount(pool, liquidity, index)?;
+
+                        let mut amounts = vec![Zero::zero(); pool.assets.len()];
+                        amounts[index] = amount_out;
+                        Self::do_remove_multi_asset_liquidity(&who, pool, &amounts, liquidity)?;
+                        Ok(amount_out)
+                    },
+                )?
+            } else {
+                let paired_asset = Self::paired_asset(lp_token_id, asset_id)?;
+                Self::do_zap_out_pair(&who, (asset_id, paired_asset), liquidity)?
+            };
+            ensure!(
+                amount_out >= min_amount_out,
+                Error::<T, I>::SlippageExceeded
+            );
+
+            log::trace!(
+                target: "stableswap::zap_out",
+                "who: {:?}, lp_token_id: {:?}, asset_id: {:?}, liquidity: {:?}, amount_out: {:?}",
+                &who,
+                &lp_token_id,
+                &asset_id,
+                &liquidity,
+                &amount_out
+            );
+
+            Self::deposit_event(Event::<T, I>::ZappedOut(
+                who,
+                lp_token_id,
+                asset_id,
+                liquidity,
+                amount_out,
+            ));
+
+            Ok(())
+        }
     }
 }
 
@@ -431,8 +1210,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
         amounts_in[amount_len - 1] = amount_out;
         for i in (1..(path.len())).rev() {
-            let (reserve_in, reserve_out) = Self::get_reserves(path[i - 1], path[i])?;
-            let amount_in = Self::get_amount_in(amounts_in[i], reserve_in, reserve_out)?;
+            let amount_in = Self::get_hop_amount_in(amounts_in[i], path[i - 1], path[i])?;
             amounts_in[i - 1] = amount_in;
         }
 
@@ -443,6 +1221,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         amount_in: BalanceOf<T, I>,
         pool_base_aum: BalanceOf<T, I>,
         pool_quote_aum: BalanceOf<T, I>,
+        amp: u128,
     ) -> Result<BalanceOf<T, I>, DispatchError> {
         let fees = T::LpFee::get()
             .checked_add(&T::ProtocolFee::get())
@@ -453,12 +1232,11 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             .checked_sub(fees)
             .ok_or(ArithmeticError::Underflow)?;
 
-        let amp = T::AmplificationCoefficient::get() as u128;
         // d = 2000000
         // poolbaseamount = 1000000
         // amountin = 997
         // new quote amount = 1000000 + 997
-        let d = Self::delta_util(pool_base_aum, pool_quote_aum)?;
+        let d = Self::delta_util(pool_base_aum, pool_quote_aum, amp)?;
         let new_quote_amount = pool_quote_aum
             .checked_add(amount_in)
             .ok_or(ArithmeticError::Overflow)?;
@@ -616,7 +1394,8 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         pool: &mut Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
         (ideal_base_amount, ideal_quote_amount): (BalanceOf<T, I>, BalanceOf<T, I>),
         (base_asset, quote_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
-    ) -> Result<(), DispatchError> {
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let amp = Self::amplification(pool.lp_token_id);
         // Initial invariant
         let mut d0 = 0u128;
         let mut d1 = 0u128;
@@ -624,7 +1403,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             // d0 = Self::do_get_delta((base_asset, quote_asset)).unwrap();
             let (tot_base_amount, tot_quote_amount) =
                 Self::get_reserves(base_asset, quote_asset).unwrap();
-            d0 = Self::delta_util(tot_base_amount, tot_quote_amount).unwrap()
+            d0 = Self::delta_util(tot_base_amount, tot_quote_amount, amp).unwrap()
         }
 
         let total_supply = T::Assets::total_issuance(pool.lp_token_id);
@@ -648,7 +1427,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         let new_quote_amount = pool.quote_amount;
 
         if Pools::<T, I>::contains_key(base_asset, quote_asset) {
-            d1 = Self::do_get_delta_on_the_fly((new_base_amount, new_quote_amount)).unwrap();
+            d1 = Self::do_get_delta_on_the_fly((new_base_amount, new_quote_amount), amp).unwrap();
 
             ensure!(d1 >= d0, Error::<T, I>::InvalidInvariant);
         }
@@ -709,7 +1488,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             &ideal_quote_amount
         );
 
-        Ok(())
+        Ok(liquidity)
     }
 
     fn calculate_reserves_to_remove(
@@ -783,6 +1562,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
     #[require_transactional]
     pub fn do_mint_protocol_fee(
         pool: &mut Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
+        (base_asset, quote_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
     ) -> Result<BalanceOf<T, I>, DispatchError> {
         // TODO: If we turn off protocol_fee later in runtime upgrade
         // this will reset root_k_last to zero which may not be good
@@ -804,16 +1584,29 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             return Ok(Zero::zero());
         }
 
-        let root_k_last = Self::delta_util(pool.base_amount_last, pool.quote_amount_last)
-            .unwrap()
-            .get_big_uint();
+        // scale both invariants by the current rates, so that the growth of the
+        // exchange rates isn't taken as fees
+        let amp = Self::amplification(pool.lp_token_id);
+        let base_rate = Self::exchange_rate(pool.lp_token_id, base_asset)?;
+        let quote_rate = Self::exchange_rate(pool.lp_token_id, quote_asset)?;
+        let root_k_last = Self::delta_util(
+            Self::scale_up(pool.base_amount_last, base_rate)?,
+            Self::scale_up(pool.quote_amount_last, quote_rate)?,
+            amp,
+        )
+        .unwrap()
+        .get_big_uint();
 
         // if the early exits do not return we know that k_last is not zero
         // and that protocol fees are on
 
-        let root_k = Self::delta_util(pool.base_amount, pool.quote_amount)
-            .unwrap()
-            .get_big_uint();
+        let root_k = Self::delta_util(
+            Self::scale_up(pool.base_amount, base_rate)?,
+            Self::scale_up(pool.quote_amount, quote_rate)?,
+            amp,
+        )
+        .unwrap()
+        .get_big_uint();
 
         if root_k <= root_k_last {
             return Ok(Zero::zero());
@@ -861,12 +1654,132 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         Ok(protocol_fees)
     }
 
-    fn do_swap(
+    fn do_swap(
+        who: &T::AccountId,
+        (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        amount_in: BalanceOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
+        if !Pools::<T, I>::contains_key(base_asset, quote_asset) {
+            return Self::do_multi_asset_swap(who, (asset_in, asset_out), amount_in);
+        }
+
+        Pools::<T, I>::try_mutate(
+            base_asset,
+            quote_asset,
+            |pool| -> Result<BalanceOf<T, I>, DispatchError> {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+
+                let amount_out =
+                    Self::do_pair_swap(who, pool, (asset_in, asset_out), is_inverted, amount_in)?;
+
+                Self::deposit_event(Event::<T, I>::Traded(
+                    who.clone(),
+                    asset_in,
+                    asset_out,
+                    amount_in,
+                    amount_out,
+                    pool.lp_token_id,
+                    pool.quote_amount,
+                    pool.base_amount,
+                ));
+
+                Ok(amount_out)
+            },
+        )
+    }
+
+    // trades against the reserves of a pool of two assets without depositing an
+    // event, the callers report the trade
+    fn do_pair_swap(
+        who: &T::AccountId,
+        pool: &mut Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
+        (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        is_inverted: bool,
+        amount_in: BalanceOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let (supply_in, supply_out) = if is_inverted {
+            (pool.quote_amount, pool.base_amount)
+        } else {
+            (pool.base_amount, pool.quote_amount)
+        };
+
+        ensure!(
+            amount_in >= T::LpFee::get().saturating_reciprocal_mul_floor(One::one()),
+            Error::<T, I>::InsufficientAmountIn
+        );
+        ensure!(!supply_out.is_zero(), Error::<T, I>::InsufficientAmountOut);
+
+        let amount_out = Self::get_scaled_amount_out(
+            amount_in,
+            (supply_in, supply_out),
+            (
+                Self::exchange_rate(pool.lp_token_id, asset_in)?,
+                Self::exchange_rate(pool.lp_token_id, asset_out)?,
+            ),
+            Self::amplification(pool.lp_token_id),
+        )?;
+
+        let (new_supply_in, new_supply_out) = (
+            supply_in
+                .checked_add(amount_in)
+                .ok_or(ArithmeticError::Overflow)?,
+            supply_out
+                .checked_sub(amount_out)
+                .ok_or(ArithmeticError::Underflow)?,
+        );
+
+        if is_inverted {
+            pool.quote_amount = new_supply_in;
+            pool.base_amount = new_supply_out;
+        } else {
+            pool.base_amount = new_supply_in;
+            pool.quote_amount = new_supply_out;
+        }
+
+        Self::do_update_oracle(pool)?;
+
+        T::Assets::transfer(asset_in, who, &Self::account_id(), amount_in, true)?;
+        T::Assets::transfer(asset_out, &Self::account_id(), who, amount_out, false)?;
+
+        log::trace!(
+            target: "stableswap::do_trade",
+            "who: {:?}, asset_in: {:?}, asset_out: {:?}, amount_in: {:?}, amount_out: {:?}",
+            &who,
+            &asset_in,
+            &asset_out,
+            &amount_in,
+            &amount_out,
+        );
+
+        Ok(amount_out)
+    }
+
+    // the other asset of the pool of two assets with the lp token
+    fn paired_asset(
+        lp_token_id: AssetIdOf<T, I>,
+        asset_id: AssetIdOf<T, I>,
+    ) -> Result<AssetIdOf<T, I>, DispatchError> {
+        let assets = Self::pool_assets(lp_token_id).ok_or(Error::<T, I>::PoolDoesNotExist)?;
+        ensure!(assets.contains(&asset_id), Error::<T, I>::AssetNotInPool);
+        assets
+            .into_iter()
+            .find(|asset| *asset != asset_id)
+            .ok_or_else(|| Error::<T, I>::AssetNotInPool.into())
+    }
+
+    // swaps the part of the amount in balancing a pool of two assets, then adds both
+    // assets as liquidity. Near the peg the price is the ratio of the exchange rates,
+    // so with `a` the normalised amount in and `x_in`, `x_out` the normalised
+    // reserves, swapping `a * x_out / (a + x_in + x_out)` leaves both assets in the
+    // ratio of the pool. What is left off the ratio stays with the account.
+    #[require_transactional]
+    fn do_zap_in_pair(
         who: &T::AccountId,
-        (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        (asset_in, paired_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
         amount_in: BalanceOf<T, I>,
     ) -> Result<BalanceOf<T, I>, DispatchError> {
-        let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
+        let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, paired_asset))?;
 
         Pools::<T, I>::try_mutate(
             base_asset,
@@ -874,65 +1787,97 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             |pool| -> Result<BalanceOf<T, I>, DispatchError> {
                 let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
 
-                let (supply_in, supply_out) = if is_inverted {
+                let (reserve_in, reserve_out) = if is_inverted {
                     (pool.quote_amount, pool.base_amount)
                 } else {
                     (pool.base_amount, pool.quote_amount)
                 };
+                let rate_in = Self::exchange_rate(pool.lp_token_id, asset_in)?;
+                let rate_out = Self::exchange_rate(pool.lp_token_id, paired_asset)?;
+                let scaled_amount_in = Self::scale_up(amount_in, rate_in)?;
+                let scaled_reserve_in = Self::scale_up(reserve_in, rate_in)?;
+                let scaled_reserve_out = Self::scale_up(reserve_out, rate_out)?;
 
-                ensure!(
-                    amount_in >= T::LpFee::get().saturating_reciprocal_mul_floor(One::one()),
-                    Error::<T, I>::InsufficientAmountIn
-                );
-                ensure!(!supply_out.is_zero(), Error::<T, I>::InsufficientAmountOut);
-
-                //let amount_out = Self::get_amount_out(amount_in, supply_in, supply_out)?;
-                let amount_out = Self::get_amount_out(amount_in, supply_in, supply_out)?;
-
-                let (new_supply_in, new_supply_out) = (
-                    supply_in
-                        .checked_add(amount_in)
+                let swap_amount = multiply_by_rational_with_rounding(
+                    amount_in,
+                    scaled_reserve_out,
+                    scaled_amount_in
+                        .checked_add(scaled_reserve_in)
+                        .and_then(|r| r.checked_add(scaled_reserve_out))
                         .ok_or(ArithmeticError::Overflow)?,
-                    supply_out
-                        .checked_sub(amount_out)
-                        .ok_or(ArithmeticError::Underflow)?,
-                );
+                    Rounding::Down,
+                )
+                .ok_or(ArithmeticError::Overflow)?;
 
-                if is_inverted {
-                    pool.quote_amount = new_supply_in;
-                    pool.base_amount = new_supply_out;
+                let paired_amount = Self::do_pair_swap(
+                    who,
+                    pool,
+                    (asset_in, paired_asset),
+                    is_inverted,
+                    swap_amount,
+                )?;
+                let remaining_amount = amount_in
+                    .checked_sub(swap_amount)
+                    .ok_or(ArithmeticError::Underflow)?;
+
+                let (base_amount, quote_amount) = if is_inverted {
+                    (paired_amount, remaining_amount)
                 } else {
-                    pool.base_amount = new_supply_in;
-                    pool.quote_amount = new_supply_out;
-                }
+                    (remaining_amount, paired_amount)
+                };
+                let (ideal_base_amount, ideal_quote_amount) =
+                    Self::get_ideal_amounts(pool, (base_amount, quote_amount))?;
 
-                Self::do_update_oracle(pool)?;
+                Self::do_mint_protocol_fee(pool, (base_asset, quote_asset))?;
 
-                T::Assets::transfer(asset_in, who, &Self::account_id(), amount_in, true)?;
-                T::Assets::transfer(asset_out, &Self::account_id(), who, amount_out, false)?;
+                Self::do_add_liquidity(
+                    who,
+                    pool,
+                    (ideal_base_amount, ideal_quote_amount),
+                    (base_asset, quote_asset),
+                )
+            },
+        )
+    }
 
-                log::trace!(
-                    target: "stableswap::do_trade",
-                    "who: {:?}, asset_in: {:?}, asset_out: {:?}, amount_in: {:?}, amount_out: {:?}",
-                    &who,
-                    &asset_in,
-                    &asset_out,
-                    &amount_in,
-                    &amount_out,
-                );
+    // removes liquidity from a pool of two assets and swaps the paired asset removed
+    // into the asset out
+    #[require_transactional]
+    fn do_zap_out_pair(
+        who: &T::AccountId,
+        (asset_out, paired_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        liquidity: BalanceOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_out, paired_asset))?;
 
-                Self::deposit_event(Event::<T, I>::Traded(
-                    who.clone(),
-                    asset_in,
-                    asset_out,
-                    amount_in,
-                    amount_out,
-                    pool.lp_token_id,
-                    pool.quote_amount,
-                    pool.base_amount,
-                ));
+        Pools::<T, I>::try_mutate(
+            base_asset,
+            quote_asset,
+            |pool| -> Result<BalanceOf<T, I>, DispatchError> {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
 
-                Ok(amount_out)
+                Self::do_mint_protocol_fee(pool, (base_asset, quote_asset))?;
+
+                let (base_amount_removed, quote_amount_removed) =
+                    Self::do_remove_liquidity(who, pool, liquidity, (base_asset, quote_asset))?;
+                let (amount_removed, paired_amount) = if is_inverted {
+                    (quote_amount_removed, base_amount_removed)
+                } else {
+                    (base_amount_removed, quote_amount_removed)
+                };
+
+                // the paired asset is sold, so the pair is inverted from the sorting above
+                let amount_swapped = Self::do_pair_swap(
+                    who,
+                    pool,
+                    (paired_asset, asset_out),
+                    !is_inverted,
+                    paired_amount,
+                )?;
+
+                Ok(amount_removed
+                    .checked_add(amount_swapped)
+                    .ok_or(ArithmeticError::Overflow)?)
             },
         )
     }
@@ -946,8 +1891,9 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
     ) -> Result<Balance, DispatchError> {
         let (tot_base_amount, tot_quote_amount) = Self::get_reserves(asset_in, asset_out).unwrap();
+        let amp = Self::pair_amplification(asset_in, asset_out)?;
 
-        let d = Self::delta_util(tot_base_amount, tot_quote_amount).unwrap();
+        let d = Self::delta_util(tot_base_amount, tot_quote_amount, amp).unwrap();
 
         Self::deposit_event(Event::<T, I>::DeltaCalculated(asset_in, asset_out, d));
 
@@ -966,12 +1912,9 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
     fn delta_util(
         tot_base_amount: BalanceOf<T, I>,
         tot_quote_amount: BalanceOf<T, I>,
+        amp: u128,
     ) -> Result<Balance, DispatchError> {
-        let d = compute_d(
-            tot_base_amount,
-            tot_quote_amount,
-            T::AmplificationCoefficient::get() as u128,
-        )?;
+        let d = compute_d(tot_base_amount, tot_quote_amount, amp)?;
 
         Ok(d)
     }
@@ -995,10 +1938,10 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         let (_, base_asset, quote_asset) = Self::sort_assets(pair)?;
         let pool = Pools::<T, I>::try_get(base_asset, quote_asset)
             .map_err(|_err| Error::<T, I>::PoolDoesNotExist)?;
-        let amp = T::AmplificationCoefficient::get() as u128;
+        let amp = Self::amplification(pool.lp_token_id);
         let pool_base_aum = pool.base_amount;
         let pool_quote_aum = pool.quote_amount;
-        let d = Self::delta_util(pool_base_aum, pool_quote_aum)?;
+        let d = Self::delta_util(pool_base_aum, pool_quote_aum, amp)?;
         let new_quote_amount = pool_quote_aum
             .checked_add(amount)
             .ok_or(ArithmeticError::Underflow)?;
@@ -1013,8 +1956,9 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
     #[allow(dead_code)]
     pub fn do_get_delta_on_the_fly(
         (tot_base_amount, tot_quote_amount): (Balance, Balance),
+        amp: u128,
     ) -> Result<Balance, DispatchError> {
-        let d = Self::delta_util(tot_base_amount, tot_quote_amount).unwrap();
+        let d = Self::delta_util(tot_base_amount, tot_quote_amount, amp).unwrap();
 
         log::trace!(
             target: "stableSwap::do_get_delta_on_the_fly",
@@ -1042,12 +1986,13 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
         // passes asset in and asset out
         let (tot_base_amount, tot_quote_amount) = Self::get_reserves(asset_in, asset_out).unwrap();
-        let d = Self::delta_util(tot_base_amount, tot_quote_amount).unwrap();
+        let amp = Self::pair_amplification(asset_in, asset_out)?;
+        let d = Self::delta_util(tot_base_amount, tot_quote_amount, amp).unwrap();
 
         let mut c = d;
         let mut s = 0u128;
 
-        let a = (T::AmplificationCoefficient::get() as u128)
+        let a = amp
             .get_big_uint()
             .checked_mul(&T::Precision::get().get_big_uint())
             .ok_or(Error::<T, I>::ConversionToU128Failed)?
@@ -1310,8 +2255,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
         amounts_out[0] = amount_in;
         for i in 0..(path.len() - 1) {
-            let (reserve_in, reserve_out) = Self::get_reserves(path[i], path[i + 1])?;
-            let amount_out = Self::get_amount_out(amounts_out[i], reserve_in, reserve_out)?;
+            let amount_out = Self::get_hop_amount_out(amounts_out[i], path[i], path[i + 1])?;
             amounts_out[i + 1] = amount_out;
         }
 
@@ -1325,6 +2269,551 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             .map(|r| r.saturating_reciprocal_mul_floor::<BalanceOf<T, I>>(One::one()))
             .ok_or(ArithmeticError::Underflow)?)
     }
+
+    /// The amplification coefficient of the pool at the current block
+    pub fn amplification(lp_token_id: AssetIdOf<T, I>) -> u128 {
+        Self::amplifications(lp_token_id)
+            .map(|ramp| ramp.amplification(frame_system::Pallet::<T>::block_number()))
+            .unwrap_or_else(|| T::AmplificationCoefficient::get().into())
+    }
+
+    // the amplification coefficient of the pool of two assets trading the pair
+    fn pair_amplification(
+        asset_a: AssetIdOf<T, I>,
+        asset_b: AssetIdOf<T, I>,
+    ) -> Result<u128, DispatchError> {
+        let (_, base_asset, quote_asset) = Self::sort_assets((asset_a, asset_b))?;
+        let pool = Pools::<T, I>::try_get(base_asset, quote_asset)
+            .map_err(|_err| Error::<T, I>::PoolDoesNotExist)?;
+        Ok(Self::amplification(pool.lp_token_id))
+    }
+
+    // whether a pool of two assets or of several assets has the lp token
+    fn pool_exists(lp_token_id: AssetIdOf<T, I>) -> bool {
+        Self::pool_assets(lp_token_id).is_some()
+    }
+
+    // the assets traded by the pool of two assets or of several assets with the lp token
+    fn pool_assets(lp_token_id: AssetIdOf<T, I>) -> Option<Vec<AssetIdOf<T, I>>> {
+        if let Some(pool) = Self::multi_asset_pools(lp_token_id) {
+            return Some(pool.assets.to_vec());
+        }
+        Self::lp_token_pairs(lp_token_id)
+            .map(|(base_asset, quote_asset)| vec![base_asset, quote_asset])
+    }
+
+    // the rate scaled assets set ahead of the creation of a pool must be traded by it
+    fn ensure_rate_scaled_assets_in_pool(
+        lp_token_id: AssetIdOf<T, I>,
+        assets: &[AssetIdOf<T, I>],
+    ) -> DispatchResult {
+        ensure!(
+            Self::rate_scaled_assets(lp_token_id)
+                .iter()
+                .all(|asset_id| assets.contains(asset_id)),
+            Error::<T, I>::AssetNotInPool
+        );
+        Ok(())
+    }
+
+    // the exchange rate scaling the balance of the asset in the pool, the rate of
+    // assets which aren't rate scaled is one
+    fn exchange_rate(
+        lp_token_id: AssetIdOf<T, I>,
+        asset_id: AssetIdOf<T, I>,
+    ) -> Result<Rate, DispatchError> {
+        if !Self::rate_scaled_assets(lp_token_id).contains(&asset_id) {
+            return Ok(Rate::one());
+        }
+        T::LiquidStakingExchangeRateProvider::get_exchange_rate(&asset_id)
+            .ok_or_else(|| Error::<T, I>::MissingExchangeRate.into())
+    }
+
+    // the exchange rates of the assets of a pool of several assets
+    fn multi_asset_exchange_rates(
+        pool: &MultiAssetPoolOf<T, I>,
+    ) -> Result<Vec<Rate>, DispatchError> {
+        pool.assets
+            .iter()
+            .map(|asset_id| Self::exchange_rate(pool.lp_token_id, *asset_id))
+            .collect()
+    }
+
+    // the balances normalised by their exchange rates, the invariant applies to them
+    fn scaled_balances(
+        balances: &[BalanceOf<T, I>],
+        rates: &[Rate],
+    ) -> Result<Vec<BalanceOf<T, I>>, DispatchError> {
+        balances
+            .iter()
+            .zip(rates.iter())
+            .map(|(balance, rate)| Self::scale_up(*balance, *rate))
+            .collect()
+    }
+
+    fn scale_up(amount: BalanceOf<T, I>, rate: Rate) -> Result<BalanceOf<T, I>, DispatchError> {
+        Ok(rate
+            .checked_mul_int(amount)
+            .ok_or(ArithmeticError::Overflow)?)
+    }
+
+    fn scale_down(
+        amount: BalanceOf<T, I>,
+        rate: Rate,
+        rounding: Rounding,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        Ok(multiply_by_rational_with_rounding(
+            amount,
+            Rate::accuracy(),
+            rate.into_inner(),
+            rounding,
+        )
+        .ok_or(ArithmeticError::Overflow)?)
+    }
+
+    // same as `get_amount_out`, the reserves and amounts are normalised by the
+    // exchange rates of their assets
+    fn get_scaled_amount_out(
+        amount_in: BalanceOf<T, I>,
+        (reserve_in, reserve_out): (BalanceOf<T, I>, BalanceOf<T, I>),
+        (rate_in, rate_out): (Rate, Rate),
+        amp: u128,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let amount_out = Self::get_amount_out(
+            Self::scale_up(amount_in, rate_in)?,
+            Self::scale_up(reserve_in, rate_in)?,
+            Self::scale_up(reserve_out, rate_out)?,
+            amp,
+        )?;
+        Self::scale_down(amount_out, rate_out, Rounding::Down)
+    }
+
+    // same as `get_amount_in`, the reserves and amounts are normalised by the
+    // exchange rates of their assets
+    fn get_scaled_amount_in(
+        amount_out: BalanceOf<T, I>,
+        (reserve_in, reserve_out): (BalanceOf<T, I>, BalanceOf<T, I>),
+        (rate_in, rate_out): (Rate, Rate),
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let amount_in = Self::get_amount_in(
+            Self::scale_up(amount_out, rate_out)?,
+            Self::scale_up(reserve_in, rate_in)?,
+            Self::scale_up(reserve_out, rate_out)?,
+        )?;
+        Self::scale_down(amount_in, rate_in, Rounding::Up)
+    }
+
+    // given an input amount of an asset, returns the output amount of the other asset
+    // from the pool of two assets trading the pair, else from the pool of several assets
+    fn get_hop_amount_out(
+        amount_in: BalanceOf<T, I>,
+        asset_in: AssetIdOf<T, I>,
+        asset_out: AssetIdOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
+        if let Some(pool) = Self::pools(base_asset, quote_asset) {
+            let (reserve_in, reserve_out) = if is_inverted {
+                (pool.quote_amount, pool.base_amount)
+            } else {
+                (pool.base_amount, pool.quote_amount)
+            };
+            return Self::get_scaled_amount_out(
+                amount_in,
+                (reserve_in, reserve_out),
+                (
+                    Self::exchange_rate(pool.lp_token_id, asset_in)?,
+                    Self::exchange_rate(pool.lp_token_id, asset_out)?,
+                ),
+                Self::amplification(pool.lp_token_id),
+            );
+        }
+
+        let (pool, i, j) = Self::get_multi_asset_pool((asset_in, asset_out))?;
+        let (amount_out, _) = Self::get_multi_asset_amount_out(&pool, i, j, amount_in)?;
+        Ok(amount_out)
+    }
+
+    // given an output amount of an asset, returns the input amount of the other asset
+    // from the pool of two assets trading the pair, else from the pool of several assets
+    fn get_hop_amount_in(
+        amount_out: BalanceOf<T, I>,
+        asset_in: AssetIdOf<T, I>,
+        asset_out: AssetIdOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
+        if let Some(pool) = Self::pools(base_asset, quote_asset) {
+            let (reserve_in, reserve_out) = if is_inverted {
+                (pool.quote_amount, pool.base_amount)
+            } else {
+                (pool.base_amount, pool.quote_amount)
+            };
+            return Self::get_scaled_amount_in(
+                amount_out,
+                (reserve_in, reserve_out),
+                (
+                    Self::exchange_rate(pool.lp_token_id, asset_in)?,
+                    Self::exchange_rate(pool.lp_token_id, asset_out)?,
+                ),
+            );
+        }
+
+        let (pool, i, j) = Self::get_multi_asset_pool((asset_in, asset_out))?;
+        Self::get_multi_asset_amount_in(&pool, i, j, amount_out)
+    }
+
+    // the pool of several assets trading the pair, with the positions of the assets
+    fn get_multi_asset_pool(
+        (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+    ) -> Result<(MultiAssetPoolOf<T, I>, usize, usize), DispatchError> {
+        let (_, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
+        let pool = Self::multi_asset_pool_pairs(base_asset, quote_asset)
+            .and_then(|lp_token_id| Self::multi_asset_pools(lp_token_id))
+            .ok_or(Error::<T, I>::PoolDoesNotExist)?;
+        let i = pool
+            .index_of(&asset_in)
+            .ok_or(Error::<T, I>::AssetNotInPool)?;
+        let j = pool
+            .index_of(&asset_out)
+            .ok_or(Error::<T, I>::AssetNotInPool)?;
+        Ok((pool, i, j))
+    }
+
+    // given an input amount of the asset at `i`, returns the output amount of the asset
+    // at `j` and the protocol fee taken out of the input amount
+    fn get_multi_asset_amount_out(
+        pool: &MultiAssetPoolOf<T, I>,
+        i: usize,
+        j: usize,
+        amount_in: BalanceOf<T, I>,
+    ) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>), DispatchError> {
+        let fees = T::LpFee::get()
+            .checked_add(&T::ProtocolFee::get())
+            .map(|r| r.mul_floor(amount_in))
+            .ok_or(ArithmeticError::Overflow)?;
+        let protocol_fee = T::ProtocolFee::get().mul_floor(amount_in);
+
+        let amount_in = amount_in
+            .checked_sub(fees)
+            .ok_or(ArithmeticError::Underflow)?;
+
+        let amp = Self::amplification(pool.lp_token_id);
+        let rates = Self::multi_asset_exchange_rates(pool)?;
+        let balances = Self::scaled_balances(&pool.balances, &rates)?;
+        let d = compute_d_n(&balances, amp)?;
+        let new_balance_in = Self::scale_up(amount_in, rates[i])?
+            .checked_add(balances[i])
+            .ok_or(ArithmeticError::Overflow)?;
+        let new_balance_out = compute_y(i, j, new_balance_in, &balances, amp, d)?;
+
+        // rounds down in favor of the pool
+        let amount_out = balances[j]
+            .checked_sub(new_balance_out)
+            .and_then(|r| r.checked_sub(One::one()))
+            .ok_or(Error::<T, I>::InsufficientSupplyOut)?;
+        let amount_out = Self::scale_down(amount_out, rates[j], Rounding::Down)?;
+
+        log::trace!(
+            target: "stableswap::get_multi_asset_amount_out",
+            "lp_token_id: {:?}, i: {:?}, j: {:?}, amount_in: {:?}, d: {:?}, amount_out: {:?}",
+            &pool.lp_token_id,
+            &i,
+            &j,
+            &amount_in,
+            &d,
+            &amount_out
+        );
+
+        Ok((amount_out, protocol_fee))
+    }
+
+    // given an output amount of the asset at `j`, returns the input amount of the asset
+    // at `i` with fees
+    fn get_multi_asset_amount_in(
+        pool: &MultiAssetPoolOf<T, I>,
+        i: usize,
+        j: usize,
+        amount_out: BalanceOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        ensure!(
+            amount_out < pool.balances[j],
+            Error::<T, I>::InsufficientSupplyOut
+        );
+
+        let amp = Self::amplification(pool.lp_token_id);
+        let rates = Self::multi_asset_exchange_rates(pool)?;
+        let balances = Self::scaled_balances(&pool.balances, &rates)?;
+        let d = compute_d_n(&balances, amp)?;
+        let new_balance_out = balances[j]
+            .checked_sub(Self::scale_up(amount_out, rates[j])?)
+            .ok_or(ArithmeticError::Underflow)?;
+        let new_balance_in = compute_y(j, i, new_balance_out, &balances, amp, d)?;
+
+        let amount_in = new_balance_in
+            .checked_sub(balances[i])
+            .ok_or(ArithmeticError::Underflow)?;
+        let amount_in = Self::scale_down(amount_in, rates[i], Rounding::Up)?;
+
+        let fee_percent = T::LpFee::get()
+            .checked_add(&T::ProtocolFee::get())
+            .and_then(|r| Ratio::from_percent(100).checked_sub(&r))
+            .ok_or(ArithmeticError::Underflow)?;
+
+        Ok(fee_percent
+            .saturating_reciprocal_mul_floor(amount_in)
+            .checked_add(One::one())
+            .ok_or(ArithmeticError::Overflow)?)
+    }
+
+    fn do_multi_asset_swap(
+        who: &T::AccountId,
+        (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        amount_in: BalanceOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let (mut pool, i, j) = Self::get_multi_asset_pool((asset_in, asset_out))?;
+
+        ensure!(
+            amount_in >= T::LpFee::get().saturating_reciprocal_mul_floor(One::one()),
+            Error::<T, I>::InsufficientAmountIn
+        );
+
+        let (amount_out, protocol_fee) = Self::get_multi_asset_amount_out(&pool, i, j, amount_in)?;
+
+        // the lp fee stays in the pool
+        pool.balances[i] = amount_in
+            .checked_sub(protocol_fee)
+            .and_then(|r| r.checked_add(pool.balances[i]))
+            .ok_or(ArithmeticError::Overflow)?;
+        pool.balances[j] = pool.balances[j]
+            .checked_sub(amount_out)
+            .ok_or(ArithmeticError::Underflow)?;
+
+        T::Assets::transfer(asset_in, who, &Self::account_id(), amount_in, true)?;
+        if !protocol_fee.is_zero() {
+            T::Assets::transfer(
+                asset_in,
+                &Self::account_id(),
+                &T::ProtocolFeeReceiver::get(),
+                protocol_fee,
+                false,
+            )?;
+        }
+        T::Assets::transfer(asset_out, &Self::account_id(), who, amount_out, false)?;
+
+        log::trace!(
+            target: "stableswap::do_multi_asset_swap",
+            "who: {:?}, asset_in: {:?}, asset_out: {:?}, amount_in: {:?}, amount_out: {:?}",
+            &who,
+            &asset_in,
+            &asset_out,
+            &amount_in,
+            &amount_out,
+        );
+
+        Self::deposit_event(Event::<T, I>::Traded(
+            who.clone(),
+            asset_in,
+            asset_out,
+            amount_in,
+            amount_out,
+            pool.lp_token_id,
+            pool.balances[i],
+            pool.balances[j],
+        ));
+        MultiAssetPools::<T, I>::insert(pool.lp_token_id, pool);
+
+        Ok(amount_out)
+    }
+
+    // adds liquidity in any proportion, the first liquidity of the pool has to be in
+    // every asset
+    #[require_transactional]
+    fn do_add_multi_asset_liquidity(
+        who: &T::AccountId,
+        pool: &mut MultiAssetPoolOf<T, I>,
+        amounts: &[BalanceOf<T, I>],
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        ensure!(
+            amounts.len() == pool.assets.len(),
+            Error::<T, I>::MismatchedAmounts
+        );
+        ensure!(
+            amounts.iter().any(|amount| !amount.is_zero()),
+            Error::<T, I>::InsufficientAmountIn
+        );
+
+        let amp = Self::amplification(pool.lp_token_id);
+        let rates = Self::multi_asset_exchange_rates(pool)?;
+        let total_supply = T::Assets::total_issuance(pool.lp_token_id);
+        let new_balances = pool
+            .balances
+            .iter()
+            .zip(amounts.iter())
+            .map(|(balance, amount)| balance.checked_add(*amount))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ArithmeticError::Overflow)?;
+        let old_balances = Self::scaled_balances(&pool.balances, &rates)?;
+        let scaled_new_balances = Self::scaled_balances(&new_balances, &rates)?;
+        let d1 = compute_d_n(&scaled_new_balances, amp)?;
+
+        let liquidity = if total_supply.is_zero() {
+            T::Assets::mint_into(
+                pool.lp_token_id,
+                &Self::lock_account_id(),
+                T::MinimumLiquidity::get(),
+            )?;
+            d1.checked_sub(T::MinimumLiquidity::get())
+                .ok_or(Error::<T, I>::InsufficientLiquidity)?
+        } else {
+            let d0 = compute_d_n(&old_balances, amp)?;
+            ensure!(d1 > d0, Error::<T, I>::InvalidInvariant);
+            let balances_after_fees =
+                Self::balances_after_imbalance_fees(&old_balances, &scaled_new_balances, d0, d1)?;
+            let d2 = compute_d_n(&balances_after_fees, amp)?;
+            multiply_by_rational_with_rounding(
+                total_supply,
+                d2.saturating_sub(d0),
+                d0,
+                Rounding::Down,
+            )
+            .ok_or(ArithmeticError::Overflow)?
+        };
+        ensure!(!liquidity.is_zero(), Error::<T, I>::InsufficientLiquidity);
+
+        for (asset_id, amount) in pool.assets.iter().zip(amounts.iter()) {
+            if !amount.is_zero() {
+                T::Assets::transfer(*asset_id, who, &Self::account_id(), *amount, true)?;
+            }
+        }
+        T::Assets::mint_into(pool.lp_token_id, who, liquidity)?;
+
+        pool.balances = new_balances
+            .try_into()
+            .map_err(|_| Error::<T, I>::InvalidPoolAssets)?;
+
+        Ok(liquidity)
+    }
+
+    #[require_transactional]
+    fn do_remove_multi_asset_liquidity(
+        who: &T::AccountId,
+        pool: &mut MultiAssetPoolOf<T, I>,
+        amounts: &[BalanceOf<T, I>],
+        liquidity: BalanceOf<T, I>,
+    ) -> Result<(), DispatchError> {
+        T::Assets::burn_from(pool.lp_token_id, who, liquidity)?;
+
+        for ((asset_id, balance), amount) in pool
+            .assets
+            .iter()
+            .zip(pool.balances.iter_mut())
+            .zip(amounts.iter())
+        {
+            *balance = balance
+                .checked_sub(*amount)
+                .ok_or(Error::<T, I>::InsufficientLiquidity)?;
+            if !amount.is_zero() {
+                T::Assets::transfer(*asset_id, &Self::account_id(), who, *amount, false)?;
+            }
+        }
+
+        log::trace!(
+            target: "stableswap::do_remove_multi_asset_liquidity",
+            "who: {:?}, lp_token_id: {:?}, amounts: {:?}, liquidity: {:?}",
+            &who,
+            &pool.lp_token_id,
+            &amounts,
+            &liquidity
+        );
+
+        Ok(())
+    }
+
+    // deducts the fee charged on the imbalance of a liquidity change from the new
+    // balances, the further the change moves a balance off the ratio of the pool
+    // the more it pays
+    fn balances_after_imbalance_fees(
+        old_balances: &[BalanceOf<T, I>],
+        new_balances: &[BalanceOf<T, I>],
+        d0: u128,
+        d1: u128,
+    ) -> Result<Vec<BalanceOf<T, I>>, DispatchError> {
+        let fee = Self::imbalance_fee(old_balances.len());
+        old_balances
+            .iter()
+            .zip(new_balances.iter())
+            .map(
+                |(old_balance, new_balance)| -> Result<BalanceOf<T, I>, DispatchError> {
+                    let ideal_balance =
+                        multiply_by_rational_with_rounding(d1, *old_balance, d0, Rounding::Down)
+                            .ok_or(ArithmeticError::Overflow)?;
+                    let difference = if ideal_balance > *new_balance {
+                        ideal_balance - new_balance
+                    } else {
+                        new_balance - ideal_balance
+                    };
+                    Ok(new_balance
+                        .checked_sub(fee.mul_floor(difference))
+                        .ok_or(ArithmeticError::Underflow)?)
+                },
+            )
+            .collect()
+    }
+
+    // the fee charged on imbalances, the share of a swap fee Curve charges
+    // which is `fee * n / (4 * (n - 1))`
+    fn imbalance_fee(n: usize) -> Ratio {
+        let n = n as u32;
+        T::LpFee::get().saturating_mul(Ratio::from_rational(n, 4 * n.saturating_sub(1)))
+    }
+
+    // given an amount of lp tokens, returns the amount of the asset at `i` they
+    // withdraw, the other assets pay fees as if they were swapped to it
+    fn get_withdraw_one_asset_amount(
+        pool: &MultiAssetPoolOf<T, I>,
+        liquidity: BalanceOf<T, I>,
+        i: usize,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let total_supply = T::Assets::total_issuance(pool.lp_token_id);
+        ensure!(
+            !liquidity.is_zero() && liquidity < total_supply,
+            Error::<T, I>::InsufficientLiquidity
+        );
+
+        let amp = Self::amplification(pool.lp_token_id);
+        let rates = Self::multi_asset_exchange_rates(pool)?;
+        let balances = Self::scaled_balances(&pool.balances, &rates)?;
+        let d0 = compute_d_n(&balances, amp)?;
+        let d1 = multiply_by_rational_with_rounding(liquidity, d0, total_supply, Rounding::Up)
+            .and_then(|r| d0.checked_sub(r))
+            .ok_or(ArithmeticError::Underflow)?;
+        let new_balance = compute_y_d(i, &balances, amp, d1)?;
+
+        let fee = Self::imbalance_fee(balances.len());
+        let balances_after_fees = balances
+            .iter()
+            .enumerate()
+            .map(|(k, balance)| -> Result<BalanceOf<T, I>, DispatchError> {
+                let expected_balance =
+                    multiply_by_rational_with_rounding(*balance, d1, d0, Rounding::Down)
+                        .ok_or(ArithmeticError::Overflow)?;
+                let difference = if k == i {
+                    expected_balance.saturating_sub(new_balance)
+                } else {
+                    balance.saturating_sub(expected_balance)
+                };
+                Ok(balance
+                    .checked_sub(fee.mul_floor(difference))
+                    .ok_or(ArithmeticError::Underflow)?)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let balance_after_fees = compute_y_d(i, &balances_after_fees, amp, d1)?;
+        let amount = balances_after_fees[i]
+            .checked_sub(balance_after_fees)
+            .and_then(|r| r.checked_sub(One::one()))
+            .ok_or(Error::<T, I>::InsufficientLiquidity)?;
+        Self::scale_down(amount, rates[i], Rounding::Down)
+    }
 }
 // For Parallel Router
 impl<T: Config<I>, I: 'static>
@@ -1367,13 +2856,23 @@ impl<T: Config<I>, I: 'static>
 
     /// Returns a vector of all of the pools in storage
     fn get_pools() -> Result<Vec<(AssetIdOf<T, I>, AssetIdOf<T, I>)>, DispatchError> {
-        Ok(Pools::<T, I>::iter_keys().collect())
+        let mut pools: Vec<_> = Pools::<T, I>::iter_keys().collect();
+        pools.extend(MultiAssetPoolPairs::<T, I>::iter_keys().filter(
+            |(base_asset, quote_asset)| !Pools::<T, I>::contains_key(base_asset, quote_asset),
+        ));
+        Ok(pools)
     }
 
     fn get_reserves(
         asset_in: AssetIdOf<T, I>,
         asset_out: AssetIdOf<T, I>,
     ) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>), DispatchError> {
+        let (_, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
+        if !Pools::<T, I>::contains_key(base_asset, quote_asset) {
+            let (pool, i, j) = Self::get_multi_asset_pool((asset_in, asset_out))?;
+            return Ok((pool.balances[i], pool.balances[j]));
+        }
+
         let (amount_x, amount_y) = Self::get_reserves(asset_in, asset_out)?;
         Ok((amount_x, amount_y))
     }
@@ -0,0 +1,97 @@
+// Copyright 2021-2022 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+pub mod v2 {
+    use super::*;
+    use crate::{pallet::StorageVersion, Config, LpTokenPairs, Pools};
+    use frame_support::{
+        log,
+        traits::{Get, OnRuntimeUpgrade},
+        weights::Weight,
+    };
+
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T, I>::get() == crate::Versions::V1,
+            "must upgrade linearly"
+        );
+        let pools_count = Pools::<T, I>::iter_keys().count();
+        log::info!("total {:#?} pools need to migrate", pools_count);
+
+        Ok(())
+    }
+
+    /// Migration to the index of the pools of two assets by their lp token, the
+    /// pools already created are indexed.
+    pub fn migrate<T: Config<I>, I: 'static>() -> Weight {
+        if StorageVersion::<T, I>::get() == crate::Versions::V1 {
+            log::info!("migrating stableswap to Versions::V2",);
+
+            let mut count = 0u64;
+            Pools::<T, I>::iter().for_each(|(base_asset, quote_asset, pool)| {
+                count += 1;
+                LpTokenPairs::<T, I>::insert(pool.lp_token_id, (base_asset, quote_asset));
+            });
+
+            StorageVersion::<T, I>::put(crate::Versions::V2);
+            log::info!(
+                "👜 completed stableswap migration to Versions::V2, {:?} pools indexed",
+                count
+            );
+
+            T::DbWeight::get().reads_writes(count + 1, count + 1)
+        } else {
+            T::DbWeight::get().reads(1)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn post_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T, I>::get() == crate::Versions::V2,
+            "must upgrade to V2"
+        );
+        for (base_asset, quote_asset, pool) in Pools::<T, I>::iter() {
+            frame_support::ensure!(
+                LpTokenPairs::<T, I>::get(pool.lp_token_id) == Some((base_asset, quote_asset)),
+                "pool must be indexed by its lp token"
+            );
+        }
+        log::info!("👜 stableswap v2 migration passes POST migrate checks ✅",);
+
+        Ok(())
+    }
+
+    /// Runs the v2 migration on runtime upgrade
+    pub struct MigrateToV2<T, I = ()>(sp_std::marker::PhantomData<(T, I)>);
+    impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV2<T, I> {
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            pre_migrate::<T, I>()?;
+            Ok(Vec::new())
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            migrate::<T, I>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+            post_migrate::<T, I>()
+        }
+    }
+}
@@ -6,13 +6,14 @@ use frame_support::{
     PalletId,
 };
 use frame_system::{self as system, Config, EnsureRoot, EnsureSigned};
-use primitives::{tokens, Balance, CurrencyId, Ratio};
+use pallet_traits::ExchangeRateProvider;
+use primitives::{tokens, Balance, CurrencyId, Rate, Ratio};
 use scale_info::TypeInfo;
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
-    RuntimeDebug,
+    FixedPointNumber, RuntimeDebug,
 };
 
 #[cfg(feature = "std")]
@@ -118,6 +119,8 @@ parameter_types! {
     pub const NumTokens: u8 = 2;
     pub const Precision: u32 = 100;
     pub const AmplificationCoefficient: u8 = 85;
+    pub const MaxPoolAssets: u32 = 4;
+    pub const MinRampBlocks: u64 = 10;
     //
     // pub DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);   // 0.05%
     // pub const MinimumLiquidity: u128 = 1_000u128;
@@ -132,6 +135,8 @@ impl pallet_stableswap::Config for Test {
     type NumTokens = NumTokens;
     type Precision = Precision;
     type AmplificationCoefficient = AmplificationCoefficient;
+    type MaxPoolAssets = MaxPoolAssets;
+    type MinRampBlocks = MinRampBlocks;
 
     type ProtocolFeeReceiver = DefaultProtocolFeeReceiver;
     type LpFee = DefaultLpFee;
@@ -139,6 +144,19 @@ impl pallet_stableswap::Config for Test {
     type ProtocolFee = DefaultProtocolFee;
     type MinimumLiquidity = MinimumLiquidity;
     type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type LiquidStakingExchangeRateProvider = LiquidStakingExchangeRateProvider;
+    type PoolsHook = ();
+}
+
+parameter_types! {
+    pub static SdotExchangeRate: Rate = Rate::saturating_from_rational(3, 2);
+}
+
+pub struct LiquidStakingExchangeRateProvider;
+impl ExchangeRateProvider<CurrencyId> for LiquidStakingExchangeRateProvider {
+    fn get_exchange_rate(asset_id: &CurrencyId) -> Option<Rate> {
+        (*asset_id == SDOT).then(SdotExchangeRate::get)
+    }
 }
 
 parameter_types! {
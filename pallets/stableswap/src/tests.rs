@@ -22,6 +22,10 @@ fn create_pool_should_work() {
             DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
             2_000
         );
+        assert_eq!(
+            DefaultStableSwap::lp_token_pairs(SAMPLE_LP_TOKEN),
+            Some((SDOT, DOT))
+        );
         assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 1_414);
         // should be issuance minus the min liq locked
         assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, BOB), 414);
@@ -939,8 +943,13 @@ fn amount_out_should_work() {
         let supply_in = 100_000_000;
         let supply_out = 100_000_000;
 
-        let amount_out =
-            DefaultStableSwap::get_amount_out(amount_in, supply_in, supply_out).unwrap();
+        let amount_out = DefaultStableSwap::get_amount_out(
+            amount_in,
+            supply_in,
+            supply_out,
+            AmplificationCoefficient::get().into(),
+        )
+        .unwrap();
 
         // actual value == 996.9900600091017
         // TODO: assumes we round down to int
@@ -1090,8 +1099,13 @@ fn amount_out_and_in_should_work() {
 
         assert_eq!(amount_in, 1004);
 
-        let amount_out =
-            DefaultStableSwap::get_amount_out(amount_in, supply_in, supply_out).unwrap();
+        let amount_out = DefaultStableSwap::get_amount_out(
+            amount_in,
+            supply_in,
+            supply_out,
+            AmplificationCoefficient::get().into(),
+        )
+        .unwrap();
 
         // old
         // assert_eq!(amount_out, 1000);
@@ -1485,11 +1499,18 @@ fn handling_fees_should_work() {
         assert_eq!(scalar, 5.0);
 
         let total_lp_token_supply = 100_000_000_000.0;
-        let old_root_k =
-            DefaultStableSwap::delta_util(100_000_000_000, 100_000_000_000).unwrap() as f64;
-        let new_root_k =
-            DefaultStableSwap::delta_util(100_000_000_000 - 5_981_998, 100_000_000_000 + 6_000_000)
-                .unwrap() as f64;
+        let old_root_k = DefaultStableSwap::delta_util(
+            100_000_000_000,
+            100_000_000_000,
+            AmplificationCoefficient::get().into(),
+        )
+        .unwrap() as f64;
+        let new_root_k = DefaultStableSwap::delta_util(
+            100_000_000_000 - 5_981_998,
+            100_000_000_000 + 6_000_000,
+            AmplificationCoefficient::get().into(),
+        )
+        .unwrap() as f64;
         let root_k_growth = new_root_k - old_root_k;
 
         let numerator = total_lp_token_supply * root_k_growth;
@@ -1525,8 +1546,13 @@ fn amount_out_should_work_simple() {
 
         // assert_eq!(amount_in, 1004);
 
-        let amount_out =
-            DefaultStableSwap::get_amount_out(amount_in, supply_in, supply_out).unwrap();
+        let amount_out = DefaultStableSwap::get_amount_out(
+            amount_in,
+            supply_in,
+            supply_out,
+            AmplificationCoefficient::get().into(),
+        )
+        .unwrap();
 
         // old
         // assert_eq!(amount_out, 1000);
@@ -1574,3 +1600,693 @@ fn swap_stable_tokens() {
         // println!("SDOT Diff\t{:?}", bal_sdot_after - bal_sdot_before);
     })
 }
+
+fn create_multi_asset_pool() {
+    assert_ok!(DefaultStableSwap::create_multi_asset_pool(
+        RawOrigin::Signed(ALICE).into(),
+        vec![DOT, SDOT, KSM],
+        vec![1_000_000, 1_000_000, 1_000_000],
+        AmplificationCoefficient::get().into(),
+        BOB,
+        SAMPLE_LP_TOKEN,
+    ));
+}
+
+#[test]
+fn create_multi_asset_pool_should_work() {
+    new_test_ext().execute_with(|| {
+        create_multi_asset_pool();
+
+        let pool = DefaultStableSwap::multi_asset_pools(SAMPLE_LP_TOKEN).unwrap();
+        assert_eq!(pool.assets.to_vec(), vec![DOT, SDOT, KSM]);
+        assert_eq!(
+            pool.balances.to_vec(),
+            vec![1_000_000, 1_000_000, 1_000_000]
+        );
+
+        // the invariant of a balanced pool is the sum of its balances
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 3_000_000);
+        assert_eq!(
+            Assets::balance(SAMPLE_LP_TOKEN, BOB),
+            3_000_000 - MINIMUM_LIQUIDITY
+        );
+        assert_eq!(
+            Assets::balance(DOT, DefaultStableSwap::account_id()),
+            1_000_000
+        );
+
+        // every pair of the pool can be routed
+        let mut pools = DefaultStableSwap::get_pools().unwrap();
+        pools.sort();
+        assert_eq!(pools, vec![(DOT, KSM), (SDOT, KSM), (SDOT, DOT)]);
+        assert_eq!(
+            DefaultStableSwap::get_reserves(KSM, SDOT).unwrap(),
+            (1_000_000, 1_000_000)
+        );
+    })
+}
+
+#[test]
+fn create_multi_asset_pool_with_invalid_parameters_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            DefaultStableSwap::create_multi_asset_pool(
+                RawOrigin::Signed(ALICE).into(),
+                vec![DOT, SDOT, DOT],
+                vec![1_000, 1_000, 1_000],
+                AmplificationCoefficient::get().into(),
+                BOB,
+                SAMPLE_LP_TOKEN,
+            ),
+            Error::<Test>::InvalidPoolAssets
+        );
+        assert_noop!(
+            DefaultStableSwap::create_multi_asset_pool(
+                RawOrigin::Signed(ALICE).into(),
+                vec![DOT, SDOT, KSM, 200, 201],
+                vec![1_000, 1_000, 1_000, 1_000, 1_000],
+                AmplificationCoefficient::get().into(),
+                BOB,
+                SAMPLE_LP_TOKEN,
+            ),
+            Error::<Test>::InvalidPoolAssets
+        );
+        assert_noop!(
+            DefaultStableSwap::create_multi_asset_pool(
+                RawOrigin::Signed(ALICE).into(),
+                vec![DOT, SDOT, KSM],
+                vec![1_000, 1_000],
+                AmplificationCoefficient::get().into(),
+                BOB,
+                SAMPLE_LP_TOKEN,
+            ),
+            Error::<Test>::MismatchedAmounts
+        );
+        assert_noop!(
+            DefaultStableSwap::create_multi_asset_pool(
+                RawOrigin::Signed(ALICE).into(),
+                vec![DOT, SDOT, KSM],
+                vec![1_000, 1_000, 1_000],
+                MAX_AMPLIFICATION + 1,
+                BOB,
+                SAMPLE_LP_TOKEN,
+            ),
+            Error::<Test>::InvalidAmplification
+        );
+        assert_noop!(
+            DefaultStableSwap::create_multi_asset_pool(
+                RawOrigin::Signed(BOB).into(),
+                vec![DOT, SDOT, KSM],
+                vec![1_000, 1_000, 1_000],
+                AmplificationCoefficient::get().into(),
+                BOB,
+                SAMPLE_LP_TOKEN,
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+    })
+}
+
+#[test]
+fn add_multi_asset_liquidity_should_work() {
+    new_test_ext().execute_with(|| {
+        create_multi_asset_pool();
+
+        // balanced liquidity pays no fee
+        assert_ok!(DefaultStableSwap::add_multi_asset_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            SAMPLE_LP_TOKEN,
+            vec![1_000_000, 1_000_000, 1_000_000],
+            3_000_000,
+        ));
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 6_000_000);
+
+        // imbalanced liquidity is worth less than its sum
+        let issuance = Assets::total_issuance(SAMPLE_LP_TOKEN);
+        assert_noop!(
+            DefaultStableSwap::add_multi_asset_liquidity(
+                RawOrigin::Signed(BOB).into(),
+                SAMPLE_LP_TOKEN,
+                vec![1_000_000, 0, 0],
+                1_000_000,
+            ),
+            Error::<Test>::SlippageExceeded
+        );
+        assert_ok!(DefaultStableSwap::add_multi_asset_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            SAMPLE_LP_TOKEN,
+            vec![1_000_000, 0, 0],
+            0,
+        ));
+        let minted = Assets::total_issuance(SAMPLE_LP_TOKEN) - issuance;
+        assert!(minted < 1_000_000 && minted > 990_000);
+        assert_eq!(
+            DefaultStableSwap::multi_asset_pools(SAMPLE_LP_TOKEN)
+                .unwrap()
+                .balances
+                .to_vec(),
+            vec![3_000_000, 2_000_000, 2_000_000]
+        );
+    })
+}
+
+#[test]
+fn remove_multi_asset_liquidity_should_work() {
+    new_test_ext().execute_with(|| {
+        create_multi_asset_pool();
+        let dot_balance = Assets::balance(DOT, BOB);
+
+        assert_noop!(
+            DefaultStableSwap::remove_multi_asset_liquidity(
+                RawOrigin::Signed(BOB).into(),
+                SAMPLE_LP_TOKEN,
+                1_500_000,
+                vec![500_001, 0, 0],
+            ),
+            Error::<Test>::SlippageExceeded
+        );
+        assert_ok!(DefaultStableSwap::remove_multi_asset_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            SAMPLE_LP_TOKEN,
+            1_500_000,
+            vec![500_000, 500_000, 500_000],
+        ));
+
+        assert_eq!(Assets::balance(DOT, BOB), dot_balance + 500_000);
+        assert_eq!(
+            Assets::balance(SAMPLE_LP_TOKEN, BOB),
+            1_500_000 - MINIMUM_LIQUIDITY
+        );
+        assert_eq!(
+            DefaultStableSwap::multi_asset_pools(SAMPLE_LP_TOKEN)
+                .unwrap()
+                .balances
+                .to_vec(),
+            vec![500_000, 500_000, 500_000]
+        );
+    })
+}
+
+#[test]
+fn remove_multi_asset_liquidity_imbalance_should_work() {
+    new_test_ext().execute_with(|| {
+        create_multi_asset_pool();
+        let dot_balance = Assets::balance(DOT, BOB);
+        let lp_balance = Assets::balance(SAMPLE_LP_TOKEN, BOB);
+
+        assert_noop!(
+            DefaultStableSwap::remove_multi_asset_liquidity_imbalance(
+                RawOrigin::Signed(BOB).into(),
+                SAMPLE_LP_TOKEN,
+                vec![100_000, 0, 0],
+                100_000,
+            ),
+            Error::<Test>::SlippageExceeded
+        );
+        assert_ok!(DefaultStableSwap::remove_multi_asset_liquidity_imbalance(
+            RawOrigin::Signed(BOB).into(),
+            SAMPLE_LP_TOKEN,
+            vec![100_000, 0, 0],
+            101_000,
+        ));
+
+        assert_eq!(Assets::balance(DOT, BOB), dot_balance + 100_000);
+        let burnt = lp_balance - Assets::balance(SAMPLE_LP_TOKEN, BOB);
+        assert!(burnt > 100_000 && burnt < 101_000);
+    })
+}
+
+#[test]
+fn remove_multi_asset_liquidity_one_asset_should_work() {
+    new_test_ext().execute_with(|| {
+        create_multi_asset_pool();
+        let ksm_balance = Assets::balance(KSM, BOB);
+
+        assert_noop!(
+            DefaultStableSwap::remove_multi_asset_liquidity_one_asset(
+                RawOrigin::Signed(BOB).into(),
+                SAMPLE_LP_TOKEN,
+                100_000,
+                100,
+                0,
+            ),
+            Error::<Test>::AssetNotInPool
+        );
+        assert_noop!(
+            DefaultStableSwap::remove_multi_asset_liquidity_one_asset(
+                RawOrigin::Signed(BOB).into(),
+                SAMPLE_LP_TOKEN,
+                100_000,
+                KSM,
+                100_000,
+            ),
+            Error::<Test>::SlippageExceeded
+        );
+        assert_ok!(DefaultStableSwap::remove_multi_asset_liquidity_one_asset(
+            RawOrigin::Signed(BOB).into(),
+            SAMPLE_LP_TOKEN,
+            100_000,
+            KSM,
+            99_000,
+        ));
+
+        let withdrawn = Assets::balance(KSM, BOB) - ksm_balance;
+        assert!(withdrawn < 100_000 && withdrawn >= 99_000);
+        assert_eq!(
+            DefaultStableSwap::multi_asset_pools(SAMPLE_LP_TOKEN)
+                .unwrap()
+                .balances
+                .to_vec(),
+            vec![1_000_000, 1_000_000, 1_000_000 - withdrawn]
+        );
+    })
+}
+
+#[test]
+fn multi_asset_pool_swap_should_work() {
+    new_test_ext().execute_with(|| {
+        create_multi_asset_pool();
+        let amount_in = 100_000;
+
+        let amounts_out = DefaultStableSwap::get_amounts_out(amount_in, vec![DOT, KSM]).unwrap();
+        let amount_out = amounts_out[1];
+        assert!(amount_out < amount_in && amount_out > 99_000);
+        let amounts_in = DefaultStableSwap::get_amounts_in(amount_out, vec![DOT, KSM]).unwrap();
+        assert!(amounts_in[0] > amount_in - 10 && amounts_in[0] <= amount_in + 1);
+
+        assert_ok!(DefaultStableSwap::swap(&EVE, (DOT, KSM), amount_in));
+
+        assert_eq!(Assets::balance(KSM, EVE), amount_out);
+        // the protocol fee is sent out of the pool, the lp fee stays in it
+        let protocol_fee = DefaultProtocolFee::get().mul_floor(amount_in);
+        assert_eq!(Assets::balance(DOT, PROTOCOL_FEE_RECEIVER), protocol_fee);
+        assert_eq!(
+            DefaultStableSwap::multi_asset_pools(SAMPLE_LP_TOKEN)
+                .unwrap()
+                .balances
+                .to_vec(),
+            vec![
+                1_000_000 + amount_in - protocol_fee,
+                1_000_000,
+                1_000_000 - amount_out
+            ]
+        );
+    })
+}
+
+#[test]
+fn ramp_amplification_should_work() {
+    new_test_ext().execute_with(|| {
+        create_multi_asset_pool();
+        let initial_amplification: u128 = AmplificationCoefficient::get().into();
+
+        assert_noop!(
+            DefaultStableSwap::ramp_amplification(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                initial_amplification * 2,
+                MinRampBlocks::get() - 1,
+            ),
+            Error::<Test>::InvalidRampBlock
+        );
+        assert_noop!(
+            DefaultStableSwap::ramp_amplification(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                initial_amplification * MAX_AMPLIFICATION_CHANGE + 1,
+                20,
+            ),
+            Error::<Test>::InvalidAmplification
+        );
+        assert_noop!(
+            DefaultStableSwap::ramp_amplification(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN_2,
+                initial_amplification * 2,
+                20,
+            ),
+            Error::<Test>::PoolDoesNotExist
+        );
+
+        assert_ok!(DefaultStableSwap::ramp_amplification(
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN,
+            initial_amplification * 2,
+            20,
+        ));
+        assert_eq!(
+            DefaultStableSwap::amplifications(SAMPLE_LP_TOKEN),
+            Some(AmplificationRamp {
+                initial_amplification,
+                future_amplification: initial_amplification * 2,
+                initial_block: 0,
+                future_block: 20,
+            })
+        );
+
+        // the amplification coefficient moves linearly
+        run_to_block(10);
+        assert_eq!(DefaultStableSwap::amplification(SAMPLE_LP_TOKEN), 127);
+        run_to_block(30);
+        assert_eq!(
+            DefaultStableSwap::amplification(SAMPLE_LP_TOKEN),
+            initial_amplification * 2
+        );
+    })
+}
+
+#[test]
+fn stop_ramp_amplification_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            (1_000_000, 1_000_000),
+            BOB,
+            SAMPLE_LP_TOKEN_2,
+        ));
+        let initial_amplification: u128 = AmplificationCoefficient::get().into();
+
+        // pools of two assets can be ramped too
+        assert_ok!(DefaultStableSwap::ramp_amplification(
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN_2,
+            initial_amplification / 2,
+            20,
+        ));
+        run_to_block(10);
+        assert_ok!(DefaultStableSwap::stop_ramp_amplification(
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN_2,
+        ));
+
+        // the amplification coefficient stays where the ramp stopped
+        run_to_block(30);
+        assert_eq!(DefaultStableSwap::amplification(SAMPLE_LP_TOKEN_2), 64);
+    })
+}
+
+#[test]
+fn set_rate_scaled_assets_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            DefaultStableSwap::set_rate_scaled_assets(
+                RawOrigin::Signed(BOB).into(),
+                SAMPLE_LP_TOKEN,
+                vec![SDOT],
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        // only liquid staking assets have an exchange rate
+        assert_noop!(
+            DefaultStableSwap::set_rate_scaled_assets(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                vec![DOT],
+            ),
+            Error::<Test>::MissingExchangeRate
+        );
+
+        assert_ok!(DefaultStableSwap::set_rate_scaled_assets(
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN,
+            vec![SDOT],
+        ));
+        assert_eq!(
+            DefaultStableSwap::rate_scaled_assets(SAMPLE_LP_TOKEN).to_vec(),
+            vec![SDOT]
+        );
+        assert_ok!(DefaultStableSwap::set_rate_scaled_assets(
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN,
+            vec![],
+        ));
+        assert!(!RateScaledAssets::<Test>::contains_key(SAMPLE_LP_TOKEN));
+
+        // the pool must trade the rate scaled assets
+        assert_ok!(DefaultStableSwap::set_rate_scaled_assets(
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN,
+            vec![SDOT],
+        ));
+        assert_noop!(
+            DefaultStableSwap::create_pool(
+                RawOrigin::Signed(ALICE).into(),
+                (DOT, KSM),
+                (1_000_000, 1_000_000),
+                BOB,
+                SAMPLE_LP_TOKEN,
+            ),
+            Error::<Test>::AssetNotInPool
+        );
+
+        // scaling can't change once the pool has liquidity
+        create_multi_asset_pool();
+        assert_noop!(
+            DefaultStableSwap::set_rate_scaled_assets(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                vec![],
+            ),
+            Error::<Test>::PoolAlreadyExists
+        );
+    })
+}
+
+#[test]
+fn rate_scaled_pool_swap_should_work() {
+    new_test_ext().execute_with(|| {
+        let amount_in = 10_000;
+        // a pool pegged 1:1 prices SDOT far below its value
+        let unscaled_amount_out = DefaultStableSwap::get_amount_out(
+            amount_in,
+            1_000_000,
+            1_500_000,
+            AmplificationCoefficient::get().into(),
+        )
+        .unwrap();
+        assert!(unscaled_amount_out < 11_000);
+
+        // the pool is balanced in value, one SDOT is worth 1.5 DOT
+        assert_ok!(DefaultStableSwap::set_rate_scaled_assets(
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN,
+            vec![SDOT],
+        ));
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            (1_500_000, 1_000_000),
+            BOB,
+            SAMPLE_LP_TOKEN,
+        ));
+        let amount_out = DefaultStableSwap::get_amounts_out(amount_in, vec![SDOT, DOT]).unwrap()[1];
+        assert!(amount_out > 14_900 && amount_out < 15_000);
+        let amounts_in = DefaultStableSwap::get_amounts_in(amount_out, vec![SDOT, DOT]).unwrap();
+        assert!(amounts_in[0] >= amount_in && amounts_in[0] < amount_in * 102 / 100);
+
+        let dot_balance = Assets::balance(DOT, EVE);
+        assert_ok!(DefaultStableSwap::swap(&EVE, (SDOT, DOT), amount_in));
+        assert_eq!(Assets::balance(DOT, EVE), dot_balance + amount_out);
+
+        // SDOT is worth more once its exchange rate grows
+        SdotExchangeRate::set(Rate::saturating_from_rational(2, 1));
+        assert!(
+            DefaultStableSwap::get_amounts_out(amount_in, vec![SDOT, DOT]).unwrap()[1] > amount_out
+        );
+    })
+}
+
+#[test]
+fn rate_scaled_multi_asset_pool_swap_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::set_rate_scaled_assets(
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN,
+            vec![SDOT],
+        ));
+        assert_ok!(DefaultStableSwap::create_multi_asset_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![DOT, SDOT, KSM],
+            vec![1_500_000, 1_000_000, 1_500_000],
+            AmplificationCoefficient::get().into(),
+            BOB,
+            SAMPLE_LP_TOKEN,
+        ));
+        let amount_in = 10_000;
+
+        let amount_out = DefaultStableSwap::get_amounts_out(amount_in, vec![SDOT, KSM]).unwrap()[1];
+        assert!(amount_out > 14_900 && amount_out < 15_000);
+
+        assert_ok!(DefaultStableSwap::swap(&EVE, (SDOT, KSM), amount_in));
+        assert_eq!(Assets::balance(KSM, EVE), amount_out);
+
+        // liquidity withdrawn as SDOT alone is priced at its exchange rate
+        let sdot_balance = Assets::balance(SDOT, BOB);
+        assert_ok!(DefaultStableSwap::remove_multi_asset_liquidity_one_asset(
+            RawOrigin::Signed(BOB).into(),
+            SAMPLE_LP_TOKEN,
+            30_000,
+            SDOT,
+            0,
+        ));
+        let sdot_withdrawn = Assets::balance(SDOT, BOB) - sdot_balance;
+        let ksm_balance = Assets::balance(KSM, BOB);
+        assert_ok!(DefaultStableSwap::remove_multi_asset_liquidity_one_asset(
+            RawOrigin::Signed(BOB).into(),
+            SAMPLE_LP_TOKEN,
+            30_000,
+            KSM,
+            0,
+        ));
+        let ksm_withdrawn = Assets::balance(KSM, BOB) - ksm_balance;
+        assert!(
+            ksm_withdrawn * 100 / sdot_withdrawn >= 145
+                && ksm_withdrawn * 100 / sdot_withdrawn <= 155
+        );
+    })
+}
+
+#[test]
+fn zap_in_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            (1_000_000, 1_000_000),
+            BOB,
+            SAMPLE_LP_TOKEN,
+        ));
+        let dot_balance = Assets::balance(DOT, EVE);
+        let sdot_balance = Assets::balance(SDOT, EVE);
+
+        assert_noop!(
+            DefaultStableSwap::zap_in(
+                RawOrigin::Signed(EVE).into(),
+                SAMPLE_LP_TOKEN,
+                KSM,
+                10_000,
+                0
+            ),
+            Error::<Test>::AssetNotInPool
+        );
+        assert_noop!(
+            DefaultStableSwap::zap_in(
+                RawOrigin::Signed(EVE).into(),
+                SAMPLE_LP_TOKEN_2,
+                DOT,
+                10_000,
+                0
+            ),
+            Error::<Test>::PoolDoesNotExist
+        );
+        assert_noop!(
+            DefaultStableSwap::zap_in(
+                RawOrigin::Signed(EVE).into(),
+                SAMPLE_LP_TOKEN,
+                DOT,
+                10_000,
+                Balance::MAX
+            ),
+            Error::<Test>::SlippageExceeded
+        );
+
+        assert_ok!(DefaultStableSwap::zap_in(
+            RawOrigin::Signed(EVE).into(),
+            SAMPLE_LP_TOKEN,
+            DOT,
+            10_000,
+            1
+        ));
+
+        // about half of the DOT is swapped, both halves are added, only dust is left
+        assert!(Assets::balance(SAMPLE_LP_TOKEN, EVE) > 0);
+        assert!(dot_balance - Assets::balance(DOT, EVE) >= 9_900);
+        assert!(Assets::balance(SDOT, EVE) - sdot_balance <= 100);
+    })
+}
+
+#[test]
+fn zap_out_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            (1_000_000, 1_000_000),
+            BOB,
+            SAMPLE_LP_TOKEN,
+        ));
+        assert_ok!(DefaultStableSwap::zap_in(
+            RawOrigin::Signed(EVE).into(),
+            SAMPLE_LP_TOKEN,
+            DOT,
+            10_000,
+            1
+        ));
+        let liquidity = Assets::balance(SAMPLE_LP_TOKEN, EVE);
+        let dot_balance = Assets::balance(DOT, EVE);
+        let sdot_balance = Assets::balance(SDOT, EVE);
+
+        assert_noop!(
+            DefaultStableSwap::zap_out(
+                RawOrigin::Signed(EVE).into(),
+                SAMPLE_LP_TOKEN,
+                liquidity,
+                DOT,
+                10_000
+            ),
+            Error::<Test>::SlippageExceeded
+        );
+        assert_ok!(DefaultStableSwap::zap_out(
+            RawOrigin::Signed(EVE).into(),
+            SAMPLE_LP_TOKEN,
+            liquidity,
+            DOT,
+            1
+        ));
+
+        // the SDOT removed is swapped back to DOT
+        let amount_out = Assets::balance(DOT, EVE) - dot_balance;
+        assert!(amount_out > 9_000 && amount_out < 10_000);
+        assert_eq!(Assets::balance(SDOT, EVE), sdot_balance);
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, EVE), 0);
+    })
+}
+
+#[test]
+fn multi_asset_pool_zap_should_work() {
+    new_test_ext().execute_with(|| {
+        create_multi_asset_pool();
+        let dot_balance = Assets::balance(DOT, EVE);
+
+        // the single asset is an imbalanced deposit
+        assert_ok!(DefaultStableSwap::zap_in(
+            RawOrigin::Signed(EVE).into(),
+            SAMPLE_LP_TOKEN,
+            DOT,
+            10_000,
+            9_900
+        ));
+        let liquidity = Assets::balance(SAMPLE_LP_TOKEN, EVE);
+        assert!(liquidity >= 9_900 && liquidity < 10_000);
+        assert_eq!(
+            DefaultStableSwap::multi_asset_pools(SAMPLE_LP_TOKEN)
+                .unwrap()
+                .balances
+                .to_vec(),
+            vec![1_010_000, 1_000_000, 1_000_000]
+        );
+
+        // and withdrawn as a single asset
+        assert_ok!(DefaultStableSwap::zap_out(
+            RawOrigin::Signed(EVE).into(),
+            SAMPLE_LP_TOKEN,
+            liquidity,
+            DOT,
+            9_800
+        ));
+        let lost = dot_balance - Assets::balance(DOT, EVE);
+        assert!(lost > 0 && lost <= 200);
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, EVE), 0);
+    })
+}
@@ -1,7 +1,14 @@
 use codec::{Decode, Encode, MaxEncodedLen};
-use frame_support::traits::tokens::Balance as BalanceT;
+use frame_support::{
+    traits::{tokens::Balance as BalanceT, Get},
+    BoundedVec, CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+};
 use scale_info::TypeInfo;
-use sp_runtime::{traits::Zero, RuntimeDebug};
+use sp_runtime::{
+    traits::{AtLeast32BitUnsigned, Saturating, Zero},
+    RuntimeDebug, SaturatedConversion,
+};
+use sp_std::fmt::Debug;
 
 #[derive(
     Encode,
@@ -46,3 +53,90 @@ impl<CurrencyId, Balance: BalanceT, BlockNumber: BalanceT> Pool<CurrencyId, Bala
         self.base_amount.is_zero() && self.quote_amount.is_zero()
     }
 }
+
+/// A bag of liquidity composed by up to `MaxAssets` different assets
+#[derive(
+    Encode,
+    Decode,
+    CloneNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    RuntimeDebugNoBound,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(MaxAssets))]
+#[codec(mel_bound(CurrencyId: MaxEncodedLen, Balance: MaxEncodedLen))]
+pub struct MultiAssetPool<
+    CurrencyId: Clone + PartialEq + Eq + Debug,
+    Balance: Clone + PartialEq + Eq + Debug,
+    MaxAssets: Get<u32>,
+> {
+    pub assets: BoundedVec<CurrencyId, MaxAssets>,
+    pub balances: BoundedVec<Balance, MaxAssets>,
+    pub lp_token_id: CurrencyId,
+}
+
+impl<CurrencyId, Balance, MaxAssets> MultiAssetPool<CurrencyId, Balance, MaxAssets>
+where
+    CurrencyId: Clone + PartialEq + Eq + Debug,
+    Balance: Clone + PartialEq + Eq + Debug,
+    MaxAssets: Get<u32>,
+{
+    /// The position of the asset in the pool
+    pub fn index_of(&self, asset_id: &CurrencyId) -> Option<usize> {
+        self.assets.iter().position(|a| a == asset_id)
+    }
+}
+
+/// A linear ramp of the amplification coefficient of a pool between two blocks
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct AmplificationRamp<BlockNumber> {
+    pub initial_amplification: u128,
+    pub future_amplification: u128,
+    pub initial_block: BlockNumber,
+    pub future_block: BlockNumber,
+}
+
+impl<BlockNumber: AtLeast32BitUnsigned + Copy> AmplificationRamp<BlockNumber> {
+    /// A constant amplification coefficient
+    pub fn new(amplification: u128, block_number: BlockNumber) -> Self {
+        Self {
+            initial_amplification: amplification,
+            future_amplification: amplification,
+            initial_block: block_number,
+            future_block: block_number,
+        }
+    }
+
+    /// The amplification coefficient at the block
+    pub fn amplification(&self, block_number: BlockNumber) -> u128 {
+        if block_number >= self.future_block {
+            return self.future_amplification;
+        }
+        if block_number <= self.initial_block {
+            return self.initial_amplification;
+        }
+
+        let elapsed: u128 = block_number
+            .saturating_sub(self.initial_block)
+            .saturated_into();
+        let duration: u128 = self
+            .future_block
+            .saturating_sub(self.initial_block)
+            .saturated_into();
+        if self.future_amplification > self.initial_amplification {
+            let change = self
+                .future_amplification
+                .saturating_sub(self.initial_amplification);
+            self.initial_amplification
+                .saturating_add(change.saturating_mul(elapsed) / duration)
+        } else {
+            let change = self
+                .initial_amplification
+                .saturating_sub(self.future_amplification);
+            self.initial_amplification
+                .saturating_sub(change.saturating_mul(elapsed) / duration)
+        }
+    }
+}
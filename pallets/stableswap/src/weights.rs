@@ -28,6 +28,16 @@ pub trait WeightInfo {
     fn add_liquidity() -> Weight;
     fn remove_liquidity() -> Weight;
     fn create_pool() -> Weight;
+    fn create_multi_asset_pool() -> Weight;
+    fn add_multi_asset_liquidity() -> Weight;
+    fn remove_multi_asset_liquidity() -> Weight;
+    fn remove_multi_asset_liquidity_imbalance() -> Weight;
+    fn remove_multi_asset_liquidity_one_asset() -> Weight;
+    fn ramp_amplification() -> Weight;
+    fn stop_ramp_amplification() -> Weight;
+    fn set_rate_scaled_assets() -> Weight;
+    fn zap_in() -> Weight;
+    fn zap_out() -> Weight;
 }
 
 /// Weights for stableswap using the Substrate node and recommended hardware.
@@ -48,6 +58,36 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn create_pool() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
+    fn create_multi_asset_pool() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn add_multi_asset_liquidity() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn remove_multi_asset_liquidity() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn remove_multi_asset_liquidity_imbalance() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn remove_multi_asset_liquidity_one_asset() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn ramp_amplification() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn stop_ramp_amplification() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn set_rate_scaled_assets() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn zap_in() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn zap_out() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
 }
 
 // For backwards compatibility and tests
@@ -67,4 +107,34 @@ impl WeightInfo for () {
     fn create_pool() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
+    fn create_multi_asset_pool() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn add_multi_asset_liquidity() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn remove_multi_asset_liquidity() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn remove_multi_asset_liquidity_imbalance() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn remove_multi_asset_liquidity_one_asset() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn ramp_amplification() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn stop_ramp_amplification() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn set_rate_scaled_assets() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn zap_in() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn zap_out() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
 }
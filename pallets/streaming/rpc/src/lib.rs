@@ -0,0 +1,183 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+pub use pallet_streaming_rpc_runtime_api::StreamingApi as StreamingRuntimeApi;
+
+use codec::Codec;
+use jsonrpsee::{
+    core::{async_trait, Error as JsonRpseeError, RpcResult},
+    proc_macros::rpc,
+    types::error::{CallError, ErrorCode, ErrorObject},
+};
+use pallet_traits::StreamInfo;
+use primitives::CurrencyId;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+/// A stream with balances in RPC representation.
+pub type RpcStreamInfo<AccountId> = StreamInfo<AccountId, CurrencyId, NumberOrHex>;
+
+#[rpc(client, server)]
+pub trait StreamingApi<BlockHash, AccountId, Balance>
+where
+    Balance: Codec + Copy + TryFrom<NumberOrHex>,
+{
+    #[method(name = "streaming_getSentStreams")]
+    fn get_sent_streams(
+        &self,
+        sender: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<RpcStreamInfo<AccountId>>>;
+    #[method(name = "streaming_getReceivedStreams")]
+    fn get_received_streams(
+        &self,
+        recipient: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<RpcStreamInfo<AccountId>>>;
+}
+
+/// A struct that implements the [`StreamingApi`].
+pub struct Streaming<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Streaming<C, B> {
+    /// Create new `Streaming` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+pub enum Error {
+    RuntimeError,
+    StreamError,
+}
+
+impl From<Error> for i32 {
+    fn from(e: Error) -> i32 {
+        match e {
+            Error::RuntimeError => 1,
+            Error::StreamError => 2,
+        }
+    }
+}
+
+#[async_trait]
+impl<C, Block, AccountId, Balance> StreamingApiServer<<Block as BlockT>::Hash, AccountId, Balance>
+    for Streaming<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static,
+    C: ProvideRuntimeApi<Block>,
+    C: HeaderBackend<Block>,
+    C::Api: StreamingRuntimeApi<Block, AccountId, Balance>,
+    AccountId: Codec,
+    Balance: Codec + Copy + TryFrom<NumberOrHex> + Into<NumberOrHex> + std::fmt::Display,
+{
+    fn get_sent_streams(
+        &self,
+        sender: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<RpcStreamInfo<AccountId>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        api.get_sent_streams(&at, sender)
+            .map_err(runtime_error_into_rpc_error)?
+            .map_err(stream_error_into_rpc_error)?
+            .into_iter()
+            .map(into_rpc_stream_info)
+            .collect()
+    }
+
+    fn get_received_streams(
+        &self,
+        recipient: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<RpcStreamInfo<AccountId>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        api.get_received_streams(&at, recipient)
+            .map_err(runtime_error_into_rpc_error)?
+            .map_err(stream_error_into_rpc_error)?
+            .into_iter()
+            .map(into_rpc_stream_info)
+            .collect()
+    }
+}
+
+fn into_rpc_stream_info<AccountId, Balance>(
+    stream: StreamInfo<AccountId, CurrencyId, Balance>,
+) -> RpcResult<RpcStreamInfo<AccountId>>
+where
+    Balance: Copy + TryInto<NumberOrHex> + std::fmt::Display,
+{
+    Ok(StreamInfo {
+        stream_id: stream.stream_id,
+        sender: stream.sender,
+        recipient: stream.recipient,
+        asset_id: stream.asset_id,
+        deposit: try_into_rpc_balance(stream.deposit)?,
+        remaining_balance: try_into_rpc_balance(stream.remaining_balance)?,
+        withdrawable: try_into_rpc_balance(stream.withdrawable)?,
+        start_time: stream.start_time,
+        end_time: stream.end_time,
+        cancellable: stream.cancellable,
+        as_collateral: stream.as_collateral,
+        finished: stream.finished,
+    })
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_error(err: impl std::fmt::Debug) -> JsonRpseeError {
+    JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        Error::RuntimeError.into(),
+        "Runtime trapped",
+        Some(format!("{:?}", err)),
+    )))
+}
+
+/// Converts a stream error into an RPC error.
+fn stream_error_into_rpc_error(err: impl std::fmt::Debug) -> JsonRpseeError {
+    JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        Error::StreamError.into(),
+        "Not able to get the streams",
+        Some(format!("{:?}", err)),
+    )))
+}
+
+fn try_into_rpc_balance<T: std::fmt::Display + Copy + TryInto<NumberOrHex>>(
+    value: T,
+) -> Result<NumberOrHex, JsonRpseeError> {
+    value.try_into().map_err(|_| {
+        JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+            ErrorCode::InvalidParams.code(),
+            format!("{} doesn't fit in NumberOrHex representation", value),
+            None::<()>,
+        )))
+    })
+}
@@ -104,6 +104,84 @@ benchmarks! {
         assert_last_event::<T>(Event::StreamWithdrawn(stream_id, recipient, KSM, withdraw_amount).into())
     }
 
+    create_with_schedule {
+        let n in 1 .. T::MaxSchedulePoints::get();
+        let caller: T::AccountId = whitelisted_caller();
+        transfer_initial_balance::<T>(caller.clone());
+        assert_ok!(Streaming::<T>::set_minimum_deposit(SystemOrigin::Root.into(), KSM, 0));
+
+        let recipient: T::AccountId = account("Streaming", 101, SEED);
+        let deposit_amount: u128 = dollar(5);
+        let start_time: u64 = 6;
+        let end_time: u64 = start_time + 12 * n as u64;
+        let points = (1..=n)
+            .map(|i| (start_time + 12 * i as u64, deposit_amount * i as u128 / n as u128))
+            .collect::<Vec<_>>();
+        let schedule = StreamSchedule::Piecewise { points: BoundedVec::truncate_from(points) };
+    }: _(SystemOrigin::Signed(caller.clone()), recipient.clone(), deposit_amount, KSM, start_time, end_time, schedule, true)
+    verify {
+        assert_last_event::<T>(Event::StreamCreated(0, caller, recipient, deposit_amount, KSM, start_time, end_time, true).into())
+    }
+
+    transfer_recipient {
+        let caller: T::AccountId = whitelisted_caller();
+        transfer_initial_balance::<T>(caller.clone());
+        assert_ok!(Streaming::<T>::set_minimum_deposit(SystemOrigin::Root.into(), KSM, 0));
+        let recipient: T::AccountId = account("Streaming", 101, SEED);
+        let new_recipient: T::AccountId = account("Streaming", 102, SEED);
+        assert_ok!(Streaming::<T>::create(SystemOrigin::Signed(caller).into(), recipient.clone(), dollar(5), KSM, 6, 18, true));
+        let stream_id: u128 = 0;
+    }: _(SystemOrigin::Signed(recipient.clone()), stream_id, new_recipient.clone())
+    verify {
+        assert_last_event::<T>(Event::StreamRecipientTransferred(stream_id, recipient, new_recipient).into())
+    }
+
+    transfer_sender {
+        let caller: T::AccountId = whitelisted_caller();
+        transfer_initial_balance::<T>(caller.clone());
+        assert_ok!(Streaming::<T>::set_minimum_deposit(SystemOrigin::Root.into(), KSM, 0));
+        let recipient: T::AccountId = account("Streaming", 101, SEED);
+        let new_sender: T::AccountId = account("Streaming", 102, SEED);
+        assert_ok!(Streaming::<T>::create(SystemOrigin::Signed(caller.clone()).into(), recipient, dollar(5), KSM, 6, 18, true));
+        let stream_id: u128 = 0;
+    }: _(SystemOrigin::Signed(caller.clone()), stream_id, new_sender.clone())
+    verify {
+        assert_last_event::<T>(Event::StreamSenderTransferred(stream_id, caller, new_sender).into())
+    }
+
+    batch_create {
+        let n in 1 .. T::MaxStreamsCount::get();
+        let caller: T::AccountId = whitelisted_caller();
+        transfer_initial_balance::<T>(caller.clone());
+        assert_ok!(Streaming::<T>::set_minimum_deposit(SystemOrigin::Root.into(), KSM, 0));
+
+        let deposit_amount: u128 = dollar(5);
+        let start_time: u64 = 6;
+        let end_time: u64 = 18;
+        let streams = (0..n)
+            .map(|i| (account("Streaming", 101 + i, SEED), deposit_amount))
+            .collect::<Vec<_>>();
+        let recipient = streams[n as usize - 1].0.clone();
+    }: _(SystemOrigin::Signed(caller.clone()), streams, KSM, start_time, end_time, true)
+    verify {
+        assert_last_event::<T>(Event::StreamCreated((n - 1).into(), caller, recipient, deposit_amount, KSM, start_time, end_time, true).into())
+    }
+
+    top_up {
+        let caller: T::AccountId = whitelisted_caller();
+        transfer_initial_balance::<T>(caller.clone());
+        assert_ok!(Streaming::<T>::set_minimum_deposit(SystemOrigin::Root.into(), KSM, 0));
+        let recipient: T::AccountId = account("Streaming", 101, SEED);
+        assert_ok!(Streaming::<T>::create(SystemOrigin::Signed(caller.clone()).into(), recipient, dollar(5), KSM, 6, 18, true));
+        pallet_timestamp::Pallet::<T>::set_timestamp(12000);
+        let stream_id: u128 = 0;
+        let top_up_amount: u128 = dollar(3);
+        let end_time: u64 = 30;
+    }: _(SystemOrigin::Signed(caller.clone()), stream_id, top_up_amount, end_time)
+    verify {
+        assert_last_event::<T>(Event::StreamToppedUp(stream_id, caller, top_up_amount, end_time).into())
+    }
+
     set_minimum_deposit {
         let minimum_deposit_amount: u128 = dollar(1);
     }: _(SystemOrigin::Root, KSM, minimum_deposit_amount)
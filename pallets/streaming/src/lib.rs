@@ -20,9 +20,10 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use crate::types::{Stream, StreamKind};
+use crate::types::{Stream, StreamKind, StreamSchedule};
 use frame_support::{
     pallet_prelude::*,
+    require_transactional,
     traits::{
         tokens::fungibles::{Inspect, Mutate, Transfer},
         UnixTime,
@@ -30,7 +31,7 @@ use frame_support::{
     transactional, PalletId,
 };
 use frame_system::pallet_prelude::*;
-use pallet_traits::Streaming as StreamingTrait;
+use pallet_traits::{StreamCollateral, StreamInfo, Streaming as StreamingTrait};
 use primitives::*;
 use sp_runtime::{
     traits::{AccountIdConversion, One, Zero},
@@ -45,7 +46,8 @@ mod tests;
 
 mod benchmarking;
 
-mod types;
+pub mod migrations;
+pub mod types;
 
 pub use pallet::*;
 
@@ -58,6 +60,13 @@ type BalanceOf<T> =
     <<T as Config>::Assets as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 type AccountOf<T> = <T as frame_system::Config>::AccountId;
 
+/// Utility type for managing upgrades/migrations.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum Versions {
+    V1,
+    V2,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
@@ -92,6 +101,10 @@ pub mod pallet {
         #[pallet::constant]
         type NativeExistentialDeposit: Get<Balance>;
 
+        /// The max count of points in a piecewise schedule
+        #[pallet::constant]
+        type MaxSchedulePoints: Get<u32>;
+
         /// The Unix time
         type UnixTime: UnixTime;
 
@@ -134,6 +147,16 @@ pub mod pallet {
         NotStarted,
         /// Stream was cancelled or completed
         HasFinished,
+        /// The schedule does not fit the deposit and duration of the stream
+        InvalidSchedule,
+        /// Stream is used as collateral
+        StreamIsCollateral,
+        /// Stream is not used as collateral
+        StreamIsNotCollateral,
+        /// Stream can be cancelled and cannot be used as collateral
+        StreamIsCancellable,
+        /// No stream in the batch
+        EmptyBatch,
     }
 
     #[pallet::event]
@@ -167,6 +190,15 @@ pub mod pallet {
         /// Set minimum deposit for creating a stream
         /// \[asset_id, minimum_deposit\]
         MinimumDepositSet(AssetIdOf<T>, BalanceOf<T>),
+        /// Transfer the recipient of a stream to another account
+        /// \[stream_id, old_recipient, new_recipient\]
+        StreamRecipientTransferred(StreamId, AccountOf<T>, AccountOf<T>),
+        /// Transfer the sender of a stream to another account
+        /// \[stream_id, old_sender, new_sender\]
+        StreamSenderTransferred(StreamId, AccountOf<T>, AccountOf<T>),
+        /// Top up the deposit of a stream and extend it
+        /// \[stream_id, sender, amount, end_time\]
+        StreamToppedUp(StreamId, AccountOf<T>, BalanceOf<T>, Timestamp),
     }
 
     /// Next Stream Id
@@ -198,6 +230,17 @@ pub mod pallet {
     #[pallet::getter(fn minimum_deposit)]
     pub type MinimumDeposits<T: Config> = StorageMap<_, Twox64Concat, AssetIdOf<T>, BalanceOf<T>>;
 
+    /// DefaultVersion is using for initialize the StorageVersion
+    #[pallet::type_value]
+    pub(super) fn DefaultVersion<T: Config>() -> Versions {
+        Versions::V1
+    }
+
+    /// Storage version of the pallet.
+    #[pallet::storage]
+    pub(crate) type StorageVersion<T: Config> =
+        StorageValue<_, Versions, ValueQuery, DefaultVersion<T>>;
+
     #[pallet::pallet]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
@@ -243,6 +286,7 @@ pub mod pallet {
                 asset_id,
                 start_time,
                 end_time,
+                StreamSchedule::Linear,
                 cancellable,
             )?;
             // Add the stream_id to stream_library for both the sender and receiver.
@@ -322,7 +366,7 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let recipient = ensure_signed(origin)?;
 
-            let mut stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::InvalidStreamId)?;
+            let stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::InvalidStreamId)?;
             ensure!(stream.is_recipient(&recipient), Error::<T>::NotTheRecipient);
             ensure!(!stream.has_finished(), Error::<T>::HasFinished);
             ensure!(stream.has_started()?, Error::<T>::NotStarted);
@@ -332,37 +376,7 @@ pub mod pallet {
                 Error::<T>::InsufficientStreamBalance
             );
 
-            let mut amount = amount;
-            if stream.asset_id == T::NativeCurrencyId::get()
-                && amount.saturating_add(T::NativeExistentialDeposit::get())
-                    >= stream.remaining_balance
-            {
-                amount = stream.remaining_balance
-            }
-
-            stream.try_deduct(amount)?;
-            stream.try_complete()?;
-            Streams::<T>::insert(stream_id, stream.clone());
-            if stream.has_finished() {
-                Self::try_push_stream_library(&stream.sender, stream_id, StreamKind::Finish)?;
-                Self::try_push_stream_library(&recipient, stream_id, StreamKind::Finish)?;
-                Self::update_finished_stream_library(&stream.sender, &recipient)?;
-            }
-
-            // Withdraw deposit from stream
-            T::Assets::transfer(
-                stream.asset_id,
-                &Self::account_id(),
-                &recipient,
-                amount,
-                false,
-            )?;
-            Self::deposit_event(Event::<T>::StreamWithdrawn(
-                stream_id,
-                stream.recipient,
-                stream.asset_id,
-                amount,
-            ));
+            Self::do_withdraw(stream_id, stream, amount)?;
 
             Ok(().into())
         }
@@ -387,6 +401,217 @@ pub mod pallet {
             Self::deposit_event(Event::<T>::MinimumDepositSet(asset_id, minimum_deposit));
             Ok(().into())
         }
+
+        /// Create a new stream which releases the deposit following a vesting schedule
+        ///
+        /// Same as `create`, but the deposit is released to the recipient by the given
+        /// schedule instead of linearly.
+        ///
+        /// - `recipient`: the receiving address
+        /// - `deposit`: the amount sender will deposit to create the stream
+        /// - `asset_id`: asset should be able to lookup.
+        /// - `start_time`: the time when the stream will start
+        /// - `end_time`: the time when the stream will end
+        /// - `schedule`: cliff, step or piecewise release of the deposit
+        #[pallet::call_index(4)]
+        #[pallet::weight(<T as Config>::WeightInfo::create_with_schedule(T::MaxSchedulePoints::get()))]
+        #[transactional]
+        pub fn create_with_schedule(
+            origin: OriginFor<T>,
+            recipient: AccountOf<T>,
+            deposit: BalanceOf<T>,
+            asset_id: AssetIdOf<T>,
+            start_time: Timestamp,
+            end_time: Timestamp,
+            schedule: StreamSchedule<T>,
+            cancellable: bool,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            let minimum_deposit =
+                Self::minimum_deposit(asset_id).ok_or(Error::<T>::InvalidAssetId)?;
+            ensure!(
+                deposit >= minimum_deposit,
+                Error::<T>::DepositLowerThanMinimum
+            );
+            Self::ensure_valid_duration(start_time, end_time)?;
+            Self::ensure_valid_schedule(deposit, start_time, end_time, &schedule)?;
+            let stream_id = Self::do_create(
+                sender.clone(),
+                recipient.clone(),
+                deposit,
+                asset_id,
+                start_time,
+                end_time,
+                schedule,
+                cancellable,
+            )?;
+            // Add the stream_id to stream_library for both the sender and receiver.
+            Self::try_push_stream_library(&sender, stream_id, StreamKind::Send)?;
+            Self::try_push_stream_library(&recipient, stream_id, StreamKind::Receive)?;
+            Ok(().into())
+        }
+
+        /// Transfer the recipient role of a stream to another account
+        ///
+        /// Can only be called by the recipient. The new recipient can withdraw
+        /// everything left in the stream, including the balance already streamed.
+        ///
+        /// - `stream_id`: the stream id which will be transferred
+        /// - `new_recipient`: the account which will receive the stream
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::transfer_recipient())]
+        #[transactional]
+        pub fn transfer_recipient(
+            origin: OriginFor<T>,
+            stream_id: StreamId,
+            new_recipient: AccountOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let recipient = ensure_signed(origin)?;
+
+            let stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::InvalidStreamId)?;
+            ensure!(stream.is_recipient(&recipient), Error::<T>::NotTheRecipient);
+            ensure!(!stream.is_collateral(), Error::<T>::StreamIsCollateral);
+
+            Self::do_transfer_recipient(stream_id, stream, new_recipient)?;
+            Ok(().into())
+        }
+
+        /// Transfer the sender role of a stream to another account
+        ///
+        /// Can only be called by the sender. The new sender can cancel the stream
+        /// and receives the balance not streamed yet.
+        ///
+        /// - `stream_id`: the stream id which will be transferred
+        /// - `new_sender`: the account which will fund the stream
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::transfer_sender())]
+        #[transactional]
+        pub fn transfer_sender(
+            origin: OriginFor<T>,
+            stream_id: StreamId,
+            new_sender: AccountOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+
+            let mut stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::InvalidStreamId)?;
+            ensure!(stream.is_sender(&sender), Error::<T>::NotTheSender);
+            ensure!(!stream.has_finished(), Error::<T>::HasFinished);
+            ensure!(!stream.is_collateral(), Error::<T>::StreamIsCollateral);
+            ensure!(
+                !stream.is_recipient(&new_sender),
+                Error::<T>::RecipientIsAlsoSender
+            );
+
+            stream.sender = new_sender.clone();
+            Streams::<T>::insert(stream_id, stream);
+
+            Self::try_remove_stream_library(&sender, stream_id, Some(StreamKind::Send))?;
+            Self::try_push_stream_library(&new_sender, stream_id, StreamKind::Send)?;
+
+            Self::deposit_event(Event::<T>::StreamSenderTransferred(
+                stream_id, sender, new_sender,
+            ));
+            Ok(().into())
+        }
+
+        /// Create a batch of streams sharing the same asset and duration
+        ///
+        /// The deposits of all the streams are transferred from the sender at once.
+        ///
+        /// - `streams`: the receiving address and the deposit of each stream
+        /// - `asset_id`: asset should be able to lookup.
+        /// - `start_time`: the time when the streams will start
+        /// - `end_time`: the time when the streams will end
+        #[pallet::call_index(7)]
+        #[pallet::weight(<T as Config>::WeightInfo::batch_create(streams.len() as u32))]
+        #[transactional]
+        pub fn batch_create(
+            origin: OriginFor<T>,
+            streams: Vec<(AccountOf<T>, BalanceOf<T>)>,
+            asset_id: AssetIdOf<T>,
+            start_time: Timestamp,
+            end_time: Timestamp,
+            cancellable: bool,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            ensure!(!streams.is_empty(), Error::<T>::EmptyBatch);
+            ensure!(
+                streams.len() as u32 <= T::MaxStreamsCount::get(),
+                Error::<T>::ExcessMaxStreamsCount
+            );
+            let minimum_deposit =
+                Self::minimum_deposit(asset_id).ok_or(Error::<T>::InvalidAssetId)?;
+            Self::ensure_valid_duration(start_time, end_time)?;
+
+            let mut total_deposit: BalanceOf<T> = Zero::zero();
+            let mut new_streams = Vec::with_capacity(streams.len());
+            for (recipient, deposit) in streams {
+                ensure!(
+                    deposit >= minimum_deposit,
+                    Error::<T>::DepositLowerThanMinimum
+                );
+                ensure!(sender != recipient, Error::<T>::RecipientIsAlsoSender);
+                let rate_per_sec = Self::rate_per_sec(deposit, start_time, end_time)?;
+                total_deposit = total_deposit
+                    .checked_add(deposit)
+                    .ok_or(ArithmeticError::Overflow)?;
+                new_streams.push(Stream::new(
+                    deposit,
+                    asset_id,
+                    rate_per_sec,
+                    sender.clone(),
+                    recipient,
+                    start_time,
+                    end_time,
+                    cancellable,
+                ));
+            }
+
+            // Transfer the deposits of all the streams to global EOA
+            T::Assets::transfer(asset_id, &sender, &Self::account_id(), total_deposit, false)?;
+
+            for stream in new_streams {
+                let recipient = stream.recipient.clone();
+                let stream_id = Self::insert_stream(stream)?;
+                Self::try_push_stream_library(&sender, stream_id, StreamKind::Send)?;
+                Self::try_push_stream_library(&recipient, stream_id, StreamKind::Receive)?;
+            }
+            Ok(().into())
+        }
+
+        /// Top up the deposit of a stream and extend its end time
+        ///
+        /// Can only be called by the sender of a linear stream. The balance already
+        /// streamed is withdrawn to the recipient, then what is left of the deposit
+        /// and the top up are streamed from now until the new end time.
+        ///
+        /// - `stream_id`: the stream id which will be topped up
+        /// - `amount`: the amount sender will add to the deposit
+        /// - `end_time`: the new end time, not before the current one
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::top_up())]
+        #[transactional]
+        pub fn top_up(
+            origin: OriginFor<T>,
+            stream_id: StreamId,
+            amount: BalanceOf<T>,
+            end_time: Timestamp,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+
+            let stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::InvalidStreamId)?;
+            ensure!(stream.is_sender(&sender), Error::<T>::NotTheSender);
+            ensure!(!stream.has_finished(), Error::<T>::HasFinished);
+            ensure!(!stream.is_collateral(), Error::<T>::StreamIsCollateral);
+            ensure!(
+                stream.schedule == StreamSchedule::Linear,
+                Error::<T>::InvalidSchedule
+            );
+            ensure!(end_time >= stream.end_time, Error::<T>::InvalidDuration);
+
+            Self::do_top_up(stream_id, stream, amount, end_time)?;
+            Ok(().into())
+        }
     }
 }
 
@@ -407,6 +632,54 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    pub fn rate_per_sec(
+        deposit: BalanceOf<T>,
+        start_time: Timestamp,
+        end_time: Timestamp,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let duration = end_time
+            .checked_sub(start_time)
+            .ok_or(Error::<T>::InvalidDuration)?;
+        let rate_per_sec = deposit
+            .checked_div(duration as u128)
+            .ok_or(Error::<T>::InvalidRatePerSecond)?;
+        ensure!(!rate_per_sec.is_zero(), Error::<T>::InvalidRatePerSecond);
+        Ok(rate_per_sec)
+    }
+
+    pub fn ensure_valid_schedule(
+        deposit: BalanceOf<T>,
+        start_time: Timestamp,
+        end_time: Timestamp,
+        schedule: &StreamSchedule<T>,
+    ) -> Result<(), DispatchError> {
+        let duration = end_time
+            .checked_sub(start_time)
+            .ok_or(Error::<T>::InvalidDuration)?;
+        let is_valid = match schedule {
+            StreamSchedule::Linear => true,
+            StreamSchedule::Cliff { cliff_time } => {
+                *cliff_time > start_time && *cliff_time < end_time
+            }
+            StreamSchedule::Step { interval } => {
+                !interval.is_zero() && *interval <= duration && (duration % interval).is_zero()
+            }
+            StreamSchedule::Piecewise { points } => {
+                // Timestamps strictly increase and amounts never decrease, the last point
+                // releases the whole deposit at the end of the stream
+                let (mut last_time, mut last_amount) = (start_time, Zero::zero());
+                let ordered = points.iter().all(|&(time, amount)| {
+                    let ordered = time > last_time && amount >= last_amount;
+                    (last_time, last_amount) = (time, amount);
+                    ordered
+                });
+                ordered && points.last() == Some(&(end_time, deposit))
+            }
+        };
+        ensure!(is_valid, Error::<T>::InvalidSchedule);
+        Ok(())
+    }
+
     pub fn update_finished_stream_library(
         sender: &AccountOf<T>,
         recipient: &AccountOf<T>,
@@ -489,6 +762,75 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Withdraws `amount` out of the stream to its recipient, the whole remaining
+    /// balance is withdrawn if what would be left of the native token is under the
+    /// existential deposit.
+    #[require_transactional]
+    pub fn do_withdraw(
+        stream_id: StreamId,
+        mut stream: Stream<T>,
+        amount: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let mut amount = amount;
+        if stream.asset_id == T::NativeCurrencyId::get()
+            && amount.saturating_add(T::NativeExistentialDeposit::get()) >= stream.remaining_balance
+        {
+            amount = stream.remaining_balance
+        }
+
+        stream.try_deduct(amount)?;
+        stream.try_complete()?;
+        Streams::<T>::insert(stream_id, stream.clone());
+        if stream.has_finished() {
+            Self::try_push_stream_library(&stream.sender, stream_id, StreamKind::Finish)?;
+            Self::try_push_stream_library(&stream.recipient, stream_id, StreamKind::Finish)?;
+            Self::update_finished_stream_library(&stream.sender, &stream.recipient)?;
+        }
+
+        // Withdraw deposit from stream
+        T::Assets::transfer(
+            stream.asset_id,
+            &Self::account_id(),
+            &stream.recipient,
+            amount,
+            false,
+        )?;
+        Self::deposit_event(Event::<T>::StreamWithdrawn(
+            stream_id,
+            stream.recipient,
+            stream.asset_id,
+            amount,
+        ));
+
+        Ok(amount)
+    }
+
+    #[require_transactional]
+    pub fn do_transfer_recipient(
+        stream_id: StreamId,
+        mut stream: Stream<T>,
+        new_recipient: AccountOf<T>,
+    ) -> DispatchResult {
+        ensure!(!stream.has_finished(), Error::<T>::HasFinished);
+        ensure!(
+            !stream.is_sender(&new_recipient),
+            Error::<T>::RecipientIsAlsoSender
+        );
+
+        let recipient = sp_std::mem::replace(&mut stream.recipient, new_recipient.clone());
+        Streams::<T>::insert(stream_id, stream);
+
+        Self::try_remove_stream_library(&recipient, stream_id, Some(StreamKind::Receive))?;
+        Self::try_push_stream_library(&new_recipient, stream_id, StreamKind::Receive)?;
+
+        Self::deposit_event(Event::<T>::StreamRecipientTransferred(
+            stream_id,
+            recipient,
+            new_recipient,
+        ));
+        Ok(())
+    }
+
     pub fn do_create(
         sender: AccountOf<T>,
         recipient: AccountOf<T>,
@@ -496,17 +838,11 @@ impl<T: Config> Pallet<T> {
         asset_id: AssetIdOf<T>,
         start_time: Timestamp,
         end_time: Timestamp,
+        schedule: StreamSchedule<T>,
         cancellable: bool,
     ) -> Result<StreamId, DispatchError> {
         ensure!(sender != recipient, Error::<T>::RecipientIsAlsoSender);
-
-        let duration = end_time
-            .checked_sub(start_time)
-            .ok_or(Error::<T>::InvalidDuration)?;
-        let rate_per_sec = deposit
-            .checked_div(duration as u128)
-            .ok_or(Error::<T>::InvalidRatePerSecond)?;
-        ensure!(!rate_per_sec.is_zero(), Error::<T>::InvalidRatePerSecond);
+        let rate_per_sec = Self::rate_per_sec(deposit, start_time, end_time)?;
 
         // Transfer deposit asset from sender to global EOA
         T::Assets::transfer(asset_id, &sender, &Self::account_id(), deposit, false)?;
@@ -516,13 +852,19 @@ impl<T: Config> Pallet<T> {
             deposit,
             asset_id,
             rate_per_sec,
-            sender.clone(),
-            recipient.clone(),
+            sender,
+            recipient,
             start_time,
             end_time,
             cancellable,
-        );
+        )
+        .with_schedule(schedule);
+
+        Self::insert_stream(stream)
+    }
 
+    /// Stores a new stream whose deposit has been transferred to the pallet account
+    fn insert_stream(stream: Stream<T>) -> Result<StreamId, DispatchError> {
         let stream_id = NextStreamId::<T>::get();
         // Increment next stream id and store the new created stream
         NextStreamId::<T>::set(
@@ -530,16 +872,111 @@ impl<T: Config> Pallet<T> {
                 .checked_add(One::one())
                 .ok_or(ArithmeticError::Overflow)?,
         );
-        Streams::<T>::insert(stream_id, stream);
+        Streams::<T>::insert(stream_id, stream.clone());
 
         // Remove the outdated and finished streams, should do update after push
-        Self::update_finished_stream_library(&sender, &recipient)?;
+        Self::update_finished_stream_library(&stream.sender, &stream.recipient)?;
 
         Self::deposit_event(Event::<T>::StreamCreated(
-            stream_id, sender, recipient, deposit, asset_id, start_time, end_time, true,
+            stream_id,
+            stream.sender,
+            stream.recipient,
+            stream.deposit,
+            stream.asset_id,
+            stream.start_time,
+            stream.end_time,
+            true,
         ));
         Ok(stream_id)
     }
+
+    #[require_transactional]
+    pub fn do_top_up(
+        stream_id: StreamId,
+        mut stream: Stream<T>,
+        amount: BalanceOf<T>,
+        end_time: Timestamp,
+    ) -> DispatchResult {
+        // Withdraw the balance already streamed, so that the stream restarts from now
+        let recipient_balance = stream.recipient_balance()?;
+        if !recipient_balance.is_zero() {
+            stream.try_deduct(recipient_balance)?;
+            T::Assets::transfer(
+                stream.asset_id,
+                &Self::account_id(),
+                &stream.recipient,
+                recipient_balance,
+                false,
+            )?;
+            Self::deposit_event(Event::<T>::StreamWithdrawn(
+                stream_id,
+                stream.recipient.clone(),
+                stream.asset_id,
+                recipient_balance,
+            ));
+        }
+
+        let start_time = stream.start_time.max(T::UnixTime::now().as_secs());
+        ensure!(end_time > start_time, Error::<T>::EndTimeBeforeStartTime);
+        let deposit = stream
+            .remaining_balance
+            .checked_add(amount)
+            .ok_or(ArithmeticError::Overflow)?;
+        let rate_per_sec = Self::rate_per_sec(deposit, start_time, end_time)?;
+
+        if !amount.is_zero() {
+            T::Assets::transfer(
+                stream.asset_id,
+                &stream.sender,
+                &Self::account_id(),
+                amount,
+                false,
+            )?;
+        }
+        stream.try_restart(deposit, rate_per_sec, start_time, end_time)?;
+        Streams::<T>::insert(stream_id, stream.clone());
+
+        Self::deposit_event(Event::<T>::StreamToppedUp(
+            stream_id,
+            stream.sender,
+            amount,
+            end_time,
+        ));
+        Ok(())
+    }
+
+    /// The streams sent or received by `account` with their withdrawable balances
+    pub fn get_streams(
+        account: &AccountOf<T>,
+        kind: StreamKind,
+    ) -> Result<Vec<StreamInfo<AccountOf<T>, AssetIdOf<T>, BalanceOf<T>>>, DispatchError> {
+        Self::stream_library(account, kind)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|stream_id| Streams::<T>::get(stream_id).map(|s| (stream_id, s)))
+            .map(|(stream_id, stream)| -> Result<_, DispatchError> {
+                let withdrawable = if stream.has_finished() {
+                    Zero::zero()
+                } else {
+                    stream.recipient_balance()?
+                };
+                Ok(StreamInfo {
+                    stream_id,
+                    withdrawable,
+                    as_collateral: stream.is_collateral(),
+                    finished: stream.has_finished(),
+                    sender: stream.sender,
+                    recipient: stream.recipient,
+                    asset_id: stream.asset_id,
+                    deposit: stream.deposit,
+                    remaining_balance: stream.remaining_balance,
+                    start_time: stream.start_time,
+                    end_time: stream.end_time,
+                    cancellable: stream.cancellable,
+                })
+            })
+            .collect()
+    }
 }
 
 impl<T: Config> StreamingTrait<AccountOf<T>, AssetIdOf<T>, BalanceOf<T>> for Pallet<T> {
@@ -563,6 +1000,7 @@ impl<T: Config> StreamingTrait<AccountOf<T>, AssetIdOf<T>, BalanceOf<T>> for Pal
             asset_id,
             start_time,
             end_time,
+            StreamSchedule::Linear,
             cancellable,
         )?;
         // Add the stream_id to stream_library for receiver.
@@ -570,3 +1008,61 @@ impl<T: Config> StreamingTrait<AccountOf<T>, AssetIdOf<T>, BalanceOf<T>> for Pal
         Ok(())
     }
 }
+
+impl<T: Config> StreamCollateral<AccountOf<T>, AssetIdOf<T>, BalanceOf<T>> for Pallet<T> {
+    fn pledge(
+        recipient: &AccountOf<T>,
+        stream_id: StreamId,
+    ) -> Result<AssetIdOf<T>, DispatchError> {
+        Streams::<T>::try_mutate(stream_id, |stream| -> Result<_, DispatchError> {
+            let stream = stream.as_mut().ok_or(Error::<T>::InvalidStreamId)?;
+            ensure!(stream.is_recipient(recipient), Error::<T>::NotTheRecipient);
+            ensure!(!stream.has_finished(), Error::<T>::HasFinished);
+            ensure!(!stream.cancellable, Error::<T>::StreamIsCancellable);
+            ensure!(!stream.is_collateral(), Error::<T>::StreamIsCollateral);
+            stream.as_collateral()?;
+            Ok(stream.asset_id)
+        })
+    }
+
+    fn release(recipient: &AccountOf<T>, stream_id: StreamId) -> Result<(), DispatchError> {
+        Streams::<T>::try_mutate(stream_id, |stream| -> DispatchResult {
+            let stream = stream.as_mut().ok_or(Error::<T>::InvalidStreamId)?;
+            ensure!(stream.is_recipient(recipient), Error::<T>::NotTheRecipient);
+            ensure!(stream.is_collateral(), Error::<T>::StreamIsNotCollateral);
+            stream.release_collateral()
+        })
+    }
+
+    fn unvested_balance(
+        stream_id: StreamId,
+    ) -> Result<(AssetIdOf<T>, BalanceOf<T>), DispatchError> {
+        let stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::InvalidStreamId)?;
+        if stream.has_finished() {
+            return Ok((stream.asset_id, Zero::zero()));
+        }
+        Ok((stream.asset_id, stream.sender_balance()?))
+    }
+
+    fn redirect(stream_id: StreamId, new_recipient: &AccountOf<T>) -> Result<(), DispatchError> {
+        let stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::InvalidStreamId)?;
+        ensure!(stream.is_collateral(), Error::<T>::StreamIsNotCollateral);
+        Self::do_transfer_recipient(stream_id, stream, new_recipient.clone())
+    }
+
+    fn withdraw_vested(stream_id: StreamId) -> Result<(AssetIdOf<T>, BalanceOf<T>), DispatchError> {
+        let stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::InvalidStreamId)?;
+        ensure!(!stream.has_finished(), Error::<T>::HasFinished);
+        let asset_id = stream.asset_id;
+        let recipient_balance = stream.recipient_balance()?;
+        if recipient_balance.is_zero() {
+            return Ok((asset_id, Zero::zero()));
+        }
+        let amount = Self::do_withdraw(stream_id, stream, recipient_balance)?;
+        Ok((asset_id, amount))
+    }
+
+    fn has_finished(stream_id: StreamId) -> bool {
+        Streams::<T>::get(stream_id).map_or(true, |stream| stream.has_finished())
+    }
+}
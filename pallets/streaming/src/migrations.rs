@@ -0,0 +1,140 @@
+// Copyright 2021-2022 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+pub mod v2 {
+    use super::*;
+    use crate::{pallet::StorageVersion, types::StreamStatus, Config};
+    use codec::DecodeAll;
+    use frame_support::{
+        log,
+        storage::unhashed,
+        traits::{Get, OnRuntimeUpgrade},
+        weights::Weight,
+    };
+
+    #[derive(Clone, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+    pub struct V1Stream<AccountId, AssetId, Balance> {
+        pub remaining_balance: Balance,
+        pub deposit: Balance,
+        pub asset_id: AssetId,
+        pub rate_per_sec: Balance,
+        pub sender: AccountId,
+        pub recipient: AccountId,
+        pub start_time: Timestamp,
+        pub end_time: Timestamp,
+        pub status: StreamStatus,
+        pub cancellable: bool,
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T>::get() == crate::Versions::V1,
+            "must upgrade linearly"
+        );
+        let streams_count = Streams::<T>::iter_keys().count();
+        log::info!("total {:#?} streams need to migrate", streams_count);
+
+        Ok(())
+    }
+
+    /// Migration to streams carrying a vesting schedule, existing streams keep
+    /// releasing their deposit linearly.
+    ///
+    /// Only streams whose encoding is exactly a `V1Stream` are translated, the streams
+    /// of a chain started with the V2 code already carry a schedule.
+    pub fn migrate<T: Config>() -> Weight {
+        if StorageVersion::<T>::get() == crate::Versions::V1 {
+            log::info!("migrating streaming to Versions::V2",);
+
+            let mut count = 0u64;
+            let mut migrated = 0u64;
+            for stream_id in Streams::<T>::iter_keys().collect::<Vec<_>>() {
+                count += 1;
+                let raw =
+                    unhashed::get_raw(&Streams::<T>::hashed_key_for(stream_id)).unwrap_or_default();
+                let stream = match V1Stream::<AccountOf<T>, AssetIdOf<T>, BalanceOf<T>>::decode_all(
+                    &mut &raw[..],
+                ) {
+                    Ok(stream) => stream,
+                    Err(_) => continue,
+                };
+                migrated += 1;
+                Streams::<T>::insert(
+                    stream_id,
+                    Stream {
+                        remaining_balance: stream.remaining_balance,
+                        deposit: stream.deposit,
+                        asset_id: stream.asset_id,
+                        rate_per_sec: stream.rate_per_sec,
+                        sender: stream.sender,
+                        recipient: stream.recipient,
+                        start_time: stream.start_time,
+                        end_time: stream.end_time,
+                        status: stream.status,
+                        cancellable: stream.cancellable,
+                        schedule: StreamSchedule::Linear,
+                    },
+                );
+            }
+
+            StorageVersion::<T>::put(crate::Versions::V2);
+            log::info!(
+                "👜 completed streaming migration to Versions::V2, {:?} of {:?} streams migrated",
+                migrated,
+                count
+            );
+
+            T::DbWeight::get().reads_writes(count + 1, migrated + 1)
+        } else {
+            T::DbWeight::get().reads(1)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T>::get() == crate::Versions::V2,
+            "must upgrade to V2"
+        );
+        frame_support::ensure!(
+            Streams::<T>::iter_keys().count() == Streams::<T>::iter().count(),
+            "all streams must be decodable"
+        );
+        log::info!("👜 streaming v2 migration passes POST migrate checks ✅",);
+
+        Ok(())
+    }
+
+    /// Runs the v2 migration on runtime upgrade
+    pub struct MigrateToV2<T>(sp_std::marker::PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            pre_migrate::<T>()?;
+            Ok(Vec::new())
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+            post_migrate::<T>()
+        }
+    }
+}
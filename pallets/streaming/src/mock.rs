@@ -157,6 +157,7 @@ parameter_types! {
     pub const StreamPalletId: PalletId = PalletId(*b"par/strm");
     pub const MaxStreamsCount: u32 = 128;
     pub const MaxFinishedStreamsCount: u32 = 2;
+    pub const MaxSchedulePoints: u32 = 4;
 }
 
 impl Config for Test {
@@ -170,6 +171,7 @@ impl Config for Test {
     type WeightInfo = ();
     type NativeCurrencyId = NativeCurrencyId;
     type NativeExistentialDeposit = ExistentialDeposit;
+    type MaxSchedulePoints = MaxSchedulePoints;
 }
 
 pub fn dollar(d: u128) -> u128 {
@@ -955,3 +955,473 @@ fn create_with_lots_stream_works() {
         }
     })
 }
+
+#[test]
+fn cliff_schedule_works() {
+    new_test_ext().execute_with(|| {
+        // Alice creates stream 100 DOT to Bob, nothing is released before the cliff
+        let stream_id = NextStreamId::<Test>::get();
+        assert_ok!(Streaming::create_with_schedule(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            dollar(100),
+            DOT,
+            6,
+            106,
+            StreamSchedule::Cliff { cliff_time: 56 },
+            true,
+        ));
+
+        // Time passes for 49 seconds, just before the cliff
+        TimestampPallet::set_timestamp(55000);
+        let stream = Streams::<Test>::get(stream_id).unwrap();
+        assert_eq!(stream.recipient_balance().unwrap(), 0);
+        assert_eq!(stream.sender_balance().unwrap(), dollar(100));
+        assert_err!(
+            Streaming::withdraw(RuntimeOrigin::signed(BOB), stream_id, 1),
+            Error::<Test>::InsufficientStreamBalance
+        );
+
+        // Half of the deposit is released at the cliff
+        TimestampPallet::set_timestamp(56000);
+        assert_ok!(Streaming::withdraw(
+            RuntimeOrigin::signed(BOB),
+            stream_id,
+            dollar(50)
+        ));
+
+        // Then the deposit is released linearly
+        TimestampPallet::set_timestamp(81000);
+        let stream = Streams::<Test>::get(stream_id).unwrap();
+        assert_eq!(stream.recipient_balance().unwrap(), dollar(25));
+        assert_eq!(stream.sender_balance().unwrap(), dollar(25));
+
+        // All the remaining is released at the end
+        TimestampPallet::set_timestamp(106000);
+        assert_ok!(Streaming::withdraw(
+            RuntimeOrigin::signed(BOB),
+            stream_id,
+            dollar(50)
+        ));
+        assert!(Streams::<Test>::get(stream_id).unwrap().has_finished());
+    });
+}
+
+#[test]
+fn step_schedule_works() {
+    new_test_ext().execute_with(|| {
+        // Alice creates stream 120 DOT to Bob, released in 4 tranches every 30 seconds
+        let stream_id = NextStreamId::<Test>::get();
+        assert_ok!(Streaming::create_with_schedule(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            dollar(120),
+            DOT,
+            6,
+            126,
+            StreamSchedule::Step { interval: 30 },
+            true,
+        ));
+
+        TimestampPallet::set_timestamp(35000);
+        let stream = Streams::<Test>::get(stream_id).unwrap();
+        assert_eq!(stream.recipient_balance().unwrap(), 0);
+
+        TimestampPallet::set_timestamp(36000);
+        assert_eq!(stream.recipient_balance().unwrap(), dollar(30));
+        assert_ok!(Streaming::withdraw(
+            RuntimeOrigin::signed(BOB),
+            stream_id,
+            dollar(30)
+        ));
+
+        // The second tranche is not released before the next interval
+        TimestampPallet::set_timestamp(95000);
+        let stream = Streams::<Test>::get(stream_id).unwrap();
+        assert_eq!(stream.recipient_balance().unwrap(), dollar(30));
+        assert_eq!(stream.sender_balance().unwrap(), dollar(60));
+
+        // Alice cancels, the unreleased tranches go back to her
+        let before_alice = <Test as Config>::Assets::balance(DOT, &ALICE);
+        let before_bob = <Test as Config>::Assets::balance(DOT, &BOB);
+        assert_ok!(Streaming::cancel(RuntimeOrigin::signed(ALICE), stream_id));
+        assert_eq!(
+            <Test as Config>::Assets::balance(DOT, &ALICE) - before_alice,
+            dollar(60)
+        );
+        assert_eq!(
+            <Test as Config>::Assets::balance(DOT, &BOB) - before_bob,
+            dollar(30)
+        );
+    });
+}
+
+#[test]
+fn piecewise_schedule_works() {
+    new_test_ext().execute_with(|| {
+        // Alice creates stream 100 DOT to Bob, 10 DOT in the first 10 seconds, nothing
+        // for the next 40 seconds and the rest in the last 50 seconds
+        let stream_id = NextStreamId::<Test>::get();
+        let points =
+            BoundedVec::try_from(vec![(16, dollar(10)), (56, dollar(10)), (106, dollar(100))])
+                .unwrap();
+        assert_ok!(Streaming::create_with_schedule(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            dollar(100),
+            DOT,
+            6,
+            106,
+            StreamSchedule::Piecewise { points },
+            true,
+        ));
+
+        TimestampPallet::set_timestamp(11000);
+        let stream = Streams::<Test>::get(stream_id).unwrap();
+        assert_eq!(stream.recipient_balance().unwrap(), dollar(5));
+
+        TimestampPallet::set_timestamp(36000);
+        assert_eq!(stream.recipient_balance().unwrap(), dollar(10));
+        assert_ok!(Streaming::withdraw(
+            RuntimeOrigin::signed(BOB),
+            stream_id,
+            dollar(10)
+        ));
+
+        // 10 + (100 - 10) * 25 / 50 = 55
+        TimestampPallet::set_timestamp(81000);
+        let stream = Streams::<Test>::get(stream_id).unwrap();
+        assert_eq!(stream.recipient_balance().unwrap(), dollar(45));
+        assert_eq!(stream.sender_balance().unwrap(), dollar(45));
+
+        TimestampPallet::set_timestamp(106000);
+        assert_eq!(stream.recipient_balance().unwrap(), dollar(90));
+        assert_eq!(stream.sender_balance().unwrap(), 0);
+    });
+}
+
+#[test]
+fn create_with_invalid_schedule_fails() {
+    new_test_ext().execute_with(|| {
+        let create = |schedule| {
+            Streaming::create_with_schedule(
+                RuntimeOrigin::signed(ALICE),
+                BOB,
+                dollar(100),
+                DOT,
+                6,
+                106,
+                schedule,
+                true,
+            )
+        };
+
+        // Cliff must be between start and end
+        assert_err!(
+            create(StreamSchedule::Cliff { cliff_time: 6 }),
+            Error::<Test>::InvalidSchedule
+        );
+        assert_err!(
+            create(StreamSchedule::Cliff { cliff_time: 106 }),
+            Error::<Test>::InvalidSchedule
+        );
+        // Interval must divide the duration
+        assert_err!(
+            create(StreamSchedule::Step { interval: 0 }),
+            Error::<Test>::InvalidSchedule
+        );
+        assert_err!(
+            create(StreamSchedule::Step { interval: 30 }),
+            Error::<Test>::InvalidSchedule
+        );
+        // Points must be ordered and release the whole deposit at the end
+        assert_err!(
+            create(StreamSchedule::Piecewise {
+                points: BoundedVec::default()
+            }),
+            Error::<Test>::InvalidSchedule
+        );
+        assert_err!(
+            create(StreamSchedule::Piecewise {
+                points: BoundedVec::try_from(vec![(56, dollar(60)), (106, dollar(90))]).unwrap()
+            }),
+            Error::<Test>::InvalidSchedule
+        );
+        assert_err!(
+            create(StreamSchedule::Piecewise {
+                points: BoundedVec::try_from(vec![
+                    (56, dollar(60)),
+                    (56, dollar(60)),
+                    (106, dollar(100))
+                ])
+                .unwrap()
+            }),
+            Error::<Test>::InvalidSchedule
+        );
+        assert_err!(
+            create(StreamSchedule::Piecewise {
+                points: BoundedVec::try_from(vec![
+                    (56, dollar(60)),
+                    (86, dollar(50)),
+                    (106, dollar(100))
+                ])
+                .unwrap()
+            }),
+            Error::<Test>::InvalidSchedule
+        );
+    });
+}
+
+#[test]
+fn transfer_recipient_works() {
+    new_test_ext().execute_with(|| {
+        // Alice creates stream 100 DOT to Bob
+        let stream_id = NextStreamId::<Test>::get();
+        assert_ok!(Streaming::create(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            dollar(100),
+            DOT,
+            6,
+            106,
+            true,
+        ));
+
+        // Only the recipient can transfer the stream, but not to the sender
+        assert_err!(
+            Streaming::transfer_recipient(RuntimeOrigin::signed(DAVE), stream_id, DAVE),
+            Error::<Test>::NotTheRecipient
+        );
+        assert_err!(
+            Streaming::transfer_recipient(RuntimeOrigin::signed(BOB), stream_id, ALICE),
+            Error::<Test>::RecipientIsAlsoSender
+        );
+
+        // Bob sells the stream to Dave after 50 seconds
+        TimestampPallet::set_timestamp(56000);
+        assert_ok!(Streaming::transfer_recipient(
+            RuntimeOrigin::signed(BOB),
+            stream_id,
+            DAVE
+        ));
+        assert!(Streams::<Test>::get(stream_id).unwrap().is_recipient(&DAVE));
+        assert!(!StreamLibrary::<Test>::get(BOB, StreamKind::Receive)
+            .unwrap()
+            .contains(&stream_id));
+        assert!(StreamLibrary::<Test>::get(DAVE, StreamKind::Receive)
+            .unwrap()
+            .contains(&stream_id));
+
+        // Dave withdraws the balance already streamed
+        assert_err!(
+            Streaming::withdraw(RuntimeOrigin::signed(BOB), stream_id, 1),
+            Error::<Test>::NotTheRecipient
+        );
+        let before_dave = <Test as Config>::Assets::balance(DOT, &DAVE);
+        assert_ok!(Streaming::withdraw(
+            RuntimeOrigin::signed(DAVE),
+            stream_id,
+            dollar(50)
+        ));
+        assert_eq!(
+            <Test as Config>::Assets::balance(DOT, &DAVE) - before_dave,
+            dollar(50)
+        );
+
+        // Stream used as collateral cannot be transferred
+        let mut stream = Streams::<Test>::get(stream_id).unwrap();
+        stream.as_collateral().unwrap();
+        Streams::<Test>::insert(stream_id, stream);
+        assert_err!(
+            Streaming::transfer_recipient(RuntimeOrigin::signed(DAVE), stream_id, BOB),
+            Error::<Test>::StreamIsCollateral
+        );
+    });
+}
+
+#[test]
+fn transfer_sender_works() {
+    new_test_ext().execute_with(|| {
+        // Alice creates stream 100 DOT to Bob
+        let stream_id = NextStreamId::<Test>::get();
+        assert_ok!(Streaming::create(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            dollar(100),
+            DOT,
+            6,
+            106,
+            true,
+        ));
+
+        assert_err!(
+            Streaming::transfer_sender(RuntimeOrigin::signed(BOB), stream_id, DAVE),
+            Error::<Test>::NotTheSender
+        );
+        assert_err!(
+            Streaming::transfer_sender(RuntimeOrigin::signed(ALICE), stream_id, BOB),
+            Error::<Test>::RecipientIsAlsoSender
+        );
+
+        // Alice hands off the stream to Dave
+        assert_ok!(Streaming::transfer_sender(
+            RuntimeOrigin::signed(ALICE),
+            stream_id,
+            DAVE
+        ));
+        assert!(!StreamLibrary::<Test>::get(ALICE, StreamKind::Send)
+            .unwrap()
+            .contains(&stream_id));
+        assert!(StreamLibrary::<Test>::get(DAVE, StreamKind::Send)
+            .unwrap()
+            .contains(&stream_id));
+
+        // Dave can cancel the stream and receives the balance not streamed yet
+        TimestampPallet::set_timestamp(56000);
+        assert_err!(
+            Streaming::cancel(RuntimeOrigin::signed(ALICE), stream_id),
+            Error::<Test>::NotTheSender
+        );
+        let before_dave = <Test as Config>::Assets::balance(DOT, &DAVE);
+        let before_bob = <Test as Config>::Assets::balance(DOT, &BOB);
+        assert_ok!(Streaming::cancel(RuntimeOrigin::signed(DAVE), stream_id));
+        assert_eq!(
+            <Test as Config>::Assets::balance(DOT, &DAVE) - before_dave,
+            dollar(50)
+        );
+        assert_eq!(
+            <Test as Config>::Assets::balance(DOT, &BOB) - before_bob,
+            dollar(50)
+        );
+    });
+}
+
+#[test]
+fn batch_create_works() {
+    new_test_ext().execute_with(|| {
+        assert_err!(
+            Streaming::batch_create(RuntimeOrigin::signed(ALICE), vec![], DOT, 6, 106, true),
+            Error::<Test>::EmptyBatch
+        );
+        assert_err!(
+            Streaming::batch_create(
+                RuntimeOrigin::signed(ALICE),
+                vec![(BOB, dollar(100)), (ALICE, dollar(50))],
+                DOT,
+                6,
+                106,
+                true
+            ),
+            Error::<Test>::RecipientIsAlsoSender
+        );
+
+        // Alice streams 100 DOT to Bob and 50 DOT to Dave with a single deposit
+        let before_alice = <Test as Config>::Assets::balance(DOT, &ALICE);
+        assert_ok!(Streaming::batch_create(
+            RuntimeOrigin::signed(ALICE),
+            vec![(BOB, dollar(100)), (DAVE, dollar(50))],
+            DOT,
+            6,
+            106,
+            true
+        ));
+        assert_eq!(
+            before_alice - <Test as Config>::Assets::balance(DOT, &ALICE),
+            dollar(150)
+        );
+        assert_eq!(NextStreamId::<Test>::get(), 2);
+        assert_eq!(
+            StreamLibrary::<Test>::get(ALICE, StreamKind::Send)
+                .unwrap()
+                .into_inner(),
+            vec![1, 0]
+        );
+        assert!(StreamLibrary::<Test>::get(BOB, StreamKind::Receive)
+            .unwrap()
+            .contains(&0));
+        assert!(StreamLibrary::<Test>::get(DAVE, StreamKind::Receive)
+            .unwrap()
+            .contains(&1));
+
+        // The streams list their withdrawable balances
+        TimestampPallet::set_timestamp(56000);
+        let streams = Streaming::get_streams(&ALICE, StreamKind::Send).unwrap();
+        assert_eq!(
+            streams
+                .iter()
+                .map(|s| (s.stream_id, s.recipient, s.withdrawable))
+                .collect::<Vec<_>>(),
+            vec![(1, DAVE, dollar(25)), (0, BOB, dollar(50))]
+        );
+        let streams = Streaming::get_streams(&DAVE, StreamKind::Receive).unwrap();
+        assert_eq!(streams.len(), 1);
+        assert_eq!(streams[0].sender, ALICE);
+        assert_eq!(streams[0].deposit, dollar(50));
+    });
+}
+
+#[test]
+fn top_up_works() {
+    new_test_ext().execute_with(|| {
+        // Alice creates stream 100 DOT to Bob
+        let stream_id = NextStreamId::<Test>::get();
+        assert_ok!(Streaming::create(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            dollar(100),
+            DOT,
+            6,
+            106,
+            true,
+        ));
+
+        assert_err!(
+            Streaming::top_up(RuntimeOrigin::signed(BOB), stream_id, dollar(50), 206),
+            Error::<Test>::NotTheSender
+        );
+        assert_err!(
+            Streaming::top_up(RuntimeOrigin::signed(ALICE), stream_id, dollar(50), 100),
+            Error::<Test>::InvalidDuration
+        );
+
+        // Alice tops up 50 DOT and extends the stream by 100 seconds halfway through,
+        // the 50 DOT already streamed go to Bob
+        TimestampPallet::set_timestamp(56000);
+        let before_alice = <Test as Config>::Assets::balance(DOT, &ALICE);
+        let before_bob = <Test as Config>::Assets::balance(DOT, &BOB);
+        assert_ok!(Streaming::top_up(
+            RuntimeOrigin::signed(ALICE),
+            stream_id,
+            dollar(50),
+            206
+        ));
+        assert_eq!(
+            before_alice - <Test as Config>::Assets::balance(DOT, &ALICE),
+            dollar(50)
+        );
+        assert_eq!(
+            <Test as Config>::Assets::balance(DOT, &BOB) - before_bob,
+            dollar(50)
+        );
+
+        // The 100 DOT left are streamed from now until the new end time
+        let stream = Streams::<Test>::get(stream_id).unwrap();
+        assert_eq!(stream.deposit, dollar(100));
+        assert_eq!(stream.remaining_balance, dollar(100));
+        assert_eq!(stream.start_time, 56);
+        assert_eq!(stream.end_time, 206);
+        assert_eq!(stream.recipient_balance().unwrap(), 0);
+
+        TimestampPallet::set_timestamp(206000);
+        assert_ok!(Streaming::withdraw(
+            RuntimeOrigin::signed(BOB),
+            stream_id,
+            dollar(100)
+        ));
+        assert!(Streams::<Test>::get(stream_id).unwrap().has_finished());
+        assert_err!(
+            Streaming::top_up(RuntimeOrigin::signed(ALICE), stream_id, dollar(50), 306),
+            Error::<Test>::HasFinished
+        );
+    });
+}
@@ -1,5 +1,8 @@
 use codec::{Decode, Encode, MaxEncodedLen};
-use frame_support::{traits::UnixTime, RuntimeDebug};
+use frame_support::{
+    traits::UnixTime, BoundedVec, CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebug,
+    RuntimeDebugNoBound,
+};
 use primitives::Timestamp;
 use scale_info::TypeInfo;
 
@@ -30,6 +33,39 @@ pub enum StreamKind {
     Finish,
 }
 
+#[derive(
+    Encode,
+    Decode,
+    CloneNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    RuntimeDebugNoBound,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(T))]
+#[codec(mel_bound())]
+pub enum StreamSchedule<T: Config> {
+    // The deposit is released linearly from `start_time` to `end_time`
+    Linear,
+    // Nothing is released before `cliff_time`, then the deposit is released as if it
+    // were linear from `start_time`
+    Cliff {
+        cliff_time: Timestamp,
+    },
+    // The deposit is released in equal tranches at the end of every `interval`
+    // seconds from `start_time`
+    Step {
+        interval: u64,
+    },
+    // The deposit is released linearly between points of (timestamp, cumulative
+    // amount released), starting from nothing at `start_time`. The last point is at
+    // `end_time` and releases the whole deposit
+    Piecewise {
+        points: BoundedVec<(Timestamp, BalanceOf<T>), T::MaxSchedulePoints>,
+    },
+}
+
 #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 #[scale_info(skip_type_params(T))]
 #[codec(mel_bound())]
@@ -54,6 +90,8 @@ pub struct Stream<T: Config> {
     pub status: StreamStatus,
     // Whether the stream can be cancelled
     pub cancellable: bool,
+    // How the deposit is released over the stream
+    pub schedule: StreamSchedule<T>,
 }
 
 impl<T: Config> Stream<T> {
@@ -80,9 +118,15 @@ impl<T: Config> Stream<T> {
                 as_collateral: false,
             },
             cancellable,
+            schedule: StreamSchedule::Linear,
         }
     }
 
+    pub fn with_schedule(mut self, schedule: StreamSchedule<T>) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
     pub fn is_sender(&self, account: &AccountOf<T>) -> bool {
         *account == self.sender
     }
@@ -106,6 +150,15 @@ impl<T: Config> Stream<T> {
         }
     }
 
+    pub fn is_collateral(&self) -> bool {
+        matches!(
+            self.status,
+            StreamStatus::Ongoing {
+                as_collateral: true
+            }
+        )
+    }
+
     pub fn has_started(&self) -> Result<bool, DispatchError> {
         let delta = self.delta_of()? as BalanceOf<T>;
 
@@ -153,6 +206,24 @@ impl<T: Config> Stream<T> {
         Ok(())
     }
 
+    // Stream what is left of the deposit again from `start_time`, the balance already
+    // streamed must have been withdrawn
+    pub fn try_restart(
+        &mut self,
+        deposit: BalanceOf<T>,
+        rate_per_sec: BalanceOf<T>,
+        start_time: Timestamp,
+        end_time: Timestamp,
+    ) -> DispatchResult {
+        self.deposit = deposit;
+        self.remaining_balance = deposit;
+        self.rate_per_sec = rate_per_sec;
+        self.start_time = start_time;
+        self.end_time = end_time;
+
+        Ok(())
+    }
+
     pub fn as_collateral(&mut self) -> DispatchResult {
         self.status = StreamStatus::Ongoing {
             as_collateral: true,
@@ -162,6 +233,14 @@ impl<T: Config> Stream<T> {
         Ok(())
     }
 
+    pub fn release_collateral(&mut self) -> DispatchResult {
+        self.status = StreamStatus::Ongoing {
+            as_collateral: false,
+        };
+
+        Ok(())
+    }
+
     pub fn delta_of(&self) -> Result<u64, DispatchError> {
         let now = T::UnixTime::now().as_secs();
         if now <= self.start_time {
@@ -176,8 +255,63 @@ impl<T: Config> Stream<T> {
         }
     }
 
-    // Measure balance of stream with rate per sec
+    // Measure the amount released by the schedule of the stream until now, including
+    // the amount withdrawn
+    fn streamed_balance(&self, delta: u64) -> Result<BalanceOf<T>, DispatchError> {
+        let streamed = match &self.schedule {
+            StreamSchedule::Linear => (delta as BalanceOf<T>)
+                .checked_mul(self.rate_per_sec)
+                .ok_or(ArithmeticError::Overflow)?,
+            StreamSchedule::Cliff { cliff_time } => {
+                if self.start_time.saturating_add(delta) < *cliff_time {
+                    Zero::zero()
+                } else {
+                    self.deposit_part(delta, self.duration()?)?
+                }
+            }
+            StreamSchedule::Step { interval } => {
+                let elapsed = delta
+                    .checked_sub(delta.checked_rem(*interval).unwrap_or_default())
+                    .ok_or(ArithmeticError::Underflow)?;
+                self.deposit_part(elapsed, self.duration()?)?
+            }
+            StreamSchedule::Piecewise { points } => {
+                let now = self.start_time.saturating_add(delta);
+                let (mut last_time, mut last_amount) = (self.start_time, Zero::zero());
+                for &(time, amount) in points.iter() {
+                    if now < time {
+                        let increase = amount
+                            .checked_sub(last_amount)
+                            .ok_or(ArithmeticError::Underflow)?;
+                        let part = increase
+                            .checked_mul((now - last_time) as BalanceOf<T>)
+                            .and_then(|r| r.checked_div((time - last_time) as BalanceOf<T>))
+                            .ok_or(ArithmeticError::Overflow)?;
+                        return Ok(last_amount
+                            .checked_add(part)
+                            .ok_or(ArithmeticError::Overflow)?);
+                    }
+                    (last_time, last_amount) = (time, amount);
+                }
+                last_amount
+            }
+        };
+
+        Ok(streamed)
+    }
+
+    // The part of the deposit matching `elapsed` seconds out of `duration`
+    fn deposit_part(&self, elapsed: u64, duration: u64) -> Result<BalanceOf<T>, DispatchError> {
+        Ok(self
+            .deposit
+            .checked_mul(elapsed as BalanceOf<T>)
+            .and_then(|r| r.checked_div(duration as BalanceOf<T>))
+            .ok_or(ArithmeticError::Overflow)?)
+    }
+
+    // Measure balance of stream with its schedule
     pub fn balance_of(&self, who: &AccountOf<T>) -> Result<BalanceOf<T>, DispatchError> {
+        let streamed_balance = self.streamed_balance(self.delta_of()?)?;
         let delta = self.delta_of()? as BalanceOf<T>;
 
         /*
@@ -190,17 +324,11 @@ impl<T: Config> Stream<T> {
             // otherwise some amount will be lost
             self.remaining_balance
         } else if self.has_withdrawn() {
-            let available_balance = delta
-                .checked_mul(self.rate_per_sec)
-                .ok_or(ArithmeticError::Overflow)?;
-
-            available_balance
+            streamed_balance
                 .checked_sub(self.claimed_balance()?)
                 .ok_or(ArithmeticError::Underflow)?
         } else {
-            delta
-                .checked_mul(self.rate_per_sec)
-                .ok_or(ArithmeticError::Overflow)?
+            streamed_balance
         };
 
         match *who {
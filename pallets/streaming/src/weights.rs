@@ -49,6 +49,11 @@ pub trait WeightInfo {
 	fn cancel() -> Weight;
 	fn withdraw() -> Weight;
 	fn set_minimum_deposit() -> Weight;
+	fn create_with_schedule(n: u32, ) -> Weight;
+	fn transfer_recipient() -> Weight;
+	fn transfer_sender() -> Weight;
+	fn batch_create(n: u32, ) -> Weight;
+	fn top_up() -> Weight;
 }
 
 /// Weights for pallet_streaming using the Substrate node and recommended hardware.
@@ -98,6 +103,69 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1 as u64))
 			.saturating_add(T::DbWeight::get().writes(2 as u64))
 	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Streaming MinimumDeposits (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Streaming NextStreamId (r:1 w:1)
+	// Storage: Streaming StreamLibrary (r:4 w:4)
+	// Storage: Streaming Streams (r:0 w:1)
+	/// The range of component `n` is `[1, 24]`.
+	fn create_with_schedule(n: u32, ) -> Weight {
+		Weight::from_ref_time(174_512_000 as u64)
+			// Standard Error: 3_000
+			.saturating_add(Weight::from_ref_time(412_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(12 as u64))
+			.saturating_add(T::DbWeight::get().writes(11 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Streaming Streams (r:1 w:1)
+	// Storage: Streaming StreamLibrary (r:2 w:2)
+	fn transfer_recipient() -> Weight {
+		Weight::from_ref_time(58_613_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Streaming Streams (r:1 w:1)
+	// Storage: Streaming StreamLibrary (r:2 w:2)
+	fn transfer_sender() -> Weight {
+		Weight::from_ref_time(57_902_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Streaming MinimumDeposits (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Streaming NextStreamId (r:1 w:1)
+	// Storage: Streaming StreamLibrary (r:2 w:2)
+	// Storage: Streaming Streams (r:0 w:1)
+	/// The range of component `n` is `[1, 128]`.
+	fn batch_create(n: u32, ) -> Weight {
+		Weight::from_ref_time(96_714_000 as u64)
+			// Standard Error: 21_000
+			.saturating_add(Weight::from_ref_time(41_287_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(10 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(8 as u64))
+			.saturating_add(T::DbWeight::get().writes((3 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Streaming Streams (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:3 w:3)
+	// Storage: System Account (r:1 w:1)
+	fn top_up() -> Weight {
+		Weight::from_ref_time(162_357_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(8 as u64))
+			.saturating_add(T::DbWeight::get().writes(7 as u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -146,4 +214,67 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1 as u64))
 			.saturating_add(RocksDbWeight::get().writes(2 as u64))
 	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Streaming MinimumDeposits (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Streaming NextStreamId (r:1 w:1)
+	// Storage: Streaming StreamLibrary (r:4 w:4)
+	// Storage: Streaming Streams (r:0 w:1)
+	/// The range of component `n` is `[1, 24]`.
+	fn create_with_schedule(n: u32, ) -> Weight {
+		Weight::from_ref_time(174_512_000 as u64)
+			// Standard Error: 3_000
+			.saturating_add(Weight::from_ref_time(412_000 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(12 as u64))
+			.saturating_add(RocksDbWeight::get().writes(11 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Streaming Streams (r:1 w:1)
+	// Storage: Streaming StreamLibrary (r:2 w:2)
+	fn transfer_recipient() -> Weight {
+		Weight::from_ref_time(58_613_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Streaming Streams (r:1 w:1)
+	// Storage: Streaming StreamLibrary (r:2 w:2)
+	fn transfer_sender() -> Weight {
+		Weight::from_ref_time(57_902_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Streaming MinimumDeposits (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Streaming NextStreamId (r:1 w:1)
+	// Storage: Streaming StreamLibrary (r:2 w:2)
+	// Storage: Streaming Streams (r:0 w:1)
+	/// The range of component `n` is `[1, 128]`.
+	fn batch_create(n: u32, ) -> Weight {
+		Weight::from_ref_time(96_714_000 as u64)
+			// Standard Error: 21_000
+			.saturating_add(Weight::from_ref_time(41_287_000 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(10 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes(8 as u64))
+			.saturating_add(RocksDbWeight::get().writes((3 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Streaming Streams (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:3 w:3)
+	// Storage: System Account (r:1 w:1)
+	fn top_up() -> Weight {
+		Weight::from_ref_time(162_357_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(8 as u64))
+			.saturating_add(RocksDbWeight::get().writes(7 as u64))
+	}
 }
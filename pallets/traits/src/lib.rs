@@ -8,7 +8,8 @@ use sp_runtime::{traits::Zero, RuntimeDebug};
 use sp_std::prelude::*;
 
 use primitives::{
-    CurrencyId, DerivativeIndex, PersistedValidationData, PriceDetail, Rate, Timestamp,
+    CurrencyId, DerivativeIndex, PersistedValidationData, PriceDetail, Rate, Ratio, StreamId,
+    Timestamp,
 };
 
 pub mod loans;
@@ -53,6 +54,8 @@ pub trait VaultTokenExchangeRateProvider<CurrencyId> {
 
 pub trait LPVaultTokenExchangeRateProvider<CurrencyId> {
     fn get_exchange_rate(lp_asset_id: &CurrencyId) -> Option<Rate>;
+    /// The staking asset which the vault of the vault token deposits
+    fn get_staking_asset(lp_asset_id: &CurrencyId) -> Option<CurrencyId>;
 }
 
 pub trait VaultTokenCurrenciesFilter<CurrencyId> {
@@ -86,10 +89,15 @@ pub struct Pool<CurrencyId, Balance, BlockNumber> {
     pub block_timestamp_last: BlockNumber,
     pub price_0_cumulative_last: Balance,
     pub price_1_cumulative_last: Balance,
+    /// Fee of the trades sent back to the pool
+    pub lp_fee: Ratio,
+    /// Share of the LP fees minted to the protocol fee receiver, `None` follows
+    /// the protocol fee of the AMM
+    pub protocol_fee: Option<Ratio>,
 }
 
 impl<CurrencyId, Balance: BalanceT, BlockNumber: BalanceT> Pool<CurrencyId, Balance, BlockNumber> {
-    pub fn new(lp_token_id: CurrencyId) -> Self {
+    pub fn new(lp_token_id: CurrencyId, lp_fee: Ratio, protocol_fee: Option<Ratio>) -> Self {
         Self {
             base_amount: Zero::zero(),
             quote_amount: Zero::zero(),
@@ -99,6 +107,8 @@ impl<CurrencyId, Balance: BalanceT, BlockNumber: BalanceT> Pool<CurrencyId, Bala
             block_timestamp_last: Zero::zero(),
             price_0_cumulative_last: Zero::zero(),
             price_1_cumulative_last: Zero::zero(),
+            lp_fee,
+            protocol_fee,
         }
     }
 
@@ -152,6 +162,31 @@ pub trait AMM<AccountId, CurrencyId, Balance, BlockNumber> {
     fn get_pool_by_asset_pair(
         pair: (CurrencyId, CurrencyId),
     ) -> Option<Pool<CurrencyId, Balance, BlockNumber>>;
+
+    /// Adds liquidity for "who" from `amount_in` of `pair.0` alone, swapping
+    /// the part that balances the pool for `pair.1` first. Returns the
+    /// liquidity minted, which must be at least `min_liquidity`.
+    fn zap_in(
+        who: &AccountId,
+        pair: (CurrencyId, CurrencyId),
+        amount_in: Balance,
+        min_liquidity: Balance,
+    ) -> Result<Balance, DispatchError>;
+}
+
+/// Exported functions of the router, to trade along the best routes of the
+/// AMM and stable swap pools.
+pub trait Router<AccountId, CurrencyId, Balance> {
+    /// Sells `amount_in` of `asset_in` for `asset_out` along the best split
+    /// routes for "who". Returns the amount out, which must be at least
+    /// `min_amount_out`.
+    fn swap_exact_tokens_for_tokens(
+        who: &AccountId,
+        asset_in: CurrencyId,
+        asset_out: CurrencyId,
+        amount_in: Balance,
+        min_amount_out: Balance,
+    ) -> Result<Balance, DispatchError>;
 }
 
 /// Exported traits from StableSwap pallet. These functions are to be used
@@ -191,6 +226,58 @@ pub trait StableSwap<AccountId, CurrencyId, Balance> {
     ) -> Result<(Balance, Balance), DispatchError>;
 }
 
+impl<AccountId, CurrencyId, Balance> StableSwap<AccountId, CurrencyId, Balance> for () {
+    fn get_amounts_out(
+        _amount_in: Balance,
+        _path: Vec<CurrencyId>,
+    ) -> Result<Vec<Balance>, DispatchError> {
+        Err(DispatchError::Other("No stable swap pools"))
+    }
+
+    fn get_amounts_in(
+        _amount_out: Balance,
+        _path: Vec<CurrencyId>,
+    ) -> Result<Vec<Balance>, DispatchError> {
+        Err(DispatchError::Other("No stable swap pools"))
+    }
+
+    fn swap(
+        _who: &AccountId,
+        _pair: (CurrencyId, CurrencyId),
+        _amount_in: Balance,
+    ) -> Result<(), DispatchError> {
+        Err(DispatchError::Other("No stable swap pools"))
+    }
+
+    fn get_pools() -> Result<Vec<(CurrencyId, CurrencyId)>, DispatchError> {
+        Ok(Vec::new())
+    }
+
+    fn get_reserves(
+        _asset_in: CurrencyId,
+        _asset_out: CurrencyId,
+    ) -> Result<(Balance, Balance), DispatchError> {
+        Err(DispatchError::Other("No stable swap pools"))
+    }
+}
+
+/// Notified by the AMM and stable swap pallets when they start or stop trading
+/// a pair of assets, the router keeps its pool graph with it.
+pub trait PoolsHook<CurrencyId> {
+    /// A pool trading the pair was created and no other pool of the pallet
+    /// trades it. Pool creation never fails because of the hook.
+    fn on_pool_created(pair: (CurrencyId, CurrencyId));
+
+    /// The last pool of the pallet trading the pair was removed
+    fn on_pool_removed(pair: (CurrencyId, CurrencyId));
+}
+
+impl<CurrencyId> PoolsHook<CurrencyId> for () {
+    fn on_pool_created(_pair: (CurrencyId, CurrencyId)) {}
+
+    fn on_pool_removed(_pair: (CurrencyId, CurrencyId)) {}
+}
+
 pub trait ConvertToBigUint {
     fn get_big_uint(&self) -> BigUint;
 }
@@ -250,3 +337,113 @@ impl<AccountId, CurrencyId, Balance> Streaming<AccountId, CurrencyId, Balance> f
         Ok(())
     }
 }
+
+/// StreamInfo is a stream seen by its sender or recipient
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct StreamInfo<AccountId, CurrencyId, Balance> {
+    pub stream_id: StreamId,
+    pub sender: AccountId,
+    pub recipient: AccountId,
+    pub asset_id: CurrencyId,
+    pub deposit: Balance,
+    /// The balance not withdrawn yet
+    pub remaining_balance: Balance,
+    /// The balance the recipient can withdraw now
+    pub withdrawable: Balance,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    pub cancellable: bool,
+    pub as_collateral: bool,
+    /// Whether the stream was cancelled or completed
+    pub finished: bool,
+}
+
+/// Streams pledged as collateral, the balance of a pledged stream which isn't vested
+/// yet backs the debt of its recipient.
+pub trait StreamCollateral<AccountId, CurrencyId, Balance> {
+    /// Pledges the stream of `recipient`, returns the asset of the stream
+    fn pledge(recipient: &AccountId, stream_id: StreamId) -> Result<CurrencyId, DispatchError>;
+
+    /// Releases the pledged stream of `recipient`
+    fn release(recipient: &AccountId, stream_id: StreamId) -> Result<(), DispatchError>;
+
+    /// The asset of a stream and its balance not vested yet
+    fn unvested_balance(stream_id: StreamId) -> Result<(CurrencyId, Balance), DispatchError>;
+
+    /// Redirects the withdrawals of a pledged stream to `new_recipient`
+    fn redirect(stream_id: StreamId, new_recipient: &AccountId) -> Result<(), DispatchError>;
+
+    /// Withdraws the vested balance of a stream to its recipient, returns the asset
+    /// and the amount withdrawn
+    fn withdraw_vested(stream_id: StreamId) -> Result<(CurrencyId, Balance), DispatchError>;
+
+    /// Whether the stream was cancelled or completed
+    fn has_finished(stream_id: StreamId) -> bool;
+}
+
+impl<AccountId, CurrencyId, Balance> StreamCollateral<AccountId, CurrencyId, Balance> for () {
+    fn pledge(_recipient: &AccountId, _stream_id: StreamId) -> Result<CurrencyId, DispatchError> {
+        Err(DispatchError::Other("No streams"))
+    }
+
+    fn release(_recipient: &AccountId, _stream_id: StreamId) -> Result<(), DispatchError> {
+        Err(DispatchError::Other("No streams"))
+    }
+
+    fn unvested_balance(_stream_id: StreamId) -> Result<(CurrencyId, Balance), DispatchError> {
+        Err(DispatchError::Other("No streams"))
+    }
+
+    fn redirect(_stream_id: StreamId, _new_recipient: &AccountId) -> Result<(), DispatchError> {
+        Err(DispatchError::Other("No streams"))
+    }
+
+    fn withdraw_vested(_stream_id: StreamId) -> Result<(CurrencyId, Balance), DispatchError> {
+        Err(DispatchError::Other("No streams"))
+    }
+
+    fn has_finished(_stream_id: StreamId) -> bool {
+        true
+    }
+}
+
+/// Exported functions of the vote-escrow pallet, the voting power of an account
+/// decays linearly until its lock expires.
+pub trait VotingEscrow<AccountId, Balance> {
+    /// The current voting power of "who"
+    fn voting_power(who: &AccountId) -> Balance;
+
+    /// The current voting power of all the accounts
+    fn total_voting_power() -> Balance;
+}
+
+impl<AccountId, Balance: Zero> VotingEscrow<AccountId, Balance> for () {
+    fn voting_power(_who: &AccountId) -> Balance {
+        Zero::zero()
+    }
+
+    fn total_voting_power() -> Balance {
+        Zero::zero()
+    }
+}
+
+/// Exported functions of the gauge controller, voting power holders decide how
+/// rewards are split across gauges each epoch.
+pub trait GaugeController<GaugeId> {
+    /// The max count of gauges
+    fn max_gauges() -> u32;
+
+    /// The gauges and their share of the votes for the current epoch
+    fn gauge_relative_weights() -> Vec<(GaugeId, Ratio)>;
+}
+
+impl<GaugeId> GaugeController<GaugeId> for () {
+    fn max_gauges() -> u32 {
+        0
+    }
+
+    fn gauge_relative_weights() -> Vec<(GaugeId, Ratio)> {
+        Vec::new()
+    }
+}
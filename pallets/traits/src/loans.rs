@@ -14,7 +14,7 @@
 
 use codec::{Decode, Encode};
 use frame_support::dispatch::DispatchError;
-use primitives::{Rate, Ratio};
+use primitives::{Rate, Ratio, Timestamp};
 use scale_info::TypeInfo;
 use sp_runtime::{FixedU128, RuntimeDebug};
 use sp_std::prelude::*;
@@ -47,6 +47,28 @@ pub trait Loans<CurrencyId, AccountId, Balance> {
     ) -> Result<(), DispatchError>;
 }
 
+/// FlashLoanReceiver is notified once the flash loaned assets have been transferred
+/// to the initiator, before the calls of the flash loan are dispatched.
+pub trait FlashLoanReceiver<AccountId, CurrencyId, Balance> {
+    fn on_flash_loan(
+        initiator: &AccountId,
+        asset_id: CurrencyId,
+        amount: Balance,
+        fee: Balance,
+    ) -> Result<(), DispatchError>;
+}
+
+impl<AccountId, CurrencyId, Balance> FlashLoanReceiver<AccountId, CurrencyId, Balance> for () {
+    fn on_flash_loan(
+        _initiator: &AccountId,
+        _asset_id: CurrencyId,
+        _amount: Balance,
+        _fee: Balance,
+    ) -> Result<(), DispatchError> {
+        Ok(())
+    }
+}
+
 pub trait LoansPositionDataProvider<CurrencyId, AccountId, Balance> {
     fn get_current_borrow_balance(
         borrower: &AccountId,
@@ -77,13 +99,124 @@ pub struct MarketInfo {
 }
 
 /// MarketStatus contains some dynamic calculated attrs of Market
-#[derive(Default, Copy, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Default, Copy, Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct MarketStatus<Balance> {
+    /// The borrow rate of variable rate borrows
     pub borrow_rate: Rate,
     pub supply_rate: Rate,
     pub exchange_rate: Rate,
     pub utilization: Ratio,
+    /// Outstanding variable rate borrows
     pub total_borrows: Balance,
     pub total_reserves: Balance,
     pub borrow_index: FixedU128,
+    /// Outstanding stable rate borrows
+    pub total_stable_borrows: Balance,
+    /// The rate a new stable rate borrow would lock, `None` if the market
+    /// doesn't offer stable rate borrows
+    pub stable_borrow_rate: Option<Rate>,
+    /// The locked rate of the outstanding stable rate borrows weighted by amount
+    pub average_stable_borrow_rate: Rate,
+}
+
+/// Identifier of a liquidation auction
+pub type AuctionId = u64;
+
+/// The price decay curve of a liquidation auction
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum AuctionDecay {
+    /// The price goes down linearly and reaches the minimum price after `duration` seconds
+    Linear { duration: Timestamp },
+    /// The price is cut by `cut` every `step` seconds
+    Stepwise { step: Timestamp, cut: Ratio },
+}
+
+/// LiquidationAuctionConfig turns the liquidation of a collateral market into a descending
+/// price auction
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct LiquidationAuctionConfig {
+    /// The collateral price relative to the oracle price when the auction starts
+    pub start_premium: Rate,
+    /// The collateral price relative to the oracle price the auction never goes below
+    pub min_price: Rate,
+    /// How the collateral price goes down over time
+    pub decay: AuctionDecay,
+    /// Seconds after which the unfilled remainder can be settled against the reserves
+    pub duration: Timestamp,
+}
+
+/// LiquidationAuction contains the collateral seized from an underwater position
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct LiquidationAuction<AccountId, CurrencyId, Balance> {
+    pub borrower: AccountId,
+    pub liquidation_asset_id: CurrencyId,
+    pub collateral_asset_id: CurrencyId,
+    /// The debt left to be repaid by bidders
+    pub debt: Balance,
+    /// The collateral voucher amount left for sale
+    pub collateral: Balance,
+    pub start_time: Timestamp,
+    pub config: LiquidationAuctionConfig,
+}
+
+/// Identifier of an efficiency mode category
+pub type EModeCategoryId = u8;
+
+/// AccountMode tells which collateral can back the borrows of an account
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum AccountMode<CurrencyId> {
+    /// All collateral backs all borrows
+    Cross,
+    /// The isolated collateral is the only collateral and backs whitelisted borrows only
+    Isolated(CurrencyId),
+    /// Collateral and borrows in the category use the category's risk params
+    EMode(EModeCategoryId),
+}
+
+/// AssetPosition is the supply and borrow of an account in one market
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct AssetPosition<CurrencyId, Balance> {
+    pub asset_id: CurrencyId,
+    /// The underlying amount supplied, including the interest earned
+    pub supplied: Balance,
+    /// The interest earned by the supply so far
+    pub supply_interest: Balance,
+    pub is_collateral: bool,
+    /// The amount owed, including the interest accrued
+    pub borrowed: Balance,
+    /// The interest accrued since the last borrow or repay
+    pub borrow_interest: Balance,
+}
+
+/// AccountPosition is the full position of an account across all markets
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct AccountPosition<CurrencyId, Balance> {
+    pub assets: Vec<AssetPosition<CurrencyId, Balance>>,
+    /// The reward accrued by the account and not claimed yet
+    pub unclaimed_reward: Balance,
+    /// Liquidation threshold value of the collateral over the borrowed value,
+    /// the account can be liquidated below one. `None` without borrows.
+    pub health_factor: Option<FixedU128>,
+}
+
+/// LiquidationSimulation is the outcome of a liquidation, amounts are in the
+/// underlying of the collateral
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct LiquidationSimulation<Balance> {
+    /// The collateral seized from the borrower
+    pub seized_collateral: Balance,
+    /// The part of the seized collateral received by the liquidator
+    pub liquidator_collateral: Balance,
+    /// The part of the seized collateral kept as incentive reserves
+    pub reserved_collateral: Balance,
+    /// The seized collateral goes into a liquidation auction instead
+    pub auction: bool,
 }
@@ -0,0 +1,129 @@
+//! Vote escrow pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+#[allow(unused_imports)]
+use crate::Pallet as VoteEscrow;
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::{assert_ok, traits::EnsureOrigin};
+use frame_system::{self, RawOrigin as SystemOrigin};
+
+const INITIAL_AMOUNT: u128 = 1_000_000_000_000_000;
+const LOCK_AMOUNT: u128 = 1_000_000_000_000;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+    frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+fn initial_set_up<T: Config>(caller: &T::AccountId) {
+    assert_ok!(T::Assets::mint_into(
+        T::LockCurrencyId::get(),
+        caller,
+        INITIAL_AMOUNT
+    ));
+}
+
+fn max_unlock_block<T: Config>() -> T::BlockNumber {
+    frame_system::Pallet::<T>::block_number().saturating_add(T::MaxLockDuration::get())
+}
+
+fn create_lock<T: Config>(caller: &T::AccountId, unlock_block: T::BlockNumber) {
+    assert_ok!(VoteEscrow::<T>::create_lock(
+        SystemOrigin::Signed(caller.clone()).into(),
+        LOCK_AMOUNT,
+        unlock_block
+    ));
+}
+
+fn add_gauge<T: Config>(gauge: T::GaugeId) {
+    assert_ok!(VoteEscrow::<T>::add_gauge(
+        T::UpdateOrigin::try_successful_origin()
+            .expect("No origin exists which can satisfy the guard"),
+        gauge
+    ));
+}
+
+benchmarks! {
+    where_clause {
+        where
+            T::GaugeId: Default
+    }
+
+    create_lock {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T>(&caller);
+        let unlock_block = max_unlock_block::<T>();
+    }: _(SystemOrigin::Signed(caller.clone()), LOCK_AMOUNT, unlock_block)
+    verify {
+        assert_last_event::<T>(Event::Locked(caller, LOCK_AMOUNT, VoteEscrow::<T>::epoch_start(unlock_block)).into());
+    }
+
+    increase_amount {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T>(&caller);
+        create_lock::<T>(&caller, max_unlock_block::<T>());
+    }: _(SystemOrigin::Signed(caller.clone()), LOCK_AMOUNT)
+    verify {
+        assert_last_event::<T>(Event::LockAmountIncreased(caller, LOCK_AMOUNT).into());
+    }
+
+    increase_unlock_time {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T>(&caller);
+        let now = frame_system::Pallet::<T>::block_number();
+        create_lock::<T>(&caller, now.saturating_add(T::MaxLockDuration::get() / 2u32.into()));
+        let unlock_block = max_unlock_block::<T>();
+    }: _(SystemOrigin::Signed(caller.clone()), unlock_block)
+    verify {
+        assert_last_event::<T>(Event::LockExtended(caller, VoteEscrow::<T>::epoch_start(unlock_block)).into());
+    }
+
+    withdraw {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T>(&caller);
+        create_lock::<T>(&caller, max_unlock_block::<T>());
+        frame_system::Pallet::<T>::set_block_number(VoteEscrow::<T>::locked(&caller).end);
+    }: _(SystemOrigin::Signed(caller.clone()))
+    verify {
+        assert_last_event::<T>(Event::Withdrawn(caller, LOCK_AMOUNT).into());
+    }
+
+    vote_for_gauge {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T>(&caller);
+        let gauge = T::GaugeId::default();
+        add_gauge::<T>(gauge);
+        create_lock::<T>(&caller, max_unlock_block::<T>());
+        assert_ok!(VoteEscrow::<T>::vote_for_gauge(
+            SystemOrigin::Signed(caller.clone()).into(),
+            gauge,
+            Ratio::from_percent(50)
+        ));
+    }: _(SystemOrigin::Signed(caller.clone()), gauge, Ratio::one())
+    verify {
+        assert_last_event::<T>(Event::Voted(caller, gauge, Ratio::one()).into());
+    }
+
+    add_gauge {
+        let gauge = T::GaugeId::default();
+    }: _(SystemOrigin::Root, gauge)
+    verify {
+        assert_last_event::<T>(Event::GaugeAdded(gauge).into());
+    }
+
+    remove_gauge {
+        let gauge = T::GaugeId::default();
+        add_gauge::<T>(gauge);
+    }: _(SystemOrigin::Root, gauge)
+    verify {
+        assert_last_event::<T>(Event::GaugeRemoved(gauge).into());
+    }
+}
+
+impl_benchmark_test_suite!(
+    VoteEscrow,
+    crate::mock::new_test_ext(),
+    crate::mock::Runtime,
+);
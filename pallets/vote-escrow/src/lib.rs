@@ -0,0 +1,728 @@
+// Copyright 2021-2022 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Vote escrow pallet
+//!
+//! ## Overview
+//!
+//! The native token is locked for up to `MaxLockDuration` blocks in exchange for
+//! voting power, which decays linearly to zero at the end of the lock. Locks end at
+//! the start of an epoch, so the total voting power only changes its slope at epoch
+//! starts, when the total voting power and the votes of the gauges are checkpointed.
+//! Voting power boosts the farming and loans reward shares of its holders and is voted
+//! on gauges. The votes of the gauges at the start of an epoch decide how rewards are
+//! split across them during the epoch, votes apply from the next epoch.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use crate::types::{GaugeVote, LockedBalance, Point};
+use frame_support::{
+    pallet_prelude::*,
+    traits::tokens::fungibles::{Inspect, Mutate, Transfer},
+    transactional, PalletId,
+};
+use frame_system::pallet_prelude::*;
+use pallet_traits::{GaugeController, VotingEscrow};
+use primitives::{Balance, CurrencyId, Ratio};
+use sp_runtime::{
+    traits::{AccountIdConversion, CheckedDiv, Saturating, Zero},
+    ArithmeticError, SaturatedConversion,
+};
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+mod benchmarking;
+
+pub mod types;
+
+pub use pallet::*;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+type AccountOf<T> = <T as frame_system::Config>::AccountId;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Assets for locking the native token
+        type Assets: Transfer<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
+            + Inspect<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
+            + Mutate<Self::AccountId, AssetId = CurrencyId, Balance = Balance>;
+
+        /// The vote escrow module id, keeps all the locked tokens.
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
+
+        /// Currency id of the locked token, the native token
+        #[pallet::constant]
+        type LockCurrencyId: Get<CurrencyId>;
+
+        /// The number of blocks of an epoch, must not be zero
+        #[pallet::constant]
+        type EpochDuration: Get<Self::BlockNumber>;
+
+        /// The max number of blocks tokens are locked for, tokens locked for this
+        /// duration get their amount of voting power
+        #[pallet::constant]
+        type MaxLockDuration: Get<Self::BlockNumber>;
+
+        /// The identifier of a gauge
+        type GaugeId: Parameter + Member + Copy + Ord + MaxEncodedLen;
+
+        /// The max count of gauges
+        #[pallet::constant]
+        type MaxGauges: Get<u32>;
+
+        /// The origin which can add and remove gauges
+        type UpdateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Amount must not be zero
+        ZeroAmount,
+        /// The account already has a lock
+        LockAlreadyExists,
+        /// The account has no lock
+        LockDoesNotExist,
+        /// The lock has expired
+        LockExpired,
+        /// The lock has not expired yet
+        LockNotExpired,
+        /// The unlock block must be after the current epoch and within MaxLockDuration
+        InvalidUnlockBlock,
+        /// The unlock block must be after the end of the lock
+        UnlockBlockNotIncreased,
+        /// The lock expires before the next epoch, when votes apply
+        LockExpiresTooSoon,
+        /// The gauge is already added
+        GaugeAlreadyExists,
+        /// The gauge is not found
+        GaugeDoesNotExist,
+        /// Excess max gauges count
+        ExcessMaxGaugesCount,
+        /// The votes of the account exceed its voting power
+        ExcessVotingPower,
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(crate) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Locks tokens
+        /// \[who, amount, end\]
+        Locked(AccountOf<T>, Balance, T::BlockNumber),
+        /// Increases the amount of a lock
+        /// \[who, amount\]
+        LockAmountIncreased(AccountOf<T>, Balance),
+        /// Extends the end of a lock
+        /// \[who, end\]
+        LockExtended(AccountOf<T>, T::BlockNumber),
+        /// Withdraws the tokens of an expired lock
+        /// \[who, amount\]
+        Withdrawn(AccountOf<T>, Balance),
+        /// Adds a gauge
+        /// \[gauge\]
+        GaugeAdded(T::GaugeId),
+        /// Removes a gauge
+        /// \[gauge\]
+        GaugeRemoved(T::GaugeId),
+        /// Votes for a gauge
+        /// \[who, gauge, weight\]
+        Voted(AccountOf<T>, T::GaugeId, Ratio),
+    }
+
+    /// Locks of the accounts
+    #[pallet::storage]
+    #[pallet::getter(fn locked)]
+    pub type Locked<T: Config> =
+        StorageMap<_, Blake2_128Concat, AccountOf<T>, LockedBalance<T::BlockNumber>, ValueQuery>;
+
+    /// The total voting power, as of its last checkpoint
+    #[pallet::storage]
+    #[pallet::getter(fn supply)]
+    pub type Supply<T: Config> = StorageValue<_, Point<T::BlockNumber>, ValueQuery>;
+
+    /// The decrease of the total voting power slope at the start of an epoch, from
+    /// the locks ending then
+    #[pallet::storage]
+    #[pallet::getter(fn slope_changes)]
+    pub type SlopeChanges<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, Balance, ValueQuery>;
+
+    /// The gauges voting power is voted on
+    #[pallet::storage]
+    #[pallet::getter(fn gauges)]
+    pub type Gauges<T: Config> = StorageValue<_, BoundedVec<T::GaugeId, T::MaxGauges>, ValueQuery>;
+
+    /// The votes of a gauge, as of its last checkpoint
+    #[pallet::storage]
+    #[pallet::getter(fn gauge_points)]
+    pub type GaugePoints<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::GaugeId, Point<T::BlockNumber>, OptionQuery>;
+
+    /// The decrease of the votes slope of a gauge at the start of an epoch, from the
+    /// votes ending then
+    #[pallet::storage]
+    #[pallet::getter(fn gauge_slope_changes)]
+    pub type GaugeSlopeChanges<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::GaugeId,
+        Twox64Concat,
+        T::BlockNumber,
+        Balance,
+        ValueQuery,
+    >;
+
+    /// The votes of a gauge at the start of an epoch
+    #[pallet::storage]
+    #[pallet::getter(fn gauge_weights)]
+    pub type GaugeWeights<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::GaugeId,
+        Twox64Concat,
+        T::BlockNumber,
+        Balance,
+        ValueQuery,
+    >;
+
+    /// The votes of an account for a gauge
+    #[pallet::storage]
+    #[pallet::getter(fn gauge_votes)]
+    pub type GaugeVotes<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AccountOf<T>,
+        Blake2_128Concat,
+        T::GaugeId,
+        GaugeVote<T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// The share of the voting power of an account voted on gauges
+    #[pallet::storage]
+    #[pallet::getter(fn voting_power_used)]
+    pub type VotingPowerUsed<T: Config> =
+        StorageMap<_, Blake2_128Concat, AccountOf<T>, Ratio, ValueQuery>;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(PhantomData<T>);
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_initialize(block_number: T::BlockNumber) -> Weight {
+            if Self::epoch_start(block_number) != block_number {
+                return Weight::zero();
+            }
+
+            // the total voting power and the votes of the gauges are checkpointed at
+            // every epoch start, so reading them only walks the current epoch
+            let supply = Self::advance_point(
+                Self::supply(),
+                block_number,
+                SlopeChanges::<T>::get,
+                |_, _| {},
+            );
+            Supply::<T>::put(supply);
+            let gauges = Self::gauges();
+            for gauge in gauges.iter() {
+                GaugePoints::<T>::insert(gauge, Self::checkpoint_gauge(*gauge, block_number));
+            }
+
+            let gauges_count = gauges.len() as u64;
+            T::DbWeight::get().reads_writes(
+                gauges_count.saturating_mul(2).saturating_add(3),
+                gauges_count.saturating_mul(2).saturating_add(1),
+            )
+        }
+
+        fn integrity_test() {
+            assert!(!T::EpochDuration::get().is_zero());
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Lock native tokens in exchange for voting power
+        ///
+        /// - `amount`: the amount of tokens to lock.
+        /// - `unlock_block`: the block the tokens can be withdrawn from, rounded down to
+        ///   the start of its epoch.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::create_lock())]
+        #[transactional]
+        pub fn create_lock(
+            origin: OriginFor<T>,
+            amount: Balance,
+            unlock_block: T::BlockNumber,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            let old_locked = Self::locked(&who);
+            ensure!(old_locked.amount.is_zero(), Error::<T>::LockAlreadyExists);
+            let end = Self::epoch_start(unlock_block);
+            Self::ensure_valid_end(end)?;
+
+            T::Assets::transfer(
+                T::LockCurrencyId::get(),
+                &who,
+                &Self::account_id(),
+                amount,
+                false,
+            )?;
+            Self::checkpoint(&who, old_locked, LockedBalance { amount, end });
+
+            Self::deposit_event(Event::<T>::Locked(who, amount, end));
+            Ok(())
+        }
+
+        /// Lock more native tokens until the end of the current lock
+        ///
+        /// - `amount`: the amount of tokens to add to the lock.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::increase_amount())]
+        #[transactional]
+        pub fn increase_amount(origin: OriginFor<T>, amount: Balance) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            let old_locked = Self::ensure_active_lock(&who)?;
+
+            T::Assets::transfer(
+                T::LockCurrencyId::get(),
+                &who,
+                &Self::account_id(),
+                amount,
+                false,
+            )?;
+            let locked = LockedBalance {
+                amount: old_locked
+                    .amount
+                    .checked_add(amount)
+                    .ok_or(ArithmeticError::Overflow)?,
+                end: old_locked.end,
+            };
+            Self::checkpoint(&who, old_locked, locked);
+
+            Self::deposit_event(Event::<T>::LockAmountIncreased(who, amount));
+            Ok(())
+        }
+
+        /// Extend the current lock, votes for gauges keep the previous end until they
+        /// are voted again
+        ///
+        /// - `unlock_block`: the new block the tokens can be withdrawn from, rounded
+        ///   down to the start of its epoch.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::increase_unlock_time())]
+        #[transactional]
+        pub fn increase_unlock_time(
+            origin: OriginFor<T>,
+            unlock_block: T::BlockNumber,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let old_locked = Self::ensure_active_lock(&who)?;
+            let end = Self::epoch_start(unlock_block);
+            ensure!(end > old_locked.end, Error::<T>::UnlockBlockNotIncreased);
+            Self::ensure_valid_end(end)?;
+
+            Self::checkpoint(
+                &who,
+                old_locked,
+                LockedBalance {
+                    amount: old_locked.amount,
+                    end,
+                },
+            );
+
+            Self::deposit_event(Event::<T>::LockExtended(who, end));
+            Ok(())
+        }
+
+        /// Withdraw the tokens of an expired lock
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::withdraw())]
+        #[transactional]
+        pub fn withdraw(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let locked = Self::locked(&who);
+            ensure!(!locked.amount.is_zero(), Error::<T>::LockDoesNotExist);
+            ensure!(
+                locked.end <= <frame_system::Pallet<T>>::block_number(),
+                Error::<T>::LockNotExpired
+            );
+
+            // the lock has no voting power left, the total voting power is unchanged
+            Locked::<T>::remove(&who);
+            T::Assets::transfer(
+                T::LockCurrencyId::get(),
+                &Self::account_id(),
+                &who,
+                locked.amount,
+                false,
+            )?;
+
+            Self::deposit_event(Event::<T>::Withdrawn(who, locked.amount));
+            Ok(())
+        }
+
+        /// Vote a share of the voting power for a gauge from the next epoch, the vote
+        /// decays with the lock. Voting a zero weight removes the vote.
+        ///
+        /// - `gauge`: the gauge to vote for.
+        /// - `weight`: the share of the voting power to give to the gauge.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::vote_for_gauge())]
+        #[transactional]
+        pub fn vote_for_gauge(
+            origin: OriginFor<T>,
+            gauge: T::GaugeId,
+            weight: Ratio,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let locked = Self::locked(&who);
+            let next_epoch = Self::next_epoch_start(<frame_system::Pallet<T>>::block_number());
+            if !weight.is_zero() {
+                ensure!(
+                    Self::gauges().contains(&gauge),
+                    Error::<T>::GaugeDoesNotExist
+                );
+                ensure!(locked.end > next_epoch, Error::<T>::LockExpiresTooSoon);
+            }
+
+            let old_vote = Self::gauge_votes(&who, gauge);
+            let used = Self::voting_power_used(&who)
+                .deconstruct()
+                .saturating_sub(old_vote.map(|v| v.weight.deconstruct()).unwrap_or_default())
+                .saturating_add(weight.deconstruct());
+            ensure!(
+                used <= Ratio::one().deconstruct(),
+                Error::<T>::ExcessVotingPower
+            );
+
+            let mut point = Self::checkpoint_gauge(gauge, next_epoch);
+            if let Some(old_vote) = old_vote.filter(|v| v.end > next_epoch) {
+                point.bias = point.bias.saturating_sub(
+                    old_vote
+                        .slope
+                        .saturating_mul(Self::blocks(next_epoch, old_vote.end)),
+                );
+                point.slope = point.slope.saturating_sub(old_vote.slope);
+                GaugeSlopeChanges::<T>::mutate(gauge, old_vote.end, |change| {
+                    *change = change.saturating_sub(old_vote.slope)
+                });
+            }
+
+            if weight.is_zero() {
+                GaugeVotes::<T>::remove(&who, gauge);
+            } else {
+                let slope = weight.mul_floor(Self::lock_slope(locked.amount));
+                point.bias = point
+                    .bias
+                    .saturating_add(slope.saturating_mul(Self::blocks(next_epoch, locked.end)));
+                point.slope = point.slope.saturating_add(slope);
+                GaugeSlopeChanges::<T>::mutate(gauge, locked.end, |change| {
+                    *change = change.saturating_add(slope)
+                });
+                GaugeVotes::<T>::insert(
+                    &who,
+                    gauge,
+                    GaugeVote {
+                        weight,
+                        slope,
+                        end: locked.end,
+                    },
+                );
+            }
+            GaugePoints::<T>::insert(gauge, point);
+            GaugeWeights::<T>::insert(gauge, next_epoch, point.bias);
+            VotingPowerUsed::<T>::insert(&who, Ratio::from_parts(used));
+
+            Self::deposit_event(Event::<T>::Voted(who, gauge, weight));
+            Ok(())
+        }
+
+        /// Add a gauge to vote for, it gets votes from the next epoch
+        ///
+        /// The origin must conform to `UpdateOrigin`.
+        ///
+        /// - `gauge`: the gauge to add.
+        #[pallet::call_index(5)]
+        #[pallet::weight(
+            T::WeightInfo::add_gauge().saturating_add(Pallet::<T>::lock_walk_weight())
+        )]
+        #[transactional]
+        pub fn add_gauge(origin: OriginFor<T>, gauge: T::GaugeId) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            Gauges::<T>::try_mutate(|gauges| -> DispatchResult {
+                ensure!(!gauges.contains(&gauge), Error::<T>::GaugeAlreadyExists);
+                gauges
+                    .try_push(gauge)
+                    .map_err(|_| Error::<T>::ExcessMaxGaugesCount)?;
+                Ok(())
+            })?;
+
+            // votes of a gauge added back are kept, they are caught up with the epochs
+            // they were not checkpointed in
+            let now = <frame_system::Pallet<T>>::block_number();
+            if GaugePoints::<T>::contains_key(gauge) {
+                GaugePoints::<T>::insert(gauge, Self::checkpoint_gauge(gauge, now));
+            } else {
+                GaugePoints::<T>::insert(
+                    gauge,
+                    Point {
+                        block: Self::next_epoch_start(now),
+                        ..Default::default()
+                    },
+                );
+            }
+
+            Self::deposit_event(Event::<T>::GaugeAdded(gauge));
+            Ok(())
+        }
+
+        /// Remove a gauge, its votes are kept until their voters vote them away
+        ///
+        /// The origin must conform to `UpdateOrigin`.
+        ///
+        /// - `gauge`: the gauge to remove.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::remove_gauge())]
+        #[transactional]
+        pub fn remove_gauge(origin: OriginFor<T>, gauge: T::GaugeId) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            Gauges::<T>::try_mutate(|gauges| -> DispatchResult {
+                let index = gauges
+                    .iter()
+                    .position(|g| *g == gauge)
+                    .ok_or(Error::<T>::GaugeDoesNotExist)?;
+                gauges.remove(index);
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::<T>::GaugeRemoved(gauge));
+            Ok(())
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    pub fn account_id() -> T::AccountId {
+        T::PalletId::get().into_account_truncating()
+    }
+
+    /// The start of the epoch of `block`
+    pub fn epoch_start(block: T::BlockNumber) -> T::BlockNumber {
+        block.saturating_sub(block % T::EpochDuration::get())
+    }
+
+    /// The start of the epoch after the epoch of `block`
+    pub fn next_epoch_start(block: T::BlockNumber) -> T::BlockNumber {
+        Self::epoch_start(block).saturating_add(T::EpochDuration::get())
+    }
+
+    /// Weight of moving a point forward over every epoch of the longest lock, after
+    /// which no vote is left to expire
+    fn lock_walk_weight() -> Weight {
+        let epochs = T::MaxLockDuration::get()
+            .checked_div(&T::EpochDuration::get())
+            .unwrap_or_default()
+            .saturated_into::<u64>()
+            .saturating_add(1);
+        T::DbWeight::get().reads_writes(epochs, epochs)
+    }
+
+    /// The current voting power of "who"
+    pub fn voting_power_of(who: &T::AccountId) -> Balance {
+        let locked = Self::locked(who);
+        let now = <frame_system::Pallet<T>>::block_number();
+        Self::lock_slope(locked.amount).saturating_mul(Self::blocks(now, locked.end))
+    }
+
+    /// The votes of a gauge at the start of `epoch`
+    pub fn gauge_weight(gauge: T::GaugeId, epoch: T::BlockNumber) -> Balance {
+        match Self::gauge_points(gauge) {
+            Some(point) if point.block < epoch => {
+                Self::advance_point(
+                    point,
+                    epoch,
+                    |change_block| GaugeSlopeChanges::<T>::get(gauge, change_block),
+                    |_, _| {},
+                )
+                .bias
+            }
+            Some(_) => Self::gauge_weights(gauge, epoch),
+            None => Zero::zero(),
+        }
+    }
+
+    fn ensure_active_lock(
+        who: &T::AccountId,
+    ) -> Result<LockedBalance<T::BlockNumber>, DispatchError> {
+        let locked = Self::locked(who);
+        ensure!(!locked.amount.is_zero(), Error::<T>::LockDoesNotExist);
+        ensure!(
+            locked.end > <frame_system::Pallet<T>>::block_number(),
+            Error::<T>::LockExpired
+        );
+        Ok(locked)
+    }
+
+    fn ensure_valid_end(end: T::BlockNumber) -> DispatchResult {
+        let now = <frame_system::Pallet<T>>::block_number();
+        ensure!(
+            end > now && end <= now.saturating_add(T::MaxLockDuration::get()),
+            Error::<T>::InvalidUnlockBlock
+        );
+        Ok(())
+    }
+
+    /// The decay of the voting power of a lock every block
+    fn lock_slope(amount: Balance) -> Balance {
+        amount
+            .checked_div(T::MaxLockDuration::get().saturated_into())
+            .unwrap_or_default()
+    }
+
+    fn blocks(from: T::BlockNumber, to: T::BlockNumber) -> Balance {
+        to.saturating_sub(from).saturated_into()
+    }
+
+    /// Moves a point forward to `to`, applying the slope changes of the epochs on
+    /// the way and reporting the votes left at their start
+    fn advance_point(
+        mut point: Point<T::BlockNumber>,
+        to: T::BlockNumber,
+        slope_changes: impl Fn(T::BlockNumber) -> Balance,
+        mut on_epoch: impl FnMut(T::BlockNumber, Balance),
+    ) -> Point<T::BlockNumber> {
+        while point.block < to {
+            // without slope, no vote is left to expire
+            if point.slope.is_zero() {
+                point.block = to;
+                break;
+            }
+
+            let next = Self::next_epoch_start(point.block).min(to);
+            point.bias = point.bias_at(next);
+            point.block = next;
+            if Self::epoch_start(next) == next {
+                point.slope = point.slope.saturating_sub(slope_changes(next));
+                on_epoch(next, point.bias);
+            }
+        }
+        point
+    }
+
+    /// Replaces the lock of "who" and updates the total voting power
+    fn checkpoint(
+        who: &T::AccountId,
+        old_locked: LockedBalance<T::BlockNumber>,
+        new_locked: LockedBalance<T::BlockNumber>,
+    ) {
+        let now = <frame_system::Pallet<T>>::block_number();
+        let mut point = Self::advance_point(Self::supply(), now, SlopeChanges::<T>::get, |_, _| {});
+
+        if old_locked.end > now {
+            let slope = Self::lock_slope(old_locked.amount);
+            point.bias = point
+                .bias
+                .saturating_sub(slope.saturating_mul(Self::blocks(now, old_locked.end)));
+            point.slope = point.slope.saturating_sub(slope);
+            SlopeChanges::<T>::mutate(old_locked.end, |change| {
+                *change = change.saturating_sub(slope)
+            });
+        }
+        if new_locked.end > now {
+            let slope = Self::lock_slope(new_locked.amount);
+            point.bias = point
+                .bias
+                .saturating_add(slope.saturating_mul(Self::blocks(now, new_locked.end)));
+            point.slope = point.slope.saturating_add(slope);
+            SlopeChanges::<T>::mutate(new_locked.end, |change| {
+                *change = change.saturating_add(slope)
+            });
+        }
+
+        Supply::<T>::put(point);
+        Locked::<T>::insert(who, new_locked);
+    }
+
+    /// Moves the votes of a gauge forward to `to`, recording the votes at the start
+    /// of the epochs on the way
+    fn checkpoint_gauge(gauge: T::GaugeId, to: T::BlockNumber) -> Point<T::BlockNumber> {
+        let point = Self::gauge_points(gauge).unwrap_or(Point {
+            block: to,
+            ..Default::default()
+        });
+        Self::advance_point(
+            point,
+            to,
+            |change_block| GaugeSlopeChanges::<T>::get(gauge, change_block),
+            |epoch, bias| GaugeWeights::<T>::insert(gauge, epoch, bias),
+        )
+    }
+}
+
+impl<T: Config> VotingEscrow<AccountOf<T>, Balance> for Pallet<T> {
+    fn voting_power(who: &AccountOf<T>) -> Balance {
+        Self::voting_power_of(who)
+    }
+
+    fn total_voting_power() -> Balance {
+        let now = <frame_system::Pallet<T>>::block_number();
+        Self::advance_point(Self::supply(), now, SlopeChanges::<T>::get, |_, _| {}).bias
+    }
+}
+
+impl<T: Config> GaugeController<T::GaugeId> for Pallet<T> {
+    fn max_gauges() -> u32 {
+        T::MaxGauges::get()
+    }
+
+    fn gauge_relative_weights() -> Vec<(T::GaugeId, Ratio)> {
+        let epoch = Self::epoch_start(<frame_system::Pallet<T>>::block_number());
+        let weights: Vec<(T::GaugeId, Balance)> = Self::gauges()
+            .into_iter()
+            .map(|gauge| (gauge, Self::gauge_weight(gauge, epoch)))
+            .collect();
+        let total_weight = weights.iter().fold(Balance::zero(), |total, (_, weight)| {
+            total.saturating_add(*weight)
+        });
+
+        weights
+            .into_iter()
+            .map(|(gauge, weight)| {
+                if total_weight.is_zero() {
+                    (gauge, Ratio::zero())
+                } else {
+                    (gauge, Ratio::from_rational(weight, total_weight))
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,387 @@
+// Copyright 2021-2022 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Unit tests for the vote escrow pallet.
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::*;
+use sp_runtime::DispatchError::BadOrigin;
+
+fn balance(who: &AccountId) -> Balance {
+    <Runtime as Config>::Assets::balance(NativeCurrencyId::get(), who)
+}
+
+fn total_voting_power() -> Balance {
+    <VoteEscrow as VotingEscrow<AccountId, Balance>>::total_voting_power()
+}
+
+fn gauge_relative_weights() -> Vec<(GaugeId, Ratio)> {
+    <VoteEscrow as GaugeController<GaugeId>>::gauge_relative_weights()
+}
+
+#[test]
+fn create_lock_should_work() {
+    new_test_ext().execute_with(|| {
+        // the unlock block is rounded down to the start of its epoch
+        assert_ok!(VoteEscrow::create_lock(
+            RuntimeOrigin::signed(ALICE),
+            1_000,
+            101
+        ));
+
+        let locked = VoteEscrow::locked(ALICE);
+        assert_eq!(locked.amount, 1_000);
+        assert_eq!(locked.end, 100);
+        assert_eq!(balance(&ALICE), 100_000_000 - 1_000);
+        assert_eq!(balance(&VoteEscrow::account_id()), 1_000);
+
+        // 1_000 / 100 every block for 99 blocks
+        assert_eq!(VoteEscrow::voting_power_of(&ALICE), 990);
+        assert_eq!(total_voting_power(), 990);
+        assert_eq!(VoteEscrow::slope_changes(100), 10);
+
+        System::assert_last_event(RuntimeEvent::VoteEscrow(crate::Event::Locked(
+            ALICE, 1_000, 100,
+        )));
+    });
+}
+
+#[test]
+fn create_lock_with_invalid_parameters_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            VoteEscrow::create_lock(RuntimeOrigin::signed(ALICE), 0, 100),
+            Error::<Runtime>::ZeroAmount
+        );
+        assert_noop!(
+            VoteEscrow::create_lock(RuntimeOrigin::signed(ALICE), 1_000, 9),
+            Error::<Runtime>::InvalidUnlockBlock
+        );
+        assert_noop!(
+            VoteEscrow::create_lock(RuntimeOrigin::signed(ALICE), 1_000, 110),
+            Error::<Runtime>::InvalidUnlockBlock
+        );
+
+        assert_ok!(VoteEscrow::create_lock(
+            RuntimeOrigin::signed(ALICE),
+            1_000,
+            100
+        ));
+        assert_noop!(
+            VoteEscrow::create_lock(RuntimeOrigin::signed(ALICE), 1_000, 100),
+            Error::<Runtime>::LockAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn voting_power_should_decay() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(VoteEscrow::create_lock(
+            RuntimeOrigin::signed(ALICE),
+            1_000,
+            100
+        ));
+        assert_ok!(VoteEscrow::create_lock(
+            RuntimeOrigin::signed(BOB),
+            2_000,
+            50
+        ));
+        assert_eq!(VoteEscrow::voting_power_of(&BOB), 980);
+        assert_eq!(total_voting_power(), 1_970);
+
+        run_to_block(25);
+        assert_eq!(VoteEscrow::voting_power_of(&ALICE), 750);
+        assert_eq!(VoteEscrow::voting_power_of(&BOB), 500);
+        assert_eq!(total_voting_power(), 1_250);
+
+        // the lock of BOB expires
+        run_to_block(50);
+        assert_eq!(VoteEscrow::voting_power_of(&BOB), 0);
+        assert_eq!(total_voting_power(), 500);
+
+        run_to_block(75);
+        assert_eq!(total_voting_power(), 250);
+
+        run_to_block(120);
+        assert_eq!(total_voting_power(), 0);
+    });
+}
+
+#[test]
+fn increase_lock_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(VoteEscrow::create_lock(
+            RuntimeOrigin::signed(ALICE),
+            1_000,
+            50
+        ));
+        assert_eq!(total_voting_power(), 490);
+
+        assert_ok!(VoteEscrow::increase_amount(
+            RuntimeOrigin::signed(ALICE),
+            1_000
+        ));
+        assert_eq!(VoteEscrow::locked(ALICE).amount, 2_000);
+        assert_eq!(balance(&VoteEscrow::account_id()), 2_000);
+        assert_eq!(total_voting_power(), 980);
+
+        assert_noop!(
+            VoteEscrow::increase_unlock_time(RuntimeOrigin::signed(ALICE), 55),
+            Error::<Runtime>::UnlockBlockNotIncreased
+        );
+        assert_ok!(VoteEscrow::increase_unlock_time(
+            RuntimeOrigin::signed(ALICE),
+            100
+        ));
+        assert_eq!(VoteEscrow::locked(ALICE).end, 100);
+        assert_eq!(VoteEscrow::slope_changes(50), 0);
+        assert_eq!(VoteEscrow::slope_changes(100), 20);
+        assert_eq!(total_voting_power(), 1_980);
+        System::assert_last_event(RuntimeEvent::VoteEscrow(crate::Event::LockExtended(
+            ALICE, 100,
+        )));
+
+        assert_noop!(
+            VoteEscrow::increase_amount(RuntimeOrigin::signed(BOB), 1_000),
+            Error::<Runtime>::LockDoesNotExist
+        );
+        run_to_block(100);
+        assert_noop!(
+            VoteEscrow::increase_amount(RuntimeOrigin::signed(ALICE), 1_000),
+            Error::<Runtime>::LockExpired
+        );
+    });
+}
+
+#[test]
+fn withdraw_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(VoteEscrow::create_lock(
+            RuntimeOrigin::signed(ALICE),
+            1_000,
+            50
+        ));
+
+        run_to_block(49);
+        assert_noop!(
+            VoteEscrow::withdraw(RuntimeOrigin::signed(ALICE)),
+            Error::<Runtime>::LockNotExpired
+        );
+
+        run_to_block(50);
+        assert_ok!(VoteEscrow::withdraw(RuntimeOrigin::signed(ALICE)));
+        assert_eq!(balance(&ALICE), 100_000_000);
+        assert_eq!(VoteEscrow::locked(ALICE), Default::default());
+        System::assert_last_event(RuntimeEvent::VoteEscrow(crate::Event::Withdrawn(
+            ALICE, 1_000,
+        )));
+
+        assert_noop!(
+            VoteEscrow::withdraw(RuntimeOrigin::signed(ALICE)),
+            Error::<Runtime>::LockDoesNotExist
+        );
+    });
+}
+
+#[test]
+fn epoch_start_should_checkpoint_votes() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(VoteEscrow::add_gauge(RuntimeOrigin::root(), GAUGE_1));
+        assert_ok!(VoteEscrow::create_lock(
+            RuntimeOrigin::signed(ALICE),
+            1_000,
+            50
+        ));
+        assert_ok!(VoteEscrow::vote_for_gauge(
+            RuntimeOrigin::signed(ALICE),
+            GAUGE_1,
+            Ratio::one()
+        ));
+
+        // the points are moved to the start of every epoch, reads only walk the
+        // current epoch
+        run_to_block(35);
+        assert_eq!(
+            VoteEscrow::supply(),
+            Point {
+                bias: 200,
+                slope: 10,
+                block: 30
+            }
+        );
+        assert_eq!(VoteEscrow::gauge_points(GAUGE_1).unwrap().block, 30);
+        assert_eq!(VoteEscrow::gauge_weights(GAUGE_1, 20), 300);
+        assert_eq!(VoteEscrow::gauge_weights(GAUGE_1, 30), 200);
+        assert_eq!(total_voting_power(), 150);
+
+        // no vote is left once the lock expires
+        run_to_block(60);
+        assert_eq!(
+            VoteEscrow::supply(),
+            Point {
+                bias: 0,
+                slope: 0,
+                block: 60
+            }
+        );
+        assert_eq!(VoteEscrow::gauge_weight(GAUGE_1, 60), 0);
+    });
+}
+
+#[test]
+fn add_and_remove_gauge_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            VoteEscrow::add_gauge(RuntimeOrigin::signed(ALICE), GAUGE_1),
+            BadOrigin
+        );
+        assert_ok!(VoteEscrow::add_gauge(RuntimeOrigin::root(), GAUGE_1));
+        assert_noop!(
+            VoteEscrow::add_gauge(RuntimeOrigin::root(), GAUGE_1),
+            Error::<Runtime>::GaugeAlreadyExists
+        );
+        assert_ok!(VoteEscrow::add_gauge(RuntimeOrigin::root(), GAUGE_2));
+        assert_ok!(VoteEscrow::add_gauge(RuntimeOrigin::root(), GAUGE_3));
+        assert_noop!(
+            VoteEscrow::add_gauge(RuntimeOrigin::root(), GAUGE_4),
+            Error::<Runtime>::ExcessMaxGaugesCount
+        );
+
+        assert_ok!(VoteEscrow::remove_gauge(RuntimeOrigin::root(), GAUGE_2));
+        assert_eq!(VoteEscrow::gauges().into_inner(), vec![GAUGE_1, GAUGE_3]);
+        System::assert_last_event(RuntimeEvent::VoteEscrow(crate::Event::GaugeRemoved(
+            GAUGE_2,
+        )));
+        assert_noop!(
+            VoteEscrow::remove_gauge(RuntimeOrigin::root(), GAUGE_2),
+            Error::<Runtime>::GaugeDoesNotExist
+        );
+    });
+}
+
+#[test]
+fn vote_for_gauge_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(VoteEscrow::add_gauge(RuntimeOrigin::root(), GAUGE_1));
+        assert_ok!(VoteEscrow::add_gauge(RuntimeOrigin::root(), GAUGE_2));
+        assert_ok!(VoteEscrow::create_lock(
+            RuntimeOrigin::signed(ALICE),
+            1_000,
+            100
+        ));
+        assert_ok!(VoteEscrow::create_lock(
+            RuntimeOrigin::signed(BOB),
+            1_000,
+            100
+        ));
+        assert_ok!(VoteEscrow::create_lock(
+            RuntimeOrigin::signed(CHARLIE),
+            1_000,
+            10
+        ));
+
+        assert_ok!(VoteEscrow::vote_for_gauge(
+            RuntimeOrigin::signed(ALICE),
+            GAUGE_1,
+            Ratio::one()
+        ));
+        assert_ok!(VoteEscrow::vote_for_gauge(
+            RuntimeOrigin::signed(BOB),
+            GAUGE_1,
+            Ratio::from_percent(50)
+        ));
+        assert_ok!(VoteEscrow::vote_for_gauge(
+            RuntimeOrigin::signed(BOB),
+            GAUGE_2,
+            Ratio::from_percent(50)
+        ));
+        System::assert_last_event(RuntimeEvent::VoteEscrow(crate::Event::Voted(
+            BOB,
+            GAUGE_2,
+            Ratio::from_percent(50),
+        )));
+        assert_eq!(VoteEscrow::voting_power_used(BOB), Ratio::one());
+
+        assert_noop!(
+            VoteEscrow::vote_for_gauge(
+                RuntimeOrigin::signed(BOB),
+                GAUGE_1,
+                Ratio::from_percent(60)
+            ),
+            Error::<Runtime>::ExcessVotingPower
+        );
+        assert_noop!(
+            VoteEscrow::vote_for_gauge(
+                RuntimeOrigin::signed(BOB),
+                GAUGE_3,
+                Ratio::from_percent(10)
+            ),
+            Error::<Runtime>::GaugeDoesNotExist
+        );
+        assert_noop!(
+            VoteEscrow::vote_for_gauge(RuntimeOrigin::signed(CHARLIE), GAUGE_1, Ratio::one()),
+            Error::<Runtime>::LockExpiresTooSoon
+        );
+
+        // votes apply from the next epoch
+        assert_eq!(
+            gauge_relative_weights(),
+            vec![(GAUGE_1, Ratio::zero()), (GAUGE_2, Ratio::zero())]
+        );
+        run_to_block(10);
+        assert_eq!(VoteEscrow::gauge_weight(GAUGE_1, 10), 1_350);
+        assert_eq!(VoteEscrow::gauge_weight(GAUGE_2, 10), 450);
+        assert_eq!(
+            gauge_relative_weights(),
+            vec![
+                (GAUGE_1, Ratio::from_percent(75)),
+                (GAUGE_2, Ratio::from_percent(25))
+            ]
+        );
+
+        // removing a vote keeps the weights of the current epoch
+        run_to_block(15);
+        assert_ok!(VoteEscrow::vote_for_gauge(
+            RuntimeOrigin::signed(ALICE),
+            GAUGE_1,
+            Ratio::zero()
+        ));
+        assert!(VoteEscrow::gauge_votes(ALICE, GAUGE_1).is_none());
+        assert_eq!(VoteEscrow::voting_power_used(ALICE), Ratio::zero());
+        assert_eq!(
+            gauge_relative_weights()[0],
+            (GAUGE_1, Ratio::from_percent(75))
+        );
+
+        run_to_block(20);
+        assert_eq!(VoteEscrow::gauge_weight(GAUGE_1, 20), 400);
+        assert_eq!(VoteEscrow::gauge_weight(GAUGE_2, 20), 400);
+        assert_eq!(
+            gauge_relative_weights(),
+            vec![
+                (GAUGE_1, Ratio::from_percent(50)),
+                (GAUGE_2, Ratio::from_percent(50))
+            ]
+        );
+
+        // votes expire with their locks
+        run_to_block(100);
+        assert_eq!(
+            gauge_relative_weights(),
+            vec![(GAUGE_1, Ratio::zero()), (GAUGE_2, Ratio::zero())]
+        );
+    });
+}
@@ -0,0 +1,47 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::RuntimeDebug;
+use primitives::{Balance, Ratio};
+use scale_info::TypeInfo;
+use sp_runtime::{
+    traits::{AtLeast32BitUnsigned, Saturating},
+    SaturatedConversion,
+};
+
+#[derive(
+    Clone, Copy, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+pub struct LockedBalance<BlockNumber> {
+    // The amount of native token locked
+    pub amount: Balance,
+    // The block the lock expires at, always the start of an epoch
+    pub end: BlockNumber,
+}
+
+/// Voting power decaying linearly, worth `bias` at `block` and losing `slope`
+/// every block after.
+#[derive(
+    Clone, Copy, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+pub struct Point<BlockNumber> {
+    pub bias: Balance,
+    pub slope: Balance,
+    pub block: BlockNumber,
+}
+
+impl<BlockNumber: AtLeast32BitUnsigned + Copy> Point<BlockNumber> {
+    /// The voting power left at `block`, which must not be before the point
+    pub fn bias_at(&self, block: BlockNumber) -> Balance {
+        let elapsed: Balance = block.saturating_sub(self.block).saturated_into();
+        self.bias.saturating_sub(self.slope.saturating_mul(elapsed))
+    }
+}
+
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct GaugeVote<BlockNumber> {
+    // The share of the voter power given to the gauge
+    pub weight: Ratio,
+    // The decay of the vote every block
+    pub slope: Balance,
+    // The block the vote decays to zero at, the end of the voter lock
+    pub end: BlockNumber,
+}
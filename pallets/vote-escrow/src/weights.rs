@@ -0,0 +1,218 @@
+// This file is part of Parallel Finance.
+
+// Copyright (C) 2022 Parallel Finance Developer.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Autogenerated weights for pallet_vote_escrow
+//!
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
+//! DATE: 2022-11-28, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
+//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("kerria-dev"), DB CACHE: 1024
+
+// Executed Command:
+// ./target/release/parallel
+// benchmark
+// pallet
+// --chain=kerria-dev
+// --execution=wasm
+// --wasm-execution=compiled
+// --pallet=pallet-vote-escrow
+// --extrinsic=*
+// --steps=50
+// --repeat=20
+// --heap-pages=4096
+// --template=./.maintain/frame-weight-template.hbs
+// --output=./pallets/vote-escrow/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_vote_escrow.
+pub trait WeightInfo {
+	fn create_lock() -> Weight;
+	fn increase_amount() -> Weight;
+	fn increase_unlock_time() -> Weight;
+	fn withdraw() -> Weight;
+	fn vote_for_gauge() -> Weight;
+	fn add_gauge() -> Weight;
+	fn remove_gauge() -> Weight;
+}
+
+/// Weights for pallet_vote_escrow using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: System Number (r:1 w:0)
+	// Storage: VoteEscrow Locked (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: VoteEscrow Supply (r:1 w:1)
+	// Storage: VoteEscrow SlopeChanges (r:1 w:1)
+	fn create_lock() -> Weight {
+		Weight::from_ref_time(79_184_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(9 as u64))
+			.saturating_add(T::DbWeight::get().writes(8 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: VoteEscrow Locked (r:1 w:1)
+	// Storage: System Number (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: VoteEscrow Supply (r:1 w:1)
+	// Storage: VoteEscrow SlopeChanges (r:2 w:2)
+	fn increase_amount() -> Weight {
+		Weight::from_ref_time(81_602_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(10 as u64))
+			.saturating_add(T::DbWeight::get().writes(9 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: VoteEscrow Locked (r:1 w:1)
+	// Storage: System Number (r:1 w:0)
+	// Storage: VoteEscrow Supply (r:1 w:1)
+	// Storage: VoteEscrow SlopeChanges (r:2 w:2)
+	fn increase_unlock_time() -> Weight {
+		Weight::from_ref_time(47_935_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: VoteEscrow Locked (r:1 w:1)
+	// Storage: System Number (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn withdraw() -> Weight {
+		Weight::from_ref_time(63_417_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(7 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: VoteEscrow Locked (r:1 w:0)
+	// Storage: System Number (r:1 w:0)
+	// Storage: VoteEscrow Gauges (r:1 w:0)
+	// Storage: VoteEscrow GaugeVotes (r:1 w:1)
+	// Storage: VoteEscrow VotingPowerUsed (r:1 w:1)
+	// Storage: VoteEscrow GaugePoints (r:1 w:1)
+	// Storage: VoteEscrow GaugeSlopeChanges (r:2 w:2)
+	// Storage: VoteEscrow GaugeWeights (r:0 w:2)
+	fn vote_for_gauge() -> Weight {
+		Weight::from_ref_time(58_341_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(9 as u64))
+			.saturating_add(T::DbWeight::get().writes(8 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: VoteEscrow Gauges (r:1 w:1)
+	// Storage: VoteEscrow GaugePoints (r:1 w:1)
+	// Storage: System Number (r:1 w:0)
+	fn add_gauge() -> Weight {
+		Weight::from_ref_time(27_513_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: VoteEscrow Gauges (r:1 w:1)
+	fn remove_gauge() -> Weight {
+		Weight::from_ref_time(24_076_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: System Number (r:1 w:0)
+	// Storage: VoteEscrow Locked (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: VoteEscrow Supply (r:1 w:1)
+	// Storage: VoteEscrow SlopeChanges (r:1 w:1)
+	fn create_lock() -> Weight {
+		Weight::from_ref_time(79_184_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(9 as u64))
+			.saturating_add(RocksDbWeight::get().writes(8 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: VoteEscrow Locked (r:1 w:1)
+	// Storage: System Number (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: VoteEscrow Supply (r:1 w:1)
+	// Storage: VoteEscrow SlopeChanges (r:2 w:2)
+	fn increase_amount() -> Weight {
+		Weight::from_ref_time(81_602_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(10 as u64))
+			.saturating_add(RocksDbWeight::get().writes(9 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: VoteEscrow Locked (r:1 w:1)
+	// Storage: System Number (r:1 w:0)
+	// Storage: VoteEscrow Supply (r:1 w:1)
+	// Storage: VoteEscrow SlopeChanges (r:2 w:2)
+	fn increase_unlock_time() -> Weight {
+		Weight::from_ref_time(47_935_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes(5 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: VoteEscrow Locked (r:1 w:1)
+	// Storage: System Number (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn withdraw() -> Weight {
+		Weight::from_ref_time(63_417_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(7 as u64))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: VoteEscrow Locked (r:1 w:0)
+	// Storage: System Number (r:1 w:0)
+	// Storage: VoteEscrow Gauges (r:1 w:0)
+	// Storage: VoteEscrow GaugeVotes (r:1 w:1)
+	// Storage: VoteEscrow VotingPowerUsed (r:1 w:1)
+	// Storage: VoteEscrow GaugePoints (r:1 w:1)
+	// Storage: VoteEscrow GaugeSlopeChanges (r:2 w:2)
+	// Storage: VoteEscrow GaugeWeights (r:0 w:2)
+	fn vote_for_gauge() -> Weight {
+		Weight::from_ref_time(58_341_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(9 as u64))
+			.saturating_add(RocksDbWeight::get().writes(8 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: VoteEscrow Gauges (r:1 w:1)
+	// Storage: VoteEscrow GaugePoints (r:1 w:1)
+	// Storage: System Number (r:1 w:0)
+	fn add_gauge() -> Weight {
+		Weight::from_ref_time(27_513_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: VoteEscrow Gauges (r:1 w:1)
+	fn remove_gauge() -> Weight {
+		Weight::from_ref_time(24_076_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+}
@@ -93,6 +93,7 @@ pub use pallet_loans;
 pub use pallet_prices;
 pub use pallet_router;
 pub use pallet_streaming;
+pub use pallet_vote_escrow;
 
 use pallet_ethereum::PostLogContent;
 use pallet_evm::{FeeCalculator, Runner};
@@ -336,6 +337,8 @@ impl Contains<RuntimeCall> for BaseCallFilter {
                 RuntimeCall::Farming(_) |
                 // Streaming
                 RuntimeCall::Streaming(_) |
+                // VoteEscrow
+                RuntimeCall::VoteEscrow(_) |
                 // Asset Management
                 RuntimeCall::AssetRegistry(_) |
                 // EVM
@@ -506,6 +509,9 @@ impl pallet_assets::Config for Runtime {
 parameter_types! {
     pub const RewardAssetId: CurrencyId = HKO;
     pub const LiquidationFreeAssetId: CurrencyId = KSM;
+    pub StreamCollateralDiscount: Ratio = Ratio::from_percent(30);
+    pub const MaxStreamCollaterals: u32 = 10;
+    pub MaxRewardBoost: Rate = Rate::saturating_from_rational(5, 2);
 }
 
 impl pallet_loans::Config for Runtime {
@@ -519,6 +525,13 @@ impl pallet_loans::Config for Runtime {
     type Assets = CurrencyAdapter;
     type RewardAssetId = RewardAssetId;
     type LiquidationFreeAssetId = LiquidationFreeAssetId;
+    type RuntimeCall = RuntimeCall;
+    type FlashLoanReceiver = ();
+    type StreamCollateral = Streaming;
+    type StreamCollateralDiscount = StreamCollateralDiscount;
+    type MaxStreamCollaterals = MaxStreamCollaterals;
+    type VotingEscrow = VoteEscrow;
+    type MaxRewardBoost = MaxRewardBoost;
 }
 
 parameter_types! {
@@ -941,6 +954,15 @@ impl InstanceFilter<RuntimeCall> for ProxyType {
                         | RuntimeCall::Loans(pallet_loans::Call::repay_borrow_all { .. })
                         | RuntimeCall::Loans(pallet_loans::Call::collateral_asset { .. })
                         | RuntimeCall::Loans(pallet_loans::Call::liquidate_borrow { .. })
+                        | RuntimeCall::Loans(pallet_loans::Call::bid_liquidation_auction { .. })
+                        | RuntimeCall::Loans(pallet_loans::Call::settle_liquidation_auction { .. })
+                        | RuntimeCall::Loans(pallet_loans::Call::set_emode { .. })
+                        | RuntimeCall::Loans(pallet_loans::Call::revoke_borrow_allowance { .. })
+                        | RuntimeCall::Loans(pallet_loans::Call::borrow_on_behalf { .. })
+                        | RuntimeCall::Loans(pallet_loans::Call::borrow_stable { .. })
+                        | RuntimeCall::Loans(
+                            pallet_loans::Call::rebalance_stable_borrow_rate { .. }
+                        )
                         | RuntimeCall::Loans(pallet_loans::Call::add_reward { .. })
                         | RuntimeCall::Loans(pallet_loans::Call::claim_reward { .. })
                         | RuntimeCall::Loans(pallet_loans::Call::claim_reward_for_market { .. })
@@ -1006,6 +1028,12 @@ impl InstanceFilter<RuntimeCall> for ProxyType {
                         | RuntimeCall::AMMRoute(
                             pallet_router::Call::swap_exact_tokens_for_tokens { .. }
                         )
+                        | RuntimeCall::AMMRoute(
+                            pallet_router::Call::swap_tokens_for_exact_tokens_split { .. }
+                        )
+                        | RuntimeCall::AMMRoute(
+                            pallet_router::Call::swap_exact_tokens_for_tokens_split { .. }
+                        )
                 )
             }
             ProxyType::EVM => {
@@ -1420,6 +1448,11 @@ impl DecimalProvider<CurrencyId> for Decimal {
     }
 }
 
+parameter_types! {
+    pub const MaxPriceGuards: u32 = 32;
+    pub const MaxTwapSources: u32 = 16;
+}
+
 impl pallet_prices::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type Source = AggregatedDataProvider;
@@ -1429,9 +1462,14 @@ impl pallet_prices::Config for Runtime {
     type LiquidStakingCurrenciesProvider = LiquidStaking;
     type VaultTokenCurrenciesFilter = Crowdloans;
     type VaultTokenExchangeRateProvider = Crowdloans;
+    type LPVaultTokenCurrenciesFilter = Farming;
+    type LPVaultTokenExchangeRateProvider = Farming;
     type VaultLoansRateProvider = Loans;
     type RelayCurrency = RelayCurrency;
     type Decimal = Decimal;
+    type UnixTime = Timestamp;
+    type MaxPriceGuards = MaxPriceGuards;
+    type MaxTwapSources = MaxTwapSources;
     type AMM = AMM;
     type Assets = CurrencyAdapter;
     type WeightInfo = pallet_prices::weights::SubstrateWeight<Runtime>;
@@ -1736,6 +1774,8 @@ parameter_types! {
     // About 30 days: 30 * 24 * 60 * 60 / 6 = 2592000 blocks
     pub const ProposalLifetime: BlockNumber = 2592000;
     pub const ThresholdPercentage: u32 = 80;
+    pub const MaxUnfinalizedHeaders: u32 = 128;
+    pub const MaxFinalizedReceiptsRoots: u32 = 1_200;
 }
 
 impl pallet_bridge::Config for Runtime {
@@ -1754,6 +1794,8 @@ impl pallet_bridge::Config for Runtime {
     type ExistentialDeposit = ExistentialDeposit;
     type ProposalLifetime = ProposalLifetime;
     type ThresholdPercentage = ThresholdPercentage;
+    type MaxUnfinalizedHeaders = MaxUnfinalizedHeaders;
+    type MaxFinalizedReceiptsRoots = MaxFinalizedReceiptsRoots;
     type WeightInfo = weights::pallet_bridge::WeightInfo<Runtime>;
 }
 
@@ -1778,6 +1820,8 @@ parameter_types! {
     pub DefaultProtocolFee: Ratio = Ratio::from_rational(0u32, 10000u32);   // 0.00% no fees for launch
     pub DefaultProtocolFeeReceiver: AccountId = TreasuryPalletId::get().into_account_truncating();
     pub const MinimumLiquidity: u128 = 1_000u128;
+    pub const MaxTicksPerPool: u32 = 1_000;
+    pub const MinPositionLiquidity: u128 = 1_000_000_000_000u128;
 }
 
 impl pallet_amm::Config for Runtime {
@@ -1792,6 +1836,9 @@ impl pallet_amm::Config for Runtime {
     type MinimumLiquidity = MinimumLiquidity;
     type MaxLengthRoute = MaxLengthRoute;
     type GetNativeCurrencyId = NativeCurrencyId;
+    type MaxTicksPerPool = MaxTicksPerPool;
+    type MinPositionLiquidity = MinPositionLiquidity;
+    type PoolsHook = AMMRoute;
 }
 
 parameter_types! {
@@ -1865,6 +1912,7 @@ parameter_types! {
     pub const StreamPalletId: PalletId = PalletId(*b"par/strm");
     pub const MaxStreamsCount: u32 = 128;
     pub const MaxFinishedStreamsCount: u32 = 10;
+    pub const MaxSchedulePoints: u32 = 24;
 }
 
 impl pallet_streaming::Config for Runtime {
@@ -1878,6 +1926,27 @@ impl pallet_streaming::Config for Runtime {
     type WeightInfo = weights::pallet_streaming::WeightInfo<Runtime>;
     type NativeCurrencyId = NativeCurrencyId;
     type NativeExistentialDeposit = ExistentialDeposit;
+    type MaxSchedulePoints = MaxSchedulePoints;
+}
+
+parameter_types! {
+    pub const VoteEscrowPalletId: PalletId = PalletId(*b"par/vote");
+    pub const EpochDuration: BlockNumber = 7 * DAYS;
+    pub const MaxLockDuration: BlockNumber = 4 * 365 * DAYS;
+    pub const MaxGauges: u32 = 50;
+}
+
+impl pallet_vote_escrow::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Assets = CurrencyAdapter;
+    type PalletId = VoteEscrowPalletId;
+    type LockCurrencyId = NativeCurrencyId;
+    type EpochDuration = EpochDuration;
+    type MaxLockDuration = MaxLockDuration;
+    type GaugeId = (CurrencyId, CurrencyId, BlockNumber);
+    type MaxGauges = MaxGauges;
+    type UpdateOrigin = EnsureRootOrMoreThanHalfGeneralCouncil;
+    type WeightInfo = pallet_vote_escrow::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -1902,6 +1971,8 @@ impl pallet_xcm_helper::Config for Runtime {
 
 parameter_types! {
     pub const MaxLengthRoute: u8 = 10;
+    pub const MaxRouteSplits: u32 = 4;
+    pub const MaxRoutePools: u32 = 100;
     pub const RouterPalletId: PalletId = PalletId(*b"ammroute");
 }
 
@@ -1909,8 +1980,11 @@ impl pallet_router::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type PalletId = RouterPalletId;
     type AMM = AMM;
+    type StableSwap = ();
     type AMMRouterWeightInfo = weights::pallet_router::WeightInfo<Runtime>;
     type MaxLengthRoute = MaxLengthRoute;
+    type MaxRouteSplits = MaxRouteSplits;
+    type MaxRoutePools = MaxRoutePools;
     type Assets = CurrencyAdapter;
     type GetNativeCurrencyId = NativeCurrencyId;
 }
@@ -1927,6 +2001,11 @@ parameter_types! {
     pub const MaxUserLockItemsCount: u32 = 100;
     pub const LockPoolMaxDuration: u32 = 2628000;
     pub const CoolDownMaxDuration: u32 = 50400;
+    pub const MaxVaultHarvestsPerBlock: u32 = 10;
+    pub MaxHarvestSlippage: Ratio = Ratio::from_percent(2);
+    pub const MinimumVaultShares: Balance = 1_000;
+    pub const MaxExtraRewardAssets: u32 = 4;
+    pub MaxBoost: Rate = Rate::saturating_from_rational(5, 2);
 }
 
 impl pallet_farming::Config for Runtime {
@@ -1939,6 +2018,16 @@ impl pallet_farming::Config for Runtime {
     type LockPoolMaxDuration = LockPoolMaxDuration;
     type CoolDownMaxDuration = CoolDownMaxDuration;
     type Decimal = Decimal;
+    type AMM = AMM;
+    type Router = AMMRoute;
+    type MaxVaultHarvestsPerBlock = MaxVaultHarvestsPerBlock;
+    type PriceFeeder = Prices;
+    type MaxHarvestSlippage = MaxHarvestSlippage;
+    type MinimumVaultShares = MinimumVaultShares;
+    type MaxExtraRewardAssets = MaxExtraRewardAssets;
+    type VotingEscrow = VoteEscrow;
+    type GaugeController = VoteEscrow;
+    type MaxBoost = MaxBoost;
 }
 
 impl pallet_emergency_shutdown::Config for Runtime {
@@ -2012,7 +2101,7 @@ construct_runtime!(
 
         // AMM
         AMM: pallet_amm::{Pallet, Call, Storage, Event<T>} = 80,
-        AMMRoute: pallet_router::{Pallet, Call, Event<T>} = 81,
+        AMMRoute: pallet_router::{Pallet, Call, Storage, Event<T>} = 81,
         CurrencyAdapter: pallet_currency_adapter::{Pallet, Call} = 82,
 
         // Others
@@ -2022,6 +2111,7 @@ construct_runtime!(
         XcmHelper: pallet_xcm_helper::{Pallet, Call, Storage, Event<T>} = 93,
         Streaming: pallet_streaming::{Pallet, Call, Storage, Event<T>} = 94,
         AssetRegistry: pallet_asset_registry::{Pallet, Call, Storage, Event<T>} = 95,
+        VoteEscrow: pallet_vote_escrow::{Pallet, Call, Storage, Event<T>} = 96,
 
         // EVM
         EVM: pallet_evm::{Pallet, Config, Call, Storage, Event<T>} = 110,
@@ -2063,6 +2153,13 @@ pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic =
     fp_self_contained::CheckedExtrinsic<AccountId, RuntimeCall, SignedExtra, H160>;
+/// Storage migrations applied on runtime upgrade.
+pub type Migrations = (
+    pallet_loans::migrations::v7::MigrateToV7<Runtime>,
+    pallet_amm::migrations::v2::MigrateToV2<Runtime>,
+    pallet_router::migrations::v2::MigrateToV2<Runtime>,
+    pallet_streaming::migrations::v2::MigrateToV2<Runtime>,
+);
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
     Runtime,
@@ -2070,7 +2167,7 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
-    (),
+    Migrations,
 >;
 
 impl fp_self_contained::SelfContainedCall for RuntimeCall {
@@ -2271,19 +2368,49 @@ impl_runtime_apis! {
             Loans::get_account_liquidity(&account)
         }
 
-        fn get_market_status(asset_id: CurrencyId) -> Result<(Rate, Rate, Rate, Ratio, Balance, Balance, sp_runtime::FixedU128), DispatchError> {
+        fn get_market_status(asset_id: CurrencyId) -> Result<pallet_traits::MarketStatus<Balance>, DispatchError> {
             Loans::get_market_status(asset_id)
         }
 
         fn get_liquidation_threshold_liquidity(account: AccountId) -> Result<(Liquidity, Shortfall, Liquidity, Shortfall), DispatchError> {
             Loans::get_account_liquidation_threshold_liquidity(&account)
         }
+
+        fn get_liquidation_auctions() -> Vec<(pallet_traits::AuctionId, pallet_traits::LiquidationAuction<AccountId, CurrencyId, Balance>, Rate)> {
+            Loans::get_liquidation_auctions()
+        }
+
+        fn get_account_mode(account: AccountId) -> pallet_traits::AccountMode<CurrencyId> {
+            Loans::get_account_mode(&account)
+        }
+
+        fn get_liquidation_candidates(min_shortfall: Shortfall, start_key: Option<Vec<u8>>, limit: u32) -> Result<(Vec<(AccountId, Shortfall)>, Option<Vec<u8>>), DispatchError> {
+            Loans::get_liquidation_candidates(min_shortfall, start_key, limit)
+        }
+
+        fn get_account_position(account: AccountId) -> Result<pallet_traits::AccountPosition<CurrencyId, Balance>, DispatchError> {
+            Loans::get_account_position(&account)
+        }
+
+        fn simulate_liquidation(borrower: AccountId, liquidation_asset_id: CurrencyId, repay_amount: Balance, collateral_asset_id: CurrencyId) -> Result<pallet_traits::LiquidationSimulation<Balance>, DispatchError> {
+            Loans::simulate_liquidation(&borrower, liquidation_asset_id, repay_amount, collateral_asset_id)
+        }
     }
 
     impl pallet_router_rpc_runtime_api::RouterApi<Block, Balance> for Runtime {
-        fn get_best_route(amount: Balance, token_in: CurrencyId, token_out: CurrencyId, reversed: bool) -> Result<(Vec<CurrencyId>, Balance), DispatchError> {
-            let (route, amount) = AMMRoute::get_best_route(amount, token_in, token_out, reversed)?;
-            Ok((route, amount))
+        fn get_best_route(amount: Balance, token_in: CurrencyId, token_out: CurrencyId, reversed: bool) -> Result<(Vec<(Vec<CurrencyId>, Balance)>, Balance), DispatchError> {
+            let (split_route, amount) = AMMRoute::get_best_split_route(amount, token_in, token_out, reversed)?;
+            Ok((split_route, amount))
+        }
+    }
+
+    impl pallet_streaming_rpc_runtime_api::StreamingApi<Block, AccountId, Balance> for Runtime {
+        fn get_sent_streams(sender: AccountId) -> Result<Vec<pallet_traits::StreamInfo<AccountId, CurrencyId, Balance>>, DispatchError> {
+            Streaming::get_streams(&sender, pallet_streaming::types::StreamKind::Send)
+        }
+
+        fn get_received_streams(recipient: AccountId) -> Result<Vec<pallet_traits::StreamInfo<AccountId, CurrencyId, Balance>>, DispatchError> {
+            Streaming::get_streams(&recipient, pallet_streaming::types::StreamKind::Receive)
         }
     }
 
@@ -2479,6 +2606,7 @@ impl_runtime_apis! {
             list_benchmark!(list, extra, pallet_xcm_helper, XcmHelper);
             list_benchmark!(list, extra, pallet_asset_registry, AssetRegistry);
             list_benchmark!(list, extra, pallet_streaming, Streaming);
+            list_benchmark!(list, extra, pallet_vote_escrow, VoteEscrow);
             list_benchmark!(list, extra, pallet_assets, Assets);
             list_benchmark!(list, extra, pallet_collator_selection, CollatorSelection);
             list_benchmark!(list, extra, pallet_proxy, Proxy);
@@ -2538,6 +2666,7 @@ impl_runtime_apis! {
             add_benchmark!(params, batches, pallet_xcm_helper, XcmHelper);
             add_benchmark!(params, batches, pallet_asset_registry, AssetRegistry);
             add_benchmark!(params, batches, pallet_streaming, Streaming);
+            add_benchmark!(params, batches, pallet_vote_escrow, VoteEscrow);
             add_benchmark!(params, batches, pallet_assets, Assets);
             add_benchmark!(params, batches, pallet_collator_selection, CollatorSelection);
             add_benchmark!(params, batches, pallet_proxy, Proxy);
@@ -39,11 +39,13 @@ impl<T: frame_system::Config> pallet_bridge::WeightInfo for WeightInfo<T> {
 	}
 	// Storage: Bridge ChainNonces (r:1 w:1)
 	// Storage: Bridge BridgeRegistry (r:0 w:1)
+	// Storage: Bridge LightClients (r:0 w:1)
+	// Storage: Bridge UnfinalizedHeaders (r:0 w:1)
 	fn unregister_chain() -> Weight {
 		// Minimum execution time: 34_641 nanoseconds.
 		Weight::from_ref_time(35_517_000)
 			.saturating_add(T::DbWeight::get().reads(1))
-			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().writes(4))
 	}
 	// Storage: Bridge BridgeTokens (r:1 w:1)
 	// Storage: Bridge AssetIds (r:1 w:1)
@@ -122,4 +124,42 @@ impl<T: frame_system::Config> pallet_bridge::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(12))
 			.saturating_add(T::DbWeight::get().writes(6))
 	}
+	// Storage: Bridge ChainNonces (r:1 w:0)
+	// Storage: Bridge FinalizedReceiptsRoots (r:0 w:1)
+	// Storage: Bridge UnfinalizedHeaders (r:0 w:1)
+	// Storage: Bridge LightClients (r:0 w:1)
+	fn initialize_light_client() -> Weight {
+		// Minimum execution time: 68_212 nanoseconds.
+		Weight::from_ref_time(69_384_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: Bridge LightClients (r:1 w:1)
+	// Storage: Bridge UnfinalizedHeaders (r:1 w:1)
+	// Storage: Bridge FinalizedReceiptsRoots (r:0 w:2)
+	/// The range of component `n` is `[1, 64]`.
+	fn submit_headers(n: u32, ) -> Weight {
+		// Minimum execution time: 112_530 nanoseconds.
+		Weight::from_ref_time(24_108_000)
+			// Standard Error: 41_000
+			.saturating_add(Weight::from_ref_time(96_275_000).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(n.into())))
+	}
+	// Storage: Bridge ChainNonces (r:1 w:0)
+	// Storage: Bridge LightClients (r:1 w:0)
+	// Storage: Bridge FinalizedReceiptsRoots (r:1 w:0)
+	// Storage: Bridge BridgeRegistry (r:1 w:1)
+	// Storage: Bridge AssetIds (r:1 w:0)
+	// Storage: Bridge BridgeTokens (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Assets Metadata (r:1 w:0)
+	// Storage: System Account (r:1 w:0)
+	fn materialize_with_proof() -> Weight {
+		// Minimum execution time: 196_511 nanoseconds.
+		Weight::from_ref_time(198_011_000)
+			.saturating_add(T::DbWeight::get().reads(11))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
 }
@@ -64,4 +64,95 @@ impl<T: frame_system::Config> pallet_amm::WeightInfo for WeightInfo<T> {
 	fn update_protocol_fee_receiver() -> Weight {
 		Weight::from_ref_time(4_114_000 as u64).saturating_add(T::DbWeight::get().writes(1 as u64))
 	}
+	// Storage: AMM FeeTiers (r:0 w:1)
+	fn set_fee_tier() -> Weight {
+		// Minimum execution time: 14_512 nanoseconds.
+		Weight::from_ref_time(14_512_000)
+			.saturating_add(T::DbWeight::get().reads(0))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: AMM ConcentratedPools (r:1 w:1)
+	// Storage: AMM FeeTiers (r:1 w:0)
+	fn create_concentrated_pool() -> Weight {
+		// Minimum execution time: 27_301 nanoseconds.
+		Weight::from_ref_time(27_301_000)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: AMM ConcentratedPools (r:1 w:1)
+	// Storage: AMM ConcentratedTicks (r:2 w:2)
+	// Storage: AMM InitializedTicks (r:1 w:1)
+	// Storage: AMM NextPositionId (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	// Storage: AMM Positions (r:0 w:1)
+	fn mint_position() -> Weight {
+		// Minimum execution time: 112_845 nanoseconds.
+		Weight::from_ref_time(112_845_000)
+			.saturating_add(T::DbWeight::get().reads(11))
+			.saturating_add(T::DbWeight::get().writes(10))
+	}
+	// Storage: AMM Positions (r:1 w:1)
+	// Storage: AMM ConcentratedPools (r:1 w:1)
+	// Storage: AMM ConcentratedTicks (r:2 w:2)
+	// Storage: AMM InitializedTicks (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	fn decrease_position_liquidity() -> Weight {
+		// Minimum execution time: 118_920 nanoseconds.
+		Weight::from_ref_time(118_920_000)
+			.saturating_add(T::DbWeight::get().reads(11))
+			.saturating_add(T::DbWeight::get().writes(10))
+	}
+	// Storage: AMM Positions (r:1 w:1)
+	// Storage: AMM ConcentratedPools (r:1 w:0)
+	// Storage: AMM ConcentratedTicks (r:2 w:0)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	fn collect_position_fees() -> Weight {
+		// Minimum execution time: 84_117 nanoseconds.
+		Weight::from_ref_time(84_117_000)
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: AMM ProtocolFeeReceiver (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets Account (r:1 w:0)
+	fn update_pool_fees() -> Weight {
+		// Minimum execution time: 38_411 nanoseconds.
+		Weight::from_ref_time(38_411_000)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: AMM DynamicFees (r:0 w:1)
+	fn set_dynamic_fee() -> Weight {
+		// Minimum execution time: 24_937 nanoseconds.
+		Weight::from_ref_time(24_937_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: AMM DynamicFees (r:1 w:1)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:5 w:5)
+	fn zap_in() -> Weight {
+		// Minimum execution time: 301_264 nanoseconds.
+		Weight::from_ref_time(301_264_000)
+			.saturating_add(T::DbWeight::get().reads(11))
+			.saturating_add(T::DbWeight::get().writes(11))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: AMM DynamicFees (r:1 w:1)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:5 w:5)
+	fn zap_out() -> Weight {
+		// Minimum execution time: 318_702 nanoseconds.
+		Weight::from_ref_time(318_702_000)
+			.saturating_add(T::DbWeight::get().reads(11))
+			.saturating_add(T::DbWeight::get().writes(11))
+	}
 }
@@ -46,4 +46,14 @@ impl<T: frame_system::Config> pallet_router::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(8 as u64))
 			.saturating_add(T::DbWeight::get().writes(8 as u64))
 	}
+	// Storage: AMMRoute RoutePools (r:1 w:0)
+	// Storage: AMM Pools (r:100 w:0)
+	// Storage: AMM DynamicFees (r:100 w:0)
+	fn get_best_route(p: u32, ) -> Weight {
+		Weight::from_ref_time(18_342_000 as u64)
+			// Standard Error: 21_000
+			.saturating_add(Weight::from_ref_time(41_607_000 as u64).saturating_mul(p as u64))
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().reads((5 as u64).saturating_mul(p as u64)))
+	}
 }
@@ -317,4 +317,280 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans LiquidationAuctionConfigs (r:0 w:1)
+	fn update_liquidation_auction_config() -> Weight {
+		// Minimum execution time: 38_000 nanoseconds.
+		Weight::from_ref_time(38_000_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Loans LiquidationAuctions (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:2 w:0)
+	// Storage: Prices EmergencyPrice (r:2 w:0)
+	// Storage: Assets Metadata (r:2 w:0)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:3 w:2)
+	// Storage: Loans TotalBorrows (r:2 w:1)
+	// Storage: Loans TotalStableBorrows (r:2 w:0)
+	// Storage: Loans TotalReserves (r:1 w:0)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	// Storage: Loans AccountBorrows (r:1 w:1)
+	// Storage: Loans RewardBorrowState (r:1 w:1)
+	// Storage: Loans RewardBorrowSpeed (r:1 w:0)
+	// Storage: Loans RewardBorrowerIndex (r:1 w:1)
+	// Storage: Loans RewardAccured (r:2 w:2)
+	// Storage: Loans RewardSupplyState (r:1 w:1)
+	// Storage: Loans RewardSupplySpeed (r:1 w:0)
+	// Storage: Loans RewardSupplierIndex (r:2 w:2)
+	// Storage: Loans AccountDeposits (r:2 w:2)
+	// Storage: Loans BorrowerAuction (r:0 w:1)
+	fn bid_liquidation_auction() -> Weight {
+		// Minimum execution time: 285_000 nanoseconds.
+		Weight::from_ref_time(285_000_000)
+			.saturating_add(T::DbWeight::get().reads(33))
+			.saturating_add(T::DbWeight::get().writes(16))
+	}
+	// Storage: Loans LiquidationAuctions (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:2 w:0)
+	// Storage: Loans TotalSupply (r:2 w:1)
+	// Storage: Assets Account (r:2 w:0)
+	// Storage: Loans TotalBorrows (r:2 w:1)
+	// Storage: Loans TotalStableBorrows (r:2 w:0)
+	// Storage: Loans TotalReserves (r:2 w:2)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	// Storage: Loans AccountBorrows (r:1 w:1)
+	// Storage: Loans RewardBorrowState (r:1 w:1)
+	// Storage: Loans RewardBorrowSpeed (r:1 w:0)
+	// Storage: Loans RewardBorrowerIndex (r:1 w:1)
+	// Storage: Loans RewardAccured (r:1 w:1)
+	// Storage: Loans RewardSupplyState (r:1 w:1)
+	// Storage: Loans RewardSupplySpeed (r:1 w:0)
+	// Storage: Loans BorrowerAuction (r:0 w:1)
+	fn settle_liquidation_auction() -> Weight {
+		// Minimum execution time: 192_000 nanoseconds.
+		Weight::from_ref_time(192_000_000)
+			.saturating_add(T::DbWeight::get().reads(24))
+			.saturating_add(T::DbWeight::get().writes(11))
+	}
+	// Storage: Loans Markets (r:3 w:0)
+	// Storage: Loans IsolationConfigs (r:0 w:1)
+	fn update_isolation_config() -> Weight {
+		// Minimum execution time: 41_000 nanoseconds.
+		Weight::from_ref_time(41_000_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Loans Markets (r:3 w:0)
+	// Storage: Loans EModeCategories (r:0 w:1)
+	fn update_emode_category() -> Weight {
+		// Minimum execution time: 40_000 nanoseconds.
+		Weight::from_ref_time(40_000_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Loans EModeCategories (r:1 w:0)
+	// Storage: Loans IsolationConfigs (r:1 w:0)
+	// Storage: Loans Markets (r:6 w:0)
+	// Storage: Loans AccountBorrows (r:6 w:0)
+	// Storage: Loans AccountDeposits (r:6 w:0)
+	// Storage: Loans LiquidationFreeCollaterals (r:1 w:0)
+	// Storage: Prices EmergencyPrice (r:5 w:0)
+	// Storage: Loans AccountEMode (r:0 w:1)
+	fn set_emode() -> Weight {
+		// Minimum execution time: 156_000 nanoseconds.
+		Weight::from_ref_time(156_000_000)
+			.saturating_add(T::DbWeight::get().reads(26))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans FlashLoanFees (r:0 w:1)
+	fn update_flash_loan_fee() -> Weight {
+		// Minimum execution time: 32_000 nanoseconds.
+		Weight::from_ref_time(32_000_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans FlashLoanFees (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans FlashLoanOutstanding (r:1 w:1)
+	// Storage: Loans TotalReserves (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	fn flash_loan() -> Weight {
+		// Minimum execution time: 131_000 nanoseconds.
+		Weight::from_ref_time(131_000_000)
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans BorrowAllowances (r:0 w:1)
+	fn approve_borrow() -> Weight {
+		// Minimum execution time: 30_000 nanoseconds.
+		Weight::from_ref_time(30_000_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Loans BorrowAllowances (r:0 w:1)
+	fn revoke_borrow_allowance() -> Weight {
+		// Minimum execution time: 25_000 nanoseconds.
+		Weight::from_ref_time(25_000_000)
+			.saturating_add(T::DbWeight::get().reads(0))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Loans BorrowAllowances (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Loans TotalReserves (r:1 w:0)
+	// Storage: Prices EmergencyPrice (r:2 w:0)
+	// Storage: Assets Metadata (r:2 w:0)
+	// Storage: Loans AccountBorrows (r:2 w:1)
+	// Storage: Loans AccountDeposits (r:1 w:0)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	// Storage: Loans LiquidationFreeCollaterals (r:1 w:0)
+	// Storage: Loans RewardBorrowState (r:1 w:1)
+	// Storage: Loans RewardBorrowSpeed (r:1 w:0)
+	// Storage: Loans RewardBorrowerIndex (r:1 w:1)
+	// Storage: Loans RewardAccured (r:1 w:1)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	fn borrow_on_behalf() -> Weight {
+		// Minimum execution time: 372_000 nanoseconds.
+		Weight::from_ref_time(372_000_000)
+			.saturating_add(T::DbWeight::get().reads(25))
+			.saturating_add(T::DbWeight::get().writes(11))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans StableBorrowConfigs (r:0 w:1)
+	fn update_stable_borrow_config() -> Weight {
+		// Minimum execution time: 27_000 nanoseconds.
+		Weight::from_ref_time(27_000_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Loans StableBorrowConfigs (r:2 w:0)
+	// Storage: Loans Markets (r:3 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:0)
+	// Storage: Loans TotalStableBorrows (r:1 w:1)
+	// Storage: Loans AverageStableBorrowRate (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Loans TotalReserves (r:1 w:0)
+	// Storage: Prices EmergencyPrice (r:2 w:0)
+	// Storage: Assets Metadata (r:2 w:0)
+	// Storage: Loans AccountBorrows (r:2 w:1)
+	// Storage: Loans AccountDeposits (r:1 w:0)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	// Storage: Loans RewardBorrowState (r:1 w:1)
+	// Storage: Loans RewardBorrowSpeed (r:1 w:0)
+	// Storage: Loans RewardBorrowerIndex (r:1 w:1)
+	// Storage: Loans RewardAccured (r:1 w:1)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	fn borrow_stable() -> Weight {
+		// Minimum execution time: 385_000 nanoseconds.
+		Weight::from_ref_time(385_000_000)
+			.saturating_add(T::DbWeight::get().reads(27))
+			.saturating_add(T::DbWeight::get().writes(12))
+	}
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans StableBorrowConfigs (r:2 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans AccountBorrows (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:0)
+	// Storage: Loans TotalStableBorrows (r:1 w:1)
+	// Storage: Loans AverageStableBorrowRate (r:1 w:1)
+	// Storage: Loans TotalReserves (r:1 w:0)
+	// Storage: Loans RewardBorrowState (r:1 w:1)
+	// Storage: Loans RewardBorrowSpeed (r:1 w:0)
+	// Storage: Loans RewardBorrowerIndex (r:1 w:1)
+	// Storage: Loans RewardAccured (r:1 w:1)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	fn rebalance_stable_borrow_rate() -> Weight {
+		// Minimum execution time: 160_000 nanoseconds.
+		Weight::from_ref_time(160_000_000)
+			.saturating_add(T::DbWeight::get().reads(16))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
+	// Storage: Streaming Streams (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans IsolationConfigs (r:1 w:0)
+	// Storage: Loans StreamCollaterals (r:1 w:1)
+	fn pledge_stream() -> Weight {
+		// Minimum execution time: 62_000 nanoseconds.
+		Weight::from_ref_time(62_000_000)
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: Loans StreamCollaterals (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans AccountDeposits (r:1 w:0)
+	// Storage: Loans AccountBorrows (r:1 w:0)
+	// Storage: Streaming Streams (r:2 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Prices EmergencyPrice (r:1 w:0)
+	fn release_stream() -> Weight {
+		// Minimum execution time: 98_000 nanoseconds.
+		Weight::from_ref_time(98_000_000)
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: Loans StreamCollaterals (r:1 w:0)
+	// Storage: Loans SeizedStreams (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans AccountDeposits (r:1 w:0)
+	// Storage: Loans AccountBorrows (r:1 w:1)
+	// Storage: Loans LiquidationFreeCollaterals (r:1 w:0)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: Loans RewardBorrowState (r:1 w:1)
+	// Storage: Loans RewardBorrowSpeed (r:1 w:0)
+	// Storage: Loans RewardBorrowerIndex (r:1 w:1)
+	// Storage: Loans RewardAccured (r:1 w:1)
+	// Storage: Streaming Streams (r:2 w:1)
+	// Storage: Streaming StreamLibrary (r:2 w:2)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Prices EmergencyPrice (r:2 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	fn liquidate_stream() -> Weight {
+		// Minimum execution time: 280_000 nanoseconds.
+		Weight::from_ref_time(280_000_000)
+			.saturating_add(T::DbWeight::get().reads(24))
+			.saturating_add(T::DbWeight::get().writes(13))
+	}
+	// Storage: Loans SeizedStreams (r:1 w:1)
+	// Storage: Streaming Streams (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:3 w:3)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans AccountBorrows (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: Loans RewardBorrowState (r:1 w:1)
+	// Storage: Loans RewardBorrowSpeed (r:1 w:0)
+	// Storage: Loans RewardBorrowerIndex (r:1 w:1)
+	// Storage: Loans RewardAccured (r:1 w:1)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	fn repay_borrow_with_stream() -> Weight {
+		// Minimum execution time: 212_000 nanoseconds.
+		Weight::from_ref_time(212_000_000)
+			.saturating_add(T::DbWeight::get().reads(18))
+			.saturating_add(T::DbWeight::get().writes(14))
+	}
 }
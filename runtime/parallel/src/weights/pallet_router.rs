@@ -47,4 +47,16 @@ impl<T: frame_system::Config> pallet_router::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(7))
 			.saturating_add(T::DbWeight::get().writes(7))
 	}
+	// Storage: AMMRoute RoutePools (r:1 w:0)
+	// Storage: AMM Pools (r:100 w:0)
+	// Storage: AMM DynamicFees (r:100 w:0)
+	/// The range of component `p` is `[2, 100]`.
+	fn get_best_route(p: u32, ) -> Weight {
+		// Minimum execution time: 95_112 nanoseconds.
+		Weight::from_ref_time(17_905_212)
+			// Standard Error: 20_874
+			.saturating_add(Weight::from_ref_time(40_961_349).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(p.into())))
+	}
 }
@@ -72,4 +72,67 @@ impl<T: frame_system::Config> pallet_streaming::WeightInfo for WeightInfo<T> {
 		Weight::from_ref_time(27_346_000)
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	// Storage: Streaming MinimumDeposits (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Streaming NextStreamId (r:1 w:1)
+	// Storage: Streaming StreamLibrary (r:4 w:4)
+	// Storage: Streaming Streams (r:0 w:1)
+	/// The range of component `n` is `[1, 24]`.
+	fn create_with_schedule(n: u32, ) -> Weight {
+		// Minimum execution time: 123_267 nanoseconds.
+		Weight::from_ref_time(124_000_512)
+			// Standard Error: 2_841
+			.saturating_add(Weight::from_ref_time(318_224).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(11))
+			.saturating_add(T::DbWeight::get().writes(10))
+	}
+	// Storage: Streaming Streams (r:1 w:1)
+	// Storage: Streaming StreamLibrary (r:2 w:2)
+	fn transfer_recipient() -> Weight {
+		// Minimum execution time: 41_208 nanoseconds.
+		Weight::from_ref_time(41_873_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: Streaming Streams (r:1 w:1)
+	// Storage: Streaming StreamLibrary (r:2 w:2)
+	fn transfer_sender() -> Weight {
+		// Minimum execution time: 40_652 nanoseconds.
+		Weight::from_ref_time(41_319_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: Streaming MinimumDeposits (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Streaming NextStreamId (r:1 w:1)
+	// Storage: Streaming StreamLibrary (r:2 w:2)
+	// Storage: Streaming Streams (r:0 w:1)
+	/// The range of component `n` is `[1, 128]`.
+	fn batch_create(n: u32, ) -> Weight {
+		// Minimum execution time: 98_143 nanoseconds.
+		Weight::from_ref_time(70_412_385)
+			// Standard Error: 16_932
+			.saturating_add(Weight::from_ref_time(29_730_118).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(7))
+			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(n.into())))
+	}
+	// Storage: Streaming Streams (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:3 w:3)
+	// Storage: System Account (r:1 w:1)
+	fn top_up() -> Weight {
+		// Minimum execution time: 116_058 nanoseconds.
+		Weight::from_ref_time(117_240_000)
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
 }
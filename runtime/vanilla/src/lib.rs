@@ -89,10 +89,12 @@ pub use pallet_crowdloans;
 pub use pallet_farming;
 pub use pallet_liquid_staking;
 pub use pallet_loans;
+pub use pallet_orders;
 pub use pallet_prices;
 pub use pallet_router;
 pub use pallet_stableswap;
 pub use pallet_streaming;
+pub use pallet_vote_escrow;
 
 use pallet_ethereum::PostLogContent;
 use pallet_evm::{FeeCalculator, Runner};
@@ -336,6 +338,8 @@ impl Contains<RuntimeCall> for BaseCallFilter {
                 RuntimeCall::Farming(_) |
                 // Streaming
                 RuntimeCall::Streaming(_) |
+                // VoteEscrow
+                RuntimeCall::VoteEscrow(_) |
                 // Asset Management
                 RuntimeCall::AssetRegistry(_) |
                 // EVM
@@ -494,6 +498,9 @@ impl pallet_assets::Config for Runtime {
 parameter_types! {
     pub const RewardAssetId: CurrencyId = HKO;
     pub const LiquidationFreeAssetId: CurrencyId = KSM;
+    pub StreamCollateralDiscount: Ratio = Ratio::from_percent(30);
+    pub const MaxStreamCollaterals: u32 = 10;
+    pub MaxRewardBoost: Rate = Rate::saturating_from_rational(5, 2);
 }
 
 impl pallet_loans::Config for Runtime {
@@ -507,6 +514,13 @@ impl pallet_loans::Config for Runtime {
     type Assets = CurrencyAdapter;
     type RewardAssetId = RewardAssetId;
     type LiquidationFreeAssetId = LiquidationFreeAssetId;
+    type RuntimeCall = RuntimeCall;
+    type FlashLoanReceiver = ();
+    type StreamCollateral = Streaming;
+    type StreamCollateralDiscount = StreamCollateralDiscount;
+    type MaxStreamCollaterals = MaxStreamCollaterals;
+    type VotingEscrow = VoteEscrow;
+    type MaxRewardBoost = MaxRewardBoost;
 }
 
 parameter_types! {
@@ -934,6 +948,15 @@ impl InstanceFilter<RuntimeCall> for ProxyType {
                         | RuntimeCall::Loans(pallet_loans::Call::repay_borrow_all { .. })
                         | RuntimeCall::Loans(pallet_loans::Call::collateral_asset { .. })
                         | RuntimeCall::Loans(pallet_loans::Call::liquidate_borrow { .. })
+                        | RuntimeCall::Loans(pallet_loans::Call::bid_liquidation_auction { .. })
+                        | RuntimeCall::Loans(pallet_loans::Call::settle_liquidation_auction { .. })
+                        | RuntimeCall::Loans(pallet_loans::Call::set_emode { .. })
+                        | RuntimeCall::Loans(pallet_loans::Call::revoke_borrow_allowance { .. })
+                        | RuntimeCall::Loans(pallet_loans::Call::borrow_on_behalf { .. })
+                        | RuntimeCall::Loans(pallet_loans::Call::borrow_stable { .. })
+                        | RuntimeCall::Loans(
+                            pallet_loans::Call::rebalance_stable_borrow_rate { .. }
+                        )
                         | RuntimeCall::Loans(pallet_loans::Call::add_reward { .. })
                         | RuntimeCall::Loans(pallet_loans::Call::claim_reward { .. })
                         | RuntimeCall::Loans(pallet_loans::Call::claim_reward_for_market { .. })
@@ -999,6 +1022,12 @@ impl InstanceFilter<RuntimeCall> for ProxyType {
                         | RuntimeCall::AMMRoute(
                             pallet_router::Call::swap_exact_tokens_for_tokens { .. }
                         )
+                        | RuntimeCall::AMMRoute(
+                            pallet_router::Call::swap_tokens_for_exact_tokens_split { .. }
+                        )
+                        | RuntimeCall::AMMRoute(
+                            pallet_router::Call::swap_exact_tokens_for_tokens_split { .. }
+                        )
                 )
             }
             ProxyType::EVM => {
@@ -1388,6 +1417,8 @@ parameter_types! {
     pub const NumTokens: u8 = 2;
     pub const Precision: u32 = 100;
     pub const AmplificationCoefficient: u8 = 85;
+    pub const MaxPoolAssets: u32 = 4;
+    pub const MinRampBlocks: BlockNumber = DAYS;
 }
 
 impl pallet_stableswap::Config for Runtime {
@@ -1403,7 +1434,45 @@ impl pallet_stableswap::Config for Runtime {
     type NumTokens = NumTokens;
     type Precision = Precision;
     type AmplificationCoefficient = AmplificationCoefficient;
+    type MaxPoolAssets = MaxPoolAssets;
+    type MinRampBlocks = MinRampBlocks;
     type CreatePoolOrigin = EnsureRootOrMoreThanHalfGeneralCouncil;
+    type LiquidStakingExchangeRateProvider = LiquidStaking;
+    type PoolsHook = AMMRoute;
+}
+
+parameter_types! {
+    pub const OrdersPalletId: PalletId = PalletId(*b"par/ordr");
+    pub const MaxOrdersPerBlock: u32 = 20;
+    pub const MaxTwapSlices: u32 = 100;
+}
+
+impl pallet_orders::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type PalletId = OrdersPalletId;
+    type MaxOrdersPerBlock = MaxOrdersPerBlock;
+    type MaxTwapSlices = MaxTwapSlices;
+    type WeightInfo = pallet_orders::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    pub const VoteEscrowPalletId: PalletId = PalletId(*b"par/vote");
+    pub const EpochDuration: BlockNumber = 7 * DAYS;
+    pub const MaxLockDuration: BlockNumber = 4 * 365 * DAYS;
+    pub const MaxGauges: u32 = 50;
+}
+
+impl pallet_vote_escrow::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Assets = CurrencyAdapter;
+    type PalletId = VoteEscrowPalletId;
+    type LockCurrencyId = NativeCurrencyId;
+    type EpochDuration = EpochDuration;
+    type MaxLockDuration = MaxLockDuration;
+    type GaugeId = (CurrencyId, CurrencyId, BlockNumber);
+    type MaxGauges = MaxGauges;
+    type UpdateOrigin = EnsureRootOrMoreThanHalfGeneralCouncil;
+    type WeightInfo = pallet_vote_escrow::weights::SubstrateWeight<Runtime>;
 }
 
 pub type TimeStampedPrice = orml_oracle::TimestampedValue<Price, Moment>;
@@ -1447,6 +1516,11 @@ impl DecimalProvider<CurrencyId> for Decimal {
     }
 }
 
+parameter_types! {
+    pub const MaxPriceGuards: u32 = 32;
+    pub const MaxTwapSources: u32 = 16;
+}
+
 impl pallet_prices::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type Source = AggregatedDataProvider;
@@ -1456,9 +1530,14 @@ impl pallet_prices::Config for Runtime {
     type LiquidStakingCurrenciesProvider = LiquidStaking;
     type VaultTokenCurrenciesFilter = Crowdloans;
     type VaultTokenExchangeRateProvider = Crowdloans;
+    type LPVaultTokenCurrenciesFilter = Farming;
+    type LPVaultTokenExchangeRateProvider = Farming;
     type VaultLoansRateProvider = Loans;
     type RelayCurrency = RelayCurrency;
     type Decimal = Decimal;
+    type UnixTime = Timestamp;
+    type MaxPriceGuards = MaxPriceGuards;
+    type MaxTwapSources = MaxTwapSources;
     type AMM = AMM;
     type Assets = CurrencyAdapter;
     type WeightInfo = pallet_prices::weights::SubstrateWeight<Runtime>;
@@ -1764,6 +1843,8 @@ parameter_types! {
     // Set a short lifetime for development
     pub const ProposalLifetime: BlockNumber = 200;
     pub const ThresholdPercentage: u32 = 80;
+    pub const MaxUnfinalizedHeaders: u32 = 128;
+    pub const MaxFinalizedReceiptsRoots: u32 = 1_200;
 }
 
 impl pallet_bridge::Config for Runtime {
@@ -1782,6 +1863,8 @@ impl pallet_bridge::Config for Runtime {
     type ExistentialDeposit = ExistentialDeposit;
     type ProposalLifetime = ProposalLifetime;
     type ThresholdPercentage = ThresholdPercentage;
+    type MaxUnfinalizedHeaders = MaxUnfinalizedHeaders;
+    type MaxFinalizedReceiptsRoots = MaxFinalizedReceiptsRoots;
     type WeightInfo = weights::pallet_bridge::WeightInfo<Runtime>;
 }
 
@@ -1806,6 +1889,8 @@ parameter_types! {
     pub DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);   // 0.05%
     pub DefaultProtocolFeeReceiver: AccountId = TreasuryPalletId::get().into_account_truncating();
     pub const MinimumLiquidity: u128 = 1_000u128;
+    pub const MaxTicksPerPool: u32 = 1_000;
+    pub const MinPositionLiquidity: u128 = 1_000_000_000_000u128;
 }
 
 impl pallet_amm::Config for Runtime {
@@ -1820,6 +1905,9 @@ impl pallet_amm::Config for Runtime {
     type MinimumLiquidity = MinimumLiquidity;
     type MaxLengthRoute = MaxLengthRoute;
     type GetNativeCurrencyId = NativeCurrencyId;
+    type MaxTicksPerPool = MaxTicksPerPool;
+    type MinPositionLiquidity = MinPositionLiquidity;
+    type PoolsHook = AMMRoute;
 }
 
 parameter_types! {
@@ -1893,6 +1981,7 @@ parameter_types! {
     pub const StreamPalletId: PalletId = PalletId(*b"par/strm");
     pub const MaxStreamsCount: u32 = 128;
     pub const MaxFinishedStreamsCount: u32 = 2;
+    pub const MaxSchedulePoints: u32 = 24;
 }
 
 impl pallet_streaming::Config for Runtime {
@@ -1906,6 +1995,7 @@ impl pallet_streaming::Config for Runtime {
     type WeightInfo = weights::pallet_streaming::WeightInfo<Runtime>;
     type NativeCurrencyId = NativeCurrencyId;
     type NativeExistentialDeposit = ExistentialDeposit;
+    type MaxSchedulePoints = MaxSchedulePoints;
 }
 
 parameter_types! {
@@ -1930,6 +2020,8 @@ impl pallet_xcm_helper::Config for Runtime {
 
 parameter_types! {
     pub const MaxLengthRoute: u8 = 10;
+    pub const MaxRouteSplits: u32 = 4;
+    pub const MaxRoutePools: u32 = 100;
     pub const RouterPalletId: PalletId = PalletId(*b"ammroute");
 }
 
@@ -1937,8 +2029,11 @@ impl pallet_router::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type PalletId = RouterPalletId;
     type AMM = AMM;
+    type StableSwap = StableSwap;
     type AMMRouterWeightInfo = weights::pallet_router::WeightInfo<Runtime>;
     type MaxLengthRoute = MaxLengthRoute;
+    type MaxRouteSplits = MaxRouteSplits;
+    type MaxRoutePools = MaxRoutePools;
     type Assets = CurrencyAdapter;
     type GetNativeCurrencyId = NativeCurrencyId;
 }
@@ -1955,6 +2050,11 @@ parameter_types! {
     pub const MaxUserLockItemsCount: u32 = 100;
     pub const LockPoolMaxDuration: u32 = 2628000;
     pub const CoolDownMaxDuration: u32 = 50400;
+    pub const MaxVaultHarvestsPerBlock: u32 = 10;
+    pub MaxHarvestSlippage: Ratio = Ratio::from_percent(2);
+    pub const MinimumVaultShares: Balance = 1_000;
+    pub const MaxExtraRewardAssets: u32 = 4;
+    pub MaxBoost: Rate = Rate::saturating_from_rational(5, 2);
 }
 
 impl pallet_farming::Config for Runtime {
@@ -1967,6 +2067,16 @@ impl pallet_farming::Config for Runtime {
     type LockPoolMaxDuration = LockPoolMaxDuration;
     type CoolDownMaxDuration = CoolDownMaxDuration;
     type Decimal = Decimal;
+    type AMM = AMM;
+    type Router = AMMRoute;
+    type MaxVaultHarvestsPerBlock = MaxVaultHarvestsPerBlock;
+    type PriceFeeder = Prices;
+    type MaxHarvestSlippage = MaxHarvestSlippage;
+    type MinimumVaultShares = MinimumVaultShares;
+    type MaxExtraRewardAssets = MaxExtraRewardAssets;
+    type VotingEscrow = VoteEscrow;
+    type GaugeController = VoteEscrow;
+    type MaxBoost = MaxBoost;
 }
 
 impl pallet_emergency_shutdown::Config for Runtime {
@@ -2041,7 +2151,7 @@ construct_runtime!(
 
         // AMM
         AMM: pallet_amm::{Pallet, Call, Storage, Event<T>} = 80,
-        AMMRoute: pallet_router::{Pallet, Call, Event<T>} = 81,
+        AMMRoute: pallet_router::{Pallet, Call, Storage, Event<T>} = 81,
         CurrencyAdapter: pallet_currency_adapter::{Pallet, Call} = 82,
 
         // Others
@@ -2052,6 +2162,8 @@ construct_runtime!(
         Streaming: pallet_streaming::{Pallet, Call, Storage, Event<T>} = 94,
         AssetRegistry: pallet_asset_registry::{Pallet, Call, Storage, Event<T>} = 95,
         StableSwap: pallet_stableswap::{Pallet, Call, Storage, Event<T>} = 96,
+        Orders: pallet_orders::{Pallet, Call, Storage, Event<T>} = 97,
+        VoteEscrow: pallet_vote_escrow::{Pallet, Call, Storage, Event<T>} = 98,
 
         // EVM
         EVM: pallet_evm::{Pallet, Config, Call, Storage, Event<T>} = 110,
@@ -2093,6 +2205,14 @@ pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic =
     fp_self_contained::CheckedExtrinsic<AccountId, RuntimeCall, SignedExtra, H160>;
+/// Storage migrations applied on runtime upgrade.
+pub type Migrations = (
+    pallet_loans::migrations::v7::MigrateToV7<Runtime>,
+    pallet_amm::migrations::v2::MigrateToV2<Runtime>,
+    pallet_router::migrations::v2::MigrateToV2<Runtime>,
+    pallet_streaming::migrations::v2::MigrateToV2<Runtime>,
+    pallet_stableswap::migrations::v2::MigrateToV2<Runtime>,
+);
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
     Runtime,
@@ -2100,7 +2220,7 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
-    (),
+    Migrations,
 >;
 
 impl fp_self_contained::SelfContainedCall for RuntimeCall {
@@ -2301,19 +2421,49 @@ impl_runtime_apis! {
             Loans::get_account_liquidity(&account)
         }
 
-        fn get_market_status(asset_id: CurrencyId) -> Result<(Rate, Rate, Rate, Ratio, Balance, Balance, sp_runtime::FixedU128), DispatchError> {
+        fn get_market_status(asset_id: CurrencyId) -> Result<pallet_traits::MarketStatus<Balance>, DispatchError> {
             Loans::get_market_status(asset_id)
         }
 
         fn get_liquidation_threshold_liquidity(account: AccountId) -> Result<(Liquidity, Shortfall, Liquidity, Shortfall), DispatchError> {
             Loans::get_account_liquidation_threshold_liquidity(&account)
         }
+
+        fn get_liquidation_auctions() -> Vec<(pallet_traits::AuctionId, pallet_traits::LiquidationAuction<AccountId, CurrencyId, Balance>, Rate)> {
+            Loans::get_liquidation_auctions()
+        }
+
+        fn get_account_mode(account: AccountId) -> pallet_traits::AccountMode<CurrencyId> {
+            Loans::get_account_mode(&account)
+        }
+
+        fn get_liquidation_candidates(min_shortfall: Shortfall, start_key: Option<Vec<u8>>, limit: u32) -> Result<(Vec<(AccountId, Shortfall)>, Option<Vec<u8>>), DispatchError> {
+            Loans::get_liquidation_candidates(min_shortfall, start_key, limit)
+        }
+
+        fn get_account_position(account: AccountId) -> Result<pallet_traits::AccountPosition<CurrencyId, Balance>, DispatchError> {
+            Loans::get_account_position(&account)
+        }
+
+        fn simulate_liquidation(borrower: AccountId, liquidation_asset_id: CurrencyId, repay_amount: Balance, collateral_asset_id: CurrencyId) -> Result<pallet_traits::LiquidationSimulation<Balance>, DispatchError> {
+            Loans::simulate_liquidation(&borrower, liquidation_asset_id, repay_amount, collateral_asset_id)
+        }
     }
 
     impl pallet_router_rpc_runtime_api::RouterApi<Block, Balance> for Runtime {
-        fn get_best_route(amount: Balance, token_in: CurrencyId, token_out: CurrencyId, reversed: bool) -> Result<(Vec<CurrencyId>, Balance), DispatchError> {
-            let (route, amount) = AMMRoute::get_best_route(amount, token_in, token_out, reversed)?;
-            Ok((route, amount))
+        fn get_best_route(amount: Balance, token_in: CurrencyId, token_out: CurrencyId, reversed: bool) -> Result<(Vec<(Vec<CurrencyId>, Balance)>, Balance), DispatchError> {
+            let (split_route, amount) = AMMRoute::get_best_split_route(amount, token_in, token_out, reversed)?;
+            Ok((split_route, amount))
+        }
+    }
+
+    impl pallet_streaming_rpc_runtime_api::StreamingApi<Block, AccountId, Balance> for Runtime {
+        fn get_sent_streams(sender: AccountId) -> Result<Vec<pallet_traits::StreamInfo<AccountId, CurrencyId, Balance>>, DispatchError> {
+            Streaming::get_streams(&sender, pallet_streaming::types::StreamKind::Send)
+        }
+
+        fn get_received_streams(recipient: AccountId) -> Result<Vec<pallet_traits::StreamInfo<AccountId, CurrencyId, Balance>>, DispatchError> {
+            Streaming::get_streams(&recipient, pallet_streaming::types::StreamKind::Receive)
         }
     }
 
@@ -2509,6 +2659,8 @@ impl_runtime_apis! {
             list_benchmark!(list, extra, pallet_xcm_helper, XcmHelper);
             list_benchmark!(list, extra, pallet_asset_registry, AssetRegistry);
             list_benchmark!(list, extra, pallet_streaming, Streaming);
+            list_benchmark!(list, extra, pallet_orders, Orders);
+            list_benchmark!(list, extra, pallet_vote_escrow, VoteEscrow);
 
             let storage_info = AllPalletsWithSystem::storage_info();
 
@@ -2557,6 +2709,8 @@ impl_runtime_apis! {
             add_benchmark!(params, batches, pallet_xcm_helper, XcmHelper);
             add_benchmark!(params, batches, pallet_asset_registry, AssetRegistry);
             add_benchmark!(params, batches, pallet_streaming, Streaming);
+            add_benchmark!(params, batches, pallet_orders, Orders);
+            add_benchmark!(params, batches, pallet_vote_escrow, VoteEscrow);
 
             if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
             Ok(batches)
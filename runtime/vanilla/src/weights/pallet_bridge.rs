@@ -39,10 +39,12 @@ impl<T: frame_system::Config> pallet_bridge::WeightInfo for WeightInfo<T> {
 	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
 	// Storage: Bridge ChainNonces (r:1 w:1)
 	// Storage: Bridge BridgeRegistry (r:0 w:1)
+	// Storage: Bridge LightClients (r:0 w:1)
+	// Storage: Bridge UnfinalizedHeaders (r:0 w:1)
 	fn unregister_chain() -> Weight {
 		Weight::from_ref_time(43_435_000 as u64)
 			.saturating_add(T::DbWeight::get().reads(2 as u64))
-			.saturating_add(T::DbWeight::get().writes(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
 	}
 	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
 	// Storage: Bridge BridgeTokens (r:1 w:1)
@@ -121,4 +123,44 @@ impl<T: frame_system::Config> pallet_bridge::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(13 as u64))
 			.saturating_add(T::DbWeight::get().writes(7 as u64))
 	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Bridge ChainNonces (r:1 w:0)
+	// Storage: Bridge FinalizedReceiptsRoots (r:0 w:1)
+	// Storage: Bridge UnfinalizedHeaders (r:0 w:1)
+	// Storage: Bridge LightClients (r:0 w:1)
+	fn initialize_light_client() -> Weight {
+		Weight::from_ref_time(69_384_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Bridge LightClients (r:1 w:1)
+	// Storage: Bridge UnfinalizedHeaders (r:1 w:1)
+	// Storage: Bridge FinalizedReceiptsRoots (r:0 w:2)
+	/// The range of component `n` is `[1, 64]`.
+	fn submit_headers(n: u32, ) -> Weight {
+		Weight::from_ref_time(24_108_000 as u64)
+			// Standard Error: 41_000
+			.saturating_add(Weight::from_ref_time(96_275_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((4 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Bridge ChainNonces (r:1 w:0)
+	// Storage: Bridge LightClients (r:1 w:0)
+	// Storage: Bridge FinalizedReceiptsRoots (r:1 w:0)
+	// Storage: Bridge BridgeRegistry (r:1 w:1)
+	// Storage: Bridge AssetIds (r:1 w:0)
+	// Storage: Bridge BridgeTokens (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Assets Metadata (r:1 w:0)
+	// Storage: System Account (r:1 w:0)
+	fn materialize_with_proof() -> Weight {
+		Weight::from_ref_time(255_984_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(12 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+	}
 }
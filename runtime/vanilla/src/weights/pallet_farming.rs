@@ -99,4 +99,86 @@ impl<T: frame_system::Config> pallet_farming::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(3 as u64))
 			.saturating_add(T::DbWeight::get().writes(3 as u64))
 	}
+	// Storage: Farming Pools (r:1 w:0)
+	// Storage: AMM Pools (r:1 w:0)
+	// Storage: Farming Vaults (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:0)
+	fn create_vault() -> Weight {
+		// Minimum execution time: 53_768 nanoseconds.
+		Weight::from_ref_time(55_128_000)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Farming Vaults (r:1 w:0)
+	// Storage: Farming Pools (r:1 w:1)
+	// Storage: Farming Positions (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	// Storage: System Account (r:1 w:1)
+	fn deposit_to_vault() -> Weight {
+		// Minimum execution time: 181_071 nanoseconds.
+		Weight::from_ref_time(182_431_000)
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().writes(9))
+	}
+	// Storage: Farming Vaults (r:1 w:0)
+	// Storage: Farming Pools (r:1 w:1)
+	// Storage: Farming Positions (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	// Storage: System Account (r:1 w:1)
+	fn withdraw_from_vault() -> Weight {
+		// Minimum execution time: 190_446 nanoseconds.
+		Weight::from_ref_time(191_806_000)
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().writes(9))
+	}
+	// Storage: Farming Vaults (r:1 w:1)
+	// Storage: Farming Pools (r:1 w:1)
+	// Storage: Farming Positions (r:1 w:1)
+	// Storage: AMM Pools (r:2 w:2)
+	// Storage: AMM ProtocolFee (r:1 w:0)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:10 w:10)
+	// Storage: System Account (r:2 w:0)
+	fn harvest() -> Weight {
+		// Minimum execution time: 522_299 nanoseconds.
+		Weight::from_ref_time(523_659_000)
+			.saturating_add(T::DbWeight::get().reads(21))
+			.saturating_add(T::DbWeight::get().writes(18))
+	}
+	// Storage: Farming Pools (r:1 w:0)
+	// Storage: Farming ExtraRewardAssets (r:1 w:1)
+	fn add_extra_reward_asset() -> Weight {
+		// Minimum execution time: 37_602 nanoseconds.
+		Weight::from_ref_time(38_214_000)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Farming ExtraRewardAssets (r:1 w:0)
+	// Storage: Farming Pools (r:1 w:1)
+	// Storage: Farming ExtraRewards (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn dispatch_extra_reward() -> Weight {
+		// Minimum execution time: 151_118 nanoseconds.
+		Weight::from_ref_time(152_940_000)
+			.saturating_add(T::DbWeight::get().reads(8))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
+	// Storage: Farming Pools (r:2 w:1)
+	// Storage: Farming WorkingSupply (r:2 w:1)
+	// Storage: Farming Positions (r:2 w:1)
+	// Storage: Farming WorkingBalances (r:2 w:1)
+	// Storage: Farming ExtraRewardAssets (r:1 w:0)
+	// Storage: VoteEscrow Locked (r:1 w:0)
+	// Storage: VoteEscrow Supply (r:1 w:0)
+	fn update_boost() -> Weight {
+		// Minimum execution time: 60_214 nanoseconds.
+		Weight::from_ref_time(61_837_000)
+			.saturating_add(T::DbWeight::get().reads(11))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
 }
@@ -67,7 +67,14 @@ async function para({ logger, options: { paraWs, network } }: ActionParameters)
   for (const { pool, liquidityAmounts, lptokenReceiver, liquidityProviderToken } of config.pools) {
     call.push(
       api.tx.sudo.sudo(
-        api.tx.amm.createPool(pool, liquidityAmounts, lptokenReceiver, liquidityProviderToken)
+        api.tx.amm.createPool(
+          pool,
+          liquidityAmounts,
+          lptokenReceiver,
+          liquidityProviderToken,
+          api.consts.amm.lpFee,
+          null
+        )
       )
     )
   }